@@ -53,6 +53,7 @@ where
         observers: RefCell::new(HashSet::default()),
     });
     id.set_scope();
+    RUNTIME.with(|runtime| runtime.effects.borrow_mut().insert(id));
 
     run_initial_effect(effect);
 }
@@ -107,6 +108,7 @@ where
         observers: RefCell::new(HashSet::default()),
     });
     id.set_scope();
+    RUNTIME.with(|runtime| runtime.effects.borrow_mut().insert(id));
 
     run_initial_updater_effect(effect)
 }
@@ -144,6 +146,8 @@ pub fn batch<T>(f: impl FnOnce() -> T) -> T {
 
 pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
     let effect_id = effect.id();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("effect", id = ?effect_id).entered();
 
     RUNTIME.with(|runtime| {
         *runtime.current_effect.borrow_mut() = Some(effect.clone());
@@ -160,7 +164,11 @@ pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
 
 pub(crate) fn run_effect(effect: Rc<dyn EffectTrait>) {
     let effect_id = effect.id();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("effect", id = ?effect_id).entered();
     effect_id.dispose();
+    // Disposing only cleans up what the last run created, the Effect itself stays
+    RUNTIME.with(|runtime| runtime.effects.borrow_mut().insert(effect_id));
 
     observer_clean_up(&effect);
 
@@ -185,6 +193,8 @@ where
     U: Fn(I, T) -> T + 'static,
 {
     let effect_id = effect.id();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("effect", id = ?effect_id).entered();
 
     let result = RUNTIME.with(|runtime| {
         *runtime.current_effect.borrow_mut() = Some(effect.clone());
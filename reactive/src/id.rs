@@ -36,10 +36,13 @@ impl Id {
     /// Dispose the relevant resources that's linking to this Id, and the all the children
     /// and grandchildren.
     pub(crate) fn dispose(&self) {
-        if let Ok((children, signal)) = RUNTIME.try_with(|runtime| {
+        if let Ok((children, signal, cleanups)) = RUNTIME.try_with(|runtime| {
+            runtime.scopes.borrow_mut().remove(self);
+            runtime.effects.borrow_mut().remove(self);
             (
                 runtime.children.borrow_mut().remove(self),
                 runtime.signals.borrow_mut().remove(self),
+                runtime.cleanups.borrow_mut().remove(self),
             )
         }) {
             if let Some(children) = children {
@@ -48,6 +51,11 @@ impl Id {
                 }
             }
 
+            // The cleanups run after the ones of the child Scopes
+            for cleanup in cleanups.into_iter().flatten() {
+                cleanup();
+            }
+
             if let Some(signal) = signal {
                 for (_, effect) in signal.subscribers() {
                     observer_clean_up(&effect);
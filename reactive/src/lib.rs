@@ -10,6 +10,6 @@ mod trigger;
 pub use context::{provide_context, use_context};
 pub use effect::{batch, create_effect, create_stateful_updater, create_updater, untrack};
 pub use memo::{create_memo, Memo};
-pub use scope::{as_child_of_current_scope, with_scope, Scope};
+pub use scope::{as_child_of_current_scope, on_cleanup, scope_stats, with_scope, Scope, ScopeStats};
 pub use signal::{create_rw_signal, create_signal, ReadSignal, RwSignal, WriteSignal};
 pub use trigger::{create_trigger, Trigger};
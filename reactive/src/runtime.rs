@@ -23,7 +23,13 @@ pub(crate) struct Runtime {
     pub(crate) current_effect: RefCell<Option<Rc<dyn EffectTrait>>>,
     pub(crate) current_scope: RefCell<Id>,
     pub(crate) children: RefCell<HashMap<Id, HashSet<Id>>>,
+    /// The Scopes which aren't disposed yet
+    pub(crate) scopes: RefCell<HashSet<Id>>,
+    /// The Effects which aren't disposed yet
+    pub(crate) effects: RefCell<HashSet<Id>>,
     pub(crate) signals: RefCell<HashMap<Id, Signal>>,
+    /// The functions to run when a Scope is disposed
+    pub(crate) cleanups: RefCell<HashMap<Id, Vec<Box<dyn FnOnce()>>>>,
     pub(crate) contexts: RefCell<HashMap<TypeId, Box<dyn Any>>>,
     pub(crate) batching: Cell<bool>,
     pub(crate) pending_effects: RefCell<SmallVec<[Rc<dyn EffectTrait>; 10]>>,
@@ -37,11 +43,15 @@ impl Default for Runtime {
 
 impl Runtime {
     pub(crate) fn new() -> Self {
+        let root_scope = Id::next();
         Self {
             current_effect: RefCell::new(None),
-            current_scope: RefCell::new(Id::next()),
+            current_scope: RefCell::new(root_scope),
             children: RefCell::new(HashMap::new()),
+            scopes: RefCell::new(HashSet::from([root_scope])),
+            effects: Default::default(),
             signals: Default::default(),
+            cleanups: Default::default(),
             contexts: Default::default(),
             batching: Cell::new(false),
             pending_effects: RefCell::new(SmallVec::new()),
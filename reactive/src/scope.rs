@@ -1,4 +1,10 @@
-use std::{any::Any, cell::RefCell, collections::HashMap, fmt, rc::Rc};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
 
 use crate::{
     create_effect,
@@ -13,9 +19,32 @@ use crate::{
 /// Every Signal has a Scope created explictly or implicity,
 /// and when you Dispose the Scope, it will clean up all the Signals
 /// that belong to the Scope and all the child Scopes
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Scope(pub(crate) Id);
 
+/// The number of reactive objects retained by Scopes, see [`Scope::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScopeStats {
+    /// The Scopes which aren't disposed, including the counted Scopes themselves
+    pub scopes: usize,
+    /// The Signals, including the ones of Memos
+    pub signals: usize,
+    /// The Effects, including the ones of Memos
+    pub effects: usize,
+}
+
+impl std::ops::Add for ScopeStats {
+    type Output = ScopeStats;
+
+    fn add(self, other: ScopeStats) -> ScopeStats {
+        ScopeStats {
+            scopes: self.scopes + other.scopes,
+            signals: self.signals + other.signals,
+            effects: self.effects + other.effects,
+        }
+    }
+}
+
 impl Default for Scope {
     fn default() -> Self {
         Self::new()
@@ -33,7 +62,9 @@ impl fmt::Debug for Scope {
 impl Scope {
     /// Create a new Scope that isn't a child or parent of any scope
     pub fn new() -> Self {
-        Self(Id::next())
+        let id = Id::next();
+        RUNTIME.with(|runtime| runtime.scopes.borrow_mut().insert(id));
+        Self(id)
     }
 
     /// The current Scope in the Runtime. Any Signal/Effect/Memo created with
@@ -46,6 +77,7 @@ impl Scope {
     pub fn create_child(&self) -> Scope {
         let child = Id::next();
         RUNTIME.with(|runtime| {
+            runtime.scopes.borrow_mut().insert(child);
             let mut children = runtime.children.borrow_mut();
             let children = children.entry(self.0).or_default();
             children.insert(child);
@@ -107,11 +139,76 @@ impl Scope {
         tracker.subscribe();
     }
 
+    /// Run `cleanup` when this Scope is disposed, after the cleanups of its child Scopes
+    pub fn on_cleanup(&self, cleanup: impl FnOnce() + 'static) {
+        RUNTIME.with(|runtime| {
+            runtime
+                .cleanups
+                .borrow_mut()
+                .entry(self.0)
+                .or_default()
+                .push(Box::new(cleanup));
+        });
+    }
+
     /// Dispose this Scope, and it will cleanup all the Signals and child Scope
     /// of this Scope.
     pub fn dispose(&self) {
         self.0.dispose();
     }
+
+    /// Whether this Scope isn't disposed yet
+    pub fn is_alive(&self) -> bool {
+        RUNTIME.with(|runtime| {
+            runtime.scopes.borrow().contains(&self.0) || runtime.effects.borrow().contains(&self.0)
+        })
+    }
+
+    /// The Scopes, Signals and Effects retained by this Scope and its child Scopes
+    pub fn stats(&self) -> ScopeStats {
+        scope_stats([*self])
+    }
+}
+
+/// The Scopes, Signals and Effects retained by `scopes` and their child Scopes, counting the
+/// ones shared by several of the `scopes` once
+pub fn scope_stats(scopes: impl IntoIterator<Item = Scope>) -> ScopeStats {
+    RUNTIME.with(|runtime| {
+        let live_scopes = runtime.scopes.borrow();
+        let effects = runtime.effects.borrow();
+        let signals = runtime.signals.borrow();
+        let children = runtime.children.borrow();
+        let mut stats = ScopeStats::default();
+        let mut visited = HashSet::new();
+        let mut pending: Vec<Id> = scopes.into_iter().map(|scope| scope.0).collect();
+        while let Some(scope) = pending.pop() {
+            if !visited.insert(scope) {
+                continue;
+            }
+            // Effects are the Scopes of the Signals created while they run
+            if effects.contains(&scope) {
+                stats.effects += 1;
+            } else if live_scopes.contains(&scope) {
+                stats.scopes += 1;
+            } else {
+                continue;
+            }
+            // Scopes and Effects tracked by an Effect have a Signal too, so they're checked first
+            for child in children.get(&scope).into_iter().flatten() {
+                if live_scopes.contains(child) || effects.contains(child) {
+                    pending.push(*child);
+                } else if signals.contains_key(child) {
+                    stats.signals += 1;
+                }
+            }
+        }
+        stats
+    })
+}
+
+/// Run `cleanup` when the current Scope is disposed
+pub fn on_cleanup(cleanup: impl FnOnce() + 'static) {
+    Scope::current().on_cleanup(cleanup);
 }
 
 /// Runs the given code with the given Scope
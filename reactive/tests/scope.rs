@@ -0,0 +1,56 @@
+use std::{cell::RefCell, rc::Rc};
+
+use floem_reactive::{create_memo, on_cleanup, scope_stats, with_scope, Scope, ScopeStats};
+
+#[test]
+fn cleanup_on_dispose() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+
+    let parent = Scope::new();
+    let child = parent.create_child();
+    parent.on_cleanup({
+        let order = order.clone();
+        move || order.borrow_mut().push("parent")
+    });
+    with_scope(child, {
+        let order = order.clone();
+        move || on_cleanup(move || order.borrow_mut().push("child"))
+    });
+
+    assert!(order.borrow().is_empty());
+
+    // The child Scope is cleaned up first, and each cleanup only runs once
+    parent.dispose();
+    parent.dispose();
+    assert_eq!(*order.borrow(), vec!["child", "parent"]);
+}
+
+#[test]
+fn retained_signals() {
+    let parent = Scope::new();
+    let child = parent.create_child();
+    let signal = parent.create_rw_signal(1);
+    with_scope(child, move || {
+        let _ = create_memo(move |_| signal.get() * 2);
+    });
+
+    let stats = |scopes, signals, effects| ScopeStats {
+        scopes,
+        signals,
+        effects,
+    };
+    // The Memo has a Signal and an Effect
+    assert_eq!(parent.stats(), stats(2, 2, 1));
+    assert_eq!(child.stats(), stats(1, 1, 1));
+    // The child Scope is only counted once
+    assert_eq!(scope_stats([parent, child]), stats(2, 2, 1));
+    // Rerunning the Effect doesn't change what is retained
+    signal.set(2);
+    assert_eq!(parent.stats(), stats(2, 2, 1));
+
+    child.dispose();
+    assert!(!child.is_alive());
+    assert_eq!(parent.stats(), stats(1, 1, 0));
+    parent.dispose();
+    assert_eq!(parent.stats(), ScopeStats::default());
+}
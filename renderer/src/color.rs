@@ -0,0 +1,179 @@
+//! Wide gamut color values, clamped to sRGB output, and interpolation in linear light.
+//!
+//! Colors can be given in Display P3 with [`set_color_space`], but the renderers and window
+//! surfaces only output sRGB: colors are converted to sRGB when drawn and the components
+//! outside of the sRGB gamut are clamped, so they show as the closest sRGB color.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use floem_peniko::Color;
+
+static COLOR_SPACE: AtomicU8 = AtomicU8::new(ColorSpace::Srgb as u8);
+
+/// The color space the components of a [`Color`] are given in. Colors are always output in
+/// sRGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    Srgb = 0,
+    /// The wide gamut space of recent displays, with the sRGB transfer function.
+    DisplayP3 = 1,
+}
+
+/// The color space of the color values given to the renderers.
+pub fn color_space() -> ColorSpace {
+    match COLOR_SPACE.load(Ordering::Relaxed) {
+        1 => ColorSpace::DisplayP3,
+        _ => ColorSpace::Srgb,
+    }
+}
+
+/// Sets the color space of the color values given to the renderers. This doesn't change the
+/// output, which stays sRGB.
+pub fn set_color_space(color_space: ColorSpace) {
+    COLOR_SPACE.store(color_space as u8, Ordering::Relaxed);
+}
+
+/// An sRGB color, converted to the [`color_space`] colors are given in.
+pub fn srgb(r: u8, g: u8, b: u8) -> Color {
+    convert(Color::rgb8(r, g, b), ColorSpace::Srgb, color_space())
+}
+
+/// A Display P3 color, converted to the [`color_space`] colors are given in.
+pub fn display_p3(r: u8, g: u8, b: u8) -> Color {
+    convert(Color::rgb8(r, g, b), ColorSpace::DisplayP3, color_space())
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn to_u8(c: f64) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Converts the components of `color` from `from` to `to`, clipping colors which are outside
+/// of the gamut of `to`.
+pub fn convert(color: Color, from: ColorSpace, to: ColorSpace) -> Color {
+    // Conversion matrices between the linear spaces, which share the D65 white point
+    const P3_TO_SRGB: [[f64; 3]; 3] = [
+        [1.224_940_2, -0.224_940_2, 0.0],
+        [-0.042_056_96, 1.042_056_96, 0.0],
+        [-0.019_637_55, -0.078_636_05, 1.098_273_6],
+    ];
+    const SRGB_TO_P3: [[f64; 3]; 3] = [
+        [0.822_461_97, 0.177_538_03, 0.0],
+        [0.033_194_2, 0.966_805_8, 0.0],
+        [0.017_082_63, 0.072_397_44, 0.910_519_93],
+    ];
+
+    let matrix = match (from, to) {
+        (ColorSpace::DisplayP3, ColorSpace::Srgb) => P3_TO_SRGB,
+        (ColorSpace::Srgb, ColorSpace::DisplayP3) => SRGB_TO_P3,
+        _ => return color,
+    };
+    let linear = [color.r, color.g, color.b].map(|c| to_linear(c as f64 / 255.0));
+    let [r, g, b] = matrix.map(|row| {
+        let c = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+        to_u8(from_linear(c))
+    });
+    Color::rgba8(r, g, b, color.a)
+}
+
+/// Converts a color given in the [`color_space`] to sRGB, which the renderers output.
+///
+/// Window surfaces are sRGB, so Display P3 colors outside of the sRGB gamut are clamped to it.
+pub fn to_output(color: Color) -> Color {
+    convert(color, color_space(), ColorSpace::Srgb)
+}
+
+/// Interpolates between two colors in linear light, which avoids the dark and desaturated
+/// midpoints of interpolating the gamma encoded components.
+pub fn mix_linear(from: Color, to: Color, t: f64) -> Color {
+    let mix = |a: u8, b: u8| {
+        let a = to_linear(a as f64 / 255.0);
+        let b = to_linear(b as f64 / 255.0);
+        to_u8(from_linear(a + (b - a) * t))
+    };
+    let alpha = from.a as f64 + (to.a as f64 - from.a as f64) * t;
+    Color::rgba8(
+        mix(from.r, to.r),
+        mix(from.g, to.g),
+        mix(from.b, to.b),
+        alpha.round().clamp(0.0, 255.0) as u8,
+    )
+}
+
+/// Adds stops between the gradient stops `(offset, color)` so a renderer interpolating the
+/// gamma encoded components approximates interpolation in linear light.
+pub fn linear_gradient_stops(stops: &[(f32, Color)]) -> Vec<(f32, Color)> {
+    const STEPS: usize = 8;
+
+    let mut result = Vec::with_capacity(stops.len() * STEPS);
+    for pair in stops.windows(2) {
+        let ((start, from), (end, to)) = (pair[0], pair[1]);
+        for step in 0..STEPS {
+            let t = step as f32 / STEPS as f32;
+            result.push((start + (end - start) * t, mix_linear(from, to, t as f64)));
+        }
+    }
+    if let Some(last) = stops.last() {
+        result.push(*last);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_peniko::Color;
+
+    use super::{convert, linear_gradient_stops, mix_linear, ColorSpace};
+
+    #[test]
+    fn mix_in_linear_light() {
+        let mid = mix_linear(Color::BLACK, Color::WHITE, 0.5);
+        assert_eq!((mid.r, mid.g, mid.b, mid.a), (188, 188, 188, 255));
+        assert_eq!(mix_linear(Color::RED, Color::BLUE, 0.0), Color::RED);
+        assert_eq!(mix_linear(Color::RED, Color::BLUE, 1.0), Color::BLUE);
+    }
+
+    #[test]
+    fn p3_round_trip() {
+        let color = Color::rgba8(200, 120, 40, 255);
+        let p3 = convert(color, ColorSpace::Srgb, ColorSpace::DisplayP3);
+        let back = convert(p3, ColorSpace::DisplayP3, ColorSpace::Srgb);
+        for (a, b) in [(color.r, back.r), (color.g, back.g), (color.b, back.b)] {
+            assert!(a.abs_diff(b) <= 1);
+        }
+    }
+
+    #[test]
+    fn wide_gamut_clamped_to_srgb() {
+        // The red primary of Display P3 is outside of the sRGB gamut
+        let red = convert(
+            Color::rgb8(255, 0, 0),
+            ColorSpace::DisplayP3,
+            ColorSpace::Srgb,
+        );
+        assert_eq!((red.r, red.g, red.b), (255, 0, 0));
+    }
+
+    #[test]
+    fn gradient_keeps_end_stops() {
+        let stops = linear_gradient_stops(&[(0.0, Color::BLACK), (1.0, Color::WHITE)]);
+        assert_eq!(stops.len(), 9);
+        assert_eq!(stops[0], (0.0, Color::BLACK));
+        assert_eq!(stops[8], (1.0, Color::WHITE));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod color;
+mod text;
+
 pub use floem_cosmic_text as cosmic_text;
 use floem_cosmic_text::TextLayout;
 use floem_peniko::{
@@ -7,6 +10,7 @@ use floem_peniko::{
 use image::DynamicImage;
 pub use resvg::tiny_skia;
 pub use resvg::usvg;
+pub use text::{rasterize_glyph, LcdFilter, TextAntialiasing, TextRendering};
 
 pub struct Svg<'a> {
     pub tree: &'a usvg::Tree,
@@ -26,6 +30,10 @@ pub trait Renderer {
 
     fn set_z_index(&mut self, z_index: i32);
 
+    /// Sets how the glyphs of the following [`draw_text`](Renderer::draw_text) calls are
+    /// rasterized.
+    fn set_text_rendering(&mut self, config: TextRendering);
+
     /// Clip to a [`Shape`].
     fn clip(&mut self, shape: &impl Shape);
 
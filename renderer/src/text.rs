@@ -0,0 +1,169 @@
+use floem_cosmic_text::{CacheKey, SwashContent, SwashImage, FONT_SYSTEM};
+use swash::{
+    scale::{Render, ScaleContext, Source, StrikeWith},
+    zeno::{Format, Transform, Vector},
+};
+
+/// How glyphs are rasterized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextRendering {
+    /// Aligns glyph outlines to the pixel grid, which makes small text sharper but changes
+    /// the shapes of the glyphs slightly.
+    pub hinting: bool,
+    pub antialiasing: TextAntialiasing,
+    /// The filter applied to subpixel antialiased glyphs to reduce color fringes.
+    pub lcd_filter: LcdFilter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextAntialiasing {
+    /// Glyph edges are blended with the background using a single coverage value.
+    Grayscale,
+    /// Glyph edges are blended per color channel, assuming an LCD with horizontal RGB
+    /// subpixels. Renderers which can't blend per channel fall back to grayscale.
+    Subpixel,
+    /// Glyph pixels are either fully covered or not at all.
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LcdFilter {
+    /// A five tap filter which removes most color fringes.
+    Default,
+    /// A three tap filter which keeps glyphs sharper, with some color fringes.
+    Light,
+    /// No filtering, which gives the sharpest glyphs and the strongest color fringes.
+    None,
+}
+
+impl Default for TextRendering {
+    /// macOS renders text without hinting and subpixel antialiasing, Windows uses both and
+    /// other platforms use hinting with grayscale antialiasing.
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self {
+                hinting: false,
+                antialiasing: TextAntialiasing::Grayscale,
+                lcd_filter: LcdFilter::Default,
+            }
+        } else if cfg!(target_os = "windows") {
+            Self {
+                hinting: true,
+                antialiasing: TextAntialiasing::Subpixel,
+                lcd_filter: LcdFilter::Default,
+            }
+        } else {
+            Self {
+                hinting: true,
+                antialiasing: TextAntialiasing::Grayscale,
+                lcd_filter: LcdFilter::Default,
+            }
+        }
+    }
+}
+
+impl LcdFilter {
+    fn weights(self) -> [u32; 5] {
+        match self {
+            LcdFilter::Default => [8, 77, 86, 77, 8],
+            LcdFilter::Light => [0, 85, 86, 85, 0],
+            LcdFilter::None => [0, 0, 256, 0, 0],
+        }
+    }
+}
+
+const SOURCES: &[Source] = &[
+    Source::ColorOutline(0),
+    Source::ColorBitmap(StrikeWith::BestFit),
+    Source::Outline,
+];
+
+/// Rasterizes a glyph with the given settings.
+///
+/// Subpixel antialiased glyphs are returned as a [`SwashContent::SubpixelMask`] with the
+/// coverage of each channel in the red, green and blue components, and their maximum in the
+/// alpha component. Color glyphs are returned as [`SwashContent::Color`].
+pub fn rasterize_glyph(cache_key: CacheKey, config: TextRendering) -> Option<SwashImage> {
+    let font = FONT_SYSTEM.lock().get_font(cache_key.font_id)?;
+    let mut context = ScaleContext::new();
+    let mut scaler = context
+        .builder(font.as_swash())
+        .size(cache_key.font_size as f32)
+        .hint(config.hinting)
+        .build();
+    let offset = Vector::new(cache_key.x_bin.as_float(), cache_key.y_bin.as_float());
+
+    if config.antialiasing != TextAntialiasing::Subpixel {
+        let mut image = Render::new(SOURCES)
+            .format(Format::Alpha)
+            .offset(offset)
+            .render(&mut scaler, cache_key.glyph_id)?;
+        if config.antialiasing == TextAntialiasing::None && image.content == SwashContent::Mask {
+            for alpha in image.data.iter_mut() {
+                *alpha = if *alpha >= 128 { 255 } else { 0 };
+            }
+        }
+        return Some(image);
+    }
+
+    // Render the glyph at three times the horizontal resolution, then filter the coverage of
+    // the subpixels and pack them into the channels of each pixel.
+    let wide = Render::new(SOURCES)
+        .format(Format::Alpha)
+        .transform(Some(Transform::scale(3.0, 1.0)))
+        .offset(Vector::new(offset.x * 3.0, offset.y))
+        .render(&mut scaler, cache_key.glyph_id)?;
+    if wide.content != SwashContent::Mask {
+        // Color glyphs can't be stretched, so they are rendered normally
+        return Render::new(SOURCES)
+            .format(Format::Alpha)
+            .offset(offset)
+            .render(&mut scaler, cache_key.glyph_id);
+    }
+    Some(filter_subpixels(wide, config.lcd_filter))
+}
+
+/// Turns a coverage mask with three samples per pixel into a subpixel mask.
+fn filter_subpixels(wide: SwashImage, filter: LcdFilter) -> SwashImage {
+    let weights = filter.weights();
+    let wide_width = wide.placement.width as i32;
+    let height = wide.placement.height as usize;
+    // Leave room for the filter spreading two subpixels to each side
+    let left = (wide.placement.left - 2).div_euclid(3);
+    let start = wide.placement.left - left * 3;
+    let width = (start + wide_width + 2 + 2) / 3;
+
+    let coverage = |row: usize, subpixel: i32| -> u32 {
+        let x = subpixel - start;
+        if x < 0 || x >= wide_width {
+            0
+        } else {
+            wide.data[row * wide_width as usize + x as usize] as u32
+        }
+    };
+
+    let mut data = Vec::with_capacity(width as usize * height * 4);
+    for row in 0..height {
+        for x in 0..width {
+            let mut pixel = [0u8; 4];
+            for (channel, value) in pixel.iter_mut().take(3).enumerate() {
+                let subpixel = x * 3 + channel as i32;
+                let sum: u32 = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, weight)| weight * coverage(row, subpixel + i as i32 - 2))
+                    .sum();
+                *value = (sum / 256).min(255) as u8;
+            }
+            pixel[3] = pixel[0].max(pixel[1]).max(pixel[2]);
+            data.extend_from_slice(&pixel);
+        }
+    }
+
+    let mut image = wide;
+    image.content = SwashContent::SubpixelMask;
+    image.placement.left = left;
+    image.placement.width = width as u32;
+    image.data = data;
+    image
+}
@@ -0,0 +1,378 @@
+//! Exposing views to screen readers and other assistive technologies with AccessKit.
+//!
+//! Each window builds an AccessKit tree with a node per visible view, once an assistive
+//! technology starts observing the window and then after each frame. A node describes the
+//! view's role, label, value and the actions it supports. Views describe themselves in
+//! [`View::accessibility`](crate::view::View::accessibility), and single views can be described
+//! further with [`Decorators::accessible`](crate::views::Decorators::accessible).
+//!
+//! The node of a view with a click listener supports the default action, which clicks the
+//! view, and the node of a keyboard navigatable view can be focused. Other actions are sent to
+//! the view as [`Event::AccessibilityAction`](crate::event::Event::AccessibilityAction).
+//!
+//! ## Example
+//! ```ignore
+//! empty()
+//!     .style(|s| s.size(24.0, 24.0).background(Color::RED))
+//!     .accessible(|cx| {
+//!         cx.set_role(Role::Image);
+//!         cx.set_label("Recording");
+//!     })
+//! ```
+
+use std::{num::NonZeroU128, ops::RangeInclusive};
+
+use accesskit::{
+    Action, ActionHandler, ActionRequest, Checked, Node, NodeBuilder, NodeClassSet, NodeId, Role,
+    Tree, TreeUpdate,
+};
+use floem_winit::window::{Window, WindowId};
+
+use crate::{
+    app::UserEvent, context::AppState, event::EventListener, id::Id, view::View, Application,
+};
+
+/// Describes a view to assistive technologies, see [`View::accessibility`].
+pub struct AccessCx {
+    id: Id,
+    node: NodeBuilder,
+    label: Option<String>,
+}
+
+impl AccessCx {
+    fn new(id: Id) -> Self {
+        Self {
+            id,
+            node: NodeBuilder::new(Role::GenericContainer),
+            label: None,
+        }
+    }
+
+    /// The id of the view being described.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Sets what kind of element the view is, a generic container by default.
+    pub fn set_role(&mut self, role: Role) {
+        self.node.set_role(role);
+    }
+
+    /// Sets the text announced for the view. Buttons, checkboxes and list items without a label
+    /// are labeled with the text of the labels they contain.
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = Some(label.into());
+    }
+
+    /// Sets the text value of the view, such as the text of an input.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.node.set_value(value.into());
+    }
+
+    /// Sets whether the view is checked, with `None` for the mixed state.
+    pub fn set_checked(&mut self, checked: Option<bool>) {
+        self.node.set_checked(match checked {
+            Some(true) => Checked::True,
+            Some(false) => Checked::False,
+            None => Checked::Mixed,
+        });
+    }
+
+    /// Sets whether the view is selected, such as an item of a list.
+    pub fn set_selected(&mut self, selected: bool) {
+        self.node.set_selected(selected);
+    }
+
+    /// Sets the value of a view picking a number in `range`, such as a slider, which the
+    /// increment and decrement actions change by `step`.
+    pub fn set_numeric_value(&mut self, value: f64, range: RangeInclusive<f64>, step: f64) {
+        self.node.set_numeric_value(value);
+        self.node.set_min_numeric_value(*range.start());
+        self.node.set_max_numeric_value(*range.end());
+        self.node.set_numeric_value_step(step);
+    }
+
+    /// Declares that the view handles `action`, received as
+    /// [`Event::AccessibilityAction`](crate::event::Event::AccessibilityAction).
+    pub fn add_action(&mut self, action: Action) {
+        self.node.add_action(action);
+    }
+
+    /// The AccessKit node of the view, to set the properties without a method here.
+    pub fn node_mut(&mut self) -> &mut NodeBuilder {
+        &mut self.node
+    }
+}
+
+/// The events of the assistive technologies sent to a window.
+pub(crate) enum AccessibilityEvent {
+    /// An assistive technology started observing the window, so the tree has to be built.
+    Activated,
+    Action(ActionRequest),
+}
+
+pub(crate) fn node_id(id: Id) -> NodeId {
+    NodeId(NonZeroU128::new(id.to_raw() as u128 + 1).unwrap())
+}
+
+pub(crate) fn view_id(node_id: NodeId) -> Id {
+    Id::from_raw((node_id.0.get() - 1) as u64)
+}
+
+/// Whether the role takes its name from the text of its content when it isn't labeled.
+fn named_from_content(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Button
+            | Role::CheckBox
+            | Role::RadioButton
+            | Role::ListItem
+            | Role::Link
+            | Role::MenuItem
+            | Role::Tab
+    )
+}
+
+/// Adds the node of `view` and of its visible children to `nodes`. Returns the id of the node
+/// and the text of the labels it contains.
+fn build_node(
+    view: &dyn View,
+    app_state: &mut AppState,
+    classes: &mut NodeClassSet,
+    nodes: &mut Vec<(NodeId, Node)>,
+) -> (NodeId, String) {
+    let id = view.id();
+    let mut children = Vec::new();
+    let mut text = String::new();
+    view.for_each_child(&mut |child| {
+        if !app_state.is_hidden(child.id()) {
+            let (child_node, child_text) = build_node(child, app_state, classes, nodes);
+            children.push(child_node);
+            if !child_text.is_empty() {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&child_text);
+            }
+        }
+        false
+    });
+
+    let mut cx = AccessCx::new(id);
+    let rect = app_state
+        .get_layout_rect(id)
+        .scale_from_origin(app_state.scale);
+    cx.node.set_bounds(accesskit::Rect {
+        x0: rect.x0,
+        y0: rect.y0,
+        x1: rect.x1,
+        y1: rect.y1,
+    });
+    cx.node.set_children(children);
+    if app_state.keyboard_navigable.contains(&id) {
+        cx.add_action(Action::Focus);
+    }
+    if app_state.disabled.contains(&id) {
+        cx.node.set_disabled();
+    }
+    let state = app_state.view_states.get(&id);
+    if state.map_or(false, |state| {
+        state.event_listeners.contains_key(&EventListener::Click)
+    }) {
+        cx.add_action(Action::Default);
+    }
+    view.accessibility(&mut cx);
+    if let Some(describe) = state.and_then(|state| state.accessibility.as_ref()) {
+        describe(&mut cx);
+    }
+
+    let role = cx.node.role();
+    if role == Role::StaticText {
+        text = cx.label.clone().unwrap_or_default();
+    }
+    if let Some(label) = cx
+        .label
+        .take()
+        .or_else(|| (named_from_content(role) && !text.is_empty()).then(|| text.clone()))
+    {
+        cx.node.set_name(label);
+    }
+    let node_id = node_id(id);
+    nodes.push((node_id, cx.node.build(classes)));
+    (node_id, text)
+}
+
+/// The tree of the window's `root` view, focused on the focused view.
+fn tree_update(
+    root: &dyn View,
+    app_state: &mut AppState,
+    classes: &mut NodeClassSet,
+) -> TreeUpdate {
+    let mut nodes = Vec::new();
+    let (root, _) = build_node(root, app_state, classes, &mut nodes);
+    let focus = app_state
+        .focus
+        .map(node_id)
+        .filter(|focus| nodes.iter().any(|(node, _)| node == focus))
+        .unwrap_or(root);
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(root)),
+        focus,
+    }
+}
+
+/// The tree given before the window built its own, which is requested with the
+/// [`AccessibilityEvent::Activated`] event.
+fn initial_tree(window_id: WindowId) -> TreeUpdate {
+    send(window_id, AccessibilityEvent::Activated);
+    let root = NodeId(NonZeroU128::MIN);
+    let node = NodeBuilder::new(Role::Window).build(&mut NodeClassSet::new());
+    TreeUpdate {
+        nodes: vec![(root, node)],
+        tree: Some(Tree::new(root)),
+        focus: root,
+    }
+}
+
+fn send(window_id: WindowId, event: AccessibilityEvent) {
+    Application::with_event_loop_proxy(|proxy| {
+        let _ = proxy.send_event(UserEvent::Accessibility { window_id, event });
+    });
+}
+
+/// Sends the actions requested by assistive technologies to the window.
+struct Handler {
+    window_id: WindowId,
+}
+
+impl ActionHandler for Handler {
+    fn do_action(&mut self, request: ActionRequest) {
+        send(self.window_id, AccessibilityEvent::Action(request));
+    }
+}
+
+#[cfg(target_os = "windows")]
+type PlatformAdapter = accesskit_windows::SubclassingAdapter;
+
+#[cfg(target_os = "macos")]
+type PlatformAdapter = accesskit_macos::SubclassingAdapter;
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+type PlatformAdapter = accesskit_unix::Adapter;
+
+/// The connection of a window to the accessibility API of the platform.
+pub(crate) struct Adapter {
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "freebsd"
+    ))]
+    adapter: PlatformAdapter,
+    classes: NodeClassSet,
+}
+
+impl Adapter {
+    /// Connects `window` to the accessibility API of the platform.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "freebsd"
+    ))]
+    pub(crate) fn new(window: &Window) -> Option<Self> {
+        let window_id = window.id();
+        #[cfg(target_os = "windows")]
+        let adapter = {
+            use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+            let RawWindowHandle::Win32(handle) = window.raw_window_handle() else {
+                return None;
+            };
+            accesskit_windows::SubclassingAdapter::new(
+                accesskit_windows::HWND(handle.hwnd as isize),
+                move || initial_tree(window_id),
+                Box::new(Handler { window_id }),
+            )
+        };
+        #[cfg(target_os = "macos")]
+        let adapter = {
+            use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+            let RawWindowHandle::AppKit(handle) = window.raw_window_handle() else {
+                return None;
+            };
+            // SAFETY: the view outlives the adapter, which is dropped along with the window
+            unsafe {
+                accesskit_macos::SubclassingAdapter::new(
+                    handle.ns_view,
+                    move || initial_tree(window_id),
+                    Box::new(Handler { window_id }),
+                )
+            }
+        };
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        let adapter = accesskit_unix::Adapter::new(
+            window.title(),
+            "Floem".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+            move || initial_tree(window_id),
+            Box::new(Handler { window_id }),
+        )?;
+        Some(Self {
+            adapter,
+            classes: NodeClassSet::new(),
+        })
+    }
+
+    /// Platforms without an accessibility API have no adapter.
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "freebsd"
+    )))]
+    pub(crate) fn new(_window: &Window) -> Option<Self> {
+        None
+    }
+
+    /// Sends the tree of `root` to the assistive technologies, if any is observing the window.
+    pub(crate) fn update(&mut self, root: &dyn View, app_state: &mut AppState) {
+        let classes = &mut self.classes;
+        let update = move || tree_update(root, app_state, classes);
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        if let Some(events) = self.adapter.update_if_active(update) {
+            events.raise();
+        }
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        self.adapter.update_if_active(update);
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd"
+        )))]
+        let _ = update;
+    }
+
+    /// Tells the assistive technologies whether the window has the keyboard focus.
+    pub(crate) fn window_focused(&self, focused: bool) {
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        self.adapter.update_window_focus_state(focused);
+        // The subclassing adapters follow the focus of the window themselves
+        #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+        let _ = focused;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{node_id, view_id};
+    use crate::id::Id;
+
+    #[test]
+    fn node_ids() {
+        let id = Id::next();
+        assert_eq!(view_id(node_id(id)), id);
+        assert_eq!(node_id(Id::from_raw(0)).0.get(), 1);
+    }
+}
@@ -4,9 +4,10 @@ use std::{
     time::{Duration, Instant},
 };
 
-use floem_reactive::Scope;
-use floem_winit::window::ResizeDirection;
-use kurbo::{Point, Size, Vec2};
+use floem_peniko::Color;
+use floem_reactive::{create_effect, RwSignal, Scope};
+use floem_winit::window::{ResizeDirection, Theme};
+use kurbo::{Insets, Point, Size, Vec2};
 
 use crate::{
     app::{add_app_update_event, AppUpdateEvent},
@@ -14,6 +15,7 @@ use crate::{
     file::{FileDialogOptions, FileInfo},
     id::Id,
     menu::Menu,
+    notification::{Notification, NotificationResponse},
     update::{UpdateMessage, CENTRAL_UPDATE_MESSAGES},
     view::View,
     window_handle::{get_current_view, set_current_view},
@@ -54,6 +56,23 @@ pub fn update_window_scale(window_scale: f64) {
     add_update_message(UpdateMessage::WindowScale(window_scale));
 }
 
+/// Binds the zoom of the current window to `zoom`, applied on top of the OS scale factor so
+/// layout, text and hit testing all follow it.
+///
+/// `Ctrl` `=` and `Ctrl` `-` (`Cmd` on macOS) zoom in and out in steps and `Ctrl` `0` resets
+/// the zoom to `1.0`, updating the signal.
+pub fn set_window_zoom(zoom: RwSignal<f64>) {
+    let window = get_current_view();
+    create_effect(move |_| {
+        let scale = zoom.get();
+        CENTRAL_UPDATE_MESSAGES.with(|msgs| {
+            msgs.borrow_mut()
+                .push((window, UpdateMessage::WindowScale(scale)));
+        });
+    });
+    add_update_message(UpdateMessage::WindowZoom(zoom));
+}
+
 pub(crate) struct Timer {
     pub(crate) token: TimerToken,
     pub(crate) action: Box<dyn FnOnce(TimerToken)>,
@@ -140,6 +159,27 @@ pub fn save_as(options: FileDialogOptions, file_info_action: impl Fn(Option<File
     });
 }
 
+/// Posts a native desktop notification, shown by the operating system outside of the app
+/// windows. `on_response` is called once the user clicks the notification or one of its
+/// buttons, or dismisses it, unless the current scope was disposed by then.
+///
+/// On macOS the notification is posted with `osascript`, which shows only the title and body.
+/// It has no action buttons and doesn't report clicks, so `on_response` is always called with
+/// [`NotificationResponse::Dismissed`] once the notification is posted.
+pub fn show_notification(
+    notification: Notification,
+    on_response: impl FnOnce(NotificationResponse) + 'static,
+) {
+    let send = create_ext_action(Scope::current(), on_response);
+    std::thread::spawn(move || {
+        send(notification.post());
+    });
+}
+
+/// Shows `menu` at `pos` in the current window, or at the cursor without a position.
+///
+/// This is a native menu on macOS and Windows, elsewhere it's rendered by Floem like the
+/// menus of [`Decorators::context_menu`](crate::views::Decorators::context_menu).
 pub fn show_context_menu(menu: Menu, pos: Option<Point>) {
     add_update_message(UpdateMessage::ShowContextMenu { menu, pos });
 }
@@ -152,6 +192,17 @@ pub fn set_window_title(title: String) {
     add_update_message(UpdateMessage::SetWindowTitle { title });
 }
 
+/// Sets whether the native window decorations use a dark or light appearance. `None` follows
+/// the system appearance.
+pub fn set_window_theme(theme: Option<Theme>) {
+    add_update_message(UpdateMessage::SetWindowTheme { theme });
+}
+
+/// Keeps the cursor inside the window while `confined` is true.
+pub fn confine_cursor(confined: bool) {
+    add_update_message(UpdateMessage::ConfineCursor { confined });
+}
+
 pub fn focus_window() {
     add_update_message(UpdateMessage::FocusWindow);
 }
@@ -164,12 +215,93 @@ pub fn set_ime_cursor_area(position: Point, size: Size) {
     add_update_message(UpdateMessage::SetImeCursorArea { position, size });
 }
 
-/// Creates a new overlay on the current window.
+/// Sets the part of the current window covered by the on-screen keyboard, as distances from
+/// its edges in logical pixels. Empty insets mark the keyboard as hidden.
+///
+/// This is meant for platform integrations which receive the frame of the keyboard from the
+/// OS, see [`soft_keyboard`](crate::soft_keyboard).
+pub fn set_soft_keyboard_insets(insets: Insets) {
+    add_update_message(UpdateMessage::SoftKeyboardInsets { insets });
+}
+
+/// How an overlay added with [`add_overlay_with_config`] is stacked and dismissed.
+#[derive(Default)]
+pub struct OverlayConfig {
+    pub(crate) z_index: i32,
+    pub(crate) modal: bool,
+    pub(crate) backdrop: Option<Color>,
+    pub(crate) dismiss_on_outside_click: bool,
+    pub(crate) on_dismiss: Option<Box<dyn Fn()>>,
+}
+
+impl OverlayConfig {
+    /// Overlays with a higher z-index are shown above the ones with a lower z-index, and
+    /// overlays with the same z-index are shown in the order they were added. Defaults to 0.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// A modal overlay covers the whole window, so pointer events outside of its view don't
+    /// reach the views and overlays below it.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
+    /// Makes the overlay modal, painting `color` over the views below it.
+    pub fn backdrop(mut self, color: Color) -> Self {
+        self.modal = true;
+        self.backdrop = Some(color);
+        self
+    }
+
+    /// Removes the overlay when the pointer is pressed outside of its view, unless it's pressed
+    /// in an overlay above it.
+    pub fn dismiss_on_outside_click(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_outside_click = dismiss;
+        self
+    }
+
+    /// Called when the overlay is removed by a click outside of it.
+    pub fn on_dismiss(mut self, on_dismiss: impl Fn() + 'static) -> Self {
+        self.on_dismiss = Some(Box::new(on_dismiss));
+        self
+    }
+}
+
+/// Creates a new overlay on the current window, above the main view of the window and the
+/// overlays with a lower or equal z-index.
+///
+/// `view` is called with the id of the overlay, which can be passed to [`remove_overlay`].
 pub fn add_overlay<V: View + 'static>(position: Point, view: impl FnOnce(Id) -> V + 'static) -> Id {
+    add_overlay_with_config(position, OverlayConfig::default(), view)
+}
+
+/// Creates a new overlay on the current window, with its view at `position`.
+///
+/// ## Example
+/// ```ignore
+/// // A dialog which closes when clicking outside of it
+/// add_overlay_with_config(
+///     Point::new(100.0, 100.0),
+///     OverlayConfig::default()
+///         .backdrop(Color::BLACK.with_alpha_factor(0.4))
+///         .dismiss_on_outside_click(true)
+///         .on_dismiss(move || open.set(false)),
+///     |overlay| dialog(overlay),
+/// );
+/// ```
+pub fn add_overlay_with_config<V: View + 'static>(
+    position: Point,
+    config: OverlayConfig,
+    view: impl FnOnce(Id) -> V + 'static,
+) -> Id {
     let id = Id::next();
     add_update_message(UpdateMessage::AddOverlay {
         id,
         position,
+        config,
         view: Box::new(move || Box::new(view(id))),
     });
     id
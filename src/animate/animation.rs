@@ -1,15 +1,20 @@
-use crate::style::{Background, BorderColor, BorderRadius, TextColor};
+use crate::{
+    action::exec_after,
+    style::{Background, BorderColor, BorderRadius, Style, StyleMapValue, TextColor},
+};
 
 use super::{
     anim_val::AnimValue, AnimId, AnimPropKind, AnimState, AnimStateKind, AnimatedProp, Easing,
     EasingFn, EasingMode,
 };
-use std::{borrow::BorrowMut, collections::HashMap, time::Duration, time::Instant};
+use std::{
+    any::Any, borrow::BorrowMut, collections::HashMap, fmt, rc::Rc, time::Duration, time::Instant,
+};
 
 use floem_peniko::Color;
 use floem_reactive::create_effect;
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Animation {
     pub(crate) id: AnimId,
     pub(crate) state: AnimState,
@@ -19,7 +24,34 @@ pub struct Animation {
     pub(crate) duration: Duration,
     pub(crate) repeat_mode: RepeatMode,
     pub(crate) repeat_count: usize,
+    pub(crate) alternate: bool,
     pub(crate) animated_props: HashMap<AnimPropKind, AnimatedProp>,
+    /// Sorted by their offsets.
+    pub(crate) keyframes: Vec<KeyFrame>,
+    pub(crate) on_complete: Option<Rc<dyn Fn()>>,
+}
+
+impl fmt::Debug for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Animation")
+            .field("id", &self.id)
+            .field("state", &self.state)
+            .field("easing", &self.easing)
+            .field("duration", &self.duration)
+            .field("repeat_mode", &self.repeat_mode)
+            .field("repeat_count", &self.repeat_count)
+            .field("alternate", &self.alternate)
+            .field("animated_props", &self.animated_props)
+            .field("keyframes", &self.keyframes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The style of a view at some point of an [`Animation`]. See [`Animation::keyframe`]
+#[derive(Clone, Debug)]
+pub(crate) struct KeyFrame {
+    offset: f64,
+    style: Style,
 }
 
 pub(crate) fn assert_valid_time(time: f64) {
@@ -50,7 +82,10 @@ pub fn animation() -> Animation {
         duration: Duration::from_secs(1),
         repeat_mode: RepeatMode::Times(1),
         repeat_count: 0,
+        alternate: false,
         animated_props: HashMap::new(),
+        keyframes: Vec::new(),
+        on_complete: None,
     }
 }
 
@@ -173,6 +208,13 @@ impl Animation {
         self
     }
 
+    /// Plays every other pass of a repeating animation backwards, so it goes back and forth
+    /// instead of jumping back to the start.
+    pub fn alternate(mut self, alternate: bool) -> Self {
+        self.alternate = alternate;
+        self
+    }
+
     /// Should the animation repeat forever?
     pub fn repeat(mut self, repeat: bool) -> Self {
         self.repeat_mode = if repeat {
@@ -189,6 +231,50 @@ impl Animation {
         self
     }
 
+    /// Sets the style properties of the view at `offset` through the animation, from `0.0` at
+    /// the start to `1.0` at the end of a pass. The properties are interpolated between the
+    /// keyframes setting them, or change halfway between the keyframes if they can't be
+    /// interpolated. A property missing from the first or last keyframe starts or ends at the
+    /// value the view's style gives it.
+    ///
+    /// ```ignore
+    /// label(|| "Pulse").animation(|a| {
+    ///     a.keyframe(0.0, |s| s.background(Color::WHITE).padding(4.0))
+    ///         .keyframe(0.5, |s| s.background(Color::LIGHT_BLUE).padding(8.0))
+    ///         .keyframe(1.0, |s| s.background(Color::WHITE).padding(4.0))
+    ///         .duration(Duration::from_millis(800))
+    ///         .repeat(true)
+    /// })
+    /// ```
+    pub fn keyframe(mut self, offset: f64, style: impl FnOnce(Style) -> Style) -> Self {
+        let offset = offset.clamp(0.0, 1.0);
+        let style = style(Style::new());
+        let index = self
+            .keyframes
+            .partition_point(|frame| frame.offset <= offset);
+        self.keyframes.insert(index, KeyFrame { offset, style });
+        self
+    }
+
+    /// Calls `on_complete` once the animation has finished all of its passes. It's called again
+    /// if the animation is restarted by a change to one of its reactive properties, and never
+    /// for an animation which repeats forever.
+    pub fn on_complete(mut self, on_complete: impl Fn() + 'static) -> Self {
+        self.on_complete = Some(Rc::new(on_complete));
+        self
+    }
+
+    /// Sets how the animation interpolates, from an [`EasingFn`] or a [`Spring`]. A spring sets
+    /// the duration of the animation to the time it takes to settle, which
+    /// [`duration`](Self::duration) can then stretch or squeeze.
+    pub fn easing(mut self, easing: impl Into<Easing>) -> Self {
+        self.easing = easing.into();
+        if let EasingFn::Spring(spring) = self.easing.func {
+            self.duration = spring.settling_duration();
+        }
+        self
+    }
+
     pub fn easing_fn(mut self, easing_fn: EasingFn) -> Self {
         self.easing.func = easing_fn;
         self
@@ -278,6 +364,7 @@ impl Animation {
             }
             AnimState::PassFinished { elapsed } => match self.repeat_mode {
                 RepeatMode::LoopForever => {
+                    self.repeat_count += 1;
                     self.state = AnimState::PassInProgress {
                         started_on: Instant::now(),
                         elapsed: Duration::ZERO,
@@ -288,6 +375,10 @@ impl Animation {
                     if self.repeat_count >= times {
                         self.state = AnimState::Completed {
                             elapsed: Some(*elapsed),
+                        };
+                        if let Some(on_complete) = self.on_complete.clone() {
+                            // Called outside of the style pass advancing the animation
+                            exec_after(Duration::ZERO, move |_| on_complete());
                         }
                     } else {
                         self.state = AnimState::PassInProgress {
@@ -309,18 +400,24 @@ impl Animation {
         self.animated_props.borrow_mut()
     }
 
-    pub(crate) fn animate_prop(&self, elapsed: Duration, prop_kind: &AnimPropKind) -> AnimValue {
-        let mut elapsed = elapsed;
-        let prop = self.animated_props.get(prop_kind).unwrap();
+    /// Whether the current pass plays backwards because the animation alternates.
+    fn is_reversed_pass(&self) -> bool {
+        let pass = match self.state {
+            // The count includes the last pass once the animation completes
+            AnimState::Completed { .. } => self.repeat_count.saturating_sub(1),
+            _ => self.repeat_count,
+        };
+        self.alternate && pass % 2 == 1
+    }
 
+    /// How far the animation is through its pass after `elapsed`, eased, from `0.0` at the
+    /// start to `1.0` at the end.
+    fn progress(&self, elapsed: Duration) -> f64 {
+        let mut elapsed = elapsed;
         if let Some(skip) = self.skip {
             elapsed += skip;
         }
 
-        if self.duration == Duration::ZERO {
-            return prop.from();
-        }
-
         if elapsed > self.duration {
             elapsed = self.duration;
         }
@@ -329,14 +426,98 @@ impl Animation {
         let time = self.easing.ease(time);
         assert_valid_time(time);
 
-        if self.auto_reverse {
+        let time = if self.auto_reverse {
             if time > 0.5 {
-                prop.animate(time * 2.0 - 1.0, AnimDirection::Backward)
+                2.0 - time * 2.0
             } else {
-                prop.animate(time * 2.0, AnimDirection::Forward)
+                time * 2.0
             }
         } else {
-            prop.animate(time, AnimDirection::Forward)
+            time
+        };
+        if self.is_reversed_pass() {
+            1.0 - time
+        } else {
+            time
+        }
+    }
+
+    pub(crate) fn animate_prop(&self, elapsed: Duration, prop_kind: &AnimPropKind) -> AnimValue {
+        let prop = self.animated_props.get(prop_kind).unwrap();
+
+        if self.duration == Duration::ZERO {
+            return prop.from();
+        }
+
+        prop.animate(self.progress(elapsed), AnimDirection::Forward)
+    }
+
+    /// Applies the style properties of the keyframes to `style` after `elapsed`.
+    pub(crate) fn animate_keyframes(&self, elapsed: Duration, style: &mut Style) {
+        if self.keyframes.is_empty() {
+            return;
+        }
+        let time = if self.duration == Duration::ZERO {
+            1.0
+        } else {
+            self.progress(elapsed)
+        };
+
+        let mut props = Vec::new();
+        for prop in self
+            .keyframes
+            .iter()
+            .flat_map(|frame| frame.style.map.keys().copied())
+        {
+            if props.contains(&prop) {
+                continue;
+            }
+            props.push(prop);
+
+            let base = style
+                .map
+                .get(&prop)
+                .and_then(|v| v.as_ref().cloned())
+                .unwrap_or_else(|| (prop.info.default_as_any)());
+            let mut frames: Vec<(f64, Rc<dyn Any>)> = self
+                .keyframes
+                .iter()
+                .filter_map(|frame| match frame.style.map.get(&prop) {
+                    Some(StyleMapValue::Val(v)) => Some((frame.offset, v.clone())),
+                    Some(StyleMapValue::Unset) => Some((frame.offset, base.clone())),
+                    None => None,
+                })
+                .collect();
+            if !matches!(frames.first(), Some((offset, _)) if *offset <= 0.0) {
+                frames.insert(0, (0.0, base.clone()));
+            }
+            if !matches!(frames.last(), Some((offset, _)) if *offset >= 1.0) {
+                frames.push((1.0, base));
+            }
+
+            // Spring easing overshoots the ends, which extrapolates the outer keyframes
+            let index = frames
+                .iter()
+                .position(|(offset, _)| *offset > time)
+                .unwrap_or(frames.len() - 1)
+                .clamp(1, frames.len() - 1);
+            let (from_offset, from) = &frames[index - 1];
+            let (to_offset, to) = &frames[index];
+            let span = to_offset - from_offset;
+            let local = if span > 0.0 {
+                (time - from_offset) / span
+            } else {
+                1.0
+            };
+            let value =
+                (prop.info.interpolate)(from.as_ref(), to.as_ref(), local).unwrap_or_else(|| {
+                    if local < 0.5 {
+                        from.clone()
+                    } else {
+                        to.clone()
+                    }
+                });
+            style.map.insert(prop, StyleMapValue::Val(value));
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::f64::consts::PI;
+use std::{f64::consts::PI, time::Duration};
 
 use super::assert_valid_time;
 
@@ -41,6 +41,118 @@ pub enum EasingFn {
     Quintic,
     /// Creates an animation that accelerates and/or decelerates using a sine formula.
     Sine,
+    /// Creates an animation that moves like a mass pulled to the end by a damped spring,
+    /// overshooting it and settling depending on the damping. The [`EasingMode`] doesn't apply.
+    Spring(Spring),
+}
+
+/// A damped spring for [`EasingFn::Spring`], pulling a mass from the start of an animation to
+/// its end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    mass: f64,
+    stiffness: f64,
+    damping: f64,
+    initial_velocity: f64,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Spring::new(1.0, 100.0, 10.0)
+    }
+}
+
+/// How far from the end the spring has to stay before it's considered settled, relative to the
+/// distance it travels.
+const SPRING_REST: f64 = 0.001;
+
+impl Spring {
+    pub fn new(mass: f64, stiffness: f64, damping: f64) -> Self {
+        Spring {
+            mass: mass.max(f64::EPSILON),
+            stiffness: stiffness.max(f64::EPSILON),
+            damping: damping.max(0.0),
+            initial_velocity: 0.0,
+        }
+    }
+
+    /// A spring which overshoots the end a few times before settling.
+    pub fn bouncy() -> Self {
+        Spring::new(1.0, 180.0, 8.0)
+    }
+
+    /// A critically damped spring, which reaches the end quickly without overshooting it.
+    pub fn stiff() -> Self {
+        Spring::new(1.0, 400.0, 40.0)
+    }
+
+    /// The velocity of the mass at the start, in distances of the animation per second. Useful
+    /// to keep the momentum of a fling or of an interrupted animation.
+    pub fn initial_velocity(mut self, velocity: f64) -> Self {
+        self.initial_velocity = velocity;
+        self
+    }
+
+    fn undamped_frequency(&self) -> f64 {
+        (self.stiffness / self.mass).sqrt()
+    }
+
+    fn damping_ratio(&self) -> f64 {
+        self.damping / (2.0 * (self.stiffness * self.mass).sqrt())
+    }
+
+    /// How fast the distance to the end shrinks, as the exponent of its envelope.
+    fn decay_rate(&self) -> f64 {
+        let omega = self.undamped_frequency();
+        let zeta = self.damping_ratio();
+        if zeta <= 1.0 {
+            zeta * omega
+        } else {
+            omega * (zeta - (zeta * zeta - 1.0).sqrt())
+        }
+    }
+
+    /// The time the spring takes to settle at the end, which is the duration an animation
+    /// easing with it gets.
+    pub fn settling_duration(&self) -> Duration {
+        let decay_rate = self.decay_rate();
+        if decay_rate <= 0.0 {
+            // An undamped spring never settles
+            return Duration::from_secs(60);
+        }
+        let settle = (1.0 + self.initial_velocity.abs()) / SPRING_REST;
+        Duration::from_secs_f64((settle.ln() / decay_rate).min(60.0))
+    }
+
+    /// The position of the mass `time` seconds in, from 0 at the start to 1 at the end.
+    pub(crate) fn position(&self, time: f64) -> f64 {
+        let omega = self.undamped_frequency();
+        let zeta = self.damping_ratio();
+        let velocity = self.initial_velocity;
+        // The displacement from the end solves `m x'' + c x' + k x = 0`, with `x(0) = -1`
+        let displacement = if (zeta - 1.0).abs() < 1e-6 {
+            (-1.0 + (velocity - omega) * time) * (-omega * time).exp()
+        } else if zeta < 1.0 {
+            let damped = omega * (1.0 - zeta * zeta).sqrt();
+            let b = (velocity - zeta * omega) / damped;
+            (-zeta * omega * time).exp() * (-(damped * time).cos() + b * (damped * time).sin())
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let c2 = (velocity + r1) / (r2 - r1);
+            let c1 = -1.0 - c2;
+            c1 * (r1 * time).exp() + c2 * (r2 * time).exp()
+        };
+        1.0 + displacement
+    }
+
+    fn ease(&self, time: f64) -> f64 {
+        if time >= 1.0 {
+            return 1.0;
+        }
+        self.position(time * self.settling_duration().as_secs_f64())
+    }
 }
 
 // See https://easings.net/ and
@@ -62,6 +174,21 @@ fn elastic_easing(time: f64) -> f64 {
     }
 }
 
+impl From<EasingFn> for Easing {
+    fn from(func: EasingFn) -> Self {
+        Easing {
+            mode: EasingMode::default(),
+            func,
+        }
+    }
+}
+
+impl From<Spring> for Easing {
+    fn from(spring: Spring) -> Self {
+        EasingFn::Spring(spring).into()
+    }
+}
+
 impl Easing {
     pub(crate) fn apply_easing_fn(&self, time: f64) -> f64 {
         assert_valid_time(time);
@@ -82,6 +209,7 @@ impl Easing {
             EasingFn::Quartic => time.powf(4.0),
             EasingFn::Quintic => time.powf(5.0),
             EasingFn::Sine => 1.0 - ((time * PI) / 2.0).cos(),
+            EasingFn::Spring(spring) => spring.ease(time),
             EasingFn::Back => todo!(),
             EasingFn::Bounce => todo!(),
         }
@@ -89,6 +217,9 @@ impl Easing {
 
     pub(crate) fn ease(&self, time: f64) -> f64 {
         assert_valid_time(time);
+        if let EasingFn::Spring(spring) = self.func {
+            return spring.ease(time);
+        }
         match self.mode {
             EasingMode::In => self.apply_easing_fn(time),
             EasingMode::Out => 1.0 - self.apply_easing_fn(1.0 - time),
@@ -102,3 +233,22 @@ impl Easing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Spring;
+
+    #[test]
+    fn spring_settles() {
+        for spring in [Spring::default(), Spring::bouncy(), Spring::stiff()] {
+            assert!(spring.position(0.0).abs() < 1e-9);
+            let settled = spring.settling_duration().as_secs_f64();
+            assert!((spring.position(settled) - 1.0).abs() < 0.01);
+            assert_eq!(spring.ease(1.0), 1.0);
+        }
+        let overshoot = (1..100)
+            .map(|i| Spring::bouncy().ease(i as f64 / 100.0))
+            .fold(0.0, f64::max);
+        assert!(overshoot > 1.0);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{any::Any, rc::Rc};
 
 use floem_peniko::Color;
+use floem_renderer::color;
 
 use crate::{animate::AnimDirection, style::StylePropRef, unit::Px};
 
@@ -68,52 +69,19 @@ impl AnimatedProp {
         from * (1.0 - time) + to * time
     }
 
-    pub(crate) fn animate_usize(
+    pub(crate) fn animate_color(
         &self,
-        from: u8,
-        to: u8,
+        from: Color,
+        to: Color,
         time: f64,
         direction: AnimDirection,
-    ) -> u8 {
+    ) -> Color {
         assert_valid_time(time);
         let (from, to) = match direction {
             AnimDirection::Forward => (from, to),
             AnimDirection::Backward => (to, from),
         };
-
-        if time == 0.0 {
-            return from;
-        }
-        if (1.0 - time).abs() < f64::EPSILON {
-            return to;
-        }
-        if from == to {
-            return from;
-        }
-
-        let from = from as f64;
-        let to = to as f64;
-
-        let val = from * (1.0 - time) + to * time;
-        if to >= from {
-            (val + 0.5) as u8
-        } else {
-            (val - 0.5) as u8
-        }
-    }
-
-    pub(crate) fn animate_color(
-        &self,
-        from: Color,
-        to: Color,
-        time: f64,
-        direction: AnimDirection,
-    ) -> Color {
-        let r = self.animate_usize(from.r, to.r, time, direction);
-        let g = self.animate_usize(from.g, to.g, time, direction);
-        let b = self.animate_usize(from.b, to.b, time, direction);
-        let a = self.animate_usize(from.a, to.a, time, direction);
-        Color { r, g, b, a }
+        color::mix_linear(from, to, time)
     }
 
     pub(crate) fn animate(&self, time: f64, direction: AnimDirection) -> AnimValue {
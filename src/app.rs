@@ -1,17 +1,26 @@
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{
+    cell::RefCell,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    rc::Rc,
+    sync::Arc,
+};
 
 use floem_reactive::WriteSignal;
+use floem_renderer::{color::ColorSpace, TextRendering};
 use floem_winit::{
-    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
     monitor::MonitorHandle,
     window::WindowId,
 };
+use kurbo::Point;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
 use crate::{
-    action::Timer, app_handle::ApplicationHandle, clipboard::Clipboard, inspector::Capture,
-    profiler::Profile, view::View, window::WindowConfig,
+    accessibility::AccessibilityEvent, action::Timer, app_handle::ApplicationHandle,
+    clipboard::Clipboard, gamepad::GamepadEvent, inspector::Capture, profiler::Profile,
+    tear_off::TearOffDrop, view::View, window::WindowConfig,
 };
 
 use raw_window_handle::HasRawDisplayHandle;
@@ -31,13 +40,42 @@ pub fn launch<V: View + 'static>(app_view: impl Fn() -> V + 'static) {
 
 pub enum AppEvent {
     WillTerminate,
-    Reopen { has_visible_windows: bool },
+    Reopen {
+        has_visible_windows: bool,
+    },
+    /// The app was launched again while running in
+    /// [single instance mode](Application::single_instance). `args` are the arguments of the
+    /// new launch, without the executable.
+    SecondInstance {
+        args: Vec<String>,
+    },
+    /// The app was asked to open links with one of the schemes enabled with
+    /// [`Application::url_schemes`].
+    OpenUrls(Vec<String>),
+    /// The app was asked to open files, such as by the file manager.
+    OpenFiles(Vec<PathBuf>),
+    /// A task set with [`set_tasks`](crate::recent_documents::set_tasks) was picked, launching
+    /// the app. This is the id of the task.
+    RunTask(String),
 }
 
 pub(crate) enum UserEvent {
     AppUpdate,
+    Gamepad(GamepadEvent),
     Idle,
     QuitApp,
+    SecondInstance {
+        args: Vec<String>,
+    },
+    Open {
+        urls: Vec<String>,
+        files: Vec<PathBuf>,
+    },
+    RunTask(String),
+    Accessibility {
+        window_id: WindowId,
+        event: AccessibilityEvent,
+    },
 }
 
 pub(crate) enum AppUpdateEvent {
@@ -48,6 +86,18 @@ pub(crate) enum AppUpdateEvent {
     CloseWindow {
         window_id: WindowId,
     },
+    /// Moves the window to `position` on the screen.
+    MoveWindow {
+        window_id: WindowId,
+        position: Point,
+    },
+    /// Calls `on_drop` with the window under `pointer` on the screen, other than the `preview`.
+    TearOffDrop {
+        pointer: Point,
+        origin: Point,
+        preview: Option<WindowId>,
+        on_drop: Rc<dyn Fn(TearOffDrop)>,
+    },
     CaptureWindow {
         window_id: WindowId,
         capture: WriteSignal<Option<Rc<Capture>>>,
@@ -80,6 +130,11 @@ pub(crate) fn add_app_update_event(event: AppUpdateEvent) {
 pub struct Application {
     handle: Option<ApplicationHandle>,
     event_listener: Option<Box<AppEventCallback>>,
+    url_schemes: Vec<String>,
+    /// Whether the links and files the app is asked to open are received.
+    handles_open: bool,
+    /// Whether a panic reaching the event loop shows the crash screen.
+    crash_screen: bool,
     event_loop: EventLoop<UserEvent>,
 }
 
@@ -100,9 +155,13 @@ impl Application {
             Clipboard::init(event_loop.raw_display_handle());
         }
         let handle = ApplicationHandle::new();
+        crate::text_scale::refresh_system_text_scale();
         Self {
             handle: Some(handle),
             event_listener: None,
+            url_schemes: Vec::new(),
+            handles_open: false,
+            crash_screen: false,
             event_loop,
         }
     }
@@ -112,6 +171,112 @@ impl Application {
         self
     }
 
+    /// Starts reading gamepads, sending their input to the focused window as
+    /// [`Event::Gamepad`](crate::event::Event::Gamepad).
+    ///
+    /// When `navigation` is true the D-pad moves the focus between views, like the arrow keys
+    /// with `Alt`, and the south button clicks the focused view.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad(self, navigation: bool) -> Self {
+        crate::gamepad::set_navigation_enabled(navigation);
+        crate::gamepad::spawn_gamepad_thread();
+        self
+    }
+
+    /// Sets the color space the components of style colors are given in, for example to use
+    /// color values from a Display P3 design.
+    ///
+    /// Windows are always drawn in sRGB, so colors are converted to sRGB when they are drawn
+    /// and wide gamut colors outside of the sRGB gamut are clamped to it.
+    pub fn color_space(self, color_space: ColorSpace) -> Self {
+        floem_renderer::color::set_color_space(color_space);
+        self
+    }
+
+    /// Sets how text is rendered in every window, unless a view overrides it with
+    /// [`Style::text_rendering`](crate::style::Style::text_rendering). The default depends on
+    /// the platform.
+    pub fn text_rendering(self, config: TextRendering) -> Self {
+        crate::renderer::set_default_text_rendering(config);
+        self
+    }
+
+    /// Multiplies the font sizes of all styles by `scale`, replacing the text size preference
+    /// of the OS. See [`set_text_scale`](crate::text_scale::set_text_scale) to change it while
+    /// the app runs.
+    pub fn text_scale(self, scale: f64) -> Self {
+        crate::text_scale::set_text_scale(Some(scale));
+        self
+    }
+
+    /// Only lets one instance of the app identified by `app_id` run at a time.
+    ///
+    /// When the app is already running, the arguments of this launch are sent to the running
+    /// instance, which receives them as [`AppEvent::SecondInstance`], and this process exits.
+    /// Call this before creating any windows.
+    pub fn single_instance(self, app_id: &str) -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if crate::single_instance::forward(app_id, &args) {
+            std::process::exit(0);
+        }
+        crate::single_instance::listen(app_id, |args| {
+            Application::with_event_loop_proxy(|proxy| {
+                let _ = proxy.send_event(UserEvent::SecondInstance { args });
+            });
+        });
+        self
+    }
+
+    /// Shows a crash screen when the UI thread panics, instead of the app silently closing.
+    ///
+    /// The crash screen is a window with the panic message and backtrace, which can be copied
+    /// to the clipboard, and a button restarting the app with the arguments it was launched
+    /// with. It's shown by launching the app again, so call this first, before
+    /// [`Application::single_instance`] and creating any windows: in that launch this shows the
+    /// crash screen and exits once it's closed.
+    ///
+    /// Panics caught by [`error_boundary`](crate::views::error_boundary) don't show the crash
+    /// screen.
+    pub fn crash_screen(mut self) -> Self {
+        if let Some((report, args)) = crate::crash_screen::launched_report() {
+            let config = WindowConfig::default()
+                .size((640.0, 480.0))
+                .title("Crash Report");
+            self.window(
+                move |_| crate::crash_screen::crash_view(report, args),
+                Some(config),
+            )
+            .run();
+            std::process::exit(0);
+        }
+        crate::crash_screen::install_hook();
+        self.crash_screen = true;
+        self
+    }
+
+    /// Receives the links with `schemes` and the files the app is asked to open as
+    /// [`AppEvent::OpenUrls`] and [`AppEvent::OpenFiles`], including the ones it was launched
+    /// with.
+    ///
+    /// On Linux and Windows they're passed as arguments, so combine this with
+    /// [`Application::single_instance`] to receive the ones opened while the app is running.
+    /// The schemes can be registered with
+    /// [`register_url_scheme`](crate::deep_link::register_url_scheme).
+    pub fn url_schemes(mut self, schemes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.url_schemes = schemes.into_iter().map(Into::into).collect();
+        self.handles_open = true;
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let (urls, files) = crate::deep_link::classify_args(&args, &self.url_schemes);
+        if !urls.is_empty() || !files.is_empty() {
+            Application::with_event_loop_proxy(|proxy| {
+                let _ = proxy.send_event(UserEvent::Open { urls, files });
+            });
+        }
+        #[cfg(target_os = "macos")]
+        crate::deep_link::apple_events::install();
+        self
+    }
+
     /// create a new window for the application, if you want multiple windows,
     /// just chain more window method to the builder
     pub fn window<V: View + 'static>(
@@ -128,34 +293,84 @@ impl Application {
     }
 
     pub fn run(mut self) {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if let Some(task) = crate::recent_documents::task_from_args(&args) {
+            Application::with_event_loop_proxy(|proxy| {
+                let _ = proxy.send_event(UserEvent::RunTask(task));
+            });
+        }
         let mut handle = self.handle.take().unwrap();
         handle.idle();
-        let _ = self.event_loop.run(move |event, event_loop| {
-            event_loop.set_control_flow(ControlFlow::Wait);
-            handle.handle_timer(event_loop);
+        let crash_screen = self.crash_screen;
+        let mut handle_event =
+            move |event: floem_winit::event::Event<UserEvent>,
+                  event_loop: &EventLoopWindowTarget<UserEvent>| {
+                event_loop.set_control_flow(ControlFlow::Wait);
+                handle.handle_timer(event_loop);
 
-            match event {
-                floem_winit::event::Event::NewEvents(_) => {}
-                floem_winit::event::Event::WindowEvent { window_id, event } => {
-                    handle.handle_window_event(window_id, event, event_loop);
-                }
-                floem_winit::event::Event::DeviceEvent { .. } => {}
-                floem_winit::event::Event::UserEvent(event) => {
-                    handle.handle_user_event(event_loop, event);
-                }
-                floem_winit::event::Event::Suspended => {}
-                floem_winit::event::Event::Resumed => {}
-                floem_winit::event::Event::AboutToWait => {}
-                floem_winit::event::Event::LoopExiting => {
-                    if let Some(action) = self.event_listener.as_ref() {
-                        action(AppEvent::WillTerminate);
+                match event {
+                    floem_winit::event::Event::NewEvents(_) => {}
+                    floem_winit::event::Event::WindowEvent { window_id, event } => {
+                        handle.handle_window_event(window_id, event, event_loop);
+                    }
+                    floem_winit::event::Event::DeviceEvent { event, .. } => {
+                        handle.handle_device_event(event);
+                    }
+                    floem_winit::event::Event::UserEvent(UserEvent::SecondInstance { args }) => {
+                        if let Some(action) = self.event_listener.as_ref() {
+                            if self.handles_open {
+                                let (urls, files) =
+                                    crate::deep_link::classify_args(&args, &self.url_schemes);
+                                Self::emit_open(action, urls, files);
+                            }
+                            if let Some(task) = crate::recent_documents::task_from_args(&args) {
+                                action(AppEvent::RunTask(task));
+                            }
+                            action(AppEvent::SecondInstance { args });
+                        }
+                    }
+                    floem_winit::event::Event::UserEvent(UserEvent::RunTask(task)) => {
+                        if let Some(action) = self.event_listener.as_ref() {
+                            action(AppEvent::RunTask(task));
+                        }
+                    }
+                    floem_winit::event::Event::UserEvent(UserEvent::Open { urls, files }) => {
+                        if let Some(action) = self.event_listener.as_ref() {
+                            Self::emit_open(action, urls, files);
+                        }
+                    }
+                    floem_winit::event::Event::UserEvent(event) => {
+                        handle.handle_user_event(event_loop, event);
                     }
+                    floem_winit::event::Event::Suspended => {}
+                    floem_winit::event::Event::Resumed => {}
+                    floem_winit::event::Event::AboutToWait => {}
+                    floem_winit::event::Event::LoopExiting => {
+                        if let Some(action) = self.event_listener.as_ref() {
+                            action(AppEvent::WillTerminate);
+                        }
+                    }
+                    floem_winit::event::Event::MemoryWarning => {}
                 }
-                floem_winit::event::Event::MemoryWarning => {}
+            };
+        let _ = self.event_loop.run(move |event, event_loop| {
+            if !crash_screen {
+                handle_event(event, event_loop);
+            } else if catch_unwind(AssertUnwindSafe(|| handle_event(event, event_loop))).is_err() {
+                crate::crash_screen::crash();
             }
         });
     }
 
+    fn emit_open(action: &AppEventCallback, urls: Vec<String>, files: Vec<PathBuf>) {
+        if !urls.is_empty() {
+            action(AppEvent::OpenUrls(urls));
+        }
+        if !files.is_empty() {
+            action(AppEvent::OpenFiles(files));
+        }
+    }
+
     pub(crate) fn with_event_loop_proxy(f: impl FnOnce(&EventLoopProxy<UserEvent>)) {
         if let Some(proxy) = EVENT_LOOP_PROXY.lock().as_ref() {
             f(proxy);
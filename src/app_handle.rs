@@ -2,18 +2,20 @@ use std::{collections::HashMap, rc::Rc, time::Instant};
 
 use floem_winit::{
     dpi::{LogicalPosition, LogicalSize},
-    event::WindowEvent,
+    event::{DeviceEvent, WindowEvent},
     event_loop::{ControlFlow, EventLoopWindowTarget},
     window::WindowId,
 };
-use kurbo::{Point, Size};
+use kurbo::{Point, Size, Vec2};
 
 use crate::{
     action::{Timer, TimerToken},
     app::{AppUpdateEvent, UserEvent, APP_UPDATE_EVENTS},
+    event::Event,
     ext_event::EXT_EVENT_HANDLER,
     inspector::Capture,
     profiler::{Profile, ProfileEvent},
+    tear_off::TearOffDrop,
     view::View,
     window::WindowConfig,
     window_handle::WindowHandle,
@@ -41,12 +43,30 @@ impl ApplicationHandle {
             UserEvent::AppUpdate => {
                 self.handle_update_event(event_loop);
             }
+            UserEvent::Gamepad(event) => {
+                for (_, handle) in self.window_handles.iter_mut() {
+                    if handle
+                        .window
+                        .as_ref()
+                        .map_or(false, |window| window.has_focus())
+                    {
+                        handle.event(Event::Gamepad(event.clone()));
+                    }
+                }
+            }
             UserEvent::Idle => {
                 self.idle();
             }
             UserEvent::QuitApp => {
                 event_loop.exit();
             }
+            UserEvent::Accessibility { window_id, event } => {
+                if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                    handle.accessibility_event(event);
+                }
+            }
+            // Delivered to the app event listener by `Application::run`
+            UserEvent::SecondInstance { .. } | UserEvent::Open { .. } | UserEvent::RunTask(_) => {}
         }
     }
 
@@ -63,6 +83,30 @@ impl ApplicationHandle {
                 AppUpdateEvent::CloseWindow { window_id } => {
                     self.close_window(window_id, event_loop);
                 }
+                AppUpdateEvent::MoveWindow {
+                    window_id,
+                    position,
+                } => {
+                    let window = self
+                        .window_handles
+                        .get(&window_id)
+                        .and_then(|handle| handle.window.as_ref());
+                    if let Some(window) = window {
+                        window.set_outer_position(LogicalPosition::new(position.x, position.y));
+                    }
+                }
+                AppUpdateEvent::TearOffDrop {
+                    pointer,
+                    origin,
+                    preview,
+                    on_drop,
+                } => {
+                    let drop = match self.window_at(pointer, preview) {
+                        Some((window_id, pos)) => TearOffDrop::Window(window_id, pos),
+                        None => TearOffDrop::Screen(origin),
+                    };
+                    on_drop(drop);
+                }
                 AppUpdateEvent::RequestTimer { timer } => {
                     self.request_timer(timer, event_loop);
                 }
@@ -149,6 +193,17 @@ impl ApplicationHandle {
             )
         });
 
+        if matches!(
+            event,
+            WindowEvent::KeyboardInput { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::Touch(_)
+        ) {
+            crate::power::record_input();
+        }
+
         match event {
             WindowEvent::ActivationTokenDone { .. } => {}
             WindowEvent::Resized(size) => {
@@ -174,7 +229,7 @@ impl ApplicationHandle {
                 window_handle.focused(focused);
             }
             WindowEvent::KeyboardInput { event, .. } => {
-                window_handle.key_event(event);
+                window_handle.key_event(event.into());
             }
             WindowEvent::ModifiersChanged(modifiers) => {
                 window_handle.modifiers = modifiers.state();
@@ -197,7 +252,9 @@ impl ApplicationHandle {
             WindowEvent::MouseInput { state, button, .. } => {
                 window_handle.mouse_input(button, state);
             }
-            WindowEvent::TouchpadMagnify { .. } => {}
+            WindowEvent::TouchpadMagnify { delta, .. } => {
+                window_handle.touchpad_magnify(delta);
+            }
             WindowEvent::SmartMagnify { .. } => {}
             WindowEvent::TouchpadRotate { .. } => {}
             WindowEvent::TouchpadPressure { .. } => {}
@@ -236,6 +293,15 @@ impl ApplicationHandle {
         }
     }
 
+    pub(crate) fn handle_device_event(&mut self, event: DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta: (x, y) } = event {
+            // Relative motion is only delivered to windows with a locked pointer
+            for (_, handle) in self.window_handles.iter_mut() {
+                handle.pointer_delta(Vec2::new(x, y));
+            }
+        }
+    }
+
     pub(crate) fn new_window(
         &mut self,
         event_loop: &EventLoopWindowTarget<UserEvent>,
@@ -282,9 +348,27 @@ impl ApplicationHandle {
             if let Some(window_level) = config.window_level {
                 window_builder = window_builder.with_window_level(window_level);
             }
+            if let Some(active) = config.active {
+                window_builder = window_builder.with_active(active);
+            }
             if let Some(title) = config.title {
                 window_builder = window_builder.with_title(title);
             }
+            if let Some(theme) = config.theme {
+                window_builder = window_builder.with_theme(Some(theme));
+            }
+            #[cfg(target_os = "macos")]
+            if let Some(titlebar) = config.macos_titlebar {
+                use floem_winit::platform::macos::WindowBuilderExtMacOS;
+                window_builder = window_builder
+                    .with_titlebar_transparent(titlebar.transparent)
+                    .with_fullsize_content_view(titlebar.transparent)
+                    .with_title_hidden(titlebar.title_hidden)
+                    .with_titlebar_buttons_hidden(titlebar.buttons_hidden);
+                if let Some((x, y)) = titlebar.traffic_lights_offset {
+                    window_builder = window_builder.with_traffic_lights_offset(x, y);
+                }
+            }
             config.apply_default_theme.unwrap_or(true)
         } else {
             true
@@ -316,6 +400,23 @@ impl ApplicationHandle {
         }
     }
 
+    /// The window at `pos` on the screen other than `except`, and `pos` in the window.
+    fn window_at(&self, pos: Point, except: Option<WindowId>) -> Option<(WindowId, Point)> {
+        self.window_handles
+            .iter()
+            .filter(|(window_id, _)| Some(**window_id) != except)
+            .find_map(|(window_id, handle)| {
+                let window = handle.window.as_ref()?;
+                let origin: LogicalPosition<f64> =
+                    window.inner_position().ok()?.to_logical(handle.scale);
+                let size: LogicalSize<f64> = window.inner_size().to_logical(handle.scale);
+                let pos = Point::new(pos.x - origin.x, pos.y - origin.y);
+                let inside =
+                    (0.0..size.width).contains(&pos.x) && (0.0..size.height).contains(&pos.y);
+                inside.then_some((*window_id, pos))
+            })
+    }
+
     fn capture_window(&mut self, window_id: WindowId) -> Option<Capture> {
         self.window_handles
             .get_mut(&window_id)
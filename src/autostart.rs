@@ -0,0 +1,257 @@
+//! Starting the app when the user logs in.
+//!
+//! The app is registered with the OS for the current user: as a desktop entry in the autostart
+//! directory on Linux, in the `Run` registry key on Windows and as a launch agent on macOS.
+//!
+//! Tray utilities usually start without showing a window. With `hidden` set, the app is
+//! started with an argument which [`launched_hidden`] detects, so it can skip opening its
+//! windows.
+//!
+//! ## Example
+//! ```ignore
+//! let start_at_login = create_rw_signal(is_autostart_enabled("com.example.Clipper"));
+//! create_effect(move |_| {
+//!     let _ = if start_at_login.get() {
+//!         enable_autostart("com.example.Clipper", "Clipper", true)
+//!     } else {
+//!         disable_autostart("com.example.Clipper")
+//!     };
+//! });
+//!
+//! let app = Application::new();
+//! if !launched_hidden() {
+//!     app.window(move |_| settings_view(start_at_login), None).run();
+//! }
+//! ```
+
+use std::{io, path::Path};
+
+/// The argument the app is started with when it's started hidden.
+const HIDDEN_ARG: &str = "--floem-hidden";
+
+fn hidden_from_args(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == HIDDEN_ARG)
+}
+
+/// Whether the app was started at login with `hidden` set, see [`enable_autostart`].
+pub fn launched_hidden() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    hidden_from_args(&args)
+}
+
+/// Starts the app identified by `app_id`, named `app_name` in the OS settings, when the user
+/// logs in, replacing its previous registration. With `hidden` set, the app is started with
+/// an argument detected by [`launched_hidden`].
+///
+/// The app is started from the path of the running executable, so this should be called
+/// again if the app is moved.
+pub fn enable_autostart(app_id: &str, app_name: &str, hidden: bool) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    enable(app_id, app_name, &exe, hidden)
+}
+
+/// Stops starting the app identified by `app_id` when the user logs in. Does nothing if it
+/// isn't started at login.
+pub fn disable_autostart(app_id: &str) -> io::Result<()> {
+    disable(app_id)
+}
+
+/// Whether the app identified by `app_id` is started when the user logs in.
+pub fn is_autostart_enabled(app_id: &str) -> bool {
+    is_enabled(app_id)
+}
+
+#[cfg(target_os = "windows")]
+fn check(status: std::process::ExitStatus) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("updating the login items failed"))
+    }
+}
+
+/// Removes `path`, which is fine if it doesn't exist.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos"))]
+fn remove_file(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn entry_path(app_id: &str) -> io::Result<std::path::PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .ok_or_else(|| io::Error::other("the home directory isn't set"))?;
+    Ok(config_home
+        .join("autostart")
+        .join(format!("{app_id}.desktop")))
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn enable(app_id: &str, app_name: &str, exe: &Path, hidden: bool) -> io::Result<()> {
+    let path = entry_path(app_id)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let args = if hidden {
+        format!(" {HIDDEN_ARG}")
+    } else {
+        String::new()
+    };
+    let entry = format!(
+        concat!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"{}\n",
+            "X-GNOME-Autostart-enabled=true\n",
+        ),
+        app_name,
+        exe.display(),
+        args
+    );
+    std::fs::write(path, entry)
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn disable(app_id: &str) -> io::Result<()> {
+    remove_file(&entry_path(app_id)?)
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn is_enabled(app_id: &str) -> bool {
+    entry_path(app_id).map_or(false, |path| path.exists())
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+#[cfg(target_os = "windows")]
+fn enable(app_id: &str, _app_name: &str, exe: &Path, hidden: bool) -> io::Result<()> {
+    let args = if hidden {
+        format!(" {HIDDEN_ARG}")
+    } else {
+        String::new()
+    };
+    let command = format!("\"{}\"{args}", exe.display());
+    check(
+        std::process::Command::new("reg")
+            .args(["add", RUN_KEY, "/v", app_id, "/d", &command, "/f"])
+            .status()?,
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn disable(app_id: &str) -> io::Result<()> {
+    if !is_enabled(app_id) {
+        return Ok(());
+    }
+    check(
+        std::process::Command::new("reg")
+            .args(["delete", RUN_KEY, "/v", app_id, "/f"])
+            .status()?,
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn is_enabled(app_id: &str) -> bool {
+    std::process::Command::new("reg")
+        .args(["query", RUN_KEY, "/v", app_id])
+        .output()
+        .map_or(false, |output| output.status.success())
+}
+
+#[cfg(target_os = "macos")]
+fn agent_path(app_id: &str) -> io::Result<std::path::PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| io::Error::other("HOME isn't set"))?;
+    Ok(Path::new(&home)
+        .join("Library/LaunchAgents")
+        .join(format!("{app_id}.plist")))
+}
+
+#[cfg(target_os = "macos")]
+fn enable(app_id: &str, _app_name: &str, exe: &Path, hidden: bool) -> io::Result<()> {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let path = agent_path(app_id)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut args = format!("<string>{}</string>", escape(&exe.to_string_lossy()));
+    if hidden {
+        args.push_str(&format!("<string>{HIDDEN_ARG}</string>"));
+    }
+    let plist = format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+            "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\">\n<dict>\n",
+            "  <key>Label</key><string>{}</string>\n",
+            "  <key>ProgramArguments</key><array>{}</array>\n",
+            "  <key>RunAtLoad</key><true/>\n",
+            "</dict>\n</plist>\n",
+        ),
+        escape(app_id),
+        args
+    );
+    std::fs::write(path, plist)
+}
+
+#[cfg(target_os = "macos")]
+fn disable(app_id: &str) -> io::Result<()> {
+    remove_file(&agent_path(app_id)?)
+}
+
+#[cfg(target_os = "macos")]
+fn is_enabled(app_id: &str) -> bool {
+    agent_path(app_id).map_or(false, |path| path.exists())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "windows",
+    target_os = "macos"
+)))]
+fn enable(_app_id: &str, _app_name: &str, _exe: &Path, _hidden: bool) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "starting at login isn't supported on this platform",
+    ))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "windows",
+    target_os = "macos"
+)))]
+fn disable(_app_id: &str) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "windows",
+    target_os = "macos"
+)))]
+fn is_enabled(_app_id: &str) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hidden_from_args;
+
+    #[test]
+    fn hidden_args() {
+        let args = ["--floem-hidden"].map(String::from);
+        assert!(hidden_from_args(&args));
+        assert!(!hidden_from_args(&["--hidden".to_string()]));
+    }
+}
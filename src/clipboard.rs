@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+use std::process::Command;
+
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use raw_window_handle::RawDisplayHandle;
@@ -18,6 +21,16 @@ pub struct Clipboard {
     selection: Option<Box<dyn ClipboardProvider>>,
 }
 
+/// The content of the clipboard, see [`Clipboard::get_content`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClipboardContent {
+    Text(String),
+    /// An image, encoded as PNG.
+    Image(Vec<u8>),
+    /// Files copied in a file manager.
+    Files(Vec<PathBuf>),
+}
+
 #[derive(Clone, Debug)]
 pub enum ClipboardError {
     NotAvailable,
@@ -45,6 +58,25 @@ impl Clipboard {
             .map_err(|e| ClipboardError::ProviderError(e.to_string()))
     }
 
+    /// Reads the clipboard as an image, a list of files or text, in that order of preference.
+    ///
+    /// Images and files are read with the clipboard tools of the platform: `wl-paste` or
+    /// `xclip` on Linux, `osascript` on macOS and PowerShell on Windows. When they aren't
+    /// available, only text is read.
+    pub fn get_content() -> Result<ClipboardContent, ClipboardError> {
+        if let Some(png) = read_image() {
+            return Ok(ClipboardContent::Image(png));
+        }
+        if let Some(files) = read_files() {
+            return Ok(ClipboardContent::Files(files));
+        }
+        let text = Self::get_contents()?;
+        Ok(match parse_uri_list(&text) {
+            Some(files) => ClipboardContent::Files(files),
+            None => ClipboardContent::Text(text),
+        })
+    }
+
     pub(crate) unsafe fn init(display: RawDisplayHandle) {
         *CLIPBOARD.lock() = Some(Self::new(display));
     }
@@ -79,3 +111,148 @@ impl Clipboard {
         };
     }
 }
+
+/// Runs `program`, returning its output if it succeeded and printed anything.
+fn command_output(program: &str, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new(program).args(args).output().ok()?;
+    (output.status.success() && !output.stdout.is_empty()).then_some(output.stdout)
+}
+
+/// Reads the clipboard content of the MIME type `mime` on Linux.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_mime(mime: &str) -> Option<Vec<u8>> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let types = command_output("wl-paste", &["--list-types"])?;
+        if !String::from_utf8_lossy(&types).lines().any(|t| t == mime) {
+            return None;
+        }
+        command_output("wl-paste", &["--no-newline", "--type", mime])
+    } else {
+        let types = command_output("xclip", &["-selection", "clipboard", "-t", "TARGETS", "-o"])?;
+        if !String::from_utf8_lossy(&types).lines().any(|t| t == mime) {
+            return None;
+        }
+        command_output("xclip", &["-selection", "clipboard", "-t", mime, "-o"])
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_image() -> Option<Vec<u8>> {
+    read_mime("image/png")
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_files() -> Option<Vec<PathBuf>> {
+    parse_uri_list(&String::from_utf8(read_mime("text/uri-list")?).ok()?)
+}
+
+#[cfg(target_os = "macos")]
+fn read_image() -> Option<Vec<u8>> {
+    // Printed as `«data PNGf89504E47...»`
+    let output = command_output("osascript", &["-e", "the clipboard as «class PNGf»"])?;
+    let output = String::from_utf8(output).ok()?;
+    let hex = output
+        .trim()
+        .strip_prefix("«data PNGf")?
+        .strip_suffix('»')?;
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn read_files() -> Option<Vec<PathBuf>> {
+    let output = command_output(
+        "osascript",
+        &["-e", "POSIX path of (the clipboard as «class furl»)"],
+    )?;
+    let path = String::from_utf8(output).ok()?;
+    Some(vec![PathBuf::from(path.trim_end_matches('\n'))])
+}
+
+#[cfg(windows)]
+fn read_image() -> Option<Vec<u8>> {
+    let path = std::env::temp_dir().join(format!("floem-paste-{}.png", std::process::id()));
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $image = [Windows.Forms.Clipboard]::GetImage(); \
+         if ($image) {{ $image.Save('{}', [Drawing.Imaging.ImageFormat]::Png) }} else {{ exit 1 }}",
+        path.to_string_lossy().replace('\'', "''")
+    );
+    command_output("powershell", &["-NoProfile", "-STA", "-Command", &script]);
+    let png = std::fs::read(&path).ok();
+    let _ = std::fs::remove_file(&path);
+    png
+}
+
+#[cfg(windows)]
+fn read_files() -> Option<Vec<PathBuf>> {
+    let output = command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Get-Clipboard -Format FileDropList | ForEach-Object { $_.FullName }",
+        ],
+    )?;
+    let files: Vec<PathBuf> = String::from_utf8_lossy(&output)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    (!files.is_empty()).then_some(files)
+}
+
+/// Parses a `text/uri-list`, returning the paths if every entry is a `file://` URI.
+pub(crate) fn parse_uri_list(text: &str) -> Option<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for line in text.lines().map(str::trim) {
+        // Comments are allowed in URI lists
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let path = line.strip_prefix("file://")?;
+        // Skip the host, which is usually empty or `localhost`
+        let path = &path[path.find('/')?..];
+        files.push(PathBuf::from(percent_decode(path)?));
+    }
+    (!files.is_empty()).then_some(files)
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_uri_list;
+
+    #[test]
+    fn uri_list() {
+        assert_eq!(
+            parse_uri_list("# copied\r\nfile:///home/me/a%20b.png\r\nfile://localhost/tmp/c\r\n"),
+            Some(vec![
+                PathBuf::from("/home/me/a b.png"),
+                PathBuf::from("/tmp/c")
+            ])
+        );
+        assert_eq!(parse_uri_list("file:///tmp/a\nhttps://example.com"), None);
+        assert_eq!(parse_uri_list("just some text"), None);
+    }
+}
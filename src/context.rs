@@ -1,5 +1,4 @@
 use floem_renderer::Renderer as FloemRenderer;
-use floem_winit::window::CursorIcon;
 use kurbo::{Affine, Insets, Point, Rect, RoundedRect, Shape, Size, Vec2};
 use std::{
     any::Any,
@@ -14,24 +13,44 @@ use taffy::{
 };
 
 use crate::{
-    action::{exec_after, show_context_menu},
+    accessibility::AccessCx,
+    action::exec_after,
     animate::AnimId,
     event::{Event, EventListener},
+    gamepad::{self, GamepadButton},
     id::Id,
     inspector::CaptureState,
+    memory,
     menu::Menu,
+    renderer::default_text_rendering,
     responsive::{GridBreakpoints, ScreenSizeBp},
+    shortcut,
     style::{
-        BuiltinStyle, CursorStyle, DisplayProp, Style, StyleClassRef, StyleProp, StyleSelector,
-        ZIndex,
+        BuiltinStyle, CursorStyle, DisplayProp, Overflow, OverflowProp, Style, StyleClassRef,
+        StyleProp, StyleSelector, TextRenderingProp, ZIndex,
     },
     unit::PxPct,
-    view::{paint_bg, paint_border, paint_outline, View, ViewData},
+    view::{paint_bg, paint_border, paint_layer_shadow, paint_outline, View, ViewData},
     view_data::ChangeFlags,
+    views::open_context_menu,
 };
 
 pub use crate::view_data::ViewState;
 
+/// Enters a `tracing` span named `$name` for `$view` until the end of the current block, with
+/// the `tracing` feature.
+macro_rules! view_span {
+    ($name:literal, $view:expr) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            $name,
+            view = %$view.debug_name(),
+            id = $view.id().to_raw()
+        )
+        .entered();
+    };
+}
+
 /// Control whether an event will continue propagating or whether it should stop.
 pub enum EventPropagation {
     /// Stop event propagation and mark the event as processed
@@ -56,6 +75,20 @@ impl EventPropagation {
 pub type EventCallback = dyn Fn(&Event) -> EventPropagation;
 pub type ResizeCallback = dyn Fn(Rect);
 pub type MenuCallback = dyn Fn() -> Menu;
+pub type PaintCallback = dyn Fn(&mut PaintCx, Rect);
+pub type HitTestCallback = dyn Fn(Point) -> bool;
+pub type AccessibilityCallback = dyn Fn(&mut AccessCx);
+
+/// The state of a view moved out of a window, see [`AppState::take_view_states`].
+pub(crate) struct MovedViewState {
+    id: Id,
+    state: ViewState,
+    disabled: bool,
+    invalid: bool,
+    keyboard_navigable: bool,
+    keyboard_navigable_grid: bool,
+    draggable: bool,
+}
 
 pub(crate) struct ResizeListener {
     pub(crate) rect: Rect,
@@ -98,7 +131,10 @@ pub struct AppState {
     pub(crate) request_compute_layout: bool,
     pub(crate) request_paint: bool,
     pub(crate) disabled: HashSet<Id>,
+    pub(crate) invalid: HashSet<Id>,
     pub(crate) keyboard_navigable: HashSet<Id>,
+    /// The containers whose children are navigated with the arrow keys by their positions.
+    pub(crate) keyboard_navigable_grids: HashSet<Id>,
     pub(crate) draggable: HashSet<Id>,
     pub(crate) dragging: Option<DragState>,
     pub(crate) drag_start: Option<(Id, Point)>,
@@ -110,10 +146,16 @@ pub struct AppState {
     /// This keeps track of all views that have an animation,
     /// regardless of the status of the animation
     pub(crate) cursor: Option<CursorStyle>,
-    pub(crate) last_cursor: CursorIcon,
+    pub(crate) last_cursor: Option<CursorStyle>,
+    /// The view the pointer is locked to, which receives [`Event::PointerDelta`].
+    pub(crate) pointer_lock: Option<Id>,
+    /// Whether the cursor is kept inside the window while the pointer isn't locked.
+    pub(crate) cursor_confined: bool,
     pub(crate) keyboard_navigation: bool,
     pub(crate) window_menu: HashMap<usize, Box<dyn Fn()>>,
     pub(crate) context_menu: HashMap<usize, Box<dyn Fn()>>,
+    /// The open popovers and their overlays, innermost last.
+    pub(crate) open_popovers: Vec<(Id, Id)>,
 
     /// This is set if we're currently capturing the window for the inspector.
     pub(crate) capture: Option<CaptureState>,
@@ -143,7 +185,9 @@ impl AppState {
             request_paint: false,
             request_compute_layout: false,
             disabled: HashSet::new(),
+            invalid: HashSet::new(),
             keyboard_navigable: HashSet::new(),
+            keyboard_navigable_grids: HashSet::new(),
             draggable: HashSet::new(),
             dragging: None,
             drag_start: None,
@@ -151,11 +195,14 @@ impl AppState {
             clicking: HashSet::new(),
             hovered: HashSet::new(),
             cursor: None,
-            last_cursor: CursorIcon::Default,
+            last_cursor: None,
+            pointer_lock: None,
+            cursor_confined: false,
             keyboard_navigation: false,
             grid_bps: GridBreakpoints::default(),
             window_menu: HashMap::new(),
             context_menu: HashMap::new(),
+            open_popovers: Vec::new(),
             capture: None,
         }
     }
@@ -164,6 +211,8 @@ impl AppState {
         if !id.has_id_path() {
             // if the id doesn't have a id path, that means it's been cleaned up,
             // so we shouldn't create a new ViewState for this Id.
+            // What's set on the stale state is dropped, so it doesn't pile up
+            self.stale_view_state.reset();
             return &mut self.stale_view_state;
         }
         self.view_states
@@ -178,21 +227,19 @@ impl AppState {
             false
         });
         let id = view.id();
-        let view_state = self.view_state(id);
-        if let Some(action) = view_state.cleanup_listener.as_ref() {
-            action();
-        }
-        let node = view_state.node;
-        if let Ok(children) = self.taffy.children(node) {
-            for child in children {
-                let _ = self.taffy.remove(child);
+        if let Some(view_state) = self.view_states.remove(&id) {
+            if let Some(action) = view_state.cleanup_listener.as_ref() {
+                action();
             }
+            self.remove_layout_nodes(view_state.node);
         }
-        let _ = self.taffy.remove(node);
+        memory::view_removed(view);
+        shortcut::view_removed(id);
         id.remove_id_path();
-        self.view_states.remove(&id);
         self.disabled.remove(&id);
+        self.invalid.remove(&id);
         self.keyboard_navigable.remove(&id);
+        self.keyboard_navigable_grids.remove(&id);
         self.draggable.remove(&id);
         self.dragging_over.remove(&id);
         self.clicking.remove(&id);
@@ -204,6 +251,100 @@ impl AppState {
         if self.active == Some(id) {
             self.active = None;
         }
+        if self.pointer_lock == Some(id) {
+            self.pointer_lock = None;
+        }
+        if self.drag_start.map_or(false, |(start, _)| start == id) {
+            self.drag_start = None;
+        }
+        // The view is dropped after this, so its id is free to reuse
+        id.release();
+    }
+
+    /// Removes `node` and the layout nodes below it. The nodes of the child views are removed
+    /// with their views first, so the nodes left are the ones made by the view itself.
+    fn remove_layout_nodes(&mut self, node: Node) {
+        if let Ok(children) = self.taffy.children(node) {
+            for child in children {
+                self.remove_layout_nodes(child);
+            }
+        }
+        let _ = self.taffy.remove(node);
+    }
+
+    /// Takes the state of a view and its children out of the app state, for the view to be moved
+    /// to another window without being rebuilt. The ids of the views are unmounted until the
+    /// state is added to the other window with [`AppState::insert_view_states`].
+    pub(crate) fn take_view_states(&mut self, view: &dyn View) -> Vec<MovedViewState> {
+        let mut states = Vec::new();
+        self.take_view_states_into(view, &mut states);
+        states
+    }
+
+    fn take_view_states_into(&mut self, view: &dyn View, states: &mut Vec<MovedViewState>) {
+        view.for_each_child(&mut |child| {
+            self.take_view_states_into(child, states);
+            false
+        });
+        let id = view.id();
+        id.remove_id_path();
+        self.dragging_over.remove(&id);
+        self.clicking.remove(&id);
+        self.hovered.remove(&id);
+        if self.focus == Some(id) {
+            self.focus = None;
+        }
+        if self.active == Some(id) {
+            self.active = None;
+        }
+        if self.pointer_lock == Some(id) {
+            self.pointer_lock = None;
+        }
+        let Some(state) = self.view_states.remove(&id) else {
+            return;
+        };
+        // Layout nodes belong to the window, the other window makes new ones
+        let _ = self.taffy.remove(state.node);
+        states.push(MovedViewState {
+            id,
+            state,
+            disabled: self.disabled.remove(&id),
+            invalid: self.invalid.remove(&id),
+            keyboard_navigable: self.keyboard_navigable.remove(&id),
+            keyboard_navigable_grid: self.keyboard_navigable_grids.remove(&id),
+            draggable: self.draggable.remove(&id),
+        });
+    }
+
+    /// Adds the state of views moved from another window, taken by
+    /// [`AppState::take_view_states`]. The views are styled and laid out again.
+    pub(crate) fn insert_view_states(&mut self, states: Vec<MovedViewState>) {
+        for moved in states {
+            let MovedViewState {
+                id,
+                mut state,
+                disabled,
+                invalid,
+                keyboard_navigable,
+                keyboard_navigable_grid,
+                draggable,
+            } = moved;
+            state.node = self.taffy.new_leaf(state.taffy_style.clone()).unwrap();
+            state.requested_changes = ChangeFlags::all();
+            state.layer_shadow = None;
+            for (is_in, set) in [
+                (disabled, &mut self.disabled),
+                (invalid, &mut self.invalid),
+                (keyboard_navigable, &mut self.keyboard_navigable),
+                (keyboard_navigable_grid, &mut self.keyboard_navigable_grids),
+                (draggable, &mut self.draggable),
+            ] {
+                if is_in {
+                    set.insert(id);
+                }
+            }
+            self.view_states.insert(id, state);
+        }
     }
 
     pub fn is_hidden(&self, id: Id) -> bool {
@@ -236,6 +377,10 @@ impl AppState {
         self.disabled.contains(id)
     }
 
+    pub fn is_invalid(&self, id: &Id) -> bool {
+        self.invalid.contains(id)
+    }
+
     pub fn is_focused(&self, id: &Id) -> bool {
         self.focus.map(|f| &f == id).unwrap_or(false)
     }
@@ -372,6 +517,48 @@ impl AppState {
         view.viewport = Some(viewport);
     }
 
+    /// Scrolls the content of a view with [`Overflow::Scroll`] by `delta`, keeping its
+    /// children in view. Returns `false` if the content can't scroll further.
+    pub(crate) fn scroll_overflow(&mut self, id: Id, delta: Vec2) -> bool {
+        let Some(layout) = self.get_layout(id) else {
+            return false;
+        };
+        let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        let node = self.view_state(id).node;
+        let content = self
+            .taffy
+            .children(node)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|child| self.taffy.layout(child).ok())
+            .fold(size.to_rect(), |content, child| {
+                content.union(Rect::new(
+                    child.location.x as f64,
+                    child.location.y as f64,
+                    (child.location.x + child.size.width) as f64,
+                    (child.location.y + child.size.height) as f64,
+                ))
+            });
+
+        let viewport = self.view_state(id).viewport.unwrap_or(size.to_rect());
+        let origin = viewport.origin() + delta;
+        let origin = Point::new(
+            origin
+                .x
+                .clamp(content.x0, (content.x1 - size.width).max(content.x0)),
+            origin
+                .y
+                .clamp(content.y0, (content.y1 - size.height).max(content.y0)),
+        );
+        if origin == viewport.origin() {
+            return false;
+        }
+        self.set_viewport(id, size.to_rect().with_origin(origin));
+        self.request_compute_layout_recursive(id);
+        self.request_paint(id);
+        true
+    }
+
     pub(crate) fn get_layout(&self, id: Id) -> Option<Layout> {
         self.view_states
             .get(&id)
@@ -460,22 +647,19 @@ impl AppState {
             || (selector_kind == StyleSelector::Dragging && view_state.dragging_style.is_some())
     }
 
-    // TODO: animated should be a HashMap<Id, AnimId>
+    // TODO: animated should be a HashMap<AnimId, Id>
     // so we don't have to loop through all view states
     pub(crate) fn get_view_id_by_anim_id(&self, anim_id: AnimId) -> Id {
         *self
             .view_states
             .iter()
-            .find(|(_, vs)| {
-                vs.animation
-                    .as_ref()
-                    .map(|a| a.id() == anim_id)
-                    .unwrap_or(false)
-            })
+            .find(|(_, vs)| vs.animations.iter().any(|a| a.id() == anim_id))
             .unwrap()
             .0
     }
 
+    /// Takes the actions out of a native context menu, to be run by `menu_action`.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
     pub(crate) fn update_context_menu(&mut self, menu: &mut Menu) {
         if let Some(action) = menu.item.action.take() {
             self.context_menu.insert(menu.item.id as usize, action);
@@ -516,6 +700,11 @@ impl AppState {
             if let Some(action) = self.get_event_listener(id, &EventListener::FocusGained) {
                 (*action)(&Event::FocusGained);
             }
+            if let Some(state) = self.view_states.get(&id) {
+                for signal in &state.focus_signals {
+                    signal.try_set(true);
+                }
+            }
         }
 
         if let Some(old_id) = old {
@@ -528,6 +717,11 @@ impl AppState {
             if let Some(action) = self.get_event_listener(old_id, &EventListener::FocusLost) {
                 (*action)(&Event::FocusLost);
             }
+            if let Some(state) = self.view_states.get(&old_id) {
+                for signal in &state.focus_signals {
+                    signal.try_set(false);
+                }
+            }
         }
     }
 }
@@ -620,6 +814,7 @@ impl<'a> EventCx<'a> {
             // we don't process events for hidden view
             return EventPropagation::Continue;
         }
+        view_span!("event", view);
         if self.app_state.is_disabled(&id) && !event.allow_disabled() {
             // if the view is disabled and the event is not processed
             // for disabled views
@@ -679,8 +874,7 @@ impl<'a> EventCx<'a> {
             Event::PointerDown(event) => {
                 self.app_state.clicking.insert(id);
                 if event.button.is_primary() {
-                    let rect = self.get_size(id).unwrap_or_default().to_rect();
-                    let now_focused = rect.contains(event.pos);
+                    let now_focused = self.is_hit(id, event.pos);
 
                     if now_focused {
                         if self.app_state.keyboard_navigable.contains(&id) {
@@ -703,7 +897,8 @@ impl<'a> EventCx<'a> {
                             Point::new(layout.x0, layout.y1)
                         };
                         if let Some(menu) = &self.app_state.view_state(id).popout_menu {
-                            show_context_menu(menu(), Some(bottom_left));
+                            let menu = menu();
+                            open_context_menu(self.app_state, menu, bottom_left);
                             return EventPropagation::Stop;
                         }
                         if self.app_state.draggable.contains(&id)
@@ -713,8 +908,7 @@ impl<'a> EventCx<'a> {
                         }
                     }
                 } else if event.button.is_secondary() {
-                    let rect = self.get_size(id).unwrap_or_default().to_rect();
-                    let now_focused = rect.contains(event.pos);
+                    let now_focused = self.is_hit(id, event.pos);
 
                     if now_focused {
                         if self.app_state.keyboard_navigable.contains(&id) {
@@ -729,8 +923,7 @@ impl<'a> EventCx<'a> {
                 }
             }
             Event::PointerMove(pointer_event) => {
-                let rect = self.get_size(id).unwrap_or_default().to_rect();
-                if rect.contains(pointer_event.pos) {
+                if self.is_hit(id, pointer_event.pos) {
                     if self.app_state.is_dragging() {
                         self.app_state.dragging_over.insert(id);
                         if let Some(action) = self.get_event_listener(id, &EventListener::DragOver)
@@ -740,10 +933,8 @@ impl<'a> EventCx<'a> {
                     } else {
                         self.app_state.hovered.insert(id);
                         let style = self.app_state.get_builtin_style(id);
-                        if let Some(cursor) = style.cursor() {
-                            if self.app_state.cursor.is_none() {
-                                self.app_state.cursor = Some(cursor);
-                            }
+                        if self.app_state.cursor.is_none() {
+                            self.app_state.cursor = style.cursor();
                         }
                     }
                 }
@@ -789,10 +980,16 @@ impl<'a> EventCx<'a> {
                     }
                 }
             }
+            Event::PointerWheel(wheel_event) => {
+                if self.app_state.get_builtin_style(id).overflow() == Overflow::Scroll
+                    && self.app_state.scroll_overflow(id, wheel_event.delta)
+                {
+                    return EventPropagation::Stop;
+                }
+            }
             Event::PointerUp(pointer_event) => {
                 if pointer_event.button.is_primary() {
-                    let rect = self.get_size(id).unwrap_or_default().to_rect();
-                    let on_view = rect.contains(pointer_event.pos);
+                    let on_view = self.is_hit(id, pointer_event.pos);
 
                     if id_path.is_none() {
                         if on_view {
@@ -858,8 +1055,7 @@ impl<'a> EventCx<'a> {
                         }
                     }
                 } else if pointer_event.button.is_secondary() {
-                    let rect = self.get_size(id).unwrap_or_default().to_rect();
-                    let on_view = rect.contains(pointer_event.pos);
+                    let on_view = self.is_hit(id, pointer_event.pos);
 
                     let last_pointer_down = self.app_state.view_state(id).last_pointer_down.take();
                     if let Some(action) =
@@ -872,18 +1068,6 @@ impl<'a> EventCx<'a> {
                             return EventPropagation::Stop;
                         }
                     }
-
-                    let viewport_event_position = {
-                        let layout = self.app_state.view_state(id).layout_rect;
-                        Point::new(
-                            layout.x0 + pointer_event.pos.x,
-                            layout.y0 + pointer_event.pos.y,
-                        )
-                    };
-                    if let Some(menu) = &self.app_state.view_state(id).context_menu {
-                        show_context_menu(menu(), Some(viewport_event_position));
-                        return EventPropagation::Stop;
-                    }
                 }
             }
             Event::KeyDown(_) => {
@@ -893,6 +1077,15 @@ impl<'a> EventCx<'a> {
                     }
                 }
             }
+            Event::Gamepad(gamepad_event) => {
+                let activate = gamepad_event.pressed() == Some(GamepadButton::South)
+                    && gamepad::navigation_enabled();
+                if activate && self.app_state.is_focused(&id) {
+                    if let Some(action) = self.get_event_listener(id, &EventListener::Click) {
+                        (*action)(&event);
+                    }
+                }
+            }
             Event::WindowResized(_) => {
                 if let Some(view_state) = self.app_state.view_states.get(&id) {
                     if view_state.has_style_selectors.has_responsive() {
@@ -912,8 +1105,7 @@ impl<'a> EventCx<'a> {
         if let Some(listener) = event.listener() {
             if let Some(action) = self.get_event_listener(id, &listener) {
                 let should_run = if let Some(pos) = event.point() {
-                    let rect = self.get_size(id).unwrap_or_default().to_rect();
-                    rect.contains(pos)
+                    self.is_hit(id, pos)
                 } else {
                     true
                 };
@@ -923,6 +1115,14 @@ impl<'a> EventCx<'a> {
             }
         }
 
+        if let Event::ContextMenu(context_menu_event) = &event {
+            if let Some(menu) = &self.app_state.view_state(id).context_menu {
+                let menu = menu();
+                open_context_menu(self.app_state, menu, context_menu_event.window_pos);
+                return EventPropagation::Stop;
+            }
+        }
+
         EventPropagation::Continue
     }
 
@@ -932,6 +1132,19 @@ impl<'a> EventCx<'a> {
             .map(|l| Size::new(l.size.width as f64, l.size.height as f64))
     }
 
+    /// Whether `pos`, relative to the view, is on the view, taking its
+    /// [hit test](crate::views::Decorators::hit_test) into account.
+    pub(crate) fn is_hit(&self, id: Id, pos: Point) -> bool {
+        let rect = self.get_size(id).unwrap_or_default().to_rect();
+        rect.contains(pos)
+            && self
+                .app_state
+                .view_states
+                .get(&id)
+                .and_then(|view_state| view_state.hit_test.as_ref())
+                .map_or(true, |hit_test| hit_test(pos))
+    }
+
     pub(crate) fn has_event_listener(&self, id: Id, listener: EventListener) -> bool {
         self.app_state
             .view_states
@@ -977,14 +1190,17 @@ impl<'a> EventCx<'a> {
         if let Some(point) = event.point() {
             let layout_rect = self.app_state.get_layout_rect(id);
             if let Some(layout) = self.get_layout(id) {
-                if layout_rect
-                    .with_origin(Point::new(
-                        layout.location.x as f64,
-                        layout.location.y as f64,
-                    ))
-                    .contains(point)
-                {
-                    return true;
+                let location = Point::new(layout.location.x as f64, layout.location.y as f64);
+                if layout_rect.with_origin(location).contains(point) {
+                    // The hit test is given the point the view receives, see `offset_event`
+                    let view_state = self.app_state.view_states.get(&id);
+                    let Some(hit_test) = view_state.and_then(|state| state.hit_test.as_ref())
+                    else {
+                        return true;
+                    };
+                    let viewport = view_state.and_then(|state| state.viewport);
+                    let origin = viewport.map_or(Point::ZERO, |viewport| viewport.origin());
+                    return hit_test(point - location.to_vec2() + origin.to_vec2());
                 }
             }
             false
@@ -999,6 +1215,7 @@ pub struct InteractionState {
     pub(crate) is_hovered: bool,
     pub(crate) is_selected: bool,
     pub(crate) is_disabled: bool,
+    pub(crate) is_invalid: bool,
     pub(crate) is_focused: bool,
     pub(crate) is_clicking: bool,
     pub(crate) using_keyboard_navigation: bool,
@@ -1009,7 +1226,7 @@ pub struct StyleCx<'a> {
     pub(crate) current_view: Id,
     pub(crate) current: Rc<Style>,
     pub(crate) direct: Style,
-    saved: Vec<Rc<Style>>,
+    pub(crate) saved: Vec<Rc<Style>>,
     pub(crate) now: Instant,
     saved_disabled: Vec<bool>,
     saved_selected: Vec<bool>,
@@ -1043,6 +1260,7 @@ impl<'a> StyleCx<'a> {
             is_selected: self.selected,
             is_hovered: self.app_state.is_hovered(id),
             is_disabled: self.app_state.is_disabled(id),
+            is_invalid: self.app_state.is_invalid(id),
             is_focused: self.app_state.is_focused(id),
             is_clicking: self.app_state.is_clicking(id),
             using_keyboard_navigation: self.app_state.keyboard_navigation,
@@ -1058,6 +1276,7 @@ impl<'a> StyleCx<'a> {
             return;
         }
         view_state.requested_changes.remove(ChangeFlags::STYLE);
+        view_span!("style", view);
 
         let view_style = view.view_style();
         let view_class = view.view_class();
@@ -1262,6 +1481,7 @@ impl<'a> ComputeLayoutCx<'a> {
             return None;
         }
 
+        view_span!("compute_layout", view);
         self.save();
 
         let layout = self.app_state().get_layout(id).unwrap();
@@ -1382,6 +1602,7 @@ impl<'a> LayoutCx<'a> {
 
     /// Internal method used by Floem to invoke the user-defined `View::layout` method.
     pub fn layout_view(&mut self, view: &mut dyn View) -> Node {
+        view_span!("layout", view);
         view.layout(self)
     }
 }
@@ -1433,6 +1654,7 @@ impl<'a> PaintCx<'a> {
             return;
         }
 
+        view_span!("paint", view);
         self.save();
         let size = self.transform(id);
         let is_empty = self
@@ -1447,11 +1669,52 @@ impl<'a> PaintCx<'a> {
                 self.set_z_index(z_index);
             }
 
+            paint_layer_shadow(self, id, &style, &view_style_props, size);
             paint_bg(self, &style, &view_style_props, size);
 
+            let (paint_under, paint_over) = {
+                let view_state = self.app_state.view_state(id);
+                (
+                    view_state.paint_under.clone(),
+                    view_state.paint_over.clone(),
+                )
+            };
+            if let Some(paint_under) = paint_under {
+                self.save();
+                paint_under(self, size.to_rect());
+                self.restore();
+            }
+
+            let text_rendering = style
+                .get(TextRenderingProp)
+                .unwrap_or_else(default_text_rendering);
+            self.paint_state.renderer.set_text_rendering(text_rendering);
+            let overflow = style.get(OverflowProp);
+            if overflow != Overflow::Visible {
+                self.save();
+                let radius = match view_style_props.border_radius() {
+                    PxPct::Px(px) => px,
+                    PxPct::Pct(pct) => size.min_side() * (pct / 100.),
+                };
+                self.clip(&size.to_rect().to_rounded_rect(radius));
+                if overflow == Overflow::Scroll {
+                    if let Some(viewport) = self.app_state.view_state(id).viewport {
+                        self.offset((-viewport.x0, -viewport.y0));
+                    }
+                }
+            }
             view.paint(self);
+            if overflow != Overflow::Visible {
+                self.restore();
+            }
             paint_border(self, &view_style_props, size);
-            paint_outline(self, &view_style_props, size)
+            paint_outline(self, &view_style_props, size);
+
+            if let Some(paint_over) = paint_over {
+                self.save();
+                paint_over(self, size.to_rect());
+                self.restore();
+            }
         }
 
         let mut drag_set_to_none = false;
@@ -1502,6 +1765,7 @@ impl<'a> PaintCx<'a> {
                     } else {
                         style
                     };
+                    paint_layer_shadow(self, id, &style, &view_style_props, size);
                     paint_bg(self, &style, &view_style_props, size);
 
                     view.paint(self);
@@ -1584,6 +1848,19 @@ impl<'a> PaintCx<'a> {
         }
     }
 
+    /// Scales what's painted afterwards by `scale`, about the current origin.
+    pub fn scale(&mut self, scale: f64) {
+        self.transform *= Affine::scale(scale);
+        self.paint_state.renderer.transform(self.transform);
+        if let Some(rect) = self.clip.as_mut() {
+            let radius = rect.radii();
+            *rect = rect
+                .rect()
+                .scale_from_origin(1.0 / scale)
+                .to_rounded_rect(radius);
+        }
+    }
+
     pub fn transform(&mut self, id: Id) -> Size {
         if let Some(layout) = self.get_layout(id) {
             let offset = layout.location;
@@ -1620,6 +1897,8 @@ impl<'a> PaintCx<'a> {
 // TODO: should this be private?
 pub struct PaintState {
     pub(crate) renderer: crate::renderer::Renderer,
+    /// The number of physical pixels per logical pixel the renderer draws with.
+    pub(crate) scale: f64,
 }
 
 impl PaintState {
@@ -1629,14 +1908,24 @@ impl PaintState {
     {
         Self {
             renderer: crate::renderer::Renderer::new(window, scale, size),
+            scale,
+        }
+    }
+
+    pub(crate) fn new_headless(scale: f64, size: Size) -> Self {
+        Self {
+            renderer: crate::renderer::Renderer::new_headless(scale, size),
+            scale,
         }
     }
 
     pub(crate) fn resize(&mut self, scale: f64, size: Size) {
+        self.scale = scale;
         self.renderer.resize(scale, size);
     }
 
     pub(crate) fn set_scale(&mut self, scale: f64) {
+        self.scale = scale;
         self.renderer.set_scale(scale);
     }
 }
@@ -1679,9 +1968,10 @@ impl<'a> UpdateCx<'a> {
         let id_path = &id_path[1..];
         if id == view.id() {
             if id_path.is_empty() {
+                view_span!("update", view);
                 view.update(self, state);
-            } else if let Some(child) = view.child_mut(id_path[0]) {
-                self.update_view(child, id_path, state);
+            } else {
+                view.update_child(self, id_path, state);
             }
         }
     }
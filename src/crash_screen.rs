@@ -0,0 +1,140 @@
+//! Showing a crash screen when the UI thread panics, see [`Application::crash_screen`].
+//!
+//! The panicking process can't show a window anymore, so it writes the report to a file in the
+//! temporary directory and launches the app again with an argument naming the file. That
+//! process shows the report instead of the app's windows, and restarting launches the app with
+//! the arguments it was first launched with.
+//!
+//! [`Application::crash_screen`]: crate::Application::crash_screen
+
+use std::{
+    backtrace::Backtrace,
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::{
+    app::quit_app,
+    intern::intern,
+    view::View,
+    views::{h_stack, scroll, static_label, v_stack, Decorators},
+    widgets::button,
+    Clipboard,
+};
+
+/// The argument the crash screen is launched with, followed by the path of the report.
+const CRASH_ARG: &str = "--floem-crash-report=";
+
+/// The message, location and backtrace of the last panic of the UI thread.
+static LAST_PANIC: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+static CRASHED: AtomicBool = AtomicBool::new(false);
+
+/// The path of the report the app was launched with to show it, and the arguments of the
+/// crashed launch.
+fn report_from_args(args: &[String]) -> Option<(PathBuf, Vec<String>)> {
+    let path = args.first()?.strip_prefix(CRASH_ARG)?;
+    Some((PathBuf::from(path), args[1..].to_vec()))
+}
+
+/// Keeps the report of the panics of the calling thread, on top of the current panic hook.
+///
+/// Panics caught by [`error_boundary`](crate::views::error_boundary) are recorded too, so the
+/// report is only shown once a panic reaches the event loop.
+pub(crate) fn install_hook() {
+    let ui_thread = std::thread::current().id();
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous_hook(info);
+        if std::thread::current().id() == ui_thread {
+            let backtrace = Backtrace::force_capture();
+            *LAST_PANIC.lock() = Some(format!("{info}\n\nBacktrace:\n{backtrace}"));
+        }
+    }));
+}
+
+/// Launches the crash screen with the report of the last panic and exits.
+pub(crate) fn crash() -> ! {
+    if !CRASHED.swap(true, Ordering::SeqCst) {
+        let report = LAST_PANIC
+            .lock()
+            .take()
+            .unwrap_or_else(|| "The app panicked without a message.".to_string());
+        let path = std::env::temp_dir().join(format!("floem-crash-{}.txt", std::process::id()));
+        if let (Ok(()), Ok(exe)) = (std::fs::write(&path, report), std::env::current_exe()) {
+            let _ = Command::new(exe)
+                .arg(format!("{CRASH_ARG}{}", path.display()))
+                .args(std::env::args().skip(1))
+                .spawn();
+        }
+    }
+    std::process::exit(101);
+}
+
+/// The report and the arguments to restart the app with, when the app was launched to show a
+/// crash report. The report file is removed once it's read.
+pub(crate) fn launched_report() -> Option<(String, Vec<String>)> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, args) = report_from_args(&args)?;
+    let report = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| format!("The crash report couldn't be read: {err}"));
+    let _ = std::fs::remove_file(&path);
+    Some((report, args))
+}
+
+pub(crate) fn crash_view(report: String, args: Vec<String>) -> impl View {
+    let (message, details) = report
+        .split_once("\n\n")
+        .map(|(message, details)| (message.to_string(), details.to_string()))
+        .unwrap_or_else(|| (report.clone(), String::new()));
+    let monospace = intern("monospace");
+    v_stack((
+        static_label("The app stopped because of an error")
+            .style(|s| s.font_size(18.0).font_bold()),
+        static_label(message).selectable(),
+        scroll(
+            static_label(details)
+                .selectable()
+                .style(move |s| s.font_family(monospace).font_size(12.0)),
+        )
+        .style(|s| s.flex_grow(1.0).flex_basis(0.0).width_full().border(1.0)),
+        h_stack((
+            button(|| "Copy Report").on_click_stop(move |_| {
+                let _ = Clipboard::set_contents(report.clone());
+            }),
+            button(|| "Restart").on_click_stop(move |_| {
+                if let Ok(exe) = std::env::current_exe() {
+                    let _ = Command::new(exe).args(&args).spawn();
+                }
+                quit_app();
+            }),
+            button(|| "Close").on_click_stop(|_| quit_app()),
+        ))
+        .style(|s| s.gap(8.0, 0.0).justify_end()),
+    ))
+    .style(|s| s.size_full().padding(16.0).gap(0.0, 12.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::report_from_args;
+
+    #[test]
+    fn crash_args() {
+        let args = ["--floem-crash-report=/tmp/crash.txt", "file.txt"].map(String::from);
+        assert_eq!(
+            report_from_args(&args),
+            Some((
+                PathBuf::from("/tmp/crash.txt"),
+                vec!["file.txt".to_string()]
+            ))
+        );
+        assert_eq!(report_from_args(&args[1..]), None);
+    }
+}
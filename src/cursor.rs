@@ -0,0 +1,176 @@
+//! Cursors drawn from images, see [`CustomCursor`].
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use floem_peniko::Color;
+use floem_renderer::{
+    usvg::{self, TreeParsing},
+    Renderer,
+};
+use image::DynamicImage;
+use kurbo::{Point, Size};
+use sha2::{Digest, Sha256};
+
+use crate::context::PaintCx;
+
+thread_local! {
+    /// The images of the custom cursors created on this thread, by the hash of their source.
+    static CURSORS: RefCell<HashMap<[u8; 32], Rc<CursorImage>>> = Default::default();
+}
+
+enum CursorImageData {
+    Image(DynamicImage),
+    Svg(usvg::Tree),
+}
+
+struct CursorImage {
+    data: CursorImageData,
+    size: Size,
+    hotspot: Point,
+}
+
+/// A cursor drawn from an image or an SVG, used with
+/// [`CursorStyle::Custom`](crate::style::CursorStyle::Custom).
+///
+/// The windowing backend can't set an image as the system cursor, so the system cursor is
+/// hidden while a custom cursor is shown and the image is drawn at the pointer position as
+/// part of the window contents. This repaints the window on every pointer move over views
+/// with a custom cursor, so prefer a standard [`CursorStyle`](crate::style::CursorStyle) for
+/// large views of expensive content.
+///
+/// A `CustomCursor` is a key to the decoded image, which is shared by all the cursors made
+/// from the same source. Creating a cursor in a style closure only hashes the source after
+/// the first time. The images are kept for the rest of the program, and a cursor created on
+/// one thread isn't drawn on another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomCursor {
+    key: [u8; 32],
+}
+
+impl CustomCursor {
+    /// Creates a cursor from an encoded image, such as a PNG file. `hotspot` is the point of
+    /// the image, in pixels, that is placed at the pointer position.
+    pub fn from_image(image: &[u8], hotspot: Point) -> Option<Self> {
+        let key = cursor_key(b"image", image, Size::ZERO, hotspot);
+        Self::get_or_insert(key, || {
+            let image = image::load_from_memory(image).ok()?;
+            Some(CursorImage::from_dynamic_image(image, hotspot))
+        })
+    }
+
+    /// Creates a cursor from `width * height` RGBA pixels.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32, hotspot: Point) -> Option<Self> {
+        let size = Size::new(width as f64, height as f64);
+        let key = cursor_key(b"rgba", &rgba, size, hotspot);
+        Self::get_or_insert(key, || {
+            let image = image::RgbaImage::from_raw(width, height, rgba)?;
+            Some(CursorImage::from_dynamic_image(
+                DynamicImage::ImageRgba8(image),
+                hotspot,
+            ))
+        })
+    }
+
+    /// Creates a cursor from an SVG drawn at `size`. `hotspot` is the point within `size`
+    /// that is placed at the pointer position.
+    pub fn from_svg(svg: &str, size: Size, hotspot: Point) -> Option<Self> {
+        let key = cursor_key(b"svg", svg.as_bytes(), size, hotspot);
+        Self::get_or_insert(key, || {
+            let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).ok()?;
+            Some(CursorImage {
+                data: CursorImageData::Svg(tree),
+                size,
+                hotspot,
+            })
+        })
+    }
+
+    fn get_or_insert(key: [u8; 32], load: impl FnOnce() -> Option<CursorImage>) -> Option<Self> {
+        let cursor = Self { key };
+        if cursor.image().is_some() {
+            return Some(cursor);
+        }
+        let image = Rc::new(load()?);
+        CURSORS.with(|cursors| cursors.borrow_mut().insert(key, image));
+        Some(cursor)
+    }
+
+    fn image(&self) -> Option<Rc<CursorImage>> {
+        CURSORS.with(|cursors| cursors.borrow().get(&self.key).cloned())
+    }
+
+    /// Draws the cursor with its hotspot at `pos`.
+    pub(crate) fn paint(&self, cx: &mut PaintCx, pos: Point) {
+        let Some(cursor) = self.image() else {
+            return;
+        };
+        let rect = cursor
+            .size
+            .to_rect()
+            .with_origin(pos - cursor.hotspot.to_vec2());
+        match &cursor.data {
+            CursorImageData::Image(img) => cx.draw_img(
+                floem_renderer::Img {
+                    img,
+                    data: img.as_bytes(),
+                    hash: &self.key,
+                },
+                rect,
+            ),
+            CursorImageData::Svg(tree) => cx.draw_svg(
+                floem_renderer::Svg {
+                    tree,
+                    hash: &self.key,
+                },
+                rect,
+                None::<Color>,
+            ),
+        }
+    }
+}
+
+impl CursorImage {
+    fn from_dynamic_image(image: DynamicImage, hotspot: Point) -> Self {
+        Self {
+            size: Size::new(image.width() as f64, image.height() as f64),
+            data: CursorImageData::Image(image),
+            hotspot,
+        }
+    }
+}
+
+/// The hash identifying a cursor made from `source`, also used by the renderers to cache the
+/// image.
+fn cursor_key(kind: &[u8], source: &[u8], size: Size, hotspot: Point) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(kind);
+    hasher.update(source);
+    for value in [size.width, size.height, hotspot.x, hotspot.y] {
+        hasher.update(value.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::{Point, Size};
+
+    use super::{CustomCursor, CURSORS};
+
+    #[test]
+    fn cursors_are_shared() {
+        let rgba = vec![255; 4 * 4 * 4];
+        let cursor = CustomCursor::from_rgba(rgba.clone(), 4, 4, Point::ZERO).unwrap();
+        let again = CustomCursor::from_rgba(rgba.clone(), 4, 4, Point::ZERO).unwrap();
+        let moved = CustomCursor::from_rgba(rgba, 4, 4, Point::new(2.0, 2.0)).unwrap();
+        assert_eq!(cursor, again);
+        assert_ne!(cursor, moved);
+        assert_eq!(CURSORS.with(|cursors| cursors.borrow().len()), 2);
+        assert_eq!(cursor.image().unwrap().size, Size::new(4.0, 4.0));
+
+        // Another thread has none of the images of this one
+        std::thread::spawn(move || assert!(cursor.image().is_none()))
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+//! Opening the app from links with a custom URL scheme, such as `myapp://settings/theme`, and
+//! from files opened with it.
+//!
+//! The requests are received as [`AppEvent::OpenUrls`](crate::AppEvent::OpenUrls) and
+//! [`AppEvent::OpenFiles`](crate::AppEvent::OpenFiles) once they're enabled with
+//! [`Application::url_schemes`](crate::Application::url_schemes).
+//!
+//! On Linux and Windows the OS launches the app with the link or the file as an argument, so
+//! the app should run in [single instance mode](crate::Application::single_instance) for them
+//! to reach the running instance. On macOS they're delivered by Apple Events.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Splits command line arguments into links with one of `schemes` and paths of existing
+/// files, ignoring the other arguments.
+pub(crate) fn classify_args(args: &[String], schemes: &[String]) -> (Vec<String>, Vec<PathBuf>) {
+    let mut urls = Vec::new();
+    let mut files = Vec::new();
+    for arg in args {
+        let is_url = arg.split_once(':').map_or(false, |(scheme, _)| {
+            schemes
+                .iter()
+                .any(|handled| handled.eq_ignore_ascii_case(scheme))
+        });
+        if is_url {
+            urls.push(arg.clone());
+        } else if !arg.starts_with('-') && Path::new(arg).exists() {
+            files.push(PathBuf::from(arg));
+        }
+    }
+    (urls, files)
+}
+
+/// Registers the app as the handler of links with `scheme`, so opening them launches the
+/// app with the link as an argument. `name` describes the app in the OS settings.
+///
+/// On macOS URL schemes can't be registered at runtime. They're declared in the
+/// `CFBundleURLTypes` key of the `Info.plist` of the app bundle instead, and this returns an
+/// [`Unsupported`](io::ErrorKind::Unsupported) error.
+pub fn register_url_scheme(scheme: &str, name: &str) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    register(scheme, name, &exe)
+}
+
+fn check(status: std::process::ExitStatus) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("registering the URL scheme failed"))
+    }
+}
+
+/// The directory for user data files, such as desktop entries.
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+pub(crate) fn data_home() -> io::Result<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .ok_or_else(|| io::Error::other("the home directory isn't set"))
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn register(scheme: &str, name: &str, exe: &Path) -> io::Result<()> {
+    let applications = data_home()?.join("applications");
+    std::fs::create_dir_all(&applications)?;
+
+    let file_name = format!("{scheme}-url-handler.desktop");
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{scheme};\n",
+        exe.display()
+    );
+    std::fs::write(applications.join(&file_name), entry)?;
+    check(
+        std::process::Command::new("xdg-mime")
+            .args(["default", &file_name, &format!("x-scheme-handler/{scheme}")])
+            .status()?,
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn register(scheme: &str, name: &str, exe: &Path) -> io::Result<()> {
+    let key = format!("HKCU\\Software\\Classes\\{scheme}");
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    let entries: [(String, Option<&str>, String); 3] = [
+        (key.clone(), None, format!("URL:{name}")),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!("{key}\\shell\\open\\command"), None, command),
+    ];
+    for (key, value, data) in &entries {
+        let mut reg = std::process::Command::new("reg");
+        reg.args(["add", key]);
+        match value {
+            Some(value) => reg.args(["/v", value]),
+            None => reg.arg("/ve"),
+        };
+        check(reg.args(["/d", data, "/f"]).status()?)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "windows")))]
+fn register(_scheme: &str, _name: &str, _exe: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "URL schemes are declared in the Info.plist of the app bundle on this platform",
+    ))
+}
+
+/// Receives the links and files the app is asked to open as Apple Events, which replace
+/// command line arguments on macOS.
+#[cfg(target_os = "macos")]
+pub(crate) mod apple_events {
+    use std::{ffi::CStr, os::raw::c_char, path::PathBuf};
+
+    use objc::{
+        class,
+        declare::ClassDecl,
+        msg_send,
+        runtime::{Object, Sel},
+        sel, sel_impl,
+    };
+
+    use crate::app::{Application, UserEvent};
+
+    const INTERNET_EVENT_CLASS: u32 = u32::from_be_bytes(*b"GURL");
+    const GET_URL: u32 = u32::from_be_bytes(*b"GURL");
+    const CORE_EVENT_CLASS: u32 = u32::from_be_bytes(*b"aevt");
+    const OPEN_DOCUMENTS: u32 = u32::from_be_bytes(*b"odoc");
+    const DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+    const FILE_URL: u32 = u32::from_be_bytes(*b"furl");
+
+    fn send(urls: Vec<String>, files: Vec<PathBuf>) {
+        Application::with_event_loop_proxy(|proxy| {
+            let _ = proxy.send_event(UserEvent::Open { urls, files });
+        });
+    }
+
+    unsafe fn string(ns_string: *mut Object) -> Option<String> {
+        if ns_string.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![ns_string, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        CStr::from_ptr(utf8).to_str().ok().map(str::to_string)
+    }
+
+    extern "C" fn handle_get_url(_: &Object, _: Sel, event: *mut Object, _reply: *mut Object) {
+        unsafe {
+            let descriptor: *mut Object =
+                msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+            if descriptor.is_null() {
+                return;
+            }
+            let url: *mut Object = msg_send![descriptor, stringValue];
+            if let Some(url) = string(url) {
+                send(vec![url], Vec::new());
+            }
+        }
+    }
+
+    extern "C" fn handle_open_documents(
+        _: &Object,
+        _: Sel,
+        event: *mut Object,
+        _reply: *mut Object,
+    ) {
+        unsafe {
+            let list: *mut Object = msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+            if list.is_null() {
+                return;
+            }
+            let count: isize = msg_send![list, numberOfItems];
+            let mut files = Vec::new();
+            // Descriptor lists are indexed from 1
+            for index in 1..=count {
+                let item: *mut Object = msg_send![list, descriptorAtIndex: index];
+                if item.is_null() {
+                    continue;
+                }
+                let url: *mut Object = msg_send![item, coerceToDescriptorType: FILE_URL];
+                if url.is_null() {
+                    continue;
+                }
+                let data: *mut Object = msg_send![url, data];
+                let length: usize = msg_send![data, length];
+                let bytes: *const u8 = msg_send![data, bytes];
+                if bytes.is_null() {
+                    continue;
+                }
+                let url = String::from_utf8_lossy(std::slice::from_raw_parts(bytes, length));
+                if let Some(paths) = crate::clipboard::parse_uri_list(&url) {
+                    files.extend(paths);
+                }
+            }
+            if !files.is_empty() {
+                send(Vec::new(), files);
+            }
+        }
+    }
+
+    /// Installs the handlers, which has to happen before the app finishes launching to receive
+    /// the events that launched it.
+    pub(crate) fn install() {
+        // The class is only declared once, even if this is called again
+        let Some(mut decl) = ClassDecl::new("FloemAppleEventHandler", class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(handleGetURLEvent:withReplyEvent:),
+                handle_get_url as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+            );
+            decl.add_method(
+                sel!(handleOpenDocumentsEvent:withReplyEvent:),
+                handle_open_documents as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+            );
+            let class = decl.register();
+            let handler: *mut Object = msg_send![class, new];
+            let manager: *mut Object =
+                msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+            let _: () = msg_send![manager,
+                setEventHandler: handler
+                andSelector: sel!(handleGetURLEvent:withReplyEvent:)
+                forEventClass: INTERNET_EVENT_CLASS
+                andEventID: GET_URL];
+            let _: () = msg_send![manager,
+                setEventHandler: handler
+                andSelector: sel!(handleOpenDocumentsEvent:withReplyEvent:)
+                forEventClass: CORE_EVENT_CLASS
+                andEventID: OPEN_DOCUMENTS];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classify_args;
+
+    #[test]
+    fn url_args() {
+        let args = ["--verbose", "MyApp://settings/theme", "other:thing"].map(String::from);
+        let (urls, files) = classify_args(&args, &["myapp".to_string()]);
+        assert_eq!(urls, vec!["MyApp://settings/theme".to_string()]);
+        assert!(files.is_empty());
+    }
+}
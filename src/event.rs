@@ -1,12 +1,15 @@
+use accesskit::{Action, ActionData};
 use floem_winit::{
     keyboard::{KeyCode, PhysicalKey},
     window::Theme,
 };
-use kurbo::{Point, Size};
+use kurbo::{Point, Size, Vec2};
 
 use crate::{
+    clipboard::ClipboardContent,
+    gamepad::GamepadEvent,
     keyboard::KeyEvent,
-    pointer::{PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    pointer::{ContextMenuEvent, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
 };
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -16,6 +19,7 @@ pub enum EventListener {
     Click,
     DoubleClick,
     SecondaryClick,
+    ContextMenu,
     DragStart,
     DragEnd,
     DragOver,
@@ -27,6 +31,8 @@ pub enum EventListener {
     PointerUp,
     PointerEnter,
     PointerLeave,
+    PointerDelta,
+    Gamepad,
     ImeEnabled,
     ImeDisabled,
     ImePreedit,
@@ -41,6 +47,8 @@ pub enum EventListener {
     WindowGotFocus,
     WindowLostFocus,
     WindowMaximizeChanged,
+    Paste,
+    AccessibilityAction,
 }
 
 #[derive(Debug, Clone)]
@@ -50,8 +58,16 @@ pub enum Event {
     PointerMove(PointerMoveEvent),
     PointerWheel(PointerWheelEvent),
     PointerLeave,
+    /// The raw motion reported by the pointing device while the pointer is locked to a view
+    /// with [`Id::request_pointer_lock`](crate::id::Id::request_pointer_lock). It is only sent
+    /// to the view holding the lock.
+    PointerDelta(Vec2),
+    /// A context menu was requested with a secondary click or the keyboard. Mouse requests are
+    /// sent to the views under the pointer and keyboard requests to the focused view.
+    ContextMenu(ContextMenuEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
+    Gamepad(GamepadEvent),
     ImeEnabled,
     ImeDisabled,
     ImePreedit {
@@ -68,6 +84,15 @@ pub enum Event {
     ThemeChanged(Theme),
     FocusGained,
     FocusLost,
+    /// The clipboard content pasted into the focused view, see
+    /// [`on_paste`](crate::views::Decorators::on_paste).
+    Paste(ClipboardContent),
+    /// An action requested by an assistive technology, such as a screen reader, for a view
+    /// which declared it in [`View::accessibility`](crate::view::View::accessibility).
+    AccessibilityAction {
+        action: Action,
+        data: Option<ActionData>,
+    },
 }
 
 impl Event {
@@ -78,6 +103,7 @@ impl Event {
             | Event::PointerMove(_)
             | Event::PointerWheel(_)
             | Event::PointerLeave
+            | Event::PointerDelta(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -90,8 +116,10 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => false,
-            Event::KeyDown(_) | Event::KeyUp(_) => true,
+            | Event::WindowLostFocus
+            | Event::AccessibilityAction { .. } => false,
+            Event::KeyDown(_) | Event::KeyUp(_) | Event::Gamepad(_) | Event::Paste(_) => true,
+            Event::ContextMenu(context_menu_event) => context_menu_event.keyboard,
         }
     }
 
@@ -102,8 +130,12 @@ impl Event {
             | Event::PointerMove(_)
             | Event::PointerWheel(_)
             | Event::PointerLeave => true,
-            Event::KeyDown(_)
+            Event::PointerDelta(_)
+            | Event::ContextMenu(_)
+            | Event::KeyDown(_)
             | Event::KeyUp(_)
+            | Event::Gamepad(_)
+            | Event::Paste(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -116,7 +148,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => false,
+            | Event::WindowLostFocus
+            | Event::AccessibilityAction { .. } => false,
         }
     }
 
@@ -140,6 +173,8 @@ impl Event {
             Event::PointerDown(_)
             | Event::PointerUp(_)
             | Event::PointerWheel(_)
+            | Event::PointerDelta(_)
+            | Event::ContextMenu(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -147,7 +182,10 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
             | Event::KeyDown(_)
-            | Event::KeyUp(_) => false,
+            | Event::KeyUp(_)
+            | Event::Gamepad(_)
+            | Event::Paste(_)
+            | Event::AccessibilityAction { .. } => false,
             Event::PointerLeave
             | Event::PointerMove(_)
             | Event::ThemeChanged(_)
@@ -167,9 +205,16 @@ impl Event {
             }
             Event::PointerMove(pointer_event) => Some(pointer_event.pos),
             Event::PointerWheel(pointer_event) => Some(pointer_event.pos),
+            // Keyboard requests go to the focused view, wherever the pointer is
+            Event::ContextMenu(context_menu_event) => {
+                (!context_menu_event.keyboard).then_some(context_menu_event.pos)
+            }
             Event::PointerLeave
+            | Event::PointerDelta(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
+            | Event::Gamepad(_)
+            | Event::Paste(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -182,7 +227,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => None,
+            | Event::WindowLostFocus
+            | Event::AccessibilityAction { .. } => None,
         }
     }
 
@@ -200,9 +246,18 @@ impl Event {
                 pointer_event.pos.x /= scale;
                 pointer_event.pos.y /= scale;
             }
+            Event::ContextMenu(context_menu_event) => {
+                context_menu_event.pos.x /= scale;
+                context_menu_event.pos.y /= scale;
+                context_menu_event.window_pos.x /= scale;
+                context_menu_event.window_pos.y /= scale;
+            }
             Event::PointerLeave
+            | Event::PointerDelta(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
+            | Event::Gamepad(_)
+            | Event::Paste(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -215,7 +270,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => {}
+            | Event::WindowLostFocus
+            | Event::AccessibilityAction { .. } => {}
         }
         self
     }
@@ -231,9 +287,15 @@ impl Event {
             Event::PointerWheel(pointer_event) => {
                 pointer_event.pos -= offset;
             }
+            Event::ContextMenu(context_menu_event) => {
+                context_menu_event.pos -= offset;
+            }
             Event::PointerLeave
+            | Event::PointerDelta(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
+            | Event::Gamepad(_)
+            | Event::Paste(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -246,7 +308,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => {}
+            | Event::WindowLostFocus
+            | Event::AccessibilityAction { .. } => {}
         }
         self
     }
@@ -258,7 +321,10 @@ impl Event {
             Event::PointerMove(_) => Some(EventListener::PointerMove),
             Event::PointerWheel(_) => Some(EventListener::PointerWheel),
             Event::PointerLeave => Some(EventListener::PointerLeave),
+            Event::PointerDelta(_) => Some(EventListener::PointerDelta),
+            Event::ContextMenu(_) => Some(EventListener::ContextMenu),
             Event::KeyDown(_) => Some(EventListener::KeyDown),
+            Event::Gamepad(_) => Some(EventListener::Gamepad),
             Event::KeyUp(_) => Some(EventListener::KeyUp),
             Event::ImeEnabled => Some(EventListener::ImeEnabled),
             Event::ImeDisabled => Some(EventListener::ImeDisabled),
@@ -273,6 +339,8 @@ impl Event {
             Event::FocusLost => Some(EventListener::FocusLost),
             Event::FocusGained => Some(EventListener::FocusGained),
             Event::ThemeChanged(_) => Some(EventListener::ThemeChanged),
+            Event::Paste(_) => Some(EventListener::Paste),
+            Event::AccessibilityAction { .. } => Some(EventListener::AccessibilityAction),
         }
     }
 }
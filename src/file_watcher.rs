@@ -0,0 +1,128 @@
+//! Watching files and directories for changes, enabled by the `file-watcher` feature.
+//!
+//! [`watch_path`] delivers the changes to the UI thread as a signal. The changes reported by
+//! the OS are collected until none arrived for [`DEBOUNCE`], so saving a file, which is often
+//! made of several writes and renames, is reported once.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{on_cleanup, ReadSignal};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::ext_event::create_signal_from_channel;
+
+/// How long changes are collected before they're reported together.
+pub const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How a watched path changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// A change of a file or directory. See [`watch_path`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathChange {
+    pub path: PathBuf,
+    pub kind: PathChangeKind,
+}
+
+/// Watches `path` for changes, and the files and directories below it when `recursive` is
+/// true.
+///
+/// The signal holds the changes reported together, in the order the paths first changed,
+/// with the last kind of change of each path. It's `None` until something changes.
+///
+/// The watch stops when the current scope is disposed, such as when the view calling this is
+/// removed.
+///
+/// ```ignore
+/// let changes = watch_path("assets", true)?;
+/// create_effect(move |_| {
+///     if let Some(changes) = changes.get() {
+///         for change in changes {
+///             reload_asset(&change.path);
+///         }
+///     }
+/// });
+/// ```
+pub fn watch_path(
+    path: impl Into<PathBuf>,
+    recursive: bool,
+) -> io::Result<ReadSignal<Option<Vec<PathChange>>>> {
+    let (raw_tx, raw_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = raw_tx.send(event);
+    })
+    .map_err(io::Error::other)?;
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(&path.into(), mode)
+        .map_err(io::Error::other)?;
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    std::thread::spawn(move || {
+        // Ends when the watcher, and with it the sender of the raw events, is dropped
+        while let Ok(event) = raw_rx.recv() {
+            let mut batch = Batch::default();
+            batch.add(event);
+            let deadline = Instant::now() + DEBOUNCE;
+            loop {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                match raw_rx.recv_timeout(timeout) {
+                    Ok(event) => batch.add(event),
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if !batch.changes.is_empty() && tx.send(batch.changes).is_err() {
+                return;
+            }
+        }
+    });
+
+    on_cleanup(move || drop(watcher));
+    Ok(create_signal_from_channel(rx))
+}
+
+#[derive(Default)]
+struct Batch {
+    changes: Vec<PathChange>,
+    /// The index of each path in `changes`.
+    indices: HashMap<PathBuf, usize>,
+}
+
+impl Batch {
+    fn add(&mut self, event: notify::Result<notify::Event>) {
+        let Ok(event) = event else {
+            return;
+        };
+        let kind = match event.kind {
+            EventKind::Create(_) => PathChangeKind::Created,
+            EventKind::Modify(_) | EventKind::Any | EventKind::Other => PathChangeKind::Modified,
+            EventKind::Remove(_) => PathChangeKind::Removed,
+            EventKind::Access(_) => return,
+        };
+        for path in event.paths {
+            match self.indices.get(&path) {
+                Some(&index) => self.changes[index].kind = kind,
+                None => {
+                    self.indices.insert(path.clone(), self.changes.len());
+                    self.changes.push(PathChange { path, kind });
+                }
+            }
+        }
+    }
+}
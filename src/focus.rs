@@ -0,0 +1,189 @@
+//! Moving the focus with Tab, in the order set by tab indices and focus scopes.
+//!
+//! The Tab order follows the tree of views, except that views with a positive tab index come
+//! first in ascending order of their index, and views with a negative index are left out. A
+//! focus scope is ordered as a whole at the position and tab index of its view, so the views
+//! inside it stay together and their indices only order them within the scope.
+//!
+//! Tab and Shift+Tab cycle through the views of the innermost scope trapping the focus which
+//! contains the focused view, or of the last one shown when the focus is outside of them.
+
+use crate::{context::AppState, id::Id, view::View};
+
+/// How a view groups the views inside it in the Tab order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FocusScope {
+    Group,
+    /// Keeps the focus inside the view, like a modal dialog.
+    Trap,
+}
+
+/// A view in the Tab order, or the views of a focus scope, with their positions in the tree.
+struct Entry {
+    tab_index: i32,
+    views: Vec<(Id, usize)>,
+}
+
+/// Orders `entries`, which are in tree order, by their tab index.
+fn order(mut entries: Vec<Entry>) -> Vec<(Id, usize)> {
+    // The sort is stable, so entries without a positive index stay in tree order
+    entries.sort_by_key(|entry| match entry.tab_index {
+        index if index > 0 => (0, index),
+        _ => (1, 0),
+    });
+    entries.into_iter().flat_map(|entry| entry.views).collect()
+}
+
+struct TabOrder<'a> {
+    app_state: &'a AppState,
+    /// The number of views visited so far.
+    position: usize,
+    focus: Option<Id>,
+    focus_position: Option<usize>,
+    /// The order of each trapping scope, in tree order.
+    traps: Vec<(Id, Vec<(Id, usize)>)>,
+}
+
+impl TabOrder<'_> {
+    fn visit(&mut self, view: &dyn View, entries: &mut Vec<Entry>) {
+        let id = view.id();
+        if self.app_state.is_hidden(id) {
+            return;
+        }
+        let position = self.position;
+        self.position += 1;
+        if self.focus == Some(id) {
+            self.focus_position = Some(position);
+        }
+
+        let state = self.app_state.view_states.get(&id);
+        let tab_index = state.and_then(|state| state.tab_index).unwrap_or(0);
+        let focusable = self.app_state.keyboard_navigable.contains(&id)
+            && !self.app_state.is_disabled(&id)
+            && tab_index >= 0;
+        let scope = state.and_then(|state| state.focus_scope);
+
+        let mut inner = Vec::new();
+        let children = if scope.is_some() {
+            &mut inner
+        } else {
+            &mut *entries
+        };
+        if focusable {
+            children.push(Entry {
+                tab_index: if scope.is_some() { 0 } else { tab_index },
+                views: vec![(id, position)],
+            });
+        }
+        view.for_each_child(&mut |child| {
+            self.visit(child, children);
+            false
+        });
+
+        if let Some(scope) = scope {
+            let views = order(inner);
+            if scope == FocusScope::Trap {
+                self.traps.push((id, views.clone()));
+            }
+            if tab_index >= 0 && !views.is_empty() {
+                entries.push(Entry { tab_index, views });
+            }
+        }
+    }
+}
+
+/// The view Tab moves the focus to from the view `from`, or Shift+Tab when `backwards`.
+pub(crate) fn tab_target(
+    root: &dyn View,
+    app_state: &AppState,
+    from: Option<Id>,
+    backwards: bool,
+) -> Option<Id> {
+    let mut tab_order = TabOrder {
+        app_state,
+        position: 0,
+        focus: from,
+        focus_position: None,
+        traps: Vec::new(),
+    };
+    let mut entries = Vec::new();
+    tab_order.visit(root, &mut entries);
+
+    let ancestors = from
+        .and_then(|id| id.id_path())
+        .map(|path| path.dispatch().to_vec())
+        .unwrap_or_default();
+    let trap = ancestors
+        .iter()
+        .rev()
+        .find_map(|id| tab_order.traps.iter().find(|(trap, _)| trap == id))
+        .or(tab_order.traps.last());
+    let views = match trap {
+        Some((_, views)) => views.clone(),
+        None => order(entries),
+    };
+    if views.is_empty() {
+        return None;
+    }
+
+    let last = views.len() - 1;
+    let current = from.and_then(|from| views.iter().position(|(id, _)| *id == from));
+    let index = match (current, tab_order.focus_position) {
+        (Some(i), _) if backwards => i.checked_sub(1).unwrap_or(last),
+        (Some(i), _) => {
+            if i == last {
+                0
+            } else {
+                i + 1
+            }
+        }
+        // The focused view isn't in the Tab order, so the order continues from its position
+        (None, Some(focus)) if backwards => views
+            .iter()
+            .rposition(|(_, position)| *position < focus)
+            .unwrap_or(last),
+        (None, Some(focus)) => views
+            .iter()
+            .position(|(_, position)| *position > focus)
+            .unwrap_or(0),
+        (None, None) if backwards => last,
+        (None, None) => 0,
+    };
+    Some(views[index].0)
+}
+
+/// Moves the focus with Tab from the view `from`, or with Shift+Tab when `backwards`.
+pub(crate) fn tab_navigation(
+    root: &dyn View,
+    app_state: &mut AppState,
+    from: Option<Id>,
+    backwards: bool,
+) {
+    if let Some(id) = tab_target(root, app_state, from, backwards) {
+        app_state.clear_focus();
+        app_state.update_focus(id, true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{order, Entry};
+    use crate::id::Id;
+
+    #[test]
+    fn tab_index_order() {
+        let ids: Vec<Id> = (0..5).map(|_| Id::next()).collect();
+        let entry = |tab_index, views: &[usize]| Entry {
+            tab_index,
+            views: views.iter().map(|&i| (ids[i], i)).collect(),
+        };
+        let ordered = order(vec![
+            entry(0, &[0]),
+            entry(2, &[1]),
+            entry(0, &[2, 3]),
+            entry(1, &[4]),
+        ]);
+        let ordered: Vec<Id> = ordered.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ordered, [ids[4], ids[1], ids[0], ids[2], ids[3]]);
+    }
+}
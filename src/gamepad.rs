@@ -0,0 +1,180 @@
+//! Gamepad input.
+//!
+//! Gamepads are read when the `gamepad` feature is enabled and [`Application::gamepad`] is
+//! called. Their input is sent as [`Event::Gamepad`](crate::event::Event::Gamepad) to the
+//! focused view, or to the root view of the focused window if no view has focus.
+//!
+//! [`Application::gamepad`]: crate::Application::gamepad
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NAVIGATION: AtomicBool = AtomicBool::new(false);
+
+/// Identifies a connected gamepad. Ids of disconnected gamepads may be reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GamepadId(pub usize);
+
+/// A gamepad button, named after its position on the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEventKind {
+    Connected {
+        name: String,
+    },
+    Disconnected,
+    ButtonDown(GamepadButton),
+    ButtonUp(GamepadButton),
+    /// The new value of an axis, between -1.0 and 1.0.
+    AxisChanged(GamepadAxis, f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadEvent {
+    pub id: GamepadId,
+    pub kind: GamepadEventKind,
+}
+
+impl GamepadEvent {
+    /// Returns the button if this event is a button press.
+    pub fn pressed(&self) -> Option<GamepadButton> {
+        match self.kind {
+            GamepadEventKind::ButtonDown(button) => Some(button),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if the D-pad moves the focus and the south button clicks the focused view.
+pub fn navigation_enabled() -> bool {
+    NAVIGATION.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_navigation_enabled(enabled: bool) {
+    NAVIGATION.store(enabled, Ordering::Relaxed);
+}
+
+/// Reads the gamepads on a background thread and forwards their input to the event loop.
+#[cfg(feature = "gamepad")]
+pub(crate) fn spawn_gamepad_thread() {
+    use std::time::Duration;
+
+    use crate::app::{Application, UserEvent};
+
+    let _ = std::thread::Builder::new()
+        .name("floem-gamepad".to_string())
+        .spawn(|| {
+            let Ok(mut gilrs) = gilrs::Gilrs::new() else {
+                return;
+            };
+            loop {
+                while let Some(event) = gilrs.next_event() {
+                    let Some(event) = convert_event(&gilrs, event) else {
+                        continue;
+                    };
+                    Application::with_event_loop_proxy(|proxy| {
+                        let _ = proxy.send_event(UserEvent::Gamepad(event));
+                    });
+                }
+                std::thread::sleep(Duration::from_millis(8));
+            }
+        });
+}
+
+#[cfg(feature = "gamepad")]
+fn convert_event(gilrs: &gilrs::Gilrs, event: gilrs::Event) -> Option<GamepadEvent> {
+    use gilrs::EventType;
+
+    let kind = match event.event {
+        EventType::Connected => GamepadEventKind::Connected {
+            name: gilrs.gamepad(event.id).name().to_string(),
+        },
+        EventType::Disconnected => GamepadEventKind::Disconnected,
+        EventType::ButtonPressed(button, _) => GamepadEventKind::ButtonDown(button.into()),
+        EventType::ButtonReleased(button, _) => GamepadEventKind::ButtonUp(button.into()),
+        EventType::AxisChanged(axis, value, _) => GamepadEventKind::AxisChanged(axis.into(), value),
+        _ => return None,
+    };
+    Some(GamepadEvent {
+        id: GamepadId(event.id.into()),
+        kind,
+    })
+}
+
+#[cfg(feature = "gamepad")]
+impl From<gilrs::Button> for GamepadButton {
+    fn from(button: gilrs::Button) -> Self {
+        use gilrs::Button;
+        match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftTrigger,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+            Button::RightTrigger => GamepadButton::RightTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger2,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::Mode => GamepadButton::Mode,
+            Button::LeftThumb => GamepadButton::LeftThumb,
+            Button::RightThumb => GamepadButton::RightThumb,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => GamepadButton::Unknown,
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<gilrs::Axis> for GamepadAxis {
+    fn from(axis: gilrs::Axis) -> Self {
+        use gilrs::Axis;
+        match axis {
+            Axis::LeftStickX => GamepadAxis::LeftStickX,
+            Axis::LeftStickY => GamepadAxis::LeftStickY,
+            Axis::LeftZ => GamepadAxis::LeftZ,
+            Axis::RightStickX => GamepadAxis::RightStickX,
+            Axis::RightStickY => GamepadAxis::RightStickY,
+            Axis::RightZ => GamepadAxis::RightZ,
+            Axis::DPadX => GamepadAxis::DPadX,
+            Axis::DPadY => GamepadAxis::DPadY,
+            _ => GamepadAxis::Unknown,
+        }
+    }
+}
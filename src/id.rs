@@ -8,17 +8,30 @@
 //! These ids are assigned via the [ViewContext](crate::ViewContext) and are unique across the entire application.
 //!
 
-use std::{any::Any, cell::RefCell, collections::HashMap, sync::atomic::AtomicU64};
+use std::{any::Any, cell::RefCell, collections::HashMap};
 
+use floem_reactive::{create_signal, ReadSignal};
 use kurbo::{Point, Rect};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 
 use crate::{
     animate::Animation,
-    context::{EventCallback, MenuCallback, ResizeCallback},
+    context::{
+        AccessibilityCallback, EventCallback, HitTestCallback, MenuCallback, PaintCallback,
+        ResizeCallback,
+    },
     event::EventListener,
+    focus::FocusScope,
+    keyboard::{Key, ModifiersState},
+    shortcut::{self, ShortcutError, ShortcutHandle},
     style::{Style, StyleClassRef, StyleSelector},
-    update::{UpdateMessage, CENTRAL_DEFERRED_UPDATE_MESSAGES, CENTRAL_UPDATE_MESSAGES},
+    tear_off::TearOff,
+    update::{
+        SimulatedInput, UpdateMessage, CENTRAL_DEFERRED_UPDATE_MESSAGES, CENTRAL_UPDATE_MESSAGES,
+    },
     view_data::{ChangeFlags, StackOffset},
+    window_handle::get_current_view,
 };
 
 thread_local! {
@@ -27,8 +40,72 @@ thread_local! {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
 /// A stable identifier for an element.
+///
+/// The lower half of an id is an index, which is reused once the view it identified is
+/// removed, and the upper half is the generation of the index, so ids which were released
+/// never identify another element.
 pub struct Id(u64);
 
+/// Hands out the indices of ids, reusing the indices of the released ids.
+#[derive(Default)]
+struct IdAllocator {
+    /// The current generation of each index.
+    generations: Vec<u32>,
+    /// The indices of the released ids.
+    free: Vec<u32>,
+}
+
+impl IdAllocator {
+    fn next(&mut self) -> Id {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.generations.len()).expect("out of ids");
+                self.generations.push(0);
+                index
+            }
+        };
+        let generation = self.generations[index as usize];
+        Id(((generation as u64) << 32) | index as u64)
+    }
+
+    fn release(&mut self, id: Id) {
+        let index = id.0 as u32;
+        let generation = (id.0 >> 32) as u32;
+        if let Some(current) = self
+            .generations
+            .get_mut(index as usize)
+            .filter(|current| **current == generation)
+        {
+            *current = current.wrapping_add(1);
+            self.free.push(index);
+        }
+    }
+
+    fn stats(&self) -> IdStats {
+        IdStats {
+            live: self.generations.len() - self.free.len(),
+            free: self.free.len(),
+        }
+    }
+}
+
+static ID_ALLOCATOR: Lazy<Mutex<IdAllocator>> = Lazy::new(Default::default);
+
+/// Counts of the ids handed out, see [`id_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IdStats {
+    /// The ids which weren't released.
+    pub live: usize,
+    /// The indices of released ids waiting to be reused.
+    pub free: usize,
+}
+
+/// Counts the ids which are in use, to check that the ids of removed views are reclaimed.
+pub fn id_stats() -> IdStats {
+    ID_ALLOCATOR.lock().stats()
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct IdPath(pub(crate) Vec<Id>);
 
@@ -42,14 +119,23 @@ impl IdPath {
 impl Id {
     /// Allocate a new, unique `Id`.
     pub fn next() -> Id {
-        static WIDGET_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
-        Id(WIDGET_ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+        ID_ALLOCATOR.lock().next()
+    }
+
+    /// Makes the index of the id available to new ids, once the view it identifies is removed.
+    /// Releasing an id twice does nothing.
+    pub(crate) fn release(self) {
+        ID_ALLOCATOR.lock().release(self);
     }
 
     pub fn to_raw(self) -> u64 {
         self.0
     }
 
+    pub(crate) fn from_raw(raw: u64) -> Id {
+        Id(raw)
+    }
+
     pub fn new(&self) -> Id {
         let mut id_path =
             ID_PATHS.with(|id_paths| id_paths.borrow().get(self).cloned().unwrap_or_default());
@@ -115,6 +201,56 @@ impl Id {
         self.add_update_message(UpdateMessage::Focus(*self));
     }
 
+    /// Moves the focus to the view after this one in the Tab order, as if this view was focused
+    /// and Tab was pressed.
+    pub fn focus_next(&self) {
+        self.add_update_message(UpdateMessage::FocusNext {
+            id: *self,
+            backwards: false,
+        });
+    }
+
+    /// Moves the focus to the view before this one in the Tab order, as if this view was
+    /// focused and Shift+Tab was pressed.
+    pub fn focus_prev(&self) {
+        self.add_update_message(UpdateMessage::FocusNext {
+            id: *self,
+            backwards: true,
+        });
+    }
+
+    /// A signal which is true while this view has the focus, to use in style closures and
+    /// effects.
+    ///
+    /// ```ignore
+    /// let input = text_input(name);
+    /// let focused = input.id().is_focused();
+    /// v_stack((label(|| "Name"), input))
+    ///     .style(move |s| s.apply_if(focused.get(), |s| s.border_color(Color::BLUE)))
+    /// ```
+    pub fn is_focused(&self) -> ReadSignal<bool> {
+        let (focused, set_focused) = create_signal(false);
+        self.add_update_message(UpdateMessage::FocusSignal {
+            id: *self,
+            signal: set_focused,
+        });
+        focused
+    }
+
+    /// Hides the cursor and locks it in place, sending the relative motion of the pointing
+    /// device to this view as [`Event::PointerDelta`](crate::event::Event::PointerDelta).
+    ///
+    /// The lock is released by [`Id::release_pointer_lock`], when the window loses focus or
+    /// when this view is removed.
+    pub fn request_pointer_lock(&self) {
+        self.add_update_message(UpdateMessage::PointerLock(*self));
+    }
+
+    /// Releases the pointer lock if it is held by this view.
+    pub fn release_pointer_lock(&self) {
+        self.add_update_message(UpdateMessage::ReleasePointerLock(*self));
+    }
+
     pub fn request_active(&self) {
         self.add_update_message(UpdateMessage::Active(*self));
     }
@@ -126,6 +262,13 @@ impl Id {
         });
     }
 
+    pub fn update_invalid(&self, is_invalid: bool) {
+        self.add_update_message(UpdateMessage::Invalid {
+            id: *self,
+            is_invalid,
+        });
+    }
+
     pub fn request_paint(&self) {
         self.add_update_message(UpdateMessage::RequestPaint);
     }
@@ -174,6 +317,49 @@ impl Id {
         self.add_update_message(UpdateMessage::KeyboardNavigable { id: *self });
     }
 
+    /// Makes the view keyboard navigatable at `index` in the Tab order, see
+    /// [`Decorators::tab_index`](crate::views::Decorators::tab_index).
+    pub fn tab_index(&self, index: i32) {
+        self.keyboard_navigatable();
+        self.add_update_message(UpdateMessage::TabIndex { id: *self, index });
+    }
+
+    /// Keeps the views inside this view together in the Tab order, see
+    /// [`Decorators::focus_scope`](crate::views::Decorators::focus_scope).
+    pub fn focus_scope(&self) {
+        self.add_update_message(UpdateMessage::FocusScope {
+            id: *self,
+            scope: FocusScope::Group,
+        });
+    }
+
+    /// Keeps the focus inside this view when moved with Tab, see
+    /// [`Decorators::trap_focus`](crate::views::Decorators::trap_focus).
+    pub fn trap_focus(&self) {
+        self.add_update_message(UpdateMessage::FocusScope {
+            id: *self,
+            scope: FocusScope::Trap,
+        });
+    }
+
+    /// Runs `action` when `shortcut` is pressed while the focus is in this view, taking
+    /// precedence over the shortcuts of the views around it. See [`shortcut`](crate::shortcut).
+    ///
+    /// Fails if the shortcut can't be parsed or conflicts with another one bound for this view.
+    pub fn register_shortcut(
+        &self,
+        shortcut: &str,
+        action: impl Fn() + 'static,
+    ) -> Result<ShortcutHandle, ShortcutError> {
+        shortcut::register(get_current_view(), Some(*self), shortcut, action)
+    }
+
+    /// Moves the focus between the keyboard navigatable views inside this view with the arrow
+    /// keys, based on their layout positions.
+    pub fn keyboard_navigatable_grid(&self) {
+        self.add_update_message(UpdateMessage::KeyboardNavigableGrid { id: *self });
+    }
+
     pub fn draggable(&self) {
         self.add_update_message(UpdateMessage::Draggable { id: *self });
     }
@@ -198,6 +384,12 @@ impl Id {
         self.add_update_message(UpdateMessage::CleanupListener { id: *self, action });
     }
 
+    /// Calls `action` when the message reaches the window, which happens once the view is
+    /// part of it.
+    pub fn update_mount_listener(&self, action: Box<dyn FnOnce(Id)>) {
+        self.add_update_message(UpdateMessage::MountListener { id: *self, action });
+    }
+
     pub fn update_animation(&self, animation: Animation) {
         self.add_update_message(UpdateMessage::Animation {
             id: *self,
@@ -205,6 +397,13 @@ impl Id {
         });
     }
 
+    pub(crate) fn update_tear_off(&self, tear_off: TearOff) {
+        self.add_update_message(UpdateMessage::TearOff {
+            id: *self,
+            tear_off,
+        });
+    }
+
     pub fn clear_focus(&self) {
         self.add_update_message(UpdateMessage::ClearFocus(*self));
     }
@@ -217,6 +416,52 @@ impl Id {
         self.add_update_message(UpdateMessage::PopoutMenu { id: *self, menu });
     }
 
+    pub fn update_paint_under(&self, action: Box<PaintCallback>) {
+        self.add_update_message(UpdateMessage::PaintUnder { id: *self, action });
+    }
+
+    pub fn update_paint_over(&self, action: Box<PaintCallback>) {
+        self.add_update_message(UpdateMessage::PaintOver { id: *self, action });
+    }
+
+    pub fn update_hit_test(&self, action: Box<HitTestCallback>) {
+        self.add_update_message(UpdateMessage::HitTest { id: *self, action });
+    }
+
+    pub fn update_accessibility(&self, action: Box<AccessibilityCallback>) {
+        self.add_update_message(UpdateMessage::Accessibility { id: *self, action });
+    }
+
+    pub fn update_title_segment(&self, segment: String) {
+        self.add_update_message(UpdateMessage::TitleSegment { id: *self, segment });
+    }
+
+    /// Clicks the view with the primary button at its center, as if the user clicked it.
+    ///
+    /// The pointer events are sent to the view once its layout is up to date, running its
+    /// event handlers and `on_click` listeners.
+    pub fn simulate_click(&self) {
+        self.simulate_input(SimulatedInput::Click);
+    }
+
+    /// Presses and releases `key` with `modifiers` held, sending the key events to the view
+    /// as if it was focused.
+    ///
+    /// The events are made as if typed on a US QWERTY keyboard, see
+    /// [`KeyInput::new`](crate::keyboard::KeyInput::new).
+    pub fn simulate_key(&self, key: Key, modifiers: ModifiersState) {
+        self.simulate_input(SimulatedInput::Key { key, modifiers });
+    }
+
+    /// Sends `text` to the view as if it was typed, such as to insert it into a text input.
+    pub fn simulate_text(&self, text: impl Into<String>) {
+        self.simulate_input(SimulatedInput::Text(text.into()));
+    }
+
+    fn simulate_input(&self, input: SimulatedInput) {
+        self.add_update_message(UpdateMessage::SimulateInput { id: *self, input });
+    }
+
     pub fn scroll_to(&self, rect: Option<Rect>) {
         self.add_update_message(UpdateMessage::ScrollTo { id: *self, rect });
     }
@@ -231,3 +476,166 @@ impl Id {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{any::Any, cell::Cell, rc::Rc};
+
+    use floem_reactive::create_rw_signal;
+
+    use super::{Id, IdAllocator, IdStats, ID_ALLOCATOR};
+    use crate::{
+        accessibility::{node_id, view_id},
+        context::UpdateCx,
+        keyboard::{Key, ModifiersState, NamedKey},
+        testing::TestHarness,
+        view::{View, ViewData},
+        views::{dyn_container, empty, empty_view_slot, label, move_view, view_slot, Decorators},
+    };
+
+    /// Counts the state updates it receives.
+    struct Recorder {
+        data: ViewData,
+        updates: Rc<Cell<usize>>,
+    }
+
+    impl View for Recorder {
+        fn view_data(&self) -> &ViewData {
+            &self.data
+        }
+
+        fn view_data_mut(&mut self) -> &mut ViewData {
+            &mut self.data
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx, _state: Box<dyn Any>) {
+            self.updates.set(self.updates.get() + 1);
+        }
+    }
+
+    fn is_released(id: Id) -> bool {
+        let generations = &ID_ALLOCATOR.lock().generations;
+        generations[id.0 as u32 as usize] != (id.0 >> 32) as u32
+    }
+
+    #[test]
+    fn id_recycling() {
+        let mut allocator = IdAllocator::default();
+        let id = allocator.next();
+        let other = allocator.next();
+        allocator.release(id);
+        assert_eq!(allocator.stats(), IdStats { live: 1, free: 1 });
+
+        let reused = allocator.next();
+        assert_eq!(reused.0 as u32, id.0 as u32);
+        assert_ne!(reused, id);
+
+        // A second release of the old id mustn't free the index of the new one
+        allocator.release(id);
+        assert_eq!(allocator.stats(), IdStats { live: 2, free: 0 });
+        assert_ne!(allocator.next(), other);
+    }
+
+    #[test]
+    fn simulate_key() {
+        let escaped = create_rw_signal(false);
+        let view = label(|| "Dialog").on_key_down(
+            Key::Named(NamedKey::Escape),
+            ModifiersState::empty(),
+            move |_| escaped.set(true),
+        );
+        let id = view.id();
+
+        // No key has been pressed on a platform window in this process
+        let mut harness = TestHarness::new(view);
+        id.simulate_key(Key::Named(NamedKey::Escape), ModifiersState::empty());
+        harness.update();
+        assert!(escaped.get());
+    }
+
+    #[test]
+    fn reused_index_gets_no_stale_state() {
+        let old = Id::next();
+        // The id the allocator hands out for the index of `old` once it's released
+        let reused = Id(old.0 + (1 << 32));
+        let old_updates = Rc::new(Cell::new(0));
+        let new_updates = Rc::new(Cell::new(0));
+        let replaced = create_rw_signal(false);
+        let view = dyn_container(move || replaced.get(), {
+            let old_updates = old_updates.clone();
+            let new_updates = new_updates.clone();
+            move |replaced| {
+                let (id, updates) = if replaced {
+                    (reused, new_updates.clone())
+                } else {
+                    (old, old_updates.clone())
+                };
+                Box::new(Recorder {
+                    data: ViewData::new(id),
+                    updates,
+                }) as Box<dyn View>
+            }
+        });
+        let mut harness = TestHarness::new(view);
+        assert!(old.id_path().is_some());
+
+        // An update sent to the old view as it's replaced, and one sent after
+        replaced.set(true);
+        old.update_state(());
+        harness.update();
+        old.update_state(());
+        harness.update();
+        assert!(is_released(old));
+        assert!(old.id_path().is_none());
+        assert!(reused.id_path().is_some());
+        assert_eq!(old_updates.get(), 0);
+        assert_eq!(new_updates.get(), 0);
+
+        reused.update_state(());
+        harness.update();
+        assert_eq!(new_updates.get(), 1);
+
+        // Assistive technologies acting on the node of the old view don't reach the new one
+        assert_ne!(node_id(reused), node_id(old));
+        assert_eq!(view_id(node_id(reused)), reused);
+        assert!(view_id(node_id(old)).id_path().is_none());
+    }
+
+    #[test]
+    fn moved_view_keeps_ids() {
+        let ids = Rc::new(Cell::new(None));
+        let show_slot = create_rw_signal(true);
+        let source = dyn_container(move || show_slot.get(), {
+            let ids = ids.clone();
+            move |show| {
+                if !show {
+                    return Box::new(empty()) as Box<dyn View>;
+                }
+                let slot = view_slot(|| label(|| "Moved"));
+                ids.set(Some((slot.id(), slot.view_id().unwrap())));
+                Box::new(slot)
+            }
+        });
+        let (from, moved) = ids.get().unwrap();
+        let target = empty_view_slot();
+        let to = target.id();
+        let mut source = TestHarness::new(source);
+        let mut target = TestHarness::new(target);
+
+        move_view(from, to);
+        source.update();
+        // The slot is removed and its window closed while the view is on its way
+        show_slot.set(false);
+        source.update();
+        assert!(is_released(from));
+        drop(source);
+        assert!(!is_released(moved));
+
+        target.update();
+        assert_eq!(moved.parent(), Some(to));
+        assert_eq!(target.text(), "Moved");
+
+        drop(target);
+        assert!(is_released(moved));
+    }
+}
@@ -2,6 +2,7 @@ use crate::app::{add_app_update_event, AppUpdateEvent};
 use crate::context::{AppState, StyleCx};
 use crate::event::{Event, EventListener};
 use crate::id::Id;
+use crate::memory::{MemoryReport, MemoryStats};
 use crate::profiler::profiler;
 use crate::style::{Style, StyleMapValue};
 use crate::view::{view_children, View};
@@ -105,6 +106,7 @@ pub struct Capture {
     pub window_size: Size,
     pub scale: f64,
     pub state: CaptureState,
+    pub memory: MemoryReport,
 }
 
 #[derive(Default)]
@@ -411,6 +413,10 @@ fn stats(capture: &Capture) -> impl View {
     );
     let w = info("Window Width", format!("{}", capture.window_size.width));
     let h = info("Window Height", format!("{}", capture.window_size.height));
+    let ids = &capture.memory.ids;
+    let live_ids = info("Live Ids", ids.live.to_string());
+    let free_ids = info("Free Ids", ids.free.to_string());
+    let view_states = info("View States", capture.memory.view_states.to_string());
     v_stack((
         style_time,
         layout_time,
@@ -420,9 +426,42 @@ fn stats(capture: &Capture) -> impl View {
         paint_time,
         w,
         h,
+        live_ids,
+        free_ids,
+        view_states,
     ))
 }
 
+fn memory(stats: MemoryStats) -> impl View {
+    v_stack((
+        info("Views", stats.views.to_string()),
+        info("Scopes", stats.scopes.to_string()),
+        info("Signals", stats.signals.to_string()),
+        info("Effects", stats.effects.to_string()),
+        info("Images", stats.images.to_string()),
+        info("Taffy Nodes", stats.taffy_nodes.to_string()),
+    ))
+}
+
+fn leaked_scopes(capture: &Capture) -> impl View {
+    let leaked = &capture.memory.leaked_scopes;
+    if leaked.is_empty() {
+        return Box::new(text("No leaked scopes").style(|s| s.padding(5.0))) as Box<dyn View>;
+    }
+    Box::new(v_stack_from_iter(leaked.iter().map(|scope| {
+        info(
+            &scope.view,
+            format!(
+                "{} signals, {} scopes, {} effects, removed {:.1} s ago",
+                scope.stats.signals,
+                scope.stats.scopes,
+                scope.stats.effects,
+                scope.removed_for.as_secs_f64()
+            ),
+        )
+    })))
+}
+
 fn selected_view(capture: &Rc<Capture>, selected: RwSignal<Option<Id>>) -> impl View {
     let capture = capture.clone();
     dyn_container(
@@ -522,6 +561,15 @@ fn selected_view(capture: &Rc<Capture>, selected: RwSignal<Option<Id>>) -> impl
                     .on_click_stop(move |_| selected.set(None));
                 let clear = stack((clear,));
 
+                let memory_header = header("Memory");
+                let stats = capture
+                    .memory
+                    .root
+                    .find(view.id)
+                    .map(|memory| memory.stats)
+                    .unwrap_or_default();
+                let memory = memory(stats);
+
                 let style_header = header("View Style");
 
                 let direct: HashSet<_> = view.direct_style.map.keys().copied().collect();
@@ -621,6 +669,8 @@ fn selected_view(capture: &Rc<Capture>, selected: RwSignal<Option<Id>>) -> impl
                         tw,
                         th,
                         clear,
+                        memory_header,
+                        memory,
                         style_header,
                         style_list,
                     ))
@@ -751,6 +801,8 @@ fn capture_view(capture: &Rc<Capture>) -> impl View {
             selected_view(capture, capture_view.selected),
             header("Stats"),
             stats(capture),
+            header("Leaked Scopes"),
+            leaked_scopes(capture),
         ))
         .style(|s| s.min_width_full()),
     )
@@ -0,0 +1,110 @@
+//! Interned strings, compared and hashed by address instead of by content.
+//!
+//! Style properties holding names, like [`font_family`](crate::style::Style::font_family), are
+//! read and compared for every view each time styles are computed. An [`Atom`] is compared by
+//! address and copied without allocating, while [`intern`] takes a global lock and hashes the
+//! string, so names should be interned once, outside of style closures, and the atom reused.
+//!
+//! Interned strings are never freed, so only names from a small set should be interned, not
+//! arbitrary text.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    ptr,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+static INTERNER: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+/// An interned string. Two atoms are equal if they were interned from equal strings.
+#[derive(Clone, Copy)]
+pub struct Atom(&'static str);
+
+/// Interns `s`, returning the same atom for equal strings.
+pub fn intern(s: &str) -> Atom {
+    let mut interner = INTERNER.lock();
+    if let Some(interned) = interner.get(s) {
+        return Atom(*interned);
+    }
+    let interned: &'static str = Box::leak(Box::from(s));
+    interner.insert(interned);
+    Atom(interned)
+}
+
+impl Atom {
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Atom {}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.as_ptr() as usize)
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(s: &str) -> Self {
+        intern(s)
+    }
+}
+
+impl From<String> for Atom {
+    fn from(s: String) -> Self {
+        intern(&s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{intern, Atom};
+
+    #[test]
+    fn interning() {
+        let a = intern("monospace");
+        let b = Atom::from(String::from("monospace"));
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_ne!(a, intern("serif"));
+        assert_eq!(&*a, "monospace");
+    }
+}
@@ -1,7 +1,278 @@
+use std::{cell::RefCell, collections::HashMap};
+
 pub use floem_winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey, NativeKey, PhysicalKey};
+use floem_winit::{
+    event::ElementState,
+    keyboard::{KeyLocation, NativeKeyCode, SmolStr},
+};
+
+thread_local! {
+    /// The character produced by each physical key in the current keyboard layout, learned
+    /// from the key events received so far.
+    static LAYOUT_LABELS: RefCell<HashMap<KeyCode, String>> = Default::default();
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEvent {
-    pub key: floem_winit::event::KeyEvent,
+    pub key: KeyInput,
     pub modifiers: ModifiersState,
 }
+
+/// A key being pressed or released, with the fields of the platform key event.
+///
+/// Unlike the platform event, this can be made without a window, to simulate key presses.
+/// The platform event is kept when there is one, see [`KeyInput::platform_event`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeyInput {
+    pub physical_key: PhysicalKey,
+    pub logical_key: Key,
+    /// The text produced by the key, if any.
+    pub text: Option<SmolStr>,
+    pub location: KeyLocation,
+    pub state: ElementState,
+    pub repeat: bool,
+    platform: Option<floem_winit::event::KeyEvent>,
+}
+
+impl KeyInput {
+    /// A key event for `key`, as if it was typed on a US QWERTY keyboard. The physical key is
+    /// only known for the named keys which have one of their own, like `Enter` or `Tab`.
+    pub fn new(key: Key, state: ElementState) -> Self {
+        let text = match &key {
+            Key::Character(text) => Some(text.clone()),
+            Key::Named(NamedKey::Space) => Some(" ".into()),
+            Key::Named(NamedKey::Enter) => Some("\r".into()),
+            Key::Named(NamedKey::Tab) => Some("\t".into()),
+            _ => None,
+        };
+        let physical_key = match &key {
+            Key::Named(named) => named_key_code(*named)
+                .map(PhysicalKey::Code)
+                .unwrap_or(PhysicalKey::Unidentified(NativeKeyCode::Unidentified)),
+            _ => PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        };
+        Self {
+            physical_key,
+            logical_key: key,
+            text,
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+            platform: None,
+        }
+    }
+
+    /// The key event received from the platform, or `None` for a simulated key press.
+    pub fn platform_event(&self) -> Option<&floem_winit::event::KeyEvent> {
+        self.platform.as_ref()
+    }
+}
+
+impl From<floem_winit::event::KeyEvent> for KeyInput {
+    fn from(event: floem_winit::event::KeyEvent) -> Self {
+        Self {
+            physical_key: event.physical_key,
+            logical_key: event.logical_key.clone(),
+            text: event.text.clone(),
+            location: event.location,
+            state: event.state,
+            repeat: event.repeat,
+            platform: Some(event),
+        }
+    }
+}
+
+impl KeyEvent {
+    /// The key that was pressed, independent of the keyboard layout. Use this for bindings
+    /// that depend on the position of the key, such as `WASD` movement in games.
+    pub fn physical_key(&self) -> PhysicalKey {
+        self.key.physical_key
+    }
+
+    /// The key that was pressed, as interpreted by the current keyboard layout and modifiers.
+    pub fn logical_key(&self) -> &Key {
+        &self.key.logical_key
+    }
+
+    /// The platform specific scancode of the physical key, if the platform provides one.
+    pub fn scancode(&self) -> Option<u32> {
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        {
+            use floem_winit::platform::scancode::PhysicalKeyExtScancode;
+            self.key.platform.as_ref()?.physical_key.to_scancode()
+        }
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        {
+            None
+        }
+    }
+
+    /// The logical key ignoring the modifiers, e.g. `1` instead of `!` while shift is held.
+    pub fn key_without_modifiers(&self) -> Key {
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        if let Some(event) = &self.key.platform {
+            use floem_winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+            return event.key_without_modifiers();
+        }
+        self.key.logical_key.clone()
+    }
+
+    /// The text produced by the key with all the modifiers applied, including the control
+    /// characters produced with `Ctrl` held. Simulated key presses produce their plain text.
+    pub fn text_with_all_modifiers(&self) -> Option<&str> {
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        if let Some(event) = &self.key.platform {
+            use floem_winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+            return event.text_with_all_modifiers();
+        }
+        self.key.text.as_deref()
+    }
+
+    /// Remembers the character the physical key produces in the current layout.
+    pub(crate) fn learn_layout(&self) {
+        let PhysicalKey::Code(code) = self.key.physical_key else {
+            return;
+        };
+        if let Key::Character(c) = self.key_without_modifiers() {
+            LAYOUT_LABELS.with(|labels| {
+                labels.borrow_mut().insert(code, c.to_uppercase());
+            });
+        }
+    }
+}
+
+/// The code of the physical key for the named keys which have a key of their own.
+fn named_key_code(key: NamedKey) -> Option<KeyCode> {
+    let code = match key {
+        NamedKey::Enter => KeyCode::Enter,
+        NamedKey::Space => KeyCode::Space,
+        NamedKey::Tab => KeyCode::Tab,
+        NamedKey::Escape => KeyCode::Escape,
+        NamedKey::Backspace => KeyCode::Backspace,
+        NamedKey::Delete => KeyCode::Delete,
+        NamedKey::Insert => KeyCode::Insert,
+        NamedKey::ArrowUp => KeyCode::ArrowUp,
+        NamedKey::ArrowDown => KeyCode::ArrowDown,
+        NamedKey::ArrowLeft => KeyCode::ArrowLeft,
+        NamedKey::ArrowRight => KeyCode::ArrowRight,
+        NamedKey::Home => KeyCode::Home,
+        NamedKey::End => KeyCode::End,
+        NamedKey::PageUp => KeyCode::PageUp,
+        NamedKey::PageDown => KeyCode::PageDown,
+        NamedKey::ContextMenu => KeyCode::ContextMenu,
+        NamedKey::F1 => KeyCode::F1,
+        NamedKey::F2 => KeyCode::F2,
+        NamedKey::F3 => KeyCode::F3,
+        NamedKey::F4 => KeyCode::F4,
+        NamedKey::F5 => KeyCode::F5,
+        NamedKey::F6 => KeyCode::F6,
+        NamedKey::F7 => KeyCode::F7,
+        NamedKey::F8 => KeyCode::F8,
+        NamedKey::F9 => KeyCode::F9,
+        NamedKey::F10 => KeyCode::F10,
+        NamedKey::F11 => KeyCode::F11,
+        NamedKey::F12 => KeyCode::F12,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Returns a label for a physical key in the current keyboard layout, for example to show
+/// the keys of a `WASD` binding as `ZQSD` on an AZERTY keyboard.
+///
+/// Platforms don't expose the layout directly, so the label of a character key is learned
+/// the first time the key is pressed. Until then, the label of the key on a US QWERTY
+/// keyboard is returned.
+pub fn key_label(key: PhysicalKey) -> Option<String> {
+    let PhysicalKey::Code(code) = key else {
+        return None;
+    };
+    LAYOUT_LABELS
+        .with(|labels| labels.borrow().get(&code).cloned())
+        .or_else(|| default_key_label(code).map(|label| label.to_string()))
+}
+
+/// The label of a key on a US QWERTY keyboard.
+fn default_key_label(code: KeyCode) -> Option<&'static str> {
+    let label = match code {
+        KeyCode::KeyA => "A",
+        KeyCode::KeyB => "B",
+        KeyCode::KeyC => "C",
+        KeyCode::KeyD => "D",
+        KeyCode::KeyE => "E",
+        KeyCode::KeyF => "F",
+        KeyCode::KeyG => "G",
+        KeyCode::KeyH => "H",
+        KeyCode::KeyI => "I",
+        KeyCode::KeyJ => "J",
+        KeyCode::KeyK => "K",
+        KeyCode::KeyL => "L",
+        KeyCode::KeyM => "M",
+        KeyCode::KeyN => "N",
+        KeyCode::KeyO => "O",
+        KeyCode::KeyP => "P",
+        KeyCode::KeyQ => "Q",
+        KeyCode::KeyR => "R",
+        KeyCode::KeyS => "S",
+        KeyCode::KeyT => "T",
+        KeyCode::KeyU => "U",
+        KeyCode::KeyV => "V",
+        KeyCode::KeyW => "W",
+        KeyCode::KeyX => "X",
+        KeyCode::KeyY => "Y",
+        KeyCode::KeyZ => "Z",
+        KeyCode::Digit0 => "0",
+        KeyCode::Digit1 => "1",
+        KeyCode::Digit2 => "2",
+        KeyCode::Digit3 => "3",
+        KeyCode::Digit4 => "4",
+        KeyCode::Digit5 => "5",
+        KeyCode::Digit6 => "6",
+        KeyCode::Digit7 => "7",
+        KeyCode::Digit8 => "8",
+        KeyCode::Digit9 => "9",
+        KeyCode::Backquote => "`",
+        KeyCode::Minus => "-",
+        KeyCode::Equal => "=",
+        KeyCode::BracketLeft => "[",
+        KeyCode::BracketRight => "]",
+        KeyCode::Backslash => "\\",
+        KeyCode::Semicolon => ";",
+        KeyCode::Quote => "'",
+        KeyCode::Comma => ",",
+        KeyCode::Period => ".",
+        KeyCode::Slash => "/",
+        KeyCode::Space => "Space",
+        KeyCode::Enter => "Enter",
+        KeyCode::Tab => "Tab",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Delete => "Delete",
+        KeyCode::Escape => "Esc",
+        KeyCode::ArrowUp => "Up",
+        KeyCode::ArrowDown => "Down",
+        KeyCode::ArrowLeft => "Left",
+        KeyCode::ArrowRight => "Right",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::ShiftLeft | KeyCode::ShiftRight => "Shift",
+        KeyCode::ControlLeft | KeyCode::ControlRight => "Ctrl",
+        KeyCode::AltLeft | KeyCode::AltRight => "Alt",
+        KeyCode::SuperLeft | KeyCode::SuperRight => "Super",
+        KeyCode::F1 => "F1",
+        KeyCode::F2 => "F2",
+        KeyCode::F3 => "F3",
+        KeyCode::F4 => "F4",
+        KeyCode::F5 => "F5",
+        KeyCode::F6 => "F6",
+        KeyCode::F7 => "F7",
+        KeyCode::F8 => "F8",
+        KeyCode::F9 => "F9",
+        KeyCode::F10 => "F10",
+        KeyCode::F11 => "F11",
+        KeyCode::F12 => "F12",
+        _ => return None,
+    };
+    Some(label)
+}
@@ -120,25 +120,50 @@
 //! - [Understanding Ids](crate::id)
 //! - [How the update lifecycle works](crate::renderer)
 //!
+pub mod accessibility;
 pub mod action;
 pub mod animate;
 mod app;
 mod app_handle;
+pub mod autostart;
 mod clipboard;
 pub mod context;
+mod crash_screen;
+pub mod cursor;
+pub mod deep_link;
 pub mod event;
 pub mod ext_event;
 pub mod file;
+#[cfg(feature = "file-watcher")]
+pub mod file_watcher;
+mod focus;
+pub mod gamepad;
 pub mod id;
 mod inspector;
+pub mod intern;
 pub mod keyboard;
+pub mod memory;
 pub mod menu;
 mod nav;
+pub mod notification;
 pub mod pointer;
+pub mod power;
+pub mod print;
+pub mod process;
 mod profiler;
+pub mod recent_documents;
 pub mod renderer;
 pub mod responsive;
+pub mod shortcut;
+mod single_instance;
+pub mod soft_keyboard;
+pub mod state_machine;
 pub mod style;
+pub mod svg_export;
+pub mod system_colors;
+pub mod tear_off;
+pub mod testing;
+pub mod text_scale;
 pub mod unit;
 mod update;
 pub mod view;
@@ -149,13 +174,17 @@ pub mod widgets;
 pub mod window;
 mod window_handle;
 
+pub use accesskit;
 pub use app::{launch, quit_app, AppEvent, Application};
-pub use clipboard::{Clipboard, ClipboardError};
+pub use clipboard::{Clipboard, ClipboardContent, ClipboardError};
 pub use context::EventPropagation;
 pub use floem_peniko as peniko;
 pub use floem_reactive as reactive;
+pub use floem_renderer::color;
 pub use floem_renderer::cosmic_text;
 pub use floem_renderer::Renderer;
+pub use floem_renderer::{LcdFilter, TextAntialiasing, TextRendering};
+pub use indexmap;
 pub use kurbo;
 pub use taffy;
 pub use window::{close_window, new_window};
@@ -0,0 +1,265 @@
+//! Reporting the memory retained by views, to find what keeps growing.
+//!
+//! [`memory_report`] counts the views, reactive scopes, signals and effects, cached images and
+//! layout nodes of each subtree of a window. The signals of a subtree are those of the scopes
+//! its views were created in, the scope of a view being the one current when it was created.
+//!
+//! A scope stays alive until it's disposed, which views like
+//! [`dyn_container`](crate::views::dyn_container) do when they remove the views created in it.
+//! A scope which is still alive once all of its views are removed is reported as leaked, with
+//! the signals it retains.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ops::Add,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{scope_stats, Scope, ScopeStats};
+use taffy::{node::Node, Taffy};
+
+use crate::{
+    context::AppState,
+    id::{id_stats, Id, IdStats},
+    update::UpdateMessage,
+    view::View,
+};
+
+/// Counts of what a subtree of views retains.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub views: usize,
+    pub scopes: usize,
+    pub signals: usize,
+    pub effects: usize,
+    /// The images cached to paint the views, such as decoded images and blurred shadows.
+    pub images: usize,
+    pub taffy_nodes: usize,
+}
+
+impl Add for MemoryStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            views: self.views + other.views,
+            scopes: self.scopes + other.scopes,
+            signals: self.signals + other.signals,
+            effects: self.effects + other.effects,
+            images: self.images + other.images,
+            taffy_nodes: self.taffy_nodes + other.taffy_nodes,
+        }
+    }
+}
+
+/// The memory retained by a view and its children.
+#[derive(Clone, Debug)]
+pub struct ViewMemory {
+    pub id: Id,
+    pub name: String,
+    /// The totals of the view and its children.
+    pub stats: MemoryStats,
+    pub children: Vec<ViewMemory>,
+}
+
+impl ViewMemory {
+    /// The memory of the view `id` in this subtree.
+    pub fn find(&self, id: Id) -> Option<&ViewMemory> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+}
+
+/// A scope which is alive although all of the views created in it were removed.
+#[derive(Clone, Debug)]
+pub struct LeakedScope {
+    /// The name of the last view of the scope which was removed.
+    pub view: String,
+    /// How long ago the last view was removed.
+    pub removed_for: Duration,
+    /// What the scope retains.
+    pub stats: ScopeStats,
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoryReport {
+    pub root: ViewMemory,
+    /// The leaked scopes of all windows.
+    pub leaked_scopes: Vec<LeakedScope>,
+    /// The ids of all windows, the ids of removed views being free to reuse.
+    pub ids: IdStats,
+    /// The number of view states of the window, which is the number of views in `root` unless
+    /// some are retained after their views were removed.
+    pub view_states: usize,
+}
+
+/// Reports the memory retained by the view `id` and its children to `on_report`, once the
+/// window has processed its pending updates.
+pub fn memory_report(id: Id, on_report: impl FnOnce(MemoryReport) + 'static) {
+    id.add_update_message(UpdateMessage::MemoryReport {
+        id,
+        on_report: Box::new(on_report),
+    });
+}
+
+#[derive(Default)]
+struct ScopeTracker {
+    /// The number of views created in each scope which weren't removed yet.
+    views: HashMap<Scope, usize>,
+    /// The scopes whose views were all removed, with the name of the last view removed and
+    /// when it was removed.
+    orphaned: HashMap<Scope, (String, Instant)>,
+    /// The number of scopes tracked after the disposed ones were last forgotten.
+    pruned_len: usize,
+}
+
+impl ScopeTracker {
+    /// Forgets the disposed scopes, once the number of scopes tracked doubled since the last
+    /// time.
+    fn prune(&mut self) {
+        if self.views.len() + self.orphaned.len() < 2 * self.pruned_len.max(64) {
+            return;
+        }
+        self.views.retain(|scope, _| scope.is_alive());
+        self.orphaned.retain(|scope, _| scope.is_alive());
+        self.pruned_len = self.views.len() + self.orphaned.len();
+    }
+}
+
+thread_local! {
+    static SCOPES: RefCell<ScopeTracker> = Default::default();
+}
+
+pub(crate) fn view_created(scope: Scope) {
+    SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        *scopes.views.entry(scope).or_default() += 1;
+        scopes.orphaned.remove(&scope);
+        scopes.prune();
+    });
+}
+
+pub(crate) fn view_removed(view: &dyn View) {
+    let scope = view.view_data().scope;
+    SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        let Some(count) = scopes.views.get_mut(&scope) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            scopes.views.remove(&scope);
+            let removed = (view.debug_name().to_string(), Instant::now());
+            scopes.orphaned.insert(scope, removed);
+            scopes.prune();
+        }
+    });
+}
+
+/// The scopes alive without any of their views, longest removed first.
+pub(crate) fn leaked_scopes() -> Vec<LeakedScope> {
+    SCOPES.with(|scopes| {
+        let mut leaked: Vec<LeakedScope> = scopes
+            .borrow()
+            .orphaned
+            .iter()
+            .filter(|(scope, _)| scope.is_alive())
+            .map(|(scope, (view, removed))| LeakedScope {
+                view: view.clone(),
+                removed_for: removed.elapsed(),
+                stats: scope.stats(),
+            })
+            .collect();
+        leaked.sort_by(|a, b| b.removed_for.cmp(&a.removed_for));
+        leaked
+    })
+}
+
+/// The number of layout nodes below `node` which aren't the nodes of child views.
+fn extra_nodes(taffy: &Taffy, node: Node, view_nodes: &HashSet<Node>) -> usize {
+    taffy
+        .children(node)
+        .into_iter()
+        .flatten()
+        .filter(|child| !view_nodes.contains(child))
+        .map(|child| 1 + extra_nodes(taffy, child, view_nodes))
+        .sum()
+}
+
+/// The memory of `view` and the scopes created for it and its children. A view owns its scope
+/// when none of the views in `ancestor_scopes` does.
+fn view_memory(
+    view: &dyn View,
+    app_state: &AppState,
+    ancestor_scopes: &mut Vec<Scope>,
+) -> (ViewMemory, Vec<Scope>) {
+    let id = view.id();
+    let scope = view.view_data().scope;
+    let owns_scope = !ancestor_scopes.contains(&scope);
+    let mut scopes = Vec::new();
+    if owns_scope {
+        ancestor_scopes.push(scope);
+        scopes.push(scope);
+    }
+
+    let mut children = Vec::new();
+    let mut child_nodes = HashSet::new();
+    view.for_each_child(&mut |child| {
+        let (memory, child_scopes) = view_memory(child, app_state, ancestor_scopes);
+        if let Some(state) = app_state.view_states.get(&child.id()) {
+            child_nodes.insert(state.node);
+        }
+        scopes.extend(child_scopes);
+        children.push(memory);
+        false
+    });
+    if owns_scope {
+        ancestor_scopes.pop();
+    }
+
+    let state = app_state.view_states.get(&id);
+    let reactive = scope_stats(scopes.iter().copied());
+    let own = MemoryStats {
+        views: 1,
+        scopes: reactive.scopes,
+        signals: reactive.signals,
+        effects: reactive.effects,
+        images: view.cached_images()
+            + state.map_or(0, |state| state.layer_shadow.is_some() as usize),
+        taffy_nodes: state.map_or(0, |state| {
+            1 + extra_nodes(&app_state.taffy, state.node, &child_nodes)
+        }),
+    };
+    // The reactive counts are already the totals, as scopes can be shared by several children
+    let stats = children.iter().fold(own, |stats, child| {
+        let total = stats + child.stats;
+        MemoryStats {
+            scopes: stats.scopes,
+            signals: stats.signals,
+            effects: stats.effects,
+            ..total
+        }
+    });
+    let memory = ViewMemory {
+        id,
+        name: view.debug_name().to_string(),
+        stats,
+        children,
+    };
+    (memory, scopes)
+}
+
+/// The memory of the window's `root` view and of the leaked scopes. The scope of the root is
+/// left out, as it's the scope the window was created in rather than one made for the window.
+pub(crate) fn report(root: &dyn View, app_state: &AppState) -> MemoryReport {
+    let (root, _) = view_memory(root, app_state, &mut vec![root.view_data().scope]);
+    MemoryReport {
+        root,
+        leaked_scopes: leaked_scopes(),
+        ids: id_stats(),
+        view_states: app_state.view_states.len(),
+    }
+}
@@ -1,5 +1,7 @@
 use std::sync::atomic::AtomicU64;
 
+use crate::shortcut::{Shortcut, ShortcutHandle};
+
 /// An entry in a menu.
 ///
 /// An entry is either a [`MenuItem`], a submenu (i.e. [`Menu`]).
@@ -30,6 +32,8 @@ impl Menu {
         }
     }
 
+    // Native context menus are only used on macOS and Windows
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
     pub(crate) fn popup(mut self) -> Self {
         self.popup = true;
         self
@@ -46,6 +50,7 @@ impl Menu {
         self.entry(MenuEntry::Separator)
     }
 
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
     pub(crate) fn platform_menu(&self) -> floem_winit::menu::Menu {
         let mut menu = if self.popup {
             floem_winit::menu::Menu::new_for_popup()
@@ -58,13 +63,14 @@ impl Menu {
                     menu.add_separator();
                 }
                 MenuEntry::Item(item) => {
-                    menu.add_item(
-                        item.id as u32,
-                        &item.title,
-                        // item.key.as_ref(),
-                        item.selected,
-                        item.enabled,
-                    );
+                    let title = match &item.shortcut {
+                        // Windows shows the text after a tab as the accelerator of the item
+                        Some(shortcut) if cfg!(target_os = "windows") => {
+                            format!("{}\t{shortcut}", item.title)
+                        }
+                        _ => item.title.clone(),
+                    };
+                    menu.add_item(item.id as u32, &title, item.selected, item.enabled);
                 }
                 MenuEntry::SubMenu(m) => {
                     let enabled = m.item.enabled;
@@ -80,7 +86,7 @@ impl Menu {
 pub struct MenuItem {
     pub(crate) id: u64,
     pub(crate) title: String,
-    // key: Option<HotKey>,
+    pub(crate) shortcut: Option<Shortcut>,
     selected: Option<bool>,
     pub(crate) enabled: bool,
     pub(crate) action: Option<Box<dyn Fn()>>,
@@ -99,7 +105,7 @@ impl MenuItem {
         Self {
             id,
             title: title.into(),
-            // key: None,
+            shortcut: None,
             selected: None,
             enabled: true,
             action: None,
@@ -111,6 +117,23 @@ impl MenuItem {
         self
     }
 
+    /// Binds the item to a shortcut registered with
+    /// [`register_shortcut`](crate::shortcut::register_shortcut), so a command registered once
+    /// is both in the menu and on the keyboard. The item shows the keys of the shortcut, and
+    /// choosing it runs the action of the shortcut unless the item has an
+    /// [action](MenuItem::action) of its own.
+    ///
+    /// The keys are read as the menu is built, so a menu built after the shortcut is
+    /// [rebound](ShortcutHandle::rebind) shows the new keys. The floem context menus and the
+    /// native menus of Windows show them, the native menus of macOS don't.
+    pub fn shortcut(mut self, handle: ShortcutHandle) -> Self {
+        self.shortcut = handle.shortcut();
+        if self.action.is_none() {
+            self.action = Some(Box::new(move || handle.run()));
+        }
+        self
+    }
+
     pub fn enabled(mut self, enabled: bool) -> Self {
         self.enabled = enabled;
         self
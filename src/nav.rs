@@ -1,24 +1,74 @@
 use floem_winit::keyboard::NamedKey;
 use kurbo::{Point, Rect};
 
-use crate::{
-    context::AppState,
-    id::Id,
-    view::{view_tab_navigation, View},
-};
+use crate::{context::AppState, focus::tab_navigation, id::Id, view::View};
 
 pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, view: &dyn View) {
     let focused = match app_state.focus {
         Some(id) => id,
         None => {
-            view_tab_navigation(
+            tab_navigation(
                 view,
                 app_state,
+                None,
                 matches!(key, NamedKey::ArrowUp | NamedKey::ArrowLeft),
             );
             return;
         }
     };
+    let candidates: Vec<Id> = app_state.keyboard_navigable.iter().copied().collect();
+    if let Some(id) = arrow_target(key, app_state, focused, candidates) {
+        app_state.clear_focus();
+        app_state.update_focus(id, true);
+    }
+}
+
+/// Moves the focus with an arrow key between the views inside the innermost
+/// `keyboard_navigatable_grid` container of the focused view.
+pub(crate) fn view_grid_navigation(key: NamedKey, app_state: &mut AppState) {
+    let Some(focused) = app_state.focus else {
+        return;
+    };
+    let Some(focused_path) = focused.id_path() else {
+        return;
+    };
+    let Some((_, ancestors)) = focused_path.dispatch().split_last() else {
+        return;
+    };
+    let Some(grid) = ancestors
+        .iter()
+        .rev()
+        .find(|id| app_state.keyboard_navigable_grids.contains(id))
+        .copied()
+    else {
+        return;
+    };
+
+    let candidates: Vec<Id> = app_state
+        .keyboard_navigable
+        .iter()
+        .copied()
+        .filter(|id| {
+            id.id_path()
+                .map(|path| path.dispatch().contains(&grid))
+                .unwrap_or(false)
+        })
+        .collect();
+    // The focus stays put at the edges of the grid instead of leaving it
+    if let Some(id) = arrow_target(key, app_state, focused, candidates) {
+        app_state.clear_focus();
+        app_state.update_focus(id, true);
+    }
+}
+
+/// Finds the view among `candidates` closest to `focused` in the direction of `key`, based on
+/// their layout positions.
+fn arrow_target(
+    key: NamedKey,
+    app_state: &mut AppState,
+    focused: Id,
+    mut candidates: Vec<Id>,
+) -> Option<Id> {
     let rect = app_state.get_layout_rect(focused).inflate(10.0, 10.0);
     let center = rect.center();
     let intersect_target = match key {
@@ -39,8 +89,7 @@ pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, vie
         NamedKey::ArrowRight => Rect::new(rect.x1, f64::NEG_INFINITY, f64::INFINITY, f64::INFINITY),
         _ => panic!(),
     };
-    let mut keyboard_navigable: Vec<Id> = app_state.keyboard_navigable.iter().copied().collect();
-    keyboard_navigable.retain(|id| {
+    candidates.retain(|id| {
         let layout = app_state.get_layout_rect(*id);
 
         !layout.intersect(intersect_target).is_empty()
@@ -50,7 +99,7 @@ pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, vie
     });
 
     let mut new_focus = None;
-    for id in keyboard_navigable {
+    for id in candidates {
         let id_rect = app_state.get_layout_rect(id);
         let id_center = id_rect.center();
         let id_edge = match key {
@@ -70,8 +119,5 @@ pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, vie
         }
     }
 
-    if let Some((id, _)) = new_focus {
-        app_state.clear_focus();
-        app_state.update_focus(id, true);
-    }
+    new_focus.map(|(id, _)| id)
 }
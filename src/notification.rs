@@ -0,0 +1,189 @@
+//! Native desktop notifications, shown outside of the app windows by the operating system.
+//!
+//! Notifications are posted with [`show_notification`](crate::action::show_notification).
+
+use std::path::PathBuf;
+
+/// A notification to post with [`show_notification`](crate::action::show_notification).
+#[derive(Debug, Clone, Default)]
+pub struct Notification {
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) icon: Option<PathBuf>,
+    pub(crate) buttons: Vec<String>,
+}
+
+/// How the user responded to a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationResponse {
+    /// The notification itself was clicked.
+    Clicked,
+    /// The button with this index was clicked.
+    Button(usize),
+    /// The notification was closed or expired without being clicked, or the platform
+    /// doesn't report responses.
+    Dismissed,
+}
+
+impl Notification {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The text below the title.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// An image file shown with the notification.
+    pub fn icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Adds a button, which is reported as [`NotificationResponse::Button`] with its index
+    /// when clicked.
+    ///
+    /// Buttons are shown on Linux and Windows. macOS only shows the title and body, and
+    /// always reports [`NotificationResponse::Dismissed`].
+    pub fn button(mut self, label: impl Into<String>) -> Self {
+        self.buttons.push(label.into());
+        self
+    }
+
+    /// Posts the notification and waits for the user to respond to it.
+    pub(crate) fn post(&self) -> NotificationResponse {
+        post(self).unwrap_or(NotificationResponse::Dismissed)
+    }
+}
+
+/// Reads the action reported by the notification tools, which is `default` when the
+/// notification is clicked and the index of a button when one is clicked.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "freebsd", target_os = "windows")),
+    allow(dead_code)
+)]
+fn parse_response(output: &str) -> NotificationResponse {
+    match output.trim() {
+        "default" => NotificationResponse::Clicked,
+        action => action
+            .parse()
+            .map(NotificationResponse::Button)
+            .unwrap_or(NotificationResponse::Dismissed),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn post(notification: &Notification) -> Option<NotificationResponse> {
+    let mut command = std::process::Command::new("notify-send");
+    command.arg("--wait").arg("--action=default=Open");
+    for (index, label) in notification.buttons.iter().enumerate() {
+        command.arg(format!("--action={index}={label}"));
+    }
+    if let Some(icon) = &notification.icon {
+        command.arg("--icon").arg(icon);
+    }
+    let output = command
+        .arg("--")
+        .arg(&notification.title)
+        .arg(&notification.body)
+        .output()
+        .ok()?;
+    Some(parse_response(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(target_os = "macos")]
+fn post(notification: &Notification) -> Option<NotificationResponse> {
+    let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        quote(&notification.body),
+        quote(&notification.title)
+    );
+    std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .status()
+        .ok()?;
+    Some(NotificationResponse::Dismissed)
+}
+
+#[cfg(target_os = "windows")]
+fn post(notification: &Notification) -> Option<NotificationResponse> {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    };
+    let image = notification
+        .icon
+        .as_ref()
+        .map(|icon| {
+            format!(
+                r#"<image placement="appLogoOverride" src="{}"/>"#,
+                escape(&icon.to_string_lossy())
+            )
+        })
+        .unwrap_or_default();
+    let actions: String = notification
+        .buttons
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            format!(
+                r#"<action content="{}" arguments="{index}" activationType="foreground"/>"#,
+                escape(label)
+            )
+        })
+        .collect();
+    let xml = format!(
+        r#"<toast launch="default"><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text>{image}</binding></visual><actions>{actions}</actions></toast>"#,
+        escape(&notification.title),
+        escape(&notification.body)
+    );
+    // The toast is shown as PowerShell, which is allowed to post them without an app identity
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom, ContentType = WindowsRuntime] > $null; \
+         $xml = New-Object Windows.Data.Xml.Dom.XmlDocument; $xml.LoadXml('{}'); \
+         $toast = New-Object Windows.UI.Notifications.ToastNotification $xml; \
+         Register-ObjectEvent $toast Activated -SourceIdentifier activated > $null; \
+         Register-ObjectEvent $toast Dismissed -SourceIdentifier dismissed > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe').Show($toast); \
+         $response = Wait-Event; \
+         if ($response.SourceIdentifier -eq 'activated') {{ ([Windows.UI.Notifications.ToastActivatedEventArgs]$response.SourceEventArgs).Arguments }}",
+        xml.replace('\'', "''")
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .ok()?;
+    Some(parse_response(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+fn post(_notification: &Notification) -> Option<NotificationResponse> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses() {
+        assert_eq!(parse_response("default\n"), NotificationResponse::Clicked);
+        assert_eq!(parse_response("1\n"), NotificationResponse::Button(1));
+        assert_eq!(parse_response(""), NotificationResponse::Dismissed);
+    }
+}
@@ -53,6 +53,19 @@ impl PointerButton {
     }
 }
 
+/// A request to open a context menu, from a secondary click or the keyboard.
+#[derive(Debug, Clone)]
+pub struct ContextMenuEvent {
+    /// The position of the menu relative to the view receiving the event.
+    pub pos: Point,
+    /// The position of the menu in the window.
+    pub window_pos: Point,
+    /// Whether the menu was requested with the Menu key or Shift+F10, in which case the
+    /// position is the bottom left corner of the focused view.
+    pub keyboard: bool,
+    pub modifiers: ModifiersState,
+}
+
 #[derive(Debug, Clone)]
 pub struct PointerInputEvent {
     pub pos: Point,
@@ -0,0 +1,288 @@
+//! The power state of the computer and whether the user is idle, so apps can do less work on
+//! battery and dim their content when the user is away.
+//!
+//! [`power_state`] is read every 30 seconds on a background thread, with `/sys/class/power_supply`
+//! and `powerprofilesctl` on Linux, `pmset` on macOS and `GetSystemPowerStatus` on Windows.
+//! [`user_idle`] is based on the input received by the app's windows only, not on the input to
+//! other apps.
+
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{ReadSignal, RwSignal, Scope};
+
+use crate::{action::exec_after, ext_event::update_signal_from_channel};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The power source and battery of the computer. See [`power_state`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PowerState {
+    /// Whether the computer runs on its battery rather than being plugged in.
+    pub on_battery: bool,
+    /// The charge of the battery from 0.0 to 1.0, or `None` without a battery.
+    pub battery_level: Option<f64>,
+    pub charging: bool,
+    /// Whether the OS is in its power saving mode, such as Low Power Mode on macOS or Energy
+    /// Saver on Windows.
+    pub power_saver: bool,
+}
+
+thread_local! {
+    static POWER_STATE: RefCell<Option<ReadSignal<PowerState>>> = RefCell::new(None);
+    static LAST_INPUT: Cell<Option<Instant>> = Cell::new(None);
+    static IDLE_SIGNALS: RefCell<Vec<(Duration, RwSignal<bool>)>> = RefCell::new(Vec::new());
+}
+
+/// The power state of the computer, which is read again every 30 seconds.
+///
+/// It's the default state, as on a plugged in computer without a battery, until it's first
+/// read and on platforms where it can't be read.
+pub fn power_state() -> ReadSignal<PowerState> {
+    POWER_STATE.with(|state| {
+        *state.borrow_mut().get_or_insert_with(|| {
+            // The signals outlive the views and windows reading them
+            let scope = Scope::new();
+            let state = scope.create_rw_signal(PowerState::default());
+            let reading = scope.create_rw_signal(None);
+            scope.create_effect(move |_| {
+                if let Some(reading) = reading.get() {
+                    state.set(reading);
+                }
+            });
+
+            let (tx, rx) = crossbeam_channel::unbounded();
+            update_signal_from_channel(reading.write_only(), rx);
+            std::thread::spawn(move || {
+                let mut last = None;
+                loop {
+                    let reading = read_power_state();
+                    if last != Some(reading) {
+                        last = Some(reading);
+                        if tx.send(reading).is_err() {
+                            return;
+                        }
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            });
+            state.read_only()
+        })
+    })
+}
+
+/// How long it has been since the user last used the keyboard, mouse or touch screen in one
+/// of the app's windows.
+pub fn idle_time() -> Duration {
+    LAST_INPUT.with(|last| last.get().map_or(Duration::ZERO, |last| last.elapsed()))
+}
+
+/// A signal which becomes true once the user hasn't used the keyboard, mouse or touch screen
+/// in the app's windows for `after`, and false again when they do.
+///
+/// Only the input to the app's own windows counts: the user is idle for the app while they
+/// work in another app, even though the system isn't idle.
+///
+/// The signal belongs to the current scope and stops being updated when it's disposed.
+///
+/// ```ignore
+/// let away = user_idle(Duration::from_secs(120));
+/// video_view().style(move |s| s.apply_if(away.get(), |s| s.opacity(0.3)))
+/// ```
+pub fn user_idle(after: Duration) -> ReadSignal<bool> {
+    let scope = Scope::current();
+    let idle = scope.create_rw_signal(false);
+    IDLE_SIGNALS.with(|signals| signals.borrow_mut().push((after, idle)));
+    scope.on_cleanup(move || {
+        IDLE_SIGNALS.with(|signals| signals.borrow_mut().retain(|(_, signal)| *signal != idle));
+    });
+    LAST_INPUT.with(|last| {
+        if last.get().is_none() {
+            last.set(Some(Instant::now()));
+        }
+    });
+    check_idle(after, idle);
+    idle.read_only()
+}
+
+/// Sets `idle` if there was no input for `after`, or checks again when there would be.
+fn check_idle(after: Duration, idle: RwSignal<bool>) {
+    if idle.try_get_untracked().is_none() {
+        // The scope of the signal was disposed
+        return;
+    }
+    let elapsed = idle_time();
+    if elapsed >= after {
+        idle.set(true);
+    } else {
+        exec_after(after - elapsed, move |_| check_idle(after, idle));
+    }
+}
+
+/// Called by the windows when they receive input from the user.
+pub(crate) fn record_input() {
+    LAST_INPUT.with(|last| last.set(Some(Instant::now())));
+    IDLE_SIGNALS.with(|signals| {
+        for (after, idle) in signals.borrow().iter().copied() {
+            // Signals which aren't idle yet already have a pending check
+            if idle.try_get_untracked() == Some(true) {
+                idle.set(false);
+                check_idle(after, idle);
+            }
+        }
+    });
+}
+
+#[cfg(not(windows))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_power_state() -> PowerState {
+    let mut state = PowerState::default();
+    let supplies = std::fs::read_dir("/sys/class/power_supply")
+        .into_iter()
+        .flatten();
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|value| value.trim().to_string())
+                .ok()
+        };
+        if read("type").as_deref() != Some("Battery") {
+            continue;
+        }
+        let status = read("status").unwrap_or_default();
+        state.on_battery |= status == "Discharging";
+        state.charging |= status == "Charging";
+        if let Some(capacity) = read("capacity").and_then(|capacity| capacity.parse::<f64>().ok()) {
+            state.battery_level = Some(capacity / 100.0);
+        }
+    }
+    state.power_saver = match command_output("powerprofilesctl", &["get"]) {
+        Some(profile) => profile == "power-saver",
+        None => std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+            .map_or(false, |profile| profile.trim() == "low-power"),
+    };
+    state
+}
+
+#[cfg(target_os = "macos")]
+fn read_power_state() -> PowerState {
+    let mut state = command_output("pmset", &["-g", "batt"])
+        .map(|output| parse_pmset_batt(&output))
+        .unwrap_or_default();
+    state.power_saver = command_output("pmset", &["-g"]).map_or(false, |output| {
+        output.lines().any(|line| {
+            let mut words = line.split_whitespace();
+            words.next() == Some("lowpowermode") && words.next() == Some("1")
+        })
+    });
+    state
+}
+
+/// Parses the output of `pmset -g batt`, such as:
+///
+/// ```text
+/// Now drawing from 'Battery Power'
+///  -InternalBattery-0 (id=4653155)	85%; discharging; 3:12 remaining present: true
+/// ```
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset_batt(output: &str) -> PowerState {
+    let mut state = PowerState {
+        on_battery: output.contains("'Battery Power'"),
+        ..Default::default()
+    };
+    if let Some(battery) = output.lines().find(|line| line.contains("InternalBattery")) {
+        let fields: Vec<_> = battery.split(['\t', ';']).map(str::trim).collect();
+        state.battery_level = fields
+            .iter()
+            .find_map(|field| field.strip_suffix('%')?.parse::<f64>().ok())
+            .map(|percent| percent / 100.0);
+        state.charging = fields.contains(&"charging");
+    }
+    state
+}
+
+#[cfg(windows)]
+fn read_power_state() -> PowerState {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        _battery_life_time: u32,
+        _battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+    const NO_BATTERY: u8 = 128;
+    const CHARGING: u8 = 8;
+    const UNKNOWN: u8 = 255;
+
+    let mut status = SystemPowerStatus::default();
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerState::default();
+    }
+    let has_battery = status.battery_flag != NO_BATTERY && status.battery_flag != UNKNOWN;
+    PowerState {
+        on_battery: status.ac_line_status == 0,
+        battery_level: (has_battery && status.battery_life_percent <= 100)
+            .then(|| status.battery_life_percent as f64 / 100.0),
+        charging: has_battery && status.battery_flag & CHARGING != 0,
+        // Set while Battery Saver or Energy Saver is on
+        power_saver: status.system_status_flag == 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::with_scope;
+
+    use super::*;
+
+    #[test]
+    fn pmset_battery() {
+        let state = parse_pmset_batt(
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t85%; discharging; 3:12 remaining present: true\n",
+        );
+        assert!(state.on_battery);
+        assert!(!state.charging);
+        assert_eq!(state.battery_level, Some(0.85));
+
+        let state = parse_pmset_batt(
+            "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t40%; charging; 1:02 remaining present: true\n",
+        );
+        assert!(!state.on_battery);
+        assert!(state.charging);
+        assert_eq!(state.battery_level, Some(0.4));
+    }
+
+    #[test]
+    fn idle_signal_disposed_with_scope() {
+        let scope = Scope::new();
+        let idle = with_scope(scope, || user_idle(Duration::from_secs(60)));
+        assert!(!idle.get_untracked());
+        assert_eq!(IDLE_SIGNALS.with(|signals| signals.borrow().len()), 1);
+
+        scope.dispose();
+        assert!(IDLE_SIGNALS.with(|signals| signals.borrow().is_empty()));
+        record_input();
+    }
+}
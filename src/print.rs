@@ -0,0 +1,376 @@
+//! Printing and PDF export of view trees.
+//!
+//! A [`PrintDocument`] lays out a view at the width of the printable area of a page and splits
+//! it across as many pages as needed, with an optional header and footer on every page.
+//!
+//! ## Example
+//! ```ignore
+//! print_document(move || invoice_view(invoice.clone()))
+//!     .setup(PageSetup::letter())
+//!     .footer(|page| label(move || format!("Page {} of {}", page.number, page.count)))
+//!     .export_pdf("invoice.pdf")?;
+//! ```
+
+use std::{
+    cell::Cell,
+    fmt::Write as _,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use floem_reactive::Scope;
+use image::DynamicImage;
+use kurbo::{Insets, Point, Rect, Size};
+
+use crate::{
+    id::Id,
+    view::View,
+    views::{container, dyn_container, empty, scroll, v_stack, Decorators},
+    window_handle::{get_current_view, set_current_view, WindowHandle},
+};
+
+/// The size and margins of printed pages, in points (1/72 inch).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSetup {
+    pub size: Size,
+    /// The header is drawn in the top margin and the footer in the bottom margin.
+    pub margins: Insets,
+    /// The resolution pages are rendered at.
+    pub dpi: f64,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self::a4()
+    }
+}
+
+impl PageSetup {
+    pub fn a4() -> Self {
+        Self {
+            size: Size::new(595.0, 842.0),
+            margins: Insets::uniform(36.0),
+            dpi: 150.0,
+        }
+    }
+
+    pub fn letter() -> Self {
+        Self {
+            size: Size::new(612.0, 792.0),
+            ..Self::a4()
+        }
+    }
+
+    /// Swaps the width and height of the page.
+    pub fn landscape(mut self) -> Self {
+        self.size = Size::new(self.size.height, self.size.width);
+        self
+    }
+
+    pub fn margins(mut self, margins: impl Into<Insets>) -> Self {
+        self.margins = margins.into();
+        self
+    }
+
+    pub fn dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// The area of a page the content is printed in.
+    pub fn content_rect(&self) -> Rect {
+        self.size.to_rect() - self.margins
+    }
+}
+
+/// The page a header or footer is built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageInfo {
+    /// The number of the page, starting at 1.
+    pub number: usize,
+    /// The total number of pages.
+    pub count: usize,
+}
+
+type PageViewFn = Rc<dyn Fn(PageInfo) -> Box<dyn View>>;
+
+/// A view tree to print or export as PDF. See [`print_document`]
+pub struct PrintDocument {
+    content: Box<dyn FnOnce() -> Box<dyn View>>,
+    header: Option<PageViewFn>,
+    footer: Option<PageViewFn>,
+    setup: PageSetup,
+}
+
+/// Creates a document from the view returned by `content`, which is built when the document
+/// is rendered.
+///
+/// The content is laid out with the width of [`PageSetup::content_rect`] and its natural
+/// height, and is cut into pages at the height of the content area.
+pub fn print_document<V: View + 'static>(content: impl FnOnce() -> V + 'static) -> PrintDocument {
+    PrintDocument {
+        content: Box::new(move || Box::new(content())),
+        header: None,
+        footer: None,
+        setup: PageSetup::default(),
+    }
+}
+
+impl PrintDocument {
+    pub fn setup(mut self, setup: PageSetup) -> Self {
+        self.setup = setup;
+        self
+    }
+
+    /// Sets a view which is built for every page and drawn in its top margin.
+    pub fn header<V: View + 'static>(mut self, header: impl Fn(PageInfo) -> V + 'static) -> Self {
+        self.header = Some(Rc::new(move |page| Box::new(header(page))));
+        self
+    }
+
+    /// Sets a view which is built for every page and drawn in its bottom margin.
+    pub fn footer<V: View + 'static>(mut self, footer: impl Fn(PageInfo) -> V + 'static) -> Self {
+        self.footer = Some(Rc::new(move |page| Box::new(footer(page))));
+        self
+    }
+
+    /// Renders every page to an image at the resolution of the [`PageSetup`].
+    pub fn render_pages(self) -> Vec<DynamicImage> {
+        let setup = self.setup;
+        let content_rect = setup.content_rect();
+        let content_size = content_rect.size();
+        let scope = Scope::new();
+        let page = scope.create_rw_signal(PageInfo {
+            number: 1,
+            count: 1,
+        });
+        let padding = scope.create_rw_signal(0.0);
+        let content_id = Rc::new(Cell::new(None::<Id>));
+
+        let content = self.content;
+        let header = self.header;
+        let footer = self.footer;
+        let margin_view = move |view_fn: Option<PageViewFn>, height: f64| {
+            dyn_container(
+                move || page.get(),
+                move |page| match &view_fn {
+                    Some(view_fn) => view_fn(page),
+                    None => Box::new(empty()),
+                },
+            )
+            .style(move |s| {
+                s.width(content_size.width)
+                    .height(height)
+                    .flex_col()
+                    .justify_center()
+            })
+        };
+
+        let running_view = get_current_view();
+        let body_id = content_id.clone();
+        let mut handle = WindowHandle::new_headless(
+            move |_| {
+                let body = container(content()).style(move |s| {
+                    s.width(content_size.width)
+                        .padding_bottom(padding.get())
+                        .flex_col()
+                });
+                body_id.set(Some(body.id()));
+                Box::new(
+                    v_stack((
+                        margin_view(header, setup.margins.y0),
+                        scroll(body)
+                            .hide_bar(|| true)
+                            .scroll_to(move || {
+                                let page = page.get();
+                                Some(Point::new(
+                                    0.0,
+                                    (page.number - 1) as f64 * content_size.height,
+                                ))
+                            })
+                            .style(move |s| s.size(content_size.width, content_size.height)),
+                        margin_view(footer, setup.margins.y1),
+                    ))
+                    .style(move |s| s.size_full().padding_left(setup.margins.x0)),
+                )
+            },
+            setup.size,
+            setup.dpi / 72.0,
+            true,
+        );
+        handle.process_update_no_paint();
+
+        // Pad the content to a whole number of pages so the last page can be scrolled to
+        let height = content_id
+            .get()
+            .and_then(|id| handle.view_size(id))
+            .map_or(0.0, |size| size.height);
+        let count = ((height / content_size.height).ceil() as usize).max(1);
+        padding.set(count as f64 * content_size.height - height);
+
+        let mut pages = Vec::with_capacity(count);
+        for number in 1..=count {
+            page.set(PageInfo { number, count });
+            handle.process_update_no_paint();
+            if let Some(image) = handle.paint() {
+                pages.push(image);
+            }
+        }
+        handle.destroy();
+        scope.dispose();
+        set_current_view(running_view);
+        pages
+    }
+
+    /// Renders the document as a PDF file.
+    pub fn to_pdf(self) -> Vec<u8> {
+        let page_size = self.setup.size;
+        write_pdf(&self.render_pages(), page_size)
+    }
+
+    pub fn export_pdf(self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_pdf())
+    }
+
+    /// Sends the document to the default printer through the system print service.
+    pub fn print(self) -> io::Result<()> {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("floem-print-{}.pdf", std::process::id()));
+        self.export_pdf(&path)?;
+        print_file(&path)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn print_file(path: &Path) -> io::Result<()> {
+    let path = path.to_string_lossy().replace('\'', "''");
+    let status = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!("Start-Process -FilePath '{path}' -Verb Print"),
+        ])
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("printing failed"))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn print_file(path: &Path) -> io::Result<()> {
+    let status = std::process::Command::new("lp").arg(path).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("printing failed"))
+    }
+}
+
+/// Writes the pages as a PDF with one image per page, stretched to `page_size` points.
+fn write_pdf(pages: &[DynamicImage], page_size: Size) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::new();
+
+    /// Appends an object made of the dictionary `entries` and an optional stream.
+    fn object(pdf: &mut Vec<u8>, offsets: &mut Vec<usize>, entries: &str, stream: Option<&[u8]>) {
+        offsets.push(pdf.len());
+        let mut header = format!("{} 0 obj\n<< {entries}", offsets.len());
+        match stream {
+            Some(stream) => {
+                let _ = write!(header, " /Length {} >>\nstream\n", stream.len());
+                pdf.extend_from_slice(header.as_bytes());
+                pdf.extend_from_slice(stream);
+                pdf.extend_from_slice(b"\nendstream");
+            }
+            None => {
+                header.push_str(" >>");
+                pdf.extend_from_slice(header.as_bytes());
+            }
+        }
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    // Objects 1 and 2 are the catalog and the page tree, then each page takes three objects.
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 3 + i * 3))
+        .collect();
+    object(&mut pdf, &mut offsets, "/Type /Catalog /Pages 2 0 R", None);
+    object(
+        &mut pdf,
+        &mut offsets,
+        &format!(
+            "/Type /Pages /Kids [{}] /Count {}",
+            kids.join(" "),
+            pages.len()
+        ),
+        None,
+    );
+
+    let (width, height) = (page_size.width, page_size.height);
+    for (i, page) in pages.iter().enumerate() {
+        let page_id = 3 + i * 3;
+        let image = page.to_rgb8();
+        let data = miniz_oxide::deflate::compress_to_vec_zlib(image.as_raw(), 6);
+        object(
+            &mut pdf,
+            &mut offsets,
+            &format!(
+                "/Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R",
+                page_id + 2,
+                page_id + 1
+            ),
+            None,
+        );
+        let content = format!("q {width} 0 0 {height} 0 0 cm /Im0 Do Q");
+        object(&mut pdf, &mut offsets, "", Some(content.as_bytes()));
+        object(
+            &mut pdf,
+            &mut offsets,
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /FlateDecode",
+                image.width(),
+                image.height()
+            ),
+            Some(&data),
+        );
+    }
+
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = writeln!(trailer, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        offsets.len() + 1
+    );
+    pdf.extend_from_slice(trailer.as_bytes());
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, RgbImage};
+    use kurbo::Size;
+
+    use super::write_pdf;
+
+    #[test]
+    fn pdf_has_a_page_per_image() {
+        let page = DynamicImage::ImageRgb8(RgbImage::new(4, 6));
+        let pdf = write_pdf(&[page.clone(), page], Size::new(200.0, 300.0));
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Kids [3 0 R 6 0 R] /Count 2"));
+        assert!(text.contains("/MediaBox [0 0 200 300]"));
+        assert!(text.contains("/Width 4 /Height 6"));
+        assert!(text.contains("xref\n0 9\n"));
+        assert!(text.ends_with("%%EOF\n"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Running a child process and following its output from the UI.
+//!
+//! [`spawn_process`] reads the output of the process on background threads and appends it,
+//! line by line, to signals on the UI thread, so build output panels and similar views can be
+//! a plain `dyn_stack` or `label` over [`ProcessOutput::lines`].
+
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use floem_reactive::{create_effect, create_rw_signal, on_cleanup, ReadSignal};
+
+use crate::ext_event::update_signal_from_channel;
+
+/// Which output of the process a line was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line written by the process, without its line ending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub text: String,
+}
+
+enum ProcessEvent {
+    Line(OutputLine),
+    Exit(io::Result<ExitStatus>),
+}
+
+/// A process started with [`spawn_process`].
+#[derive(Clone)]
+pub struct ProcessOutput {
+    /// The lines of stdout and stderr, in the order they were read.
+    pub lines: ReadSignal<Vec<OutputLine>>,
+    /// The exit status once the process has exited, or the error waiting for it.
+    pub status: ReadSignal<Option<Result<ExitStatus, String>>>,
+    child: Arc<Mutex<Child>>,
+}
+
+impl ProcessOutput {
+    /// The lines written to one of the outputs.
+    pub fn stream_lines(&self, stream: OutputStream) -> Vec<String> {
+        self.lines.with(|lines| {
+            lines
+                .iter()
+                .filter(|line| line.stream == stream)
+                .map(|line| line.text.clone())
+                .collect()
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status.with(|status| status.is_none())
+    }
+
+    /// Kills the process. Its exit status is still reported through [`ProcessOutput::status`].
+    pub fn kill(&self) {
+        let _ = self.child.lock().unwrap().kill();
+    }
+}
+
+/// Spawns `command` with its stdout and stderr piped, and streams their lines into signals.
+/// The process gets no stdin.
+///
+/// The process is killed if it's still running when the current scope is disposed, such as
+/// when the view which started it is removed.
+///
+/// ```ignore
+/// let mut command = Command::new("cargo");
+/// command.arg("build");
+/// let build = spawn_process(command)?;
+/// dyn_stack(
+///     move || build.lines.get().into_iter().enumerate(),
+///     |(i, _)| *i,
+///     |(_, line)| label(move || line.text.clone()),
+/// )
+/// ```
+pub fn spawn_process(mut command: Command) -> io::Result<ProcessOutput> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let readers = [
+        spawn_reader(stdout, OutputStream::Stdout, tx.clone()),
+        spawn_reader(stderr, OutputStream::Stderr, tx.clone()),
+    ];
+
+    let child = Arc::new(Mutex::new(child));
+    {
+        let child = child.clone();
+        std::thread::spawn(move || {
+            for reader in readers {
+                let _ = reader.join();
+            }
+            // Waiting would hold the lock and block `kill`, so the process is polled instead
+            let status = loop {
+                match child.lock().unwrap().try_wait() {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) => {}
+                    Err(e) => break Err(e),
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            };
+            let _ = tx.send(ProcessEvent::Exit(status));
+        });
+    }
+
+    let lines = create_rw_signal(Vec::new());
+    let status = create_rw_signal(None);
+    let event = create_rw_signal(None);
+    update_signal_from_channel(event.write_only(), rx);
+    create_effect(move |_| {
+        event.with(|event| match event {
+            Some(ProcessEvent::Line(line)) => lines.update(|lines| lines.push(line.clone())),
+            Some(ProcessEvent::Exit(result)) => {
+                status.set(Some(result.as_ref().copied().map_err(|e| e.to_string())))
+            }
+            None => {}
+        })
+    });
+
+    {
+        let child = child.clone();
+        on_cleanup(move || {
+            if status.with_untracked(|status| status.is_none()) {
+                let _ = child.lock().unwrap().kill();
+            }
+        });
+    }
+
+    Ok(ProcessOutput {
+        lines: lines.read_only(),
+        status: status.read_only(),
+        child,
+    })
+}
+
+fn spawn_reader(
+    reader: impl Read + Send + 'static,
+    stream: OutputStream,
+    tx: crossbeam_channel::Sender<ProcessEvent>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+            let text = String::from_utf8_lossy(&buf);
+            let text = text.trim_end_matches(['\n', '\r']).to_string();
+            if tx
+                .send(ProcessEvent::Line(OutputLine { stream, text }))
+                .is_err()
+            {
+                return;
+            }
+        }
+    })
+}
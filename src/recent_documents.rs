@@ -0,0 +1,398 @@
+//! Publishing the documents recently opened with the app, and tasks launching it, to the OS.
+//!
+//! Recent documents are listed in the recent items of the Apple menu and the Dock on macOS, in
+//! the jump list of the app on Windows, and in the recent files of file managers on Linux.
+//! Picking one opens it with the app, which receives it as
+//! [`AppEvent::OpenFiles`](crate::AppEvent::OpenFiles) once opening files is enabled with
+//! [`Application::url_schemes`](crate::Application::url_schemes). On Windows a document is only
+//! listed in the jump list if its file type is registered to the app.
+//!
+//! Tasks are listed as actions of the launcher of the app on Linux. Picking one launches the
+//! app with an argument naming the task, and the app receives
+//! [`AppEvent::RunTask`](crate::AppEvent::RunTask), including when it's launched by the task.
+//! Combine this with [single instance mode](crate::Application::single_instance) for the
+//! running instance to receive the tasks picked while it's running.
+//!
+//! ## Example
+//! ```ignore
+//! set_tasks("com.example.Editor", "Editor", &[Task::new("new-window", "New Window")])?;
+//!
+//! Application::new()
+//!     .single_instance("com.example.Editor")
+//!     .url_schemes(Vec::<String>::new())
+//!     .on_event(|event| match event {
+//!         AppEvent::OpenFiles(files) => files.iter().for_each(|file| open(file)),
+//!         AppEvent::RunTask(task) if task == "new-window" => open_window(),
+//!         _ => {}
+//!     })
+//!     .window(move |_| app_view(), None)
+//!     .run();
+//!
+//! // Once a document is opened
+//! add_recent_document(&path)?;
+//! ```
+
+use std::{io, path::Path};
+
+/// The argument tasks launch the app with, followed by the id of the task.
+const TASK_ARG: &str = "--floem-task=";
+
+/// The id of the task the app was launched with, if any.
+pub(crate) fn task_from_args(args: &[String]) -> Option<String> {
+    args.iter()
+        .find_map(|arg| arg.strip_prefix(TASK_ARG))
+        .map(str::to_string)
+}
+
+/// A task launching the app, listed next to the recent documents. See [`set_tasks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    id: String,
+    // Tasks are only listed on Linux
+    #[cfg_attr(not(any(target_os = "linux", target_os = "freebsd")), allow(dead_code))]
+    title: String,
+}
+
+impl Task {
+    /// A task received as [`AppEvent::RunTask`](crate::AppEvent::RunTask) with `id`, which
+    /// can only contain ASCII letters, digits and `-`.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Task {
+            id: id.into(),
+            title: title.into(),
+        }
+    }
+}
+
+/// Adds the file at `path` to the recent documents of the app, moving it to the top if it's
+/// there already.
+pub fn add_recent_document(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = std::fs::canonicalize(path)?;
+    add_recent(&path)
+}
+
+/// Removes the documents added with [`add_recent_document`] from the recent documents.
+///
+/// Jump lists can't be cleared without COM on Windows, so this returns an
+/// [`Unsupported`](io::ErrorKind::Unsupported) error there.
+pub fn clear_recent_documents() -> io::Result<()> {
+    clear_recent()
+}
+
+/// Replaces the tasks of the app identified by `app_id`, which is named `app_name` in the
+/// launcher.
+///
+/// On Linux this writes the desktop entry `app_id.desktop` to the user's applications,
+/// replacing the one installed there if there's one. Tasks need jump lists made with COM on
+/// Windows and the Dock menu of the app delegate on macOS, so this returns an
+/// [`Unsupported`](io::ErrorKind::Unsupported) error there.
+pub fn set_tasks(app_id: &str, app_name: &str, tasks: &[Task]) -> io::Result<()> {
+    let valid =
+        |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if let Some(task) = tasks.iter().find(|task| !valid(&task.id)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid task id `{}`", task.id),
+        ));
+    }
+    write_tasks(app_id, app_name, tasks)
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn add_recent(path: &Path) -> io::Result<()> {
+    let file = crate::deep_link::data_home()?.join("recently-used.xbel");
+    let exe = std::env::current_exe()?;
+    let xbel = std::fs::read_to_string(&file).unwrap_or_default();
+    let xbel = xbel::add_bookmark(
+        &xbel,
+        &xbel::file_uri(path),
+        &xbel::app_name(&exe),
+        &format!("'{}' %u", exe.display()),
+        &xbel::timestamp(std::time::SystemTime::now()),
+    );
+    std::fs::write(file, xbel)
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn clear_recent() -> io::Result<()> {
+    let file = crate::deep_link::data_home()?.join("recently-used.xbel");
+    let xbel = match std::fs::read_to_string(&file) {
+        Ok(xbel) => xbel,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let app_name = xbel::app_name(&std::env::current_exe()?);
+    std::fs::write(file, xbel::remove_app_bookmarks(&xbel, &app_name))
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn write_tasks(app_id: &str, app_name: &str, tasks: &[Task]) -> io::Result<()> {
+    let applications = crate::deep_link::data_home()?.join("applications");
+    std::fs::create_dir_all(&applications)?;
+    let exe = std::env::current_exe()?;
+
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName={app_name}\nExec=\"{}\" %F\nActions=",
+        exe.display()
+    );
+    for task in tasks {
+        entry.push_str(&format!("{};", task.id));
+    }
+    entry.push('\n');
+    for task in tasks {
+        entry.push_str(&format!(
+            "\n[Desktop Action {}]\nName={}\nExec=\"{}\" {TASK_ARG}{}\n",
+            task.id,
+            task.title,
+            exe.display(),
+            task.id
+        ));
+    }
+    std::fs::write(applications.join(format!("{app_id}.desktop")), entry)
+}
+
+/// Recent files are bookmarks in the `recently-used.xbel` file on Linux.
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+mod xbel {
+    use std::{
+        path::Path,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    const HEADER: &str = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<xbel version=\"1.0\"\n",
+        "      xmlns:bookmark=\"http://www.freedesktop.org/standards/desktop-bookmarks\"\n",
+        "      xmlns:mime=\"http://www.freedesktop.org/standards/shared-mime-info\"\n",
+        ">\n",
+    );
+
+    /// The name the bookmarks of the app are added with.
+    pub(super) fn app_name(exe: &Path) -> String {
+        exe.file_stem()
+            .map_or_else(|| "floem".to_string(), |name| name.to_string_lossy().into())
+    }
+
+    pub(super) fn file_uri(path: &Path) -> String {
+        let mut uri = "file://".to_string();
+        for byte in path.to_string_lossy().bytes() {
+            if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+                uri.push(byte as char);
+            } else {
+                uri.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        uri
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    /// `time` in UTC, in the ISO 8601 format of the timestamps of bookmarks.
+    pub(super) fn timestamp(time: SystemTime) -> String {
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+        // The civil date from the days since the epoch, with eras of 400 years starting in March
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!(
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    }
+
+    /// Removes the bookmarks for which `remove` returns true.
+    fn remove_bookmarks(xbel: &str, remove: impl Fn(&str) -> bool) -> String {
+        let mut kept = String::with_capacity(xbel.len());
+        let mut rest = xbel;
+        while let Some(start) = rest.find("<bookmark ") {
+            let Some(end) = rest[start..].find("</bookmark>") else {
+                break;
+            };
+            let end = start + end + "</bookmark>".len();
+            if remove(&rest[start..end]) {
+                // Drops the indentation and the line break around the bookmark too
+                kept.push_str(rest[..start].trim_end_matches([' ', '\t']));
+                rest = rest[end..].strip_prefix('\n').unwrap_or(&rest[end..]);
+            } else {
+                kept.push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+        kept.push_str(rest);
+        kept
+    }
+
+    pub(super) fn remove_app_bookmarks(xbel: &str, app_name: &str) -> String {
+        let application = format!("<bookmark:application name=\"{}\"", escape(app_name));
+        remove_bookmarks(xbel, |bookmark| bookmark.contains(&application))
+    }
+
+    /// Adds a bookmark for the file at `uri` opened by the app, replacing the bookmark for the
+    /// file if there's one.
+    pub(super) fn add_bookmark(
+        xbel: &str,
+        uri: &str,
+        app_name: &str,
+        exec: &str,
+        now: &str,
+    ) -> String {
+        let href = format!("<bookmark href=\"{}\"", escape(uri));
+        let xbel = remove_bookmarks(xbel, |bookmark| bookmark.starts_with(&href));
+        let bookmark = format!(
+            concat!(
+                "  {href} added=\"{now}\" modified=\"{now}\" visited=\"{now}\">\n",
+                "    <info>\n",
+                "      <metadata owner=\"http://freedesktop.org\">\n",
+                "        <mime:mime-type type=\"application/octet-stream\"/>\n",
+                "        <bookmark:applications>\n",
+                "          <bookmark:application name=\"{}\" exec=\"{}\" ",
+                "modified=\"{now}\" count=\"1\"/>\n",
+                "        </bookmark:applications>\n",
+                "      </metadata>\n",
+                "    </info>\n",
+                "  </bookmark>\n",
+            ),
+            escape(app_name),
+            escape(exec),
+        );
+        match xbel.rfind("</xbel>") {
+            Some(end) => format!("{}{bookmark}{}", &xbel[..end], &xbel[end..]),
+            None => format!("{HEADER}{bookmark}</xbel>\n"),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn add_recent(path: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHAddToRecentDocs(flags: u32, data: *const std::ffi::c_void);
+    }
+    const SHARD_PATHW: u32 = 3;
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    unsafe { SHAddToRecentDocs(SHARD_PATHW, path.as_ptr().cast()) };
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn add_recent(path: &Path) -> io::Result<()> {
+    use objc::{class, msg_send, runtime::Object, sel, sel_impl};
+
+    let path = std::ffi::CString::new(path.to_string_lossy().as_bytes())
+        .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    unsafe {
+        let path: *mut Object = msg_send![class!(NSString), stringWithUTF8String: path.as_ptr()];
+        let url: *mut Object = msg_send![class!(NSURL), fileURLWithPath: path];
+        let controller: *mut Object =
+            msg_send![class!(NSDocumentController), sharedDocumentController];
+        let _: () = msg_send![controller, noteNewRecentDocumentURL: url];
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn clear_recent() -> io::Result<()> {
+    use objc::{class, msg_send, runtime::Object, sel, sel_impl};
+
+    unsafe {
+        let controller: *mut Object =
+            msg_send![class!(NSDocumentController), sharedDocumentController];
+        let _: () = msg_send![controller, clearRecentDocuments: std::ptr::null_mut::<Object>()];
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "macos")))]
+fn clear_recent() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "recent documents can't be cleared on this platform",
+    ))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "windows",
+    target_os = "macos"
+)))]
+fn add_recent(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "recent documents aren't supported on this platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+fn write_tasks(_app_id: &str, _app_name: &str, _tasks: &[Task]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "tasks aren't supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::task_from_args;
+
+    #[test]
+    fn task_args() {
+        let args = ["--verbose", "--floem-task=new-window"].map(String::from);
+        assert_eq!(task_from_args(&args), Some("new-window".to_string()));
+        assert_eq!(task_from_args(&args[..1]), None);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    #[test]
+    fn xbel() {
+        use std::{
+            path::Path,
+            time::{Duration, UNIX_EPOCH},
+        };
+
+        use super::xbel::{add_bookmark, file_uri, remove_app_bookmarks, timestamp};
+
+        let now = timestamp(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert_eq!(now, "2023-11-14T22:13:20Z");
+        let uri = file_uri(Path::new("/home/me/my notes.txt"));
+        assert_eq!(uri, "file:///home/me/my%20notes.txt");
+
+        let xbel = add_bookmark("", &uri, "editor", "'editor' %u", &now);
+        let other = add_bookmark(&xbel, "file:///other.txt", "viewer", "'viewer' %u", &now);
+        // Adding the file again replaces its bookmark
+        let again = add_bookmark(&other, &uri, "editor", "'editor' %u", &now);
+        assert_eq!(again.matches("<bookmark ").count(), 2);
+        assert!(again.ends_with("</bookmark>\n</xbel>\n"));
+
+        let cleared = remove_app_bookmarks(&again, "editor");
+        assert_eq!(
+            cleared,
+            add_bookmark("", "file:///other.txt", "viewer", "'viewer' %u", &now)
+        );
+    }
+}
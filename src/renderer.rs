@@ -47,18 +47,38 @@
 //! - Only one view can be active at a time.
 //! - Only one view can be focused at a time.
 //!
+use std::cell::Cell;
+
 use crate::cosmic_text::TextLayout;
 use floem_peniko::BrushRef;
-use floem_renderer::Img;
+use floem_renderer::{Img, TextRendering};
 use floem_tiny_skia_renderer::TinySkiaRenderer;
 use floem_vger_renderer::VgerRenderer;
 use image::DynamicImage;
 use kurbo::{Affine, Rect, Shape, Size};
 
+use crate::svg_export::SvgRenderer;
+
+thread_local! {
+    static DEFAULT_TEXT_RENDERING: Cell<TextRendering> = Cell::new(TextRendering::default());
+}
+
+/// The text rendering used by views which don't set
+/// [`Style::text_rendering`](crate::style::Style::text_rendering).
+pub fn default_text_rendering() -> TextRendering {
+    DEFAULT_TEXT_RENDERING.with(|config| config.get())
+}
+
+pub(crate) fn set_default_text_rendering(config: TextRendering) {
+    DEFAULT_TEXT_RENDERING.with(|c| c.set(config));
+}
+
 #[allow(clippy::large_enum_variant)]
 pub enum Renderer {
     Vger(VgerRenderer),
     TinySkia(TinySkiaRenderer),
+    /// Records the draw calls as an SVG document, see [`crate::svg_export`].
+    Svg(SvgRenderer),
 }
 
 impl Renderer {
@@ -99,11 +119,21 @@ impl Renderer {
         }
     }
 
+    /// Creates a renderer which draws into an offscreen image instead of a window.
+    pub fn new_headless(scale: f64, size: Size) -> Self {
+        let size = Size::new(size.width.max(1.0), size.height.max(1.0));
+        match TinySkiaRenderer::new_headless(size.width as u32, size.height as u32, scale) {
+            Ok(tiny_skia) => Self::TinySkia(tiny_skia),
+            Err(err) => panic!("Failed to create TinySkiaRenderer: {err}"),
+        }
+    }
+
     pub fn resize(&mut self, scale: f64, size: Size) {
         let size = Size::new(size.width.max(1.0), size.height.max(1.0));
         match self {
             Renderer::Vger(r) => r.resize(size.width as u32, size.height as u32, scale),
             Renderer::TinySkia(r) => r.resize(size.width as u32, size.height as u32, scale),
+            Renderer::Svg(_) => {}
         }
     }
 
@@ -111,6 +141,7 @@ impl Renderer {
         match self {
             Renderer::Vger(r) => r.set_scale(scale),
             Renderer::TinySkia(r) => r.set_scale(scale),
+            Renderer::Svg(_) => {}
         }
     }
 }
@@ -124,6 +155,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(r) => {
                 r.begin(capture);
             }
+            Renderer::Svg(r) => {
+                r.begin(capture);
+            }
         }
     }
 
@@ -135,6 +169,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.clip(shape);
             }
+            Renderer::Svg(v) => {
+                v.clip(shape);
+            }
         }
     }
 
@@ -146,6 +183,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.clear_clip();
             }
+            Renderer::Svg(v) => {
+                v.clear_clip();
+            }
         }
     }
 
@@ -157,6 +197,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.stroke(shape, brush, width);
             }
+            Renderer::Svg(v) => {
+                v.stroke(shape, brush, width);
+            }
         }
     }
 
@@ -173,6 +216,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.fill(path, brush, blur_radius);
             }
+            Renderer::Svg(v) => {
+                v.fill(path, brush, blur_radius);
+            }
         }
     }
 
@@ -184,6 +230,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.draw_text(layout, pos);
             }
+            Renderer::Svg(v) => {
+                v.draw_text(layout, pos);
+            }
         }
     }
 
@@ -195,6 +244,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.draw_img(img, rect);
             }
+            Renderer::Svg(v) => {
+                v.draw_img(img, rect);
+            }
         }
     }
 
@@ -211,6 +263,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.draw_svg(svg, rect, brush);
             }
+            Renderer::Svg(v) => {
+                v.draw_svg(svg, rect, brush);
+            }
         }
     }
 
@@ -222,6 +277,9 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.transform(transform);
             }
+            Renderer::Svg(v) => {
+                v.transform(transform);
+            }
         }
     }
 
@@ -233,6 +291,23 @@ impl floem_renderer::Renderer for Renderer {
             Renderer::TinySkia(v) => {
                 v.set_z_index(z_index);
             }
+            Renderer::Svg(v) => {
+                v.set_z_index(z_index);
+            }
+        }
+    }
+
+    fn set_text_rendering(&mut self, config: TextRendering) {
+        match self {
+            Renderer::Vger(v) => {
+                v.set_text_rendering(config);
+            }
+            Renderer::TinySkia(v) => {
+                v.set_text_rendering(config);
+            }
+            Renderer::Svg(v) => {
+                v.set_text_rendering(config);
+            }
         }
     }
 
@@ -240,6 +315,7 @@ impl floem_renderer::Renderer for Renderer {
         match self {
             Renderer::Vger(r) => r.finish(),
             Renderer::TinySkia(r) => r.finish(),
+            Renderer::Svg(r) => r.finish(),
         }
     }
 }
@@ -0,0 +1,683 @@
+//! Keyboard shortcuts bound in a window.
+//!
+//! A shortcut is written as keys joined by `+`, like `Ctrl+Shift+P`. A chord of several
+//! keystrokes is written with spaces between them, like `Ctrl+K Ctrl+S`. `Cmd` is the Command
+//! key on macOS and `Ctrl` on the other platforms, so `Cmd+S` saves on all of them.
+//!
+//! [`register_shortcut`] binds a shortcut in the whole window, and
+//! [`Id::register_shortcut`](crate::id::Id::register_shortcut) only while the focus is in a
+//! view, such as a [focus scope](crate::views::Decorators::focus_scope). The bindings of the
+//! innermost view containing the focus take precedence, and the ones of the window come last.
+//! Shortcuts are matched before the focused view gets the key event.
+//!
+//! A binding is removed with its view, or when the reactive scope it was registered in is
+//! disposed. [`shortcuts`] lists the bindings of the window, for example to show them in a
+//! command palette.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use floem_reactive::on_cleanup;
+use floem_winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::{id::Id, keyboard::KeyEvent, window_handle::get_current_view};
+
+/// The names of the keys which aren't characters. The first name of a key is the one displayed.
+const NAMED_KEYS: [(&str, NamedKey); 31] = [
+    ("Enter", NamedKey::Enter),
+    ("Tab", NamedKey::Tab),
+    ("Space", NamedKey::Space),
+    ("Backspace", NamedKey::Backspace),
+    ("Delete", NamedKey::Delete),
+    ("Del", NamedKey::Delete),
+    ("Insert", NamedKey::Insert),
+    ("Esc", NamedKey::Escape),
+    ("Escape", NamedKey::Escape),
+    ("Home", NamedKey::Home),
+    ("End", NamedKey::End),
+    ("PageUp", NamedKey::PageUp),
+    ("PageDown", NamedKey::PageDown),
+    ("Up", NamedKey::ArrowUp),
+    ("Down", NamedKey::ArrowDown),
+    ("Left", NamedKey::ArrowLeft),
+    ("Right", NamedKey::ArrowRight),
+    ("F1", NamedKey::F1),
+    ("F2", NamedKey::F2),
+    ("F3", NamedKey::F3),
+    ("F4", NamedKey::F4),
+    ("F5", NamedKey::F5),
+    ("F6", NamedKey::F6),
+    ("F7", NamedKey::F7),
+    ("F8", NamedKey::F8),
+    ("F9", NamedKey::F9),
+    ("F10", NamedKey::F10),
+    ("F11", NamedKey::F11),
+    ("F12", NamedKey::F12),
+    ("ContextMenu", NamedKey::ContextMenu),
+    ("PrintScreen", NamedKey::PrintScreen),
+];
+
+/// The modifier `Cmd` stands for: Command on macOS and Control on the other platforms.
+fn command_modifier() -> ModifiersState {
+    if cfg!(target_os = "macos") {
+        ModifiersState::SUPER
+    } else {
+        ModifiersState::CONTROL
+    }
+}
+
+fn parse_modifier(name: &str) -> Option<ModifiersState> {
+    let modifier = match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => ModifiersState::CONTROL,
+        "alt" | "option" | "opt" => ModifiersState::ALT,
+        "shift" => ModifiersState::SHIFT,
+        "cmd" | "command" | "cmdorctrl" | "mod" => command_modifier(),
+        "super" | "meta" | "win" => ModifiersState::SUPER,
+        _ => return None,
+    };
+    Some(modifier)
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Character(c.to_lowercase().collect::<String>().into()));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, key)| Key::Named(*key))
+}
+
+/// A key pressed with modifiers, like `Ctrl+Shift+P`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Keystroke {
+    pub modifiers: ModifiersState,
+    /// A named key, or a character in lowercase.
+    pub key: Key,
+}
+
+impl Keystroke {
+    /// The keystroke of a key press, or `None` for a modifier key.
+    pub fn from_event(event: &KeyEvent) -> Option<Keystroke> {
+        let key = match event.key_without_modifiers() {
+            Key::Named(
+                NamedKey::Shift
+                | NamedKey::Control
+                | NamedKey::Alt
+                | NamedKey::AltGraph
+                | NamedKey::Super
+                | NamedKey::Meta
+                | NamedKey::Hyper
+                | NamedKey::Fn
+                | NamedKey::CapsLock,
+            ) => return None,
+            Key::Named(key) => Key::Named(key),
+            Key::Character(c) => Key::Character(c.to_lowercase().into()),
+            _ => return None,
+        };
+        Some(Keystroke {
+            modifiers: event.modifiers,
+            key,
+        })
+    }
+}
+
+impl FromStr for Keystroke {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifier_names, key_name) = if s == "+" {
+            ("", s)
+        } else if let Some(modifier_names) = s.strip_suffix("++") {
+            (modifier_names, "+")
+        } else {
+            s.rsplit_once('+').unwrap_or(("", s))
+        };
+        let mut modifiers = ModifiersState::empty();
+        if !modifier_names.is_empty() {
+            for name in modifier_names.split('+') {
+                modifiers |= parse_modifier(name)
+                    .ok_or_else(|| ShortcutError::UnknownModifier(name.to_string()))?;
+            }
+        }
+        let key = parse_key(key_name).ok_or_else(|| ShortcutError::UnknownKey(key_name.into()))?;
+        Ok(Keystroke { modifiers, key })
+    }
+}
+
+impl fmt::Display for Keystroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mac = cfg!(target_os = "macos");
+        let modifiers = [
+            (ModifiersState::CONTROL, "Ctrl"),
+            (ModifiersState::ALT, if mac { "Option" } else { "Alt" }),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::SUPER, if mac { "Cmd" } else { "Super" }),
+        ];
+        for (modifier, name) in modifiers {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match &self.key {
+            Key::Character(c) => f.write_str(&c.to_uppercase()),
+            Key::Named(key) => match NAMED_KEYS.iter().find(|(_, named)| named == key) {
+                Some((name, _)) => f.write_str(name),
+                None => write!(f, "{key:?}"),
+            },
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+/// The keystrokes of a shortcut, one for a single key or several for a chord.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut(Vec<Keystroke>);
+
+impl Shortcut {
+    pub fn keystrokes(&self) -> &[Keystroke] {
+        &self.0
+    }
+
+    /// Whether one of the shortcuts is the same as the other or is the start of its chord, in
+    /// which case they can't be told apart while typing.
+    pub fn conflicts_with(&self, other: &Shortcut) -> bool {
+        self.0.starts_with(&other.0) || other.0.starts_with(&self.0)
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keystrokes = s
+            .split_whitespace()
+            .map(Keystroke::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keystrokes.is_empty() {
+            return Err(ShortcutError::Empty);
+        }
+        Ok(Shortcut(keystrokes))
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, keystroke) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{keystroke}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a shortcut couldn't be registered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShortcutError {
+    /// The shortcut has no keys.
+    Empty,
+    UnknownModifier(String),
+    UnknownKey(String),
+    /// The shortcut conflicts with `existing`, which is bound for the same view. See
+    /// [`Shortcut::conflicts_with`].
+    Conflict {
+        shortcut: Shortcut,
+        existing: Shortcut,
+    },
+}
+
+impl fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutError::Empty => f.write_str("the shortcut has no keys"),
+            ShortcutError::UnknownModifier(name) => write!(f, "unknown modifier `{name}`"),
+            ShortcutError::UnknownKey(name) => write!(f, "unknown key `{name}`"),
+            ShortcutError::Conflict { shortcut, existing } => {
+                write!(f, "`{shortcut}` conflicts with `{existing}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError {}
+
+struct Binding {
+    id: u64,
+    shortcut: Shortcut,
+    /// The view the focus must be in, or `None` for the whole window.
+    scope: Option<Id>,
+    name: Option<String>,
+    action: Rc<dyn Fn()>,
+}
+
+/// What a keystroke did.
+enum Matched {
+    Unmatched,
+    /// The keystroke started or continued a chord, or ended one which isn't bound.
+    Consumed,
+    Run(Rc<dyn Fn()>),
+}
+
+#[derive(Default)]
+struct WindowShortcuts {
+    bindings: Vec<Binding>,
+    /// The keystrokes of the chord being typed.
+    pending: Vec<Keystroke>,
+}
+
+impl WindowShortcuts {
+    fn add(
+        &mut self,
+        scope: Option<Id>,
+        shortcut: Shortcut,
+        action: Rc<dyn Fn()>,
+    ) -> Result<u64, ShortcutError> {
+        self.check_conflicts(scope, &shortcut, None)?;
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.bindings.push(Binding {
+            id,
+            shortcut,
+            scope,
+            name: None,
+            action,
+        });
+        Ok(id)
+    }
+
+    /// Fails if `shortcut` conflicts with a binding for `scope` other than the binding `except`.
+    fn check_conflicts(
+        &self,
+        scope: Option<Id>,
+        shortcut: &Shortcut,
+        except: Option<u64>,
+    ) -> Result<(), ShortcutError> {
+        match self.bindings.iter().find(|binding| {
+            Some(binding.id) != except
+                && binding.scope == scope
+                && binding.shortcut.conflicts_with(shortcut)
+        }) {
+            Some(existing) => Err(ShortcutError::Conflict {
+                shortcut: shortcut.clone(),
+                existing: existing.shortcut.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The bindings which apply while the focus is in the views of `focus_path`, the ones
+    /// taking precedence first. Bindings hidden by one of an inner view are left out.
+    fn active(&self, focus_path: &[Id]) -> Vec<&Binding> {
+        let scopes = focus_path.iter().rev().map(|id| Some(*id)).chain([None]);
+        let mut active: Vec<&Binding> = Vec::new();
+        for scope in scopes {
+            let start = active.len();
+            for binding in self
+                .bindings
+                .iter()
+                .filter(|binding| binding.scope == scope)
+            {
+                if !active[..start]
+                    .iter()
+                    .any(|inner| inner.shortcut.conflicts_with(&binding.shortcut))
+                {
+                    active.push(binding);
+                }
+            }
+        }
+        active
+    }
+
+    fn keystroke(&mut self, keystroke: Keystroke, focus_path: &[Id]) -> Matched {
+        let chord = !self.pending.is_empty();
+        let mut keys = std::mem::take(&mut self.pending);
+        keys.push(keystroke);
+
+        let mut pending = false;
+        for binding in self.active(focus_path) {
+            if binding.shortcut.0 == keys {
+                return Matched::Run(binding.action.clone());
+            }
+            pending |= binding.shortcut.0.starts_with(&keys);
+        }
+        if pending {
+            self.pending = keys;
+            Matched::Consumed
+        } else if chord {
+            // The rest of a chord which isn't bound doesn't reach the views either
+            Matched::Consumed
+        } else {
+            Matched::Unmatched
+        }
+    }
+}
+
+thread_local! {
+    /// The shortcuts of the windows, by the id of their root view.
+    static SHORTCUTS: RefCell<HashMap<Id, WindowShortcuts>> = Default::default();
+}
+
+/// A binding registered with [`register_shortcut`] or
+/// [`Id::register_shortcut`](crate::id::Id::register_shortcut).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShortcutHandle {
+    window: Id,
+    id: u64,
+}
+
+impl ShortcutHandle {
+    fn with_binding<T>(&self, f: impl FnOnce(&mut Binding) -> T) -> Option<T> {
+        SHORTCUTS.with(|shortcuts| {
+            shortcuts
+                .borrow_mut()
+                .get_mut(&self.window)?
+                .bindings
+                .iter_mut()
+                .find(|binding| binding.id == self.id)
+                .map(f)
+        })
+    }
+
+    /// Names the command the binding runs, for example to list it in a command palette.
+    pub fn name(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.with_binding(|binding| binding.name = Some(name));
+        self
+    }
+
+    /// The keys of the binding, or `None` once it's unregistered.
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        self.with_binding(|binding| binding.shortcut.clone())
+    }
+
+    /// Binds the action to other keys, such as the ones a user chose in a keymap editor.
+    /// The menu items bound to the shortcut show the new keys once they're built again.
+    ///
+    /// Fails if the shortcut can't be parsed or conflicts with another one bound for the same
+    /// view, keeping the old keys.
+    pub fn rebind(&self, shortcut: &str) -> Result<(), ShortcutError> {
+        let shortcut: Shortcut = shortcut.parse()?;
+        SHORTCUTS.with(|shortcuts| {
+            let mut shortcuts = shortcuts.borrow_mut();
+            let Some(window) = shortcuts.get_mut(&self.window) else {
+                return Ok(());
+            };
+            let Some(scope) = window
+                .bindings
+                .iter()
+                .find(|binding| binding.id == self.id)
+                .map(|binding| binding.scope)
+            else {
+                return Ok(());
+            };
+            window.check_conflicts(scope, &shortcut, Some(self.id))?;
+            // A chord typed with the old keys doesn't go on with the new ones
+            window.pending.clear();
+            if let Some(binding) = window
+                .bindings
+                .iter_mut()
+                .find(|binding| binding.id == self.id)
+            {
+                binding.shortcut = shortcut;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs the action of the binding, as if its shortcut was pressed.
+    pub fn run(&self) {
+        if let Some(action) = self.with_binding(|binding| binding.action.clone()) {
+            action();
+        }
+    }
+
+    pub fn unregister(self) {
+        SHORTCUTS.with(|shortcuts| {
+            if let Some(window) = shortcuts.borrow_mut().get_mut(&self.window) {
+                window.bindings.retain(|binding| binding.id != self.id);
+            }
+        });
+    }
+}
+
+pub(crate) fn register(
+    window: Id,
+    scope: Option<Id>,
+    shortcut: &str,
+    action: impl Fn() + 'static,
+) -> Result<ShortcutHandle, ShortcutError> {
+    let shortcut = shortcut.parse()?;
+    let id = SHORTCUTS.with(|shortcuts| {
+        shortcuts
+            .borrow_mut()
+            .entry(window)
+            .or_default()
+            .add(scope, shortcut, Rc::new(action))
+    })?;
+    let handle = ShortcutHandle { window, id };
+    on_cleanup(move || handle.unregister());
+    Ok(handle)
+}
+
+/// Runs `action` when `shortcut` is pressed in the current window, like `Ctrl+Shift+P` or the
+/// chord `Ctrl+K Ctrl+S`.
+///
+/// Fails if the shortcut can't be parsed or conflicts with another one bound in the whole
+/// window.
+pub fn register_shortcut(
+    shortcut: &str,
+    action: impl Fn() + 'static,
+) -> Result<ShortcutHandle, ShortcutError> {
+    register(get_current_view(), None, shortcut, action)
+}
+
+/// A shortcut bound in a window.
+#[derive(Clone, Debug)]
+pub struct ShortcutInfo {
+    pub handle: ShortcutHandle,
+    pub shortcut: Shortcut,
+    /// The name set with [`ShortcutHandle::name`].
+    pub name: Option<String>,
+    /// The view the focus must be in for the shortcut to apply, or `None` for the whole window.
+    pub scope: Option<Id>,
+}
+
+fn window_shortcuts(f: impl FnOnce(&WindowShortcuts) -> Vec<ShortcutInfo>) -> Vec<ShortcutInfo> {
+    let window = get_current_view();
+    SHORTCUTS.with(|shortcuts| shortcuts.borrow().get(&window).map(f).unwrap_or_default())
+}
+
+fn info(window: Id, binding: &Binding) -> ShortcutInfo {
+    ShortcutInfo {
+        handle: ShortcutHandle {
+            window,
+            id: binding.id,
+        },
+        shortcut: binding.shortcut.clone(),
+        name: binding.name.clone(),
+        scope: binding.scope,
+    }
+}
+
+/// The shortcuts bound in the current window, in the order they were registered.
+pub fn shortcuts() -> Vec<ShortcutInfo> {
+    let window = get_current_view();
+    window_shortcuts(|shortcuts| {
+        let bindings = shortcuts.bindings.iter();
+        bindings.map(|binding| info(window, binding)).collect()
+    })
+}
+
+/// The shortcuts which apply while the focus is on the view `focus`, the ones taking
+/// precedence first.
+pub fn active_shortcuts(focus: Option<Id>) -> Vec<ShortcutInfo> {
+    let window = get_current_view();
+    let focus_path = focus
+        .and_then(|id| id.id_path())
+        .map(|path| path.0)
+        .unwrap_or_default();
+    window_shortcuts(|shortcuts| {
+        let bindings = shortcuts.active(&focus_path).into_iter();
+        bindings.map(|binding| info(window, binding)).collect()
+    })
+}
+
+/// Runs the shortcut completed by the key press `event` in the window `root`, while the focus
+/// is on the view `focus`. Returns `true` if the key press was part of a shortcut.
+pub(crate) fn dispatch(root: Id, focus: Option<Id>, event: &KeyEvent) -> bool {
+    let Some(keystroke) = Keystroke::from_event(event) else {
+        return false;
+    };
+    let focus_path = focus
+        .and_then(|id| id.id_path())
+        .map(|path| path.0)
+        .unwrap_or_default();
+    let matched = SHORTCUTS.with(|shortcuts| {
+        let mut shortcuts = shortcuts.borrow_mut();
+        let window = shortcuts.get_mut(&root)?;
+        Some(window.keystroke(keystroke, &focus_path))
+    });
+    match matched {
+        None | Some(Matched::Unmatched) => false,
+        Some(Matched::Consumed) => true,
+        Some(Matched::Run(action)) => {
+            action();
+            true
+        }
+    }
+}
+
+/// Removes the bindings of the view `id`, which was removed.
+pub(crate) fn view_removed(id: Id) {
+    SHORTCUTS.with(|shortcuts| {
+        for window in shortcuts.borrow_mut().values_mut() {
+            window.bindings.retain(|binding| binding.scope != Some(id));
+        }
+    });
+}
+
+pub(crate) fn remove_window_shortcuts(root: Id) {
+    SHORTCUTS.with(|shortcuts| shortcuts.borrow_mut().remove(&root));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use floem_winit::keyboard::{Key, ModifiersState, NamedKey};
+
+    use super::{register, Keystroke, Matched, Shortcut, ShortcutError, WindowShortcuts};
+    use crate::{id::Id, menu::MenuItem};
+
+    fn keystroke(s: &str) -> Keystroke {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_shortcuts() {
+        let ctrl_shift = ModifiersState::CONTROL | ModifiersState::SHIFT;
+        assert_eq!(
+            keystroke("Ctrl+Shift+P"),
+            Keystroke {
+                modifiers: ctrl_shift,
+                key: Key::Character("p".into()),
+            }
+        );
+        assert_eq!(keystroke("ctrl++").key, Key::Character("+".into()));
+        assert_eq!(keystroke("Esc").key, Key::Named(NamedKey::Escape));
+        assert_eq!(keystroke("Shift+PageDown").to_string(), "Shift+PageDown");
+
+        let chord: Shortcut = "Ctrl+K  Ctrl+S".parse().unwrap();
+        assert_eq!(chord.keystrokes().len(), 2);
+        assert_eq!(chord.to_string(), "Ctrl+K Ctrl+S");
+
+        assert_eq!(" ".parse::<Shortcut>(), Err(ShortcutError::Empty));
+        assert_eq!(
+            "Hyper+A".parse::<Shortcut>(),
+            Err(ShortcutError::UnknownModifier("Hyper".into()))
+        );
+        assert_eq!(
+            "Ctrl+Foo".parse::<Shortcut>(),
+            Err(ShortcutError::UnknownKey("Foo".into()))
+        );
+    }
+
+    #[test]
+    fn chords_and_scopes() {
+        let count = Rc::new(Cell::new(0));
+        let action = |n: usize| {
+            let count = count.clone();
+            Rc::new(move || count.set(count.get() + n)) as Rc<dyn Fn()>
+        };
+        let scope = Id::next();
+        let mut shortcuts = WindowShortcuts::default();
+        shortcuts
+            .add(None, "Ctrl+K Ctrl+S".parse().unwrap(), action(1))
+            .unwrap();
+        shortcuts
+            .add(Some(scope), "Ctrl+K".parse().unwrap(), action(10))
+            .unwrap();
+        assert!(matches!(
+            shortcuts.add(None, "Ctrl+K".parse().unwrap(), action(100)),
+            Err(ShortcutError::Conflict { .. })
+        ));
+
+        let mut press =
+            |s: &str, focus_path: &[Id]| match shortcuts.keystroke(keystroke(s), focus_path) {
+                Matched::Unmatched => "unmatched",
+                Matched::Consumed => "consumed",
+                Matched::Run(action) => {
+                    action();
+                    "run"
+                }
+            };
+        assert_eq!(press("Ctrl+K", &[]), "consumed");
+        assert_eq!(press("Ctrl+S", &[]), "run");
+        assert_eq!(count.get(), 1);
+
+        // The binding of the focused view hides the chord of the window
+        assert_eq!(press("Ctrl+K", &[scope]), "run");
+        assert_eq!(count.get(), 11);
+
+        assert_eq!(press("Ctrl+K", &[]), "consumed");
+        assert_eq!(press("Ctrl+X", &[]), "consumed");
+        assert_eq!(press("Ctrl+S", &[]), "unmatched");
+        assert_eq!(count.get(), 11);
+    }
+
+    #[test]
+    fn menu_items_follow_bindings() {
+        let saved = Rc::new(Cell::new(0));
+        let handle = register(Id::next(), None, "Ctrl+Shift+S", {
+            let saved = saved.clone();
+            move || saved.set(saved.get() + 1)
+        })
+        .unwrap();
+
+        let item = MenuItem::new("Save As").shortcut(handle);
+        assert_eq!(item.shortcut.as_ref().unwrap().to_string(), "Ctrl+Shift+S");
+        (item.action.unwrap())();
+        assert_eq!(saved.get(), 1);
+
+        // An item of its own action keeps it
+        let item = MenuItem::new("Other").action(|| {}).shortcut(handle);
+        (item.action.unwrap())();
+        assert_eq!(saved.get(), 1);
+
+        handle.rebind("Ctrl+Alt+S").unwrap();
+        let item = MenuItem::new("Save As").shortcut(handle);
+        assert_eq!(item.shortcut.unwrap().to_string(), "Ctrl+Alt+S");
+
+        handle.unregister();
+        assert!(MenuItem::new("Save As").shortcut(handle).shortcut.is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Forwards the arguments of a second launch of an app to its running instance.
+//!
+//! The running instance listens on a Unix socket in the runtime directory, or on a loopback
+//! TCP port written to a file in the temporary directory on Windows. The arguments are sent
+//! separated by NUL characters, which can't appear in them.
+
+use std::io::{Read, Write};
+
+fn encode_args(args: &[String]) -> Vec<u8> {
+    args.join("\0").into_bytes()
+}
+
+fn decode_args(data: &[u8]) -> Vec<String> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(data)
+        .split('\0')
+        .map(str::to_string)
+        .collect()
+}
+
+/// The name of the socket or port file, keeping only characters valid in file names.
+fn file_name(app_id: &str, extension: &str) -> String {
+    let app_id: String = app_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{app_id}.{extension}")
+}
+
+fn read_args(mut stream: impl Read) -> Option<Vec<String>> {
+    let mut data = Vec::new();
+    stream.read_to_end(&mut data).ok()?;
+    Some(decode_args(&data))
+}
+
+#[cfg(unix)]
+fn socket_path(app_id: &str) -> std::path::PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    dir.join(file_name(app_id, "sock"))
+}
+
+/// Sends `args` to the running instance of `app_id`, returning false if there's none.
+#[cfg(unix)]
+pub(crate) fn forward(app_id: &str, args: &[String]) -> bool {
+    let Ok(mut stream) = std::os::unix::net::UnixStream::connect(socket_path(app_id)) else {
+        return false;
+    };
+    stream.write_all(&encode_args(args)).is_ok()
+}
+
+/// Listens for the arguments of later launches of `app_id` on a background thread.
+#[cfg(unix)]
+pub(crate) fn listen(app_id: &str, on_args: impl Fn(Vec<String>) + Send + 'static) {
+    let path = socket_path(app_id);
+    // The socket is left behind when an instance crashes, and nothing answered on it
+    let _ = std::fs::remove_file(&path);
+    let Ok(listener) = std::os::unix::net::UnixListener::bind(&path) else {
+        return;
+    };
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Some(args) = read_args(stream) {
+                on_args(args);
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn port_path(app_id: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(file_name(app_id, "port"))
+}
+
+/// Sends `args` to the running instance of `app_id`, returning false if there's none.
+#[cfg(not(unix))]
+pub(crate) fn forward(app_id: &str, args: &[String]) -> bool {
+    let Some(port) = std::fs::read_to_string(port_path(app_id))
+        .ok()
+        .and_then(|port| port.trim().parse::<u16>().ok())
+    else {
+        return false;
+    };
+    let address = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) =
+        std::net::TcpStream::connect_timeout(&address, std::time::Duration::from_secs(1))
+    else {
+        return false;
+    };
+    stream.write_all(&encode_args(args)).is_ok()
+}
+
+/// Listens for the arguments of later launches of `app_id` on a background thread.
+#[cfg(not(unix))]
+pub(crate) fn listen(app_id: &str, on_args: impl Fn(Vec<String>) + Send + 'static) {
+    let Ok(listener) = std::net::TcpListener::bind(("127.0.0.1", 0)) else {
+        return;
+    };
+    let Ok(address) = listener.local_addr() else {
+        return;
+    };
+    if std::fs::write(port_path(app_id), address.port().to_string()).is_err() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Some(args) = read_args(stream) {
+                on_args(args);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_round_trip() {
+        let args = vec![
+            "--open".to_string(),
+            "a file.txt".to_string(),
+            String::new(),
+        ];
+        assert_eq!(decode_args(&encode_args(&args)), args);
+        assert_eq!(decode_args(&encode_args(&[])), Vec::<String>::new());
+    }
+}
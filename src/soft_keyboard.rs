@@ -0,0 +1,87 @@
+//! The on-screen keyboard of touch devices.
+//!
+//! Each window has a [`SoftKeyboard`] with signals tracking whether the keyboard is shown and
+//! which part of the window it covers, which views can use to move content out of its way.
+//! [`Scroll::keep_focus_above_keyboard`](crate::views::Scroll::keep_focus_above_keyboard) does
+//! this for the focused view.
+//!
+//! The windowing backend doesn't report the frame of the keyboard, so it is set by the
+//! platform integration with
+//! [`set_soft_keyboard_insets`](crate::action::set_soft_keyboard_insets). On Android and iOS, enabling IME
+//! input with [`set_ime_allowed`](crate::action::set_ime_allowed) also marks the keyboard as
+//! shown.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use floem_reactive::{create_rw_signal, ReadSignal, RwSignal};
+use kurbo::Insets;
+
+use crate::{id::Id, update::CURRENT_RUNNING_VIEW_HANDLE};
+
+thread_local! {
+    /// The keyboards of the windows, by the id of their root view.
+    static SOFT_KEYBOARDS: RefCell<HashMap<Id, SoftKeyboardSignals>> = Default::default();
+}
+
+/// The state of the on-screen keyboard of a window. See [`soft_keyboard`]
+#[derive(Clone, Copy)]
+pub struct SoftKeyboard {
+    /// Whether the keyboard is shown.
+    pub visible: ReadSignal<bool>,
+    /// The distances from the edges of the window the keyboard covers, in logical pixels.
+    /// Keyboards usually only cover the bottom of the window.
+    pub insets: ReadSignal<Insets>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct SoftKeyboardSignals {
+    pub(crate) visible: RwSignal<bool>,
+    pub(crate) insets: RwSignal<Insets>,
+}
+
+impl SoftKeyboardSignals {
+    /// Updates the keyboard after the platform reported the part of the window it covers.
+    pub(crate) fn set_insets(&self, insets: Insets) {
+        self.insets.set(insets);
+        self.visible.set(insets != Insets::ZERO);
+    }
+
+    /// Updates the keyboard after IME input was enabled or disabled, which shows or hides the
+    /// keyboard on platforms without a physical one.
+    pub(crate) fn set_ime_allowed(&self, allowed: bool) {
+        if cfg!(any(target_os = "android", target_os = "ios")) {
+            self.visible.set(allowed);
+            if !allowed {
+                self.insets.set(Insets::ZERO);
+            }
+        }
+    }
+}
+
+/// Returns the signals of the keyboard of the window `root` belongs to, creating them in the
+/// current scope the first time.
+pub(crate) fn window_soft_keyboard(root: Id) -> SoftKeyboardSignals {
+    SOFT_KEYBOARDS.with(|keyboards| {
+        *keyboards
+            .borrow_mut()
+            .entry(root)
+            .or_insert_with(|| SoftKeyboardSignals {
+                visible: create_rw_signal(false),
+                insets: create_rw_signal(Insets::ZERO),
+            })
+    })
+}
+
+pub(crate) fn remove_window_soft_keyboard(root: Id) {
+    SOFT_KEYBOARDS.with(|keyboards| keyboards.borrow_mut().remove(&root));
+}
+
+/// The on-screen keyboard of the current window.
+pub fn soft_keyboard() -> SoftKeyboard {
+    let root = CURRENT_RUNNING_VIEW_HANDLE.with(|root| *root.borrow());
+    let keyboard = window_soft_keyboard(root);
+    SoftKeyboard {
+        visible: keyboard.visible.read_only(),
+        insets: keyboard.insets.read_only(),
+    }
+}
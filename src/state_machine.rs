@@ -0,0 +1,215 @@
+//! State machines driving the style of interactive views.
+//!
+//! A [`StateMachine`] holds one of a set of user defined states and moves between them on
+//! pointer and keyboard input, custom triggers, or after some time in a state. It's bound to
+//! a view with [`Decorators::style_machine`](crate::views::Decorators::style_machine), which
+//! feeds it the view's input and styles the view by its state.
+//!
+//! ```ignore
+//! #[derive(Clone, PartialEq)]
+//! enum Press {
+//!     Idle,
+//!     Pressed,
+//!     Released,
+//! }
+//!
+//! let machine = StateMachine::new(Press::Idle)
+//!     .on(Press::Idle, StateTrigger::PointerDown, Press::Pressed)
+//!     .on(Press::Pressed, StateTrigger::PointerUp, Press::Released)
+//!     // The released style lingers before going back to idle
+//!     .after(Press::Released, Duration::from_millis(300), Press::Idle);
+//!
+//! label(|| "Press me").style_machine(&machine, |s, state| match state {
+//!     Press::Idle => s,
+//!     Press::Pressed => s.background(Color::DARK_GRAY),
+//!     Press::Released => s.background(Color::LIGHT_GRAY),
+//! })
+//! ```
+//!
+//! The style properties animate between the states with
+//! [`Style::transition`](crate::style::Style::transition).
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use floem_reactive::{create_rw_signal, ReadSignal, RwSignal};
+
+use crate::{action::exec_after, event::Event};
+
+/// What moves a [`StateMachine`] from one state to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateTrigger {
+    PointerDown,
+    PointerUp,
+    /// The pointer moved onto the view.
+    PointerEnter,
+    PointerLeave,
+    KeyDown,
+    KeyUp,
+    /// A trigger sent with [`StateMachine::fire`].
+    Custom(&'static str),
+}
+
+enum On {
+    Trigger(StateTrigger),
+    After(Duration),
+}
+
+struct Rule<S> {
+    /// `None` applies the rule in any state.
+    from: Option<S>,
+    on: On,
+    to: S,
+}
+
+/// A set of states and the transitions between them. See the [module docs](self)
+pub struct StateMachine<S: 'static> {
+    state: RwSignal<S>,
+    rules: Rc<RefCell<Vec<Rule<S>>>>,
+    /// Counts the states entered, so timers only fire in the state they were started for.
+    entered: Rc<Cell<u64>>,
+    hovered: Rc<Cell<bool>>,
+}
+
+impl<S> Clone for StateMachine<S> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state,
+            rules: self.rules.clone(),
+            entered: self.entered.clone(),
+            hovered: self.hovered.clone(),
+        }
+    }
+}
+
+impl<S: Clone + PartialEq + 'static> StateMachine<S> {
+    pub fn new(initial: S) -> Self {
+        Self {
+            state: create_rw_signal(initial),
+            rules: Default::default(),
+            entered: Default::default(),
+            hovered: Default::default(),
+        }
+    }
+
+    /// Moves from `from` to `to` on `trigger`. Rules are checked in the order they're added,
+    /// and the first one matching the state and trigger is used.
+    pub fn on(self, from: S, trigger: StateTrigger, to: S) -> Self {
+        self.rule(Some(from), On::Trigger(trigger), to)
+    }
+
+    /// Moves to `to` on `trigger` from any state.
+    pub fn on_any(self, trigger: StateTrigger, to: S) -> Self {
+        self.rule(None, On::Trigger(trigger), to)
+    }
+
+    /// Moves from `from` to `to` once the machine has been in `from` for `duration`.
+    pub fn after(self, from: S, duration: Duration, to: S) -> Self {
+        self.rule(Some(from), On::After(duration), to)
+    }
+
+    fn rule(self, from: Option<S>, on: On, to: S) -> Self {
+        self.rules.borrow_mut().push(Rule { from, on, to });
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> ReadSignal<S> {
+        self.state.read_only()
+    }
+
+    /// Moves to `state` directly, without checking the transitions.
+    pub fn set(&self, state: S) {
+        self.enter(state);
+    }
+
+    /// Applies the first transition for `trigger` from the current state, returning false if
+    /// there's none.
+    pub fn fire(&self, trigger: StateTrigger) -> bool {
+        let to = self.state.with_untracked(|state| {
+            self.rules.borrow().iter().find_map(|rule| {
+                let applies = rule.from.as_ref().map_or(true, |from| from == state)
+                    && matches!(&rule.on, On::Trigger(on) if *on == trigger);
+                applies.then(|| rule.to.clone())
+            })
+        });
+        match to {
+            Some(to) => {
+                self.enter(to);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn enter(&self, state: S) {
+        self.state.set(state);
+        self.entered.set(self.entered.get() + 1);
+        self.start_timers();
+    }
+
+    /// Starts the timed transitions from the current state.
+    pub(crate) fn start_timers(&self) {
+        let entered = self.entered.get();
+        let timers: Vec<_> = self.state.with_untracked(|state| {
+            self.rules
+                .borrow()
+                .iter()
+                .filter_map(|rule| match rule.on {
+                    On::After(duration) if rule.from.as_ref() == Some(state) => {
+                        Some((duration, rule.to.clone()))
+                    }
+                    _ => None,
+                })
+                .collect()
+        });
+        for (duration, to) in timers {
+            let machine = self.clone();
+            exec_after(duration, move |_| {
+                if machine.entered.get() == entered {
+                    machine.enter(to);
+                }
+            });
+        }
+    }
+
+    /// Fires the trigger matching an event received by the view the machine is bound to.
+    pub(crate) fn handle_event(&self, event: &Event) {
+        let trigger = match event {
+            Event::PointerDown(_) => StateTrigger::PointerDown,
+            Event::PointerUp(_) => StateTrigger::PointerUp,
+            // The view only receives pointer moves while the pointer is over it
+            Event::PointerMove(_) if !self.hovered.replace(true) => StateTrigger::PointerEnter,
+            Event::PointerLeave => {
+                self.hovered.set(false);
+                StateTrigger::PointerLeave
+            }
+            Event::KeyDown(_) => StateTrigger::KeyDown,
+            Event::KeyUp(_) => StateTrigger::KeyUp,
+            _ => return,
+        };
+        self.fire(trigger);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transitions() {
+        let machine = StateMachine::new(0)
+            .on(0, StateTrigger::PointerDown, 1)
+            .on(1, StateTrigger::PointerUp, 2)
+            .on_any(StateTrigger::Custom("reset"), 0);
+        assert!(!machine.fire(StateTrigger::PointerUp));
+        assert!(machine.fire(StateTrigger::PointerDown));
+        assert!(machine.fire(StateTrigger::PointerUp));
+        assert_eq!(machine.state().get_untracked(), 2);
+        assert!(machine.fire(StateTrigger::Custom("reset")));
+        assert_eq!(machine.state().get_untracked(), 0);
+    }
+}
@@ -28,8 +28,12 @@
 use floem_peniko::Color;
 use floem_renderer::cosmic_text;
 use floem_renderer::cosmic_text::{LineHeightValue, Weight};
+use floem_renderer::{color, Renderer, TextRendering};
 use im_rc::hashmap::Entry;
+use image::{DynamicImage, GenericImageView};
+use kurbo::Insets;
 use rustc_hash::FxHasher;
+use sha2::{Digest, Sha256};
 use std::any::{type_name, Any};
 use std::collections::HashMap;
 use std::fmt::{self, Debug};
@@ -50,11 +54,12 @@ use taffy::{
     },
 };
 
-use crate::context::InteractionState;
+use crate::context::{InteractionState, PaintCx};
+use crate::intern::Atom;
 use crate::responsive::{ScreenSize, ScreenSizeBp};
 use crate::unit::{Px, PxPct, PxPctAuto, UnitExt};
 use crate::view::View;
-use crate::views::{empty, stack, text, Decorators};
+use crate::views::{empty, stack, text, Decorators, NineSlice};
 
 pub trait StylePropValue: Clone + PartialEq + Debug {
     fn debug_view(&self) -> Option<Box<dyn View>> {
@@ -88,11 +93,18 @@ impl<T: StylePropValue> StylePropValue for Line<T> {}
 impl StylePropValue for GridPlacement {}
 impl StylePropValue for CursorStyle {}
 impl StylePropValue for BoxShadow {}
+impl StylePropValue for TextShadow {}
+impl StylePropValue for BackgroundImage {}
+impl StylePropValue for BackgroundPattern {}
 impl StylePropValue for String {}
+impl StylePropValue for Atom {}
 impl StylePropValue for Weight {}
 impl StylePropValue for cosmic_text::Style {}
 impl StylePropValue for TextOverflow {}
+impl StylePropValue for Overflow {}
 impl StylePropValue for LineHeightValue {}
+impl StylePropValue for TextRendering {}
+impl StylePropValue for FontVariation {}
 impl StylePropValue for Size<LengthPercentage> {}
 
 impl<T: StylePropValue> StylePropValue for Option<T> {
@@ -134,6 +146,14 @@ impl StylePropValue for PxPctAuto {
         };
         Some(Box::new(text(label)))
     }
+
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Px(v1), Self::Px(v2)) => Some(Self::Px(v1.interpolate(v2, value)?)),
+            (Self::Pct(v1), Self::Pct(v2)) => Some(Self::Pct(v1.interpolate(v2, value)?)),
+            _ => None,
+        }
+    }
 }
 impl StylePropValue for PxPct {
     fn debug_view(&self) -> Option<Box<dyn View>> {
@@ -143,6 +163,14 @@ impl StylePropValue for PxPct {
         };
         Some(Box::new(text(label)))
     }
+
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Px(v1), Self::Px(v2)) => Some(Self::Px(v1.interpolate(v2, value)?)),
+            (Self::Pct(v1), Self::Pct(v2)) => Some(Self::Pct(v1.interpolate(v2, value)?)),
+            _ => None,
+        }
+    }
 }
 impl StylePropValue for Color {
     fn debug_view(&self) -> Option<Box<dyn View>> {
@@ -167,19 +195,7 @@ impl StylePropValue for Color {
     }
 
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
-        let r = f64::interpolate(&(self.r as f64), &(other.r as f64), value)
-            .unwrap()
-            .round() as u8;
-        let g = f64::interpolate(&(self.g as f64), &(other.g as f64), value)
-            .unwrap()
-            .round() as u8;
-        let b = f64::interpolate(&(self.b as f64), &(other.b as f64), value)
-            .unwrap()
-            .round() as u8;
-        let a = f64::interpolate(&(self.a as f64), &(other.a as f64), value)
-            .unwrap()
-            .round() as u8;
-        Some(Color { r, g, b, a })
+        Some(color::mix_linear(*self, *other, value))
     }
 }
 
@@ -248,6 +264,8 @@ pub struct StylePropInfo {
     pub(crate) default_as_any: fn() -> Rc<dyn Any>,
     pub(crate) debug_any: fn(val: &dyn Any) -> String,
     pub(crate) debug_view: fn(val: &dyn Any) -> Option<Box<dyn View>>,
+    /// Interpolates between two values of the property, `None` if its type can't be.
+    pub(crate) interpolate: fn(from: &dyn Any, to: &dyn Any, time: f64) -> Option<Rc<dyn Any>>,
 }
 
 impl StylePropInfo {
@@ -281,6 +299,12 @@ impl StylePropInfo {
                     )
                 }
             },
+            interpolate: |from, to, time| {
+                let from = from.downcast_ref::<T>()?;
+                let to = to.downcast_ref::<T>()?;
+                from.interpolate(to, time)
+                    .map(|v| Rc::new(v) as Rc<dyn Any>)
+            },
         }
     }
 }
@@ -727,6 +751,12 @@ impl Style {
                 self.apply_mut(map);
             }
         }
+        if interact_state.is_invalid {
+            if let Some(mut map) = self.selectors.remove(&StyleSelector::Invalid) {
+                map.apply_interact_state(interact_state, screen_size_bp);
+                self.apply_mut(map);
+            }
+        }
 
         let focused_keyboard =
             interact_state.using_keyboard_navigation && interact_state.is_focused;
@@ -866,6 +896,7 @@ pub enum StyleSelector {
     Active,
     Dragging,
     Selected,
+    Invalid,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
@@ -897,6 +928,73 @@ impl StyleSelectors {
     }
 }
 
+/// A position on an axis of a variable font, such as `wght` for the weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontVariation {
+    pub tag: [u8; 4],
+    pub value: f32,
+}
+
+impl FontVariation {
+    pub const fn new(tag: &[u8; 4], value: f32) -> Self {
+        Self { tag: *tag, value }
+    }
+}
+
+/// Applies the registered axes of `variations` to the font matching of `attrs`: `wght` sets the
+/// weight, `wdth` the width in percent, and `slnt` and `ital` select an oblique or italic face.
+///
+/// The text shaper can't set the axes of a variable font, so this only approximates them by
+/// picking the closest face: the weight is exact only for the weights the font provides, the
+/// width is rounded to the nearest of the nine standard widths, and `slnt` and `ital` are on or
+/// off rather than an angle. Other axes are ignored.
+pub(crate) fn apply_font_variations<'a>(
+    mut attrs: cosmic_text::Attrs<'a>,
+    variations: &[FontVariation],
+) -> cosmic_text::Attrs<'a> {
+    use cosmic_text::Stretch;
+
+    for variation in variations {
+        match &variation.tag {
+            b"wght" => attrs = attrs.weight(Weight(variation.value.clamp(1.0, 1000.0) as u16)),
+            b"wdth" => {
+                const WIDTHS: [(f32, Stretch); 9] = [
+                    (50.0, Stretch::UltraCondensed),
+                    (62.5, Stretch::ExtraCondensed),
+                    (75.0, Stretch::Condensed),
+                    (87.5, Stretch::SemiCondensed),
+                    (100.0, Stretch::Normal),
+                    (112.5, Stretch::SemiExpanded),
+                    (125.0, Stretch::Expanded),
+                    (150.0, Stretch::ExtraExpanded),
+                    (200.0, Stretch::UltraExpanded),
+                ];
+                let stretch = WIDTHS
+                    .iter()
+                    .min_by(|a, b| {
+                        (a.0 - variation.value)
+                            .abs()
+                            .total_cmp(&(b.0 - variation.value).abs())
+                    })
+                    .map(|(_, stretch)| *stretch)
+                    .unwrap_or(Stretch::Normal);
+                attrs = attrs.stretch(stretch);
+            }
+            b"slnt" if variation.value != 0.0 => attrs = attrs.style(cosmic_text::Style::Oblique),
+            b"ital" if variation.value >= 0.5 => attrs = attrs.style(cosmic_text::Style::Italic),
+            _ => {}
+        }
+    }
+    attrs
+}
+
+/// Sets `value` as the entry of `list` with the same tag.
+fn set_tagged<T>(mut list: Vec<T>, value: T, tag: impl Fn(&T) -> [u8; 4]) -> Vec<T> {
+    list.retain(|item| tag(item) != tag(&value));
+    list.push(value);
+    list
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextOverflow {
     Wrap,
@@ -904,11 +1002,24 @@ pub enum TextOverflow {
     Ellipsis,
 }
 
+/// How a view shows the content which extends past its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// The content is drawn outside of the view.
+    Visible,
+    /// The content is clipped to the view, including its rounded corners.
+    Hidden,
+    /// The content is clipped to the view and scrolled with the pointer wheel, without
+    /// scroll bars. Use a [`scroll`](crate::views::scroll()) view for those.
+    Scroll,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CursorStyle {
     Default,
     Pointer,
     Text,
+    VerticalText,
     ColResize,
     RowResize,
     WResize,
@@ -919,8 +1030,299 @@ pub enum CursorStyle {
     NeResize,
     SwResize,
     SeResize,
+    EwResize,
+    NsResize,
     NeswResize,
     NwseResize,
+    Move,
+    AllScroll,
+    Grab,
+    Grabbing,
+    ZoomIn,
+    ZoomOut,
+    Crosshair,
+    Cell,
+    Help,
+    ContextMenu,
+    Wait,
+    Progress,
+    NotAllowed,
+    NoDrop,
+    Copy,
+    Alias,
+    /// A cursor drawn from an image. See [`CustomCursor`](crate::cursor::CustomCursor)
+    Custom(crate::cursor::CustomCursor),
+}
+
+/// An image drawn over the background color of a view, see [`Style::background_image`].
+#[derive(Clone)]
+pub struct BackgroundImage {
+    image: Rc<DynamicImage>,
+    hash: Rc<[u8]>,
+    nine_slice: Option<(Insets, Rc<NineSlice>)>,
+    repeat: BackgroundRepeat,
+    spacing: kurbo::Vec2,
+    offset: kurbo::Vec2,
+}
+
+/// How a [`BackgroundImage`] covers the view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackgroundRepeat {
+    /// The image is stretched over the view.
+    #[default]
+    NoRepeat,
+    /// The image is drawn at its size and repeated horizontally.
+    RepeatX,
+    /// The image is drawn at its size and repeated vertically.
+    RepeatY,
+    /// The image is drawn at its size and repeated in both directions.
+    Repeat,
+}
+
+impl BackgroundImage {
+    /// Creates a background image from an encoded image, such as a PNG file.
+    pub fn from_image(image: &[u8]) -> Option<Self> {
+        let image = image::load_from_memory(image).ok()?;
+        Some(Self::from_dynamic_image(image))
+    }
+
+    /// Creates a background image from `width * height` RGBA pixels.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Option<Self> {
+        let image = image::RgbaImage::from_raw(width, height, rgba)?;
+        Some(Self::from_dynamic_image(DynamicImage::ImageRgba8(image)))
+    }
+
+    fn from_dynamic_image(image: DynamicImage) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(image.as_bytes());
+        Self {
+            hash: hasher.finalize().to_vec().into(),
+            image: Rc::new(image),
+            nine_slice: None,
+            repeat: BackgroundRepeat::NoRepeat,
+            spacing: kurbo::Vec2::ZERO,
+            offset: kurbo::Vec2::ZERO,
+        }
+    }
+
+    /// Repeats the image to tile the view instead of stretching it.
+    pub fn repeat(mut self, repeat: BackgroundRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Leaves a gap of `x` and `y` pixels between repeated tiles.
+    pub fn spacing(mut self, x: f64, y: f64) -> Self {
+        self.spacing = kurbo::Vec2::new(x, y);
+        self
+    }
+
+    /// Moves the tiles by `x` and `y` pixels from the top left corner of the view.
+    pub fn offset(mut self, x: f64, y: f64) -> Self {
+        self.offset = kurbo::Vec2::new(x, y);
+        self
+    }
+
+    /// Draws the image as a nine-slice image with the stretchable area inside `insets`, which
+    /// are given in image pixels. The corners keep their size while the edges and the center
+    /// stretch to fill the view.
+    pub fn nine_slice(mut self, insets: impl Into<Insets>) -> Self {
+        let insets = insets.into();
+        let slices = NineSlice::new(&self.image, &self.hash, insets);
+        self.nine_slice = Some((insets, Rc::new(slices)));
+        self
+    }
+
+    /// Draws the image stretched over or tiling `rect`.
+    pub(crate) fn paint(&self, cx: &mut PaintCx, rect: kurbo::Rect) {
+        let img = || floem_renderer::Img {
+            img: &self.image,
+            data: self.image.as_bytes(),
+            hash: &self.hash,
+        };
+        if self.repeat == BackgroundRepeat::NoRepeat {
+            match &self.nine_slice {
+                Some((_, slices)) => slices.paint(cx, rect),
+                None => cx.draw_img(img(), rect),
+            }
+            return;
+        }
+
+        let (width, height) = self.image.dimensions();
+        let size = kurbo::Size::new(width as f64, height as f64);
+        if size.is_empty() {
+            return;
+        }
+        let (repeat_x, repeat_y) = match self.repeat {
+            BackgroundRepeat::RepeatX => (true, false),
+            BackgroundRepeat::RepeatY => (false, true),
+            _ => (true, true),
+        };
+        let xs = tile_starts(
+            rect.x0,
+            rect.x1,
+            size.width + self.spacing.x.max(0.0),
+            self.offset.x,
+            repeat_x,
+        );
+        let ys = tile_starts(
+            rect.y0,
+            rect.y1,
+            size.height + self.spacing.y.max(0.0),
+            self.offset.y,
+            repeat_y,
+        );
+        for y in ys {
+            for x in xs.clone() {
+                cx.draw_img(img(), size.to_rect().with_origin((x, y)));
+            }
+        }
+    }
+}
+
+/// Returns the starts of the tiles covering `start..end`, which are `step` apart and aligned
+/// to `start + offset`. Without `repeat`, there is a single tile at `start + offset`.
+fn tile_starts(start: f64, end: f64, step: f64, offset: f64, repeat: bool) -> Vec<f64> {
+    if !repeat || step <= 0.0 {
+        return vec![start + offset];
+    }
+    let mut tile = start + offset.rem_euclid(step);
+    if tile > start {
+        tile -= step;
+    }
+    let mut starts = Vec::new();
+    while tile < end {
+        starts.push(tile);
+        tile += step;
+    }
+    starts
+}
+
+/// A pattern drawn over the background color of a view, see [`Style::background_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackgroundPattern {
+    /// Squares of `size` pixels alternating between two colors, such as the checkerboard
+    /// shown behind transparent images.
+    Checkerboard { size: f64, colors: (Color, Color) },
+    /// Stripes of `width` pixels in `color`, separated by `gap` pixels and rotated clockwise by
+    /// `angle` degrees from vertical.
+    Stripes {
+        color: Color,
+        width: f64,
+        gap: f64,
+        angle: f64,
+    },
+}
+
+impl BackgroundPattern {
+    /// The light and dark gray checkerboard used to show transparency.
+    pub fn transparency_checkerboard() -> Self {
+        Self::Checkerboard {
+            size: 8.0,
+            colors: (Color::rgb8(0xff, 0xff, 0xff), Color::rgb8(0xcc, 0xcc, 0xcc)),
+        }
+    }
+
+    pub(crate) fn paint(&self, cx: &mut PaintCx, rect: kurbo::Rect) {
+        match *self {
+            BackgroundPattern::Checkerboard { size, colors } => {
+                if size <= 0.0 {
+                    return;
+                }
+                cx.fill(&rect, colors.0, 0.0);
+                let mut path = kurbo::BezPath::new();
+                let columns = (rect.width() / size).ceil() as usize;
+                let rows = (rect.height() / size).ceil() as usize;
+                for row in 0..rows {
+                    for column in (row % 2..columns).step_by(2) {
+                        let origin = (rect.x0 + column as f64 * size, rect.y0 + row as f64 * size);
+                        let square = kurbo::Rect::from_origin_size(origin, (size, size));
+                        path.extend(kurbo::Shape::path_elements(&square, 0.1));
+                    }
+                }
+                cx.fill(&path, colors.1, 0.0);
+            }
+            BackgroundPattern::Stripes {
+                color,
+                width,
+                gap,
+                angle,
+            } => {
+                let period = width + gap.max(0.0);
+                if width <= 0.0 || period <= 0.0 {
+                    return;
+                }
+                // Stripes run along `along` and are stacked along `across`
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let across = kurbo::Vec2::new(cos, sin);
+                let along = kurbo::Vec2::new(-sin, cos);
+                let corners = [
+                    rect.origin(),
+                    kurbo::Point::new(rect.x1, rect.y0),
+                    kurbo::Point::new(rect.x0, rect.y1),
+                    kurbo::Point::new(rect.x1, rect.y1),
+                ];
+                let project = |p: kurbo::Point, axis: kurbo::Vec2| p.to_vec2().dot(axis);
+                let (min, max) = corners
+                    .iter()
+                    .map(|p| project(*p, across))
+                    .fold((f64::MAX, f64::MIN), |(min, max), t| {
+                        (min.min(t), max.max(t))
+                    });
+                let (min_along, max_along) = corners
+                    .iter()
+                    .map(|p| project(*p, along))
+                    .fold((f64::MAX, f64::MIN), |(min, max), t| {
+                        (min.min(t), max.max(t))
+                    });
+
+                let mut path = kurbo::BezPath::new();
+                let mut t = (min / period).floor() * period;
+                while t < max {
+                    let points = [
+                        (t, min_along),
+                        (t + width, min_along),
+                        (t + width, max_along),
+                        (t, max_along),
+                    ]
+                    .map(|(a, b)| (across * a + along * b).to_point());
+                    path.move_to(points[0]);
+                    for point in &points[1..] {
+                        path.line_to(*point);
+                    }
+                    path.close_path();
+                    t += period;
+                }
+                cx.fill(&path, color, 0.0);
+            }
+        }
+    }
+}
+
+impl PartialEq for BackgroundImage {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.image, &other.image)
+            && self.nine_slice.as_ref().map(|(insets, _)| insets)
+                == other.nine_slice.as_ref().map(|(insets, _)| insets)
+            && self.repeat == other.repeat
+            && self.spacing == other.spacing
+            && self.offset == other.offset
+    }
+}
+
+impl Debug for BackgroundImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackgroundImage")
+            .field("size", &self.image.dimensions())
+            .field(
+                "nine_slice",
+                &self.nine_slice.as_ref().map(|(insets, _)| insets),
+            )
+            .field("repeat", &self.repeat)
+            .field("spacing", &self.spacing)
+            .field("offset", &self.offset)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -944,6 +1346,14 @@ impl Default for BoxShadow {
     }
 }
 
+/// A shadow painted behind text. See [`Style::text_shadow`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextShadow {
+    pub offset: kurbo::Vec2,
+    pub blur_radius: f64,
+    pub color: Color,
+}
+
 /// The value for a [`Style`] property
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StyleValue<T> {
@@ -1069,6 +1479,7 @@ define_builtin_props!(
     BorderRadius border_radius: PxPct {} = PxPct::Px(0.0),
     OutlineColor outline_color: Color {} = Color::TRANSPARENT,
     Outline outline: Px {} = Px(0.0),
+    OutlineOffset outline_offset: Px {} = Px(0.0),
     BorderColor border_color: Color {} = Color::BLACK,
     PaddingLeft padding_left: PxPct {} = PxPct::Px(0.0),
     PaddingTop padding_top: PxPct {} = PxPct::Px(0.0),
@@ -1086,15 +1497,24 @@ define_builtin_props!(
     Cursor cursor nocb: Option<CursorStyle> {} = None,
     TextColor color nocb: Option<Color> { inherited } = None,
     Background background nocb: Option<Color> {} = None,
+    BackgroundImageProp background_image nocb: Option<BackgroundImage> {} = None,
+    BackgroundPatternProp background_pattern nocb: Option<BackgroundPattern> {} = None,
     Foreground foreground nocb: Option<Color> {} = None,
     BoxShadowProp box_shadow nocb: Option<BoxShadow> {} = None,
+    LayerShadowProp layer_shadow nocb: Option<BoxShadow> {} = None,
     FontSize font_size nocb: Option<f32> { inherited } = None,
-    FontFamily font_family nocb: Option<String> { inherited } = None,
+    FontFamily font_family nocb: Option<Atom> { inherited } = None,
     FontWeight font_weight nocb: Option<Weight> { inherited } = None,
     FontStyle font_style nocb: Option<cosmic_text::Style> { inherited } = None,
+    FontVariations font_variations nocb: Vec<FontVariation> { inherited } = Vec::new(),
     CursorColor cursor_color nocb: Option<Color> {} = None,
     TextOverflowProp text_overflow: TextOverflow {} = TextOverflow::Wrap,
+    OverflowProp overflow: Overflow {} = Overflow::Visible,
     LineHeight line_height nocb: Option<LineHeightValue> { inherited } = None,
+    // The `text_scale`, set on the root style of each window
+    TextScale text_scale nocb: f64 { inherited } = 1.0,
+    TextShadowProp text_shadow nocb: Vec<TextShadow> { inherited } = Vec::new(),
+    TextRenderingProp text_rendering nocb: Option<TextRendering> { inherited } = None,
     AspectRatio aspect_ratio: Option<f32> {} = None,
     Gap gap nocb: Size<LengthPercentage> {} = Size::zero(),
 );
@@ -1105,6 +1525,21 @@ prop_extracter! {
         pub family: FontFamily,
         pub weight: FontWeight,
         pub style: FontStyle,
+        pub variations: FontVariations,
+        pub scale: TextScale,
+    }
+}
+
+impl FontProps {
+    /// The font size multiplied by the [`text_scale`](crate::text_scale::text_scale), falling
+    /// back to `default` when the scale isn't 1 so unstyled text is scaled too.
+    pub fn scaled_size(&self, default: f32) -> Option<f32> {
+        let scale = self.scale();
+        match self.size() {
+            Some(size) => Some(size * scale as f32),
+            None if scale != 1.0 => Some(default * scale as f32),
+            None => None,
+        }
     }
 }
 
@@ -1184,6 +1619,12 @@ impl Style {
         self.selector(StyleSelector::Active, style)
     }
 
+    /// Similar to the `:invalid` css selector, this style activates when the view has been
+    /// marked as invalid, such as a `text_input` failing its validation.
+    pub fn invalid(self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(StyleSelector::Invalid, style)
+    }
+
     pub fn responsive(mut self, size: ScreenSize, style: impl FnOnce(Style) -> Style) -> Self {
         let over = style(Style::default());
         for breakpoint in size.breakpoints() {
@@ -1226,6 +1667,32 @@ impl Style {
         )
     }
 
+    /// Sets the gap between rows, keeping the column gap of this style.
+    pub fn row_gap(self, height: impl Into<PxPct>) -> Self {
+        let height: PxPct = height.into();
+        let gap = self.get(Gap);
+        self.set(
+            Gap,
+            Size {
+                width: gap.width,
+                height: height.into(),
+            },
+        )
+    }
+
+    /// Sets the gap between columns, keeping the row gap of this style.
+    pub fn column_gap(self, width: impl Into<PxPct>) -> Self {
+        let width: PxPct = width.into();
+        let gap = self.get(Gap);
+        self.set(
+            Gap,
+            Size {
+                width: width.into(),
+                height: gap.height,
+            },
+        )
+    }
+
     pub fn size(self, width: impl Into<PxPctAuto>, height: impl Into<PxPctAuto>) -> Self {
         self.width(width).height(height)
     }
@@ -1475,6 +1942,27 @@ impl Style {
         self.set_style_value(Background, color.into().map(Some))
     }
 
+    /// Draws `image` over the view, above the background color and pattern and clipped to
+    /// the border radius. See [`BackgroundImage::repeat`] to tile the image.
+    pub fn background_image(self, image: impl Into<StyleValue<BackgroundImage>>) -> Self {
+        self.set_style_value(BackgroundImageProp, image.into().map(Some))
+    }
+
+    /// Draws `pattern` over the background color and below the background image.
+    pub fn background_pattern(self, pattern: impl Into<StyleValue<BackgroundPattern>>) -> Self {
+        self.set_style_value(BackgroundPatternProp, pattern.into().map(Some))
+    }
+
+    /// Draws a drop shadow around the view with the shape of the view cut out of it, so it
+    /// doesn't show through translucent or rounded backgrounds. This suits floating surfaces
+    /// like popovers, menus and dialogs in overlays.
+    ///
+    /// The shadow is rendered into an image which is kept until the size of the view or the
+    /// shadow changes.
+    pub fn layer_shadow(self, shadow: impl Into<StyleValue<BoxShadow>>) -> Self {
+        self.set_style_value(LayerShadowProp, shadow.into().map(Some))
+    }
+
     pub fn box_shadow_blur(self, blur_radius: impl Into<PxPct>) -> Self {
         let mut value = self.get(BoxShadowProp).unwrap_or_default();
         value.blur_radius = blur_radius.into();
@@ -1509,7 +1997,15 @@ impl Style {
         self.set_style_value(FontSize, size.into().map(Some))
     }
 
-    pub fn font_family(self, family: impl Into<StyleValue<String>>) -> Self {
+    /// Sets the font family, or a comma separated list of families to fall back on, as an
+    /// [interned](crate::intern) name. Interning takes a lock, so intern the name once outside
+    /// of the style closure:
+    ///
+    /// ```ignore
+    /// let monospace = intern("monospace");
+    /// label(|| "Code").style(move |s| s.font_family(monospace))
+    /// ```
+    pub fn font_family(self, family: impl Into<StyleValue<Atom>>) -> Self {
         self.set_style_value(FontFamily, family.into().map(Some))
     }
 
@@ -1525,14 +2021,62 @@ impl Style {
         self.set_style_value(FontStyle, style.into().map(Some))
     }
 
+    /// Sets the position on an axis of a variable font, replacing any previous value for the
+    /// same axis. For example `font_variation(b"wght", 450.0)`. Only the registered axes are
+    /// used, and only to pick the closest face of the font, see `apply_font_variations`.
+    pub fn font_variation(self, tag: &[u8; 4], value: f32) -> Self {
+        let variations = set_tagged(
+            self.get(FontVariations),
+            FontVariation::new(tag, value),
+            |v| v.tag,
+        );
+        self.set(FontVariations, variations)
+    }
+
     pub fn cursor_color(self, color: impl Into<StyleValue<Color>>) -> Self {
         self.set_style_value(CursorColor, color.into().map(Some))
     }
 
+    /// Adds a shadow behind the text of labels and rich text, moved by `offset` and blurred
+    /// by `blur_radius`. Shadows can be stacked by calling this again, with the first shadow
+    /// painted on top.
+    ///
+    /// ```rust
+    /// # use floem::{peniko::Color, style::Style};
+    /// Style::new()
+    ///     .text_shadow((0.0, 1.0), 0.0, Color::WHITE)
+    ///     .text_shadow((0.0, 2.0), 4.0, Color::BLACK.with_alpha_factor(0.5));
+    /// ```
+    pub fn text_shadow(
+        self,
+        offset: impl Into<kurbo::Vec2>,
+        blur_radius: f64,
+        color: Color,
+    ) -> Self {
+        let mut shadows = self.get(TextShadowProp);
+        shadows.push(TextShadow {
+            offset: offset.into(),
+            blur_radius,
+            color,
+        });
+        self.set(TextShadowProp, shadows)
+    }
+
+    /// Sets the line height as a multiple of the font size.
     pub fn line_height(self, normal: f32) -> Self {
         self.set(LineHeight, Some(LineHeightValue::Normal(normal)))
     }
 
+    /// Sets the line height in pixels, regardless of the font size.
+    pub fn line_height_px(self, px: f32) -> Self {
+        self.set(LineHeight, Some(LineHeightValue::Px(px)))
+    }
+
+    /// Overrides the hinting and antialiasing of text in this view and its children.
+    pub fn text_rendering(self, config: impl Into<StyleValue<TextRendering>>) -> Self {
+        self.set_style_value(TextRenderingProp, config.into().map(Some))
+    }
+
     pub fn text_ellipsis(self) -> Self {
         self.text_overflow(TextOverflow::Ellipsis)
     }
@@ -1699,9 +2243,9 @@ impl Style {
 
 #[cfg(test)]
 mod tests {
-    use super::{Style, StyleValue};
+    use super::{tile_starts, Style, StyleValue};
     use crate::{
-        style::{PaddingBottom, PaddingLeft},
+        style::{FontVariation, FontVariations, PaddingBottom, PaddingLeft},
         unit::PxPct,
     };
 
@@ -1778,4 +2322,34 @@ mod tests {
             StyleValue::Val(PxPct::Px(100.0))
         );
     }
+
+    #[test]
+    fn font_variation_replaces_tag() {
+        let style = Style::new()
+            .font_variation(b"wght", 300.0)
+            .font_variation(b"wdth", 75.0)
+            .font_variation(b"wght", 650.0);
+
+        assert_eq!(
+            style.get(FontVariations),
+            vec![
+                FontVariation::new(b"wdth", 75.0),
+                FontVariation::new(b"wght", 650.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn tiles_cover_range() {
+        assert_eq!(
+            tile_starts(0.0, 25.0, 10.0, 0.0, true),
+            vec![0.0, 10.0, 20.0]
+        );
+        assert_eq!(
+            tile_starts(0.0, 20.0, 10.0, 4.0, true),
+            vec![-6.0, 4.0, 14.0]
+        );
+        assert_eq!(tile_starts(5.0, 20.0, 10.0, -3.0, true), vec![2.0, 12.0]);
+        assert_eq!(tile_starts(5.0, 20.0, 10.0, 3.0, false), vec![8.0]);
+    }
 }
@@ -0,0 +1,361 @@
+//! Exporting rendered views as SVG documents.
+//!
+//! [`export_svg`] paints a view and its children with [`SvgRenderer`], which turns the draw
+//! calls into SVG elements instead of pixels: shapes become paths, text becomes `<text>`
+//! elements and images are embedded as data URLs.
+
+use std::fmt::Write;
+
+use floem_peniko::{BrushRef, Color, GradientKind};
+use floem_renderer::{cosmic_text::TextLayout, Img, Svg, TextRendering};
+use image::DynamicImage;
+use kurbo::{Affine, Point, Rect, Shape, Size};
+
+use crate::{id::Id, update::UpdateMessage};
+
+/// Paints the view `id` and its children as an SVG document, passing it to `on_export` once
+/// the window has processed its pending updates.
+///
+/// The document has the size of the view, with the view drawn at its origin.
+pub fn export_svg(id: Id, on_export: impl FnOnce(String) + 'static) {
+    id.add_update_message(UpdateMessage::ExportSvg {
+        id,
+        on_export: Box::new(on_export),
+    });
+}
+
+/// A renderer which records the draw calls as SVG elements.
+#[derive(Default)]
+pub struct SvgRenderer {
+    transform: Affine,
+    /// The id of the clip path applied to the elements being drawn.
+    clip: Option<usize>,
+    defs: String,
+    body: String,
+    /// The text drawn, a line for each line of the text layouts.
+    text: String,
+    next_id: usize,
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the SVG document for everything drawn since [`begin`](floem_renderer::Renderer::begin).
+    pub fn document(&self, size: Size) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+             xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+             width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+             <defs>\n{defs}</defs>\n{body}</svg>\n",
+            w = size.width,
+            h = size.height,
+            defs = self.defs,
+            body = self.body,
+        )
+    }
+
+    /// Returns the text drawn since [`begin`](floem_renderer::Renderer::begin), in the order it
+    /// was drawn, with a line for each line of text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{}", self.next_id)
+    }
+
+    /// Appends an element, wrapped in a group applying the current clip.
+    fn push_element(&mut self, element: &str) {
+        match self.clip {
+            Some(clip) => {
+                let _ = writeln!(self.body, "<g clip-path=\"url(#clip{clip})\">{element}</g>");
+            }
+            None => {
+                let _ = writeln!(self.body, "{element}");
+            }
+        }
+    }
+
+    /// Returns the attribute value painting with `brush`, defining a gradient if needed.
+    fn paint<'b>(&mut self, brush: impl Into<BrushRef<'b>>) -> Option<String> {
+        match brush.into() {
+            BrushRef::Solid(color) => Some(color_attr(color)),
+            BrushRef::Gradient(gradient) => {
+                let id = self.next_id("gradient");
+                let (tag, attrs) = match gradient.kind {
+                    GradientKind::Linear { start, end } => (
+                        "linearGradient",
+                        format!(
+                            "x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+                            start.x, start.y, end.x, end.y
+                        ),
+                    ),
+                    GradientKind::Radial {
+                        start_center,
+                        start_radius,
+                        end_center,
+                        end_radius,
+                    } => (
+                        "radialGradient",
+                        format!(
+                            "fx=\"{}\" fy=\"{}\" fr=\"{start_radius}\" cx=\"{}\" cy=\"{}\" r=\"{end_radius}\"",
+                            start_center.x, start_center.y, end_center.x, end_center.y
+                        ),
+                    ),
+                    GradientKind::Sweep { .. } => return None,
+                };
+                let _ = write!(
+                    self.defs,
+                    "<{tag} id=\"{id}\" gradientUnits=\"userSpaceOnUse\" {attrs}>"
+                );
+                for stop in gradient.stops.iter() {
+                    let _ = write!(
+                        self.defs,
+                        "<stop offset=\"{}\" stop-color=\"{}\"/>",
+                        stop.offset,
+                        color_attr(stop.color)
+                    );
+                }
+                let _ = writeln!(self.defs, "</{tag}>");
+                Some(format!("url(#{id})"))
+            }
+            BrushRef::Image(_) => None,
+        }
+    }
+
+    fn transform_attr(&self) -> String {
+        let [a, b, c, d, e, f] = self.transform.as_coeffs();
+        format!("matrix({a} {b} {c} {d} {e} {f})")
+    }
+}
+
+fn color_attr(color: Color) -> String {
+    let color = floem_renderer::color::to_output(color);
+    if color.a == 255 {
+        format!("rgb({},{},{})", color.r, color.g, color.b)
+    } else {
+        format!(
+            "rgba({},{},{},{})",
+            color.r,
+            color.g,
+            color.b,
+            color.a as f64 / 255.0
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(TABLE[(n >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn png_data_url(img: &DynamicImage) -> Option<String> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut png, image::ImageOutputFormat::Png).ok()?;
+    Some(format!("data:image/png;base64,{}", base64(png.get_ref())))
+}
+
+impl floem_renderer::Renderer for SvgRenderer {
+    fn begin(&mut self, _capture: bool) {
+        *self = Self::default();
+    }
+
+    fn transform(&mut self, transform: Affine) {
+        self.transform = transform;
+    }
+
+    fn set_z_index(&mut self, _z_index: i32) {}
+
+    fn set_text_rendering(&mut self, _config: TextRendering) {}
+
+    fn clip(&mut self, shape: &impl Shape) {
+        self.next_id += 1;
+        let id = self.next_id;
+        let _ = writeln!(
+            self.defs,
+            "<clipPath id=\"clip{id}\"><path d=\"{}\" transform=\"{}\"/></clipPath>",
+            shape.to_path(0.1).to_svg(),
+            self.transform_attr()
+        );
+        self.clip = Some(id);
+    }
+
+    fn clear_clip(&mut self) {
+        self.clip = None;
+    }
+
+    fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
+        let Some(paint) = self.paint(brush) else {
+            return;
+        };
+        let element = format!(
+            "<path d=\"{}\" transform=\"{}\" fill=\"none\" stroke=\"{paint}\" stroke-width=\"{width}\"/>",
+            shape.to_path(0.1).to_svg(),
+            self.transform_attr()
+        );
+        self.push_element(&element);
+    }
+
+    fn fill<'b>(&mut self, path: &impl Shape, brush: impl Into<BrushRef<'b>>, blur_radius: f64) {
+        let Some(paint) = self.paint(brush) else {
+            return;
+        };
+        let filter = if blur_radius > 0.0 {
+            let id = self.next_id("blur");
+            let _ = writeln!(
+                self.defs,
+                "<filter id=\"{id}\"><feGaussianBlur stdDeviation=\"{}\"/></filter>",
+                blur_radius / 2.0
+            );
+            format!(" filter=\"url(#{id})\"")
+        } else {
+            String::new()
+        };
+        let element = format!(
+            "<path d=\"{}\" transform=\"{}\" fill=\"{paint}\"{filter}/>",
+            path.to_path(0.1).to_svg(),
+            self.transform_attr()
+        );
+        self.push_element(&element);
+    }
+
+    fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
+        let pos: Point = pos.into();
+        let mut element = format!("<g transform=\"{}\">", self.transform_attr());
+        for line in layout.layout_runs() {
+            if !self.text.is_empty() {
+                self.text.push('\n');
+            }
+            self.text.push_str(line.text);
+            for glyph in line.glyphs {
+                if glyph.is_tab {
+                    continue;
+                }
+                let Some(text) = line.text.get(glyph.start..glyph.end) else {
+                    continue;
+                };
+                let family = floem_renderer::cosmic_text::FONT_SYSTEM
+                    .lock()
+                    .db()
+                    .face(glyph.cache_key.font_id)
+                    .and_then(|face| face.families.first().map(|(name, _)| escape(name)))
+                    .unwrap_or_else(|| "sans-serif".to_string());
+                let _ = write!(
+                    element,
+                    "<text x=\"{}\" y=\"{}\" font-family=\"{family}\" font-size=\"{}\" fill=\"{}\" xml:space=\"preserve\">{}</text>",
+                    pos.x + glyph.x as f64,
+                    pos.y + line.line_y as f64,
+                    glyph.font_size,
+                    color_attr(glyph.color),
+                    escape(text)
+                );
+            }
+        }
+        element.push_str("</g>");
+        self.push_element(&element);
+    }
+
+    fn draw_svg<'b>(&mut self, svg: Svg<'b>, rect: Rect, brush: Option<impl Into<BrushRef<'b>>>) {
+        use floem_renderer::usvg::TreeWriting;
+
+        let data = svg
+            .tree
+            .to_string(&floem_renderer::usvg::XmlOptions::default());
+        // Tinted icons are filled with the brush color, keeping their alpha
+        let filter = match brush.and_then(|brush| self.paint(brush)) {
+            Some(paint) if !paint.starts_with("url(") => {
+                let id = self.next_id("tint");
+                let _ = writeln!(
+                    self.defs,
+                    "<filter id=\"{id}\"><feFlood flood-color=\"{paint}\"/>\
+                     <feComposite in2=\"SourceAlpha\" operator=\"in\"/></filter>"
+                );
+                format!(" filter=\"url(#{id})\"")
+            }
+            _ => String::new(),
+        };
+        let element = format!(
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" transform=\"{}\"{filter} \
+             xlink:href=\"data:image/svg+xml;base64,{}\"/>",
+            rect.x0,
+            rect.y0,
+            rect.width(),
+            rect.height(),
+            self.transform_attr(),
+            base64(data.as_bytes())
+        );
+        self.push_element(&element);
+    }
+
+    fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
+        let Some(url) = png_data_url(img.img) else {
+            return;
+        };
+        let element = format!(
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" transform=\"{}\" \
+             preserveAspectRatio=\"none\" xlink:href=\"{url}\"/>",
+            rect.x0,
+            rect.y0,
+            rect.width(),
+            rect.height(),
+            self.transform_attr(),
+        );
+        self.push_element(&element);
+    }
+
+    fn finish(&mut self) -> Option<DynamicImage> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, escape};
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn escape_markup() {
+        assert_eq!(escape("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+    }
+}
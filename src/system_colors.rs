@@ -0,0 +1,215 @@
+//! The accent and UI colors of the desktop.
+//!
+//! [`system_colors`] returns signals holding the colors the user picked for their OS, which
+//! are read again when the OS switches between light and dark mode. The colors are read with
+//! `gsettings` on Linux (GNOME), `defaults` on macOS and the registry on Windows, falling back
+//! to the usual colors of the platform.
+
+use std::process::Command;
+
+use floem_peniko::Color;
+use floem_reactive::{ReadSignal, RwSignal, Scope};
+use floem_renderer::color::{mix_linear, srgb};
+use floem_winit::window::Theme;
+
+thread_local! {
+    static SYSTEM_COLORS: SystemColorsSignals = {
+        // The signals outlive the views and windows reading them
+        let scope = Scope::new();
+        let palette = Palette::read(false);
+        SystemColorsSignals {
+            dark: scope.create_rw_signal(false),
+            accent: scope.create_rw_signal(palette.accent),
+            window_background: scope.create_rw_signal(palette.window_background),
+            text: scope.create_rw_signal(palette.text),
+            highlight: scope.create_rw_signal(palette.highlight),
+        }
+    };
+}
+
+/// Signals holding the colors of the desktop. See [`system_colors`]
+#[derive(Clone, Copy)]
+pub struct SystemColors {
+    /// The accent color of controls.
+    pub accent: ReadSignal<Color>,
+    pub window_background: ReadSignal<Color>,
+    pub text: ReadSignal<Color>,
+    /// The background of selected text and items.
+    pub highlight: ReadSignal<Color>,
+}
+
+/// The colors of the desktop, updated when the OS theme changes.
+pub fn system_colors() -> SystemColors {
+    SYSTEM_COLORS.with(|colors| SystemColors {
+        accent: colors.accent.read_only(),
+        window_background: colors.window_background.read_only(),
+        text: colors.text.read_only(),
+        highlight: colors.highlight.read_only(),
+    })
+}
+
+/// Reads the colors again after a window reported the OS theme.
+pub(crate) fn update_system_colors(theme: Theme) {
+    SYSTEM_COLORS.with(|colors| {
+        let dark = theme == Theme::Dark;
+        if colors.dark.get_untracked() == dark {
+            return;
+        }
+        colors.dark.set(dark);
+        let palette = Palette::read(dark);
+        colors.accent.set(palette.accent);
+        colors.window_background.set(palette.window_background);
+        colors.text.set(palette.text);
+        colors.highlight.set(palette.highlight);
+    });
+}
+
+struct SystemColorsSignals {
+    /// Whether the colors were read for a dark theme.
+    dark: RwSignal<bool>,
+    accent: RwSignal<Color>,
+    window_background: RwSignal<Color>,
+    text: RwSignal<Color>,
+    highlight: RwSignal<Color>,
+}
+
+struct Palette {
+    accent: Color,
+    window_background: Color,
+    text: Color,
+    highlight: Color,
+}
+
+impl Palette {
+    fn read(dark: bool) -> Self {
+        let accent = read_accent().unwrap_or_else(default_accent);
+        let (window_background, text) = if dark {
+            (srgb(36, 36, 36), srgb(255, 255, 255))
+        } else {
+            (srgb(246, 246, 246), srgb(20, 20, 20))
+        };
+        let highlight =
+            read_highlight().unwrap_or_else(|| mix_linear(window_background, accent, 0.4));
+        Self {
+            accent,
+            window_background,
+            text,
+            highlight,
+        }
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn default_accent() -> Color {
+    if cfg!(target_os = "macos") {
+        srgb(0, 122, 255)
+    } else if cfg!(windows) {
+        srgb(0, 120, 212)
+    } else {
+        srgb(53, 132, 228)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_accent() -> Option<Color> {
+    let name = command_output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "accent-color"],
+    )?;
+    let (r, g, b) = match name.trim_matches('\'') {
+        "blue" => (53, 132, 228),
+        "teal" => (33, 144, 164),
+        "green" => (58, 148, 74),
+        "yellow" => (200, 136, 0),
+        "orange" => (237, 91, 0),
+        "red" => (230, 45, 66),
+        "pink" => (213, 97, 153),
+        "purple" => (145, 65, 172),
+        "slate" => (111, 131, 150),
+        _ => return None,
+    };
+    Some(srgb(r, g, b))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn read_highlight() -> Option<Color> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn read_accent() -> Option<Color> {
+    // The blue accent is the default, which removes the setting
+    let (r, g, b) = match command_output("defaults", &["read", "-g", "AppleAccentColor"]).as_deref()
+    {
+        Some("-1") => (140, 140, 140),
+        Some("0") => (255, 82, 89),
+        Some("1") => (247, 130, 27),
+        Some("2") => (255, 198, 0),
+        Some("3") => (98, 186, 70),
+        Some("5") => (165, 80, 167),
+        Some("6") => (247, 79, 158),
+        _ => (0, 122, 255),
+    };
+    Some(srgb(r, g, b))
+}
+
+#[cfg(target_os = "macos")]
+fn read_highlight() -> Option<Color> {
+    // Printed as the components followed by a name, like `0.968627 0.831373 1.000000 Purple`
+    let value = command_output("defaults", &["read", "-g", "AppleHighlightColor"])?;
+    let mut components = value.split_whitespace().map(|c| {
+        c.parse::<f64>()
+            .ok()
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    });
+    let (r, g, b) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    Some(srgb(r, g, b))
+}
+
+#[cfg(windows)]
+fn read_accent() -> Option<Color> {
+    // Printed as `AccentColor    REG_DWORD    0xffd77800`, in ABGR order
+    let output = command_output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\DWM",
+            "/v",
+            "AccentColor",
+        ],
+    )?;
+    let value = output.split_whitespace().last()?.strip_prefix("0x")?;
+    let value = u32::from_str_radix(value, 16).ok()?;
+    Some(srgb(value as u8, (value >> 8) as u8, (value >> 16) as u8))
+}
+
+#[cfg(windows)]
+fn read_highlight() -> Option<Color> {
+    // Printed as `Hilight    REG_SZ    0 120 215`
+    let output = command_output(
+        "reg",
+        &["query", r"HKCU\Control Panel\Colors", "/v", "Hilight"],
+    )?;
+    let mut components = output
+        .split_whitespace()
+        .rev()
+        .take(3)
+        .map(|c| c.parse::<u8>().ok());
+    let (b, g, r) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    Some(srgb(r, g, b))
+}
@@ -0,0 +1,138 @@
+//! Dragging views out of their window, like the tabs of a browser.
+//!
+//! A view with [`Decorators::tear_off`](crate::views::Decorators::tear_off) is dragged like a
+//! [`draggable`](crate::views::Decorators::draggable) view inside its window. Once the pointer
+//! leaves the window, a preview window follows it instead, and dropping the view there calls its
+//! handler with a [`TearOffDrop`] telling where it was dropped, so the content can be moved to a
+//! new window or docked into another one.
+//!
+//! Tearing off needs the position of the window on the screen, which isn't available on Wayland.
+//!
+//! The content can be moved to the other window as it is, without being rebuilt, by holding it
+//! in a [`view_slot`](crate::views::view_slot) and moving it with
+//! [`move_view`](crate::views::move_view).
+//!
+//! ## Example
+//! ```ignore
+//! tab_header(doc)
+//!     .tear_off(
+//!         move || tab_header(doc).style(|s| s.background(Color::WHITE.with_alpha_factor(0.8))),
+//!         move |drop| match drop {
+//!             TearOffDrop::Screen(origin) => {
+//!                 new_window(
+//!                     move |_| {
+//!                         let slot = empty_view_slot();
+//!                         move_view(editor_slot(doc), slot.id());
+//!                         // Closes the tab once the editor is moved out of it
+//!                         tabs.update(|tabs| tabs.retain(|tab| *tab != doc));
+//!                         slot.style(|s| s.size_full())
+//!                     },
+//!                     Some(WindowConfig::default().position(origin)),
+//!                 );
+//!             }
+//!             TearOffDrop::Window(window_id, _) => move_to_window(window_id, doc),
+//!         },
+//!     )
+//! ```
+
+use std::{cell::Cell, rc::Rc};
+
+use kurbo::{Point, Size, Vec2};
+
+use crate::{
+    app::{add_app_update_event, AppUpdateEvent},
+    id::Id,
+    view::View,
+    views::{container, Decorators},
+    window::{close_window, new_window, WindowConfig, WindowId, WindowLevel},
+};
+
+/// Where a view torn off its window was dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TearOffDrop {
+    /// Dropped outside of the app's windows. This is the position of the top left corner of the
+    /// preview on the screen, where a new window for the content lines up with it.
+    Screen(Point),
+    /// Dropped onto one of the app's windows, at this position in the window.
+    Window(WindowId, Point),
+}
+
+#[derive(Clone)]
+pub(crate) struct TearOff {
+    pub(crate) preview: Rc<dyn Fn() -> Box<dyn View>>,
+    pub(crate) on_drop: Rc<dyn Fn(TearOffDrop)>,
+}
+
+/// The window following the pointer while a view is dragged outside of its window.
+pub(crate) struct TearOffPreview {
+    pub(crate) id: Id,
+    /// Where the pointer holds the preview, relative to its top left corner.
+    grab: Vec2,
+    /// Set once the window is opened, after the app processes its updates.
+    window_id: Rc<Cell<Option<WindowId>>>,
+    closed: Rc<Cell<bool>>,
+}
+
+impl TearOffPreview {
+    /// Opens a preview window of `size` under `pointer`, a position on the screen.
+    pub(crate) fn open(id: Id, tear_off: &TearOff, size: Size, grab: Vec2, pointer: Point) -> Self {
+        let window_id = Rc::new(Cell::new(None));
+        let closed = Rc::new(Cell::new(false));
+        let preview = tear_off.preview.clone();
+        let config = WindowConfig::default()
+            .size(size)
+            .position(pointer - grab)
+            .show_titlebar(false)
+            .with_transparent(true)
+            .resizable(false)
+            .window_level(WindowLevel::AlwaysOnTop)
+            .active(false)
+            .apply_default_theme(false);
+        new_window(
+            {
+                let window_id = window_id.clone();
+                let closed = closed.clone();
+                move |id| {
+                    window_id.set(Some(id));
+                    if closed.get() {
+                        // The drag ended before the window was opened
+                        close_window(id);
+                    }
+                    container(preview()).style(|s| s.size_full())
+                }
+            },
+            Some(config),
+        );
+        TearOffPreview {
+            id,
+            grab,
+            window_id,
+            closed,
+        }
+    }
+
+    pub(crate) fn window_id(&self) -> Option<WindowId> {
+        self.window_id.get()
+    }
+
+    /// The position of the top left corner of the preview with the pointer at `pointer`.
+    pub(crate) fn origin(&self, pointer: Point) -> Point {
+        pointer - self.grab
+    }
+
+    pub(crate) fn move_to(&self, pointer: Point) {
+        if let Some(window_id) = self.window_id.get() {
+            add_app_update_event(AppUpdateEvent::MoveWindow {
+                window_id,
+                position: self.origin(pointer),
+            });
+        }
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.set(true);
+        if let Some(window_id) = self.window_id.get() {
+            close_window(window_id);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Testing views without a window.
+//!
+//! A [`TestHarness`] mounts a view into a headless window, lays it out at a given size and
+//! drives it with synthetic input, so tests can check the layout of its views and the text they
+//! render.
+//!
+//! ## Example
+//! ```ignore
+//! let count = create_rw_signal(0);
+//! let button = label(move || format!("Clicked {} times", count.get()))
+//!     .on_click_stop(move |_| count.update(|count| *count += 1));
+//! let button_id = button.id();
+//!
+//! let mut harness = TestHarness::new(button);
+//! harness.click(button_id);
+//! assert_eq!(count.get(), 1);
+//! assert_eq!(harness.text(), "Clicked 1 times");
+//! ```
+
+use floem_winit::event::{ElementState, MouseButton};
+use image::DynamicImage;
+use kurbo::{Point, Rect, Size, Vec2};
+
+use crate::{
+    event::Event,
+    id::Id,
+    keyboard::{Key, KeyInput, ModifiersState},
+    pointer::PointerWheelEvent,
+    view::View,
+    window_handle::{get_current_view, set_current_view, WindowHandle},
+};
+
+/// A view mounted into a window which isn't shown, for tests.
+///
+/// Positions are in logical pixels, relative to the top left corner of the window. Input is
+/// processed as it's sent, along with the updates it causes, so the layout is current after
+/// each call.
+pub struct TestHarness {
+    handle: WindowHandle,
+    root: Id,
+    running_view: Id,
+}
+
+impl TestHarness {
+    /// Mounts `view` into a headless window of 800x600.
+    pub fn new(view: impl View + 'static) -> Self {
+        Self::new_with_size(view, Size::new(800.0, 600.0))
+    }
+
+    /// Mounts `view` into a headless window of `size`.
+    pub fn new_with_size(view: impl View + 'static, size: Size) -> Self {
+        let running_view = get_current_view();
+        let root = view.id();
+        let mut handle = WindowHandle::new_headless(move |_| Box::new(view), size, 1.0, true);
+        handle.process_update_no_paint();
+        TestHarness {
+            handle,
+            root,
+            running_view,
+        }
+    }
+
+    /// The id of the mounted view.
+    pub fn root(&self) -> Id {
+        self.root
+    }
+
+    /// Processes pending updates, such as the ones of signals set by the test, restyling and
+    /// laying out the views as needed.
+    pub fn update(&mut self) {
+        self.handle.process_update_no_paint();
+    }
+
+    /// Resizes the window, laying out the views again.
+    pub fn resize(&mut self, size: Size) {
+        self.handle.size(size);
+    }
+
+    /// Moves the pointer to `pos`, updating the hovered views.
+    pub fn pointer_move(&mut self, pos: Point) {
+        self.handle.pointer_move(pos);
+    }
+
+    /// Moves the pointer to `pos` and clicks the primary button.
+    pub fn click_at(&mut self, pos: Point) {
+        self.pointer_move(pos);
+        self.handle
+            .mouse_input(MouseButton::Left, ElementState::Pressed);
+        self.handle
+            .mouse_input(MouseButton::Left, ElementState::Released);
+    }
+
+    /// Clicks the center of the view `id`.
+    pub fn click(&mut self, id: Id) {
+        let center = self.layout_rect(id).center();
+        self.click_at(center);
+    }
+
+    /// Moves the pointer to `pos` and scrolls by `delta`, in pixels. A positive `y` scrolls
+    /// down.
+    pub fn scroll_by(&mut self, pos: Point, delta: Vec2) {
+        self.pointer_move(pos);
+        self.handle.event(Event::PointerWheel(PointerWheelEvent {
+            pos,
+            delta,
+            modifiers: self.handle.modifiers,
+        }));
+    }
+
+    /// Presses and releases `key` with `modifiers` held, sending the key events to the focused
+    /// view. Use [`TestHarness::type_text`] to enter text.
+    pub fn key_press(&mut self, key: Key, modifiers: ModifiersState) {
+        let held = std::mem::replace(&mut self.handle.modifiers, modifiers);
+        self.handle
+            .key_event(KeyInput::new(key.clone(), ElementState::Pressed));
+        self.handle
+            .key_event(KeyInput::new(key, ElementState::Released));
+        self.handle.modifiers = held;
+    }
+
+    /// Enters `text` into the focused view, as committed by an input method.
+    pub fn type_text(&mut self, text: impl Into<String>) {
+        self.handle.event(Event::ImeCommit(text.into()));
+    }
+
+    /// The layout rectangle of the view `id`, relative to the window.
+    pub fn layout_rect(&mut self, id: Id) -> Rect {
+        self.handle.app_state.get_layout_rect(id)
+    }
+
+    /// The size of the view `id`, or `None` if it isn't laid out.
+    pub fn size(&self, id: Id) -> Option<Size> {
+        self.handle.view_size(id)
+    }
+
+    pub fn is_focused(&self, id: Id) -> bool {
+        self.handle.app_state.is_focused(&id)
+    }
+
+    pub fn is_hovered(&self, id: Id) -> bool {
+        self.handle.app_state.is_hovered(&id)
+    }
+
+    /// The text rendered by the mounted view, with a line for each line of text, in the order
+    /// it's painted.
+    pub fn text(&mut self) -> String {
+        self.view_text(self.root)
+    }
+
+    /// The text rendered by the view `id` and its children, with a line for each line of text,
+    /// in the order it's painted.
+    pub fn view_text(&mut self, id: Id) -> String {
+        self.handle
+            .paint_svg(id)
+            .map(|(svg, _)| svg.text().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Renders the window into an image.
+    pub fn render(&mut self) -> Option<DynamicImage> {
+        self.handle.paint()
+    }
+}
+
+impl Drop for TestHarness {
+    fn drop(&mut self) {
+        self.handle.destroy();
+        set_current_view(self.running_view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::create_rw_signal;
+    use kurbo::{Point, Rect, Size, Vec2};
+
+    use super::TestHarness;
+    use crate::{
+        keyboard::{Key, ModifiersState, NamedKey},
+        view::View,
+        views::{label, scroll, v_stack, Decorators},
+    };
+
+    #[test]
+    fn click_and_text() {
+        let count = create_rw_signal(0);
+        let button = label(move || format!("Clicked {}", count.get()))
+            .on_click_stop(move |_| count.update(|count| *count += 1))
+            .style(|s| s.width(100.0).height(20.0).margin_top(10.0));
+        let button_id = button.id();
+
+        let mut harness = TestHarness::new_with_size(v_stack((button,)), Size::new(200.0, 100.0));
+        assert_eq!(
+            harness.layout_rect(button_id),
+            Rect::new(0.0, 10.0, 100.0, 30.0)
+        );
+
+        harness.click_at(Point::new(50.0, 5.0));
+        assert_eq!(count.get(), 0);
+        harness.click(button_id);
+        assert_eq!(count.get(), 1);
+        assert!(harness.is_hovered(button_id));
+        assert_eq!(harness.text(), "Clicked 1");
+    }
+
+    #[test]
+    fn scroll() {
+        let content = v_stack((label(|| "Content"),)).style(|s| s.height(300.0));
+        let content_id = content.id();
+        let view = scroll(content).style(|s| s.size(100.0, 100.0));
+
+        let mut harness = TestHarness::new_with_size(view, Size::new(100.0, 100.0));
+        assert_eq!(harness.layout_rect(content_id).y0, 0.0);
+        harness.scroll_by(Point::new(50.0, 50.0), Vec2::new(0.0, 40.0));
+        assert_eq!(harness.layout_rect(content_id).y0, -40.0);
+        assert_eq!(
+            harness.size(content_id).map(|size| size.height),
+            Some(300.0)
+        );
+    }
+
+    #[test]
+    fn key_press() {
+        let presses = create_rw_signal(Vec::new());
+        let view = label(|| "Keys")
+            .keyboard_navigatable()
+            .on_key_down(
+                Key::Named(NamedKey::Enter),
+                ModifiersState::empty(),
+                move |_| presses.update(|presses| presses.push("enter")),
+            )
+            .on_key_down(
+                Key::Character("s".into()),
+                ModifiersState::CONTROL,
+                move |_| presses.update(|presses| presses.push("save")),
+            );
+        let id = view.id();
+
+        let mut harness = TestHarness::new(view);
+        id.request_focus();
+        harness.update();
+        assert!(harness.is_focused(id));
+
+        harness.key_press(Key::Named(NamedKey::Enter), ModifiersState::empty());
+        harness.key_press(Key::Character("s".into()), ModifiersState::empty());
+        harness.key_press(Key::Character("s".into()), ModifiersState::CONTROL);
+        assert_eq!(presses.get(), vec!["enter", "save"]);
+    }
+}
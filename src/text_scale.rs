@@ -0,0 +1,98 @@
+//! The factor font sizes are multiplied by, for users who need larger text.
+//!
+//! By default it follows the text size preference of the OS, which is read on a background
+//! thread when the app starts and again when a window reports a change of the OS theme, as the
+//! desktops send their settings changes together. The app can replace it with
+//! [`set_text_scale`], for example from a zoom setting of its own.
+
+use std::{cell::Cell, process::Command};
+
+use floem_reactive::Scope;
+
+use crate::ext_event::create_ext_action;
+
+thread_local! {
+    /// The scale set by the app, which replaces the preference of the OS.
+    static APP_SCALE: Cell<Option<f64>> = const { Cell::new(None) };
+    /// The text size preference of the OS, once it was read.
+    static SYSTEM_SCALE: Cell<f64> = const { Cell::new(1.0) };
+}
+
+/// The factor the font sizes of styles are multiplied by.
+pub fn text_scale() -> f64 {
+    APP_SCALE
+        .with(Cell::get)
+        .unwrap_or_else(|| SYSTEM_SCALE.with(Cell::get))
+}
+
+/// Multiplies the font sizes of all styles by `scale`, replacing the text size preference of
+/// the OS, or follows the preference again with `None`. The windows are restyled with the new
+/// sizes on their next update.
+pub fn set_text_scale(scale: Option<f64>) {
+    APP_SCALE.with(|app| app.set(scale.map(|scale| scale.max(0.1))));
+}
+
+/// Reads the text size preference of the OS on a background thread, using it once it's read.
+pub(crate) fn refresh_system_text_scale() {
+    let apply = create_ext_action(Scope::current(), |scale: Option<f64>| {
+        SYSTEM_SCALE.with(|system| system.set(scale.unwrap_or(1.0)));
+    });
+    std::thread::spawn(move || apply(system_text_scale()));
+}
+
+/// Reads the text size preference of the OS as a factor, if the platform has one.
+///
+/// This is the text scaling factor of GNOME on Linux and the "Make text bigger" setting on
+/// Windows. It runs a command, so call it off the UI thread.
+pub fn system_text_scale() -> Option<f64> {
+    let scale = if cfg!(target_os = "windows") {
+        let output = Command::new("reg")
+            .args([
+                "query",
+                r"HKCU\Software\Microsoft\Accessibility",
+                "/v",
+                "TextScaleFactor",
+            ])
+            .output()
+            .ok()?;
+        // The value is a percentage, printed as `TextScaleFactor    REG_DWORD    0x96`
+        let output = String::from_utf8_lossy(&output.stdout);
+        let value = output.split_whitespace().last()?.strip_prefix("0x")?;
+        u32::from_str_radix(value, 16).ok()? as f64 / 100.0
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        let output = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "text-scaling-factor"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .ok()?
+    } else {
+        return None;
+    };
+    (scale > 0.0).then_some(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::set_text_scale;
+    use crate::{testing::TestHarness, view::View, views::label};
+
+    #[test]
+    fn restyles_on_change() {
+        let view = label(|| "Scaled");
+        let id = view.id();
+        let mut harness = TestHarness::new(view);
+        let height = harness.layout_rect(id).height();
+
+        set_text_scale(Some(2.0));
+        harness.update();
+        let scaled = harness.layout_rect(id).height();
+        assert!(scaled > height * 1.5, "{scaled} isn't about twice {height}");
+
+        set_text_scale(None);
+        harness.update();
+        assert_eq!(harness.layout_rect(id).height(), height);
+    }
+}
@@ -1,15 +1,23 @@
 use std::{any::Any, cell::RefCell, collections::HashMap};
 
-use floem_winit::window::ResizeDirection;
-use kurbo::{Point, Rect, Size, Vec2};
+use floem_reactive::{RwSignal, WriteSignal};
+use floem_winit::window::{ResizeDirection, Theme};
+use kurbo::{Insets, Point, Rect, Size, Vec2};
 
 use crate::{
+    action::OverlayConfig,
     animate::{AnimUpdateMsg, Animation},
-    context::{EventCallback, ResizeCallback},
+    context::{
+        AccessibilityCallback, EventCallback, HitTestCallback, PaintCallback, ResizeCallback,
+    },
     event::EventListener,
+    focus::FocusScope,
     id::Id,
+    keyboard::{Key, ModifiersState},
+    memory::MemoryReport,
     menu::Menu,
     style::{Style, StyleClassRef, StyleSelector},
+    tear_off::TearOff,
     view::View,
     view_data::{ChangeFlags, StackOffset},
 };
@@ -30,15 +38,49 @@ thread_local! {
 // pub type FileDialogs = HashMap<FileDialogToken, Box<dyn Fn(Option<FileInfo>)>>;
 type DeferredUpdateMessages = HashMap<Id, Vec<(Id, Box<dyn Any>)>>;
 
+/// Input sent to a view as if it came from the user, see [`Id::simulate_click`].
+pub(crate) enum SimulatedInput {
+    Click,
+    Key { key: Key, modifiers: ModifiersState },
+    Text(String),
+}
+
 pub(crate) enum UpdateMessage {
     Focus(Id),
     ClearFocus(Id),
+    /// Moves the focus with Tab from the view, or with Shift+Tab when `backwards`.
+    FocusNext {
+        id: Id,
+        backwards: bool,
+    },
+    FocusSignal {
+        id: Id,
+        signal: WriteSignal<bool>,
+    },
+    TabIndex {
+        id: Id,
+        index: i32,
+    },
+    FocusScope {
+        id: Id,
+        scope: FocusScope,
+    },
+    PointerLock(Id),
+    ReleasePointerLock(Id),
+    ConfineCursor {
+        confined: bool,
+    },
     Active(Id),
     WindowScale(f64),
+    WindowZoom(RwSignal<f64>),
     Disabled {
         id: Id,
         is_disabled: bool,
     },
+    Invalid {
+        id: Id,
+        is_invalid: bool,
+    },
     RequestChange {
         id: Id,
         flags: ChangeFlags,
@@ -65,6 +107,9 @@ pub(crate) enum UpdateMessage {
     KeyboardNavigable {
         id: Id,
     },
+    KeyboardNavigableGrid {
+        id: Id,
+    },
     Draggable {
         id: Id,
     },
@@ -85,6 +130,10 @@ pub(crate) enum UpdateMessage {
         id: Id,
         action: Box<dyn Fn()>,
     },
+    MountListener {
+        id: Id,
+        action: Box<dyn FnOnce(Id)>,
+    },
     ToggleWindowMaximized,
     SetWindowMaximized(bool),
     MinimizeWindow,
@@ -95,6 +144,10 @@ pub(crate) enum UpdateMessage {
         id: Id,
         animation: Animation,
     },
+    TearOff {
+        id: Id,
+        tear_off: TearOff,
+    },
     ContextMenu {
         id: Id,
         menu: Box<dyn Fn() -> Menu>,
@@ -103,6 +156,26 @@ pub(crate) enum UpdateMessage {
         id: Id,
         menu: Box<dyn Fn() -> Menu>,
     },
+    PaintUnder {
+        id: Id,
+        action: Box<PaintCallback>,
+    },
+    PaintOver {
+        id: Id,
+        action: Box<PaintCallback>,
+    },
+    HitTest {
+        id: Id,
+        action: Box<HitTestCallback>,
+    },
+    Accessibility {
+        id: Id,
+        action: Box<AccessibilityCallback>,
+    },
+    SimulateInput {
+        id: Id,
+        input: SimulatedInput,
+    },
     ShowContextMenu {
         menu: Menu,
         pos: Option<Point>,
@@ -113,15 +186,31 @@ pub(crate) enum UpdateMessage {
     SetWindowTitle {
         title: String,
     },
+    TitleSegment {
+        id: Id,
+        segment: String,
+    },
+    SetWindowTheme {
+        theme: Option<Theme>,
+    },
     AddOverlay {
         id: Id,
         position: Point,
+        config: OverlayConfig,
         view: Box<dyn FnOnce() -> Box<dyn View>>,
     },
     RemoveOverlay {
         id: Id,
     },
     Inspect,
+    ExportSvg {
+        id: Id,
+        on_export: Box<dyn FnOnce(String)>,
+    },
+    MemoryReport {
+        id: Id,
+        on_report: Box<dyn FnOnce(MemoryReport)>,
+    },
     ScrollTo {
         id: Id,
         rect: Option<Rect>,
@@ -134,4 +223,7 @@ pub(crate) enum UpdateMessage {
         position: Point,
         size: Size,
     },
+    SoftKeyboardInsets {
+        insets: Insets,
+    },
 }
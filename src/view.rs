@@ -84,15 +84,20 @@
 //!
 
 use floem_renderer::Renderer;
-use kurbo::{Circle, Insets, Line, Point, Rect, RoundedRect, Size};
+use image::{DynamicImage, RgbaImage};
+use kurbo::{Circle, Insets, Line, Point, Rect, RoundedRect, Shape, Size};
 use std::any::Any;
 use taffy::prelude::Node;
 
 use crate::{
+    accessibility::AccessCx,
     context::{AppState, ComputeLayoutCx, EventCx, LayoutCx, PaintCx, StyleCx, UpdateCx},
     event::Event,
     id::Id,
-    style::{BoxShadowProp, Style, StyleClassRef},
+    style::{
+        BackgroundImageProp, BackgroundPatternProp, BoxShadow, BoxShadowProp, LayerShadowProp,
+        Style, StyleClassRef,
+    },
     view_data::ViewStyleProps,
     EventPropagation,
 };
@@ -174,6 +179,14 @@ pub trait View {
     /// `_cx.app_state_mut().request_changes`.
     fn update(&mut self, _cx: &mut UpdateCx, _state: Box<dyn Any>) {}
 
+    /// Passes a state update on to the child starting `id_path`, the path to the descendant the
+    /// update is for. Override this to wrap the updates of the view's descendants.
+    fn update_child(&mut self, cx: &mut UpdateCx, id_path: &[Id], state: Box<dyn Any>) {
+        if let Some(child) = self.child_mut(id_path[0]) {
+            cx.update_view(child, id_path, state);
+        }
+    }
+
     /// Use this method to style the view's children.
     ///
     /// If the style changes needs other passes to run you're expected to call
@@ -234,6 +247,19 @@ pub trait View {
         });
     }
 
+    /// Describes the view to screen readers and other assistive technologies, such as its role,
+    /// label and value. The children are described by themselves.
+    ///
+    /// The actions added with [`AccessCx::add_action`] are received as
+    /// [`Event::AccessibilityAction`].
+    fn accessibility(&self, _cx: &mut AccessCx) {}
+
+    /// The number of images the view keeps to paint itself, such as decoded images and
+    /// rendered effects, for [`memory_report`](crate::memory::memory_report).
+    fn cached_images(&self) -> usize {
+        0
+    }
+
     /// Scrolls the view and all direct and indirect children to bring the `target` view to be
     /// visible. Returns true if this view contains or is the target.
     fn scroll_to(&mut self, cx: &mut AppState, target: Id, rect: Option<Rect>) -> bool {
@@ -297,34 +323,46 @@ pub(crate) fn paint_bg(
         crate::unit::PxPct::Px(px) => px,
         crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
     };
-    if radius > 0.0 {
-        let rect = size.to_rect();
-        let width = rect.width();
-        let height = rect.height();
+    let rect = size.to_rect();
+    let width = rect.width();
+    let height = rect.height();
+    let shape = if radius > 0.0 {
         if width > 0.0 && height > 0.0 && radius > width.max(height) / 2.0 {
             let radius = width.max(height) / 2.0;
             let circle = Circle::new(rect.center(), radius);
-            let bg = match style.background() {
-                Some(color) => color,
-                None => return,
-            };
-            cx.fill(&circle, bg, 0.0);
+            if let Some(bg) = style.background() {
+                cx.fill(&circle, bg, 0.0);
+            }
+            Rect::from_center_size(rect.center(), Size::new(radius * 2.0, radius * 2.0))
+                .to_rounded_rect(radius)
         } else {
             paint_box_shadow(cx, computed_style, rect, Some(radius));
-            let bg = match style.background() {
-                Some(color) => color,
-                None => return,
-            };
             let rounded_rect = rect.to_rounded_rect(radius);
-            cx.fill(&rounded_rect, bg, 0.0);
+            if let Some(bg) = style.background() {
+                cx.fill(&rounded_rect, bg, 0.0);
+            }
+            rounded_rect
         }
     } else {
-        paint_box_shadow(cx, computed_style, size.to_rect(), None);
-        let bg = match style.background() {
-            Some(color) => color,
-            None => return,
-        };
-        cx.fill(&size.to_rect(), bg, 0.0);
+        paint_box_shadow(cx, computed_style, rect, None);
+        if let Some(bg) = style.background() {
+            cx.fill(&rect, bg, 0.0);
+        }
+        rect.to_rounded_rect(0.0)
+    };
+
+    let pattern = computed_style.get(BackgroundPatternProp);
+    let image = computed_style.get(BackgroundImageProp);
+    if pattern.is_some() || image.is_some() {
+        cx.save();
+        cx.clip(&shape);
+        if let Some(pattern) = pattern {
+            pattern.paint(cx, rect);
+        }
+        if let Some(image) = image {
+            image.paint(cx, rect);
+        }
+        cx.restore();
     }
 }
 
@@ -363,6 +401,140 @@ fn paint_box_shadow(cx: &mut PaintCx, style: &Style, rect: Rect, rect_radius: Op
     }
 }
 
+/// A drop shadow with the shape of its view cut out, rendered into an image. See
+/// [`Style::layer_shadow`].
+pub(crate) struct LayerShadow {
+    shadow: BoxShadow,
+    size: Size,
+    radius: f64,
+    scale: f64,
+    img: DynamicImage,
+    hash: Vec<u8>,
+    /// Where the image is drawn, relative to the view.
+    rect: Rect,
+}
+
+impl LayerShadow {
+    fn new(id: Id, shadow: BoxShadow, size: Size, radius: f64, scale: f64) -> Option<Self> {
+        let min = size.min_side();
+        let resolve = |value: crate::unit::PxPct| match value {
+            crate::unit::PxPct::Px(px) => px,
+            crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
+        };
+        let spread = resolve(shadow.spread);
+        let blur_radius = resolve(shadow.blur_radius).max(0.0);
+        let offset = kurbo::Vec2::new(resolve(shadow.h_offset), resolve(shadow.v_offset));
+
+        let surface = size.to_rect().to_rounded_rect(radius);
+        let shadow_shape = (size.to_rect().inflate(spread, spread) + offset)
+            .to_rounded_rect((radius + spread).max(0.0));
+        // Leave room for the blur to fade out around the shadow
+        let pad = (blur_radius * 2.0).ceil();
+        let rect = shadow_shape.rect().inflate(pad, pad).expand();
+        let width = (rect.width() * scale).ceil() as u32;
+        let height = (rect.height() * scale).ceil() as u32;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        // Samples the coverage of `shape` at the pixel `(x, y)` with 4 points
+        let coverage = |shape: &RoundedRect, x: u32, y: u32| -> f64 {
+            let inside = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+                .iter()
+                .filter(|(dx, dy)| {
+                    let point = Point::new(
+                        rect.x0 + (x as f64 + dx) / scale,
+                        rect.y0 + (y as f64 + dy) / scale,
+                    );
+                    shape.contains(point)
+                })
+                .count();
+            inside as f64 / 4.0
+        };
+
+        let mut mask = image::GrayImage::from_fn(width, height, |x, y| {
+            image::Luma([(coverage(&shadow_shape, x, y) * 255.0).round() as u8])
+        });
+        if blur_radius > 0.0 {
+            // Like CSS, the blur radius is twice the standard deviation
+            mask = image::imageops::blur(&mask, (blur_radius * scale / 2.0) as f32);
+        }
+
+        let color = floem_renderer::color::to_output(shadow.color);
+        let img = RgbaImage::from_fn(width, height, |x, y| {
+            let cut = 1.0 - coverage(&surface, x, y);
+            let alpha = mask.get_pixel(x, y)[0] as f64 / 255.0 * cut * color.a as f64;
+            image::Rgba([color.r, color.g, color.b, alpha.round() as u8])
+        });
+
+        let mut hash = b"layer_shadow".to_vec();
+        hash.extend_from_slice(&id.to_raw().to_le_bytes());
+        for value in [size.width, size.height, radius, scale, blur_radius, spread] {
+            hash.extend_from_slice(&value.to_le_bytes());
+        }
+        hash.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+        hash.extend_from_slice(&offset.x.to_le_bytes());
+        hash.extend_from_slice(&offset.y.to_le_bytes());
+
+        Some(Self {
+            shadow,
+            size,
+            radius,
+            scale,
+            img: DynamicImage::ImageRgba8(img),
+            hash,
+            rect,
+        })
+    }
+}
+
+/// Paints the layer shadow of the view `id` below its background.
+pub(crate) fn paint_layer_shadow(
+    cx: &mut PaintCx,
+    id: Id,
+    computed_style: &Style,
+    style: &ViewStyleProps,
+    size: Size,
+) {
+    let Some(shadow) = computed_style.get(LayerShadowProp) else {
+        cx.app_state.view_state(id).layer_shadow = None;
+        return;
+    };
+    let radius = match style.border_radius() {
+        crate::unit::PxPct::Px(px) => px,
+        crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
+    }
+    .min(size.min_side() / 2.0);
+    let scale = cx.paint_state.scale;
+
+    let cached = cx
+        .app_state
+        .view_state(id)
+        .layer_shadow
+        .take()
+        .filter(|cached| {
+            cached.shadow == shadow
+                && cached.size == size
+                && cached.radius == radius
+                && cached.scale == scale
+        });
+    let Some(layer_shadow) = cached.or_else(|| LayerShadow::new(id, shadow, size, radius, scale))
+    else {
+        return;
+    };
+    cx.draw_img(
+        floem_renderer::Img {
+            img: &layer_shadow.img,
+            data: layer_shadow.img.as_bytes(),
+            hash: &layer_shadow.hash,
+        },
+        layer_shadow.rect,
+    );
+    cx.app_state.view_state(id).layer_shadow = Some(layer_shadow);
+}
+
+/// Paints the outline around the border box, moved out by the outline offset. The outline
+/// doesn't take part in the layout, so it can appear without moving any content.
 pub(crate) fn paint_outline(cx: &mut PaintCx, style: &ViewStyleProps, size: Size) {
     let outline = style.outline().0;
     if outline == 0. {
@@ -370,13 +542,14 @@ pub(crate) fn paint_outline(cx: &mut PaintCx, style: &ViewStyleProps, size: Size
         return;
     }
     let half = outline / 2.0;
-    let rect = size.to_rect().inflate(half, half);
+    let offset = style.outline_offset().0;
+    let rect = size.to_rect().inflate(offset + half, offset + half);
     let border_radius = match style.border_radius() {
         crate::unit::PxPct::Px(px) => px,
         crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
     };
     cx.stroke(
-        &rect.to_rounded_rect(border_radius + half),
+        &rect.to_rounded_rect((border_radius + offset + half).max(0.0)),
         style.outline_color(),
         outline,
     );
@@ -452,133 +625,6 @@ pub(crate) fn view_children(view: &dyn View) -> Vec<&dyn View> {
     result
 }
 
-/// Tab navigation finds the next or previous view with the `keyboard_navigatable` status in the tree.
-#[allow(dead_code)]
-pub(crate) fn view_tab_navigation(root_view: &dyn View, app_state: &mut AppState, backwards: bool) {
-    let start = app_state
-        .focus
-        .filter(|id| id.id_path().is_some())
-        .unwrap_or(root_view.id());
-
-    assert!(
-        view_filtered_children(root_view, start.id_path().unwrap().dispatch()).is_some(),
-        "The focused view is missing from the tree"
-    );
-
-    let tree_iter = |id: Id| {
-        if backwards {
-            view_tree_previous(root_view, &id)
-                .unwrap_or_else(|| view_nested_last_child(root_view).id())
-        } else {
-            view_tree_next(root_view, &id).unwrap_or(root_view.id())
-        }
-    };
-
-    let mut new_focus = tree_iter(start);
-    while new_focus != start && !app_state.can_focus(new_focus) {
-        new_focus = tree_iter(new_focus);
-    }
-
-    app_state.clear_focus();
-    app_state.update_focus(new_focus, true);
-}
-
-fn view_filtered_children<'a>(view: &'a dyn View, id_path: &[Id]) -> Option<Vec<&'a dyn View>> {
-    let id = id_path[0];
-    let id_path = &id_path[1..];
-
-    if id == view.id() {
-        if id_path.is_empty() {
-            Some(view_children(view))
-        } else if let Some(child) = view.child(id_path[0]) {
-            view_filtered_children(child, id_path)
-        } else {
-            None
-        }
-    } else {
-        None
-    }
-}
-
-/// Get the next item in the tree, either the first child or the next sibling of this view or of the first parent view
-fn view_tree_next(root_view: &dyn View, id: &Id) -> Option<Id> {
-    let id_path = id.id_path().unwrap();
-
-    if let Some(child) = view_filtered_children(root_view, id_path.dispatch())
-        .unwrap()
-        .into_iter()
-        .next()
-    {
-        return Some(child.id());
-    }
-
-    let mut ancestor = *id;
-    loop {
-        let id_path = ancestor.id_path().unwrap();
-        if id_path.dispatch().is_empty() {
-            return None;
-        }
-        if let Some(next_sibling) = view_next_sibling(root_view, id_path.dispatch()) {
-            return Some(next_sibling.id());
-        }
-        ancestor = ancestor.parent()?;
-    }
-}
-
-/// Get the id of the view after this one (but with the same parent and level of nesting)
-fn view_next_sibling<'a>(root_view: &'a dyn View, id_path: &[Id]) -> Option<&'a dyn View> {
-    let id = *id_path.last().unwrap();
-    let parent = &id_path[0..(id_path.len() - 1)];
-
-    if parent.is_empty() {
-        // We're the root, which has no sibling
-        return None;
-    }
-
-    let children = view_filtered_children(root_view, parent).unwrap();
-    let pos = children.iter().position(|v| v.id() == id).unwrap();
-
-    if pos + 1 < children.len() {
-        Some(children[pos + 1])
-    } else {
-        None
-    }
-}
-
-/// Get the next item in the tree, the deepest last child of the previous sibling of this view or the parent
-fn view_tree_previous(root_view: &dyn View, id: &Id) -> Option<Id> {
-    let id_path = id.id_path().unwrap();
-
-    view_previous_sibling(root_view, id_path.dispatch())
-        .map(|view| view_nested_last_child(view).id())
-        .or_else(|| {
-            (root_view.id() != *id).then_some(
-                id.parent()
-                    .unwrap_or_else(|| view_nested_last_child(root_view).id()),
-            )
-        })
-}
-
-/// Get the id of the view before this one (but with the same parent and level of nesting)
-fn view_previous_sibling<'a>(root_view: &'a dyn View, id_path: &[Id]) -> Option<&'a dyn View> {
-    let id = *id_path.last().unwrap();
-    let parent = &id_path[0..(id_path.len() - 1)];
-
-    if parent.is_empty() {
-        // We're the root, which has no sibling
-        return None;
-    }
-
-    let children = view_filtered_children(root_view, parent).unwrap();
-    let pos = children.iter().position(|v| v.id() == id).unwrap();
-
-    if pos > 0 {
-        Some(children[pos - 1])
-    } else {
-        None
-    }
-}
-
 pub(crate) fn view_children_set_parent_id(view: &dyn View) {
     let parent_id = view.id();
     view.for_each_child(&mut |child| {
@@ -588,14 +634,6 @@ pub(crate) fn view_children_set_parent_id(view: &dyn View) {
     });
 }
 
-fn view_nested_last_child(view: &dyn View) -> &dyn View {
-    let mut last_child = view;
-    while let Some(new_last_child) = view_children(last_child).pop() {
-        last_child = new_last_child;
-    }
-    last_child
-}
-
 /// Produces an ascii art debug display of all of the views.
 #[allow(dead_code)]
 pub(crate) fn view_debug_tree(root_view: &dyn View) {
@@ -676,6 +714,10 @@ impl View for Box<dyn View> {
         (**self).update(cx, state)
     }
 
+    fn update_child(&mut self, cx: &mut UpdateCx, id_path: &[Id], state: Box<dyn Any>) {
+        (**self).update_child(cx, id_path, state)
+    }
+
     fn style(&mut self, cx: &mut StyleCx) {
         (**self).style(cx)
     }
@@ -701,6 +743,14 @@ impl View for Box<dyn View> {
         (**self).paint(cx)
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        (**self).accessibility(cx)
+    }
+
+    fn cached_images(&self) -> usize {
+        (**self).cached_images()
+    }
+
     fn scroll_to(&mut self, cx: &mut AppState, target: Id, rect: Option<Rect>) -> bool {
         (**self).scroll_to(cx, target, rect)
     }
@@ -1,21 +1,28 @@
 use crate::{
     animate::{AnimPropKind, Animation},
-    context::{EventCallback, InteractionState, MenuCallback, MoveListener, ResizeListener},
+    context::{
+        AccessibilityCallback, EventCallback, HitTestCallback, InteractionState, MenuCallback,
+        MoveListener, PaintCallback, ResizeListener,
+    },
     event::EventListener,
+    focus::FocusScope,
     id::{Id, ID_PATHS},
+    memory,
     pointer::PointerInputEvent,
     prop_extracter,
     responsive::ScreenSizeBp,
     style::{
         Background, BorderBottom, BorderColor, BorderLeft, BorderRadius, BorderRight, BorderTop,
-        LayoutProps, Outline, OutlineColor, Style, StyleClassRef, StyleSelectors,
+        LayoutProps, Outline, OutlineColor, OutlineOffset, Style, StyleClassRef, StyleSelectors,
     },
-    view::View,
+    tear_off::TearOff,
+    view::{LayerShadow, View},
 };
 use bitflags::bitflags;
+use floem_reactive::{Scope, WriteSignal};
 use kurbo::Rect;
 use smallvec::SmallVec;
-use std::{collections::HashMap, marker::PhantomData, time::Duration};
+use std::{collections::HashMap, marker::PhantomData, rc::Rc, time::Duration};
 use taffy::node::Node;
 
 /// A stack of view attributes. Each entry is associated with a view decorator call.
@@ -65,14 +72,19 @@ pub struct ViewData {
     pub(crate) id: Id,
     pub(crate) style: Stack<Style>,
     pub(crate) event_handlers: Vec<Box<EventCallback>>,
+    /// The scope current when the view was created, which the view's signals belong to.
+    pub(crate) scope: Scope,
 }
 
 impl ViewData {
     pub fn new(id: Id) -> Self {
+        let scope = Scope::current();
+        memory::view_created(scope);
         Self {
             id,
             style: Default::default(),
             event_handlers: Default::default(),
+            scope,
         }
     }
     pub fn id(&self) -> Id {
@@ -117,6 +129,7 @@ prop_extracter! {
 
         pub outline: Outline,
         pub outline_color: OutlineColor,
+        pub outline_offset: OutlineOffset,
         pub border_color: BorderColor,
         pub background: Background,
     }
@@ -142,7 +155,9 @@ pub struct ViewState {
     pub(crate) layout_rect: Rect,
     pub(crate) layout_props: LayoutProps,
     pub(crate) view_style_props: ViewStyleProps,
-    pub(crate) animation: Option<Animation>,
+    /// Applied in order, so the later animations of a property override the earlier ones.
+    pub(crate) animations: Vec<Animation>,
+    pub(crate) tear_off: Option<TearOff>,
     pub(crate) class: Option<StyleClassRef>,
     pub(crate) dragging_style: Option<Style>,
     pub(crate) combined_style: Style,
@@ -150,16 +165,35 @@ pub struct ViewState {
     pub(crate) event_listeners: HashMap<EventListener, Box<EventCallback>>,
     pub(crate) context_menu: Option<Box<MenuCallback>>,
     pub(crate) popout_menu: Option<Box<MenuCallback>>,
+    /// Custom painting below the view's content, above its background.
+    pub(crate) paint_under: Option<Rc<PaintCallback>>,
+    /// Custom painting above the view's content and border.
+    pub(crate) paint_over: Option<Rc<PaintCallback>>,
+    /// Decides which points of the view receive pointer events, in addition to its bounds.
+    pub(crate) hit_test: Option<Box<HitTestCallback>>,
+    /// Describes the view to assistive technologies after `View::accessibility`.
+    pub(crate) accessibility: Option<Box<AccessibilityCallback>>,
     pub(crate) resize_listener: Option<ResizeListener>,
     pub(crate) move_listener: Option<MoveListener>,
     pub(crate) cleanup_listener: Option<Box<dyn Fn()>>,
     pub(crate) last_pointer_down: Option<PointerInputEvent>,
+    /// The rendered layer shadow, kept until the view's size or shadow changes.
+    pub(crate) layer_shadow: Option<LayerShadow>,
+    /// Orders the view in the Tab order, a negative index leaving it out.
+    pub(crate) tab_index: Option<i32>,
+    pub(crate) focus_scope: Option<FocusScope>,
+    /// The signals of [`Id::is_focused`], set when the view gains or loses the focus.
+    pub(crate) focus_signals: Vec<WriteSignal<bool>>,
 }
 
 impl ViewState {
     pub(crate) fn new(taffy: &mut taffy::Taffy) -> Self {
+        Self::with_node(taffy.new_leaf(taffy::style::Style::DEFAULT).unwrap())
+    }
+
+    fn with_node(node: Node) -> Self {
         Self {
-            node: taffy.new_leaf(taffy::style::Style::DEFAULT).unwrap(),
+            node,
             viewport: None,
             layout_rect: Rect::ZERO,
             layout_props: Default::default(),
@@ -167,7 +201,8 @@ impl ViewState {
             requested_changes: ChangeFlags::all(),
             request_style_recursive: false,
             has_style_selectors: StyleSelectors::default(),
-            animation: None,
+            animations: Vec::new(),
+            tear_off: None,
             class: None,
             combined_style: Style::new(),
             taffy_style: taffy::style::Style::DEFAULT,
@@ -175,13 +210,26 @@ impl ViewState {
             event_listeners: HashMap::new(),
             context_menu: None,
             popout_menu: None,
+            paint_under: None,
+            paint_over: None,
+            hit_test: None,
+            accessibility: None,
             resize_listener: None,
             move_listener: None,
             cleanup_listener: None,
+            layer_shadow: None,
             last_pointer_down: None,
+            tab_index: None,
+            focus_scope: None,
+            focus_signals: Vec::new(),
         }
     }
 
+    /// Drops everything set on the state, keeping its layout node.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::with_node(self.node);
+    }
+
     /// Returns `true` if a new frame is requested.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn compute_style(
@@ -206,38 +254,38 @@ impl ViewState {
             .apply_classes_from_context(classes, context)
             .apply(view_data.style());
 
-        'anim: {
-            if let Some(animation) = self.animation.as_mut() {
-                if animation.is_completed() && animation.is_auto_reverse() {
-                    break 'anim;
-                }
+        for animation in self.animations.iter_mut() {
+            if animation.is_completed() && animation.is_auto_reverse() {
+                continue;
+            }
 
-                new_frame = true;
-
-                let props = animation.props();
-
-                for kind in props.keys() {
-                    let val =
-                        animation.animate_prop(animation.elapsed().unwrap_or(Duration::ZERO), kind);
-                    match kind {
-                        AnimPropKind::Width => {
-                            computed_style = computed_style.width(val.get_f32());
-                        }
-                        AnimPropKind::Height => {
-                            computed_style = computed_style.height(val.get_f32());
-                        }
-                        AnimPropKind::Prop { prop } => {
-                            computed_style
-                                .map
-                                .insert(*prop, crate::style::StyleMapValue::Val(val.get_any()));
-                        }
-                        AnimPropKind::Scale => todo!(),
+            // Completed animations keep their end values without new frames
+            new_frame |= !animation.is_completed();
+
+            let elapsed = animation.elapsed().unwrap_or(Duration::ZERO);
+            let props = animation.props();
+
+            for kind in props.keys() {
+                let val = animation.animate_prop(elapsed, kind);
+                match kind {
+                    AnimPropKind::Width => {
+                        computed_style = computed_style.width(val.get_f32());
+                    }
+                    AnimPropKind::Height => {
+                        computed_style = computed_style.height(val.get_f32());
                     }
+                    AnimPropKind::Prop { prop } => {
+                        computed_style
+                            .map
+                            .insert(*prop, crate::style::StyleMapValue::Val(val.get_any()));
+                    }
+                    AnimPropKind::Scale => todo!(),
                 }
-
-                animation.advance();
-                debug_assert!(!animation.is_idle());
             }
+            animation.animate_keyframes(elapsed, &mut computed_style);
+
+            animation.advance();
+            debug_assert!(!animation.is_idle());
         }
 
         self.has_style_selectors = computed_style.selectors();
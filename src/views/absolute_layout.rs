@@ -0,0 +1,415 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{as_child_of_current_scope, create_effect, Scope};
+use floem_renderer::Renderer;
+use kurbo::{Line, Point, Rect, Size};
+
+use crate::{
+    context::{AppState, EventCx, PaintCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    view::{default_event, view_children_set_parent_id, View, ViewData},
+};
+
+use super::{
+    dyn_stack::{apply_diff, diff_items, Diff},
+    Decorators, Snapping,
+};
+
+const GUIDE_COLOR: Color = Color::rgb8(0xec, 0x48, 0x99);
+
+enum AbsoluteLayoutUpdate {
+    ZIndex(Id, i32),
+    Draggable(Id, Draggable),
+}
+
+#[derive(Clone)]
+struct Draggable {
+    position: Rc<dyn Fn() -> Point>,
+    on_drag: Rc<dyn Fn(Point)>,
+}
+
+struct Drag {
+    id: Id,
+    pointer: Point,
+    /// The position and the layout rectangle of the view when the drag started.
+    position: Point,
+    rect: Rect,
+    /// The layout rectangles of the views to snap to.
+    others: Vec<Rect>,
+}
+
+/// A view placed at reactive coordinates in an [`absolute_layout`]. See [`placed`]
+pub struct Placed {
+    view: Box<dyn View>,
+    position: Rc<dyn Fn() -> Point>,
+    z_index: Option<Box<dyn Fn() -> i32>>,
+    on_drag: Option<Rc<dyn Fn(Point)>>,
+}
+
+/// Places `view` in an [`absolute_layout`] with its top left corner at `position`, relative
+/// to the padding box of the layout.
+pub fn placed<V: View + 'static>(view: V, position: impl Fn() -> Point + 'static) -> Placed {
+    let position: Rc<dyn Fn() -> Point> = Rc::new(position);
+    let view = view.style({
+        let position = position.clone();
+        move |s| {
+            let position = position();
+            s.absolute().inset_left(position.x).inset_top(position.y)
+        }
+    });
+    Placed {
+        view: Box::new(view),
+        position,
+        z_index: None,
+        on_drag: None,
+    }
+}
+
+impl Placed {
+    /// Sizes the view explicitly, instead of by its own style and content.
+    pub fn size(mut self, size: impl Fn() -> Size + 'static) -> Self {
+        self.view = self.view.style(move |s| {
+            let size = size();
+            s.width(size.width).height(size.height)
+        });
+        self
+    }
+
+    /// Paints the view above the views with lower z indexes, and gives it the pointer events
+    /// before them. Views with the same z index are stacked in order, the last on top.
+    pub fn z_index(mut self, z_index: impl Fn() -> i32 + 'static) -> Self {
+        self.z_index = Some(Box::new(z_index));
+        self
+    }
+
+    /// Lets the view be dragged with the primary pointer button, calling `on_drag` with each
+    /// position it's dragged to, which should move the view by changing what the position of
+    /// [`placed`] returns. Presses the view handles itself, such as on a button in it, don't
+    /// start a drag. See [`AbsoluteLayout::snapping`]
+    ///
+    /// ```ignore
+    /// placed(card(), move || pos.get()).on_drag(move |new_pos| pos.set(new_pos))
+    /// ```
+    pub fn on_drag(mut self, on_drag: impl Fn(Point) + 'static) -> Self {
+        self.on_drag = Some(Rc::new(on_drag));
+        self
+    }
+
+    fn into_view(self, layout: Id) -> Box<dyn View> {
+        let id = self.view.id();
+        if let Some(z_index) = self.z_index {
+            create_effect(move |_| {
+                layout.update_state(AbsoluteLayoutUpdate::ZIndex(id, z_index()));
+            });
+        }
+        if let Some(on_drag) = self.on_drag {
+            let draggable = Draggable {
+                position: self.position,
+                on_drag,
+            };
+            layout.update_state(AbsoluteLayoutUpdate::Draggable(id, draggable));
+        }
+        self.view
+    }
+}
+
+/// A container which places its children at coordinates instead of by flex or grid layout.
+/// See [`absolute_layout`] and [`dyn_absolute_layout`]
+pub struct AbsoluteLayout<T: 'static> {
+    data: ViewData,
+    /// The children in the order of their items, which the diffs of the items refer to.
+    children: Vec<Option<(Box<dyn View>, Scope)>>,
+    view_fn: Box<dyn Fn(T) -> (Box<dyn View>, Scope)>,
+    z_indexes: HashMap<Id, i32>,
+    draggables: HashMap<Id, Draggable>,
+    snapping: Option<Snapping>,
+    drag: Option<Drag>,
+    /// The guide lines showing what the dragged view is aligned with.
+    guides: Vec<Line>,
+    phantom: PhantomData<T>,
+}
+
+/// A container for whiteboards, design tools and the like, where each child is [`placed`] at
+/// reactive coordinates, optionally with an explicit size, regardless of its siblings.
+///
+/// The children are painted in order, and the last ones get the pointer events first, unless
+/// [`Placed::z_index`] orders them otherwise. Children can be dragged with
+/// [`Placed::on_drag`], snapping into place with [`AbsoluteLayout::snapping`].
+///
+/// ```ignore
+/// let pos = create_rw_signal(Point::new(40.0, 40.0));
+/// absolute_layout([
+///     placed(label(|| "Background"), || Point::ZERO).z_index(|| -1),
+///     placed(empty().style(|s| s.background(Color::RED)), move || pos.get())
+///         .size(|| Size::new(100.0, 60.0)),
+/// ])
+/// .style(|s| s.size(800.0, 600.0))
+/// ```
+pub fn absolute_layout(children: impl IntoIterator<Item = Placed>) -> AbsoluteLayout<Placed> {
+    let id = Id::next();
+    let view_fn = as_child_of_current_scope(move |placed: Placed| placed.into_view(id));
+    let children = children
+        .into_iter()
+        .map(|placed| {
+            let (child, scope) = view_fn(placed);
+            child.id().set_parent(id);
+            view_children_set_parent_id(child.as_ref());
+            Some((child, scope))
+        })
+        .collect();
+    AbsoluteLayout {
+        data: ViewData::new(id),
+        children,
+        view_fn: Box::new(view_fn),
+        z_indexes: HashMap::new(),
+        draggables: HashMap::new(),
+        snapping: None,
+        drag: None,
+        guides: Vec::new(),
+        phantom: PhantomData,
+    }
+}
+
+/// An [`absolute_layout`] whose children are kept up to date with `each_fn` like a
+/// [`dyn_stack`](super::dyn_stack), for shapes added and removed from a whiteboard.
+///
+/// ```ignore
+/// dyn_absolute_layout(
+///     move || shapes.get(),
+///     |shape| shape.id,
+///     |shape| placed(shape_view(&shape), move || shape.position.get()).z_index(move || shape.z),
+/// )
+/// ```
+pub fn dyn_absolute_layout<IF, I, T, KF, K, VF>(
+    each_fn: IF,
+    key_fn: KF,
+    view_fn: VF,
+) -> AbsoluteLayout<T>
+where
+    IF: Fn() -> I + 'static,
+    I: IntoIterator<Item = T>,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + 'static,
+    VF: Fn(T) -> Placed + 'static,
+    T: 'static,
+{
+    let id = Id::next();
+    diff_items(id, each_fn, key_fn);
+    let view_fn = as_child_of_current_scope(move |item: T| view_fn(item).into_view(id));
+    AbsoluteLayout {
+        data: ViewData::new(id),
+        children: Vec::new(),
+        view_fn: Box::new(view_fn),
+        z_indexes: HashMap::new(),
+        draggables: HashMap::new(),
+        snapping: None,
+        drag: None,
+        guides: Vec::new(),
+        phantom: PhantomData,
+    }
+}
+
+impl<T> AbsoluteLayout<T> {
+    /// Snaps the views dragged by [`Placed::on_drag`] to a grid and to the edges and centers of
+    /// the other views, showing guide lines through the ones they're aligned with. Holding Alt
+    /// drags freely.
+    pub fn snapping(mut self, snapping: Snapping) -> Self {
+        self.snapping = Some(snapping);
+        self
+    }
+
+    /// The layout rectangles of the children back to front, relative to this view.
+    fn child_rects(&self, app_state: &mut AppState) -> Vec<(Id, Rect)> {
+        let origin = app_state.get_layout_rect(self.id()).origin().to_vec2();
+        self.stacked()
+            .into_iter()
+            .map(|child| (child.id(), app_state.get_layout_rect(child.id()) - origin))
+            .collect()
+    }
+
+    /// The children back to front, by their z indexes and then in order.
+    fn stacked(&self) -> Vec<&dyn View> {
+        let mut children: Vec<_> = self
+            .children
+            .iter()
+            .flatten()
+            .map(|(child, _)| child.as_ref())
+            .collect();
+        children.sort_by_key(|child| self.z_index(child.id()));
+        children
+    }
+
+    fn stacked_mut(&mut self) -> Vec<&mut dyn View> {
+        let z_indexes = &self.z_indexes;
+        let mut children: Vec<_> = self
+            .children
+            .iter_mut()
+            .flatten()
+            .map(|(child, _)| child.as_mut())
+            .collect();
+        children.sort_by_key(|child| z_indexes.get(&child.id()).copied().unwrap_or(0));
+        children
+    }
+
+    fn z_index(&self, id: Id) -> i32 {
+        self.z_indexes.get(&id).copied().unwrap_or(0)
+    }
+}
+
+impl<T> View for AbsoluteLayout<T> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for child in self.stacked() {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for child in self.stacked_mut() {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for child in self.stacked_mut().into_iter().rev() {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "AbsoluteLayout".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        let state = match state.downcast::<AbsoluteLayoutUpdate>() {
+            Ok(update) => {
+                match *update {
+                    AbsoluteLayoutUpdate::ZIndex(id, z_index) => {
+                        self.z_indexes.insert(id, z_index);
+                    }
+                    AbsoluteLayoutUpdate::Draggable(id, draggable) => {
+                        self.draggables.insert(id, draggable);
+                    }
+                }
+                cx.app_state.request_paint(self.id());
+                return;
+            }
+            Err(state) => state,
+        };
+        if let Ok(diff) = state.downcast::<Diff<T>>() {
+            apply_diff(
+                self.id(),
+                cx.app_state,
+                *diff,
+                &mut self.children,
+                &self.view_fn,
+            );
+            let children = &self.children;
+            let is_child = |id: &Id| {
+                children
+                    .iter()
+                    .flatten()
+                    .any(|(child, _)| child.id() == *id)
+            };
+            self.z_indexes.retain(|id, _| is_child(id));
+            self.draggables.retain(|id, _| is_child(id));
+            if self.drag.as_ref().is_some_and(|drag| !is_child(&drag.id)) {
+                self.drag = None;
+                self.guides.clear();
+            }
+            cx.request_all(self.id());
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if self.drag.is_none() && default_event(self, cx, id_path, event.clone()).is_processed() {
+            return EventPropagation::Stop;
+        }
+
+        match &event {
+            Event::PointerDown(pointer) if pointer.button.is_primary() => {
+                let rects = self.child_rects(cx.app_state);
+                // Only the topmost view under the pointer can be dragged
+                let Some(&(id, rect)) = rects.iter().rev().find(|(_, r)| r.contains(pointer.pos))
+                else {
+                    return EventPropagation::Continue;
+                };
+                let Some(draggable) = self.draggables.get(&id) else {
+                    return EventPropagation::Continue;
+                };
+                self.drag = Some(Drag {
+                    id,
+                    pointer: pointer.pos,
+                    position: (draggable.position)(),
+                    rect,
+                    others: rects
+                        .iter()
+                        .filter(|(other, _)| *other != id)
+                        .map(|(_, rect)| *rect)
+                        .collect(),
+                });
+                cx.update_active(self.id());
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer) => {
+                let Some(drag) = &self.drag else {
+                    return EventPropagation::Continue;
+                };
+                let delta = pointer.pos - drag.pointer;
+                let (position, guides) = match self.snapping {
+                    Some(snapping) if !pointer.modifiers.alt_key() => {
+                        snapping.snap(drag.position + delta, drag.rect + delta, &drag.others)
+                    }
+                    _ => (drag.position + delta, Vec::new()),
+                };
+                if let Some(draggable) = self.draggables.get(&drag.id) {
+                    (draggable.on_drag)(position);
+                }
+                if guides != self.guides {
+                    self.guides = guides;
+                    cx.app_state.request_paint(self.id());
+                }
+                EventPropagation::Stop
+            }
+            Event::PointerUp(_) if self.drag.is_some() => {
+                self.drag = None;
+                self.guides.clear();
+                cx.app_state.request_paint(self.id());
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        for child in self.stacked_mut() {
+            cx.paint_view(child);
+        }
+        for guide in &self.guides {
+            cx.stroke(guide, GUIDE_COLOR, 1.0);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::rc::Rc;
+
+use floem_peniko::Color;
+use floem_reactive::create_effect;
+use floem_renderer::Renderer;
+use image::{DynamicImage, GenericImageView};
+use kurbo::Size;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    context::{PaintCx, StyleCx, UpdateCx},
+    id::Id,
+    style::Overflow,
+    style_class,
+    unit::UnitExt,
+    view::{View, ViewData},
+    views::{label, Decorators, Label, ObjectFit, Shimmer, SkeletonStyle},
+};
+
+style_class!(pub AvatarClass);
+
+/// The backgrounds of the letter fallback, picked by the name so a person keeps their color.
+const FALLBACK_COLORS: [Color; 8] = [
+    Color::rgb8(0xe5, 0x73, 0x73),
+    Color::rgb8(0xf0, 0x62, 0x92),
+    Color::rgb8(0xba, 0x68, 0xc8),
+    Color::rgb8(0x79, 0x86, 0xcb),
+    Color::rgb8(0x4f, 0xc3, 0xf7),
+    Color::rgb8(0x4d, 0xb6, 0xac),
+    Color::rgb8(0x81, 0xc7, 0x84),
+    Color::rgb8(0xff, 0xb7, 0x4d),
+];
+
+enum AvatarUpdate {
+    Image(Option<Rc<DynamicImage>>),
+    Name(String),
+    Loading(bool),
+}
+
+/// A picture of a person or an entity. See [`avatar`]
+pub struct Avatar {
+    data: ViewData,
+    initials: Label,
+    img: Option<Rc<DynamicImage>>,
+    img_hash: Option<Vec<u8>>,
+    fit: ObjectFit,
+    fallback_color: Color,
+    loading: bool,
+    loading_style: SkeletonStyle,
+    shimmer: Shimmer,
+}
+
+/// A picture of a person or an entity, cropped to a circle.
+///
+/// `image` gives the encoded picture, and the initials of `name` are shown on a colored
+/// background when there's none. Set [`Avatar::loading`] to show a shimmer while the picture
+/// is loading, and [`Avatar::fit`] to choose how the picture is fitted to the avatar.
+///
+/// The avatar is 40 pixels wide by default. Set a border radius to crop it to a rounded
+/// rectangle instead:
+///
+/// ```ignore
+/// avatar(move || photo.get(), move || user.get().name)
+///     .style(|s| s.size(64.0, 64.0).border_radius(8.0))
+/// ```
+pub fn avatar(
+    image: impl Fn() -> Option<Vec<u8>> + 'static,
+    name: impl Fn() -> String + 'static,
+) -> Avatar {
+    let id = Id::next();
+    create_effect(move |_| {
+        let img = image().and_then(|image| image::load_from_memory(&image).ok().map(Rc::new));
+        id.update_state(AvatarUpdate::Image(img));
+    });
+    let name = Rc::new(name);
+    {
+        let name = name.clone();
+        create_effect(move |_| {
+            id.update_state(AvatarUpdate::Name(name()));
+        });
+    }
+    let initials = label(move || initials_of(&name()))
+        .style(|s| s.color(Color::WHITE).font_size(16.0).font_bold());
+    Avatar {
+        data: ViewData::new(id),
+        initials,
+        img: None,
+        img_hash: None,
+        fit: ObjectFit::Cover,
+        fallback_color: FALLBACK_COLORS[0],
+        loading: false,
+        loading_style: Default::default(),
+        shimmer: Default::default(),
+    }
+    .class(AvatarClass)
+    .style(|s| {
+        s.size(40.0, 40.0)
+            .border_radius(50.pct())
+            .overflow(Overflow::Hidden)
+            .items_center()
+            .justify_center()
+    })
+}
+
+impl Avatar {
+    /// Shows a shimmer instead of the picture or the initials while `loading` is true.
+    pub fn loading(self, loading: impl Fn() -> bool + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_state(AvatarUpdate::Loading(loading()));
+        });
+        self
+    }
+
+    /// How the picture is fitted to the avatar. Pictures [cover](ObjectFit::Cover) it by
+    /// default, cropping whichever side doesn't fit.
+    pub fn fit(mut self, fit: ObjectFit) -> Self {
+        self.fit = fit;
+        self
+    }
+}
+
+/// The first letters of the first and the last word of `name`.
+fn initials_of(name: &str) -> String {
+    let mut words = name.split_whitespace();
+    let first = words.next().and_then(|word| word.chars().next());
+    let last = words.last().and_then(|word| word.chars().next());
+    first
+        .into_iter()
+        .chain(last)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn fallback_color(name: &str) -> Color {
+    let hash = Sha256::digest(name.trim().as_bytes());
+    FALLBACK_COLORS[hash[0] as usize % FALLBACK_COLORS.len()]
+}
+
+impl View for Avatar {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.initials);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.initials);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.initials);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Avatar".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(update) = state.downcast::<AvatarUpdate>() {
+            match *update {
+                AvatarUpdate::Image(img) => {
+                    self.img_hash = img.as_ref().map(|img| {
+                        let mut hasher = Sha256::new();
+                        hasher.update(img.as_bytes());
+                        hasher.finalize().to_vec()
+                    });
+                    self.img = img;
+                }
+                AvatarUpdate::Name(name) => {
+                    self.fallback_color = fallback_color(&name);
+                }
+                AvatarUpdate::Loading(loading) => {
+                    self.loading = loading;
+                }
+            }
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        self.loading_style.read(cx);
+        cx.style_view(&mut self.initials);
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let rect = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default()
+            .to_rect();
+        // The picture and the shimmer are cropped by the overflow clip of the avatar
+        if self.loading {
+            self.shimmer
+                .paint(cx, &self.loading_style, rect.to_rounded_rect(0.0), rect);
+            self.shimmer.request_frame(self.id());
+        } else if let (Some(img), Some(hash)) = (self.img.as_ref(), self.img_hash.as_ref()) {
+            let (width, height) = img.dimensions();
+            let content = cx.get_content_rect(self.id());
+            let dest = self
+                .fit
+                .rect(Size::new(width as f64, height as f64), content);
+            cx.draw_img(
+                floem_renderer::Img {
+                    img,
+                    data: img.as_bytes(),
+                    hash,
+                },
+                dest,
+            );
+        } else {
+            cx.fill(&rect, self.fallback_color, 0.0);
+            cx.paint_view(&mut self.initials);
+        }
+    }
+
+    fn cached_images(&self) -> usize {
+        self.img.is_some() as usize
+    }
+}
@@ -0,0 +1,324 @@
+use std::{cell::Cell, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Rect, Size, Vec2};
+use taffy::style::FlexWrap;
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{ComputeLayoutCx, UpdateCx},
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style::CursorStyle,
+    style_class,
+    view::{default_compute_layout, View, ViewData},
+    views::{dyn_stack, h_stack, label, text_input, Decorators},
+};
+
+style_class!(pub ChipsInputClass);
+style_class!(pub ChipClass);
+style_class!(pub ChipSuggestionsClass);
+
+/// The characters which commit the typed text to a chip, also used to split pasted text.
+const SEPARATORS: [char; 3] = [',', '\n', '\t'];
+
+enum ChipsUpdate {
+    Suggestions(Vec<String>),
+}
+
+/// A text field collecting entries as chips. See [`chips_input`]
+pub struct ChipsInput {
+    data: ViewData,
+    child: Box<dyn View>,
+    chips: RwSignal<Vec<String>>,
+    buffer: RwSignal<String>,
+    input: Id,
+    suggestions: RwSignal<Vec<String>>,
+    highlighted: RwSignal<Option<usize>>,
+    overlay: Option<Id>,
+    window_origin: Point,
+    size: Size,
+}
+
+/// Adds the trimmed `text` to `chips` unless it's empty or already there.
+fn commit(chips: RwSignal<Vec<String>>, text: &str) {
+    let text = text.trim();
+    if text.is_empty() || chips.with_untracked(|chips| chips.iter().any(|chip| chip == text)) {
+        return;
+    }
+    chips.update(|chips| chips.push(text.to_string()));
+}
+
+/// A text field where the typed text is committed to a removable chip when pressing `Enter`
+/// or typing a comma, such as for email recipients or labels.
+///
+/// Pressing `Backspace` in the empty field removes the last chip, and pasted text is split
+/// into chips at commas, tabs and line breaks. Chips can also be removed by clicking their
+/// close button. Duplicates aren't added.
+///
+/// Use [`ChipsInput::suggestions`] to show suggestions for the typed text below the field.
+pub fn chips_input(chips: RwSignal<Vec<String>>) -> ChipsInput {
+    let buffer = create_rw_signal(String::new());
+    let suggestions = create_rw_signal(Vec::new());
+    let highlighted = create_rw_signal(None);
+
+    // Commit everything before the last separator, which also splits pasted text
+    create_effect(move |_| {
+        let text = buffer.get();
+        if let Some(end) = text.rfind(SEPARATORS) {
+            for part in text[..end].split(SEPARATORS) {
+                commit(chips, part);
+            }
+            buffer.set(text[end + 1..].to_string());
+        }
+    });
+
+    let chip_list = dyn_stack(
+        move || chips.get(),
+        |chip| chip.clone(),
+        move |chip| {
+            let removed = chip.clone();
+            h_stack((
+                label(move || chip.clone()),
+                label(|| "×")
+                    .on_click_stop(move |_| {
+                        chips.update(|chips| chips.retain(|chip| *chip != removed));
+                    })
+                    .style(|s| s.padding_horiz(2.0).cursor(CursorStyle::Pointer)),
+            ))
+            .class(ChipClass)
+            .style(|s| s.items_center().gap(4.0, 0.0))
+        },
+    )
+    .style(|s| s.flex_wrap(FlexWrap::Wrap).gap(4.0, 4.0));
+
+    // Backspace only removes a chip when the field was already empty before it was pressed
+    let was_empty = Rc::new(Cell::new(true));
+    let input = text_input(buffer)
+        .on_event(EventListener::KeyDown, move |event| {
+            let Event::KeyDown(key_event) = event else {
+                return EventPropagation::Continue;
+            };
+            let empty = buffer.with_untracked(|buffer| buffer.is_empty());
+            let handled = match key_event.key.logical_key {
+                Key::Named(NamedKey::Enter) => {
+                    match highlighted.get_untracked() {
+                        Some(index) => {
+                            if let Some(suggestion) =
+                                suggestions.with_untracked(|list| list.get(index).cloned())
+                            {
+                                commit(chips, &suggestion);
+                            }
+                        }
+                        None => commit(chips, &buffer.get_untracked()),
+                    }
+                    buffer.set(String::new());
+                    true
+                }
+                Key::Named(NamedKey::Backspace) if empty && was_empty.get() => {
+                    chips.update(|chips| {
+                        chips.pop();
+                    });
+                    true
+                }
+                Key::Named(NamedKey::ArrowDown) => {
+                    let count = suggestions.with_untracked(|list| list.len());
+                    if count > 0 {
+                        highlighted.update(|highlighted| {
+                            *highlighted = Some(highlighted.map_or(0, |index| (index + 1) % count));
+                        });
+                    }
+                    count > 0
+                }
+                Key::Named(NamedKey::ArrowUp) => {
+                    let count = suggestions.with_untracked(|list| list.len());
+                    if count > 0 {
+                        highlighted.update(|highlighted| {
+                            *highlighted = Some(
+                                highlighted.map_or(count - 1, |index| (index + count - 1) % count),
+                            );
+                        });
+                    }
+                    count > 0
+                }
+                Key::Named(NamedKey::Escape) => {
+                    let open = suggestions.with_untracked(|list| !list.is_empty());
+                    suggestions.set(Vec::new());
+                    open
+                }
+                _ => false,
+            };
+            was_empty.set(buffer.with_untracked(|buffer| buffer.is_empty()));
+            if handled {
+                EventPropagation::Stop
+            } else {
+                EventPropagation::Continue
+            }
+        })
+        .style(|s| s.flex_grow(1.0).min_width(60.0).border(0.0));
+    let input_id = input.id();
+
+    let child = h_stack((chip_list, input))
+        .style(|s| s.items_center().flex_wrap(FlexWrap::Wrap).gap(4.0, 4.0));
+
+    let id = Id::next();
+    create_effect(move |_| {
+        id.update_state(ChipsUpdate::Suggestions(suggestions.get()));
+    });
+
+    ChipsInput {
+        data: ViewData::new(id),
+        child: Box::new(child),
+        chips,
+        buffer,
+        input: input_id,
+        suggestions,
+        highlighted,
+        overlay: None,
+        window_origin: Point::ZERO,
+        size: Size::ZERO,
+    }
+    .class(ChipsInputClass)
+    .on_click_stop(move |_| input_id.request_focus())
+}
+
+impl ChipsInput {
+    /// Shows the suggestions `suggest` returns for the typed text in a list below the field.
+    /// Clicking a suggestion, or choosing it with the arrow keys and pressing `Enter`, adds it
+    /// as a chip.
+    pub fn suggestions(self, suggest: impl Fn(&str) -> Vec<String> + 'static) -> Self {
+        let buffer = self.buffer;
+        let chips = self.chips;
+        let suggestions = self.suggestions;
+        let highlighted = self.highlighted;
+        create_effect(move |_| {
+            let text = buffer.get();
+            let text = text.trim();
+            let list = if text.is_empty() {
+                Vec::new()
+            } else {
+                chips.with(|chips| {
+                    suggest(text)
+                        .into_iter()
+                        .filter(|suggestion| !chips.contains(suggestion))
+                        .collect()
+                })
+            };
+            highlighted.set(None);
+            suggestions.set(list);
+        });
+        let input = self.input;
+        input.update_event_listener(
+            EventListener::FocusLost,
+            Box::new(move |_| {
+                suggestions.set(Vec::new());
+                EventPropagation::Continue
+            }),
+        );
+        self
+    }
+
+    fn open_suggestions(&mut self) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let chips = self.chips;
+        let buffer = self.buffer;
+        let suggestions = self.suggestions;
+        let highlighted = self.highlighted;
+        let input = self.input;
+        let position = self.window_origin + Vec2::new(0.0, self.size.height);
+        let width = self.size.width;
+        self.overlay = Some(add_overlay(position, move |_| {
+            dyn_stack(
+                move || suggestions.get().into_iter().enumerate(),
+                |(index, suggestion)| (*index, suggestion.clone()),
+                move |(index, suggestion)| {
+                    let text = suggestion.clone();
+                    label(move || suggestion.clone())
+                        // Commit on press, before the field loses focus and closes the list
+                        .on_event_stop(EventListener::PointerDown, move |_| {
+                            commit(chips, &text);
+                            buffer.set(String::new());
+                            input.request_focus();
+                        })
+                        .style(move |s| {
+                            s.padding_horiz(8.0)
+                                .padding_vert(4.0)
+                                .hover(|s| s.background(Color::rgba8(0, 0, 0, 15)))
+                                .apply_if(highlighted.get() == Some(index), |s| {
+                                    s.background(Color::rgba8(0, 0, 0, 30))
+                                })
+                        })
+                },
+            )
+            .class(ChipSuggestionsClass)
+            .style(move |s| s.flex_col().min_width(width))
+        }));
+    }
+
+    fn close_suggestions(&mut self) {
+        if let Some(overlay) = self.overlay.take() {
+            remove_overlay(overlay);
+        }
+    }
+}
+
+impl View for ChipsInput {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ChipsInput".into()
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<ChipsUpdate>() {
+            match *state {
+                ChipsUpdate::Suggestions(list) => {
+                    if list.is_empty() {
+                        self.close_suggestions();
+                    } else {
+                        self.open_suggestions();
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        if let Some(layout) = cx.get_layout(self.id()) {
+            self.size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        }
+        default_compute_layout(self, cx)
+    }
+}
+
+impl Drop for ChipsInput {
+    fn drop(&mut self) {
+        self.close_suggestions();
+    }
+}
@@ -0,0 +1,509 @@
+use std::{cell::RefCell, rc::Rc};
+
+use kurbo::{Point, Rect, Size, Vec2};
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{AppState, ComputeLayoutCx, EventCx, StyleCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    keyboard::{Key, KeyEvent, NamedKey},
+    menu::{Menu, MenuEntry},
+    style::Style,
+    style_class,
+    view::{default_compute_layout, View, ViewData},
+    views::{empty, h_stack, svg, text, Decorators, PopoverUpdate},
+};
+
+style_class!(pub ContextMenuClass);
+style_class!(pub ContextMenuItemClass);
+style_class!(pub ContextMenuSeparatorClass);
+style_class!(pub ContextMenuShortcutClass);
+
+const SUBMENU_ICON: &str = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M10.072 8.024L5.715 3.667l.618-.62L11 7.716v.618L6.333 13l-.618-.619 4.357-4.357z"/></svg>"#;
+
+/// An entry of an open menu, with the actions taken out of the [`Menu`] so submenus can be
+/// opened again.
+enum Entry {
+    Separator,
+    Item {
+        title: String,
+        /// The keys of the shortcut the item is bound to.
+        shortcut: Option<String>,
+        enabled: bool,
+        action: Option<Rc<dyn Fn()>>,
+    },
+    SubMenu {
+        title: String,
+        enabled: bool,
+        entries: Rc<Vec<Entry>>,
+    },
+}
+
+impl Entry {
+    fn from_menu(children: Vec<MenuEntry>) -> Vec<Entry> {
+        children
+            .into_iter()
+            .map(|entry| match entry {
+                MenuEntry::Separator => Entry::Separator,
+                MenuEntry::Item(item) => Entry::Item {
+                    title: item.title,
+                    shortcut: item.shortcut.map(|shortcut| shortcut.to_string()),
+                    enabled: item.enabled,
+                    action: item.action.map(Rc::from),
+                },
+                MenuEntry::SubMenu(menu) => Entry::SubMenu {
+                    title: menu.item.title,
+                    enabled: menu.item.enabled,
+                    entries: Rc::new(Entry::from_menu(menu.children)),
+                },
+            })
+            .collect()
+    }
+
+    /// Whether the entry can be highlighted and chosen.
+    fn is_enabled(&self) -> bool {
+        match self {
+            Entry::Separator => false,
+            Entry::Item { enabled, .. } | Entry::SubMenu { enabled, .. } => *enabled,
+        }
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let (title, shortcut, enabled, has_submenu) = match self {
+            Entry::Separator => return Box::new(empty().class(ContextMenuSeparatorClass)),
+            Entry::Item {
+                title,
+                shortcut,
+                enabled,
+                ..
+            } => (title.clone(), shortcut.clone(), *enabled, false),
+            Entry::SubMenu { title, enabled, .. } => (title.clone(), None, *enabled, true),
+        };
+        let has_shortcut = shortcut.is_some();
+        Box::new(
+            h_stack((
+                text(title).style(|s| s.flex_grow(1.0)),
+                text(shortcut.unwrap_or_default())
+                    .class(ContextMenuShortcutClass)
+                    .style(move |s| s.apply_if(!has_shortcut, |s| s.hide())),
+                svg(|| SUBMENU_ICON.to_string()).style(move |s| {
+                    s.size(16.0, 16.0)
+                        .margin_left(20.0)
+                        .apply_if(!has_submenu, |s| s.hide())
+                }),
+            ))
+            .class(ContextMenuItemClass)
+            .disabled(move || !enabled),
+        )
+    }
+}
+
+/// The menus open from one call to [`open_context_menu`], shared by the menu and its submenus.
+struct MenuChain {
+    /// The view focused before the menu opened, focused again when it closes.
+    prev_focus: Option<Id>,
+    /// Every menu opened in the chain.
+    menus: Vec<Id>,
+    /// Set once the outermost menu closes.
+    closed: bool,
+}
+
+enum ContextMenuUpdate {
+    SubmenuClosed(Id),
+}
+
+/// A menu rendered in an overlay. See [`open_context_menu`]
+struct ContextMenu {
+    data: ViewData,
+    rows: Vec<Box<dyn View>>,
+    entries: Rc<Vec<Entry>>,
+    overlay: Id,
+    chain: Rc<RefCell<MenuChain>>,
+    /// The outermost menu, closed when an item is chosen.
+    root: Id,
+    /// The menu this is a submenu of and its width, to open on its left when there's no room
+    /// on the right.
+    parent: Option<(Id, f64)>,
+    /// The position of the overlay, in window coordinates.
+    position: Point,
+    /// Moves the menu back inside the window.
+    offset: Vec2,
+    highlighted: Option<usize>,
+    /// The index of the entry of the open submenu, and the submenu.
+    submenu: Option<(usize, Id)>,
+    /// Whether a button was pressed over the menu, so releasing the button which opened the
+    /// menu doesn't choose an item.
+    pressed: bool,
+    closed: bool,
+}
+
+/// Opens `menu` rendered by Floem at `position` in window coordinates, above everything else.
+///
+/// The menu takes the focus. Its items are highlighted when hovered and with the arrow keys,
+/// and chosen by clicking them or with `Enter`. Submenus open when their entry is hovered or
+/// with the right arrow key, and close with the left arrow key. Clicking outside of a menu
+/// closes it, and `Escape` closes the focused menu along with its submenus.
+pub(crate) fn open_context_menu(app_state: &mut AppState, menu: Menu, position: Point) {
+    let chain = Rc::new(RefCell::new(MenuChain {
+        prev_focus: app_state.focus,
+        menus: Vec::new(),
+        closed: false,
+    }));
+    let entries = Rc::new(Entry::from_menu(menu.children));
+    ContextMenu::open(app_state, entries, position, chain, None, None, true);
+}
+
+impl ContextMenu {
+    /// Opens a menu, or a submenu of `parent`, in a new overlay and returns its id. Menus
+    /// opened with the keyboard take the focus and highlight their first item.
+    #[allow(clippy::too_many_arguments)]
+    fn open(
+        app_state: &mut AppState,
+        entries: Rc<Vec<Entry>>,
+        position: Point,
+        chain: Rc<RefCell<MenuChain>>,
+        root: Option<Id>,
+        parent: Option<(Id, f64)>,
+        keyboard: bool,
+    ) -> Id {
+        let id = Id::next();
+        chain.borrow_mut().menus.push(id);
+        let overlay = add_overlay(position, move |overlay| {
+            let rows = entries.iter().map(Entry::view).collect();
+            let highlighted = if keyboard && parent.is_some() {
+                entries.iter().position(Entry::is_enabled)
+            } else {
+                None
+            };
+            if keyboard {
+                id.request_focus();
+            }
+            ContextMenu {
+                data: ViewData::new(id),
+                rows,
+                entries,
+                overlay,
+                chain,
+                root: root.unwrap_or(id),
+                parent,
+                position,
+                offset: Vec2::ZERO,
+                highlighted,
+                submenu: None,
+                pressed: false,
+                closed: false,
+            }
+            .keyboard_navigatable()
+            .class(ContextMenuClass)
+        });
+        app_state.open_popovers.push((id, overlay));
+        id
+    }
+
+    fn close(&mut self, app_state: &mut AppState) {
+        if self.closed {
+            return;
+        }
+        self.closed = true;
+        self.close_submenu(app_state);
+
+        let id = self.id();
+        app_state.open_popovers.retain(|(menu, _)| *menu != id);
+        let mut chain = self.chain.borrow_mut();
+        match self.parent {
+            Some((parent, _)) => {
+                parent.update_state(ContextMenuUpdate::SubmenuClosed(id));
+                if !chain.closed && app_state.focus == Some(id) {
+                    parent.request_focus();
+                }
+            }
+            None => {
+                chain.closed = true;
+                let focus_inside = app_state
+                    .focus
+                    .map_or(true, |focus| chain.menus.contains(&focus));
+                if focus_inside {
+                    if let Some(prev_focus) = chain.prev_focus {
+                        prev_focus.request_focus();
+                    }
+                }
+            }
+        }
+        remove_overlay(self.overlay);
+    }
+
+    fn close_submenu(&mut self, app_state: &mut AppState) {
+        if let Some((index, submenu)) = self.submenu.take() {
+            submenu.update_state(PopoverUpdate::Dismiss);
+            app_state.request_style(self.rows[index].id());
+        }
+    }
+
+    fn open_submenu(&mut self, app_state: &mut AppState, index: usize, keyboard: bool) {
+        if !keyboard && self.submenu.map(|(open, _)| open) == Some(index) {
+            return;
+        }
+        self.close_submenu(app_state);
+        let Entry::SubMenu { entries, .. } = &self.entries[index] else {
+            return;
+        };
+        let menu = app_state.get_layout_rect(self.id());
+        let row = app_state.get_layout_rect(self.rows[index].id());
+        let submenu = ContextMenu::open(
+            app_state,
+            entries.clone(),
+            Point::new(menu.x1, row.y0),
+            self.chain.clone(),
+            Some(self.root),
+            Some((self.id(), menu.width())),
+            keyboard,
+        );
+        self.submenu = Some((index, submenu));
+        app_state.request_style(self.rows[index].id());
+    }
+
+    fn set_highlighted(&mut self, app_state: &mut AppState, highlighted: Option<usize>) {
+        if highlighted == self.highlighted {
+            return;
+        }
+        for index in [self.highlighted, highlighted].into_iter().flatten() {
+            app_state.request_style(self.rows[index].id());
+        }
+        self.highlighted = highlighted;
+    }
+
+    /// Highlights the next enabled entry after `from`, `step` entries at a time, wrapping
+    /// around. Without `from`, this starts from the first entry, or the last one going back.
+    fn move_highlight(&mut self, app_state: &mut AppState, from: Option<usize>, step: isize) {
+        let count = self.entries.len() as isize;
+        let start = match from {
+            Some(index) => index as isize,
+            None if step > 0 => -1,
+            None => count,
+        };
+        let next = (1..=count)
+            .map(|n| (start + step * n).rem_euclid(count) as usize)
+            .find(|&index| self.entries[index].is_enabled());
+        if next.is_some() {
+            self.set_highlighted(app_state, next);
+        }
+    }
+
+    fn hover(&mut self, app_state: &mut AppState, index: usize) {
+        let entries = self.entries.clone();
+        let entry = &entries[index];
+        if !entry.is_enabled() {
+            self.set_highlighted(app_state, None);
+            self.close_submenu(app_state);
+            return;
+        }
+        self.set_highlighted(app_state, Some(index));
+        if matches!(entry, Entry::SubMenu { .. }) {
+            self.open_submenu(app_state, index, false);
+        } else {
+            self.close_submenu(app_state);
+        }
+    }
+
+    fn choose(&mut self, app_state: &mut AppState, index: usize, keyboard: bool) {
+        let entries = self.entries.clone();
+        match &entries[index] {
+            Entry::Item {
+                enabled: true,
+                action,
+                ..
+            } => {
+                if let Some(action) = action.clone() {
+                    action();
+                }
+                self.root.update_state(PopoverUpdate::Dismiss);
+            }
+            Entry::SubMenu { enabled: true, .. } => self.open_submenu(app_state, index, keyboard),
+            _ => {}
+        }
+    }
+
+    /// The index of the row at `pos`, relative to the menu.
+    fn row_at(&self, app_state: &mut AppState, pos: Point) -> Option<usize> {
+        let origin = app_state.get_layout_rect(self.id()).origin().to_vec2();
+        self.rows
+            .iter()
+            .position(|row| (app_state.get_layout_rect(row.id()) - origin).contains(pos))
+    }
+
+    fn key_down(&mut self, app_state: &mut AppState, event: &KeyEvent) -> EventPropagation {
+        let Key::Named(key) = &event.key.logical_key else {
+            return EventPropagation::Continue;
+        };
+        match key {
+            NamedKey::ArrowDown => self.move_highlight(app_state, self.highlighted, 1),
+            NamedKey::ArrowUp => self.move_highlight(app_state, self.highlighted, -1),
+            NamedKey::Home => self.move_highlight(app_state, None, 1),
+            NamedKey::End => self.move_highlight(app_state, None, -1),
+            NamedKey::ArrowRight => {
+                if let Some(index) = self.highlighted {
+                    if matches!(self.entries[index], Entry::SubMenu { .. }) {
+                        self.open_submenu(app_state, index, true);
+                    }
+                }
+            }
+            NamedKey::ArrowLeft if self.parent.is_some() => self.close(app_state),
+            NamedKey::Escape => self.close(app_state),
+            NamedKey::Enter | NamedKey::Space => {
+                if let Some(index) = self.highlighted {
+                    self.choose(app_state, index, true);
+                }
+            }
+            _ => return EventPropagation::Continue,
+        }
+        EventPropagation::Stop
+    }
+
+    /// How far to move the menu to fit it in the window. Submenus without room on the right
+    /// open on the left of their parent instead.
+    fn fit(&self, size: Size, window: Size) -> Vec2 {
+        let mut offset = Vec2::ZERO;
+        if self.position.x + size.width > window.width {
+            offset.x = match self.parent {
+                Some((_, parent_width)) => -(size.width + parent_width),
+                None => window.width - size.width - self.position.x,
+            };
+        }
+        if self.position.y + size.height > window.height {
+            offset.y = window.height - size.height - self.position.y;
+        }
+        Vec2::new(
+            offset.x.max(-self.position.x),
+            offset.y.max(-self.position.y),
+        )
+    }
+}
+
+impl View for ContextMenu {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for row in &self.rows {
+            if for_each(row) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for row in &mut self.rows {
+            if for_each(row) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for row in self.rows.iter_mut().rev() {
+            if for_each(row) {
+                break;
+            }
+        }
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(
+            Style::new()
+                .flex_col()
+                .margin_left(self.offset.x)
+                .margin_top(self.offset.y),
+        )
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ContextMenu".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        let state = match state.downcast::<PopoverUpdate>() {
+            Ok(state) => {
+                if let PopoverUpdate::Dismiss = *state {
+                    self.close(cx.app_state);
+                }
+                return;
+            }
+            Err(state) => state,
+        };
+        if let Ok(state) = state.downcast::<ContextMenuUpdate>() {
+            let ContextMenuUpdate::SubmenuClosed(submenu) = *state;
+            if let Some((index, open)) = self.submenu {
+                if open == submenu {
+                    self.submenu = None;
+                    cx.app_state.request_style(self.rows[index].id());
+                }
+            }
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let submenu = self.submenu.map(|(index, _)| index);
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            if Some(index) == self.highlighted || Some(index) == submenu {
+                cx.save();
+                cx.selected();
+                cx.style_view(row);
+                cx.restore();
+            } else {
+                cx.style_view(row);
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        if let Some(layout) = cx.get_layout(self.id()) {
+            let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+            let window = cx.app_state.root_size / cx.app_state.scale;
+            let offset = self.fit(size, window);
+            if offset != self.offset {
+                self.offset = offset;
+                cx.app_state.request_style(self.id());
+            }
+        }
+        default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerMove(pointer) => {
+                if let Some(index) = self.row_at(cx.app_state, pointer.pos) {
+                    self.hover(cx.app_state, index);
+                }
+                EventPropagation::Stop
+            }
+            Event::PointerDown(pointer) => {
+                self.pressed = self.row_at(cx.app_state, pointer.pos).is_some();
+                EventPropagation::Stop
+            }
+            Event::PointerUp(pointer) => {
+                if std::mem::take(&mut self.pressed) {
+                    if let Some(index) = self.row_at(cx.app_state, pointer.pos) {
+                        self.choose(cx.app_state, index, false);
+                    }
+                }
+                EventPropagation::Stop
+            }
+            Event::KeyDown(event) => self.key_down(cx.app_state, event),
+            _ => EventPropagation::Continue,
+        }
+    }
+}
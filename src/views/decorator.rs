@@ -1,14 +1,24 @@
-use floem_reactive::{create_effect, create_updater};
+use std::{cell::Cell, rc::Rc};
+
+use floem_reactive::{create_effect, create_updater, on_cleanup, with_scope, RwSignal, Scope};
 use floem_winit::keyboard::{Key, ModifiersState};
 use kurbo::{Point, Rect};
 
 use crate::{
-    action::{set_window_menu, set_window_title, update_window_scale},
-    animate::Animation,
+    accessibility::AccessCx,
+    action::{set_window_menu, set_window_title, set_window_zoom, update_window_scale},
+    animate::{self, Animation},
+    clipboard::ClipboardContent,
+    context::PaintCx,
     event::{Event, EventListener},
+    id::Id,
     menu::Menu,
+    pointer::ContextMenuEvent,
+    state_machine::StateMachine,
     style::{Style, StyleClass, StyleSelector},
+    tear_off::{TearOff, TearOffDrop},
     view::View,
+    views::{shader_effect, ShaderEffect},
     EventPropagation,
 };
 
@@ -42,6 +52,23 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Styles the view by the state of `machine`, which is moved by the view's input. See
+    /// [`state_machine`](crate::state_machine)
+    fn style_machine<S: Clone + PartialEq + 'static>(
+        mut self,
+        machine: &StateMachine<S>,
+        style: impl Fn(Style, &S) -> Style + 'static,
+    ) -> Self {
+        let handler_machine = machine.clone();
+        self.view_data_mut().event_handlers.push(Box::new(move |e| {
+            handler_machine.handle_event(e);
+            EventPropagation::Continue
+        }));
+        machine.start_timers();
+        let state = machine.state();
+        self.style(move |s| state.with(|state| style(s, state)))
+    }
+
     /// The visual style to apply when the mouse hovers over the element
     fn dragging_style(self, style: impl Fn(Style) -> Style + 'static) -> Self {
         let id = self.id();
@@ -64,12 +91,75 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Allows the element to be navigated to with the keyboard at `index` in the Tab order,
+    /// similar to tabindex in html. Views with a positive index come first in ascending order,
+    /// followed by the other views in the order of the tree. A negative index leaves the view
+    /// out of the Tab order, although it can still be focused otherwise.
+    fn tab_index(self, index: i32) -> Self {
+        let id = self.id();
+        id.tab_index(index);
+        self
+    }
+
+    /// Keeps the keyboard navigatable views inside this view together in the Tab order, at
+    /// the position and [tab index](Decorators::tab_index) of this view. The tab indices of
+    /// the views inside only order them within the scope, so parts of a form can be ordered
+    /// on their own.
+    fn focus_scope(self) -> Self {
+        let id = self.id();
+        id.focus_scope();
+        self
+    }
+
+    /// Makes this view a [focus scope](Decorators::focus_scope) which Tab and Shift+Tab don't
+    /// leave, cycling through the views inside it instead, as for a modal dialog. When the
+    /// focus is outside of it, Tab moves the focus into the last view trapping the focus shown.
+    ///
+    /// Modal overlays trap the focus by themselves.
+    fn trap_focus(self) -> Self {
+        let id = self.id();
+        id.trap_focus();
+        self
+    }
+
+    /// Moves the focus between the keyboard navigatable views inside this view with the arrow
+    /// keys, picking the closest view in the direction of the key based on the layout positions
+    /// rather than the order of the children. This suits toolbars, icon grids and calendars.
+    ///
+    /// The focus stays on the current view at the edges of the container.
+    fn keyboard_navigatable_grid(self) -> Self {
+        let id = self.id();
+        id.keyboard_navigatable_grid();
+        self
+    }
+
     fn draggable(self) -> Self {
         let id = self.id();
         id.draggable();
         self
     }
 
+    /// Lets the view be dragged out of its window, like a browser tab. Inside the window it's
+    /// dragged like a [`draggable`](Self::draggable) view, and outside of it a window with the
+    /// view made by `preview` follows the pointer. Dropping it there calls `on_drop` with where
+    /// it was dropped. See the [`tear_off`](crate::tear_off) module
+    ///
+    /// The preview window is transparent, so a translucent background for `preview` shows the
+    /// windows under it.
+    fn tear_off<V: View + 'static>(
+        self,
+        preview: impl Fn() -> V + 'static,
+        on_drop: impl Fn(TearOffDrop) + 'static,
+    ) -> Self {
+        let id = self.id();
+        id.draggable();
+        id.update_tear_off(TearOff {
+            preview: Rc::new(move || Box::new(preview())),
+            on_drop: Rc::new(on_drop),
+        });
+        self
+    }
+
     fn disabled(self, disabled_fn: impl Fn() -> bool + 'static) -> Self {
         let id = self.id();
 
@@ -223,6 +313,45 @@ pub trait Decorators: View + Sized {
         })
     }
 
+    /// Add an event handler for [EventListener::ContextMenu], which is sent for secondary
+    /// clicks and the Menu key. Returning [`EventPropagation::Stop`] prevents the
+    /// [`context_menu`](Decorators::context_menu) of the view from opening.
+    fn on_context_menu(
+        self,
+        action: impl Fn(&ContextMenuEvent) -> EventPropagation + 'static,
+    ) -> Self {
+        let id = self.id();
+        id.update_event_listener(
+            EventListener::ContextMenu,
+            Box::new(move |e| {
+                if let Event::ContextMenu(e) = e {
+                    action(e)
+                } else {
+                    EventPropagation::Continue
+                }
+            }),
+        );
+        self
+    }
+
+    /// Add an event handler for [EventListener::Paste], which is sent to the focused view
+    /// instead of the key event of the paste shortcut, with the decoded clipboard content.
+    fn on_paste(self, action: impl Fn(&ClipboardContent) + 'static) -> Self {
+        let id = self.id();
+        id.update_event_listener(
+            EventListener::Paste,
+            Box::new(move |e| {
+                if let Event::Paste(content) = e {
+                    action(content);
+                    EventPropagation::Stop
+                } else {
+                    EventPropagation::Continue
+                }
+            }),
+        );
+        self
+    }
+
     fn on_resize(self, action: impl Fn(Rect) + 'static) -> Self {
         let id = self.id();
         id.update_resize_listener(Box::new(action));
@@ -235,17 +364,53 @@ pub trait Decorators: View + Sized {
         self
     }
 
-    fn on_cleanup(self, action: impl Fn() + 'static) -> Self {
+    /// Calls `action` with the view's id once the view is added to a window, such as when a
+    /// [`dyn_stack`](super::dyn_stack) creates it. It runs in the view's scope, so the effects
+    /// created by it are disposed with the view.
+    fn on_mount(self, action: impl FnOnce(Id) + 'static) -> Self {
         let id = self.id();
-        id.update_cleanup_listener(Box::new(action));
+        let scope = Scope::current();
+        id.update_mount_listener(Box::new(move |id| with_scope(scope, || action(id))));
         self
     }
 
-    fn animation(self, anim: Animation) -> Self {
+    /// Calls `action` once when the view is removed or its scope is disposed, such as when a
+    /// [`dyn_stack`](super::dyn_stack) or [`dyn_container`](super::dyn_container) removes it or
+    /// its window is closed. Use it to stop what was started in [`on_mount`](Self::on_mount).
+    fn on_cleanup(self, action: impl Fn() + 'static) -> Self {
         let id = self.id();
-        create_effect(move |_| {
-            id.update_animation(anim.clone());
-        });
+        let action = Rc::new(action);
+        let cleaned_up = Rc::new(Cell::new(false));
+        let cleanup = {
+            let action = action.clone();
+            let cleaned_up = cleaned_up.clone();
+            move || {
+                if !cleaned_up.replace(true) {
+                    action();
+                }
+            }
+        };
+        id.update_cleanup_listener(Box::new(cleanup.clone()));
+        on_cleanup(cleanup);
+        self
+    }
+
+    /// Animates the view with the [`Animation`] built by `animation`. The animation is driven by
+    /// the frame loop, and a view can have several of them, the later ones overriding the
+    /// properties they share with the earlier ones.
+    ///
+    /// ```ignore
+    /// label(|| "Hello")
+    ///     .animation(|a| {
+    ///         a.keyframe(0.0, |s| s.margin_left(0.0))
+    ///             .keyframe(1.0, |s| s.margin_left(40.0))
+    ///             .easing(Spring::bouncy())
+    ///             .on_complete(|| println!("done"))
+    ///     })
+    ///     .animation(|a| a.background(move || color.get()))
+    /// ```
+    fn animation(self, animation: impl FnOnce(Animation) -> Animation) -> Self {
+        self.id().update_animation(animation(animate::animation()));
         self
     }
 
@@ -275,6 +440,15 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Binds the zoom of the window to `zoom`, which is also updated by the `Ctrl` `=`,
+    /// `Ctrl` `-` and `Ctrl` `0` shortcuts. See [`set_window_zoom`].
+    fn window_zoom(self, zoom: RwSignal<f64>) -> Self {
+        set_window_zoom(zoom);
+        self
+    }
+
+    /// Binds the title of the window to `title_fn`. The
+    /// [title segment](Decorators::title_segment) of the focused view is shown before it.
     fn window_title(self, title_fn: impl Fn() -> String + 'static) -> Self {
         create_effect(move |_| {
             let window_title = title_fn();
@@ -283,6 +457,25 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Shows `segment` before the [window title](Decorators::window_title) while the view or
+    /// one of its children has the focus, like the title of the current tab in a browser. It
+    /// stays when the focus moves to a view without a segment, such as a toolbar.
+    ///
+    /// ```ignore
+    /// editor(doc).title_segment(move || {
+    ///     let dirty = if doc.is_dirty() { "• " } else { "" };
+    ///     format!("{dirty}{}", doc.file_name())
+    /// })
+    /// ```
+    fn title_segment(self, segment: impl Fn() -> String + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            let segment = segment();
+            id.update_title_segment(segment);
+        });
+        self
+    }
+
     fn window_menu(self, menu_fn: impl Fn() -> Menu + 'static) -> Self {
         create_effect(move |_| {
             let menu = menu_fn();
@@ -291,19 +484,112 @@ pub trait Decorators: View + Sized {
         self
     }
 
-    /// Adds a secondary-click context menu to the view, which opens at the mouse position.
+    /// Adds a context menu to the view, which opens at the mouse position on secondary clicks
+    /// and below the focused view when requested with the keyboard.
+    ///
+    /// The menu is rendered by Floem on every platform, with
+    /// [`ContextMenuClass`](super::ContextMenuClass) on the menus,
+    /// [`ContextMenuItemClass`](super::ContextMenuItemClass) on their items and
+    /// [`ContextMenuSeparatorClass`](super::ContextMenuSeparatorClass) on the separators.
+    /// It's navigated with the arrow keys, items are chosen with `Enter`, and it closes on
+    /// clicks outside of it and with `Escape`. `menu` is called each time it opens.
+    ///
+    /// ```ignore
+    /// label(|| "Document").context_menu(|| {
+    ///     Menu::new("")
+    ///         .entry(MenuItem::new("Rename").action(rename))
+    ///         .entry(MenuItem::new("Delete").enabled(false))
+    ///         .separator()
+    ///         .entry(Menu::new("Share").entry(MenuItem::new("Email").action(email)))
+    /// })
+    /// ```
     fn context_menu(self, menu: impl Fn() -> Menu + 'static) -> Self {
         let id = self.id();
         id.update_context_menu(Box::new(menu));
         self
     }
 
-    /// Adds a primary-click context menu, which opens below the view.
+    /// Adds a primary-click context menu, which opens below the view. See
+    /// [`context_menu`](Decorators::context_menu)
     fn popout_menu(self, menu: impl Fn() -> Menu + 'static) -> Self {
         let id = self.id();
         id.update_popout_menu(Box::new(menu));
         self
     }
+
+    /// Draws with `paint` below the view's content and above its background, such as for
+    /// selection highlights. `paint` is given the rectangle of the view in its own coordinates.
+    fn on_paint_under(self, paint: impl Fn(&mut PaintCx, Rect) + 'static) -> Self {
+        let id = self.id();
+        id.update_paint_under(Box::new(paint));
+        self
+    }
+
+    /// Draws with `paint` above the view's content and border, such as for badges or debug
+    /// overlays. `paint` is given the rectangle of the view in its own coordinates, and isn't
+    /// clipped to it.
+    ///
+    /// ```ignore
+    /// button(|| "Inbox").on_paint_over(move |cx, rect| {
+    ///     if unread.get() > 0 {
+    ///         cx.fill(&Circle::new((rect.x1 - 4.0, rect.y0 + 4.0), 4.0), Color::RED, 0.0);
+    ///     }
+    /// })
+    /// ```
+    fn on_paint_over(self, paint: impl Fn(&mut PaintCx, Rect) + 'static) -> Self {
+        let id = self.id();
+        id.update_paint_over(Box::new(paint));
+        self
+    }
+
+    /// Only lets the points of the view for which `hit_test` returns true receive pointer
+    /// events and hover it, so they go to the views below it elsewhere. The point is relative
+    /// to the view.
+    ///
+    /// This allows non-rectangular views, such as a circular button, and decorative layers
+    /// which let clicks through with `.hit_test(|_| false)`.
+    ///
+    /// ```ignore
+    /// button(|| "+")
+    ///     .style(|s| s.size(40.0, 40.0).border_radius(20.0))
+    ///     .hit_test(|point| point.distance(Point::new(20.0, 20.0)) <= 20.0)
+    /// ```
+    fn hit_test(self, hit_test: impl Fn(Point) -> bool + 'static) -> Self {
+        let id = self.id();
+        id.update_hit_test(Box::new(hit_test));
+        self
+    }
+
+    /// Describes the view to screen readers and other assistive technologies, after the view
+    /// describes itself with [`View::accessibility`]. See the [`accessibility`] module.
+    ///
+    /// ```ignore
+    /// svg(|| PLAY_ICON.to_string())
+    ///     .on_click_stop(move |_| play())
+    ///     .accessible(|cx| {
+    ///         cx.set_role(Role::Button);
+    ///         cx.set_label("Play");
+    ///     })
+    /// ```
+    ///
+    /// [`accessibility`]: crate::accessibility
+    fn accessible(self, describe: impl Fn(&mut AccessCx) + 'static) -> Self {
+        let id = self.id();
+        id.update_accessibility(Box::new(describe));
+        self
+    }
+
+    /// Draws the view through a WGSL fragment shader. See [`shader_effect`](super::shader_effect)
+    fn shader_effect(
+        self,
+        wgsl_source: impl Into<String>,
+        uniforms: impl Fn() -> Vec<f32> + 'static,
+    ) -> ShaderEffect
+    where
+        Self: 'static,
+    {
+        shader_effect(self, wgsl_source, uniforms)
+    }
 }
 
 impl<V: View> Decorators for V {}
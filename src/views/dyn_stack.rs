@@ -8,11 +8,13 @@ use rustc_hash::FxHasher;
 use smallvec::SmallVec;
 
 use crate::{
-    context::{AppState, UpdateCx},
+    context::{AppState, PaintCx, UpdateCx},
     id::Id,
     view::{view_children_set_parent_id, View, ViewData},
 };
 
+use super::reorder::{Reorder, ReorderMsg};
+
 pub(crate) type FxIndexSet<T> = indexmap::IndexSet<T, BuildHasherDefault<FxHasher>>;
 
 #[derive(educe::Educe)]
@@ -28,6 +30,7 @@ where
     children: Vec<Option<(V, Scope)>>,
     view_fn: Box<dyn Fn(T) -> (V, Scope)>,
     phantom: PhantomData<T>,
+    reorder: Option<Reorder>,
 }
 
 pub fn dyn_stack<IF, I, T, KF, K, VF, V>(each_fn: IF, key_fn: KF, view_fn: VF) -> DynStack<V, T>
@@ -41,6 +44,27 @@ where
     T: 'static,
 {
     let id = Id::next();
+    diff_items(id, each_fn, key_fn);
+    let view_fn = Box::new(as_child_of_current_scope(view_fn));
+    DynStack {
+        data: ViewData::new(id),
+        children: Vec::new(),
+        view_fn,
+        phantom: PhantomData,
+        reorder: None,
+    }
+}
+
+/// Sends the [`Diff`] from the previous items of `each_fn` to the view `id` each time they
+/// change, telling the items apart by their keys.
+pub(super) fn diff_items<IF, I, T, KF, K>(id: Id, each_fn: IF, key_fn: KF)
+where
+    IF: Fn() -> I + 'static,
+    I: IntoIterator<Item = T>,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + 'static,
+    T: 'static,
+{
     create_effect(move |prev_hash_run| {
         let items = each_fn();
         let items = items.into_iter().collect::<SmallVec<[_; 128]>>();
@@ -68,12 +92,58 @@ where
         id.update_state(diff);
         HashRun(hashed_items)
     });
-    let view_fn = Box::new(as_child_of_current_scope(view_fn));
-    DynStack {
-        data: ViewData::new(id),
-        children: Vec::new(),
-        view_fn,
-        phantom: PhantomData,
+}
+
+impl<V: View + 'static, T> DynStack<V, T> {
+    /// Lets the items be dragged to reorder them, showing a line where the dragged item would
+    /// be dropped. The dragged item follows the pointer, styled with its `dragging_style`.
+    ///
+    /// The stack doesn't move its items, [`DynStack::on_reorder`] is called to update the
+    /// data they come from. This takes the drag and drop event listeners of the items.
+    pub fn draggable_items(mut self) -> Self {
+        if self.reorder.is_none() {
+            let mut reorder = Reorder::default();
+            for child in self.children.iter().flatten() {
+                reorder.attach(self.id(), child.0.id());
+            }
+            self.reorder = Some(reorder);
+        }
+        self
+    }
+
+    /// Calls `on_reorder` with the index of the item dropped and the index it should move to,
+    /// when the items are dragged. This enables [`DynStack::draggable_items`].
+    ///
+    /// ```ignore
+    /// dyn_stack(move || items.get(), |item| item.id, |item| label(move || item.name.clone()))
+    ///     .on_reorder(move |from, to| {
+    ///         items.update(|items| {
+    ///             let item = items.remove(from);
+    ///             items.insert(to, item);
+    ///         })
+    ///     })
+    /// ```
+    pub fn on_reorder(self, on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        let mut stack = self.draggable_items();
+        if let Some(reorder) = stack.reorder.as_mut() {
+            reorder.set_on_reorder(on_reorder);
+        }
+        stack
+    }
+
+    fn child_ids(&self) -> Vec<Id> {
+        self.children
+            .iter()
+            .flatten()
+            .map(|child| child.0.id())
+            .collect()
+    }
+
+    fn is_horizontal(&self, app_state: &mut AppState) -> bool {
+        matches!(
+            app_state.get_builtin_style(self.id()).flex_direction(),
+            taffy::style::FlexDirection::Row | taffy::style::FlexDirection::RowReverse
+        )
     }
 }
 
@@ -123,6 +193,19 @@ impl<V: View + 'static, T> View for DynStack<V, T> {
     }
 
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        let state = match state.downcast::<ReorderMsg>() {
+            Ok(msg) => {
+                let items = self.child_ids();
+                let horizontal = self.is_horizontal(cx.app_state);
+                if let Some(reorder) = self.reorder.as_mut() {
+                    if reorder.update(cx.app_state, *msg, &items, horizontal) {
+                        cx.app_state.request_paint(self.id());
+                    }
+                }
+                return;
+            }
+            Err(state) => state,
+        };
         if let Ok(diff) = state.downcast() {
             apply_diff(
                 self.id(),
@@ -131,9 +214,28 @@ impl<V: View + 'static, T> View for DynStack<V, T> {
                 &mut self.children,
                 &self.view_fn,
             );
+            if self.reorder.is_some() {
+                let items = self.child_ids();
+                let id = self.id();
+                let reorder = self.reorder.as_mut().unwrap();
+                reorder.retain(&items);
+                for item in items {
+                    reorder.attach(id, item);
+                }
+            }
             cx.request_all(self.id());
         }
     }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        for child in self.children.iter_mut().flatten() {
+            cx.paint_view(&mut child.0);
+        }
+        if let Some(reorder) = &self.reorder {
+            let horizontal = self.is_horizontal(cx.app_state);
+            reorder.paint(cx, self.id(), horizontal);
+        }
+    }
 }
 
 #[derive(Debug)]
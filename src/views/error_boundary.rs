@@ -0,0 +1,364 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use floem_reactive::{with_scope, Scope};
+use kurbo::Rect;
+use taffy::prelude::Node;
+
+use crate::{
+    context::{ComputeLayoutCx, EventCx, LayoutCx, PaintCx, StyleCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    view::{view_children_set_parent_id, View, ViewData},
+    views::empty,
+};
+
+type ChildFn = dyn Fn() -> Box<dyn View>;
+type FallbackFn = dyn Fn(String, ErrorBoundaryRetry) -> Box<dyn View>;
+
+enum ErrorBoundaryState {
+    Failed(String),
+    Retry,
+}
+
+/// A handle given to the fallback of an [`ErrorBoundary`] which can be used to rebuild the child.
+#[derive(Clone, Copy)]
+pub struct ErrorBoundaryRetry {
+    id: Id,
+}
+
+impl ErrorBoundaryRetry {
+    /// Discard the fallback view and try building the child again.
+    pub fn retry(&self) {
+        self.id.update_state(ErrorBoundaryState::Retry);
+    }
+}
+
+/// A wrapper that isolates panics of its child. See [`error_boundary`]
+pub struct ErrorBoundary {
+    data: ViewData,
+    child: Box<dyn View>,
+    child_scope: Scope,
+    parent_scope: Scope,
+    child_fn: Box<ChildFn>,
+    fallback_fn: Box<FallbackFn>,
+    /// Set when a panic was caught and the child is waiting to be replaced.
+    failed: bool,
+    showing_fallback: bool,
+}
+
+/// A wrapper that catches panics raised while building, updating, styling, laying out,
+/// handling events for or painting its child and its descendants.
+///
+/// State updates sent to the descendants with [`Id::update_state`], including the ones sent
+/// by the effects of views such as `label`, are passed through the boundary, so a panic in
+/// [`View::update`] is caught too. The closures of the effects themselves run when a signal
+/// is set, so their panics are only caught when the signal is set by one of the handled
+/// passes, such as an event handler of a descendant.
+///
+/// When the child panics, it is removed and replaced with the view returned by `fallback_fn`,
+/// which receives the panic message and an [`ErrorBoundaryRetry`] handle to rebuild the child.
+///
+/// ## Example
+/// ```ignore
+/// error_boundary(
+///     || faulty_widget(),
+///     |err, retry| {
+///         v_stack((
+///             label(move || format!("Something went wrong: {err}")),
+///             button(|| "Retry").on_click_stop(move |_| retry.retry()),
+///         ))
+///     },
+/// )
+/// ```
+pub fn error_boundary<V: View + 'static, F: View + 'static>(
+    child_fn: impl Fn() -> V + 'static,
+    fallback_fn: impl Fn(String, ErrorBoundaryRetry) -> F + 'static,
+) -> ErrorBoundary {
+    let parent_scope = Scope::current();
+    let mut boundary = ErrorBoundary {
+        data: ViewData::new(Id::next()),
+        child: Box::new(empty()),
+        child_scope: parent_scope,
+        parent_scope,
+        child_fn: Box::new(move || Box::new(child_fn())),
+        fallback_fn: Box::new(move |err, retry| Box::new(fallback_fn(err, retry))),
+        failed: false,
+        showing_fallback: false,
+    };
+    let (child, child_scope) = boundary.build_child();
+    boundary.child = child;
+    boundary.child_scope = child_scope;
+    boundary
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+impl ErrorBoundary {
+    /// Builds the child, falling back to the fallback view if building panics.
+    fn build_child(&mut self) -> (Box<dyn View>, Scope) {
+        let scope = self.parent_scope.create_child();
+        let result = with_scope(scope, || {
+            catch_unwind(AssertUnwindSafe(|| (self.child_fn)()))
+        });
+        match result {
+            Ok(child) => {
+                self.showing_fallback = false;
+                (child, scope)
+            }
+            Err(payload) => {
+                scope.dispose();
+                self.build_fallback(panic_message(payload))
+            }
+        }
+    }
+
+    fn build_fallback(&mut self, err: String) -> (Box<dyn View>, Scope) {
+        self.showing_fallback = true;
+        let scope = self.parent_scope.create_child();
+        let retry = ErrorBoundaryRetry { id: self.id() };
+        let fallback = with_scope(scope, || (self.fallback_fn)(err, retry));
+        (fallback, scope)
+    }
+
+    fn replace_child(&mut self, cx: &mut UpdateCx, new_child: (Box<dyn View>, Scope)) {
+        let old_child_scope = self.child_scope;
+        cx.app_state_mut().remove_view(&mut self.child);
+        (self.child, self.child_scope) = new_child;
+        old_child_scope.dispose();
+        self.child.id().set_parent(self.id());
+        view_children_set_parent_id(&*self.child);
+        self.failed = false;
+        cx.request_all(self.id());
+    }
+
+    /// Runs `f` on the child, catching any panic. Once a panic has been caught the child
+    /// is no longer touched until it has been replaced in the next update.
+    fn guard<R>(&mut self, f: impl FnOnce(&mut dyn View) -> R) -> Option<R> {
+        if self.failed {
+            return None;
+        }
+        match catch_unwind(AssertUnwindSafe(|| f(&mut *self.child))) {
+            Ok(r) => Some(r),
+            Err(payload) => {
+                self.failed = true;
+                // A panicking fallback is left disabled instead of being rebuilt forever.
+                if !self.showing_fallback {
+                    self.id()
+                        .update_state(ErrorBoundaryState::Failed(panic_message(payload)));
+                }
+                None
+            }
+        }
+    }
+}
+
+impl View for ErrorBoundary {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ErrorBoundary".into()
+    }
+
+    fn update_child(&mut self, cx: &mut UpdateCx, id_path: &[Id], state: Box<dyn Any>) {
+        self.guard(|child| {
+            if child.id() == id_path[0] {
+                cx.update_view(child, id_path, state);
+            }
+        });
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(state) = state.downcast::<ErrorBoundaryState>() {
+            let new_child = match *state {
+                ErrorBoundaryState::Failed(err) => self.build_fallback(err),
+                ErrorBoundaryState::Retry => self.build_child(),
+            };
+            self.replace_child(cx, new_child);
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let depth = cx.saved.len();
+        if self.guard(|child| cx.style_view(child)).is_none() {
+            while cx.saved.len() > depth {
+                cx.restore();
+            }
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> Node {
+        cx.layout_node(self.id(), true, |cx| {
+            self.guard(|child| cx.layout_view(child))
+                .into_iter()
+                .collect()
+        })
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        let depth = cx.saved_viewports.len();
+        match self.guard(|child| cx.compute_view_layout(child)) {
+            Some(rect) => rect,
+            None => {
+                while cx.saved_viewports.len() > depth {
+                    cx.restore();
+                }
+                None
+            }
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        self.guard(|child| cx.view_event(child, id_path, event))
+            .unwrap_or(EventPropagation::Continue)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let depth = cx.saved_transforms.len();
+        if self.guard(|child| cx.paint_view(child)).is_none() {
+            while cx.saved_transforms.len() > depth {
+                cx.restore();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{any::Any, cell::Cell, rc::Rc};
+
+    use super::{error_boundary, ErrorBoundaryRetry};
+    use crate::{
+        context::{PaintCx, UpdateCx},
+        id::Id,
+        testing::TestHarness,
+        view::{View, ViewData},
+        views::label,
+    };
+
+    struct Panicking {
+        data: ViewData,
+        in_paint: bool,
+    }
+
+    impl View for Panicking {
+        fn view_data(&self) -> &ViewData {
+            &self.data
+        }
+
+        fn view_data_mut(&mut self) -> &mut ViewData {
+            &mut self.data
+        }
+
+        fn update(&mut self, _cx: &mut UpdateCx, _state: Box<dyn Any>) {
+            panic!("update failed");
+        }
+
+        fn paint(&mut self, _cx: &mut PaintCx) {
+            if self.in_paint {
+                panic!("paint failed");
+            }
+        }
+    }
+
+    fn panicking(in_paint: bool) -> Box<dyn View> {
+        Box::new(Panicking {
+            data: ViewData::new(Id::next()),
+            in_paint,
+        })
+    }
+
+    #[test]
+    fn fallback_on_update_panic() {
+        let child = Rc::new(Cell::new(None));
+        let view = error_boundary(
+            {
+                let child = child.clone();
+                move || {
+                    let view = panicking(false);
+                    child.set(Some(view.id()));
+                    view
+                }
+            },
+            |err, _| label(move || format!("Error: {err}")),
+        );
+
+        let mut harness = TestHarness::new(view);
+        assert_eq!(harness.text(), "");
+        child.get().unwrap().update_state(());
+        harness.update();
+        assert_eq!(harness.text(), "Error: update failed");
+    }
+
+    #[test]
+    fn retry_after_paint_panic() {
+        let fail = Rc::new(Cell::new(true));
+        let retry = Rc::new(Cell::new(None::<ErrorBoundaryRetry>));
+        let view = error_boundary(
+            {
+                let fail = fail.clone();
+                move || {
+                    if fail.get() {
+                        panicking(true)
+                    } else {
+                        Box::new(label(|| "Recovered"))
+                    }
+                }
+            },
+            {
+                let retry = retry.clone();
+                move |err, handle| {
+                    retry.set(Some(handle));
+                    label(move || format!("Error: {err}"))
+                }
+            },
+        );
+
+        let mut harness = TestHarness::new(view);
+        // The panic is caught while painting, the fallback replaces the child on the update
+        harness.text();
+        harness.update();
+        assert_eq!(harness.text(), "Error: paint failed");
+
+        fail.set(false);
+        retry.get().unwrap().retry();
+        harness.update();
+        assert_eq!(harness.text(), "Recovered");
+    }
+}
@@ -3,9 +3,11 @@ use std::rc::Rc;
 use floem_reactive::create_effect;
 use floem_renderer::Renderer;
 use image::{DynamicImage, GenericImageView};
+use kurbo::{Insets, Rect, Size};
 use sha2::{Digest, Sha256};
 
 use crate::{
+    context::PaintCx,
     id::Id,
     style::Style,
     unit::UnitExt,
@@ -21,6 +23,7 @@ pub struct ImageStyle {
 
 /// How the content of a replaced element, such as an img or video, should be resized to fit its container.
 /// See <https://developer.mozilla.org/en-US/docs/Web/CSS/object-fit>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ObjectFit {
     /// The replaced content is sized to fill the element's content box.
     /// The entire object will completely fill the box.
@@ -39,6 +42,25 @@ pub enum ObjectFit {
     None,
 }
 
+impl ObjectFit {
+    /// The rect content of `size` is drawn in to fit it to `rect`, centered in it.
+    pub fn rect(&self, size: Size, rect: Rect) -> Rect {
+        if size.is_empty() {
+            return rect;
+        }
+        let scale_x = rect.width() / size.width;
+        let scale_y = rect.height() / size.height;
+        let size = match self {
+            ObjectFit::Fill => return rect,
+            ObjectFit::Contain => size * scale_x.min(scale_y),
+            ObjectFit::Cover => size * scale_x.max(scale_y),
+            ObjectFit::ScaleDown => size * scale_x.min(scale_y).min(1.0),
+            ObjectFit::None => size,
+        };
+        Rect::from_center_size(rect.center(), size)
+    }
+}
+
 /// Specifies the alignment of the element's contents within the element's box.
 /// Areas of the box which aren't covered by the replaced element's object will show the element's background.
 /// See <https://developer.mozilla.org/en-US/docs/Web/CSS/object-position>.
@@ -85,12 +107,111 @@ impl ImageStyle {
     }
 }
 
+/// An image cut into nine pieces by stretchable insets.
+///
+/// The corners keep their size, the edges stretch along one axis and the center stretches
+/// along both, so frames and skinned buttons can be scaled without distorting their corners.
+pub(crate) struct NineSlice {
+    /// The insets in image pixels, clamped so the corners don't overlap.
+    insets: Insets,
+    /// The pieces in rows from the top left, with their hashes, or `None` for empty pieces.
+    pieces: Vec<Option<(DynamicImage, Vec<u8>)>>,
+}
+
+impl NineSlice {
+    pub(crate) fn new(img: &DynamicImage, hash: &[u8], insets: Insets) -> Self {
+        let (width, height) = (img.width() as f64, img.height() as f64);
+        let insets = clamp_insets(insets, width, height);
+        let xs = slice_edges(insets.x0, insets.x1, width);
+        let ys = slice_edges(insets.y0, insets.y1, height);
+
+        let mut pieces = Vec::with_capacity(9);
+        for (row, y) in ys.windows(2).enumerate() {
+            for (column, x) in xs.windows(2).enumerate() {
+                let (x0, x1) = (x[0].round() as u32, x[1].round() as u32);
+                let (y0, y1) = (y[0].round() as u32, y[1].round() as u32);
+                if x1 <= x0 || y1 <= y0 {
+                    pieces.push(None);
+                    continue;
+                }
+                let piece = img.crop_imm(x0, y0, x1 - x0, y1 - y0);
+                let mut piece_hash = hash.to_vec();
+                piece_hash.push((row * 3 + column) as u8);
+                for edge in [x0, y0, x1, y1] {
+                    piece_hash.extend_from_slice(&edge.to_le_bytes());
+                }
+                pieces.push(Some((piece, piece_hash)));
+            }
+        }
+        Self { insets, pieces }
+    }
+
+    /// The number of pieces which aren't empty.
+    pub(crate) fn image_count(&self) -> usize {
+        self.pieces.iter().flatten().count()
+    }
+
+    /// Draws the image stretched to `rect`. If `rect` is smaller than the corners, they're
+    /// scaled down to fit.
+    pub(crate) fn paint(&self, cx: &mut PaintCx, rect: Rect) {
+        let insets = clamp_insets(self.insets, rect.width(), rect.height());
+        let xs = slice_edges(insets.x0, insets.x1, rect.width()).map(|x| rect.x0 + x);
+        let ys = slice_edges(insets.y0, insets.y1, rect.height()).map(|y| rect.y0 + y);
+
+        for (row, y) in ys.windows(2).enumerate() {
+            for (column, x) in xs.windows(2).enumerate() {
+                let Some((piece, hash)) = &self.pieces[row * 3 + column] else {
+                    continue;
+                };
+                let dest = Rect::new(x[0], y[0], x[1], y[1]);
+                if dest.is_empty() {
+                    continue;
+                }
+                cx.draw_img(
+                    floem_renderer::Img {
+                        img: piece,
+                        data: piece.as_bytes(),
+                        hash,
+                    },
+                    dest,
+                );
+            }
+        }
+    }
+}
+
+/// Scales `insets` down proportionally when the opposite insets would overlap.
+fn clamp_insets(insets: Insets, width: f64, height: f64) -> Insets {
+    let insets = Insets::new(
+        insets.x0.max(0.0),
+        insets.y0.max(0.0),
+        insets.x1.max(0.0),
+        insets.y1.max(0.0),
+    );
+    let scale = |a: f64, b: f64, size: f64| {
+        if a + b > size && a + b > 0.0 {
+            size / (a + b)
+        } else {
+            1.0
+        }
+    };
+    let x = scale(insets.x0, insets.x1, width);
+    let y = scale(insets.y0, insets.y1, height);
+    Insets::new(insets.x0 * x, insets.y0 * y, insets.x1 * x, insets.y1 * y)
+}
+
+fn slice_edges(start: f64, end: f64, size: f64) -> [f64; 4] {
+    [0.0, start, size - end, size]
+}
+
 pub struct Img {
     data: ViewData,
     //FIXME: store the pixel format(once its added to vger), for now we only store RGBA(RGB is converted to RGBA)
     img: Option<Rc<DynamicImage>>,
     img_hash: Option<Vec<u8>>,
     img_dimensions: Option<(u32, u32)>,
+    nine_slice_insets: Option<Insets>,
+    nine_slice: Option<NineSlice>,
     content_node: Option<Node>,
 }
 
@@ -108,10 +229,26 @@ pub(crate) fn img_dynamic(image: impl Fn() -> Option<Rc<DynamicImage>> + 'static
         img: None,
         img_hash: None,
         img_dimensions: None,
+        nine_slice_insets: None,
+        nine_slice: None,
         content_node: None,
     }
 }
 
+impl Img {
+    /// Draws the image as a nine-slice image with the stretchable area inside `insets`,
+    /// which are given in image pixels.
+    ///
+    /// The corners outside of the insets keep their size while the edges and the center are
+    /// stretched to fill the view, so the view can be sized freely with
+    /// [`style`](crate::views::Decorators::style).
+    pub fn nine_slice(mut self, insets: impl Into<Insets>) -> Self {
+        self.nine_slice_insets = Some(insets.into());
+        self.nine_slice = None;
+        self
+    }
+}
+
 impl View for Img {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -134,6 +271,7 @@ impl View for Img {
             });
             self.img = *img;
             self.img_dimensions = self.img.as_ref().map(|img| img.dimensions());
+            self.nine_slice = None;
             cx.request_layout(self.id());
         }
     }
@@ -165,6 +303,13 @@ impl View for Img {
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         if let Some(img) = self.img.as_ref() {
             let rect = cx.get_content_rect(self.id());
+            if let Some(insets) = self.nine_slice_insets {
+                let hash = self.img_hash.as_ref().unwrap();
+                self.nine_slice
+                    .get_or_insert_with(|| NineSlice::new(img, hash, insets))
+                    .paint(cx, rect);
+                return;
+            }
             cx.draw_img(
                 floem_renderer::Img {
                     img,
@@ -175,4 +320,9 @@ impl View for Img {
             );
         }
     }
+
+    fn cached_images(&self) -> usize {
+        let pieces = self.nine_slice.as_ref().map_or(0, NineSlice::image_count);
+        self.img.is_some() as usize + pieces
+    }
 }
@@ -0,0 +1,215 @@
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MaskSlot {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Any,
+    Literal(char),
+}
+
+impl MaskSlot {
+    fn accepts(self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_alphabetic(),
+            MaskSlot::Alphanumeric => c.is_alphanumeric(),
+            MaskSlot::Any => !c.is_control(),
+            MaskSlot::Literal(_) => false,
+        }
+    }
+}
+
+/// A pattern restricting what can be entered in a [`TextInput`](super::TextInput), such as
+/// `(###) ###-####` for phone numbers or `##/##/####` for dates.
+///
+/// Each character of the pattern is either a placeholder for one entered character or a
+/// literal which is inserted automatically:
+/// - `#` accepts a digit,
+/// - `A` accepts a letter,
+/// - `*` accepts a letter or a digit,
+/// - `?` accepts any character,
+/// - `\` makes the next character a literal, e.g. `\#`,
+/// - any other character is a literal.
+///
+/// The characters entered in the placeholders make up the raw value, while the text shown in
+/// the input is the formatted value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+}
+
+impl InputMask {
+    pub fn new(pattern: &str) -> Self {
+        let mut slots = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            slots.push(match c {
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Letter,
+                '*' => MaskSlot::Alphanumeric,
+                '?' => MaskSlot::Any,
+                '\\' => match chars.next() {
+                    Some(escaped) => MaskSlot::Literal(escaped),
+                    None => MaskSlot::Literal('\\'),
+                },
+                c => MaskSlot::Literal(c),
+            });
+        }
+        Self { slots }
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = MaskSlot> + '_ {
+        self.slots
+            .iter()
+            .copied()
+            .filter(|slot| !matches!(slot, MaskSlot::Literal(_)))
+    }
+
+    /// Assigns `chars` to the placeholders in order, dropping the characters that don't fit.
+    fn fit(&self, chars: impl Iterator<Item = char>) -> String {
+        let mut placeholders = self.placeholders().peekable();
+        let mut raw = String::new();
+        for c in chars {
+            let Some(placeholder) = placeholders.peek() else {
+                break;
+            };
+            if placeholder.accepts(c) {
+                raw.push(c);
+                placeholders.next();
+            }
+        }
+        raw
+    }
+
+    /// Formats a raw value, inserting the literals up to the last entered character.
+    pub fn format(&self, raw: &str) -> String {
+        let mut formatted = String::new();
+        let mut literals = String::new();
+        let mut raw = raw.chars();
+        for slot in &self.slots {
+            match slot {
+                MaskSlot::Literal(literal) => literals.push(*literal),
+                slot => {
+                    let Some(c) = raw.next() else {
+                        break;
+                    };
+                    if !slot.accepts(c) {
+                        break;
+                    }
+                    formatted.push_str(&literals);
+                    formatted.push(c);
+                    literals.clear();
+                }
+            }
+        }
+        formatted
+    }
+
+    /// Extracts the raw value from formatted text, skipping the literals and any character
+    /// which doesn't fit the pattern.
+    pub fn raw(&self, formatted: &str) -> String {
+        let mut slots = self.slots.iter().copied().peekable();
+        let mut raw = String::new();
+        'chars: for c in formatted.chars() {
+            while let Some(slot) = slots.peek().copied() {
+                match slot {
+                    MaskSlot::Literal(literal) => {
+                        slots.next();
+                        if literal == c {
+                            continue 'chars;
+                        }
+                    }
+                    slot => {
+                        if slot.accepts(c) {
+                            raw.push(c);
+                            slots.next();
+                        }
+                        continue 'chars;
+                    }
+                }
+            }
+            break;
+        }
+        raw
+    }
+
+    /// Returns true if `raw` fills every placeholder of the pattern.
+    pub fn is_complete(&self, raw: &str) -> bool {
+        self.fit(raw.chars()).chars().count() == self.placeholders().count()
+    }
+
+    /// Replaces `range` of the formatted text with `text`, returning the new formatted text and
+    /// the byte offset after the inserted text.
+    ///
+    /// Returns `None` if `text` is not empty but none of its characters fit the pattern.
+    pub(crate) fn replace(
+        &self,
+        formatted: &str,
+        range: Range<usize>,
+        text: &str,
+    ) -> Option<(String, usize)> {
+        let raw: Vec<char> = self.raw(formatted).chars().collect();
+        let start = self.raw(&formatted[..range.start]).chars().count();
+        let end = self.raw(&formatted[..range.end]).chars().count();
+
+        let head = self.fit(raw[..start].iter().copied().chain(text.chars()));
+        if !text.is_empty() && head.chars().count() == start {
+            return None;
+        }
+        let new_raw = self.fit(head.chars().chain(raw[end..].iter().copied()));
+        Some((self.format(&new_raw), self.format(&head).len()))
+    }
+
+    /// Reformats text after it was edited without the mask, such as after deleting characters,
+    /// returning the formatted text and the new position of `cursor`.
+    pub(crate) fn normalize(&self, text: &str, cursor: usize) -> (String, usize) {
+        let raw = self.raw(text);
+        let head = self.raw(&text[..cursor]);
+        (self.format(&raw), self.format(&head).len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputMask;
+
+    #[test]
+    fn format_inserts_literals() {
+        let mask = InputMask::new("(###) ###-####");
+        assert_eq!(mask.format(""), "");
+        assert_eq!(mask.format("5"), "(5");
+        assert_eq!(mask.format("555"), "(555");
+        assert_eq!(mask.format("5551"), "(555) 1");
+        assert_eq!(mask.format("5551234567"), "(555) 123-4567");
+    }
+
+    #[test]
+    fn raw_skips_literals() {
+        let mask = InputMask::new("(###) ###-####");
+        assert_eq!(mask.raw("(555) 123-4567"), "5551234567");
+        assert_eq!(mask.raw("5551234567"), "5551234567");
+        assert_eq!(mask.raw("(555) 1"), "5551");
+        assert!(mask.is_complete("5551234567"));
+        assert!(!mask.is_complete("555"));
+    }
+
+    #[test]
+    fn replace_restricts_character_classes() {
+        let mask = InputMask::new("AA-##");
+        assert_eq!(mask.replace("", 0..0, "a"), Some(("a".to_string(), 1)));
+        assert_eq!(mask.replace("ab", 2..2, "1"), Some(("ab-1".to_string(), 4)));
+        assert_eq!(mask.replace("ab", 2..2, "c"), None);
+        assert_eq!(mask.replace("ab-1", 0..0, "1"), None);
+    }
+
+    #[test]
+    fn normalize_after_delete() {
+        let mask = InputMask::new("##/##/####");
+        // "12/3" with the "3" deleted
+        assert_eq!(mask.normalize("12/", 3), ("12".to_string(), 2));
+        // "12/34" with the "2" deleted
+        assert_eq!(mask.normalize("1/34", 1), ("13/4".to_string(), 1));
+    }
+}
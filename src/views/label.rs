@@ -1,19 +1,27 @@
 use std::{any::Any, fmt::Display};
 
 use crate::{
-    context::UpdateCx,
+    accessibility::AccessCx,
+    context::{EventCx, PaintCx, PaintState, UpdateCx},
     cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
+    event::{Event, EventPropagation},
     id::Id,
     prop_extracter,
     style::Style,
-    style::{FontProps, LineHeight, TextColor, TextOverflow, TextOverflowProp},
+    style::{
+        apply_font_variations, CursorStyle, FontProps, LineHeight, TextColor, TextOverflow,
+        TextOverflowProp, TextShadow, TextShadowProp,
+    },
     unit::PxPct,
     view::{View, ViewData},
+    views::{range_segments, SelectionColor, TextSelection},
 };
+use accesskit::Role;
 use floem_peniko::Color;
 use floem_reactive::create_updater;
 use floem_renderer::Renderer;
-use kurbo::{Point, Rect};
+use image::{DynamicImage, RgbaImage};
+use kurbo::{Point, Rect, Size};
 use taffy::prelude::Node;
 
 prop_extracter! {
@@ -21,6 +29,8 @@ prop_extracter! {
         color: TextColor,
         text_overflow: TextOverflowProp,
         line_height: LineHeight,
+        text_shadow: TextShadowProp,
+        selection_color: SelectionColor,
     }
 }
 
@@ -40,6 +50,9 @@ pub struct Label {
     text_overflow_listener: Option<TextOverflowListener>,
     font: FontProps,
     style: Extracter,
+    shadows: TextShadows,
+    selectable: bool,
+    selection: TextSelection,
 }
 
 impl Label {
@@ -55,6 +68,9 @@ impl Label {
             text_overflow_listener: None,
             font: FontProps::default(),
             style: Default::default(),
+            shadows: TextShadows::default(),
+            selectable: false,
+            selection: TextSelection::default(),
         }
     }
 }
@@ -85,9 +101,42 @@ impl Label {
         self
     }
 
+    /// Lets the text be selected by dragging over it, double clicking a word or triple clicking
+    /// a line, and copied with `Ctrl` `C` (`Cmd` `C` on macOS) while the label has focus.
+    ///
+    /// The selection is painted with the [`SelectionColor`] of the label's style.
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        self
+    }
+
+    /// The text as it's displayed, which is shortened when it overflows with an ellipsis.
+    fn displayed_text(&self) -> &str {
+        self.available_text.as_deref().unwrap_or(&self.label)
+    }
+
+    fn displayed_text_layout(&self) -> Option<&TextLayout> {
+        self.available_text_layout
+            .as_ref()
+            .or(self.text_layout.as_ref())
+    }
+
+    /// The index of the displayed text at `pos`, relative to the label.
+    fn index_at(&self, cx: &EventCx, pos: Point) -> usize {
+        let origin = self
+            .text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map_or(Point::ZERO, |layout| {
+                Point::new(layout.location.x as f64, layout.location.y as f64)
+            });
+        self.displayed_text_layout().map_or(0, |text_layout| {
+            text_layout.hit_point(pos - origin.to_vec2()).index
+        })
+    }
+
     fn get_attrs_list(&self) -> AttrsList {
         let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
-        if let Some(font_size) = self.font.size() {
+        if let Some(font_size) = self.font.scaled_size(14.0) {
             attrs = attrs.font_size(font_size);
         }
         if let Some(font_style) = self.font.style() {
@@ -103,6 +152,7 @@ impl Label {
         if let Some(font_weight) = self.font.weight() {
             attrs = attrs.weight(font_weight);
         }
+        attrs = apply_font_variations(attrs, &self.font.variations());
         if let Some(line_height) = self.style.line_height() {
             attrs = attrs.line_height(line_height);
         }
@@ -110,6 +160,7 @@ impl Label {
     }
 
     fn set_text_layout(&mut self) {
+        self.shadows.clear();
         let mut text_layout = TextLayout::new();
         let attrs_list = self.get_attrs_list();
         text_layout.set_text(self.label.as_str(), attrs_list.clone());
@@ -136,9 +187,15 @@ impl View for Label {
         format!("Label: {:?}", self.label).into()
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::StaticText);
+        cx.set_label(self.label.clone());
+    }
+
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         if let Ok(state) = state.downcast() {
             self.label = *state;
+            self.selection.clear();
             self.text_layout = None;
             self.available_text = None;
             self.available_width = None;
@@ -149,6 +206,7 @@ impl View for Label {
 
     fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
         if self.font.read(cx) | self.style.read(cx) {
+            self.shadows.set(self.style.text_shadow());
             self.text_layout = None;
             self.available_text = None;
             self.available_width = None;
@@ -160,7 +218,7 @@ impl View for Label {
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id(), true, |cx| {
             let (width, height) = if self.label.is_empty() {
-                (0.0, self.font.size().unwrap_or(14.0))
+                (0.0, self.font.scaled_size(14.0).unwrap_or(14.0))
             } else {
                 if self.text_layout.is_none() {
                     self.set_text_layout();
@@ -235,9 +293,16 @@ impl View for Label {
                     };
                     self.available_text = Some(new_text);
                     self.available_width = Some(available_width);
+                    self.selection.clear();
                     self.set_text_layout();
                 }
             } else {
+                if self.available_text.is_some() {
+                    self.selection.clear();
+                }
+                if self.available_text_layout.is_some() {
+                    self.shadows.clear();
+                }
                 self.available_text = None;
                 self.available_width = None;
                 self.available_text_layout = None;
@@ -248,11 +313,13 @@ impl View for Label {
                     let mut text_layout = text_layout.clone();
                     text_layout.set_size(available_width, f32::MAX);
                     self.available_text_layout = Some(text_layout);
+                    self.shadows.clear();
                     self.available_width = Some(available_width);
                     cx.app_state_mut().request_layout(self.id());
                 }
             } else {
                 if self.available_text_layout.is_some() {
+                    self.shadows.clear();
                     cx.app_state_mut().request_layout(self.id());
                 }
                 self.available_text = None;
@@ -273,6 +340,48 @@ impl View for Label {
         None
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if !self.selectable {
+            return EventPropagation::Continue;
+        }
+        match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let index = self.index_at(cx, pointer_event.pos);
+                let text = self.displayed_text().to_string();
+                self.selection
+                    .pointer_down(&text, index, pointer_event.count);
+                cx.update_active(self.id());
+                self.id().request_focus();
+                self.id().request_paint();
+                return EventPropagation::Stop;
+            }
+            Event::PointerMove(pointer_event) => {
+                cx.app_state.cursor = Some(CursorStyle::Text);
+                let index = self.index_at(cx, pointer_event.pos);
+                let text = self.displayed_text().to_string();
+                if self.selection.pointer_move(&text, index) {
+                    self.id().request_paint();
+                }
+            }
+            Event::PointerUp(_) => self.selection.pointer_up(),
+            Event::KeyDown(key_event) => {
+                if self
+                    .selection
+                    .copy_shortcut(self.displayed_text(), key_event)
+                {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+        EventPropagation::Continue
+    }
+
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         if self.label.is_empty() {
             return;
@@ -281,10 +390,128 @@ impl View for Label {
         let text_node = self.text_node.unwrap();
         let location = cx.app_state.taffy.layout(text_node).unwrap().location;
         let point = Point::new(location.x as f64, location.y as f64);
-        if let Some(text_layout) = self.available_text_layout.as_ref() {
-            cx.draw_text(text_layout, point);
-        } else {
-            cx.draw_text(self.text_layout.as_ref().unwrap(), point);
+        let id = self.id();
+        let text_layout = self
+            .available_text_layout
+            .as_ref()
+            .unwrap_or_else(|| self.text_layout.as_ref().unwrap());
+        if let Some(range) = self.selection.range() {
+            let color = self.style.selection_color();
+            for segment in range_segments(text_layout, range) {
+                cx.fill(&(segment.rect + point.to_vec2()), color, 0.0);
+            }
         }
+        self.shadows.paint(cx, id, text_layout, point);
+        cx.draw_text(text_layout, point);
+    }
+
+    fn cached_images(&self) -> usize {
+        self.shadows.image_count()
+    }
+}
+
+/// The shadows of a text layout, rendered once and kept until the text or the shadows change.
+#[derive(Default)]
+pub(crate) struct TextShadows {
+    shadows: Vec<TextShadow>,
+    /// The blurred shadow images, their hashes and the rects they're drawn in, relative to the
+    /// text position.
+    images: Option<Vec<(DynamicImage, Vec<u8>, Rect)>>,
+    generation: u64,
+}
+
+impl TextShadows {
+    pub(crate) fn set(&mut self, shadows: Vec<TextShadow>) {
+        if self.shadows != shadows {
+            self.shadows = shadows;
+            self.clear();
+        }
+    }
+
+    /// Drops the rendered shadows, which must be called when the text layout changes.
+    pub(crate) fn clear(&mut self) {
+        self.images = None;
+    }
+
+    /// The number of rendered shadows kept.
+    pub(crate) fn image_count(&self) -> usize {
+        self.images.as_ref().map_or(0, Vec::len)
+    }
+
+    pub(crate) fn paint(
+        &mut self,
+        cx: &mut PaintCx,
+        id: Id,
+        text_layout: &TextLayout,
+        point: Point,
+    ) {
+        if self.shadows.is_empty() {
+            return;
+        }
+        if self.images.is_none() {
+            self.generation += 1;
+            self.images = Some(self.render(id, text_layout, cx.paint_state.scale));
+        }
+        // The first shadow is on top, so it's painted last
+        for (img, hash, rect) in self.images.iter().flatten().rev() {
+            cx.draw_img(
+                floem_renderer::Img {
+                    img,
+                    data: img.as_bytes(),
+                    hash,
+                },
+                *rect + point.to_vec2(),
+            );
+        }
+    }
+
+    fn render(
+        &self,
+        id: Id,
+        text_layout: &TextLayout,
+        scale: f64,
+    ) -> Vec<(DynamicImage, Vec<u8>, Rect)> {
+        let max_blur = self
+            .shadows
+            .iter()
+            .map(|shadow| shadow.blur_radius)
+            .fold(0.0, f64::max);
+        let text_size = text_layout.size();
+        if text_size.is_empty() {
+            return Vec::new();
+        }
+        // Leave room for the blur to fade out around the text
+        let pad = (max_blur * 2.0).ceil();
+        let size = Size::new(text_size.width + pad * 2.0, text_size.height + pad * 2.0);
+
+        let mut paint_state = PaintState::new_headless(scale, size * scale);
+        paint_state.renderer.begin(false);
+        paint_state
+            .renderer
+            .draw_text(text_layout, Point::new(pad, pad));
+        let Some(mask) = paint_state.renderer.finish().map(|img| img.to_rgba8()) else {
+            return Vec::new();
+        };
+
+        self.shadows
+            .iter()
+            .enumerate()
+            .map(|(i, shadow)| {
+                let color = floem_renderer::color::to_output(shadow.color);
+                let mut img = RgbaImage::from_fn(mask.width(), mask.height(), |x, y| {
+                    let alpha = mask.get_pixel(x, y)[3] as u32 * color.a as u32 / 255;
+                    image::Rgba([color.r, color.g, color.b, alpha as u8])
+                });
+                if shadow.blur_radius > 0.0 {
+                    // Like CSS, the blur radius is twice the standard deviation
+                    img = image::imageops::blur(&img, (shadow.blur_radius * scale / 2.0) as f32);
+                }
+                let mut hash = id.to_raw().to_le_bytes().to_vec();
+                hash.extend_from_slice(&self.generation.to_le_bytes());
+                hash.extend_from_slice(&i.to_le_bytes());
+                let rect = size.to_rect() + shadow.offset - kurbo::Vec2::new(pad, pad);
+                (DynamicImage::ImageRgba8(img), hash, rect)
+            })
+            .collect()
     }
 }
@@ -1,15 +1,22 @@
-use super::{v_stack_from_iter, Decorators, Stack};
-use crate::context::StyleCx;
+use super::reorder::{Reorder, ReorderMsg};
+use super::{dyn_stack, v_stack_from_iter, Decorators, DynStack, Stack};
+use crate::context::{PaintCx, StyleCx};
 use crate::reactive::create_effect;
 use crate::style::Style;
 use crate::EventPropagation;
 use crate::{
+    accessibility::AccessCx,
     event::{Event, EventListener},
     id::Id,
-    keyboard::{Key, NamedKey},
+    keyboard::{Key, ModifiersState, NamedKey},
     view::{View, ViewData},
 };
-use floem_reactive::{create_rw_signal, RwSignal};
+use accesskit::Role;
+use floem_reactive::{create_rw_signal, ReadSignal, RwSignal};
+use indexmap::IndexSet;
+use std::any::Any;
+use std::hash::Hash;
+use std::rc::Rc;
 
 enum ListUpdate {
     SelectionChanged,
@@ -18,15 +25,20 @@ enum ListUpdate {
 
 pub(crate) struct Item {
     pub(crate) data: ViewData,
-    pub(crate) index: usize,
-    pub(crate) selection: RwSignal<Option<usize>>,
+    /// Whether the item is selected, checked when it's styled.
+    pub(crate) is_selected: Box<dyn Fn() -> bool>,
     pub(crate) child: Box<dyn View>,
 }
 
 pub struct List {
     data: ViewData,
     selection: RwSignal<Option<usize>>,
+    selected: RwSignal<IndexSet<usize>>,
+    multi_select: RwSignal<bool>,
+    /// The keys of the items of a [`keyed_list`], by index.
+    keys: Rc<Vec<Box<dyn Any>>>,
     child: Stack,
+    reorder: Option<Reorder>,
 }
 
 impl List {
@@ -34,6 +46,58 @@ impl List {
         self.selection
     }
 
+    /// The indices of the selected items. Unless [`List::multi_select`] is enabled, this holds
+    /// the [`List::selection`].
+    pub fn selected(&self) -> RwSignal<IndexSet<usize>> {
+        self.selected
+    }
+
+    /// Allows selecting several items: Ctrl+click (Cmd+click on macOS) toggles an item,
+    /// Shift+click and Shift+arrow keys select a range, and Ctrl+A (Cmd+A) selects every item.
+    ///
+    /// [`List::selection`] is then the item last clicked or moved to, while
+    /// [`List::selected`] holds every selected item.
+    pub fn multi_select(self, multi_select: bool) -> Self {
+        self.multi_select.set(multi_select);
+        self
+    }
+
+    /// Calls `on_change` with the selected items whenever they change.
+    pub fn on_selection_change(self, on_change: impl Fn(&IndexSet<usize>) + 'static) -> Self {
+        let selected = self.selected;
+        create_effect(move |_| selected.with(|selected| on_change(selected)));
+        self
+    }
+
+    /// Lets the items be dragged to reorder them, showing a line where the dragged item would
+    /// be dropped. The dragged item follows the pointer, styled with its `dragging_style`.
+    ///
+    /// The list doesn't move its items, [`List::on_reorder`] is called to update the data they
+    /// come from. This takes the drag and drop event listeners of the items.
+    pub fn draggable_items(mut self) -> Self {
+        if self.reorder.is_none() {
+            let mut reorder = Reorder::default();
+            for item in &self.child.children {
+                reorder.attach(self.id(), item.id());
+            }
+            self.reorder = Some(reorder);
+        }
+        self
+    }
+
+    /// Calls `on_reorder` with the index of the item dropped and the index it should move to,
+    /// when the items are dragged. This enables [`List::draggable_items`].
+    ///
+    /// The item should be removed from the data at the first index, then inserted at the
+    /// second.
+    pub fn on_reorder(self, on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        let mut list = self.draggable_items();
+        if let Some(reorder) = list.reorder.as_mut() {
+            reorder.set_on_reorder(on_reorder);
+        }
+        list
+    }
+
     pub fn on_select(self, on_select: impl Fn(Option<usize>) + 'static) -> Self {
         create_effect(move |_| {
             let selection = self.selection.get();
@@ -41,6 +105,51 @@ impl List {
         });
         self
     }
+
+    /// Calls `on_select` with the key of the selected item of a [`keyed_list`], so the
+    /// callback stays correct when the items are filtered or sorted before reaching the list.
+    ///
+    /// `K` must be the key type of the list, other types give `None`.
+    pub fn on_select_key<K: Clone + 'static>(
+        self,
+        on_select: impl Fn(Option<K>) + 'static,
+    ) -> Self {
+        let keys = self.keys.clone();
+        create_effect(move |_| {
+            let key = self
+                .selection
+                .get()
+                .and_then(|index| keys.get(index))
+                .and_then(|key| key.downcast_ref::<K>())
+                .cloned();
+            on_select(key);
+        });
+        self
+    }
+}
+
+/// A [`list`] with a key for each item, which [`List::on_select_key`] passes to the callback
+/// instead of the index.
+pub fn keyed_list<T, K, V>(
+    items: impl IntoIterator<Item = T>,
+    key_fn: impl Fn(&T) -> K,
+    view_fn: impl Fn(T) -> V,
+) -> List
+where
+    K: Clone + 'static,
+    V: View + 'static,
+{
+    let mut keys: Vec<Box<dyn Any>> = Vec::new();
+    let views: Vec<V> = items
+        .into_iter()
+        .map(|item| {
+            keys.push(Box::new(key_fn(&item)));
+            view_fn(item)
+        })
+        .collect();
+    let mut list = list(views);
+    list.keys = Rc::new(keys);
+    list
 }
 
 pub fn list<V>(iterator: impl IntoIterator<Item = V>) -> List
@@ -49,20 +158,59 @@ where
 {
     let id = Id::next();
     let selection = create_rw_signal(None);
+    let selected = create_rw_signal(IndexSet::new());
+    let multi_select = create_rw_signal(false);
+    // The item a Shift+click or Shift+arrow key range starts from
+    let anchor = create_rw_signal(None::<usize>);
+    create_effect(move |_| {
+        let selection = selection.get();
+        if !multi_select.get_untracked() {
+            selected.set(selection.into_iter().collect());
+        }
+    });
     create_effect(move |_| {
-        selection.track();
+        selected.track();
         id.update_state(ListUpdate::SelectionChanged);
     });
+
+    let select = move |index: usize, modifiers: ModifiersState| {
+        if !multi_select.get_untracked() {
+            if selection.get_untracked() != Some(index) {
+                selection.set(Some(index));
+            }
+            return;
+        }
+        match anchor.get_untracked().or(selection.get_untracked()) {
+            Some(anchor) if modifiers.shift_key() => {
+                selected.set((anchor.min(index)..=anchor.max(index)).collect());
+            }
+            _ if toggle_modifier(modifiers) => {
+                anchor.set(Some(index));
+                selected.update(|selected| {
+                    if !selected.shift_remove(&index) {
+                        selected.insert(index);
+                    }
+                });
+            }
+            _ => {
+                anchor.set(Some(index));
+                selected.set(IndexSet::from([index]));
+            }
+        }
+        selection.set(Some(index));
+    };
+
     let stack = v_stack_from_iter(iterator.into_iter().enumerate().map(move |(index, v)| {
         Item {
             data: ViewData::new(Id::next()),
-            selection,
-            index,
+            is_selected: Box::new(move || {
+                selected.with_untracked(|selected| selected.contains(&index))
+            }),
             child: Box::new(v),
         }
-        .on_click_stop(move |_| {
-            if selection.get_untracked() != Some(index) {
-                selection.set(Some(index))
+        .on_click_stop(move |event| {
+            if let Event::PointerUp(event) = event {
+                select(index, event.modifiers);
             }
         })
     }))
@@ -71,68 +219,278 @@ where
     List {
         data: ViewData::new(id),
         selection,
+        selected,
+        multi_select,
+        keys: Rc::new(Vec::new()),
         child: stack,
+        reorder: None,
     }
     .keyboard_navigatable()
     .on_event(EventListener::KeyDown, move |e| {
-        if let Event::KeyDown(key_event) = e {
-            match key_event.key.logical_key {
-                Key::Named(NamedKey::Home) => {
-                    if length > 0 {
-                        selection.set(Some(0));
-                        id.update_state(ListUpdate::ScrollToSelected);
-                    }
-                    EventPropagation::Stop
-                }
-                Key::Named(NamedKey::End) => {
-                    if length > 0 {
-                        selection.set(Some(length - 1));
-                        id.update_state(ListUpdate::ScrollToSelected);
-                    }
-                    EventPropagation::Stop
+        let Event::KeyDown(key_event) = e else {
+            return EventPropagation::Continue;
+        };
+        if length == 0 {
+            return EventPropagation::Continue;
+        }
+        // Only Shift is passed on, so arrow keys don't toggle items when Ctrl is held
+        let modifiers = key_event.modifiers & ModifiersState::SHIFT;
+        let current = selection.get_untracked();
+        let key = &key_event.key.logical_key;
+        if matches!(key, Key::Character(ch) if ch.eq_ignore_ascii_case("a"))
+            && toggle_modifier(key_event.modifiers)
+            && multi_select.get_untracked()
+        {
+            selected.set((0..length).collect());
+            return EventPropagation::Stop;
+        }
+        let Some(next) = navigation_target(key, current, length) else {
+            return EventPropagation::Continue;
+        };
+        if current != Some(next) || modifiers.shift_key() {
+            select(next, modifiers);
+            id.update_state(ListUpdate::ScrollToSelected);
+        }
+        EventPropagation::Stop
+    })
+}
+
+/// The item Home, End or an arrow key moves the selection to, in a list of `length` items.
+fn navigation_target(key: &Key, current: Option<usize>, length: usize) -> Option<usize> {
+    match key {
+        Key::Named(NamedKey::Home) => Some(0),
+        Key::Named(NamedKey::End) => Some(length - 1),
+        Key::Named(NamedKey::ArrowUp) => Some(current.map_or(length - 1, |i| i.saturating_sub(1))),
+        Key::Named(NamedKey::ArrowDown) => Some(current.map_or(0, |i| (i + 1).min(length - 1))),
+        _ => None,
+    }
+}
+
+pub(super) fn toggle_modifier(modifiers: ModifiersState) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.super_key()
+    } else {
+        modifiers.control_key()
+    }
+}
+
+/// A reactive [`list`]. See [`dyn_list`]
+pub struct DynList<T: 'static, K: 'static> {
+    data: ViewData,
+    selection: RwSignal<Option<usize>>,
+    selected_key: RwSignal<Option<K>>,
+    child: DynStack<Item, T>,
+}
+
+impl<T, K: Clone> DynList<T, K> {
+    /// The index of the selected item, which follows the item as items are added, removed and
+    /// moved.
+    pub fn selection(&self) -> RwSignal<Option<usize>> {
+        self.selection
+    }
+
+    /// The key of the selected item.
+    pub fn selected_key(&self) -> ReadSignal<Option<K>> {
+        self.selected_key.read_only()
+    }
+
+    pub fn on_select(self, on_select: impl Fn(Option<usize>) + 'static) -> Self {
+        let selection = self.selection;
+        create_effect(move |_| on_select(selection.get()));
+        self
+    }
+
+    /// Calls `on_select` with the key of the selected item.
+    pub fn on_select_key(self, on_select: impl Fn(Option<K>) + 'static) -> Self {
+        let selected_key = self.selected_key;
+        create_effect(move |_| on_select(selected_key.get()));
+        self
+    }
+
+    /// Lets the items be dragged to reorder them. See [`DynStack::draggable_items`].
+    pub fn draggable_items(mut self) -> Self {
+        self.child = self.child.draggable_items();
+        self
+    }
+
+    /// Calls `on_reorder` with the index of the item dropped and the index it should move to,
+    /// when the items are dragged. See [`DynStack::on_reorder`].
+    pub fn on_reorder(mut self, on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        self.child = self.child.on_reorder(on_reorder);
+        self
+    }
+}
+
+/// A [`list`] whose items are kept up to date with `each_fn` like a
+/// [`dyn_stack`](super::dyn_stack), keeping the keyboard navigation and selection styling
+/// of a [`list`].
+///
+/// The selection is kept on the same item, by its key, as items are added, removed and
+/// moved, and is cleared when the selected item is removed.
+///
+/// ```ignore
+/// dyn_list(
+///     move || todos.get(),
+///     |todo| todo.id,
+///     |todo| label(move || todo.title.clone()),
+/// )
+/// .on_select_key(|id: Option<u64>| println!("selected {id:?}"))
+/// ```
+pub fn dyn_list<IF, I, T, KF, K, VF, V>(each_fn: IF, key_fn: KF, view_fn: VF) -> DynList<T, K>
+where
+    IF: Fn() -> I + 'static,
+    I: IntoIterator<Item = T>,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + Clone + 'static,
+    VF: Fn(T) -> V + 'static,
+    V: View + 'static,
+    T: 'static,
+{
+    let id = Id::next();
+    let selection = create_rw_signal(None::<usize>);
+    let selected_key = create_rw_signal(None::<K>);
+    // The keys of the items, by index
+    let keys = create_rw_signal(Vec::<K>::new());
+
+    // The selected key follows the selection when it's changed...
+    create_effect(move |_| {
+        let key = selection
+            .get()
+            .and_then(|index| keys.with_untracked(|keys| keys.get(index).cloned()));
+        if selected_key.with_untracked(|selected_key| selected_key != &key) {
+            selected_key.set(key);
+        }
+        id.update_state(ListUpdate::SelectionChanged);
+    });
+    // ...and the selection follows the selected key when the items change
+    create_effect(move |_| {
+        let index = keys.with(|keys| {
+            selected_key.with_untracked(|selected_key| {
+                let selected_key = selected_key.as_ref()?;
+                keys.iter().position(|key| key == selected_key)
+            })
+        });
+        if selection.get_untracked() != index {
+            selection.set(index);
+        }
+    });
+
+    let key_fn = Rc::new(key_fn);
+    let each_key_fn = key_fn.clone();
+    let item_key_fn = key_fn.clone();
+    let stack = dyn_stack(
+        move || {
+            let items: Vec<T> = each_fn().into_iter().collect();
+            keys.set(items.iter().map(|item| each_key_fn(item)).collect());
+            items
+        },
+        move |item| key_fn(item),
+        move |item| {
+            let key = item_key_fn(&item);
+            let selected = key.clone();
+            Item {
+                data: ViewData::new(Id::next()),
+                is_selected: Box::new(move || {
+                    selected_key.with_untracked(|key| key.as_ref() == Some(&selected))
+                }),
+                child: Box::new(view_fn(item)),
+            }
+            .on_click_stop(move |_| {
+                let index = keys.with_untracked(|keys| keys.iter().position(|k| k == &key));
+                if index.is_some() && selection.get_untracked() != index {
+                    selection.set(index);
                 }
-                Key::Named(NamedKey::ArrowUp) => {
-                    let current = selection.get_untracked();
-                    match current {
-                        Some(i) => {
-                            if i > 0 {
-                                selection.set(Some(i - 1));
-                                id.update_state(ListUpdate::ScrollToSelected);
-                            }
-                        }
-                        None => {
-                            if length > 0 {
-                                selection.set(Some(length - 1));
-                                id.update_state(ListUpdate::ScrollToSelected);
-                            }
-                        }
-                    }
-                    EventPropagation::Stop
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full().height_full());
+
+    DynList {
+        data: ViewData::new(id),
+        selection,
+        selected_key,
+        child: stack,
+    }
+    .keyboard_navigatable()
+    .on_event(EventListener::KeyDown, move |e| {
+        let Event::KeyDown(key_event) = e else {
+            return EventPropagation::Continue;
+        };
+        let length = keys.with_untracked(|keys| keys.len());
+        if length == 0 {
+            return EventPropagation::Continue;
+        }
+        let current = selection.get_untracked();
+        let Some(next) = navigation_target(&key_event.key.logical_key, current, length) else {
+            return EventPropagation::Continue;
+        };
+        if current != Some(next) {
+            selection.set(Some(next));
+            id.update_state(ListUpdate::ScrollToSelected);
+        }
+        EventPropagation::Stop
+    })
+}
+
+impl<T, K: 'static> View for DynList<T, K> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "DynList".into()
+    }
+
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::List);
+    }
+
+    fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(change) = state.downcast::<ListUpdate>() {
+            match *change {
+                ListUpdate::SelectionChanged => {
+                    cx.app_state_mut().request_style_recursive(self.id())
                 }
-                Key::Named(NamedKey::ArrowDown) => {
-                    let current = selection.get_untracked();
-                    match current {
-                        Some(i) => {
-                            if i < length - 1 {
-                                selection.set(Some(i + 1));
-                                id.update_state(ListUpdate::ScrollToSelected);
-                            }
-                        }
-                        None => {
-                            if length > 0 {
-                                selection.set(Some(0));
-                                id.update_state(ListUpdate::ScrollToSelected);
-                            }
+                ListUpdate::ScrollToSelected => {
+                    let Some(index) = self.selection.get_untracked() else {
+                        return;
+                    };
+                    let mut item = None;
+                    let mut position = 0;
+                    self.child.for_each_child(&mut |child| {
+                        if position == index {
+                            item = Some(child.id());
+                            return true;
                         }
+                        position += 1;
+                        false
+                    });
+                    if let Some(item) = item {
+                        item.scroll_to(None);
                     }
-                    EventPropagation::Stop
                 }
-                _ => EventPropagation::Continue,
             }
-        } else {
-            EventPropagation::Continue
         }
-    })
+    }
 }
 
 impl View for List {
@@ -163,7 +521,33 @@ impl View for List {
         "List".into()
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::List);
+        if self.multi_select.get_untracked() {
+            cx.node_mut().set_multiselectable();
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        cx.paint_view(&mut self.child);
+        if let Some(reorder) = &self.reorder {
+            reorder.paint(cx, self.id(), false);
+        }
+    }
+
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        let state = match state.downcast::<ReorderMsg>() {
+            Ok(msg) => {
+                if let Some(reorder) = self.reorder.as_mut() {
+                    let items: Vec<Id> = self.child.children.iter().map(|item| item.id()).collect();
+                    if reorder.update(cx.app_state, *msg, &items, false) {
+                        cx.app_state.request_paint(self.id());
+                    }
+                }
+                return;
+            }
+            Err(state) => state,
+        };
         if let Ok(change) = state.downcast::<ListUpdate>() {
             match *change {
                 ListUpdate::SelectionChanged => {
@@ -211,9 +595,13 @@ impl View for Item {
         "Item".into()
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::ListItem);
+        cx.set_selected((self.is_selected)());
+    }
+
     fn style(&mut self, cx: &mut StyleCx<'_>) {
-        let selected = self.selection.get_untracked();
-        if Some(self.index) == selected {
+        if (self.is_selected)() {
             cx.save();
             cx.selected();
             cx.style_view(&mut self.child);
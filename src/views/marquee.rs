@@ -0,0 +1,229 @@
+use floem_peniko::Color;
+use floem_reactive::{create_rw_signal, RwSignal};
+use floem_renderer::Renderer;
+use indexmap::IndexSet;
+use kurbo::{Point, Rect};
+
+use crate::{
+    context::{AppState, EventCx, PaintCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    view::{View, ViewData},
+};
+
+use super::list::toggle_modifier;
+
+const MARQUEE_COLOR: Color = Color::rgb8(0x3b, 0x82, 0xf6);
+const MARQUEE_FILL: Color = Color::rgba8(0x3b, 0x82, 0xf6, 40);
+
+/// How a marquee combines the items it covers with the selection from before the drag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarqueeMode {
+    Replace,
+    /// Shift adds the covered items to the selection.
+    Add,
+    /// Ctrl (Cmd on macOS) toggles the covered items, removing the selected ones.
+    Toggle,
+}
+
+struct MarqueeDrag {
+    start: Point,
+    end: Point,
+    mode: MarqueeMode,
+    initial: IndexSet<usize>,
+}
+
+/// A container with rubber band selection of the children of its child. See
+/// [`marquee_select`]
+pub struct MarqueeSelect {
+    data: ViewData,
+    child: Box<dyn View>,
+    selection: RwSignal<IndexSet<usize>>,
+    on_select: Option<Box<dyn Fn(&IndexSet<usize>)>>,
+    drag: Option<MarqueeDrag>,
+}
+
+/// Lets the children of `child` be selected by dragging a rectangle over them from empty space,
+/// for file managers and canvas editors. The children are identified by their index, so
+/// `child` is usually a stack, a [`dyn_stack`](super::dyn_stack) or an
+/// [`absolute_layout`](super::absolute_layout).
+///
+/// The children touched by the rectangle are selected as it's dragged. Shift adds them to the
+/// selection, and Ctrl (Cmd on macOS) toggles them. Clicking empty space without a modifier
+/// clears the selection.
+///
+/// ```ignore
+/// marquee_select(v_stack_from_iter(files.iter().map(file_row)))
+///     .on_select(|selected| println!("{selected:?}"))
+/// ```
+pub fn marquee_select<V: View + 'static>(child: V) -> MarqueeSelect {
+    MarqueeSelect {
+        data: ViewData::new(Id::next()),
+        child: Box::new(child),
+        selection: create_rw_signal(IndexSet::new()),
+        on_select: None,
+        drag: None,
+    }
+}
+
+impl MarqueeSelect {
+    /// The indices of the selected children, in the order they were selected.
+    pub fn selection(&self) -> RwSignal<IndexSet<usize>> {
+        self.selection
+    }
+
+    /// Calls `on_select` with the selection each time a drag changes it.
+    pub fn on_select(mut self, on_select: impl Fn(&IndexSet<usize>) + 'static) -> Self {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// The rectangles of the children of the child, relative to this view.
+    fn item_rects(&self, app_state: &mut AppState) -> Vec<Rect> {
+        let origin = app_state.get_layout_rect(self.id()).origin().to_vec2();
+        let mut rects = Vec::new();
+        self.child.for_each_child(&mut |item| {
+            rects.push(app_state.get_layout_rect(item.id()) - origin);
+            false
+        });
+        rects
+    }
+
+    fn update_selection(&mut self, app_state: &mut AppState) {
+        let Some(drag) = &self.drag else {
+            return;
+        };
+        let marquee = Rect::from_points(drag.start, drag.end);
+        let covered = self
+            .item_rects(app_state)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, rect)| rect.intersect(marquee).area() > 0.0)
+            .map(|(index, _)| index);
+        let mut selection = drag.initial.clone();
+        for index in covered {
+            match drag.mode {
+                MarqueeMode::Replace | MarqueeMode::Add => {
+                    selection.insert(index);
+                }
+                MarqueeMode::Toggle => {
+                    if !drag.initial.contains(&index) {
+                        selection.insert(index);
+                    } else {
+                        selection.shift_remove(&index);
+                    }
+                }
+            }
+        }
+        if self
+            .selection
+            .with_untracked(|current| *current != selection)
+        {
+            if let Some(on_select) = &self.on_select {
+                on_select(&selection);
+            }
+            self.selection.set(selection);
+        }
+    }
+}
+
+impl View for MarqueeSelect {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "MarqueeSelect".into()
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if self.drag.is_none()
+            && cx
+                .view_event(&mut self.child, id_path, event.clone())
+                .is_processed()
+        {
+            return EventPropagation::Stop;
+        }
+
+        match &event {
+            Event::PointerDown(pointer) if pointer.button.is_primary() => {
+                // Dragging starts from empty space, a press on an item is left to the item
+                if self
+                    .item_rects(cx.app_state)
+                    .iter()
+                    .any(|rect| rect.contains(pointer.pos))
+                {
+                    return EventPropagation::Continue;
+                }
+                let mode = if pointer.modifiers.shift_key() {
+                    MarqueeMode::Add
+                } else if toggle_modifier(pointer.modifiers) {
+                    MarqueeMode::Toggle
+                } else {
+                    MarqueeMode::Replace
+                };
+                let initial = match mode {
+                    MarqueeMode::Replace => IndexSet::new(),
+                    MarqueeMode::Add | MarqueeMode::Toggle => self.selection.get_untracked(),
+                };
+                self.drag = Some(MarqueeDrag {
+                    start: pointer.pos,
+                    end: pointer.pos,
+                    mode,
+                    initial,
+                });
+                self.update_selection(cx.app_state);
+                cx.update_active(self.id());
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer) => match self.drag.as_mut() {
+                Some(drag) => {
+                    drag.end = pointer.pos;
+                    self.update_selection(cx.app_state);
+                    self.id().request_paint();
+                    EventPropagation::Stop
+                }
+                None => EventPropagation::Continue,
+            },
+            Event::PointerUp(_) if self.drag.is_some() => {
+                self.drag = None;
+                self.id().request_paint();
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        cx.paint_view(&mut self.child);
+        if let Some(drag) = &self.drag {
+            let marquee = Rect::from_points(drag.start, drag.end);
+            cx.fill(&marquee, MARQUEE_FILL, 0.0);
+            cx.stroke(&marquee, MARQUEE_COLOR, 1.0);
+        }
+    }
+}
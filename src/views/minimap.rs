@@ -0,0 +1,362 @@
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, ReadSignal, RwSignal};
+use floem_renderer::Renderer;
+use image::DynamicImage;
+use kurbo::{Affine, Point, Rect, Size};
+
+use crate::{
+    context::{EventCx, PaintCx, PaintState},
+    event::Event,
+    id::Id,
+    prop_extracter,
+    style::Background,
+    style_class,
+    view::{View, ViewData},
+    views::Decorators,
+    EventPropagation,
+};
+
+style_class!(pub MinimapIndicator);
+
+prop_extracter! {
+    IndicatorStyle {
+        color: Background,
+    }
+}
+
+const INDICATOR_COLOR: Color = Color::rgba8(128, 128, 128, 60);
+
+/// Connects a [`minimap`] to the scroll view it gives an overview of, which is set up with
+/// [`Scroll::minimap_link`](crate::views::Scroll::minimap_link).
+#[derive(Clone, Copy)]
+pub struct MinimapLink {
+    /// The visible part of the scrolled content, relative to it.
+    pub(crate) viewport: RwSignal<Rect>,
+    pub(crate) content_size: RwSignal<Size>,
+    /// The origin the minimap asks the scroll view to scroll to.
+    pub(crate) scroll_to: RwSignal<Option<Point>>,
+}
+
+impl MinimapLink {
+    pub fn new() -> Self {
+        Self {
+            viewport: create_rw_signal(Rect::ZERO),
+            content_size: create_rw_signal(Size::ZERO),
+            scroll_to: create_rw_signal(None),
+        }
+    }
+
+    /// The visible part of the scrolled content, relative to it.
+    pub fn viewport(&self) -> ReadSignal<Rect> {
+        self.viewport.read_only()
+    }
+
+    /// The size of the scrolled content.
+    pub fn content_size(&self) -> ReadSignal<Size> {
+        self.content_size.read_only()
+    }
+}
+
+impl Default for MinimapLink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum MinimapContent {
+    View(Box<dyn View>),
+    Painter(Box<dyn Fn(&mut PaintCx, Size)>),
+}
+
+/// A scaled-down overview of the content of a scroll view. See [`minimap`]
+pub struct Minimap {
+    data: ViewData,
+    link: MinimapLink,
+    content: MinimapContent,
+    indicator: IndicatorStyle,
+    /// The content drawn at the scale of the minimap, and the hash identifying it in the
+    /// renderer caches.
+    image: Option<(DynamicImage, Vec<u8>)>,
+    generation: u64,
+    /// The distance from the top of the indicator to the pointer while dragging it.
+    drag_offset: Option<f64>,
+}
+
+/// A scaled-down overview of the content of a scroll view, with an indicator of the visible
+/// part which can be dragged to scroll.
+///
+/// `content` builds a second instance of the scrolled content, which is laid out at the width
+/// of the scrolled content and drawn scaled down to the width of the minimap. It doesn't
+/// receive events. Content taller than the minimap scrolls along with the scroll view.
+///
+/// ```ignore
+/// let link = MinimapLink::new();
+/// h_stack((
+///     scroll(document(text)).minimap_link(link),
+///     minimap(link, move || document(text)).style(|s| s.width(80.0).height_full()),
+/// ))
+/// ```
+pub fn minimap<V: View + 'static>(link: MinimapLink, content: impl FnOnce() -> V) -> Minimap {
+    let content = content().style(move |s| {
+        s.absolute()
+            .inset_left(0.0)
+            .inset_top(0.0)
+            .width(link.content_size.get().width)
+    });
+    Minimap::new(link, MinimapContent::View(Box::new(content)))
+}
+
+/// A [`minimap`] drawing a custom summary of the content instead of a scaled-down copy.
+///
+/// `paint` is called with the size of the content, with the drawing scaled down to the width
+/// of the minimap.
+pub fn minimap_painter(link: MinimapLink, paint: impl Fn(&mut PaintCx, Size) + 'static) -> Minimap {
+    Minimap::new(link, MinimapContent::Painter(Box::new(paint)))
+}
+
+impl Minimap {
+    fn new(link: MinimapLink, content: MinimapContent) -> Self {
+        let id = Id::next();
+        create_effect(move |_| {
+            link.viewport.track();
+            link.content_size.track();
+            id.request_paint();
+        });
+        Minimap {
+            data: ViewData::new(id),
+            link,
+            content,
+            indicator: Default::default(),
+            image: None,
+            generation: 0,
+            drag_offset: None,
+        }
+    }
+
+    fn size(&self, cx: &mut EventCx) -> Size {
+        cx.get_size(self.id()).unwrap_or_default()
+    }
+
+    /// The scale of the content, and the distance the scaled content is scrolled by when it's
+    /// taller than the minimap.
+    fn geometry(&self, size: Size) -> (f64, f64) {
+        let content = self.link.content_size.get_untracked();
+        let viewport = self.link.viewport.get_untracked();
+        if content.width <= 0.0 || size.width <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let scale = size.width / content.width;
+        let overflow = content.height * scale - size.height;
+        let scrollable = content.height - viewport.height();
+        let offset = if overflow > 0.0 && scrollable > 0.0 {
+            overflow * (viewport.y0 / scrollable).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (scale, offset)
+    }
+
+    /// The indicator of the visible part of the content, in the coordinates of the minimap.
+    fn indicator_rect(&self, size: Size) -> Rect {
+        let (scale, offset) = self.geometry(size);
+        let viewport = self.link.viewport.get_untracked();
+        Rect::new(
+            0.0,
+            viewport.y0 * scale - offset,
+            size.width,
+            viewport.y1 * scale - offset,
+        )
+    }
+
+    /// Asks the scroll view to scroll so the top of the indicator is at `y`.
+    fn scroll_indicator_to(&self, size: Size, y: f64) {
+        let (scale, offset) = self.geometry(size);
+        if scale <= 0.0 {
+            return;
+        }
+        let viewport = self.link.viewport.get_untracked();
+        self.link
+            .scroll_to
+            .set(Some(Point::new(viewport.x0, (y + offset) / scale)));
+    }
+
+    /// Draws the content offscreen at the scale of the minimap.
+    fn render(&mut self, cx: &mut PaintCx, scale: f64) -> Option<()> {
+        let MinimapContent::View(child) = &mut self.content else {
+            return None;
+        };
+        let layout = cx.get_layout(child.id())?;
+        let content = Size::new(layout.size.width as f64, layout.size.height as f64);
+        let scale = scale * cx.paint_state.scale;
+        let pixels = Size::new(
+            (content.width * scale).ceil(),
+            (content.height * scale).ceil(),
+        );
+        if pixels.is_empty() {
+            return None;
+        }
+        let mut paint_state = PaintState::new_headless(scale, pixels);
+        paint_state.renderer.begin(false);
+        let mut offscreen = PaintCx {
+            app_state: &mut *cx.app_state,
+            paint_state: &mut paint_state,
+            transform: Affine::IDENTITY,
+            clip: None,
+            z_index: None,
+            saved_transforms: Vec::new(),
+            saved_clips: Vec::new(),
+            saved_z_indexes: Vec::new(),
+        };
+        offscreen.offset((-layout.location.x as f64, -layout.location.y as f64));
+        offscreen.paint_view(child);
+        let img = paint_state.renderer.finish()?;
+
+        self.generation += 1;
+        let mut hash = self.id().to_raw().to_le_bytes().to_vec();
+        hash.extend_from_slice(&self.generation.to_le_bytes());
+        self.image = Some((img, hash));
+        Some(())
+    }
+}
+
+impl View for Minimap {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        if let MinimapContent::View(child) = &self.content {
+            for_each(child);
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        if let MinimapContent::View(child) = &mut self.content {
+            for_each(child);
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        if let MinimapContent::View(child) = &mut self.content {
+            for_each(child);
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Minimap".into()
+    }
+
+    fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
+        let style = cx.style().apply_class(MinimapIndicator);
+        if self.indicator.read_style(cx, &style) {
+            cx.app_state_mut().request_paint(self.id());
+        }
+        if let MinimapContent::View(child) = &mut self.content {
+            cx.style_view(child);
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        // The content is only drawn, so it doesn't get the events
+        match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let size = self.size(cx);
+                let indicator = self.indicator_rect(size);
+                let pos = pointer_event.pos;
+                let grab = if indicator.contains(pos) {
+                    pos.y - indicator.y0
+                } else {
+                    // Center the indicator on the pointer
+                    let grab = indicator.height() / 2.0;
+                    self.scroll_indicator_to(size, pos.y - grab);
+                    grab
+                };
+                self.drag_offset = Some(grab);
+                cx.update_active(self.id());
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer_event) => {
+                if let Some(grab) = self.drag_offset {
+                    let size = self.size(cx);
+                    self.scroll_indicator_to(size, pointer_event.pos.y - grab);
+                    return EventPropagation::Stop;
+                }
+                EventPropagation::Continue
+            }
+            Event::PointerUp(_) => {
+                self.drag_offset = None;
+                EventPropagation::Continue
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        let (scale, offset) = self.geometry(size);
+        if scale <= 0.0 {
+            return;
+        }
+        let content = self.link.content_size.get_untracked();
+
+        cx.save();
+        cx.clip(&size.to_rect());
+        match &self.content {
+            MinimapContent::View(_) => {
+                if self.render(cx, scale).is_none() {
+                    self.image = None;
+                }
+                if let Some((img, hash)) = self.image.as_ref() {
+                    let rect = Rect::new(
+                        0.0,
+                        -offset,
+                        content.width * scale,
+                        content.height * scale - offset,
+                    );
+                    cx.draw_img(
+                        floem_renderer::Img {
+                            img,
+                            data: img.as_bytes(),
+                            hash,
+                        },
+                        rect,
+                    );
+                }
+            }
+            MinimapContent::Painter(paint) => {
+                let transform = cx.transform;
+                cx.paint_state.renderer.transform(
+                    transform * Affine::translate((0.0, -offset)) * Affine::scale(scale),
+                );
+                paint(cx, content);
+                cx.paint_state.renderer.transform(transform);
+            }
+        }
+        let indicator = self.indicator_rect(size);
+        cx.fill(
+            &indicator,
+            self.indicator.color().unwrap_or(INDICATOR_COLOR),
+            0.0,
+        );
+        cx.restore();
+    }
+
+    fn cached_images(&self) -> usize {
+        self.image.is_some() as usize
+    }
+}
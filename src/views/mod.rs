@@ -9,6 +9,9 @@ pub use label::*;
 mod rich_text;
 pub use rich_text::*;
 
+mod text_selection;
+pub use text_selection::*;
+
 mod dyn_stack;
 pub use dyn_stack::*;
 
@@ -27,6 +30,12 @@ pub use container_box::*;
 mod dyn_container;
 pub use dyn_container::*;
 
+mod view_slot;
+pub use view_slot::*;
+
+mod error_boundary;
+pub use error_boundary::*;
+
 mod value_container;
 pub use value_container::*;
 
@@ -36,27 +45,71 @@ pub use decorator::*;
 mod list;
 pub use list::*;
 
+mod reorder;
+
+mod marquee;
+pub use marquee::*;
+
+mod minimap;
+pub use minimap::*;
+
+mod pager;
+pub use pager::*;
+
 mod virtual_list;
 pub use virtual_list::*;
 
 mod virtual_stack;
 pub use virtual_stack::*;
 
+mod zoom_pan;
+pub use zoom_pan::*;
+
+mod absolute_layout;
+pub use absolute_layout::*;
+
+mod snapping;
+pub use snapping::*;
+
+mod table;
+pub use table::*;
+
 pub mod scroll;
 pub use scroll::{scroll, Scroll};
 
+mod skeleton;
+pub use skeleton::*;
+
+mod resizable;
+pub use resizable::*;
+
 mod tab;
 pub use tab::*;
 
 mod tooltip;
 pub use tooltip::*;
 
+mod popover;
+pub use popover::*;
+
+mod context_menu;
+pub use context_menu::*;
+
 mod stack;
 pub use stack::*;
 
 mod text_input;
 pub use text_input::*;
 
+mod text_area;
+pub use text_area::*;
+
+mod input_mask;
+pub use input_mask::*;
+
+mod chips_input;
+pub use chips_input::*;
+
 mod empty;
 pub use empty::*;
 
@@ -68,3 +121,14 @@ pub use drag_resize_window_area::*;
 
 mod img;
 pub use img::*;
+
+mod avatar;
+pub use avatar::*;
+
+mod shader_effect;
+pub use shader_effect::*;
+
+#[cfg(feature = "terminal")]
+mod terminal;
+#[cfg(feature = "terminal")]
+pub use terminal::*;
@@ -0,0 +1,301 @@
+use std::time::{Duration, Instant};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Rect, Size};
+
+use crate::{
+    action::exec_after,
+    context::{AppState, ComputeLayoutCx, EventCx, PaintCx, UpdateCx},
+    event::Event,
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style_class,
+    view::{View, ViewData},
+    views::{empty, h_stack_from_iter, Decorators, Stack},
+    EventPropagation,
+};
+
+style_class!(pub PageIndicatorClass);
+style_class!(pub PageIndicatorDotClass);
+
+/// How long the pages slide when the page changes.
+const TRANSITION: Duration = Duration::from_millis(250);
+
+/// How far, in pages, a swipe has to move to change the page.
+const SWIPE_THRESHOLD: f64 = 0.2;
+
+/// How far the pointer moves before a press becomes a swipe, so clicks still reach the pages.
+const DRAG_SLOP: f64 = 8.0;
+
+enum PagerUpdate {
+    Page(usize),
+    Tick,
+}
+
+struct PagerDrag {
+    start_x: f64,
+    start_position: f64,
+    swiping: bool,
+}
+
+/// A view showing one page at a time. See [`pager`]
+pub struct Pager {
+    data: ViewData,
+    child: Stack,
+    current: RwSignal<usize>,
+    count: usize,
+    width: f64,
+    /// The horizontal position of the pages, in pages from the first one.
+    position: f64,
+    target: usize,
+    /// The position the transition started from, and when.
+    transition: Option<(f64, Instant)>,
+    drag: Option<PagerDrag>,
+}
+
+/// Shows one of `pages` at a time, changing pages with swipes and the left and right arrow
+/// keys. The pages slide when the page changes.
+///
+/// The current page is held by [`Pager::current`], which can also be set to change the page.
+/// [`page_indicator`] shows a row of dots for the pages.
+pub fn pager<V>(pages: impl IntoIterator<Item = V>) -> Pager
+where
+    V: View + 'static,
+{
+    let id = Id::next();
+    let current = create_rw_signal(0);
+    create_effect(move |_| {
+        id.update_state(PagerUpdate::Page(current.get()));
+    });
+    let child = h_stack_from_iter(
+        pages
+            .into_iter()
+            .map(|page| page.style(|s| s.width_full().height_full().flex_shrink(0.0))),
+    )
+    .style(|s| s.width_full().height_full());
+    let count = child.children.len();
+    Pager {
+        data: ViewData::new(id),
+        child,
+        current,
+        count,
+        width: 0.0,
+        position: 0.0,
+        target: 0,
+        transition: None,
+        drag: None,
+    }
+    .keyboard_navigatable()
+}
+
+/// A row of dots for the pages of a [`pager`], highlighting the current page. Clicking a dot
+/// shows its page.
+pub fn page_indicator(current: RwSignal<usize>, count: usize) -> Stack {
+    h_stack_from_iter((0..count).map(move |index| {
+        empty()
+            .class(PageIndicatorDotClass)
+            .style(move |s| {
+                s.size(8.0, 8.0)
+                    .border_radius(4.0)
+                    .background(Color::rgba8(0, 0, 0, 60))
+                    .apply_if(current.get() == index, |s| {
+                        s.background(Color::rgba8(0, 0, 0, 160))
+                    })
+            })
+            .on_click_stop(move |_| current.set(index))
+    }))
+    .class(PageIndicatorClass)
+    .style(|s| s.gap(6.0, 0.0).justify_center().padding(6.0))
+}
+
+impl Pager {
+    /// The index of the current page.
+    pub fn current(&self) -> RwSignal<usize> {
+        self.current
+    }
+
+    fn set_position(&mut self, cx: &mut UpdateCx, position: f64) {
+        self.position = position;
+        self.update_viewport(cx.app_state_mut());
+    }
+
+    fn update_viewport(&self, app_state: &mut AppState) {
+        let size = app_state
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        let origin = Point::new(self.position * size.width, 0.0);
+        app_state.set_viewport(self.child.id(), Rect::from_origin_size(origin, size));
+        app_state.request_compute_layout_recursive(self.id());
+        app_state.request_paint(self.id());
+    }
+
+    fn schedule_tick(&self) {
+        let id = self.id();
+        exec_after(Duration::from_millis(16), move |_| {
+            id.update_state(PagerUpdate::Tick);
+        });
+    }
+
+    fn max_position(&self) -> f64 {
+        self.count.saturating_sub(1) as f64
+    }
+}
+
+impl View for Pager {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Pager".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(update) = state.downcast::<PagerUpdate>() {
+            match *update {
+                PagerUpdate::Page(page) => {
+                    self.target = page.min(self.count.saturating_sub(1));
+                    if self.width <= 0.0 {
+                        // Nothing is shown yet, so there's nothing to animate
+                        self.set_position(cx, self.target as f64);
+                    } else {
+                        if self.transition.is_none() {
+                            self.schedule_tick();
+                        }
+                        self.transition = Some((self.position, Instant::now()));
+                    }
+                }
+                PagerUpdate::Tick => {
+                    let Some((from, start)) = self.transition else {
+                        return;
+                    };
+                    let t = (start.elapsed().as_secs_f64() / TRANSITION.as_secs_f64()).min(1.0);
+                    let eased = 1.0 - (1.0 - t).powi(3);
+                    let position = from + (self.target as f64 - from) * eased;
+                    self.set_position(cx, position);
+                    if t < 1.0 {
+                        self.schedule_tick();
+                    } else {
+                        self.transition = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        let width = cx
+            .get_layout(self.id())
+            .map(|layout| layout.size.width as f64)
+            .unwrap_or_default();
+        if width != self.width {
+            self.width = width;
+            self.update_viewport(cx.app_state_mut());
+        }
+        cx.compute_view_layout(&mut self.child);
+        None
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::KeyDown(key_event) if cx.app_state.is_focused(&self.id()) => {
+                let current = self.current.get_untracked();
+                match key_event.key.logical_key {
+                    Key::Named(NamedKey::ArrowLeft) if current > 0 => {
+                        self.current.set(current - 1);
+                        return EventPropagation::Stop;
+                    }
+                    Key::Named(NamedKey::ArrowRight) if current + 1 < self.count => {
+                        self.current.set(current + 1);
+                        return EventPropagation::Stop;
+                    }
+                    _ => {}
+                }
+            }
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                self.drag = Some(PagerDrag {
+                    start_x: pointer_event.pos.x,
+                    start_position: self.position,
+                    swiping: false,
+                });
+            }
+            Event::PointerMove(pointer_event) => {
+                if let Some(drag) = self.drag.as_mut() {
+                    let dx = pointer_event.pos.x - drag.start_x;
+                    if !drag.swiping && dx.abs() > DRAG_SLOP {
+                        drag.swiping = true;
+                        cx.update_active(self.id());
+                    }
+                    if drag.swiping && self.width > 0.0 {
+                        let position =
+                            (drag.start_position - dx / self.width).clamp(0.0, self.max_position());
+                        self.transition = None;
+                        self.position = position;
+                        self.update_viewport(cx.app_state);
+                        return EventPropagation::Stop;
+                    }
+                }
+            }
+            Event::PointerUp(_) => {
+                if let Some(drag) = self.drag.take() {
+                    if drag.swiping {
+                        let start = drag.start_position.round();
+                        let moved = self.position - drag.start_position;
+                        let page = if moved > SWIPE_THRESHOLD {
+                            start + 1.0
+                        } else if moved < -SWIPE_THRESHOLD {
+                            start - 1.0
+                        } else {
+                            start
+                        };
+                        // Setting the page animates to it, even when it doesn't change
+                        self.current
+                            .set(page.clamp(0.0, self.max_position()) as usize);
+                        return EventPropagation::Stop;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        cx.view_event(&mut self.child, id_path, event)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        cx.save();
+        cx.clip(&size.to_rect());
+        cx.offset((-self.position * size.width, 0.0));
+        cx.paint_view(&mut self.child);
+        cx.restore();
+    }
+}
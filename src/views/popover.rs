@@ -0,0 +1,264 @@
+use std::rc::Rc;
+
+use floem_reactive::create_effect;
+use kurbo::{Point, Rect, Size, Vec2};
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{AppState, ComputeLayoutCx, EventCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    style_class,
+    view::{default_compute_layout, View, ViewData},
+    views::{container_box, Decorators},
+};
+
+style_class!(pub PopoverClass);
+
+/// Where the content of a [`Popover`] is placed relative to its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PopoverPlacement {
+    /// Below the anchor, aligned to its left edge.
+    #[default]
+    Below,
+    /// Right of the anchor, aligned to its top edge. Useful for nested popovers.
+    Right,
+}
+
+pub(crate) enum PopoverUpdate {
+    Show(bool),
+    Dismiss,
+}
+
+/// A view that opens anchored content above everything else. See [`popover`]
+pub struct Popover {
+    data: ViewData,
+    child: Box<dyn View>,
+    content: Rc<dyn Fn() -> Box<dyn View>>,
+    placement: PopoverPlacement,
+    overlay: Option<Id>,
+    /// The view that had focus when the popover opened, to return focus to when it closes.
+    prev_focus: Option<Id>,
+    window_origin: Point,
+    size: Size,
+    on_toggle: Option<Box<dyn Fn(bool)>>,
+}
+
+/// A view that opens `content` in an overlay anchored to `anchor` when the anchor is clicked.
+///
+/// The content is focused when the popover opens and focus returns to the previously focused
+/// view when it closes. The popover closes when clicking outside of both the anchor and the
+/// content, or when pressing `Escape`. Popovers can be nested by placing a popover inside the
+/// content of another one; only the innermost popover is closed by `Escape`.
+///
+/// The content is wrapped in a view with the [`PopoverClass`] class.
+///
+/// ## Example
+/// ```ignore
+/// let open = create_rw_signal(false);
+/// popover(label(|| "Options"), || {
+///     v_stack((label(|| "First"), label(|| "Second")))
+/// })
+/// .show(move || open.get())
+/// .on_toggle(move |is_open| open.set(is_open))
+/// ```
+pub fn popover<V: View + 'static, C: View + 'static>(
+    anchor: V,
+    content: impl Fn() -> C + 'static,
+) -> Popover {
+    Popover {
+        data: ViewData::new(Id::next()),
+        child: Box::new(anchor),
+        content: Rc::new(move || Box::new(content())),
+        placement: PopoverPlacement::default(),
+        overlay: None,
+        prev_focus: None,
+        window_origin: Point::ZERO,
+        size: Size::ZERO,
+        on_toggle: None,
+    }
+}
+
+impl Popover {
+    /// Sets where the content is placed relative to the anchor.
+    pub fn placement(mut self, placement: PopoverPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Reactively opens or closes the popover.
+    pub fn show(self, show: impl Fn() -> bool + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_state(PopoverUpdate::Show(show()));
+        });
+        self
+    }
+
+    /// Called when the popover is opened or closed by the user.
+    pub fn on_toggle(mut self, on_toggle: impl Fn(bool) + 'static) -> Self {
+        self.on_toggle = Some(Box::new(on_toggle));
+        self
+    }
+
+    fn open(&mut self, app_state: &mut AppState) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let position = match self.placement {
+            PopoverPlacement::Below => self.window_origin + Vec2::new(0.0, self.size.height),
+            PopoverPlacement::Right => self.window_origin + Vec2::new(self.size.width, 0.0),
+        };
+        let content = self.content.clone();
+        let overlay = add_overlay(position, move |_| {
+            let content = container_box(content())
+                .keyboard_navigatable()
+                .class(PopoverClass);
+            content.id().request_focus();
+            content
+        });
+        self.prev_focus = app_state.focus;
+        app_state.open_popovers.push((self.id(), overlay));
+        self.overlay = Some(overlay);
+    }
+
+    fn close(&mut self, app_state: &mut AppState) {
+        let Some(overlay) = self.overlay.take() else {
+            return;
+        };
+        let id = self.id();
+        app_state
+            .open_popovers
+            .retain(|(popover, _)| *popover != id);
+
+        let focus_inside = app_state.focus.map_or(true, |focus| {
+            focus
+                .id_path()
+                .map_or(false, |path| path.0.contains(&overlay))
+        });
+        if focus_inside {
+            if let Some(prev_focus) = self.prev_focus.take() {
+                prev_focus.request_focus();
+            }
+        }
+        remove_overlay(overlay);
+    }
+
+    fn set_open(&mut self, app_state: &mut AppState, open: bool) {
+        if open {
+            self.open(app_state);
+        } else {
+            self.close(app_state);
+        }
+    }
+}
+
+/// Closes the open popovers, innermost first, until one is found whose anchor or content
+/// contains `pos`.
+pub(crate) fn dismiss_popovers_at(app_state: &mut AppState, pos: Point) {
+    while let Some(&(popover, overlay)) = app_state.open_popovers.last() {
+        let contains = |id: Id| {
+            app_state
+                .view_states
+                .get(&id)
+                .map_or(false, |state| state.layout_rect.contains(pos))
+        };
+        if app_state.view_states.contains_key(&popover) && (contains(popover) || contains(overlay))
+        {
+            break;
+        }
+        app_state.open_popovers.pop();
+        popover.update_state(PopoverUpdate::Dismiss);
+    }
+}
+
+/// Closes the innermost open popover.
+pub(crate) fn dismiss_top_popover(app_state: &mut AppState) {
+    while let Some((popover, _)) = app_state.open_popovers.pop() {
+        if app_state.view_states.contains_key(&popover) {
+            popover.update_state(PopoverUpdate::Dismiss);
+            return;
+        }
+    }
+}
+
+impl View for Popover {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Popover".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<PopoverUpdate>() {
+            match *state {
+                PopoverUpdate::Show(show) => self.set_open(cx.app_state, show),
+                PopoverUpdate::Dismiss => {
+                    if self.overlay.is_some() {
+                        self.close(cx.app_state);
+                        if let Some(on_toggle) = &self.on_toggle {
+                            on_toggle(false);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        if let Some(layout) = cx.get_layout(self.id()) {
+            self.size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        }
+        default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let propagation = cx.view_event(&mut self.child, id_path, event.clone());
+        if let Event::PointerDown(pointer_event) = &event {
+            if pointer_event.button.is_primary() && propagation.is_continue() {
+                let open = self.overlay.is_none();
+                self.set_open(cx.app_state, open);
+                if let Some(on_toggle) = &self.on_toggle {
+                    on_toggle(open);
+                }
+                return EventPropagation::Stop;
+            }
+        }
+        propagation
+    }
+}
+
+impl Drop for Popover {
+    fn drop(&mut self) {
+        if let Some(id) = self.overlay {
+            remove_overlay(id)
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{cell::Cell, collections::HashSet, rc::Rc};
+
+use floem_peniko::Color;
+use floem_renderer::Renderer;
+use kurbo::{Point, Rect};
+
+use crate::{
+    context::{AppState, PaintCx},
+    event::{EventListener, EventPropagation},
+    id::Id,
+};
+
+const INDICATOR_COLOR: Color = Color::rgb8(0x3b, 0x82, 0xf6);
+const INDICATOR_WIDTH: f64 = 2.0;
+
+/// What the items of a container with draggable items report to it.
+pub(crate) enum ReorderMsg {
+    Start(Id),
+    /// The dragged item is over an item, at a position in the item's coordinates.
+    Over(Id, Point),
+    Drop,
+    End,
+}
+
+/// The drag to reorder state of a container, for `draggable_items` on [`List`](super::List)
+/// and [`DynStack`](super::DynStack).
+#[derive(Default)]
+pub(crate) struct Reorder {
+    on_reorder: Option<Box<dyn Fn(usize, usize)>>,
+    /// The items which report their drags.
+    items: HashSet<Id>,
+    /// Whether one of the items is dragged, so drags of other views are left alone.
+    active: Rc<Cell<bool>>,
+    dragging: Option<Id>,
+    /// The item the dragged item is over, and whether it would go after it.
+    target: Option<(Id, bool)>,
+}
+
+impl Reorder {
+    pub(crate) fn set_on_reorder(&mut self, on_reorder: impl Fn(usize, usize) + 'static) {
+        self.on_reorder = Some(Box::new(on_reorder));
+    }
+
+    /// Makes `item` draggable, reporting its drags and the drags over it to `container`.
+    ///
+    /// This takes the drag and drop event listeners of the item.
+    pub(crate) fn attach(&mut self, container: Id, item: Id) {
+        if !self.items.insert(item) {
+            return;
+        }
+        item.draggable();
+        let active = self.active.clone();
+        item.update_event_listener(
+            EventListener::DragStart,
+            Box::new(move |_| {
+                active.set(true);
+                container.update_state(ReorderMsg::Start(item));
+                EventPropagation::Continue
+            }),
+        );
+        let active = self.active.clone();
+        item.update_event_listener(
+            EventListener::DragOver,
+            Box::new(move |event| {
+                if let Some(pos) = event.point().filter(|_| active.get()) {
+                    container.update_state(ReorderMsg::Over(item, pos));
+                }
+                EventPropagation::Continue
+            }),
+        );
+        let active = self.active.clone();
+        item.update_event_listener(
+            EventListener::Drop,
+            Box::new(move |_| {
+                if !active.get() {
+                    return EventPropagation::Continue;
+                }
+                container.update_state(ReorderMsg::Drop);
+                // Processing the drop keeps the dragged item from sliding back
+                EventPropagation::Stop
+            }),
+        );
+        let active = self.active.clone();
+        item.update_event_listener(
+            EventListener::DragEnd,
+            Box::new(move |_| {
+                active.set(false);
+                container.update_state(ReorderMsg::End);
+                EventPropagation::Continue
+            }),
+        );
+    }
+
+    /// Forgets the items which were removed from the container.
+    pub(crate) fn retain(&mut self, items: &[Id]) {
+        let items: HashSet<Id> = items.iter().copied().collect();
+        self.items.retain(|item| items.contains(item));
+    }
+
+    /// Handles a message from one of the `items` of the container, in their order, calling
+    /// the reorder callback on a drop. Returns whether the insertion indicator changed.
+    pub(crate) fn update(
+        &mut self,
+        app_state: &mut AppState,
+        msg: ReorderMsg,
+        items: &[Id],
+        horizontal: bool,
+    ) -> bool {
+        match msg {
+            ReorderMsg::Start(item) => {
+                self.dragging = Some(item);
+                self.target = None;
+                false
+            }
+            ReorderMsg::Over(item, pos) => {
+                let size = app_state.get_layout_rect(item).size();
+                let after = if horizontal {
+                    pos.x > size.width / 2.0
+                } else {
+                    pos.y > size.height / 2.0
+                };
+                let target = Some((item, after));
+                let changed = self.target != target;
+                self.target = target;
+                changed
+            }
+            ReorderMsg::Drop => {
+                if let (Some(dragging), Some((target, after))) = (self.dragging, self.target) {
+                    let from = items.iter().position(|item| *item == dragging);
+                    let slot = items
+                        .iter()
+                        .position(|item| *item == target)
+                        .map(|index| index + after as usize);
+                    if let (Some(from), Some(slot)) = (from, slot) {
+                        // The slot counts the dragged item, which is taken out first
+                        let to = if slot > from { slot - 1 } else { slot };
+                        if to != from {
+                            if let Some(on_reorder) = &self.on_reorder {
+                                on_reorder(from, to);
+                            }
+                        }
+                    }
+                }
+                self.clear()
+            }
+            ReorderMsg::End => self.clear(),
+        }
+    }
+
+    fn clear(&mut self) -> bool {
+        let had_target = self.target.is_some();
+        self.dragging = None;
+        self.target = None;
+        had_target
+    }
+
+    /// Paints a line where the dragged item would be dropped, in the coordinates of
+    /// `container`.
+    pub(crate) fn paint(&self, cx: &mut PaintCx, container: Id, horizontal: bool) {
+        let Some((target, after)) = self.target else {
+            return;
+        };
+        if Some(target) == self.dragging {
+            return;
+        }
+        let origin = cx.app_state.get_layout_rect(container).origin();
+        let rect = cx.app_state.get_layout_rect(target) - origin.to_vec2();
+        let half = INDICATOR_WIDTH / 2.0;
+        let line = if horizontal {
+            let x = if after { rect.x1 } else { rect.x0 };
+            Rect::new(x - half, rect.y0, x + half, rect.y1)
+        } else {
+            let y = if after { rect.y1 } else { rect.y0 };
+            Rect::new(rect.x0, y - half, rect.x1, y + half)
+        };
+        cx.fill(&line, INDICATOR_COLOR, 0.0);
+    }
+}
@@ -0,0 +1,312 @@
+use bitflags::bitflags;
+use floem_reactive::create_effect;
+use kurbo::{Point, Rect, Size};
+use taffy::{prelude::Node, style::Dimension};
+
+use crate::{
+    context::{ComputeLayoutCx, EventCx, LayoutCx, StyleCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    prop, prop_extracter,
+    style::CursorStyle,
+    unit::Px,
+    view::{default_compute_layout, View, ViewData},
+};
+
+bitflags! {
+    /// The edges of a [`Resizable`] that can be dragged.
+    ///
+    /// Combining two adjacent edges also enables dragging the corner between them.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ResizeEdges: u8 {
+        const LEFT = 1;
+        const RIGHT = 2;
+        const TOP = 4;
+        const BOTTOM = 8;
+    }
+}
+
+impl ResizeEdges {
+    fn cursor(self) -> Option<CursorStyle> {
+        let horizontal = self.intersects(ResizeEdges::LEFT | ResizeEdges::RIGHT);
+        let vertical = self.intersects(ResizeEdges::TOP | ResizeEdges::BOTTOM);
+        match (horizontal, vertical) {
+            (true, true) => {
+                if self == ResizeEdges::LEFT | ResizeEdges::TOP
+                    || self == ResizeEdges::RIGHT | ResizeEdges::BOTTOM
+                {
+                    Some(CursorStyle::NwseResize)
+                } else {
+                    Some(CursorStyle::NeswResize)
+                }
+            }
+            (true, false) => Some(CursorStyle::ColResize),
+            (false, true) => Some(CursorStyle::RowResize),
+            (false, false) => None,
+        }
+    }
+}
+
+// The width of the area along each enabled edge which can be dragged.
+prop!(pub ResizeHandleSize: Px {} = Px(6.0));
+
+prop_extracter! {
+    ResizableStyle {
+        handle_size: ResizeHandleSize,
+    }
+}
+
+enum ResizableUpdate {
+    Size(Option<Size>),
+}
+
+/// A wrapper that lets the user resize its child by dragging its edges. See [`resizable`]
+pub struct Resizable {
+    data: ViewData,
+    child: Box<dyn View>,
+    edges: ResizeEdges,
+    min_size: Size,
+    max_size: Size,
+    /// The size set by dragging or by [`Resizable::size`]. `None` uses the styled size.
+    size: Option<Size>,
+    layout_size: Size,
+    window_origin: Point,
+    /// The edges being dragged, with the window position and size at the start of the drag.
+    held: Option<(ResizeEdges, Point, Size)>,
+    on_resize: Option<Box<dyn Fn(Size)>>,
+    style: ResizableStyle,
+}
+
+/// A wrapper that lets the user resize its child by dragging its edges.
+///
+/// By default the right and bottom edges can be dragged. Once the user drags an edge the
+/// dragged size takes precedence over the width and height set in the style, clamped to
+/// [`Resizable::min_size`] and [`Resizable::max_size`].
+///
+/// ## Example
+/// ```ignore
+/// let size = create_rw_signal(None);
+/// resizable(sidebar())
+///     .edges(ResizeEdges::RIGHT)
+///     .min_size(120.0, 0.0)
+///     .max_size(480.0, f64::INFINITY)
+///     .size(move || size.get())
+///     .on_resize_drag(move |new_size| size.set(Some(new_size)))
+/// ```
+pub fn resizable<V: View + 'static>(child: V) -> Resizable {
+    Resizable {
+        data: ViewData::new(Id::next()),
+        child: Box::new(child),
+        edges: ResizeEdges::RIGHT | ResizeEdges::BOTTOM,
+        min_size: Size::ZERO,
+        max_size: Size::new(f64::INFINITY, f64::INFINITY),
+        size: None,
+        layout_size: Size::ZERO,
+        window_origin: Point::ZERO,
+        held: None,
+        on_resize: None,
+        style: Default::default(),
+    }
+}
+
+impl Resizable {
+    /// Sets which edges can be dragged.
+    pub fn edges(mut self, edges: ResizeEdges) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// Sets the minimum size the user can drag this view to.
+    pub fn min_size(mut self, width: f64, height: f64) -> Self {
+        self.min_size = Size::new(width, height);
+        self
+    }
+
+    /// Sets the maximum size the user can drag this view to.
+    pub fn max_size(mut self, width: f64, height: f64) -> Self {
+        self.max_size = Size::new(width, height);
+        self
+    }
+
+    /// Reactively sets the size of this view. `None` resets it to the styled size.
+    pub fn size(self, size: impl Fn() -> Option<Size> + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_state(ResizableUpdate::Size(size()));
+        });
+        self
+    }
+
+    /// Called with the new size whenever the user drags an edge.
+    pub fn on_resize_drag(mut self, on_resize: impl Fn(Size) + 'static) -> Self {
+        self.on_resize = Some(Box::new(on_resize));
+        self
+    }
+
+    fn clamp(&self, size: Size) -> Size {
+        size.clamp(self.min_size, self.max_size)
+    }
+
+    /// Returns the enabled edges that are under `pos`.
+    fn hit_edges(&self, pos: Point) -> ResizeEdges {
+        let handle = self.style.handle_size().0;
+        let rect = self.layout_size.to_rect();
+        if !rect.inset(handle / 2.0).contains(pos) {
+            return ResizeEdges::empty();
+        }
+        let mut edges = ResizeEdges::empty();
+        if pos.x < rect.x0 + handle {
+            edges |= ResizeEdges::LEFT;
+        } else if pos.x > rect.x1 - handle {
+            edges |= ResizeEdges::RIGHT;
+        }
+        if pos.y < rect.y0 + handle {
+            edges |= ResizeEdges::TOP;
+        } else if pos.y > rect.y1 - handle {
+            edges |= ResizeEdges::BOTTOM;
+        }
+        edges & self.edges
+    }
+
+    fn drag_to(&mut self, cx: &mut EventCx, pos: Point) {
+        let Some((edges, start_pos, start_size)) = self.held else {
+            return;
+        };
+        let delta = self.window_origin + pos.to_vec2() - start_pos;
+        let mut size = start_size;
+        if edges.contains(ResizeEdges::RIGHT) {
+            size.width += delta.x;
+        } else if edges.contains(ResizeEdges::LEFT) {
+            size.width -= delta.x;
+        }
+        if edges.contains(ResizeEdges::BOTTOM) {
+            size.height += delta.y;
+        } else if edges.contains(ResizeEdges::TOP) {
+            size.height -= delta.y;
+        }
+        let size = self.clamp(size);
+        if Some(size) != self.size {
+            self.size = Some(size);
+            cx.request_layout(self.id());
+            if let Some(on_resize) = &self.on_resize {
+                on_resize(size);
+            }
+        }
+    }
+}
+
+impl View for Resizable {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Resizable".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<ResizableUpdate>() {
+            match *state {
+                ResizableUpdate::Size(size) => {
+                    self.size = size.map(|size| self.clamp(size));
+                }
+            }
+            cx.request_layout(self.id());
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        self.style.read(cx);
+        cx.style_view(&mut self.child);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> Node {
+        let node = cx.layout_node(self.id(), true, |cx| vec![cx.layout_view(&mut self.child)]);
+        if let Some(size) = self.size {
+            let mut style = cx
+                .app_state_mut()
+                .view_state(self.id())
+                .combined_style
+                .to_taffy_style();
+            if self
+                .edges
+                .intersects(ResizeEdges::LEFT | ResizeEdges::RIGHT)
+            {
+                style.size.width = Dimension::Points(size.width as f32);
+            }
+            if self
+                .edges
+                .intersects(ResizeEdges::TOP | ResizeEdges::BOTTOM)
+            {
+                style.size.height = Dimension::Points(size.height as f32);
+            }
+            cx.set_style(node, style);
+        }
+        node
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        if let Some(layout) = cx.get_layout(self.id()) {
+            self.layout_size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        }
+        default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let edges = self.hit_edges(pointer_event.pos);
+                if !edges.is_empty() {
+                    let start_pos = self.window_origin + pointer_event.pos.to_vec2();
+                    self.held = Some((edges, start_pos, self.layout_size));
+                    cx.update_active(self.id());
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerMove(pointer_event) => {
+                if let Some((edges, _, _)) = self.held {
+                    cx.app_state.cursor = edges.cursor();
+                    self.drag_to(cx, pointer_event.pos);
+                    return EventPropagation::Stop;
+                }
+                if let Some(cursor) = self.hit_edges(pointer_event.pos).cursor() {
+                    cx.app_state.cursor = Some(cursor);
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerUp(_) => {
+                if self.held.take().is_some() {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+
+        cx.view_event(&mut self.child, id_path, event)
+    }
+}
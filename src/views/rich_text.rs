@@ -1,24 +1,161 @@
-use std::any::Any;
+use std::{any::Any, ops::Range, rc::Rc};
 
+use floem_peniko::Color;
 use floem_reactive::create_effect;
-use floem_renderer::{cosmic_text::TextLayout, Renderer};
+use floem_renderer::{
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, Style as FontStyle, TextLayout, Weight},
+    Renderer,
+};
 use kurbo::{Point, Rect};
 use taffy::prelude::Node;
 
 use crate::{
-    context::UpdateCx,
+    context::{EventCx, StyleCx, UpdateCx},
+    event::{Event, EventPropagation},
     id::Id,
-    style::{Style, TextOverflow},
+    prop_extracter,
+    style::{
+        apply_font_variations, CursorStyle, FontProps, LineHeight, Style, TextColor, TextOverflow,
+        TextShadowProp,
+    },
     unit::PxPct,
     view::{View, ViewData},
+    views::{range_segments, SelectionColor, TextSelection, TextShadows},
 };
 
+prop_extracter! {
+    Extracter {
+        color: TextColor,
+        line_height: LineHeight,
+        text_shadow: TextShadowProp,
+        selection_color: SelectionColor,
+    }
+}
+
+/// A piece of the text of [`text_spans`] with its own style.
+pub struct TextSpan {
+    text: String,
+    color: Option<Color>,
+    weight: Option<Weight>,
+    italic: bool,
+    font_size: Option<f32>,
+    underline: bool,
+    strikethrough: bool,
+    on_click: Option<Rc<dyn Fn()>>,
+}
+
+/// A piece of text for [`text_spans`], styled like the rest of the view until its own style
+/// is set.
+pub fn span(text: impl Into<String>) -> TextSpan {
+    TextSpan {
+        text: text.into(),
+        color: None,
+        weight: None,
+        italic: false,
+        font_size: None,
+        underline: false,
+        strikethrough: false,
+        on_click: None,
+    }
+}
+
+impl TextSpan {
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(Weight::BOLD)
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    /// Called when the span is clicked.
+    pub fn on_click(mut self, on_click: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Rc::new(on_click));
+        self
+    }
+
+    /// Makes the span a link, underlined and calling `on_click` when clicked.
+    pub fn link(self, on_click: impl Fn() + 'static) -> Self {
+        self.underline().on_click(on_click)
+    }
+
+    fn attrs<'a>(&self, mut attrs: Attrs<'a>) -> Attrs<'a> {
+        if let Some(color) = self.color {
+            attrs = attrs.color(color);
+        }
+        if let Some(weight) = self.weight {
+            attrs = attrs.weight(weight);
+        }
+        if self.italic {
+            attrs = attrs.style(FontStyle::Italic);
+        }
+        if let Some(font_size) = self.font_size {
+            attrs = attrs.font_size(font_size);
+        }
+        attrs
+    }
+}
+
 pub struct RichText {
     data: ViewData,
     text_layout: TextLayout,
     text_node: Option<Node>,
     text_overflow: TextOverflow,
     available_width: f32,
+    font: FontProps,
+    style: Extracter,
+    shadows: TextShadows,
+    /// The spans of a [`text_spans`] view and their ranges in the text.
+    spans: Option<Vec<(Range<usize>, TextSpan)>>,
+    selectable: bool,
+    selection: TextSelection,
+    /// The span the primary button was pressed on, clicked if it's released on it.
+    pressed_span: Option<usize>,
+}
+
+impl RichText {
+    fn new(id: Id, text_layout: TextLayout) -> Self {
+        RichText {
+            data: ViewData::new(id),
+            text_layout,
+            text_node: None,
+            text_overflow: TextOverflow::Wrap,
+            available_width: 0.0,
+            font: FontProps::default(),
+            style: Default::default(),
+            shadows: TextShadows::default(),
+            spans: None,
+            selectable: false,
+            selection: TextSelection::default(),
+            pressed_span: None,
+        }
+    }
 }
 
 pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
@@ -28,12 +165,136 @@ pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
         let new_text_layout = text_layout();
         id.update_state(new_text_layout);
     });
-    RichText {
-        data: ViewData::new(id),
-        text_layout: text,
-        text_node: None,
-        text_overflow: TextOverflow::Wrap,
-        available_width: 0.0,
+    RichText::new(id, text)
+}
+
+/// A text made of spans with their own color, weight, decorations and click handlers, wrapped
+/// as a whole. The font, size and color of the view's style apply to the spans which don't
+/// set their own.
+///
+/// ## Example
+/// ```ignore
+/// text_spans(move || {
+///     vec![
+///         span("Found "),
+///         span(query.get()).bold(),
+///         span(" in "),
+///         span("main.rs").link(move || open("main.rs")),
+///     ]
+/// })
+/// .selectable()
+/// ```
+pub fn text_spans(spans: impl Fn() -> Vec<TextSpan> + 'static) -> RichText {
+    let id = Id::next();
+    let initial = spans();
+    create_effect(move |prev| {
+        let new_spans = spans();
+        // The first spans are passed to the view directly
+        if prev.is_some() {
+            id.update_state(new_spans);
+        }
+    });
+    let mut rich_text = RichText::new(id, TextLayout::new());
+    rich_text.set_spans(initial);
+    rich_text
+}
+
+impl RichText {
+    /// Lets the text be selected like a [`Label::selectable`](crate::views::Label::selectable).
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        self
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        let range = self.selection.range()?;
+        self.text().get(range).map(str::to_string)
+    }
+
+    fn text(&self) -> String {
+        let lines: Vec<&str> = self
+            .text_layout
+            .lines
+            .iter()
+            .map(|line| line.text())
+            .collect();
+        lines.join("\n")
+    }
+
+    fn set_spans(&mut self, spans: Vec<TextSpan>) {
+        let mut start = 0;
+        let spans = spans
+            .into_iter()
+            .map(|span| {
+                let range = start..start + span.text.len();
+                start = range.end;
+                (range, span)
+            })
+            .collect();
+        self.spans = Some(spans);
+        self.selection.clear();
+        self.set_span_layout();
+    }
+
+    /// Lays the spans out with the font of the view's style.
+    fn set_span_layout(&mut self) {
+        let Some(spans) = &self.spans else {
+            return;
+        };
+        let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
+        if let Some(font_size) = self.font.scaled_size(14.0) {
+            attrs = attrs.font_size(font_size);
+        }
+        if let Some(font_style) = self.font.style() {
+            attrs = attrs.style(font_style);
+        }
+        let font_family = self.font.family().as_ref().map(|font_family| {
+            let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
+            family
+        });
+        if let Some(font_family) = font_family.as_ref() {
+            attrs = attrs.family(font_family);
+        }
+        if let Some(font_weight) = self.font.weight() {
+            attrs = attrs.weight(font_weight);
+        }
+        attrs = apply_font_variations(attrs, &self.font.variations());
+        if let Some(line_height) = self.style.line_height() {
+            attrs = attrs.line_height(line_height);
+        }
+
+        let text: String = spans.iter().map(|(_, span)| span.text.as_str()).collect();
+        let mut attrs_list = AttrsList::new(attrs);
+        for (range, span) in spans {
+            attrs_list.add_span(range.clone(), span.attrs(attrs));
+        }
+        let mut text_layout = TextLayout::new();
+        text_layout.set_text(&text, attrs_list);
+        if self.text_overflow == TextOverflow::Wrap && self.available_width > 0.0 {
+            text_layout.set_size(self.available_width, f32::MAX);
+        }
+        self.text_layout = text_layout;
+        self.shadows.clear();
+    }
+
+    /// Where the text is drawn, relative to the view.
+    fn text_origin(&self, cx: &EventCx) -> Point {
+        self.text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map_or(Point::ZERO, |layout| {
+                Point::new(layout.location.x as f64, layout.location.y as f64)
+            })
+    }
+
+    /// The index of the clickable span at `pos`, relative to the text.
+    fn clickable_span_at(&self, pos: Point) -> Option<usize> {
+        self.spans.as_ref()?.iter().position(|(range, span)| {
+            span.on_click.is_some()
+                && range_segments(&self.text_layout, range.clone())
+                    .iter()
+                    .any(|segment| segment.rect.contains(pos))
+        })
     }
 }
 
@@ -59,6 +320,14 @@ impl View for RichText {
     }
 
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        let state = match state.downcast::<Vec<TextSpan>>() {
+            Ok(spans) => {
+                self.set_spans(*spans);
+                cx.request_layout(self.id());
+                return;
+            }
+            Err(state) => state,
+        };
         if let Ok(state) = state.downcast() {
             let mut text_layout: TextLayout = *state;
             if self.text_overflow == TextOverflow::Wrap && self.available_width > 0.0 {
@@ -66,10 +335,23 @@ impl View for RichText {
             }
 
             self.text_layout = text_layout;
+            self.selection.clear();
+            self.shadows.clear();
             cx.request_layout(self.id());
         }
     }
 
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        if self.font.read(cx) | self.style.read(cx) {
+            self.shadows.set(self.style.text_shadow());
+            if self.spans.is_some() {
+                self.set_span_layout();
+                cx.app_state_mut().request_layout(self.id());
+            }
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id(), true, |cx| {
             let size = self.text_layout.size();
@@ -109,16 +391,117 @@ impl View for RichText {
         if self.text_overflow == TextOverflow::Wrap && self.available_width != available_width {
             self.available_width = available_width;
             self.text_layout.set_size(self.available_width, f32::MAX);
+            self.shadows.clear();
             cx.app_state_mut().request_layout(self.id());
         }
 
         None
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let pos = pointer_event.pos - self.text_origin(cx).to_vec2();
+                self.pressed_span = self.clickable_span_at(pos);
+                if self.selectable {
+                    let index = self.text_layout.hit_point(pos).index;
+                    let text = self.text();
+                    self.selection
+                        .pointer_down(&text, index, pointer_event.count);
+                    cx.update_active(self.id());
+                    self.id().request_focus();
+                    self.id().request_paint();
+                }
+                if self.pressed_span.is_some() || self.selectable {
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerMove(pointer_event) => {
+                let pos = pointer_event.pos - self.text_origin(cx).to_vec2();
+                if self.selectable {
+                    let index = self.text_layout.hit_point(pos).index;
+                    if self.selection.pointer_move(&self.text(), index) {
+                        self.id().request_paint();
+                    }
+                }
+                if self.clickable_span_at(pos).is_some() {
+                    cx.app_state.cursor = Some(CursorStyle::Pointer);
+                } else if self.selectable {
+                    cx.app_state.cursor = Some(CursorStyle::Text);
+                }
+            }
+            Event::PointerUp(pointer_event) => {
+                self.selection.pointer_up();
+                let pos = pointer_event.pos - self.text_origin(cx).to_vec2();
+                let pressed = self.pressed_span.take();
+                // Selecting text over a link doesn't click it
+                if pressed.is_some() && self.selection.range().is_none() {
+                    if pressed == self.clickable_span_at(pos) {
+                        let on_click = pressed
+                            .and_then(|index| self.spans.as_ref()?[index].1.on_click.clone());
+                        if let Some(on_click) = on_click {
+                            on_click();
+                            return EventPropagation::Stop;
+                        }
+                    }
+                }
+            }
+            Event::KeyDown(key_event) => {
+                if self.selection.copy_shortcut(&self.text(), key_event) {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+        EventPropagation::Continue
+    }
+
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         let text_node = self.text_node.unwrap();
         let location = cx.app_state.taffy.layout(text_node).unwrap().location;
         let point = Point::new(location.x as f64, location.y as f64);
+        let id = self.id();
+
+        if let Some(range) = self.selection.range() {
+            let color = self.style.selection_color();
+            for segment in range_segments(&self.text_layout, range) {
+                cx.fill(&(segment.rect + point.to_vec2()), color, 0.0);
+            }
+        }
+
+        self.shadows.paint(cx, id, &self.text_layout, point);
         cx.draw_text(&self.text_layout, point);
+
+        let color = self.style.color().unwrap_or(Color::BLACK);
+        for (range, span) in self.spans.iter().flatten() {
+            if !span.underline && !span.strikethrough {
+                continue;
+            }
+            let color = span.color.unwrap_or(color);
+            for segment in range_segments(&self.text_layout, range.clone()) {
+                // Offsets from the baseline approximating the metrics of most fonts
+                let thickness = (segment.font_size / 14.0).max(1.0);
+                let mut lines = Vec::new();
+                if span.underline {
+                    lines.push(segment.baseline + segment.font_size * 0.1);
+                }
+                if span.strikethrough {
+                    lines.push(segment.baseline - segment.font_size * 0.3);
+                }
+                for y in lines {
+                    let line = Rect::new(segment.rect.x0, y, segment.rect.x1, y + thickness);
+                    cx.fill(&(line + point.to_vec2()), color, 0.0);
+                }
+            }
+        }
+    }
+
+    fn cached_images(&self) -> usize {
+        self.shadows.image_count()
     }
 }
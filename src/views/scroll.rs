@@ -1,17 +1,26 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
 use floem_peniko::Color;
 use floem_reactive::create_effect;
 use floem_renderer::Renderer;
-use kurbo::{Point, Rect, Size, Vec2};
+use kurbo::{Insets, Point, Rect, Size, Vec2};
 
 use crate::{
+    action::exec_after,
     context::{AppState, ComputeLayoutCx, PaintCx},
     event::Event,
     id::Id,
     prop, prop_extracter,
+    soft_keyboard::soft_keyboard,
     style::{Background, BorderColor, BorderRadius, Style, StyleSelector},
     style_class,
     unit::Px,
     view::{View, ViewData},
+    views::MinimapLink,
     EventPropagation,
 };
 
@@ -24,6 +33,98 @@ enum ScrollState {
     HiddenBar(bool),
     PropagatePointerWheel(bool),
     VerticalScrollAsHorizontal(bool),
+    KeyboardInsets(Insets),
+    AutoScroll(Option<AutoScroll>),
+    /// A drag moved to a point, in window coordinates.
+    AutoScrollAt(Point),
+    AutoScrollTick,
+}
+
+thread_local! {
+    /// The ids of the scroll views, which receive the positions of custom drags.
+    static SCROLL_VIEWS: RefCell<HashSet<Id>> = Default::default();
+}
+
+/// How a scroll view scrolls when a drag gets close to its edges. See [`Scroll::auto_scroll`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AutoScroll {
+    /// The distance from the edges of the viewport where scrolling starts.
+    pub edge: f64,
+    /// The speed when the drag reaches the edge, in pixels per second. It grows linearly from
+    /// zero at `edge` pixels from it.
+    pub max_speed: f64,
+}
+
+impl Default for AutoScroll {
+    fn default() -> Self {
+        Self {
+            edge: 40.0,
+            max_speed: 1000.0,
+        }
+    }
+}
+
+impl AutoScroll {
+    /// The scrolling velocity, in pixels per second, for a drag at `pos` over the `viewport`.
+    /// Drags past the edges of the viewport scroll at the maximum speed.
+    pub fn velocity(&self, viewport: Rect, pos: Point) -> Vec2 {
+        let axis = |pos: f64, min: f64, max: f64| {
+            let edge = self.edge.min((max - min) / 2.0);
+            if edge <= 0.0 {
+                0.0
+            } else if pos < min + edge {
+                -self.max_speed * ((min + edge - pos) / edge).min(1.0)
+            } else if pos > max - edge {
+                self.max_speed * ((pos - (max - edge)) / edge).min(1.0)
+            } else {
+                0.0
+            }
+        };
+        Vec2::new(
+            axis(pos.x, viewport.x0, viewport.x1),
+            axis(pos.y, viewport.y0, viewport.y1),
+        )
+    }
+}
+
+/// Auto-scrolls the scroll views containing `id` while a drag at `pos`, in window coordinates,
+/// is close to their edges.
+///
+/// Drags of [draggable](crate::views::Decorators::draggable) views do this automatically.
+/// Custom drags, like text selection or reordering with an active view following the pointer,
+/// call this on each pointer move. Scrolling stops when the drag moves away from the edges, or
+/// when no view is dragged or active anymore.
+pub fn auto_scroll_ancestors(id: Id, pos: Point) {
+    let Some(path) = id.id_path() else {
+        return;
+    };
+    SCROLL_VIEWS.with(|scrolls| {
+        let scrolls = scrolls.borrow();
+        for ancestor in path.dispatch() {
+            if *ancestor != id && scrolls.contains(ancestor) {
+                ancestor.update_state(ScrollState::AutoScrollAt(pos));
+            }
+        }
+    });
+}
+
+/// Scrolls the nearest scroll view containing `id` by `delta`, such as to keep the content in
+/// view still when the size of the content above it changes.
+pub(crate) fn scroll_ancestor_by(id: Id, delta: Vec2) {
+    let Some(path) = id.id_path() else {
+        return;
+    };
+    SCROLL_VIEWS.with(|scrolls| {
+        let scrolls = scrolls.borrow();
+        let scroll = path
+            .dispatch()
+            .iter()
+            .rev()
+            .find(|ancestor| **ancestor != id && scrolls.contains(ancestor));
+        if let Some(scroll) = scroll {
+            scroll.update_state(ScrollState::ScrollDelta(delta));
+        }
+    });
 }
 
 /// Minimum length for any scrollbar to be when measured on that
@@ -94,11 +195,20 @@ pub struct Scroll {
     track_style: ScrollStyle,
     track_hover_style: ScrollStyle,
     hide: bool,
+    /// The insets of the on-screen keyboard, when the focused view is kept above it.
+    keyboard_insets: Insets,
+    auto_scroll: Option<AutoScroll>,
+    auto_scroll_velocity: Vec2,
+    /// The time of the last auto-scroll step, while auto-scrolling.
+    auto_scroll_tick: Option<Instant>,
+    minimap: Option<MinimapLink>,
 }
 
 pub fn scroll<V: View + 'static>(child: V) -> Scroll {
+    let id = Id::next();
+    SCROLL_VIEWS.with(|scrolls| scrolls.borrow_mut().insert(id));
     Scroll {
-        data: ViewData::new(Id::next()),
+        data: ViewData::new(id),
         child: Box::new(child),
         actual_rect: Rect::ZERO,
         child_size: Size::ZERO,
@@ -113,6 +223,11 @@ pub fn scroll<V: View + 'static>(child: V) -> Scroll {
         propagate_pointer_wheel: false,
         vertical_scroll_as_horizontal: false,
         hide: false,
+        keyboard_insets: Insets::ZERO,
+        auto_scroll: Some(AutoScroll::default()),
+        auto_scroll_velocity: Vec2::ZERO,
+        auto_scroll_tick: None,
+        minimap: None,
         handle_style: Default::default(),
         handle_active_style: Default::default(),
         handle_hover_style: Default::default(),
@@ -203,6 +318,87 @@ impl Scroll {
         self
     }
 
+    /// Keeps the focused view visible above the on-screen keyboard, letting the content scroll
+    /// further so its end can be moved out from under the keyboard.
+    pub fn keep_focus_above_keyboard(self, keep: impl Fn() -> bool + 'static) -> Self {
+        let id = self.id();
+        let keyboard = soft_keyboard();
+        create_effect(move |_| {
+            let insets = if keep() {
+                keyboard.insets.get()
+            } else {
+                Insets::ZERO
+            };
+            id.update_state_deferred(ScrollState::KeyboardInsets(insets));
+        });
+        self
+    }
+
+    /// Reports the viewport and the size of the content to a [`minimap`](super::minimap), and
+    /// scrolls when its indicator is dragged.
+    pub fn minimap_link(mut self, link: MinimapLink) -> Self {
+        self.minimap = Some(link);
+        self.scroll_to(move || link.scroll_to.get())
+    }
+
+    /// Sets how the scroll view scrolls when a drag gets close to its edges, or disables it with
+    /// `None`. It's enabled with [`AutoScroll::default`] otherwise.
+    pub fn auto_scroll(self, config: impl Fn() -> Option<AutoScroll> + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_state(ScrollState::AutoScroll(config()));
+        });
+        self
+    }
+
+    /// Updates the auto-scroll velocity for a drag at `pos`, relative to the scroll view.
+    fn auto_scroll_at(&mut self, pos: Point) {
+        let Some(config) = self.auto_scroll else {
+            return;
+        };
+        self.auto_scroll_velocity = config.velocity(self.actual_rect, pos);
+        if self.auto_scroll_velocity != Vec2::ZERO && self.auto_scroll_tick.is_none() {
+            self.auto_scroll_tick = Some(Instant::now());
+            self.schedule_auto_scroll_tick();
+        }
+    }
+
+    fn schedule_auto_scroll_tick(&self) {
+        let id = self.id();
+        exec_after(Duration::from_millis(16), move |_| {
+            id.update_state(ScrollState::AutoScrollTick);
+        });
+    }
+
+    /// Scrolls by the distance covered since the last step, while a drag is in progress.
+    fn auto_scroll_step(&mut self, app_state: &mut AppState) {
+        let dragging =
+            app_state.is_dragging() || app_state.active.is_some_and(|active| active != self.id());
+        match self.auto_scroll_tick {
+            Some(last) if dragging && self.auto_scroll_velocity != Vec2::ZERO => {
+                let now = Instant::now();
+                let elapsed = (now - last).as_secs_f64();
+                self.auto_scroll_tick = Some(now);
+                self.do_scroll_delta(app_state, self.auto_scroll_velocity * elapsed);
+                self.schedule_auto_scroll_tick();
+            }
+            _ => {
+                self.auto_scroll_tick = None;
+                self.auto_scroll_velocity = Vec2::ZERO;
+            }
+        }
+    }
+
+    /// The height at the bottom of the scroll view covered by the on-screen keyboard.
+    fn keyboard_covered_height(&self, app_state: &mut AppState) -> f64 {
+        if self.keyboard_insets.y1 <= 0.0 {
+            return 0.0;
+        }
+        let keyboard_top = (app_state.root_size.height - self.keyboard_insets.y1) / app_state.scale;
+        let bottom = app_state.get_layout_rect(self.id()).y0 + self.actual_rect.y1;
+        (bottom - keyboard_top).clamp(0.0, self.actual_rect.height())
+    }
+
     fn do_scroll_delta(&mut self, app_state: &mut AppState, delta: Vec2) {
         let new_origin = self.child_viewport.origin() + delta;
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(new_origin));
@@ -233,35 +429,23 @@ impl Scroll {
             }
         }
 
+        // The part of the viewport covered by the keyboard isn't visible.
+        let mut child_viewport = self.child_viewport;
+        child_viewport.y1 -= self.keyboard_covered_height(app_state);
+
         // clamp the target region size to our own size.
         // this means we will show the portion of the target region that
         // includes the origin.
         let target_size = Size::new(
-            rect.width().min(self.child_viewport.width()),
-            rect.height().min(self.child_viewport.height()),
+            rect.width().min(child_viewport.width()),
+            rect.height().min(child_viewport.height()),
         );
         let rect = rect.with_size(target_size);
 
-        let x0 = closest_on_axis(
-            rect.min_x(),
-            self.child_viewport.min_x(),
-            self.child_viewport.max_x(),
-        );
-        let x1 = closest_on_axis(
-            rect.max_x(),
-            self.child_viewport.min_x(),
-            self.child_viewport.max_x(),
-        );
-        let y0 = closest_on_axis(
-            rect.min_y(),
-            self.child_viewport.min_y(),
-            self.child_viewport.max_y(),
-        );
-        let y1 = closest_on_axis(
-            rect.max_y(),
-            self.child_viewport.min_y(),
-            self.child_viewport.max_y(),
-        );
+        let x0 = closest_on_axis(rect.min_x(), child_viewport.min_x(), child_viewport.max_x());
+        let x1 = closest_on_axis(rect.max_x(), child_viewport.min_x(), child_viewport.max_x());
+        let y0 = closest_on_axis(rect.min_y(), child_viewport.min_y(), child_viewport.max_y());
+        let y1 = closest_on_axis(rect.max_y(), child_viewport.min_y(), child_viewport.max_y());
 
         let delta_x = if x0.abs() > x1.abs() { x0 } else { x1 };
         let delta_y = if y0.abs() > y1.abs() { y0 } else { y1 };
@@ -282,7 +466,7 @@ impl Scroll {
         let actual_rect = self.actual_rect;
         let actual_size = actual_rect.size();
         let width = actual_rect.width();
-        let height = actual_rect.height();
+        let height = actual_rect.height() - self.keyboard_covered_height(app_state);
         let child_size = self.child_size;
 
         let mut child_viewport = child_viewport;
@@ -311,6 +495,9 @@ impl Scroll {
             if let Some(onscroll) = &self.onscroll {
                 onscroll(child_viewport);
             }
+            if let Some(minimap) = self.minimap {
+                minimap.viewport.set(child_viewport);
+            }
         }
         Some(())
     }
@@ -616,6 +803,13 @@ impl Scroll {
     }
 }
 
+impl Drop for Scroll {
+    fn drop(&mut self) {
+        let id = self.id();
+        SCROLL_VIEWS.with(|scrolls| scrolls.borrow_mut().remove(&id));
+    }
+}
+
 impl View for Scroll {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -678,6 +872,31 @@ impl View for Scroll {
                 ScrollState::VerticalScrollAsHorizontal(value) => {
                     self.vertical_scroll_as_horizontal = value;
                 }
+                ScrollState::AutoScroll(config) => {
+                    self.auto_scroll = config;
+                    if config.is_none() {
+                        self.auto_scroll_velocity = Vec2::ZERO;
+                    }
+                }
+                ScrollState::AutoScrollAt(pos) => {
+                    let origin = cx.app_state.get_layout_rect(self.id()).origin();
+                    self.auto_scroll_at(pos - origin.to_vec2());
+                }
+                ScrollState::AutoScrollTick => {
+                    self.auto_scroll_step(cx.app_state);
+                }
+                ScrollState::KeyboardInsets(insets) => {
+                    self.keyboard_insets = insets;
+                    if let Some(focus) = cx.app_state.focus {
+                        if focus != self.id()
+                            && focus
+                                .id_path()
+                                .is_some_and(|path| path.dispatch().contains(&self.id()))
+                        {
+                            self.do_scroll_to_view(cx.app_state, focus, None);
+                        }
+                    }
+                }
             }
             cx.request_layout(self.id());
         }
@@ -715,6 +934,11 @@ impl View for Scroll {
 
     fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
         self.update_size(cx.app_state_mut());
+        if let Some(minimap) = self.minimap {
+            if minimap.content_size.get_untracked() != self.child_size {
+                minimap.content_size.set(self.child_size);
+            }
+        }
         self.clamp_child_viewport(cx.app_state_mut(), self.child_viewport);
         self.computed_child_viewport = self.child_viewport;
         cx.compute_view_layout(&mut self.child);
@@ -791,6 +1015,9 @@ impl View for Scroll {
                 }
             }
             Event::PointerMove(event) => {
+                if cx.app_state.is_dragging() {
+                    self.auto_scroll_at(event.pos);
+                }
                 if !self.hide {
                     let pos = event.pos + scroll_offset;
                     self.update_hover_states(cx.app_state, event.pos);
@@ -0,0 +1,168 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use floem_reactive::create_updater;
+use floem_renderer::Renderer;
+use floem_vger_renderer::effect::EffectRunner;
+use image::DynamicImage;
+use kurbo::{Affine, Size};
+
+use crate::{
+    context::{PaintCx, PaintState, UpdateCx},
+    id::Id,
+    view::{View, ViewData},
+};
+
+thread_local! {
+    /// The GPU device running the effects, or `None` if there's no usable GPU.
+    static EFFECT_RUNNER: RefCell<Option<Option<EffectRunner>>> = Default::default();
+}
+
+/// A view which draws its child through a fragment shader. See [`shader_effect`]
+pub struct ShaderEffect {
+    data: ViewData,
+    child: Box<dyn View>,
+    source: Rc<str>,
+    values: Vec<f32>,
+    /// The output of the shader, and the hash identifying it in the renderer caches.
+    output: Option<(DynamicImage, Vec<u8>)>,
+    generation: u64,
+}
+
+/// Draws `child` into a texture and runs the WGSL fragment shader `source` over it.
+///
+/// The shader is appended to [`EFFECT_PRELUDE`](floem_vger_renderer::effect::EFFECT_PRELUDE),
+/// which declares the `source` texture, its `source_sampler` and `effect.size` in physical
+/// pixels, and must define `fs_main`. The values returned by `values` are available through
+/// `effect_value(index)` and are updated reactively.
+///
+/// ```ignore
+/// shader_effect(
+///     button,
+///     "@fragment
+///     fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+///         let color = textureSample(source, source_sampler, in.uv);
+///         let gray = dot(color.rgb, vec3<f32>(0.299, 0.587, 0.114));
+///         return vec4<f32>(mix(color.rgb, vec3<f32>(gray), effect_value(0u)), color.a);
+///     }",
+///     move || vec![if disabled.get() { 1.0 } else { 0.0 }],
+/// )
+/// ```
+///
+/// The child is drawn on the CPU and read back from the GPU on every paint, so effects are
+/// best kept to small parts of a window. If there's no GPU or the shader is invalid, the
+/// child is drawn without the effect.
+pub fn shader_effect<V: View + 'static>(
+    child: V,
+    source: impl Into<String>,
+    values: impl Fn() -> Vec<f32> + 'static,
+) -> ShaderEffect {
+    let id = Id::next();
+    let values = create_updater(values, move |values| id.update_state(values));
+    let source: String = source.into();
+    ShaderEffect {
+        data: ViewData::new(id),
+        child: Box::new(child),
+        source: source.into(),
+        values,
+        output: None,
+        generation: 0,
+    }
+}
+
+impl ShaderEffect {
+    /// Draws the child offscreen and runs the shader over it.
+    fn render(&mut self, cx: &mut PaintCx, size: Size) -> Option<()> {
+        let scale = cx.paint_state.scale;
+        let mut paint_state = PaintState::new_headless(scale, size * scale);
+        paint_state.renderer.begin(false);
+        let mut offscreen = PaintCx {
+            app_state: &mut *cx.app_state,
+            paint_state: &mut paint_state,
+            transform: Affine::IDENTITY,
+            clip: None,
+            z_index: None,
+            saved_transforms: Vec::new(),
+            saved_clips: Vec::new(),
+            saved_z_indexes: Vec::new(),
+        };
+        offscreen.paint_view(&mut self.child);
+        let input = paint_state.renderer.finish()?.to_rgba8();
+
+        let output = EFFECT_RUNNER.with(|runner| {
+            let mut runner = runner.borrow_mut();
+            let runner = runner.get_or_insert_with(|| EffectRunner::new().ok());
+            runner
+                .as_mut()?
+                .apply(&self.source, &input, &self.values)
+                .ok()
+        })?;
+
+        self.generation += 1;
+        let mut hash = self.id().to_raw().to_le_bytes().to_vec();
+        hash.extend_from_slice(&self.generation.to_le_bytes());
+        self.output = Some((DynamicImage::ImageRgba8(output), hash));
+        Some(())
+    }
+}
+
+impl View for ShaderEffect {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ShaderEffect".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(values) = state.downcast::<Vec<f32>>() {
+            self.values = *values;
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        if size.is_empty() || self.render(cx, size).is_none() {
+            self.output = None;
+            cx.paint_view(&mut self.child);
+            return;
+        }
+        if let Some((img, hash)) = self.output.as_ref() {
+            cx.draw_img(
+                floem_renderer::Img {
+                    img,
+                    data: img.as_bytes(),
+                    hash,
+                },
+                size.to_rect(),
+            );
+        }
+    }
+
+    fn cached_images(&self) -> usize {
+        self.output.is_some() as usize
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use floem_peniko::{Color, Gradient};
+use kurbo::{Point, Rect, RoundedRect, Size};
+
+use crate::{
+    action::exec_after,
+    context::{ComputeLayoutCx, EventCx, PaintCx, StyleCx},
+    event::Event,
+    id::Id,
+    prop, prop_extracter,
+    style::BorderRadius,
+    style_class,
+    unit::PxPct,
+    view::{View, ViewData},
+    views::Decorators,
+    EventPropagation,
+};
+
+style_class!(pub SkeletonClass);
+
+prop!(pub SkeletonColor: Color {} = Color::rgba8(128, 128, 128, 50));
+prop!(pub ShimmerColor: Color {} = Color::rgba8(255, 255, 255, 90));
+
+prop_extracter! {
+    pub(crate) SkeletonStyle {
+        color: SkeletonColor,
+        shimmer: ShimmerColor,
+        border_radius: BorderRadius,
+    }
+}
+
+/// How long the shimmer takes to sweep across a placeholder.
+const SHIMMER_PERIOD: Duration = Duration::from_millis(1500);
+
+/// The corner radius of the placeholders [`skeleton_of`] draws.
+const BONE_RADIUS: f64 = 4.0;
+
+thread_local! {
+    /// Shimmers are timed from here so all placeholders sweep together.
+    static SHIMMER_EPOCH: Instant = Instant::now();
+}
+
+/// Animates the shimmer of a placeholder by painting it again every frame while it's shown.
+#[derive(Default)]
+pub(crate) struct Shimmer {
+    frame_requested: Rc<Cell<bool>>,
+}
+
+impl Shimmer {
+    pub(crate) fn request_frame(&self, id: Id) {
+        if self.frame_requested.replace(true) {
+            return;
+        }
+        let frame_requested = self.frame_requested.clone();
+        exec_after(Duration::from_millis(16), move |_| {
+            frame_requested.set(false);
+            id.request_paint();
+        });
+    }
+
+    /// Paints a placeholder with the shimmer sweeping across `area`.
+    pub(crate) fn paint(
+        &self,
+        cx: &mut PaintCx,
+        style: &SkeletonStyle,
+        shape: RoundedRect,
+        area: Rect,
+    ) {
+        cx.fill(&shape, style.color(), 0.0);
+
+        let elapsed = SHIMMER_EPOCH.with(|epoch| epoch.elapsed());
+        let phase = (elapsed.as_secs_f64() / SHIMMER_PERIOD.as_secs_f64()).fract();
+        let band = (area.width() * 0.4).max(40.0);
+        let center = area.x0 - band + phase * (area.width() + band * 2.0);
+        let shimmer = style.shimmer();
+        let clear = Color { a: 0, ..shimmer };
+
+        cx.save();
+        cx.clip(&shape);
+        // Two gradients, as the renderers only support gradients with two stops
+        let leading = Rect::new(center - band / 2.0, area.y0, center, area.y1);
+        let trailing = Rect::new(center, area.y0, center + band / 2.0, area.y1);
+        cx.fill(
+            &leading,
+            &Gradient::new_linear((leading.x0, 0.0), (leading.x1, 0.0))
+                .with_stops([clear, shimmer]),
+            0.0,
+        );
+        cx.fill(
+            &trailing,
+            &Gradient::new_linear((trailing.x0, 0.0), (trailing.x1, 0.0))
+                .with_stops([shimmer, clear]),
+            0.0,
+        );
+        cx.restore();
+    }
+}
+
+/// A placeholder shape with an animated shimmer. See [`skeleton_line`], [`skeleton_circle`]
+/// and [`skeleton_rect`]
+pub struct Skeleton {
+    data: ViewData,
+    style: SkeletonStyle,
+    shimmer: Shimmer,
+}
+
+fn skeleton() -> Skeleton {
+    Skeleton {
+        data: ViewData::new(Id::next()),
+        style: Default::default(),
+        shimmer: Default::default(),
+    }
+    .class(SkeletonClass)
+}
+
+/// A placeholder for a line of text, filling the available width.
+pub fn skeleton_line() -> Skeleton {
+    skeleton().style(|s| s.width_full().height(12.0).border_radius(4.0))
+}
+
+/// A circular placeholder, for avatars and icons.
+pub fn skeleton_circle(diameter: f64) -> Skeleton {
+    skeleton().style(move |s| s.size(diameter, diameter).border_radius(diameter / 2.0))
+}
+
+/// A rectangular placeholder, for images and cards. It has no size by default.
+pub fn skeleton_rect() -> Skeleton {
+    skeleton().style(|s| s.border_radius(4.0))
+}
+
+impl View for Skeleton {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Skeleton".into()
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        if self.style.read(cx) {
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        let rect = size.to_rect();
+        let radius = match self.style.border_radius() {
+            PxPct::Px(px) => px,
+            PxPct::Pct(pct) => size.min_side() * (pct / 100.),
+        };
+        self.shimmer
+            .paint(cx, &self.style, rect.to_rounded_rect(radius), rect);
+        self.shimmer.request_frame(self.id());
+    }
+}
+
+/// A placeholder approximating the layout of a view. See [`skeleton_of`]
+pub struct SkeletonOf {
+    data: ViewData,
+    child: Box<dyn View>,
+    style: SkeletonStyle,
+    shimmer: Shimmer,
+    window_origin: Point,
+    /// The placeholders for the views in `child` without children, relative to this view.
+    bones: Vec<Rect>,
+}
+
+/// A placeholder with the layout of `child`, for loading states of content which isn't
+/// available yet.
+///
+/// `child` is laid out but not shown, and every view in it without children is drawn as a
+/// placeholder with an animated shimmer instead. `child` doesn't receive pointer events.
+///
+/// ```ignore
+/// dyn_container(
+///     move || profile.get(),
+///     |profile| match profile {
+///         Some(profile) => profile_card(profile).any(),
+///         None => skeleton_of(profile_card(Profile::default())).any(),
+///     },
+/// )
+/// ```
+pub fn skeleton_of<V: View + 'static>(child: V) -> SkeletonOf {
+    SkeletonOf {
+        data: ViewData::new(Id::next()),
+        child: Box::new(child),
+        style: Default::default(),
+        shimmer: Default::default(),
+        window_origin: Point::ZERO,
+        bones: Vec::new(),
+    }
+    .class(SkeletonClass)
+}
+
+fn collect_leaves(view: &dyn View, leaves: &mut Vec<Id>) {
+    let mut has_children = false;
+    view.for_each_child(&mut |child| {
+        has_children = true;
+        collect_leaves(child, leaves);
+        false
+    });
+    if !has_children {
+        leaves.push(view.id());
+    }
+}
+
+impl View for SkeletonOf {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "SkeletonOf".into()
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        if self.style.read(cx) {
+            cx.app_state_mut().request_paint(self.id());
+        }
+        cx.style_view(&mut self.child);
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        let layout = cx.compute_view_layout(&mut self.child);
+
+        let mut leaves = Vec::new();
+        collect_leaves(&*self.child, &mut leaves);
+        let app_state = cx.app_state_mut();
+        self.bones = leaves
+            .into_iter()
+            .filter(|id| !app_state.is_hidden(*id))
+            .map(|id| app_state.get_layout_rect(id) - self.window_origin.to_vec2())
+            .filter(|rect| rect.area() > 0.0)
+            .collect();
+        layout
+    }
+
+    fn event(
+        &mut self,
+        _cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        // The placeholder stands in for the content, so the content doesn't get pointer events
+        if event.is_pointer() {
+            EventPropagation::Stop
+        } else {
+            EventPropagation::Continue
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = cx
+            .get_layout(self.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        for bone in &self.bones {
+            let radius = BONE_RADIUS.min(bone.width().min(bone.height()) / 2.0);
+            self.shimmer.paint(
+                cx,
+                &self.style,
+                bone.to_rounded_rect(radius),
+                size.to_rect(),
+            );
+        }
+        self.shimmer.request_frame(self.id());
+    }
+}
@@ -0,0 +1,161 @@
+use kurbo::{Line, Point, Rect, Vec2};
+
+/// How close two lines have to be after snapping to be shown as aligned.
+const ALIGNED: f64 = 0.01;
+
+/// How the views dragged in an [`absolute_layout`](super::absolute_layout) snap into place.
+/// See [`AbsoluteLayout::snapping`](super::AbsoluteLayout::snapping)
+///
+/// ```ignore
+/// absolute_layout(shapes).snapping(Snapping::new().grid(8.0).threshold(4.0))
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snapping {
+    grid: f64,
+    to_views: bool,
+    threshold: f64,
+}
+
+impl Default for Snapping {
+    fn default() -> Self {
+        Snapping {
+            grid: 0.0,
+            to_views: true,
+            threshold: 6.0,
+        }
+    }
+}
+
+impl Snapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snaps the positions of the dragged views to a grid of this size, 0.0 turns the grid off.
+    /// Defaults to 0.0.
+    pub fn grid(mut self, grid: f64) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Whether the edges and centers of a dragged view snap to the edges and centers of the
+    /// other views, which takes precedence over the grid. Defaults to `true`.
+    pub fn to_views(mut self, to_views: bool) -> Self {
+        self.to_views = to_views;
+        self
+    }
+
+    /// How close in pixels an edge or center has to be dragged to another view's to snap to
+    /// it. Defaults to 6.0.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Snaps `position`, where a view is dragged to with `rect` as its layout rectangle, to the
+    /// grid and to the rectangles of the `others` views. Returns the snapped position and the
+    /// guide lines through the edges and centers it's aligned with.
+    pub(crate) fn snap(&self, position: Point, rect: Rect, others: &[Rect]) -> (Point, Vec<Line>) {
+        let mut offset = if self.grid > 0.0 {
+            Point::new(
+                (position.x / self.grid).round() * self.grid,
+                (position.y / self.grid).round() * self.grid,
+            ) - position
+        } else {
+            Vec2::ZERO
+        };
+        if !self.to_views {
+            return (position + offset, Vec::new());
+        }
+
+        let others_x = others.iter().map(|other| lines(other.x0, other.x1));
+        if let Some(dx) = self.closest(lines(rect.x0, rect.x1), others_x) {
+            offset.x = dx;
+        }
+        let others_y = others.iter().map(|other| lines(other.y0, other.y1));
+        if let Some(dy) = self.closest(lines(rect.y0, rect.y1), others_y) {
+            offset.y = dy;
+        }
+
+        let rect = rect + offset;
+        let mut guides = Vec::new();
+        for x in lines(rect.x0, rect.x1) {
+            let aligned = others
+                .iter()
+                .filter(|other| is_aligned(x, lines(other.x0, other.x1)));
+            if let Some((y0, y1)) = span(aligned, (rect.y0, rect.y1), |other| (other.y0, other.y1))
+            {
+                guides.push(Line::new((x, y0), (x, y1)));
+            }
+        }
+        for y in lines(rect.y0, rect.y1) {
+            let aligned = others
+                .iter()
+                .filter(|other| is_aligned(y, lines(other.y0, other.y1)));
+            if let Some((x0, x1)) = span(aligned, (rect.x0, rect.x1), |other| (other.x0, other.x1))
+            {
+                guides.push(Line::new((x0, y), (x1, y)));
+            }
+        }
+        (position + offset, guides)
+    }
+
+    /// The smallest offset within the threshold moving one of the `moving` lines onto one of
+    /// the `targets`.
+    fn closest(&self, moving: [f64; 3], targets: impl Iterator<Item = [f64; 3]>) -> Option<f64> {
+        targets
+            .flatten()
+            .flat_map(|target| moving.map(|line| target - line))
+            .filter(|offset| offset.abs() <= self.threshold)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+    }
+}
+
+/// The start, center and end of a span.
+fn lines(start: f64, end: f64) -> [f64; 3] {
+    [start, (start + end) / 2.0, end]
+}
+
+fn is_aligned(line: f64, lines: [f64; 3]) -> bool {
+    lines.iter().any(|other| (other - line).abs() < ALIGNED)
+}
+
+/// The span covering `own` and the spans of the `aligned` rectangles, if there are any.
+fn span<'a>(
+    aligned: impl Iterator<Item = &'a Rect>,
+    own: (f64, f64),
+    span_of: impl Fn(&Rect) -> (f64, f64),
+) -> Option<(f64, f64)> {
+    aligned.fold(None, |covered, other| {
+        let (start, end) = covered.unwrap_or(own);
+        let (other_start, other_end) = span_of(other);
+        Some((start.min(other_start), end.max(other_end)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::{Point, Rect};
+
+    use super::Snapping;
+
+    #[test]
+    fn snap() {
+        let others = [Rect::new(100.0, 100.0, 200.0, 150.0)];
+        let rect = Rect::new(103.0, 296.0, 163.0, 336.0);
+
+        // The left edges align, the grid rounds the vertical position
+        let snapping = Snapping::new().grid(10.0);
+        let (position, guides) = snapping.snap(Point::new(103.0, 296.0), rect, &others);
+        assert_eq!(position, Point::new(100.0, 300.0));
+        assert_eq!(guides.len(), 1);
+        assert_eq!(guides[0].p0, Point::new(100.0, 100.0));
+        assert_eq!(guides[0].p1, Point::new(100.0, 340.0));
+
+        // Too far to align
+        let snapping = Snapping::new().threshold(2.0);
+        let (position, guides) = snapping.snap(Point::new(103.0, 296.0), rect, &others);
+        assert_eq!(position, Point::new(103.0, 296.0));
+        assert!(guides.is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     context::UpdateCx,
     id::Id,
     style::Style,
+    unit::PxPct,
     view::{View, ViewData},
     view_tuple::ViewTuple,
 };
@@ -12,6 +13,7 @@ pub struct Stack {
     data: ViewData,
     pub(crate) children: Vec<Box<dyn View>>,
     direction: Option<FlexDirection>,
+    gap: Option<PxPct>,
 }
 
 pub fn stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
@@ -19,6 +21,7 @@ pub fn stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: None,
+        gap: None,
     }
 }
 
@@ -28,6 +31,7 @@ pub fn h_stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: Some(FlexDirection::Row),
+        gap: None,
     }
 }
 
@@ -37,6 +41,7 @@ pub fn v_stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: Some(FlexDirection::Column),
+        gap: None,
     }
 }
 
@@ -51,6 +56,7 @@ where
             .map(|v| -> Box<dyn View> { Box::new(v) })
             .collect(),
         direction,
+        gap: None,
     }
 }
 
@@ -78,6 +84,28 @@ where
     from_iter(iterator, Some(FlexDirection::Column))
 }
 
+impl Stack {
+    /// Used by the [`stack!`](crate::stack!) macros to build a stack from already boxed views.
+    #[doc(hidden)]
+    pub fn from_boxed(children: Vec<Box<dyn View>>, direction: Option<FlexDirection>) -> Stack {
+        Stack {
+            data: ViewData::new(Id::next()),
+            children,
+            direction,
+            gap: None,
+        }
+    }
+
+    /// Sets the spacing between the children of this stack.
+    ///
+    /// For `h_stack` and `v_stack` this is the gap along the stack direction, otherwise it is
+    /// applied to both rows and columns. A gap set with [`Style::gap`] takes precedence.
+    pub fn gap(mut self, gap: impl Into<PxPct>) -> Self {
+        self.gap = Some(gap.into());
+        self
+    }
+}
+
 impl View for Stack {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -88,8 +116,21 @@ impl View for Stack {
     }
 
     fn view_style(&self) -> Option<crate::style::Style> {
-        self.direction
-            .map(|direction| Style::new().flex_direction(direction))
+        if self.direction.is_none() && self.gap.is_none() {
+            return None;
+        }
+        let mut style = Style::new();
+        if let Some(direction) = self.direction {
+            style = style.flex_direction(direction);
+        }
+        if let Some(gap) = self.gap {
+            style = match self.direction {
+                Some(FlexDirection::Row) => style.column_gap(gap),
+                Some(FlexDirection::Column) => style.row_gap(gap),
+                _ => style.gap(gap, gap),
+            };
+        }
+        Some(style)
     }
 
     fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
@@ -134,3 +175,164 @@ impl View for Stack {
         }
     }
 }
+
+/// Creates a [`Stack`] from children of different types.
+///
+/// Besides plain views, a child can be written as
+/// - `if cond => view` to only add `view` when `cond` is true,
+/// - `if cond => a, else => b` to add either `a` or `b`,
+/// - `?option` to add the view inside an `Option` if there is one,
+/// - `..iter` to add every view produced by an iterator.
+///
+/// The conditions are evaluated once when the stack is built. Use
+/// [`dyn_container`](crate::views::dyn_container()) for children that should react to signals.
+///
+/// ## Example
+/// ```
+/// # use floem::{v_stack, views::label, widgets::checkbox};
+/// # let (show_subtitle, checked) = (true, false);
+/// # let footer = Some(label(|| "Footer"));
+/// # let items = vec!["Item".to_string()];
+/// v_stack![
+///     label(|| "Title"),
+///     if show_subtitle => label(|| "Subtitle"),
+///     if checked => checkbox(|| true), else => label(|| "Unchecked"),
+///     ?footer,
+///     ..items.iter().map(|item| {
+///         let item = item.clone();
+///         label(move || item.clone())
+///     }),
+/// ];
+/// ```
+#[macro_export]
+macro_rules! stack {
+    ($($children:tt)*) => {
+        $crate::views::Stack::from_boxed(
+            $crate::__stack_children!($($children)*),
+            ::core::option::Option::None,
+        )
+    };
+}
+
+/// Creates a horizontal [`Stack`] from children of different types. See [`stack!`](crate::stack!).
+#[macro_export]
+macro_rules! h_stack {
+    ($($children:tt)*) => {
+        $crate::views::Stack::from_boxed(
+            $crate::__stack_children!($($children)*),
+            ::core::option::Option::Some($crate::taffy::style::FlexDirection::Row),
+        )
+    };
+}
+
+/// Creates a vertical [`Stack`] from children of different types. See [`stack!`](crate::stack!).
+#[macro_export]
+macro_rules! v_stack {
+    ($($children:tt)*) => {
+        $crate::views::Stack::from_boxed(
+            $crate::__stack_children!($($children)*),
+            ::core::option::Option::Some($crate::taffy::style::FlexDirection::Column),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __stack_children {
+    ($($children:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut children: ::std::vec::Vec<::std::boxed::Box<dyn $crate::view::View>> =
+            ::std::vec::Vec::new();
+        $crate::__stack_push!(children; $($children)*);
+        children
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __stack_push {
+    ($children:ident;) => {};
+    ($children:ident; if $cond:expr => $view:expr, else => $other:expr $(, $($rest:tt)*)?) => {
+        if $cond {
+            $children.push(::std::boxed::Box::new($view));
+        } else {
+            $children.push(::std::boxed::Box::new($other));
+        }
+        $crate::__stack_push!($children; $($($rest)*)?);
+    };
+    ($children:ident; if $cond:expr => $view:expr $(, $($rest:tt)*)?) => {
+        if $cond {
+            $children.push(::std::boxed::Box::new($view));
+        }
+        $crate::__stack_push!($children; $($($rest)*)?);
+    };
+    ($children:ident; ? $option:expr $(, $($rest:tt)*)?) => {
+        if let ::core::option::Option::Some(view) = $option {
+            $children.push(::std::boxed::Box::new(view));
+        }
+        $crate::__stack_push!($children; $($($rest)*)?);
+    };
+    ($children:ident; .. $iter:expr $(, $($rest:tt)*)?) => {
+        for view in $iter {
+            $children.push(::std::boxed::Box::new(view));
+        }
+        $crate::__stack_push!($children; $($($rest)*)?);
+    };
+    ($children:ident; $view:expr $(, $($rest:tt)*)?) => {
+        $children.push(::std::boxed::Box::new($view));
+        $crate::__stack_push!($children; $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use taffy::style::FlexDirection;
+
+    use crate::{
+        id::Id,
+        view::View,
+        views::{empty, label, Empty},
+    };
+
+    fn child_ids(view: &dyn View) -> Vec<Id> {
+        let mut ids = Vec::new();
+        view.for_each_child(&mut |child| {
+            ids.push(child.id());
+            false
+        });
+        ids
+    }
+
+    #[test]
+    fn macro_children() {
+        let title = label(|| "Title");
+        let row = crate::h_stack![label(|| "Left"), empty(),];
+        let unchecked = label(|| "Unchecked");
+        let footer = label(|| "Footer");
+        let items = vec![empty(), empty()];
+        let expected = [title.id(), row.id(), unchecked.id(), footer.id()]
+            .into_iter()
+            .chain(items.iter().map(|item| item.id()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(row.direction, Some(FlexDirection::Row));
+        assert_eq!(child_ids(&row).len(), 2);
+
+        let stack = crate::v_stack![
+            title,
+            if false => label(|| "Subtitle"),
+            row,
+            if false => empty(), else => unchecked,
+            ?Some(footer),
+            ?None::<Empty>,
+            ..items,
+        ];
+        assert_eq!(stack.direction, Some(FlexDirection::Column));
+        assert_eq!(child_ids(&stack), expected);
+
+        let stack = crate::stack![if true => empty(), else => label(|| "Other")];
+        assert_eq!(stack.direction, None);
+        assert_eq!(child_ids(&stack).len(), 1);
+        assert!(child_ids(&crate::stack![]).is_empty());
+    }
+}
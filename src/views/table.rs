@@ -0,0 +1,830 @@
+use std::{cell::RefCell, collections::HashMap, hash::Hash, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_memo, create_rw_signal, on_cleanup, Memo, RwSignal};
+use kurbo::{Point, Rect};
+
+use crate::{
+    context::{ComputeLayoutCx, EventCx},
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    keyboard::{Key, ModifiersState, NamedKey},
+    style::{CursorStyle, Style},
+    style_class,
+    view::{default_compute_layout, View, ViewData},
+};
+
+use super::{
+    clip, container, dyn_container, h_stack, h_stack_from_iter, label, scroll, text_input, v_stack,
+    virtual_stack, Decorators, Stack, VirtualDirection, VirtualItemSize, VirtualVector,
+};
+
+style_class!(pub TableClass);
+style_class!(pub TableHeaderClass);
+style_class!(pub TableRowClass);
+
+const HEADER_HEIGHT: f64 = 28.0;
+const ROW_HEIGHT: f64 = 24.0;
+/// How far from a column edge in the header the pointer may be to drag it.
+const SPLITTER_DISTANCE: f64 = 4.0;
+/// How far a header has to be dragged before it's moved rather than clicked.
+const DRAG_DISTANCE: f64 = 4.0;
+const CELL_PADDING: f64 = 6.0;
+const HEADER_BACKGROUND: Color = Color::rgb8(0xf3, 0xf3, 0xf3);
+const LINE_COLOR: Color = Color::rgb8(0xdd, 0xdd, 0xdd);
+
+/// How the width of a [`Column`] is chosen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    /// A width in pixels.
+    Fixed(f64),
+    /// A share of the width left over by the fixed columns, split by the weights of the flexible
+    /// columns.
+    Flex(f64),
+}
+
+/// The order of a sorted column of a [`table`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// The order and the resized widths of the columns of a [`table`], which an app can save and
+/// restore. See [`Table::column_layout`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnLayout {
+    /// The indices of the columns from left to right. The columns are shown in the order they
+    /// were given if this isn't an order of all of them.
+    pub order: Vec<usize>,
+    /// The width each column was resized to, by index. The other columns follow their
+    /// [`ColumnWidth`].
+    pub widths: Vec<Option<f64>>,
+}
+
+/// How the cells of an [editable](Column::editable) column are edited.
+struct CellEditor<T> {
+    value: Box<dyn Fn(&T) -> String>,
+    commit: Box<dyn Fn(&T, String)>,
+}
+
+/// A column of a [`table`], with the view of its header and the view of its cell in each row.
+pub struct Column<T> {
+    header: Box<dyn Fn() -> Box<dyn View>>,
+    cell: Rc<dyn Fn(&T) -> Box<dyn View>>,
+    editor: Option<Rc<CellEditor<T>>>,
+    width: ColumnWidth,
+    min_width: f64,
+    max_width: f64,
+    sortable: bool,
+}
+
+impl<T> Column<T> {
+    pub fn new<H: View + 'static, C: View + 'static>(
+        header: impl Fn() -> H + 'static,
+        cell: impl Fn(&T) -> C + 'static,
+    ) -> Self {
+        Self {
+            header: Box::new(move || Box::new(header())),
+            cell: Rc::new(move |row| Box::new(cell(row))),
+            editor: None,
+            width: ColumnWidth::Flex(1.0),
+            min_width: 40.0,
+            max_width: f64::INFINITY,
+            sortable: false,
+        }
+    }
+
+    /// Defaults to an equal share of the width, [`ColumnWidth::Flex(1.0)`](ColumnWidth::Flex).
+    pub fn width(mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The narrowest the column is shown or resized to. Defaults to 40.0.
+    pub fn min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// The widest the column is shown or resized to. Defaults to no limit.
+    pub fn max_width(mut self, max_width: f64) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Lets the cells of the column be edited as text. The editor starts with the `value` of
+    /// the row, and `commit` is called with the row and the edited text. See [`table`]
+    pub fn editable(
+        mut self,
+        value: impl Fn(&T) -> String + 'static,
+        commit: impl Fn(&T, String) + 'static,
+    ) -> Self {
+        self.editor = Some(Rc::new(CellEditor {
+            value: Box::new(value),
+            commit: Box::new(commit),
+        }));
+        self
+    }
+
+    /// Lets the header be clicked to sort by the column. See [`Table::on_sort`]
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+/// The width a column is given and the narrowest and widest it can be.
+#[derive(Clone, Copy)]
+struct WidthPolicy {
+    width: ColumnWidth,
+    min: f64,
+    max: f64,
+}
+
+impl WidthPolicy {
+    fn clamp(&self, width: f64) -> f64 {
+        width.min(self.max).max(self.min)
+    }
+}
+
+/// The widths of the columns of a table `available` pixels wide, where `resized` holds the
+/// widths the columns were resized to.
+fn column_widths(policies: &[WidthPolicy], resized: &[Option<f64>], available: f64) -> Vec<f64> {
+    let width = |index: usize| resized.get(index).copied().flatten();
+    let mut fixed = 0.0;
+    let mut weights = 0.0;
+    for (index, policy) in policies.iter().enumerate() {
+        match (width(index), policy.width) {
+            (Some(width), _) | (None, ColumnWidth::Fixed(width)) => fixed += policy.clamp(width),
+            (None, ColumnWidth::Flex(weight)) => weights += weight,
+        }
+    }
+    let left = (available - fixed).max(0.0);
+    policies
+        .iter()
+        .enumerate()
+        .map(|(index, policy)| {
+            let width = match (width(index), policy.width) {
+                (Some(width), _) | (None, ColumnWidth::Fixed(width)) => width,
+                (None, ColumnWidth::Flex(weight)) if weights > 0.0 => left * weight / weights,
+                (None, ColumnWidth::Flex(_)) => 0.0,
+            };
+            policy.clamp(width)
+        })
+        .collect()
+}
+
+/// `order` if it's an order of all the `count` columns, or else the order they were given.
+fn valid_order(order: &[usize], count: usize) -> Vec<usize> {
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    if sorted.into_iter().eq(0..count) {
+        order.to_vec()
+    } else {
+        (0..count).collect()
+    }
+}
+
+/// The left edge of each column, by index, when the columns are placed in `order`.
+fn column_offsets(widths: &[f64], order: &[usize]) -> Vec<f64> {
+    let mut offsets = vec![0.0; widths.len()];
+    let mut left = 0.0;
+    for &column in order {
+        offsets[column] = left;
+        left += widths[column];
+    }
+    offsets
+}
+
+/// The editable column after `column` in `order`, or before it going `backwards`.
+fn next_editable(
+    order: &[usize],
+    editable: &[bool],
+    column: usize,
+    backwards: bool,
+) -> Option<usize> {
+    let position = order.iter().position(|c| *c == column)?;
+    let is_editable = |c: &&usize| editable[**c];
+    if backwards {
+        order[..position].iter().rev().find(is_editable).copied()
+    } else {
+        order[position + 1..].iter().find(is_editable).copied()
+    }
+}
+
+/// The edit of a cell of an editable column.
+struct CellEdit<T> {
+    row: Rc<T>,
+    editor: Rc<CellEditor<T>>,
+    editable: Rc<Vec<bool>>,
+    layout: RwSignal<ColumnLayout>,
+    /// The column whose cell is edited in the row.
+    editing: RwSignal<Option<usize>>,
+    column: usize,
+}
+
+impl<T: 'static> CellEdit<T> {
+    /// Ends the edit, committing `text` if there's one, and edits the cell of `next`.
+    fn finish(&self, text: Option<String>, next: Option<usize>) {
+        if self.editing.get_untracked() != Some(self.column) {
+            return;
+        }
+        if let Some(text) = text {
+            (self.editor.commit)(&self.row, text);
+        }
+        self.editing.set(next);
+    }
+
+    fn next(&self, backwards: bool) -> Option<usize> {
+        let count = self.editable.len();
+        let order = self
+            .layout
+            .with_untracked(|layout| valid_order(&layout.order, count));
+        next_editable(&order, &self.editable, self.column, backwards)
+    }
+
+    /// The text input the cell is edited with. Its keys are shortcuts of the input, so they're
+    /// handled before the input gets them.
+    fn view(self) -> impl View {
+        let buffer = create_rw_signal((self.editor.value)(&self.row));
+        let input = text_input(buffer).style(|s| s.width_full());
+        let id = input.id();
+        id.request_focus();
+        let edit = Rc::new(self);
+        // The cell the input is in, which gets the focus back once the edit ends
+        let focus_cell = move || {
+            if let Some(cell) = id.parent().and_then(|content| content.parent()) {
+                cell.request_focus();
+            }
+        };
+        let shortcuts: [(&str, Box<dyn Fn(&CellEdit<T>)>); 4] = [
+            (
+                "Enter",
+                Box::new(move |edit| {
+                    focus_cell();
+                    edit.finish(Some(buffer.get_untracked()), None);
+                }),
+            ),
+            (
+                "Esc",
+                Box::new(move |edit| {
+                    focus_cell();
+                    edit.finish(None, None);
+                }),
+            ),
+            ("Tab", Box::new(move |edit| edit.tab(buffer, false))),
+            ("Shift+Tab", Box::new(move |edit| edit.tab(buffer, true))),
+        ];
+        for (shortcut, action) in shortcuts {
+            let edit = edit.clone();
+            // The shortcuts are bound to a new view, so they can't conflict
+            let _ = id.register_shortcut(shortcut, move || action(&edit));
+        }
+        input.on_event_cont(EventListener::FocusLost, move |_| {
+            edit.finish(Some(buffer.get_untracked()), None);
+        })
+    }
+
+    /// Commits the edit and edits the next editable cell of the row, if there's one.
+    fn tab(&self, buffer: RwSignal<String>, backwards: bool) {
+        let next = self.next(backwards);
+        self.finish(Some(buffer.get_untracked()), next);
+    }
+}
+
+/// The views a column is fitted to when the edge of its header is double-clicked.
+#[derive(Default)]
+struct Measured {
+    /// The title and the sort arrow of each header.
+    headers: Vec<(Id, Id)>,
+    /// The content of each cell of the rows which are shown, by the id of the row.
+    rows: HashMap<Id, Vec<Id>>,
+}
+
+/// A press on a header, which moves the column once it's dragged.
+#[derive(Clone, Copy)]
+struct HeaderPress {
+    column: usize,
+    x: f64,
+    dragging: bool,
+}
+
+/// A table of rows with resizable, movable and sortable columns. See [`table`]
+pub struct Table {
+    data: ViewData,
+    child: Stack,
+    widths: Memo<Vec<f64>>,
+    offsets: Memo<Vec<f64>>,
+    layout: RwSignal<ColumnLayout>,
+    policies: Vec<WidthPolicy>,
+    measured: Rc<RefCell<Measured>>,
+    available: RwSignal<f64>,
+    sort: RwSignal<Option<(usize, SortDirection)>>,
+    window_origin: Point,
+    /// The column being resized, with the window position and the width at the start of the
+    /// drag.
+    held: Option<(usize, f64, f64)>,
+    pressed: Option<HeaderPress>,
+}
+
+/// A table showing the rows of `each_fn` in `columns`, with a header above them.
+///
+/// Only the visible rows are created, so a table can show many thousands of rows. Dragging the
+/// right edge of a column in the header resizes it, and double-clicking the edge fits the
+/// column to its header and the rows shown. Dragging a header moves its column, and clicking
+/// the header of a [sortable](Column::sortable) column asks for the rows to be sorted by it
+/// with [`Table::on_sort`]. The table doesn't sort the rows itself. The order and widths the
+/// columns are given by the user are kept in [`Table::column_layout`].
+///
+/// The cells of an [editable](Column::editable) column are edited by double-clicking them or
+/// pressing F2 or Enter on them. Enter or moving the focus away commits the text, Escape
+/// cancels the edit, and Tab commits it and edits the next editable cell of the row.
+///
+/// ```ignore
+/// table(
+///     vec![
+///         Column::new(|| label(|| "Name"), |file: &File| label(move || file.name.clone()))
+///             .sortable(true),
+///         Column::new(|| label(|| "Size"), |file: &File| label(move || file.size))
+///             .width(ColumnWidth::Fixed(80.0)),
+///     ],
+///     move || files.get(),
+///     |file| file.path.clone(),
+/// )
+/// .on_sort(move |column, direction| files.update(|files| sort_files(files, column, direction)))
+/// ```
+pub fn table<T, IF, I, KF, K>(columns: Vec<Column<T>>, each_fn: IF, key_fn: KF) -> Table
+where
+    T: 'static,
+    IF: Fn() -> I + 'static,
+    I: VirtualVector<T>,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + 'static,
+{
+    let count = columns.len();
+    let layout = create_rw_signal(ColumnLayout {
+        order: (0..count).collect(),
+        widths: vec![None; count],
+    });
+    let available = create_rw_signal(0.0);
+    let sort = create_rw_signal(None::<(usize, SortDirection)>);
+    let policies: Vec<_> = columns
+        .iter()
+        .map(|column| WidthPolicy {
+            width: column.width,
+            min: column.min_width,
+            max: column.max_width,
+        })
+        .collect();
+    let widths = create_memo({
+        let policies = policies.clone();
+        move |_| layout.with(|layout| column_widths(&policies, &layout.widths, available.get()))
+    });
+    let offsets = create_memo(move |_| {
+        let order = layout.with(|layout| valid_order(&layout.order, count));
+        widths.with(|widths| column_offsets(widths, &order))
+    });
+    let total_width = move || widths.with(|widths| widths.iter().sum::<f64>());
+    let place = move |s: Style, index: usize| {
+        s.absolute()
+            .inset_left(offsets.with(|offsets| offsets[index]))
+            .width(widths.with(|widths| widths[index]))
+            .height_full()
+            .items_center()
+            .padding_horiz(CELL_PADDING)
+    };
+    let measured = Rc::new(RefCell::new(Measured::default()));
+
+    let header = h_stack_from_iter(columns.iter().enumerate().map(|(index, column)| {
+        let sortable = column.sortable;
+        let arrow = move || match sort.get() {
+            Some((column, SortDirection::Ascending)) if column == index => "▲",
+            Some((column, SortDirection::Descending)) if column == index => "▼",
+            _ => "",
+        };
+        // The title and arrow keep their natural widths, which the column is fitted to
+        let title = container((column.header)()).style(|s| s.flex_shrink(0.0));
+        let arrow = label(arrow).style(|s| s.flex_shrink(0.0));
+        measured.borrow_mut().headers.push((title.id(), arrow.id()));
+        clip(h_stack((title, arrow)).style(|s| s.size_full().items_center().justify_between()))
+            .on_click_stop(move |_| {
+                if !sortable {
+                    return;
+                }
+                let direction = match sort.get_untracked() {
+                    Some((column, SortDirection::Ascending)) if column == index => {
+                        SortDirection::Descending
+                    }
+                    _ => SortDirection::Ascending,
+                };
+                sort.set(Some((index, direction)));
+            })
+            .style(move |s| {
+                place(s, index)
+                    .border_right(1.0)
+                    .border_color(LINE_COLOR)
+                    .apply_if(sortable, |s| s.cursor(CursorStyle::Pointer))
+            })
+    }))
+    .class(TableHeaderClass)
+    .style(move |s| {
+        s.height(HEADER_HEIGHT)
+            .width(total_width())
+            .min_width_full()
+            .background(HEADER_BACKGROUND)
+            .border_bottom(1.0)
+            .border_color(LINE_COLOR)
+    });
+
+    let cells: Rc<Vec<_>> = Rc::new(
+        columns
+            .iter()
+            .map(|column| (column.cell.clone(), column.editor.clone()))
+            .collect(),
+    );
+    let editable: Rc<Vec<_>> = Rc::new(columns.iter().map(|c| c.editor.is_some()).collect());
+    let rows = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        each_fn,
+        key_fn,
+        {
+            let measured = measured.clone();
+            move |row| {
+                let row = Rc::new(row);
+                let editing = create_rw_signal(None::<usize>);
+                let mut content_ids = Vec::new();
+                let mut views = Vec::new();
+                for (index, (cell, editor)) in cells.iter().enumerate() {
+                    let Some(editor) = editor.clone() else {
+                        let content = container(cell(&row)).style(|s| s.flex_shrink(0.0));
+                        content_ids.push(content.id());
+                        views.push(clip(content).style(move |s| place(s, index)));
+                        continue;
+                    };
+                    let is_editing = move || editing.get() == Some(index);
+                    let content = dyn_container(is_editing, {
+                        let (row, cell, editable) = (row.clone(), cell.clone(), editable.clone());
+                        move |is_editing| {
+                            if !is_editing {
+                                return cell(&row);
+                            }
+                            let edit = CellEdit {
+                                row: row.clone(),
+                                editor: editor.clone(),
+                                editable: editable.clone(),
+                                layout,
+                                editing,
+                                column: index,
+                            };
+                            Box::new(edit.view())
+                        }
+                    })
+                    .style(move |s| {
+                        s.flex_shrink(0.0)
+                            .apply_if(is_editing(), |s| s.width_full())
+                    });
+                    content_ids.push(content.id());
+                    let start = move |_: &Event| {
+                        if editing.get_untracked() != Some(index) {
+                            editing.set(Some(index));
+                        }
+                    };
+                    let no_modifiers = ModifiersState::empty();
+                    views.push(
+                        clip(content)
+                            .keyboard_navigatable()
+                            .on_double_click_stop(start)
+                            .on_key_down(Key::Named(NamedKey::F2), no_modifiers, start)
+                            .on_key_down(Key::Named(NamedKey::Enter), no_modifiers, start)
+                            .style(move |s| place(s, index)),
+                    );
+                }
+                let row = h_stack_from_iter(views)
+                    .class(TableRowClass)
+                    .style(move |s| {
+                        s.height(ROW_HEIGHT)
+                            .width(total_width())
+                            .min_width_full()
+                            .border_bottom(1.0)
+                            .border_color(LINE_COLOR)
+                    });
+                let row_id = row.id();
+                measured.borrow_mut().rows.insert(row_id, content_ids);
+                let measured = measured.clone();
+                on_cleanup(move || {
+                    measured.borrow_mut().rows.remove(&row_id);
+                });
+                row
+            }
+        },
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = v_stack((
+        header,
+        scroll(rows).style(|s| s.flex_grow(1.0).width_full()),
+    ))
+    .style(|s| s.size_full());
+
+    Table {
+        data: ViewData::new(Id::next()),
+        child,
+        widths,
+        offsets,
+        layout,
+        policies,
+        measured,
+        available,
+        sort,
+        window_origin: Point::ZERO,
+        held: None,
+        pressed: None,
+    }
+    .class(TableClass)
+}
+
+impl Table {
+    /// The column the rows are sorted by and the direction, as last chosen by clicking a
+    /// header.
+    pub fn sort(&self) -> RwSignal<Option<(usize, SortDirection)>> {
+        self.sort
+    }
+
+    /// Calls `on_sort` with the index of the column and the direction to sort the rows by,
+    /// when the header of a sortable column is clicked.
+    pub fn on_sort(self, on_sort: impl Fn(usize, SortDirection) + 'static) -> Self {
+        let sort = self.sort;
+        create_effect(move |_| {
+            if let Some((column, direction)) = sort.get() {
+                on_sort(column, direction);
+            }
+        });
+        self
+    }
+
+    /// The current widths of the columns, which change as the table or its columns are
+    /// resized.
+    pub fn column_widths(&self) -> Memo<Vec<f64>> {
+        self.widths
+    }
+
+    /// The order and the widths the user gave the columns. Setting it restores a saved
+    /// layout.
+    pub fn column_layout(&self) -> RwSignal<ColumnLayout> {
+        self.layout
+    }
+
+    /// The left and right edges of `column`.
+    fn column_edges(&self, column: usize) -> (f64, f64) {
+        let left = self.offsets.with_untracked(|offsets| offsets[column]);
+        let width = self.widths.with_untracked(|widths| widths[column]);
+        (left, left + width)
+    }
+
+    /// The column whose right edge is under `pos`, in the header.
+    fn splitter_at(&self, pos: Point) -> Option<usize> {
+        if pos.y < 0.0 || pos.y > HEADER_HEIGHT {
+            return None;
+        }
+        (0..self.policies.len())
+            .find(|&column| (pos.x - self.column_edges(column).1).abs() <= SPLITTER_DISTANCE)
+    }
+
+    /// The column at `x`, from the left edge of the table.
+    fn column_at(&self, x: f64) -> Option<usize> {
+        (0..self.policies.len()).find(|&column| {
+            let (left, right) = self.column_edges(column);
+            left <= x && x < right
+        })
+    }
+
+    fn set_width(&self, column: usize, width: f64) {
+        let count = self.policies.len();
+        let width = self.policies[column].clamp(width);
+        self.layout.update(|layout| {
+            layout.widths.resize(count, None);
+            layout.widths[column] = Some(width);
+        });
+    }
+
+    /// The width fitting the header of `column` and its cells in the rows shown.
+    fn fit_width(&self, cx: &EventCx, column: usize) -> f64 {
+        let width = |id: Id| {
+            cx.get_layout(id)
+                .map_or(0.0, |layout| layout.size.width as f64)
+        };
+        let measured = self.measured.borrow();
+        let (title, arrow) = measured.headers[column];
+        let content = measured
+            .rows
+            .values()
+            .map(|cells| width(cells[column]))
+            .fold(width(title) + width(arrow), f64::max);
+        content + 2.0 * CELL_PADDING
+    }
+
+    /// Moves the dragged `column` to the place of the column under `x`, once `x` would be
+    /// over `column` in its new place, so a narrow column doesn't swap back and forth with a
+    /// wide one.
+    fn drag_column(&self, column: usize, x: f64) {
+        let Some(over) = self.column_at(x).filter(|over| *over != column) else {
+            return;
+        };
+        let count = self.policies.len();
+        let mut order = self
+            .layout
+            .with_untracked(|layout| valid_order(&layout.order, count));
+        let Some(to) = order.iter().position(|c| *c == over) else {
+            return;
+        };
+        order.retain(|c| *c != column);
+        order.insert(to, column);
+        let (left, width) = self
+            .widths
+            .with_untracked(|widths| (column_offsets(widths, &order)[column], widths[column]));
+        if left <= x && x < left + width {
+            self.layout.update(|layout| layout.order = order);
+        }
+    }
+}
+
+impl View for Table {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Table".into()
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        if let Some(layout) = cx.get_layout(self.id()) {
+            let width = layout.size.width as f64;
+            if self.available.get_untracked() != width {
+                self.available.set(width);
+            }
+        }
+        default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let pos = pointer_event.pos;
+                if let Some(column) = self.splitter_at(pos) {
+                    if pointer_event.count == 2 {
+                        self.set_width(column, self.fit_width(cx, column));
+                        return EventPropagation::Stop;
+                    }
+                    let width = self.widths.with_untracked(|widths| widths[column]);
+                    let start = self.window_origin.x + pos.x;
+                    self.held = Some((column, start, width));
+                    cx.update_active(self.id());
+                    return EventPropagation::Stop;
+                }
+                // The press goes on to the header, which is clicked unless it's dragged
+                self.pressed = self
+                    .column_at(pos.x)
+                    .filter(|_| (0.0..HEADER_HEIGHT).contains(&pos.y))
+                    .map(|column| HeaderPress {
+                        column,
+                        x: pos.x,
+                        dragging: false,
+                    });
+            }
+            Event::PointerMove(pointer_event) => {
+                if let Some((column, start, start_width)) = self.held {
+                    cx.app_state.cursor = Some(CursorStyle::ColResize);
+                    let delta = self.window_origin.x + pointer_event.pos.x - start;
+                    self.set_width(column, start_width + delta);
+                    return EventPropagation::Stop;
+                }
+                if let Some(mut press) = self.pressed {
+                    let x = pointer_event.pos.x;
+                    if !press.dragging && (x - press.x).abs() > DRAG_DISTANCE {
+                        press.dragging = true;
+                        self.pressed = Some(press);
+                        cx.update_active(self.id());
+                    }
+                    if press.dragging {
+                        cx.app_state.cursor = Some(CursorStyle::Grabbing);
+                        self.drag_column(press.column, x);
+                        return EventPropagation::Stop;
+                    }
+                }
+                if self.splitter_at(pointer_event.pos).is_some() {
+                    cx.app_state.cursor = Some(CursorStyle::ColResize);
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerUp(_) => {
+                let dragged = self.pressed.take().map_or(false, |press| press.dragging);
+                // A dragged header isn't clicked
+                if self.held.take().is_some() || dragged {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+
+        cx.view_event(&mut self.child, id_path, event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        column_offsets, column_widths, next_editable, valid_order, ColumnWidth, WidthPolicy,
+    };
+
+    fn policy(width: ColumnWidth) -> WidthPolicy {
+        WidthPolicy {
+            width,
+            min: 40.0,
+            max: f64::INFINITY,
+        }
+    }
+
+    #[test]
+    fn widths() {
+        let policies = [
+            policy(ColumnWidth::Fixed(100.0)),
+            policy(ColumnWidth::Flex(1.0)),
+            policy(ColumnWidth::Flex(3.0)),
+        ];
+        assert_eq!(
+            column_widths(&policies, &[None, None, None], 500.0),
+            vec![100.0, 100.0, 300.0]
+        );
+        // A resized flexible column keeps its width, the others share what's left
+        assert_eq!(
+            column_widths(&policies, &[None, Some(200.0), None], 500.0),
+            vec![100.0, 200.0, 200.0]
+        );
+        // Columns don't shrink below their minimum width
+        assert_eq!(
+            column_widths(&policies, &[None, None, None], 120.0),
+            vec![100.0, 40.0, 40.0]
+        );
+        // Nor grow above their maximum width
+        let capped = [
+            policies[0],
+            WidthPolicy {
+                max: 150.0,
+                ..policies[1]
+            },
+        ];
+        assert_eq!(
+            column_widths(&capped, &[Some(300.0), None], 500.0),
+            vec![300.0, 150.0]
+        );
+    }
+
+    #[test]
+    fn order() {
+        let widths = [100.0, 50.0, 80.0];
+        assert_eq!(column_offsets(&widths, &[2, 0, 1]), vec![80.0, 180.0, 0.0]);
+        // A saved order which doesn't fit the columns falls back to their own order
+        assert_eq!(valid_order(&[2, 0, 1], 3), vec![2, 0, 1]);
+        assert_eq!(valid_order(&[1, 0], 3), vec![0, 1, 2]);
+        assert_eq!(valid_order(&[0, 0, 1], 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn tab_between_editable_cells() {
+        let editable = [true, false, true, true];
+        let order = [3, 1, 0, 2];
+        assert_eq!(next_editable(&order, &editable, 3, false), Some(0));
+        assert_eq!(next_editable(&order, &editable, 0, false), Some(2));
+        assert_eq!(next_editable(&order, &editable, 2, false), None);
+        assert_eq!(next_editable(&order, &editable, 0, true), Some(3));
+        assert_eq!(next_editable(&order, &editable, 3, true), None);
+    }
+}
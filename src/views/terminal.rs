@@ -0,0 +1,1107 @@
+use std::{
+    any::Any,
+    collections::VecDeque,
+    io::{self, Read, Write},
+    process::Command,
+};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, ReadSignal, RwSignal};
+use floem_renderer::Renderer;
+use kurbo::{Point, Rect, Size};
+use portable_pty::{native_pty_system, Child, MasterPty, PtySize};
+use vte::{Params, Parser, Perform};
+
+pub use portable_pty::CommandBuilder;
+
+use crate::{
+    context::{ComputeLayoutCx, EventCx, PaintCx, StyleCx, UpdateCx},
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout, Weight},
+    event::Event,
+    ext_event::create_signal_from_channel,
+    id::Id,
+    keyboard::{Key, KeyEvent, NamedKey},
+    prop_extracter,
+    style::{Background, FontProps, TextColor},
+    style_class,
+    view::{View, ViewData},
+    views::Decorators,
+    Clipboard, EventPropagation,
+};
+
+style_class!(pub TerminalClass);
+
+prop_extracter! {
+    Extracter {
+        color: TextColor,
+        background: Background,
+    }
+}
+
+const DEFAULT_FONT_SIZE: f32 = 13.0;
+const DEFAULT_SCROLLBACK: usize = 10_000;
+const SELECTION_COLOR: Color = Color::rgba8(90, 140, 230, 90);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct CellStyle {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    ch: char,
+    style: CellStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            ch: ' ',
+            style: CellStyle::default(),
+        }
+    }
+}
+
+/// The 16 colors of the ANSI palette, as used by xterm.
+fn ansi_color(index: u16) -> Color {
+    const COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let (r, g, b) = COLORS[index as usize % 16];
+    Color::rgb8(r, g, b)
+}
+
+/// A color of the 256 color palette: the ANSI colors, a 6×6×6 color cube and 24 grays.
+fn indexed_color(index: u16) -> Color {
+    match index {
+        0..=15 => ansi_color(index),
+        16..=231 => {
+            let index = index - 16;
+            let level = |value: u16| {
+                if value == 0 {
+                    0
+                } else {
+                    (55 + value * 40) as u8
+                }
+            };
+            Color::rgb8(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = (8 + (index.min(255) - 232) * 10) as u8;
+            Color::rgb8(gray, gray, gray)
+        }
+    }
+}
+
+/// The lines of a terminal and the state changed by the escape sequences written to it.
+struct Screen {
+    cols: usize,
+    rows: usize,
+    /// The scrollback followed by the `rows` lines of the screen.
+    lines: VecDeque<Vec<Cell>>,
+    scrollback: usize,
+    /// The row and column of the cursor on the screen.
+    cursor: (usize, usize),
+    /// Set after printing to the last column, so the next character wraps to the next line.
+    pending_wrap: bool,
+    style: CellStyle,
+    saved: ((usize, usize), CellStyle),
+    /// The first and last row scrolled by line feeds.
+    region: (usize, usize),
+    cursor_visible: bool,
+    /// The main screen, kept while the alternate screen is shown.
+    main: Option<VecDeque<Vec<Cell>>>,
+    title: Option<String>,
+    /// Bytes to write back to the program, such as replies to cursor position requests.
+    replies: Vec<u8>,
+}
+
+impl Screen {
+    fn new(cols: usize, rows: usize, scrollback: usize) -> Self {
+        Screen {
+            cols,
+            rows,
+            lines: (0..rows).map(|_| vec![Cell::default(); cols]).collect(),
+            scrollback,
+            cursor: (0, 0),
+            pending_wrap: false,
+            style: CellStyle::default(),
+            saved: ((0, 0), CellStyle::default()),
+            region: (0, rows - 1),
+            cursor_visible: true,
+            main: None,
+            title: None,
+            replies: Vec::new(),
+        }
+    }
+
+    /// The index in `lines` of a row of the screen.
+    fn index(&self, row: usize) -> usize {
+        self.lines.len() - self.rows + row
+    }
+
+    /// A cell erased with the current background.
+    fn blank(&self) -> Cell {
+        Cell {
+            ch: ' ',
+            style: CellStyle {
+                bg: self.style.bg,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn blank_line(&self) -> Vec<Cell> {
+        vec![self.blank(); self.cols]
+    }
+
+    fn row_mut(&mut self, row: usize) -> &mut Vec<Cell> {
+        let index = self.index(row);
+        &mut self.lines[index]
+    }
+
+    fn line_text(&self, index: usize) -> String {
+        self.lines[index].iter().map(|cell| cell.ch).collect()
+    }
+
+    fn print(&mut self, ch: char) {
+        if self.pending_wrap {
+            self.pending_wrap = false;
+            self.cursor.1 = 0;
+            self.linefeed();
+        }
+        let (row, col) = self.cursor;
+        let style = self.style;
+        self.row_mut(row)[col] = Cell { ch, style };
+        if col + 1 >= self.cols {
+            self.pending_wrap = true;
+        } else {
+            self.cursor.1 += 1;
+        }
+    }
+
+    fn linefeed(&mut self) {
+        if self.cursor.0 == self.region.1 {
+            self.scroll_up(1);
+        } else if self.cursor.0 + 1 < self.rows {
+            self.cursor.0 += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        if self.cursor.0 == self.region.0 {
+            self.scroll_down(1);
+        } else {
+            self.cursor.0 = self.cursor.0.saturating_sub(1);
+        }
+    }
+
+    /// Scrolls the region up, moving its first line into the scrollback when the region is the
+    /// whole main screen.
+    fn scroll_up(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.region == (0, self.rows - 1) && self.main.is_none() {
+                self.lines.push_back(self.blank_line());
+                while self.lines.len() > self.rows + self.scrollback {
+                    self.lines.pop_front();
+                }
+            } else {
+                self.remove_insert(self.region.0, self.region.1);
+            }
+        }
+    }
+
+    fn scroll_down(&mut self, count: usize) {
+        for _ in 0..count {
+            self.remove_insert(self.region.1, self.region.0);
+        }
+    }
+
+    /// Removes the line at row `remove` and inserts a blank line at row `insert`.
+    fn remove_insert(&mut self, remove: usize, insert: usize) {
+        let remove = self.index(remove);
+        let insert = self.index(insert);
+        self.lines.remove(remove);
+        self.lines.insert(insert, self.blank_line());
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.cursor = (row.min(self.rows - 1), col.min(self.cols - 1));
+        self.pending_wrap = false;
+    }
+
+    fn erase_cells(&mut self, row: usize, cols: std::ops::Range<usize>) {
+        let blank = self.blank();
+        let end = cols.end.min(self.cols);
+        for cell in &mut self.row_mut(row)[cols.start.min(end)..end] {
+            *cell = blank;
+        }
+    }
+
+    fn erase_display(&mut self, mode: u16) {
+        let (row, col) = self.cursor;
+        match mode {
+            0 => {
+                self.erase_cells(row, col..self.cols);
+                for row in row + 1..self.rows {
+                    self.erase_cells(row, 0..self.cols);
+                }
+            }
+            1 => {
+                for row in 0..row {
+                    self.erase_cells(row, 0..self.cols);
+                }
+                self.erase_cells(row, 0..col + 1);
+            }
+            2 => {
+                for row in 0..self.rows {
+                    self.erase_cells(row, 0..self.cols);
+                }
+            }
+            3 => {
+                let scrollback = self.lines.len() - self.rows;
+                self.lines.drain(..scrollback);
+            }
+            _ => {}
+        }
+    }
+
+    fn erase_line(&mut self, mode: u16) {
+        let (row, col) = self.cursor;
+        match mode {
+            0 => self.erase_cells(row, col..self.cols),
+            1 => self.erase_cells(row, 0..col + 1),
+            2 => self.erase_cells(row, 0..self.cols),
+            _ => {}
+        }
+    }
+
+    fn insert_lines(&mut self, count: usize) {
+        let row = self.cursor.0;
+        if (self.region.0..=self.region.1).contains(&row) {
+            for _ in 0..count {
+                self.remove_insert(self.region.1, row);
+            }
+        }
+    }
+
+    fn delete_lines(&mut self, count: usize) {
+        let row = self.cursor.0;
+        if (self.region.0..=self.region.1).contains(&row) {
+            for _ in 0..count {
+                self.remove_insert(row, self.region.1);
+            }
+        }
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let (row, col) = self.cursor;
+        let cols = self.cols;
+        let blank = self.blank();
+        let line = self.row_mut(row);
+        line.drain(col..(col + count).min(cols));
+        line.resize(cols, blank);
+    }
+
+    fn insert_chars(&mut self, count: usize) {
+        let (row, col) = self.cursor;
+        let cols = self.cols;
+        let blank = self.blank();
+        let line = self.row_mut(row);
+        for _ in 0..count.min(cols - col) {
+            line.insert(col, blank);
+        }
+        line.truncate(cols);
+    }
+
+    fn set_alternate_screen(&mut self, alternate: bool) {
+        if alternate && self.main.is_none() {
+            let screen = (0..self.rows).map(|_| vec![Cell::default(); self.cols]);
+            self.main = Some(std::mem::replace(&mut self.lines, screen.collect()));
+            self.saved = (self.cursor, self.style);
+            self.erase_display(2);
+        } else if !alternate {
+            if let Some(main) = self.main.take() {
+                self.lines = main;
+                // The screen may have grown while the alternate screen was shown
+                while self.lines.len() < self.rows {
+                    self.lines.push_back(vec![Cell::default(); self.cols]);
+                }
+                (self.cursor, self.style) = self.saved;
+                self.move_to(self.cursor.0, self.cursor.1);
+            }
+        }
+    }
+
+    fn sgr(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            self.style = CellStyle::default();
+        }
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => self.style = CellStyle::default(),
+                1 => self.style.bold = true,
+                4 => self.style.underline = true,
+                7 => self.style.inverse = true,
+                22 => self.style.bold = false,
+                24 => self.style.underline = false,
+                27 => self.style.inverse = false,
+                30..=37 => self.style.fg = Some(ansi_color(param - 30)),
+                39 => self.style.fg = None,
+                40..=47 => self.style.bg = Some(ansi_color(param - 40)),
+                49 => self.style.bg = None,
+                90..=97 => self.style.fg = Some(ansi_color(param - 90 + 8)),
+                100..=107 => self.style.bg = Some(ansi_color(param - 100 + 8)),
+                38 | 48 => {
+                    let color = match params.next() {
+                        Some(5) => params.next().map(indexed_color),
+                        Some(2) => {
+                            let mut channel = || params.next().unwrap_or(0).min(255) as u8;
+                            Some(Color::rgb8(channel(), channel(), channel()))
+                        }
+                        _ => None,
+                    };
+                    if param == 38 {
+                        self.style.fg = color;
+                    } else {
+                        self.style.bg = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn resize(&mut self, cols: usize, rows: usize) {
+        if cols == self.cols && rows == self.rows {
+            return;
+        }
+        for line in self.lines.iter_mut().chain(self.main.iter_mut().flatten()) {
+            line.resize(cols, Cell::default());
+        }
+        self.cols = cols;
+        if rows > self.rows {
+            // The scrollback comes back onto the screen before blank lines are added below
+            let from_scrollback = (rows - self.rows).min(self.lines.len() - self.rows);
+            self.cursor.0 += from_scrollback;
+            while self.lines.len() < rows {
+                self.lines.push_back(vec![Cell::default(); cols]);
+            }
+        } else {
+            // Blank lines below the cursor are dropped, the other lines go to the scrollback
+            let mut excess = self.rows - rows;
+            while excess > 0
+                && self.cursor.0 + 1 < self.rows
+                && self.lines.back().map_or(false, |line| {
+                    line.iter().all(|cell| *cell == Cell::default())
+                })
+            {
+                self.lines.pop_back();
+                self.rows -= 1;
+                excess -= 1;
+            }
+            self.cursor.0 = self.cursor.0.saturating_sub(excess);
+        }
+        self.rows = rows;
+        self.region = (0, rows - 1);
+        self.move_to(self.cursor.0, self.cursor.1);
+    }
+}
+
+impl Perform for Screen {
+    fn print(&mut self, ch: char) {
+        Screen::print(self, ch);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' | 0x0b | 0x0c => self.linefeed(),
+            b'\r' => {
+                self.cursor.1 = 0;
+                self.pending_wrap = false;
+            }
+            0x08 => self.move_to(self.cursor.0, self.cursor.1.saturating_sub(1)),
+            b'\t' => self.move_to(self.cursor.0, (self.cursor.1 / 8 + 1) * 8),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        let values: Vec<u16> = params.iter().flatten().copied().collect();
+        // The count or position of most sequences, where 0 and missing mean 1
+        let arg = |index: usize| {
+            values
+                .get(index)
+                .copied()
+                .filter(|value| *value != 0)
+                .unwrap_or(1) as usize
+        };
+        let (row, col) = self.cursor;
+        match (intermediates.first(), action) {
+            (None, 'A') => self.move_to(row.saturating_sub(arg(0)), col),
+            (None, 'B' | 'e') => self.move_to(row + arg(0), col),
+            (None, 'C' | 'a') => self.move_to(row, col + arg(0)),
+            (None, 'D') => self.move_to(row, col.saturating_sub(arg(0))),
+            (None, 'E') => self.move_to(row + arg(0), 0),
+            (None, 'F') => self.move_to(row.saturating_sub(arg(0)), 0),
+            (None, 'G' | '`') => self.move_to(row, arg(0) - 1),
+            (None, 'd') => self.move_to(arg(0) - 1, col),
+            (None, 'H' | 'f') => self.move_to(arg(0) - 1, arg(1) - 1),
+            (None, 'J') => self.erase_display(values.first().copied().unwrap_or(0)),
+            (None, 'K') => self.erase_line(values.first().copied().unwrap_or(0)),
+            (None, 'L') => self.insert_lines(arg(0)),
+            (None, 'M') => self.delete_lines(arg(0)),
+            (None, 'P') => self.delete_chars(arg(0)),
+            (None, '@') => self.insert_chars(arg(0)),
+            (None, 'X') => self.erase_cells(row, col..col + arg(0)),
+            (None, 'S') => self.scroll_up(arg(0)),
+            (None, 'T') => self.scroll_down(arg(0)),
+            (None, 'm') => self.sgr(&values),
+            (None, 'r') => {
+                let top = arg(0) - 1;
+                let bottom = values
+                    .get(1)
+                    .copied()
+                    .filter(|value| *value != 0)
+                    .map_or(self.rows, usize::from)
+                    - 1;
+                if top < bottom && bottom < self.rows {
+                    self.region = (top, bottom);
+                    self.move_to(0, 0);
+                }
+            }
+            (None, 's') => self.saved = (self.cursor, self.style),
+            (None, 'u') => (self.cursor, self.style) = self.saved,
+            (None, 'n') if values.first() == Some(&6) => {
+                let reply = format!("\x1b[{};{}R", row + 1, col + 1);
+                self.replies.extend_from_slice(reply.as_bytes());
+            }
+            (Some(b'?'), 'h' | 'l') => {
+                let set = action == 'h';
+                for mode in &values {
+                    match mode {
+                        25 => self.cursor_visible = set,
+                        47 | 1047 | 1049 => self.set_alternate_screen(set),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+        match byte {
+            b'7' => self.saved = (self.cursor, self.style),
+            b'8' => (self.cursor, self.style) = self.saved,
+            b'D' => self.linefeed(),
+            b'E' => {
+                self.linefeed();
+                self.cursor.1 = 0;
+            }
+            b'M' => self.reverse_index(),
+            b'c' => *self = Screen::new(self.cols, self.rows, self.scrollback),
+            _ => {}
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        if let [b"0" | b"2", title, ..] = params {
+            self.title = Some(String::from_utf8_lossy(title).to_string());
+        }
+    }
+}
+
+/// The bytes a terminal program expects for a key press, or `None` for keys it doesn't use.
+fn key_bytes(event: &KeyEvent) -> Option<Vec<u8>> {
+    let named: &[u8] = match &event.key.logical_key {
+        Key::Named(NamedKey::Enter) => b"\r",
+        Key::Named(NamedKey::Backspace) => b"\x7f",
+        Key::Named(NamedKey::Tab) => b"\t",
+        Key::Named(NamedKey::Escape) => b"\x1b",
+        Key::Named(NamedKey::ArrowUp) => b"\x1b[A",
+        Key::Named(NamedKey::ArrowDown) => b"\x1b[B",
+        Key::Named(NamedKey::ArrowRight) => b"\x1b[C",
+        Key::Named(NamedKey::ArrowLeft) => b"\x1b[D",
+        Key::Named(NamedKey::Home) => b"\x1b[H",
+        Key::Named(NamedKey::End) => b"\x1b[F",
+        Key::Named(NamedKey::Insert) => b"\x1b[2~",
+        Key::Named(NamedKey::Delete) => b"\x1b[3~",
+        Key::Named(NamedKey::PageUp) => b"\x1b[5~",
+        Key::Named(NamedKey::PageDown) => b"\x1b[6~",
+        _ => b"",
+    };
+    let mut bytes = if !named.is_empty() {
+        named.to_vec()
+    } else if event.modifiers.control_key() {
+        let ch = match &event.key.logical_key {
+            Key::Character(text) => text.chars().next()?,
+            Key::Named(NamedKey::Space) => ' ',
+            _ => return None,
+        };
+        let code = match ch.to_ascii_lowercase() {
+            ch @ 'a'..='z' => ch as u8 & 0x1f,
+            '@' | ' ' | '2' => 0,
+            '[' | '3' => 0x1b,
+            '\\' | '4' => 0x1c,
+            ']' | '5' => 0x1d,
+            '^' | '6' => 0x1e,
+            '_' | '7' | '/' => 0x1f,
+            _ => return None,
+        };
+        vec![code]
+    } else {
+        event.key.text.as_ref()?.as_bytes().to_vec()
+    };
+    if event.modifiers.alt_key() {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
+
+/// The URL containing the character at `col` of `line`, if any.
+fn url_at(line: &str, col: usize) -> Option<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if !chars.get(col)?.is_ascii_graphic() {
+        return None;
+    }
+    let start = chars[..col]
+        .iter()
+        .rposition(|ch| !ch.is_ascii_graphic())
+        .map_or(0, |index| index + 1);
+    let end = chars[col..]
+        .iter()
+        .position(|ch| !ch.is_ascii_graphic())
+        .map_or(chars.len(), |index| col + index);
+    let word: String = chars[start..end].iter().collect();
+    let url = word.trim_end_matches(['.', ',', ';', ':', ')', ']', '\'', '"', '>']);
+    let offset = url.find("https://").or_else(|| url.find("http://"))?;
+    (start + offset <= col).then(|| url[offset..].to_string())
+}
+
+fn open_url(url: &str) {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut command = Command::new("xdg-open");
+    let _ = command.arg(url).spawn();
+}
+
+enum TerminalOutput {
+    Bytes(Vec<u8>),
+    Exited,
+}
+
+/// A terminal emulator running a program in a pseudo terminal. See [`terminal`]
+pub struct Terminal {
+    data: ViewData,
+    screen: Screen,
+    parser: Parser,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn Child + Send + Sync>,
+    font: FontProps,
+    style: Extracter,
+    family: Vec<FamilyOwned>,
+    cell_size: Size,
+    /// How many lines the view is scrolled up into the scrollback.
+    scroll: usize,
+    /// The selected cells, from where the pointer was pressed to where it is, as indices in
+    /// the lines of the screen and columns.
+    selection: Option<((usize, usize), (usize, usize))>,
+    selecting: bool,
+    title: RwSignal<Option<String>>,
+    exited: RwSignal<bool>,
+}
+
+/// A terminal emulator running `command` in a pseudo terminal, or the user's shell if it's
+/// `None`.
+///
+/// It understands the escape sequences of xterm used by common command line programs, such
+/// as cursor movement, colors and the alternate screen of full screen programs. The number
+/// of columns and rows follows the size of the view.
+///
+/// Lines scrolled off the screen are kept in a scrollback, which is shown by scrolling with
+/// the mouse wheel. Text is selected by dragging and copied with `Ctrl+Shift+C` (`Cmd+C` on
+/// macOS), and `Ctrl+Shift+V` (`Cmd+V`) pastes. Clicking a URL while holding `Ctrl` (`Cmd`)
+/// opens it.
+///
+/// Requires the `terminal` feature.
+///
+/// ```ignore
+/// let mut command = CommandBuilder::new("htop");
+/// command.cwd(project_dir);
+/// terminal(Some(command))?.style(|s| s.size_full().font_size(14.0))
+/// ```
+pub fn terminal(command: Option<CommandBuilder>) -> io::Result<Terminal> {
+    let id = Id::next();
+    let size = PtySize {
+        rows: 24,
+        cols: 80,
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let pair = native_pty_system()
+        .openpty(size)
+        .map_err(io::Error::other)?;
+    let mut command = command.unwrap_or_else(CommandBuilder::new_default_prog);
+    if command.get_env("TERM").is_none() {
+        command.env("TERM", "xterm-256color");
+    }
+    let child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(io::Error::other)?;
+    let mut reader = pair.master.try_clone_reader().map_err(io::Error::other)?;
+    let writer = pair.master.take_writer().map_err(io::Error::other)?;
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    std::thread::spawn(move || {
+        let mut buf = [0; 4096];
+        // Reading fails or ends once the program has exited
+        while let Ok(len @ 1..) = reader.read(&mut buf) {
+            if tx.send(TerminalOutput::Bytes(buf[..len].to_vec())).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(TerminalOutput::Exited);
+    });
+    let output = create_signal_from_channel(rx);
+    let exited = create_rw_signal(false);
+    create_effect(move |_| {
+        output.with(|output| match output {
+            Some(TerminalOutput::Bytes(bytes)) => id.update_state(bytes.clone()),
+            Some(TerminalOutput::Exited) => exited.set(true),
+            None => {}
+        })
+    });
+
+    Ok(Terminal {
+        data: ViewData::new(id),
+        screen: Screen::new(size.cols.into(), size.rows.into(), DEFAULT_SCROLLBACK),
+        parser: Parser::new(),
+        master: pair.master,
+        writer,
+        child,
+        font: FontProps::default(),
+        style: Default::default(),
+        family: Vec::new(),
+        cell_size: Size::ZERO,
+        scroll: 0,
+        selection: None,
+        selecting: false,
+        title: create_rw_signal(None),
+        exited,
+    }
+    .class(TerminalClass)
+    .keyboard_navigatable())
+}
+
+impl Terminal {
+    /// The title set by the program, such as the current directory set by many shells.
+    pub fn title(&self) -> ReadSignal<Option<String>> {
+        self.title.read_only()
+    }
+
+    /// Becomes true when the program has exited.
+    pub fn exited(&self) -> ReadSignal<bool> {
+        self.exited.read_only()
+    }
+
+    /// Keeps up to `lines` lines scrolled off the screen, 10 000 by default.
+    pub fn scrollback(mut self, lines: usize) -> Self {
+        self.screen.scrollback = lines;
+        self
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let _ = self.writer.write_all(bytes);
+        let _ = self.writer.flush();
+        self.scroll = 0;
+        self.id().request_paint();
+    }
+
+    fn attrs(&self) -> Attrs<'_> {
+        Attrs::new()
+            .family(&self.family)
+            .font_size(
+                self.font
+                    .scaled_size(DEFAULT_FONT_SIZE)
+                    .unwrap_or(DEFAULT_FONT_SIZE),
+            )
+            .color(self.style.color().unwrap_or(Color::BLACK))
+    }
+
+    /// The index in the lines of the screen of the first visible line.
+    fn first_visible(&self) -> usize {
+        self.screen.lines.len() - self.screen.rows - self.scroll
+    }
+
+    /// The line index and column of the cell at `pos`.
+    fn cell_at(&self, pos: Point) -> (usize, usize) {
+        if self.cell_size.area() <= 0.0 {
+            return (self.first_visible(), 0);
+        }
+        let row = (pos.y / self.cell_size.height).max(0.0) as usize;
+        let col = (pos.x / self.cell_size.width).max(0.0) as usize;
+        (
+            self.first_visible() + row.min(self.screen.rows - 1),
+            col.min(self.screen.cols - 1),
+        )
+    }
+
+    fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection?;
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let lines: Vec<String> = (start.0..=end.0)
+            .map(|index| {
+                let line = self.screen.line_text(index);
+                let from = if index == start.0 { start.1 } else { 0 };
+                let to = if index == end.0 {
+                    end.1 + 1
+                } else {
+                    line.len()
+                };
+                let text: String = line
+                    .chars()
+                    .skip(from)
+                    .take(to.saturating_sub(from))
+                    .collect();
+                text.trim_end().to_string()
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    fn is_selected(&self, index: usize, col: usize) -> bool {
+        let Some((start, end)) = self.selection else {
+            return false;
+        };
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        start != end && (start..=end).contains(&(index, col))
+    }
+}
+
+impl View for Terminal {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Terminal".into()
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(bytes) = state.downcast::<Vec<u8>>() {
+            for byte in bytes.iter() {
+                self.parser.advance(&mut self.screen, *byte);
+            }
+            if !self.screen.replies.is_empty() {
+                let replies = std::mem::take(&mut self.screen.replies);
+                let _ = self.writer.write_all(&replies);
+            }
+            if self
+                .title
+                .with_untracked(|title| *title != self.screen.title)
+            {
+                self.title.set(self.screen.title.clone());
+            }
+            // The selected line indices no longer match once lines are scrolled off
+            self.selection = None;
+            self.scroll = self.scroll.min(self.screen.lines.len() - self.screen.rows);
+            self.id().request_paint();
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        if self.font.read(cx) | self.style.read(cx) || self.family.is_empty() {
+            let family = self.font.family();
+            self.family =
+                FamilyOwned::parse_list(family.as_deref().unwrap_or("monospace")).collect();
+            let mut text_layout = TextLayout::new();
+            text_layout.set_text("M", AttrsList::new(self.attrs()));
+            self.cell_size = text_layout.size();
+            cx.app_state_mut().request_layout(self.id());
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        if let Some(layout) = cx.get_layout(self.id()) {
+            if !self.cell_size.area() <= 0.0 {
+                let cols = (layout.size.width as f64 / self.cell_size.width) as usize;
+                let rows = (layout.size.height as f64 / self.cell_size.height) as usize;
+                let (cols, rows) = (cols.max(2), rows.max(1));
+                if (cols, rows) != (self.screen.cols, self.screen.rows) {
+                    self.screen.resize(cols, rows);
+                    self.scroll = self.scroll.min(self.screen.lines.len() - rows);
+                    self.selection = None;
+                    let _ = self.master.resize(PtySize {
+                        rows: rows as u16,
+                        cols: cols as u16,
+                        pixel_width: layout.size.width as u16,
+                        pixel_height: layout.size.height as u16,
+                    });
+                }
+            }
+        }
+        crate::view::default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::KeyDown(key_event) => {
+                let shortcut = if cfg!(target_os = "macos") {
+                    key_event.modifiers.super_key()
+                } else {
+                    key_event.modifiers.control_key() && key_event.modifiers.shift_key()
+                };
+                let key = match &key_event.key.logical_key {
+                    Key::Character(ch) if shortcut => Some(ch.to_lowercase()),
+                    _ => None,
+                };
+                match key.as_deref() {
+                    Some("c") => {
+                        if let Some(text) = self.selected_text() {
+                            let _ = Clipboard::set_contents(text);
+                        }
+                    }
+                    Some("v") => {
+                        if let Ok(text) = Clipboard::get_contents() {
+                            self.write(text.as_bytes());
+                        }
+                    }
+                    _ => match key_bytes(key_event) {
+                        Some(bytes) => self.write(&bytes),
+                        None => return EventPropagation::Continue,
+                    },
+                }
+                EventPropagation::Stop
+            }
+            Event::ImeCommit(text) => {
+                self.write(text.as_bytes());
+                EventPropagation::Stop
+            }
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let cell = self.cell_at(pointer_event.pos);
+                self.selection = Some((cell, cell));
+                self.selecting = true;
+                cx.update_active(self.id());
+                self.id().request_focus();
+                self.id().request_paint();
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer_event) if self.selecting => {
+                let cell = self.cell_at(pointer_event.pos);
+                if let Some((_, end)) = self.selection.as_mut() {
+                    *end = cell;
+                }
+                self.id().request_paint();
+                EventPropagation::Stop
+            }
+            Event::PointerUp(pointer_event) => {
+                self.selecting = false;
+                let open_link = if cfg!(target_os = "macos") {
+                    pointer_event.modifiers.super_key()
+                } else {
+                    pointer_event.modifiers.control_key()
+                };
+                if open_link {
+                    let (index, col) = self.cell_at(pointer_event.pos);
+                    if let Some(url) = url_at(&self.screen.line_text(index), col) {
+                        open_url(&url);
+                    }
+                }
+                EventPropagation::Continue
+            }
+            Event::PointerWheel(wheel) if !self.cell_size.area() <= 0.0 => {
+                let lines = (wheel.delta.y / self.cell_size.height).round() as isize;
+                let lines = if lines == 0 {
+                    wheel.delta.y.signum() as isize
+                } else {
+                    lines
+                };
+                let max = self.screen.lines.len() - self.screen.rows;
+                self.scroll = (self.scroll as isize - lines).clamp(0, max as isize) as usize;
+                self.id().request_paint();
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        if self.cell_size.area() <= 0.0 {
+            return;
+        }
+        let Size { width, height } = self.cell_size;
+        let default_fg = self.style.color().unwrap_or(Color::BLACK);
+        let default_bg = self.style.background().unwrap_or(Color::WHITE);
+        let first = self.first_visible();
+        for row in 0..self.screen.rows {
+            let index = first + row;
+            let y = row as f64 * height;
+            let line = &self.screen.lines[index];
+
+            let mut text = String::new();
+            let mut attrs_list = AttrsList::new(self.attrs());
+            for (col, cell) in line.iter().enumerate() {
+                let (fg, bg) = if cell.style.inverse {
+                    (
+                        cell.style.bg.or(Some(default_bg)),
+                        cell.style.fg.or(Some(default_fg)),
+                    )
+                } else {
+                    (cell.style.fg, cell.style.bg)
+                };
+                let rect = Rect::new(col as f64 * width, y, (col + 1) as f64 * width, y + height);
+                if let Some(bg) = bg {
+                    cx.fill(&rect, bg, 0.0);
+                }
+                if self.is_selected(index, col) {
+                    cx.fill(&rect, SELECTION_COLOR, 0.0);
+                }
+                if cell.style.underline {
+                    let underline = Rect::new(rect.x0, rect.y1 - 1.0, rect.x1, rect.y1);
+                    cx.fill(&underline, fg.unwrap_or(default_fg), 0.0);
+                }
+
+                let start = text.len();
+                text.push(cell.ch);
+                if fg.is_some() || cell.style.bold {
+                    let mut attrs = self.attrs().color(fg.unwrap_or(default_fg));
+                    if cell.style.bold {
+                        attrs = attrs.weight(Weight::BOLD);
+                    }
+                    attrs_list.add_span(start..text.len(), attrs);
+                }
+            }
+            let mut text_layout = TextLayout::new();
+            text_layout.set_text(&text, attrs_list);
+            cx.draw_text(&text_layout, Point::new(0.0, y));
+        }
+
+        if self.scroll == 0 && self.screen.cursor_visible {
+            let (row, col) = self.screen.cursor;
+            let rect = Rect::new(
+                col as f64 * width,
+                row as f64 * height,
+                (col + 1) as f64 * width,
+                (row + 1) as f64 * height,
+            );
+            let color = if cx.is_focused(self.id()) {
+                default_fg.with_alpha_factor(0.6)
+            } else {
+                default_fg.with_alpha_factor(0.25)
+            };
+            cx.fill(&rect, color, 0.0);
+        }
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(screen: &mut Screen, input: &str) {
+        let mut parser = Parser::new();
+        for byte in input.bytes() {
+            parser.advance(screen, byte);
+        }
+    }
+
+    fn screen_text(screen: &Screen) -> Vec<String> {
+        (0..screen.rows)
+            .map(|row| screen.line_text(screen.index(row)).trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn escape_sequences() {
+        let mut screen = Screen::new(10, 3, 100);
+        run(&mut screen, "hello\r\n\x1b[31mred\x1b[0m\r\nx");
+        assert_eq!(screen_text(&screen), ["hello", "red", "x"]);
+        assert_eq!(screen.lines[1][0].style.fg, Some(ansi_color(1)));
+        assert_eq!(screen.lines[1][3].style.fg, None);
+
+        // Scrolls "hello" into the scrollback
+        run(&mut screen, "\r\nlast");
+        assert_eq!(screen_text(&screen), ["red", "x", "last"]);
+        assert_eq!(screen.line_text(0).trim_end(), "hello");
+
+        run(&mut screen, "\x1b[1;2H\x1b[K\x1b[2;1H\x1b[2P");
+        assert_eq!(screen_text(&screen), ["r", "", "last"]);
+        assert_eq!(screen.cursor, (1, 0));
+
+        run(&mut screen, "\x1b]0;title\x07");
+        assert_eq!(screen.title.as_deref(), Some("title"));
+    }
+
+    #[test]
+    fn wrapping() {
+        let mut screen = Screen::new(4, 2, 0);
+        run(&mut screen, "abcdef");
+        assert_eq!(screen_text(&screen), ["abcd", "ef"]);
+        screen.resize(6, 2);
+        assert_eq!(screen_text(&screen), ["abcd", "ef"]);
+    }
+
+    #[test]
+    fn urls() {
+        let line = "see https://example.com/a, then";
+        assert_eq!(url_at(line, 10).as_deref(), Some("https://example.com/a"));
+        assert_eq!(url_at(line, 1), None);
+        assert_eq!(url_at(line, 3), None);
+    }
+}
@@ -0,0 +1,901 @@
+use std::{
+    any::Any,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, RwSignal};
+use floem_renderer::{
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
+    Renderer,
+};
+use floem_winit::keyboard::{Key, NamedKey};
+use kurbo::{Point, Rect};
+use taffy::prelude::Node;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    action::exec_after,
+    context::{EventCx, StyleCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    keyboard::KeyEvent,
+    prop_extracter,
+    style::{apply_font_variations, FontProps, LineHeight, Style, TextColor},
+    style_class,
+    unit::PxPct,
+    view::{View, ViewData},
+    views::{auto_scroll_ancestors, range_segments, Decorators, SelectionColor, TextSelection},
+    Clipboard,
+};
+
+style_class!(pub TextAreaClass);
+
+prop_extracter! {
+    Extracter {
+        color: TextColor,
+        line_height: LineHeight,
+        selection_color: SelectionColor,
+    }
+}
+
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
+/// The number of edits which can be undone.
+const MAX_UNDO: usize = 1000;
+
+/// Which of the visual lines meeting at a soft wrap the caret is shown on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Affinity {
+    /// At the end of the line before the wrap.
+    Upstream,
+    /// At the start of the line after the wrap.
+    Downstream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    Other,
+}
+
+/// A replacement of `deleted` by `inserted` at `start`, with the selections around it.
+#[derive(Debug, Clone)]
+struct Edit {
+    start: usize,
+    deleted: String,
+    inserted: String,
+    /// The anchor and caret before and after the edit.
+    before: (usize, usize),
+    after: (usize, usize),
+    kind: EditKind,
+}
+
+impl Edit {
+    /// Merges `next` into this edit if they're undone together: typing or deleting in a row,
+    /// breaking before each new word typed.
+    fn merge(&mut self, next: &Edit) -> bool {
+        let merged = match (self.kind, next.kind) {
+            (EditKind::Typing, EditKind::Typing)
+                if next.deleted.is_empty()
+                    && self.start + self.inserted.len() == next.start
+                    && !(next.inserted.starts_with(char::is_whitespace)
+                        && !self.inserted.ends_with(char::is_whitespace)) =>
+            {
+                self.inserted.push_str(&next.inserted);
+                true
+            }
+            (EditKind::Deleting, EditKind::Deleting) => {
+                if next.start + next.deleted.len() == self.start {
+                    self.deleted.insert_str(0, &next.deleted);
+                    self.start = next.start;
+                    true
+                } else if next.start == self.start {
+                    self.deleted.push_str(&next.deleted);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        };
+        if merged {
+            self.after = next.after;
+        }
+        merged
+    }
+}
+
+#[derive(Default)]
+struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    fn push(&mut self, edit: Edit) {
+        self.redo.clear();
+        if let Some(last) = self.undo.last_mut() {
+            if last.merge(&edit) {
+                return;
+            }
+        }
+        self.undo.push(edit);
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Reverts the last edit of `text`, returning the anchor and caret from before it.
+    fn undo(&mut self, text: &mut String) -> Option<(usize, usize)> {
+        let edit = self.undo.pop()?;
+        text.replace_range(edit.start..edit.start + edit.inserted.len(), &edit.deleted);
+        let before = edit.before;
+        self.redo.push(edit);
+        Some(before)
+    }
+
+    /// Reapplies the last undone edit of `text`, returning the anchor and caret after it.
+    fn redo(&mut self, text: &mut String) -> Option<(usize, usize)> {
+        let edit = self.redo.pop()?;
+        text.replace_range(edit.start..edit.start + edit.deleted.len(), &edit.inserted);
+        let after = edit.after;
+        self.undo.push(edit);
+        Some(after)
+    }
+
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// A line of the text as it's displayed, after wrapping.
+struct VisualLine {
+    /// The range of the text on the line, without the line break.
+    range: Range<usize>,
+    top: f64,
+    height: f64,
+    /// The indices the caret can be at on the line, with their x position.
+    stops: Vec<(usize, f64)>,
+}
+
+impl VisualLine {
+    fn x_of(&self, index: usize) -> f64 {
+        self.stops
+            .iter()
+            .rev()
+            .find(|(stop, _)| *stop <= index)
+            .map_or(0.0, |(_, x)| *x)
+    }
+
+    /// The stop closest to `x`.
+    fn index_at(&self, x: f64) -> usize {
+        self.stops
+            .iter()
+            .min_by(|(_, a), (_, b)| (a - x).abs().total_cmp(&(b - x).abs()))
+            .map_or(self.range.start, |(index, _)| *index)
+    }
+}
+
+fn visual_lines(text_layout: &TextLayout) -> Vec<VisualLine> {
+    let mut line_start = 0;
+    let line_starts: Vec<usize> = text_layout
+        .lines
+        .iter()
+        .map(|line| {
+            let start = line_start;
+            line_start += line.text().len() + 1;
+            start
+        })
+        .collect();
+
+    text_layout
+        .layout_runs()
+        .map(|run| {
+            let start = line_starts.get(run.line_i).copied().unwrap_or(0);
+            let mut stops: Vec<(usize, f64)> = run
+                .glyphs
+                .iter()
+                .flat_map(|glyph| {
+                    [
+                        (start + glyph.start, glyph.x as f64),
+                        (start + glyph.end, (glyph.x + glyph.w) as f64),
+                    ]
+                })
+                .collect();
+            stops.sort_by_key(|(index, _)| *index);
+            stops.dedup_by_key(|(index, _)| *index);
+            if stops.is_empty() {
+                stops.push((start, 0.0));
+            }
+            VisualLine {
+                range: stops[0].0..stops[stops.len() - 1].0,
+                top: run.line_top as f64,
+                height: run.line_height as f64,
+                stops,
+            }
+        })
+        .collect()
+}
+
+fn prev_grapheme(text: &str, index: usize) -> usize {
+    text[..index]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+fn next_grapheme(text: &str, index: usize) -> usize {
+    text[index..]
+        .graphemes(true)
+        .next()
+        .map_or(text.len(), |g| index + g.len())
+}
+
+fn prev_word_start(text: &str, index: usize) -> usize {
+    text[..index]
+        .split_word_bound_indices()
+        .rev()
+        .find(|(_, word)| !word.trim().is_empty())
+        .map_or(0, |(i, _)| i)
+}
+
+fn next_word_end(text: &str, index: usize) -> usize {
+    text[index..]
+        .split_word_bound_indices()
+        .find(|(_, word)| !word.trim().is_empty())
+        .map_or(text.len(), |(i, word)| index + i + word.len())
+}
+
+fn clamp_to_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// A multi-line text editor. See [`text_area`]
+pub struct TextArea {
+    data: ViewData,
+    buffer: RwSignal<String>,
+    /// The contents of the buffer, as they're laid out.
+    text: String,
+    text_layout: TextLayout,
+    text_node: Option<Node>,
+    lines: Vec<VisualLine>,
+    available_width: f32,
+    font: FontProps,
+    style: Extracter,
+    /// The end of the selection which stays put, which is the caret when nothing is selected.
+    anchor: usize,
+    caret: usize,
+    affinity: Affinity,
+    /// The x position kept while moving the caret up and down.
+    goal_x: Option<f64>,
+    history: History,
+    pointer_selection: TextSelection,
+    scroll_caret_into_view: bool,
+    last_cursor_action_on: Instant,
+}
+
+/// A multi-line text editor showing and editing `buffer`, wrapping its lines to the width of
+/// the view.
+///
+/// The text can be selected with the pointer and the keyboard, and copied, cut and pasted with
+/// the usual shortcuts. `Ctrl` `Z` undoes the last edits (`Cmd` `Z` on macOS), grouping the
+/// text typed word by word, and `Ctrl` `Shift` `Z` or `Ctrl` `Y` redoes them. Changing the
+/// buffer from outside of the editor clears its undo history.
+///
+/// The editor grows with its text, so put it in a [`scroll`](crate::views::scroll) to limit
+/// its height. The caret is kept in view when it moves.
+///
+/// ## Example
+/// ```ignore
+/// let comment = create_rw_signal(String::new());
+/// scroll(text_area(comment).style(|s| s.width_full()))
+///     .style(|s| s.width(400.0).max_height(200.0))
+/// ```
+pub fn text_area(buffer: RwSignal<String>) -> TextArea {
+    let id = Id::next();
+    create_effect(move |_| {
+        let text = buffer.get();
+        id.update_state(text);
+    });
+    let text = buffer.get_untracked();
+    let caret = text.len();
+    TextArea {
+        data: ViewData::new(id),
+        buffer,
+        text,
+        text_layout: TextLayout::new(),
+        text_node: None,
+        lines: Vec::new(),
+        available_width: 0.0,
+        font: FontProps::default(),
+        style: Default::default(),
+        anchor: caret,
+        caret,
+        affinity: Affinity::Downstream,
+        goal_x: None,
+        history: History::default(),
+        pointer_selection: TextSelection::default(),
+        scroll_caret_into_view: false,
+        last_cursor_action_on: Instant::now(),
+    }
+    .keyboard_navigatable()
+    .class(TextAreaClass)
+}
+
+impl TextArea {
+    fn get_attrs_list(&self) -> AttrsList {
+        let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
+        if let Some(font_size) = self.font.scaled_size(DEFAULT_FONT_SIZE) {
+            attrs = attrs.font_size(font_size);
+        }
+        if let Some(font_style) = self.font.style() {
+            attrs = attrs.style(font_style);
+        }
+        let font_family = self.font.family().as_ref().map(|font_family| {
+            let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
+            family
+        });
+        if let Some(font_family) = font_family.as_ref() {
+            attrs = attrs.family(font_family);
+        }
+        if let Some(font_weight) = self.font.weight() {
+            attrs = attrs.weight(font_weight);
+        }
+        attrs = apply_font_variations(attrs, &self.font.variations());
+        if let Some(line_height) = self.style.line_height() {
+            attrs = attrs.line_height(line_height);
+        }
+        AttrsList::new(attrs)
+    }
+
+    fn update_text_layout(&mut self) {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_text(&self.text, self.get_attrs_list());
+        if self.available_width > 0.0 {
+            text_layout.set_size(self.available_width, f32::MAX);
+        }
+        self.text_layout = text_layout;
+        self.lines = visual_lines(&self.text_layout);
+    }
+
+    fn selection(&self) -> Range<usize> {
+        self.anchor.min(self.caret)..self.anchor.max(self.caret)
+    }
+
+    /// The visual line the caret is shown on.
+    fn caret_line(&self) -> usize {
+        let mut containing = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.range.start <= self.caret && self.caret <= line.range.end);
+        let line = match self.affinity {
+            Affinity::Upstream => containing.next(),
+            Affinity::Downstream => containing.last(),
+        };
+        line.map(|(i, _)| i).unwrap_or_else(|| {
+            self.lines
+                .iter()
+                .rposition(|line| line.range.start <= self.caret)
+                .unwrap_or(0)
+        })
+    }
+
+    /// The rect of the caret, relative to the text.
+    fn caret_rect(&self) -> Rect {
+        let Some(line) = self.lines.get(self.caret_line()) else {
+            let height = self
+                .font
+                .scaled_size(DEFAULT_FONT_SIZE)
+                .unwrap_or(DEFAULT_FONT_SIZE);
+            return Rect::new(0.0, 0.0, 1.0, height as f64);
+        };
+        let x = line.x_of(self.caret);
+        Rect::new(x, line.top, x + 1.0, line.top + line.height)
+    }
+
+    /// The index on the visual line `line` closest to `x`, and the affinity showing the caret
+    /// on that line.
+    fn index_on_line(&self, line: usize, x: f64) -> (usize, Affinity) {
+        let Some(visual_line) = self.lines.get(line) else {
+            return (self.text.len(), Affinity::Downstream);
+        };
+        let index = visual_line.index_at(x);
+        let wraps = self
+            .lines
+            .get(line + 1)
+            .is_some_and(|next| next.range.start == visual_line.range.end);
+        if index == visual_line.range.end && wraps {
+            (index, Affinity::Upstream)
+        } else {
+            (index, Affinity::Downstream)
+        }
+    }
+
+    /// The index at `pos`, relative to the text.
+    fn index_at(&self, pos: Point) -> (usize, Affinity) {
+        let line = self
+            .lines
+            .iter()
+            .position(|line| pos.y < line.top + line.height)
+            .unwrap_or(self.lines.len().saturating_sub(1));
+        self.index_on_line(line, pos.x)
+    }
+
+    fn text_origin(&self, cx: &EventCx) -> Point {
+        self.text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map_or(Point::ZERO, |layout| {
+                Point::new(layout.location.x as f64, layout.location.y as f64)
+            })
+    }
+
+    /// Moves the caret to `index`, extending the selection when `extend` is set.
+    fn move_caret(&mut self, index: usize, affinity: Affinity, extend: bool) {
+        self.caret = index;
+        if !extend {
+            self.anchor = index;
+        }
+        self.affinity = affinity;
+        self.goal_x = None;
+        self.scroll_caret_into_view = true;
+    }
+
+    fn move_vertically(&mut self, down: bool, extend: bool) {
+        let goal_x = self.goal_x.unwrap_or_else(|| self.caret_rect().x0);
+        let line = self.caret_line();
+        let (index, affinity) = match (down, line) {
+            (false, 0) => (0, Affinity::Downstream),
+            (false, line) => self.index_on_line(line - 1, goal_x),
+            (true, line) if line + 1 >= self.lines.len() => (self.text.len(), Affinity::Downstream),
+            (true, line) => self.index_on_line(line + 1, goal_x),
+        };
+        self.move_caret(index, affinity, extend);
+        self.goal_x = Some(goal_x);
+    }
+
+    /// Replaces `range` of the text with `text`, placing the caret after it.
+    fn replace(&mut self, range: Range<usize>, text: &str, kind: EditKind) -> bool {
+        if range.is_empty() && text.is_empty() {
+            return false;
+        }
+        let before = (self.anchor, self.caret);
+        let deleted = self.text[range.clone()].to_string();
+        self.text.replace_range(range.clone(), text);
+        let caret = range.start + text.len();
+        self.history.push(Edit {
+            start: range.start,
+            deleted,
+            inserted: text.to_string(),
+            before,
+            after: (caret, caret),
+            kind,
+        });
+        self.text_changed(caret, caret);
+        true
+    }
+
+    fn text_changed(&mut self, anchor: usize, caret: usize) {
+        self.buffer.set(self.text.clone());
+        self.update_text_layout();
+        self.move_caret(anchor, Affinity::Downstream, false);
+        self.caret = caret;
+        self.id().request_layout();
+    }
+
+    fn insert(&mut self, text: &str, kind: EditKind) -> bool {
+        let text = text.replace("\r\n", "\n");
+        self.replace(self.selection(), &text, kind)
+    }
+
+    /// Deletes the selection, or the text from the caret to `to` when nothing is selected.
+    fn delete(&mut self, to: usize) -> bool {
+        let selection = self.selection();
+        if !selection.is_empty() {
+            return self.replace(selection, "", EditKind::Other);
+        }
+        let range = self.caret.min(to)..self.caret.max(to);
+        self.replace(range, "", EditKind::Deleting)
+    }
+
+    fn undo(&mut self) -> bool {
+        let Some((anchor, caret)) = self.history.undo(&mut self.text) else {
+            return false;
+        };
+        self.text_changed(anchor, caret);
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some((anchor, caret)) = self.history.redo(&mut self.text) else {
+            return false;
+        };
+        self.text_changed(anchor, caret);
+        true
+    }
+
+    fn copy(&self) -> bool {
+        let selection = self.selection();
+        if selection.is_empty() {
+            return false;
+        }
+        let _ = Clipboard::set_contents(self.text[selection].to_string());
+        true
+    }
+
+    fn handle_key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers;
+        let extend = modifiers.shift_key();
+        let (command, word) = if cfg!(target_os = "macos") {
+            (modifiers.super_key(), modifiers.alt_key())
+        } else {
+            (modifiers.control_key(), modifiers.control_key())
+        };
+        match &event.key.logical_key {
+            Key::Character(ch) if command => match ch.to_lowercase().as_str() {
+                "a" => {
+                    self.move_caret(0, Affinity::Downstream, false);
+                    self.move_caret(self.text.len(), Affinity::Downstream, true);
+                    true
+                }
+                "c" => self.copy(),
+                "x" => self.copy() && self.delete(self.caret),
+                "v" => match Clipboard::get_contents() {
+                    Ok(text) => self.insert(&text, EditKind::Other),
+                    Err(_) => false,
+                },
+                "z" if extend => self.redo(),
+                "z" => self.undo(),
+                "y" if !cfg!(target_os = "macos") => self.redo(),
+                _ => false,
+            },
+            Key::Character(ch) if !modifiers.control_key() && !modifiers.super_key() => {
+                self.insert(ch, EditKind::Typing)
+            }
+            Key::Named(NamedKey::Space) => self.insert(" ", EditKind::Typing),
+            Key::Named(NamedKey::Enter) => self.insert("\n", EditKind::Other),
+            Key::Named(NamedKey::Backspace) => {
+                let to = if word {
+                    prev_word_start(&self.text, self.caret)
+                } else {
+                    prev_grapheme(&self.text, self.caret)
+                };
+                self.delete(to)
+            }
+            Key::Named(NamedKey::Delete) => {
+                let to = if word {
+                    next_word_end(&self.text, self.caret)
+                } else {
+                    next_grapheme(&self.text, self.caret)
+                };
+                self.delete(to)
+            }
+            Key::Named(NamedKey::ArrowLeft) => {
+                let selection = self.selection();
+                let index = if !extend && !selection.is_empty() {
+                    selection.start
+                } else if word {
+                    prev_word_start(&self.text, self.caret)
+                } else {
+                    prev_grapheme(&self.text, self.caret)
+                };
+                self.move_caret(index, Affinity::Downstream, extend);
+                true
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                let selection = self.selection();
+                let index = if !extend && !selection.is_empty() {
+                    selection.end
+                } else if word {
+                    next_word_end(&self.text, self.caret)
+                } else {
+                    next_grapheme(&self.text, self.caret)
+                };
+                self.move_caret(index, Affinity::Downstream, extend);
+                true
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                self.move_vertically(false, extend);
+                true
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                self.move_vertically(true, extend);
+                true
+            }
+            Key::Named(NamedKey::Home) if command => {
+                self.move_caret(0, Affinity::Downstream, extend);
+                true
+            }
+            Key::Named(NamedKey::End) if command => {
+                self.move_caret(self.text.len(), Affinity::Downstream, extend);
+                true
+            }
+            Key::Named(NamedKey::Home) => {
+                let start = self
+                    .lines
+                    .get(self.caret_line())
+                    .map_or(0, |line| line.range.start);
+                self.move_caret(start, Affinity::Downstream, extend);
+                true
+            }
+            Key::Named(NamedKey::End) => {
+                let line = self.caret_line();
+                let end = self
+                    .lines
+                    .get(line)
+                    .map_or(self.text.len(), |line| line.range.end);
+                let (_, affinity) = self.index_on_line(line, f64::INFINITY);
+                self.move_caret(end, affinity, extend);
+                true
+            }
+            Key::Named(NamedKey::Escape) => {
+                cx.app_state.clear_focus();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl View for TextArea {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "TextArea".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(text) = state.downcast::<String>() {
+            if *text == self.text {
+                // The buffer was set by an edit of the editor
+                return;
+            }
+            self.text = *text;
+            self.history.clear();
+            self.anchor = clamp_to_char_boundary(&self.text, self.anchor);
+            self.caret = clamp_to_char_boundary(&self.text, self.caret);
+            self.update_text_layout();
+            cx.request_layout(self.id());
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        if self.font.read(cx) | self.style.read(cx) || self.lines.is_empty() {
+            self.update_text_layout();
+            cx.app_state_mut().request_layout(self.id());
+        }
+    }
+
+    fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
+        cx.layout_node(self.id(), true, |cx| {
+            let size = self.text_layout.size();
+            let caret_height = self.caret_rect().height() as f32;
+
+            if self.text_node.is_none() {
+                self.text_node = Some(
+                    cx.app_state_mut()
+                        .taffy
+                        .new_leaf(taffy::style::Style::DEFAULT)
+                        .unwrap(),
+                );
+            }
+            let text_node = self.text_node.unwrap();
+
+            let style = Style::new()
+                .width(size.width as f32)
+                .height((size.height as f32).max(caret_height))
+                .to_taffy_style();
+            let _ = cx.app_state_mut().taffy.set_style(text_node, style);
+            vec![text_node]
+        })
+    }
+
+    fn compute_layout(&mut self, cx: &mut crate::context::ComputeLayoutCx) -> Option<Rect> {
+        let layout = cx.get_layout(self.id()).unwrap();
+        let style = cx.app_state_mut().get_builtin_style(self.id());
+        let padding_left = match style.padding_left() {
+            PxPct::Px(padding) => padding as f32,
+            PxPct::Pct(pct) => pct as f32 * layout.size.width,
+        };
+        let padding_right = match style.padding_right() {
+            PxPct::Px(padding) => padding as f32,
+            PxPct::Pct(pct) => pct as f32 * layout.size.width,
+        };
+        let available_width = layout.size.width - padding_left - padding_right;
+        if self.available_width != available_width {
+            self.available_width = available_width;
+            self.update_text_layout();
+            cx.app_state_mut().request_layout(self.id());
+        }
+
+        if self.scroll_caret_into_view {
+            self.scroll_caret_into_view = false;
+            let origin = self
+                .text_node
+                .and_then(|node| cx.app_state.taffy.layout(node).ok())
+                .map_or(Point::ZERO, |layout| {
+                    Point::new(layout.location.x as f64, layout.location.y as f64)
+                });
+            self.id()
+                .scroll_to(Some(self.caret_rect() + origin.to_vec2()));
+        }
+        None
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let handled = match &event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                let pos = pointer_event.pos - self.text_origin(cx).to_vec2();
+                let (index, affinity) = self.index_at(pos);
+                if pointer_event.modifiers.shift_key() {
+                    self.move_caret(index, affinity, true);
+                } else {
+                    self.pointer_selection
+                        .pointer_down(&self.text, index, pointer_event.count);
+                    let (anchor, caret) = self.pointer_selection.ends();
+                    self.move_caret(anchor, affinity, false);
+                    self.caret = caret;
+                }
+                cx.update_active(self.id());
+                self.id().request_focus();
+                true
+            }
+            Event::PointerMove(pointer_event) => {
+                let pos = pointer_event.pos - self.text_origin(cx).to_vec2();
+                let (index, affinity) = self.index_at(pos);
+                if self.pointer_selection.pointer_move(&self.text, index) {
+                    let (anchor, caret) = self.pointer_selection.ends();
+                    self.move_caret(anchor, affinity, false);
+                    self.caret = caret;
+                }
+                if cx.is_active(self.id()) {
+                    let origin = cx.app_state.get_layout_rect(self.id()).origin();
+                    auto_scroll_ancestors(self.id(), pointer_event.pos + origin.to_vec2());
+                }
+                false
+            }
+            Event::PointerUp(_) => {
+                self.pointer_selection.pointer_up();
+                false
+            }
+            Event::KeyDown(key_event) => self.handle_key_down(cx, key_event),
+            Event::ImeCommit(text) => self.insert(text, EditKind::Typing),
+            _ => false,
+        };
+
+        if handled {
+            self.last_cursor_action_on = Instant::now();
+            cx.app_state.request_layout(self.id());
+            EventPropagation::Stop
+        } else {
+            EventPropagation::Continue
+        }
+    }
+
+    fn paint(&mut self, cx: &mut crate::context::PaintCx) {
+        let text_node = self.text_node.unwrap();
+        let location = cx.app_state.taffy.layout(text_node).unwrap().location;
+        let point = Point::new(location.x as f64, location.y as f64);
+        let is_focused = cx.app_state.is_focused(&self.id());
+
+        let selection = self.selection();
+        if is_focused && !selection.is_empty() {
+            let color = self.style.selection_color();
+            for segment in range_segments(&self.text_layout, selection) {
+                cx.fill(&(segment.rect + point.to_vec2()), color, 0.0);
+            }
+        }
+
+        cx.draw_text(&self.text_layout, point);
+
+        if !is_focused {
+            return;
+        }
+        let is_cursor_visible = (self.last_cursor_action_on.elapsed().as_millis()
+            / CURSOR_BLINK_INTERVAL_MS as u128)
+            % 2
+            == 0;
+        if is_cursor_visible {
+            let cursor_color = cx
+                .app_state
+                .get_computed_style(self.id())
+                .builtin()
+                .cursor_color()
+                .or(self.style.color())
+                .unwrap_or(Color::BLACK);
+            cx.fill(&(self.caret_rect() + point.to_vec2()), cursor_color, 0.0);
+        }
+
+        let id = self.id();
+        exec_after(
+            Duration::from_millis(CURSOR_BLINK_INTERVAL_MS),
+            Box::new(move |_| {
+                id.request_paint();
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_word_end, prev_word_start, Edit, EditKind, History};
+
+    fn typed(start: usize, text: &str) -> Edit {
+        Edit {
+            start,
+            deleted: String::new(),
+            inserted: text.to_string(),
+            before: (start, start),
+            after: (start + text.len(), start + text.len()),
+            kind: EditKind::Typing,
+        }
+    }
+
+    #[test]
+    fn undo_typing_by_words() {
+        let mut history = History::default();
+        let mut text = String::new();
+        for (i, ch) in "ab cd".char_indices() {
+            text.push(ch);
+            history.push(typed(i, &ch.to_string()));
+        }
+
+        assert_eq!(history.undo(&mut text), Some((2, 2)));
+        assert_eq!(text, "ab");
+        assert_eq!(history.undo(&mut text), Some((0, 0)));
+        assert_eq!(text, "");
+        assert_eq!(history.undo(&mut text), None);
+
+        assert_eq!(history.redo(&mut text), Some((2, 2)));
+        assert_eq!(history.redo(&mut text), Some((5, 5)));
+        assert_eq!(text, "ab cd");
+    }
+
+    #[test]
+    fn backspaces_are_undone_together() {
+        let mut history = History::default();
+        let mut text = "hello".to_string();
+        for start in (3..5).rev() {
+            let deleted = text.split_off(start);
+            history.push(Edit {
+                start,
+                deleted,
+                inserted: String::new(),
+                before: (start + 1, start + 1),
+                after: (start, start),
+                kind: EditKind::Deleting,
+            });
+        }
+        assert_eq!(text, "hel");
+        assert_eq!(history.undo(&mut text), Some((5, 5)));
+        assert_eq!(text, "hello");
+    }
+
+    #[test]
+    fn word_motion() {
+        let text = "let x = 10;";
+        assert_eq!(next_word_end(text, 0), 3);
+        assert_eq!(next_word_end(text, 3), 5);
+        assert_eq!(prev_word_start(text, 5), 4);
+        assert_eq!(prev_word_start(text, 4), 0);
+    }
+}
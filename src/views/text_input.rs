@@ -1,14 +1,17 @@
-use crate::action::exec_after;
+use crate::accessibility::AccessCx;
+use crate::action::{exec_after, set_ime_allowed, set_ime_cursor_area};
 use crate::event::EventListener;
 use crate::keyboard::{self, KeyEvent};
 use crate::pointer::{PointerButton, PointerInputEvent};
-use crate::reactive::{create_effect, RwSignal};
-use crate::style::{CursorColor, FontProps, PaddingLeft};
-use crate::style::{FontStyle, FontWeight, TextColor};
+use crate::reactive::{create_effect, create_memo, Memo, ReadSignal, RwSignal};
+use crate::style::{apply_font_variations, CursorColor, FontProps};
+use crate::style::{FontSize, FontStyle, FontWeight, TextColor};
 use crate::unit::{PxPct, PxPctAuto};
-use crate::view::ViewData;
-use crate::widgets::PlaceholderTextClass;
+use crate::view::{default_compute_layout, default_event, ViewData};
+use crate::views::{auto_scroll_ancestors, dyn_container, empty, label, InputMask};
+use crate::widgets::{PlaceholderTextClass, TextInputErrorClass};
 use crate::{prop, prop_extracter, Clipboard, EventPropagation};
+use accesskit::{Action, ActionData, Role};
 use floem_reactive::create_rw_signal;
 use taffy::prelude::{Layout, Node};
 
@@ -21,6 +24,7 @@ use crate::{peniko::Color, style::Style, view::View};
 use std::{
     any::Any,
     ops::Range,
+    rc::Rc,
     time::{Duration, Instant},
 };
 
@@ -53,7 +57,7 @@ prop_extracter! {
 prop_extracter! {
     PlaceholderStyle {
         pub color: TextColor,
-        //TODO: pub font_size: FontSize,
+        pub font_size: FontSize,
         pub font_weight: FontWeight,
         pub font_style: FontStyle,
     }
@@ -94,6 +98,93 @@ pub struct TextInput {
     cursor_width: f64, // TODO: make this configurable
     is_focused: bool,
     last_cursor_action_on: Instant,
+    leading: Option<Box<dyn View>>,
+    trailing: Option<Box<dyn View>>,
+    // Shown below the input while `error` holds a message
+    error_view: Option<Box<dyn View>>,
+    error: RwSignal<Option<String>>,
+    max_length: Option<usize>,
+    filter: Option<Box<dyn Fn(char) -> bool>>,
+    filter_text: Option<Box<dyn Fn(&str) -> bool>>,
+    selection_state: RwSignal<TextInputSelection>,
+    scroll_caret_into_view: bool,
+    password: bool,
+    mask_char: char,
+    // Shows the password unmasked
+    revealed: bool,
+    reveal_last_typed: bool,
+    // Byte offset of the last typed character of a password, shown unmasked until the timeout
+    last_typed: Option<(usize, Instant)>,
+    mask: Option<Rc<InputMask>>,
+    /// The text being composed with an IME, shown at the caret until it's committed.
+    preedit: Option<Preedit>,
+    /// The caret area last sent to the IME, in window coordinates.
+    ime_cursor_area: Option<Rect>,
+}
+
+struct Preedit {
+    text: String,
+    /// The byte range of the composition cursor in the text, hidden when `None`.
+    cursor: Option<(usize, usize)>,
+}
+
+/// The caret position and selected range of a [`TextInput`], as byte offsets into its buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInputSelection {
+    pub caret: usize,
+    pub range: Option<Range<usize>>,
+}
+
+enum TextInputUpdate {
+    Caret(usize),
+    Selection(Range<usize>),
+    SelectAll,
+    ScrollCaretIntoView,
+    Reveal(bool),
+}
+
+/// A handle to control the caret and selection of a [`TextInput`] after it has been built.
+/// See [`TextInput::handle`]
+#[derive(Clone, Copy)]
+pub struct TextInputHandle {
+    id: Id,
+    selection: RwSignal<TextInputSelection>,
+}
+
+impl TextInputHandle {
+    /// Returns the byte offset of the caret. This is tracked reactively.
+    pub fn caret(&self) -> usize {
+        self.selection.with(|selection| selection.caret)
+    }
+
+    /// Returns the selected byte range, if any. This is tracked reactively.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection.with(|selection| selection.range.clone())
+    }
+
+    /// Returns the signal holding the caret and selection, updated whenever either changes.
+    pub fn selection_signal(&self) -> ReadSignal<TextInputSelection> {
+        self.selection.read_only()
+    }
+
+    /// Moves the caret to the byte offset `caret`, clearing the selection.
+    pub fn set_caret(&self, caret: usize) {
+        self.id.update_state(TextInputUpdate::Caret(caret));
+    }
+
+    /// Selects the byte range `range`, moving the caret to its end.
+    pub fn set_selection(&self, range: Range<usize>) {
+        self.id.update_state(TextInputUpdate::Selection(range));
+    }
+
+    pub fn select_all(&self) {
+        self.id.update_state(TextInputUpdate::SelectAll);
+    }
+
+    /// Scrolls the text and any parent scroll views so that the caret is visible.
+    pub fn scroll_caret_into_view(&self) {
+        self.id.update_state(TextInputUpdate::ScrollCaretIntoView);
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -146,13 +237,32 @@ pub fn text_input(buffer: RwSignal<String>) -> TextInput {
         height: 0.0,
         is_focused: false,
         last_cursor_action_on: Instant::now(),
+        leading: None,
+        trailing: None,
+        error_view: None,
+        error: create_rw_signal(None),
+        max_length: None,
+        filter: None,
+        filter_text: None,
+        selection_state: create_rw_signal(TextInputSelection::default()),
+        scroll_caret_into_view: false,
+        password: false,
+        mask_char: DEFAULT_MASK_CHAR,
+        revealed: false,
+        reveal_last_typed: cfg!(any(target_os = "android", target_os = "ios")),
+        last_typed: None,
+        mask: None,
+        preedit: None,
+        ime_cursor_area: None,
     }
     .keyboard_navigatable()
     .on_event_stop(EventListener::FocusGained, move |_| {
         is_focused.set(true);
+        set_ime_allowed(true);
     })
     .on_event_stop(EventListener::FocusLost, move |_| {
         is_focused.set(false);
+        set_ime_allowed(false);
     })
 }
 
@@ -219,9 +329,321 @@ const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
 /// See https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input/text#size
 // TODO: allow this to be set in the styles
 const APPROX_VISIBLE_CHARS_TARGET: f32 = 10.0;
+const DEFAULT_MASK_CHAR: char = '•';
+const LAST_TYPED_REVEAL_MS: u64 = 1000;
 
 impl TextInput {
+    /// Adds a view before the text, such as a search icon.
+    pub fn leading<V: View + 'static>(mut self, view: V) -> Self {
+        self.leading = Some(Box::new(view));
+        self
+    }
+
+    /// Adds a view after the text, such as a button clearing the input.
+    pub fn trailing<V: View + 'static>(mut self, view: V) -> Self {
+        self.trailing = Some(Box::new(view));
+        self
+    }
+
+    /// Reactively sets the validation error of this input.
+    ///
+    /// While `error` returns a message the input is styled with [`Style::invalid`] and the
+    /// message is shown below the input, in a view with the [`TextInputErrorClass`] class.
+    pub fn error(mut self, error: impl Fn() -> Option<String> + 'static) -> Self {
+        let id = self.id();
+        let error_signal = self.error;
+        create_effect(move |_| {
+            let error = error();
+            id.update_invalid(error.is_some());
+            error_signal.set(error);
+        });
+        if self.error_view.is_none() {
+            self = self.error_message(|error| label(move || error.clone()));
+        }
+        self
+    }
+
+    /// Validates the text whenever it changes, using the returned error as the validation
+    /// error. See [`TextInput::error`].
+    pub fn validate(self, validate: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        let buffer = self.buffer;
+        self.error(move || buffer.with(|text| validate(text).err()))
+    }
+
+    /// Sets the view showing the validation error message.
+    pub fn error_message<V: View + 'static>(
+        mut self,
+        view_fn: impl Fn(String) -> V + 'static,
+    ) -> Self {
+        let error = self.error;
+        let view = dyn_container(
+            move || error.get(),
+            move |error| match error {
+                Some(error) => Box::new(view_fn(error)),
+                None => Box::new(empty()),
+            },
+        )
+        .class(TextInputErrorClass)
+        .style(move |s| {
+            s.absolute()
+                .inset_top_pct(100.0)
+                .apply_if(error.with(|error| error.is_none()), |s| s.hide())
+        });
+        self.error_view = Some(Box::new(view));
+        self
+    }
+
+    /// Limits the number of characters the user can enter.
+    ///
+    /// Typed and pasted text is truncated to fit. Text set directly on the buffer is not limited.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Only allows the user to enter characters for which `filter` returns true.
+    ///
+    /// Rejected characters are dropped from pasted text.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// text_input(buffer).filter(|c| c.is_ascii_digit())
+    /// ```
+    pub fn filter(mut self, filter: impl Fn(char) -> bool + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Rejects typed or pasted text when the resulting text doesn't pass `filter`, which can
+    /// be used to match the text against a pattern such as a regex.
+    ///
+    /// Deleting text is always allowed, so `filter` should accept prefixes of valid text.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let identifier = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    /// text_input(buffer).filter_text(move |text| identifier.is_match(text))
+    /// ```
+    pub fn filter_text(mut self, filter: impl Fn(&str) -> bool + 'static) -> Self {
+        self.filter_text = Some(Box::new(filter));
+        self
+    }
+
+    /// Masks every character of the text, for entering passwords.
+    ///
+    /// While masked, the text can't be copied or cut and word based movement and selection
+    /// apply to the whole text.
+    pub fn password(mut self) -> Self {
+        self.password = true;
+        self
+    }
+
+    /// Sets the character shown in place of each character of a password. Defaults to `•`.
+    pub fn mask_char(mut self, mask_char: char) -> Self {
+        self.mask_char = mask_char;
+        self
+    }
+
+    /// Reactively shows a password unmasked, such as for a "show password" toggle.
+    pub fn reveal(self, reveal: impl Fn() -> bool + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_state(TextInputUpdate::Reveal(reveal()));
+        });
+        self
+    }
+
+    /// Sets whether the last typed character of a password is briefly shown unmasked.
+    ///
+    /// Defaults to true on Android and iOS.
+    pub fn reveal_last_typed(mut self, reveal_last_typed: bool) -> Self {
+        self.reveal_last_typed = reveal_last_typed;
+        self
+    }
+
+    /// Restricts the text to the pattern of `mask`, inserting its literals as the user types.
+    ///
+    /// The buffer holds the formatted text. Use [`TextInput::raw_value`] to get the raw value.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let input = text_input(phone).mask(InputMask::new("(###) ###-####"));
+    /// let digits = input.raw_value();
+    /// ```
+    pub fn mask(mut self, mask: InputMask) -> Self {
+        self.mask = Some(Rc::new(mask));
+        self
+    }
+
+    /// Returns the text without the literals of the mask set with [`TextInput::mask`], or the
+    /// text as is without a mask.
+    pub fn raw_value(&self) -> Memo<String> {
+        let buffer = self.buffer;
+        let mask = self.mask.clone();
+        create_memo(move |_| match &mask {
+            Some(mask) => buffer.with(|text| mask.raw(text)),
+            None => buffer.get(),
+        })
+    }
+
+    /// Returns a handle to read and set the caret and selection of this input.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let input = text_input(buffer);
+    /// let handle = input.handle();
+    /// // Insert text at the caret
+    /// let caret = handle.caret();
+    /// buffer.update(|text| text.insert_str(caret, "inserted"));
+    /// handle.set_caret(caret + "inserted".len());
+    /// ```
+    pub fn handle(&self) -> TextInputHandle {
+        TextInputHandle {
+            id: self.id(),
+            selection: self.selection_state,
+        }
+    }
+
+    /// Returns true if `pos` is over the leading or trailing view.
+    fn is_over_adornment(&self, cx: &EventCx, pos: Point) -> bool {
+        [&self.leading, &self.trailing]
+            .into_iter()
+            .flatten()
+            .any(|view| {
+                cx.get_layout(view.id()).map_or(false, |layout| {
+                    Rect::from_origin_size(
+                        (layout.location.x as f64, layout.location.y as f64),
+                        (layout.size.width as f64, layout.size.height as f64),
+                    )
+                    .contains(pos)
+                })
+            })
+    }
+
+    fn is_masked(&self) -> bool {
+        self.password && !self.revealed
+    }
+
+    fn display_char(&self, idx: usize, c: char) -> char {
+        if !self.is_masked()
+            || self
+                .last_typed
+                .is_some_and(|(typed_idx, _)| typed_idx == idx)
+        {
+            c
+        } else {
+            self.mask_char
+        }
+    }
+
+    /// Returns the text shown in the input, which is masked in password mode, with the IME
+    /// composition at the caret.
+    fn display_text(&self) -> String {
+        let mut text = self.buffer.with_untracked(|buff| {
+            if !self.is_masked() {
+                return buff.clone();
+            }
+            buff.char_indices()
+                .map(|(idx, c)| self.display_char(idx, c))
+                .collect()
+        });
+        if let Some(preedit) = &self.preedit {
+            text.insert_str(self.to_masked_idx(self.cursor_glyph_idx), &preedit.text);
+        }
+        text
+    }
+
+    /// Converts a byte offset in the buffer to a byte offset in the displayed text.
+    fn to_display_idx(&self, idx: usize) -> usize {
+        let display_idx = self.to_masked_idx(idx);
+        match &self.preedit {
+            Some(preedit) if idx > self.cursor_glyph_idx => display_idx + preedit.text.len(),
+            _ => display_idx,
+        }
+    }
+
+    /// The byte range of the IME composition in the displayed text.
+    fn preedit_display_range(&self) -> Option<Range<usize>> {
+        let preedit = self.preedit.as_ref()?;
+        let start = self.to_masked_idx(self.cursor_glyph_idx);
+        Some(start..start + preedit.text.len())
+    }
+
+    /// The byte offset of the caret in the displayed text, which is in the IME composition
+    /// while there's one.
+    fn caret_display_idx(&self) -> usize {
+        let caret = self.to_display_idx(self.cursor_glyph_idx);
+        match &self.preedit {
+            Some(preedit) => {
+                caret
+                    + preedit
+                        .cursor
+                        .map_or(preedit.text.len(), |(start, _)| start)
+            }
+            None => caret,
+        }
+    }
+
+    /// Converts a byte offset in the buffer to a byte offset in the masked text.
+    fn to_masked_idx(&self, idx: usize) -> usize {
+        if !self.is_masked() {
+            return idx;
+        }
+        self.buffer.with_untracked(|buff| {
+            buff.char_indices()
+                .take_while(|(char_idx, _)| *char_idx < idx)
+                .map(|(char_idx, c)| self.display_char(char_idx, c).len_utf8())
+                .sum()
+        })
+    }
+
+    /// Converts a byte offset in the displayed text to a byte offset in the buffer.
+    fn from_display_idx(&self, display_idx: usize) -> usize {
+        let display_idx = match self.preedit_display_range() {
+            Some(range) if display_idx >= range.end => display_idx - range.len(),
+            Some(range) if display_idx > range.start => range.start,
+            _ => display_idx,
+        };
+        if !self.is_masked() {
+            return display_idx;
+        }
+        self.buffer.with_untracked(|buff| {
+            let mut display_len = 0;
+            for (idx, c) in buff.char_indices() {
+                if display_len >= display_idx {
+                    return idx;
+                }
+                display_len += self.display_char(idx, c).len_utf8();
+            }
+            buff.len()
+        })
+    }
+
+    /// Briefly shows the character before the cursor unmasked.
+    fn reveal_typed_char(&mut self) {
+        let typed_idx = self.buffer.with_untracked(|buff| {
+            buff[..self.cursor_glyph_idx]
+                .char_indices()
+                .next_back()
+                .map(|(idx, _)| idx)
+        });
+        self.last_typed = typed_idx.map(|idx| (idx, Instant::now()));
+
+        let id = self.id();
+        exec_after(
+            Duration::from_millis(LAST_TYPED_REVEAL_MS),
+            Box::new(move |_| {
+                id.request_layout();
+            }),
+        );
+    }
+
     fn move_cursor(&mut self, move_kind: Movement, direction: Direction) -> bool {
+        // Word boundaries would give away the masked text
+        let move_kind = match move_kind {
+            Movement::Word if self.is_masked() => Movement::Line,
+            move_kind => move_kind,
+        };
         match (move_kind, direction) {
             (Movement::Glyph, Direction::Left) => {
                 let untracked_buffer = self.buffer.get_untracked();
@@ -290,7 +712,7 @@ impl TextInput {
     fn clip_text(&mut self, node_layout: &Layout) {
         let virt_text = self.text_buf.as_ref().unwrap();
         let node_width = node_layout.size.width as f64;
-        let cursor_text_loc = Cursor::new(0, self.cursor_glyph_idx);
+        let cursor_text_loc = Cursor::new(0, self.caret_display_idx());
         let layout_cursor = virt_text.layout_cursor(&cursor_text_loc);
         let cursor_glyph_pos = virt_text.hit_position(layout_cursor.glyph);
         let cursor_x = cursor_glyph_pos.point.x;
@@ -317,12 +739,10 @@ impl TextInput {
             .index;
 
         let new_text = self
-            .buffer
-            .get()
-            .chars()
-            .skip(clip_start)
-            .take(clip_end - clip_start)
-            .collect();
+            .display_text()
+            .get(clip_start..clip_end)
+            .unwrap_or_default()
+            .to_string();
 
         self.cursor_x -= clip_start_x;
         self.clip_start_idx = clip_start;
@@ -359,6 +779,10 @@ impl TextInput {
     }
 
     fn handle_double_click(&mut self, pos_x: f64, pos_y: f64, cx: &mut EventCx) {
+        if self.is_masked() {
+            self.select_all();
+            return;
+        }
         let clicked_glyph_idx = self.get_box_position(pos_x, pos_y, cx);
 
         self.buffer.with_untracked(|buff| {
@@ -369,27 +793,21 @@ impl TextInput {
     }
 
     fn get_box_position(&self, pos_x: f64, pos_y: f64, cx: &mut EventCx) -> usize {
-        let layout = cx.get_layout(self.id()).unwrap();
-        let style = cx.app_state.get_builtin_style(self.id());
+        let text_node = self.text_node.unwrap();
+        let node_location = cx.app_state.taffy.layout(text_node).unwrap().location;
 
-        let padding_left = match style.padding_left() {
-            PxPct::Px(padding) => padding as f32,
-            PxPct::Pct(pct) => pct as f32 * layout.size.width,
-        };
-        let padding_top = match style.padding_top() {
-            PxPct::Px(padding) => padding as f32,
-            PxPct::Pct(pct) => pct as f32 * layout.size.width,
-        };
-        self.text_buf
+        let display_idx = self
+            .text_buf
             .as_ref()
             .unwrap()
             .hit_point(Point::new(
-                pos_x + self.clip_start_x - padding_left as f64,
+                pos_x + self.clip_start_x - node_location.x as f64,
                 // TODO: prevent cursor incorrectly going to end of buffer when clicking
                 // slightly below the text
-                pos_y - padding_top as f64,
+                pos_y - node_location.y as f64,
             ))
-            .index
+            .index;
+        self.from_display_idx(display_idx)
     }
 
     fn get_selection_rect(&self, node_layout: &Layout, left_padding: f64) -> Rect {
@@ -402,12 +820,19 @@ impl TextInput {
         let virtual_text = self.text_buf.as_ref().unwrap();
         let text_height = virtual_text.size().height;
 
-        let selection_start_x =
-            virtual_text.hit_position(selection.start).point.x - self.clip_start_x;
+        let selection_start_x = virtual_text
+            .hit_position(self.to_display_idx(selection.start))
+            .point
+            .x
+            - self.clip_start_x;
         let selection_start_x = selection_start_x.max(node_layout.location.x as f64 - left_padding);
 
-        let selection_end_x =
-            virtual_text.hit_position(selection.end).point.x + left_padding - self.clip_start_x;
+        let selection_end_x = virtual_text
+            .hit_position(self.to_display_idx(selection.end))
+            .point
+            .x
+            + left_padding
+            - self.clip_start_x;
         let selection_end_x =
             selection_end_x.min(selection_start_x + self.width as f64 + left_padding);
 
@@ -450,8 +875,12 @@ impl TextInput {
         let mut text_layout = TextLayout::new();
         let attrs_list = self.get_text_attrs();
 
-        self.buffer
-            .with_untracked(|buff| text_layout.set_text(buff, attrs_list.clone()));
+        if self.last_typed.is_some_and(|(_, typed_at)| {
+            typed_at.elapsed() >= Duration::from_millis(LAST_TYPED_REVEAL_MS)
+        }) {
+            self.last_typed = None;
+        }
+        text_layout.set_text(&self.display_text(), attrs_list.clone());
 
         let glyph_max_size = self.get_font_glyph_max_size();
         self.height = glyph_max_size.height as f32;
@@ -469,17 +898,20 @@ impl TextInput {
     }
 
     fn font_size(&self) -> f32 {
-        self.font.size().unwrap_or(DEFAULT_FONT_SIZE)
+        self.font
+            .scaled_size(DEFAULT_FONT_SIZE)
+            .unwrap_or(DEFAULT_FONT_SIZE)
     }
 
     pub fn get_placeholder_text_attrs(&self) -> AttrsList {
         let mut attrs = Attrs::new().color(self.placeholder_style.color().unwrap_or(Color::BLACK));
 
-        //TODO:
-        // self.placeholder_style
-        //     .font_size()
-        //     .unwrap_or(self.font_size())
-        attrs = attrs.font_size(self.font_size());
+        attrs = attrs.font_size(
+            self.placeholder_style
+                .font_size()
+                .map(|size| size * self.font.scale() as f32)
+                .unwrap_or_else(|| self.font_size()),
+        );
 
         if let Some(font_style) = self.placeholder_style.font_style() {
             attrs = attrs.style(font_style);
@@ -492,6 +924,7 @@ impl TextInput {
         } else if let Some(font_weight) = self.font.weight() {
             attrs = attrs.weight(font_weight);
         }
+        attrs = apply_font_variations(attrs, &self.font.variations());
         AttrsList::new(attrs)
     }
 
@@ -513,24 +946,104 @@ impl TextInput {
         if let Some(font_weight) = self.font.weight() {
             attrs = attrs.weight(font_weight);
         }
+        attrs = apply_font_variations(attrs, &self.font.variations());
         AttrsList::new(attrs)
     }
 
-    fn select_all(&mut self, cx: &mut EventCx) {
-        let text_node = self.text_node.unwrap();
-        let node_layout = *cx.app_state.taffy.layout(text_node).unwrap();
-        let len = self.buffer.with(|val| val.len());
+    // The text is clipped around the new cursor position in the next `compute_layout`
+    fn select_all(&mut self) {
+        let len = self.buffer.with_untracked(|val| val.len());
         self.cursor_glyph_idx = len;
+        self.selection = Some(0..len);
+    }
 
-        let text_buf = self.text_buf.as_ref().unwrap();
-        let buf_width = text_buf.size().width;
-        let node_width = node_layout.size.width as f64;
+    /// Returns the closest char boundary at or before `idx` in the buffer.
+    fn clamp_to_char_boundary(&self, idx: usize) -> usize {
+        self.buffer.with_untracked(|buff| {
+            let mut idx = idx.min(buff.len());
+            while !buff.is_char_boundary(idx) {
+                idx -= 1;
+            }
+            idx
+        })
+    }
 
-        if buf_width > node_width {
-            self.clip_text(&node_layout);
+    fn update_selection_state(&self) {
+        let state = TextInputSelection {
+            caret: self.cursor_glyph_idx,
+            range: self.selection.clone(),
+        };
+        if self.selection_state.with_untracked(|old| old != &state) {
+            self.selection_state.set(state);
         }
+    }
 
-        self.selection = Some(0..len);
+    /// Inserts `text` at the cursor, replacing the selection. The text is first passed through
+    /// the character filter and truncated to fit the max length, and the insertion is rejected
+    /// if the resulting text doesn't pass the text filter.
+    ///
+    /// Returns false if the buffer was not changed.
+    fn insert_text(&mut self, text: &str) -> bool {
+        let mut text: String = match &self.filter {
+            Some(filter) => text.chars().filter(|c| filter(*c)).collect(),
+            None => text.to_string(),
+        };
+        let selection = self
+            .selection
+            .clone()
+            .unwrap_or(self.cursor_glyph_idx..self.cursor_glyph_idx);
+
+        if let Some(max_length) = self.max_length {
+            let kept_len = self.buffer.with_untracked(|buff| {
+                let selected_len = buff
+                    .get(selection.clone())
+                    .map_or(0, |selected| selected.chars().count());
+                buff.chars().count() - selected_len
+            });
+            truncate_chars(&mut text, max_length.saturating_sub(kept_len));
+        }
+        if text.is_empty() {
+            return false;
+        }
+
+        let (new_buff, cursor) = if let Some(mask) = &self.mask {
+            match self
+                .buffer
+                .with_untracked(|buff| mask.replace(buff, selection, &text))
+            {
+                Some(replaced) => replaced,
+                None => return false,
+            }
+        } else {
+            let mut new_buff = self.buffer.get_untracked();
+            replace_range(&mut new_buff, selection.clone(), Some(&text));
+            (new_buff, selection.start + text.len())
+        };
+        if let Some(filter_text) = &self.filter_text {
+            if !filter_text(&new_buff) {
+                return false;
+            }
+        }
+
+        self.buffer.set(new_buff);
+        self.cursor_glyph_idx = cursor;
+        self.selection = None;
+        true
+    }
+
+    /// Reformats the text with the mask after it was edited without it, e.g. by deleting text.
+    fn normalize_masked_text(&mut self) {
+        let Some(mask) = &self.mask else {
+            return;
+        };
+        let (text, cursor) = self
+            .buffer
+            .with_untracked(|buff| mask.normalize(buff, self.cursor_glyph_idx));
+        if self.buffer.with_untracked(|buff| buff != &text) {
+            self.buffer.set(text);
+        }
+        self.cursor_glyph_idx = cursor;
+        self.selection = None;
     }
 
     fn handle_modifier_cmd(
@@ -547,9 +1060,10 @@ impl TextInput {
 
         match command {
             TextCommand::SelectAll => {
-                self.select_all(cx);
+                self.select_all();
                 true
             }
+            TextCommand::Copy | TextCommand::Cut if self.is_masked() => true,
             TextCommand::Copy => {
                 if let Some(selection) = &self.selection {
                     let selection_txt = self
@@ -592,21 +1106,7 @@ impl TextInput {
                     return false;
                 }
 
-                if let Some(selection) = &self.selection {
-                    self.buffer.update(|buf| {
-                        replace_range(buf, selection.clone(), Some(&clipboard_content))
-                    });
-
-                    self.cursor_glyph_idx +=
-                        clipboard_content.len() - selection.len().min(clipboard_content.len());
-                    self.selection = None;
-                } else {
-                    self.buffer
-                        .update(|buf| buf.insert_str(self.cursor_glyph_idx, &clipboard_content));
-                    self.cursor_glyph_idx += clipboard_content.len();
-                }
-
-                true
+                self.insert_text(&clipboard_content)
             }
             TextCommand::None => {
                 self.selection = None;
@@ -623,30 +1123,13 @@ impl TextInput {
                     return true;
                 }
 
-                let selection = self.selection.clone();
-                if let Some(selection) = selection {
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection.clone(), None));
-                    self.cursor_glyph_idx = selection.start;
-                    self.selection = None;
-                }
-
-                self.buffer
-                    .update(|buf| buf.insert_str(self.cursor_glyph_idx, &ch.clone()));
-                self.move_cursor(Movement::Glyph, Direction::Right)
-            }
-            Key::Named(NamedKey::Space) => {
-                if let Some(selection) = &self.selection {
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection.clone(), None));
-                    self.cursor_glyph_idx = selection.start;
-                    self.selection = None;
-                } else {
-                    self.buffer
-                        .update(|buf| buf.insert(self.cursor_glyph_idx, ' '));
+                let inserted = self.insert_text(ch);
+                if inserted && self.is_masked() && self.reveal_last_typed {
+                    self.reveal_typed_char();
                 }
-                self.move_cursor(Movement::Glyph, Direction::Right)
+                inserted
             }
+            Key::Named(NamedKey::Space) => self.insert_text(" "),
             Key::Named(NamedKey::Backspace) => {
                 let selection = self.selection.clone();
                 if let Some(selection) = selection {
@@ -837,19 +1320,43 @@ impl TextInput {
         cx.draw_text(placeholder_buff, text_start_point);
     }
 
+    /// Underlines the IME composition, with a thicker line under the part being converted.
+    fn paint_preedit_underline(&self, node_layout: &Layout, cx: &mut crate::context::PaintCx<'_>) {
+        let (Some(preedit), Some(range)) = (&self.preedit, self.preedit_display_range()) else {
+            return;
+        };
+        let text_buf = self.text_buf.as_ref().unwrap();
+        let color = self.style.color().unwrap_or(Color::BLACK);
+        let x_of = |idx: usize| {
+            text_buf.hit_position(idx).point.x - self.clip_start_x + node_layout.location.x as f64
+        };
+        let bottom = node_layout.location.y as f64 + self.height as f64;
+        let mut underlines = vec![(range.clone(), 1.0)];
+        if let Some((start, end)) = preedit.cursor.filter(|(start, end)| start != end) {
+            underlines.push((range.start + start..range.start + end, 2.0));
+        }
+        for (range, thickness) in underlines {
+            let line = Rect::new(
+                x_of(range.start),
+                bottom - thickness,
+                x_of(range.end),
+                bottom,
+            );
+            cx.fill(&line, color, 0.0);
+        }
+    }
+
     fn paint_selection_rect(&self, &node_layout: &Layout, cx: &mut crate::context::PaintCx<'_>) {
         let style = cx.app_state.get_computed_style(self.id());
         let cursor_color = style.get(CursorColor);
 
-        let padding_left = match style.get(PaddingLeft) {
-            PxPct::Px(padding) => padding,
-            PxPct::Pct(pct) => pct / 100.0 * node_layout.size.width as f64,
-        };
+        // The text node is offset by the padding and the leading view
+        let left_offset = node_layout.location.x as f64;
 
         let horiz_pad = 1.0;
         let border_radius = self.selection_style.selection_corner_radius();
         let selection_rect = self
-            .get_selection_rect(&node_layout, padding_left)
+            .get_selection_rect(&node_layout, left_offset)
             .inflate(horiz_pad, 0.0)
             .to_rounded_rect(border_radius);
         cx.fill(
@@ -891,6 +1398,13 @@ fn replace_range(buff: &mut String, del_range: Range<usize>, replacement: Option
     buff.push_str(&after_del_range);
 }
 
+/// Truncates `text` to at most `max_chars` characters.
+fn truncate_chars(text: &mut String, max_chars: usize) {
+    if let Some((idx, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(idx);
+    }
+}
+
 fn get_dbl_click_selection(glyph_idx: usize, buffer: &String) -> Range<usize> {
     let mut selectable_ranges: Vec<Range<usize>> = Vec::new();
     let glyph_idx = usize::min(glyph_idx, buffer.len().saturating_sub(1));
@@ -937,10 +1451,56 @@ impl View for TextInput {
         &mut self.data
     }
 
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for child in [&self.leading, &self.trailing, &self.error_view]
+            .into_iter()
+            .flatten()
+        {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for child in [&mut self.leading, &mut self.trailing, &mut self.error_view]
+            .into_iter()
+            .flatten()
+        {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for child in [&mut self.error_view, &mut self.trailing, &mut self.leading]
+            .into_iter()
+            .flatten()
+        {
+            if for_each(child) {
+                break;
+            }
+        }
+    }
+
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        if self.password {
+            return "TextInput: <password>".into();
+        }
         format!("TextInput: {:?}", self.buffer.get_untracked()).into()
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::TextInput);
+        // Passwords are read as their masked text
+        cx.set_value(self.display_text());
+        cx.add_action(Action::SetValue);
+    }
+
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         if let Ok(state) = state.downcast::<(String, bool)>() {
             let (_, is_focused) = *state;
@@ -950,6 +1510,27 @@ impl View for TextInput {
 
             self.is_focused = is_focused;
             cx.request_layout(self.id());
+        } else if let Ok(state) = state.downcast::<TextInputUpdate>() {
+            match *state {
+                TextInputUpdate::Caret(caret) => {
+                    self.cursor_glyph_idx = self.clamp_to_char_boundary(caret);
+                    self.selection = None;
+                }
+                TextInputUpdate::Selection(range) => {
+                    let start = self.clamp_to_char_boundary(range.start);
+                    let end = self.clamp_to_char_boundary(range.end);
+                    self.update_selection(start, end);
+                    self.cursor_glyph_idx = end;
+                }
+                TextInputUpdate::SelectAll => self.select_all(),
+                TextInputUpdate::ScrollCaretIntoView => self.scroll_caret_into_view = true,
+                TextInputUpdate::Reveal(revealed) => {
+                    self.revealed = revealed;
+                    self.last_typed = None;
+                }
+            }
+            self.last_cursor_action_on = Instant::now();
+            cx.request_layout(self.id());
         } else {
             eprintln!("downcast failed");
         }
@@ -958,9 +1539,13 @@ impl View for TextInput {
     fn event(
         &mut self,
         cx: &mut EventCx,
-        _id_path: Option<&[Id]>,
+        id_path: Option<&[Id]>,
         event: Event,
     ) -> EventPropagation {
+        if default_event(self, cx, id_path, event.clone()).is_processed() {
+            return EventPropagation::Stop;
+        }
+
         let buff_len = self.buffer.with_untracked(|buff| buff.len());
         // Workaround for cursor going out of bounds when text buffer is modified externally
         // TODO: find a better way to handle this
@@ -975,7 +1560,7 @@ impl View for TextInput {
                     button: PointerButton::Primary,
                     ..
                 },
-            ) => {
+            ) if !self.is_over_adornment(cx, event.pos) => {
                 cx.update_active(self.id());
                 cx.app_state_mut().request_layout(self.id());
 
@@ -992,10 +1577,53 @@ impl View for TextInput {
                 if cx.is_active(self.id()) {
                     let selection_stop = self.get_box_position(event.pos.x, event.pos.y, cx);
                     self.update_selection(self.cursor_glyph_idx, selection_stop);
+                    let origin = cx.app_state.get_layout_rect(self.id()).origin();
+                    auto_scroll_ancestors(self.id(), event.pos + origin.to_vec2());
                 }
                 false
             }
-            Event::KeyDown(event) => self.handle_key_down(cx, event),
+            // Keys typed while composing are handled by the IME
+            Event::KeyDown(_) if self.preedit.is_some() => false,
+            Event::KeyDown(event) => {
+                self.last_typed = None;
+                let old_len = self.buffer.with_untracked(|buff| buff.len());
+                let handled = self.handle_key_down(cx, event);
+                if handled && self.buffer.with_untracked(|buff| buff.len()) < old_len {
+                    self.normalize_masked_text();
+                }
+                handled
+            }
+            Event::ImePreedit { text, cursor } => {
+                self.preedit = (!text.is_empty()).then(|| Preedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                });
+                true
+            }
+            Event::ImeCommit(text) => {
+                self.last_typed = None;
+                self.preedit = None;
+                self.insert_text(text)
+            }
+            Event::ImeDisabled => {
+                self.preedit = None;
+                true
+            }
+            Event::AccessibilityAction {
+                action: Action::SetValue,
+                data: Some(ActionData::Value(value)),
+            } => {
+                self.last_typed = None;
+                if value.is_empty() {
+                    self.buffer.set(String::new());
+                    self.cursor_glyph_idx = 0;
+                    self.selection = None;
+                    true
+                } else {
+                    self.selection = Some(0..buff_len);
+                    self.insert_text(value)
+                }
+            }
             _ => false,
         };
 
@@ -1021,6 +1649,11 @@ impl View for TextInput {
 
         let placeholder_style = style.clone().apply_class(PlaceholderTextClass);
         self.placeholder_style.read_style(cx, &placeholder_style);
+
+        self.for_each_child_mut(&mut |child| {
+            cx.style_view(child);
+            false
+        });
     }
 
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
@@ -1030,6 +1663,8 @@ impl View for TextInput {
 
             if was_focused && !self.is_focused {
                 self.selection = None;
+                self.preedit = None;
+                self.ime_cursor_area = None;
             }
 
             if self.text_node.is_none() {
@@ -1089,13 +1724,26 @@ impl View for TextInput {
                 PxPctAuto::Pct(_) => PxPctAuto::Pct(100.),
             };
 
+            // Allow the text node to shrink to make room for the leading and trailing views
             let style = Style::new()
                 .width(taffy_node_width)
+                .min_width(0.0)
                 .height(self.height)
                 .to_taffy_style();
             let _ = cx.app_state_mut().taffy.set_style(text_node, style);
 
-            vec![text_node]
+            let mut nodes = Vec::new();
+            if let Some(leading) = &mut self.leading {
+                nodes.push(cx.layout_view(leading));
+            }
+            nodes.push(text_node);
+            if let Some(trailing) = &mut self.trailing {
+                nodes.push(cx.layout_view(trailing));
+            }
+            if let Some(error_view) = &mut self.error_view {
+                nodes.push(cx.layout_view(error_view));
+            }
+            nodes
         })
     }
 
@@ -1107,6 +1755,7 @@ impl View for TextInput {
         let text_node = self.text_node.unwrap();
         let node_layout = *cx.app_state.taffy.layout(text_node).unwrap();
         let node_width = node_layout.size.width as f64;
+        self.width = node_layout.size.width;
 
         if buf_width > node_width {
             self.clip_text(&node_layout);
@@ -1118,14 +1767,34 @@ impl View for TextInput {
                 .text_buf
                 .as_ref()
                 .unwrap()
-                .hit_position(self.cursor_glyph_idx);
+                .hit_position(self.caret_display_idx());
             self.cursor_x = hit_pos.point.x;
         }
 
-        None
+        if self.scroll_caret_into_view {
+            self.scroll_caret_into_view = false;
+            self.id()
+                .scroll_to(Some(self.get_cursor_rect(&node_layout)));
+        }
+        if self.is_focused {
+            // Keeps the IME candidate window next to the caret
+            let cursor_area = self.get_cursor_rect(&node_layout) + cx.window_origin.to_vec2();
+            if self.ime_cursor_area != Some(cursor_area) {
+                self.ime_cursor_area = Some(cursor_area);
+                set_ime_cursor_area(cursor_area.origin(), cursor_area.size());
+            }
+        }
+        self.update_selection_state();
+
+        default_compute_layout(self, cx)
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
+        self.for_each_child_mut(&mut |child| {
+            cx.paint_view(child);
+            false
+        });
+
         if !cx.app_state.is_focused(&self.id())
             && self.buffer.with_untracked(|buff| buff.is_empty())
         {
@@ -1171,6 +1840,8 @@ impl View for TextInput {
             self.paint_selection_rect(&node_layout, cx);
         }
 
+        self.paint_preedit_underline(&node_layout, cx);
+
         let id = self.id();
         exec_after(
             Duration::from_millis(CURSOR_BLINK_INTERVAL_MS),
@@ -1185,7 +1856,7 @@ impl View for TextInput {
 mod tests {
     use crate::views::text_input::get_dbl_click_selection;
 
-    use super::replace_range;
+    use super::{replace_range, truncate_chars};
 
     #[test]
     fn replace_range_start() {
@@ -1233,6 +1904,17 @@ mod tests {
         assert_eq!("Sample", s);
     }
 
+    #[test]
+    fn truncate_chars_multibyte() {
+        let mut s = "añb".to_owned();
+        truncate_chars(&mut s, 2);
+        assert_eq!("añ", s);
+
+        let mut s = "añb".to_owned();
+        truncate_chars(&mut s, 5);
+        assert_eq!("añb", s);
+    }
+
     #[test]
     fn dbl_click_whitespace_before_word() {
         let s = "  select  ".to_owned();
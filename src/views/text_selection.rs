@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+use floem_peniko::Color;
+use floem_renderer::cosmic_text::TextLayout;
+use kurbo::Rect;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    keyboard::{Key, KeyEvent},
+    prop,
+};
+
+prop!(pub SelectionColor: Color {} = Color::rgba8(90, 140, 230, 90));
+
+/// The part of a range of a text layout on a visual line.
+pub(crate) struct Segment {
+    pub(crate) rect: Rect,
+    pub(crate) baseline: f64,
+    pub(crate) font_size: f64,
+}
+
+/// The parts of `range` of the text of `text_layout` on each visual line, relative to the
+/// text. Each part covers the height of its line.
+pub(crate) fn range_segments(text_layout: &TextLayout, range: Range<usize>) -> Vec<Segment> {
+    let mut line_start = 0;
+    let line_starts: Vec<usize> = text_layout
+        .lines
+        .iter()
+        .map(|line| {
+            let start = line_start;
+            line_start += line.text().len() + 1;
+            start
+        })
+        .collect();
+
+    let mut segments = Vec::new();
+    for run in text_layout.layout_runs() {
+        let line_start = line_starts.get(run.line_i).copied().unwrap_or(0);
+        let mut covered: Option<(f64, f64, f64)> = None;
+        for glyph in run.glyphs {
+            let (start, end) = (line_start + glyph.start, line_start + glyph.end);
+            if start >= range.end || end <= range.start {
+                continue;
+            }
+            let (x0, x1) = (glyph.x as f64, (glyph.x + glyph.w) as f64);
+            let font_size = glyph.font_size as f64;
+            covered = Some(match covered {
+                Some((min, max, size)) => (min.min(x0), max.max(x1), size.max(font_size)),
+                None => (x0, x1, font_size),
+            });
+        }
+        if let Some((x0, x1, font_size)) = covered {
+            let top = run.line_top as f64;
+            segments.push(Segment {
+                rect: Rect::new(x0, top, x1, top + run.line_height as f64),
+                baseline: run.line_y as f64,
+                font_size,
+            });
+        }
+    }
+    segments
+}
+
+/// What a drag selects, set by the number of clicks starting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Granularity {
+    #[default]
+    Character,
+    Word,
+    Line,
+}
+
+/// Selecting text with the pointer: dragging selects characters, double clicking selects a
+/// word and triple clicking a line, and dragging after those extends the selection by words or
+/// lines.
+#[derive(Default)]
+pub(crate) struct TextSelection {
+    /// The range the drag started with, which stays selected.
+    anchor: Range<usize>,
+    selection: Range<usize>,
+    granularity: Granularity,
+    dragging: bool,
+}
+
+impl TextSelection {
+    /// The unit of `text` containing the byte at `index`.
+    fn unit_at(&self, text: &str, index: usize) -> Range<usize> {
+        let index = index.min(text.len());
+        match self.granularity {
+            Granularity::Character => index..index,
+            Granularity::Word => text
+                .split_word_bound_indices()
+                .map(|(start, word)| start..start + word.len())
+                .find(|word| word.contains(&index) || word.end == text.len())
+                .unwrap_or(index..index),
+            Granularity::Line => {
+                let start = text[..index].rfind('\n').map_or(0, |i| i + 1);
+                let end = text[index..].find('\n').map_or(text.len(), |i| index + i);
+                start..end
+            }
+        }
+    }
+
+    /// Starts selecting at `index` of `text`, pressed with `count` clicks.
+    pub(crate) fn pointer_down(&mut self, text: &str, index: usize, count: u8) {
+        self.granularity = match count {
+            0 | 1 => Granularity::Character,
+            2 => Granularity::Word,
+            _ => Granularity::Line,
+        };
+        self.anchor = self.unit_at(text, index);
+        self.selection = self.anchor.clone();
+        self.dragging = true;
+    }
+
+    /// Extends the selection to `index` while dragging. Returns whether the selection changed.
+    pub(crate) fn pointer_move(&mut self, text: &str, index: usize) -> bool {
+        if !self.dragging {
+            return false;
+        }
+        let unit = self.unit_at(text, index);
+        let selection = if unit.start < self.anchor.start {
+            unit.start..self.anchor.end
+        } else {
+            self.anchor.start..unit.end.max(self.anchor.end)
+        };
+        let changed = selection != self.selection;
+        self.selection = selection;
+        changed
+    }
+
+    pub(crate) fn pointer_up(&mut self) {
+        self.dragging = false;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = TextSelection::default();
+    }
+
+    /// The end of the selection the drag started from and the end following the pointer.
+    pub(crate) fn ends(&self) -> (usize, usize) {
+        if self.selection.start < self.anchor.start {
+            (self.selection.end, self.selection.start)
+        } else {
+            (self.selection.start, self.selection.end)
+        }
+    }
+
+    /// The selected range, if it isn't empty.
+    pub(crate) fn range(&self) -> Option<Range<usize>> {
+        (!self.selection.is_empty()).then(|| self.selection.clone())
+    }
+
+    /// Copies the selection of `text` for `Ctrl` `C`, or `Cmd` `C` on macOS. Returns whether
+    /// the key was handled.
+    pub(crate) fn copy_shortcut(&self, text: &str, event: &KeyEvent) -> bool {
+        let command = if cfg!(target_os = "macos") {
+            event.modifiers.super_key()
+        } else {
+            event.modifiers.control_key()
+        };
+        let is_copy = match &event.key.logical_key {
+            Key::Character(ch) => command && ch.eq_ignore_ascii_case("c"),
+            _ => false,
+        };
+        match self.range().and_then(|range| text.get(range)) {
+            Some(selected) if is_copy => {
+                let _ = crate::Clipboard::set_contents(selected.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextSelection;
+
+    #[test]
+    fn word_and_line_selection() {
+        let text = "Hello big world\nSecond line";
+        let mut selection = TextSelection::default();
+
+        selection.pointer_down(text, 7, 2);
+        assert_eq!(selection.range(), Some(6..9));
+        // Dragging back extends by words, keeping the first word selected
+        selection.pointer_move(text, 1);
+        assert_eq!(selection.range(), Some(0..9));
+        selection.pointer_up();
+        assert!(!selection.pointer_move(text, 12));
+
+        selection.pointer_down(text, 20, 3);
+        assert_eq!(selection.range(), Some(16..27));
+
+        selection.pointer_down(text, 3, 1);
+        assert_eq!(selection.range(), None);
+        selection.pointer_move(text, 8);
+        assert_eq!(selection.range(), Some(3..8));
+    }
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use floem_reactive::{with_scope, Scope};
+
+use crate::{
+    action::exec_after,
+    context::{MovedViewState, UpdateCx},
+    id::Id,
+    view::{view_children_set_parent_id, View, ViewData},
+};
+
+enum ViewSlotUpdate {
+    /// Moves the view of the slot to the slot with this id.
+    MoveTo(Id),
+    Receive(MovedView),
+}
+
+/// A view taken out of a window along with its state, on its way to a slot in another window.
+struct MovedView {
+    view: Box<dyn View>,
+    scope: Scope,
+    states: Vec<MovedViewState>,
+}
+
+/// A container holding a view which can be moved to another slot. See [`view_slot`]
+pub struct ViewSlot {
+    data: ViewData,
+    child: Option<(Box<dyn View>, Scope)>,
+}
+
+/// A container holding the view made by `child`, which can be moved to another slot, in the
+/// same window or in another one, with [`move_view`].
+///
+/// The view is moved as it is, along with its state and the reactive scope it was made in, so
+/// it isn't rebuilt and its signals and effects keep running. The scope isn't a child of the
+/// window's scope and lives as long as the view, so closing the window the view was made in
+/// doesn't dispose it.
+///
+/// ## Example
+/// ```ignore
+/// let editor = view_slot(move || editor_view(doc));
+/// let editor_slot = editor.id();
+///
+/// // When the editor is torn off its window
+/// new_window(
+///     move |_| {
+///         let slot = empty_view_slot();
+///         move_view(editor_slot, slot.id());
+///         slot.style(|s| s.size_full())
+///     },
+///     None,
+/// );
+/// ```
+pub fn view_slot<V: View + 'static>(child: impl FnOnce() -> V) -> ViewSlot {
+    let scope = Scope::new();
+    let child = with_scope(scope, move || Box::new(child()) as Box<dyn View>);
+    ViewSlot {
+        data: ViewData::new(Id::next()),
+        child: Some((child, scope)),
+    }
+}
+
+/// A slot without a view, which receives views moved with [`move_view`]. See [`view_slot`]
+pub fn empty_view_slot() -> ViewSlot {
+    ViewSlot {
+        data: ViewData::new(Id::next()),
+        child: None,
+    }
+}
+
+/// Moves the view of the slot `from` into the slot `to`, which can be in another window,
+/// replacing the view it holds. See [`view_slot`]
+///
+/// The view is moved once the window of `from` processes its updates, so `from` has to stay in
+/// its window until then. The view stays where it is if `to` isn't in a window by then, or is
+/// inside the moved view.
+pub fn move_view(from: Id, to: Id) {
+    from.update_state(ViewSlotUpdate::MoveTo(to));
+}
+
+impl ViewSlot {
+    /// The id of the view held by the slot.
+    pub fn view_id(&self) -> Option<Id> {
+        self.child.as_ref().map(|(child, _)| child.id())
+    }
+}
+
+impl View for ViewSlot {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        if let Some((child, _)) = &self.child {
+            for_each(child);
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        if let Some((child, _)) = &mut self.child {
+            for_each(child);
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        if let Some((child, _)) = &mut self.child {
+            for_each(child);
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ViewSlot".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        let Ok(update) = state.downcast::<ViewSlotUpdate>() else {
+            return;
+        };
+        match *update {
+            ViewSlotUpdate::MoveTo(to) => {
+                let Some(child_id) = self.view_id() else {
+                    return;
+                };
+                let can_move = to != self.id()
+                    && to
+                        .id_path()
+                        .map_or(false, |path| !path.0.contains(&child_id));
+                if !can_move {
+                    return;
+                }
+                let Some((view, scope)) = self.child.take() else {
+                    return;
+                };
+                let states = cx.app_state.take_view_states(&*view);
+                cx.app_state.request_layout(self.id());
+                to.update_state(ViewSlotUpdate::Receive(MovedView {
+                    view,
+                    scope,
+                    states,
+                }));
+                // Wakes the other window up, in case it already processed its updates
+                exec_after(Duration::ZERO, |_| {});
+            }
+            ViewSlotUpdate::Receive(moved) => {
+                if let Some((mut old, old_scope)) = self.child.take() {
+                    cx.app_state.remove_view(&mut *old);
+                    old_scope.dispose();
+                }
+                let MovedView {
+                    view,
+                    scope,
+                    states,
+                } = moved;
+                view.id().set_parent(self.id());
+                view_children_set_parent_id(&*view);
+                cx.app_state.insert_view_states(states);
+                self.child = Some((view, scope));
+                cx.app_state.request_all(self.id());
+            }
+        }
+    }
+}
+
+impl Drop for ViewSlot {
+    fn drop(&mut self) {
+        if let Some((_, scope)) = &self.child {
+            scope.dispose();
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use super::{
     virtual_stack, Decorators, Item, VirtualDirection, VirtualItemSize, VirtualStack, VirtualVector,
 };
-use crate::context::ComputeLayoutCx;
+use crate::action::exec_after;
+use crate::context::{AppState, ComputeLayoutCx};
 use crate::reactive::create_effect;
 use crate::EventPropagation;
 use crate::{
@@ -12,20 +13,51 @@ use crate::{
 };
 use floem_reactive::{create_rw_signal, RwSignal};
 use kurbo::{Rect, Size};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 enum ListUpdate {
     SelectionChanged,
     ScrollToSelected,
+    ScrollToIndex(usize, ScrollAlignment),
+    ScrollFrame,
+}
+
+/// Where [`VirtualList::scroll_to_index`] places the item in the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAlignment {
+    /// The start of the item is aligned with the start of the viewport.
+    Start,
+    Center,
+    /// The end of the item is aligned with the end of the viewport.
+    End,
+    /// Scrolls the least to make the item visible, if it isn't already.
+    Nearest,
+}
+
+struct ScrollAnimation {
+    from: f64,
+    to: f64,
+    start: Instant,
+    duration: Duration,
 }
 
 pub struct VirtualList<T: 'static> {
     data: ViewData,
     direction: VirtualDirection,
     child_size: Size,
+    viewport: Rect,
     selection: RwSignal<Option<usize>>,
     offsets: RwSignal<Vec<f64>>,
+    /// The ids of the item views by index, which may have been removed since.
+    item_ids: Rc<RefCell<HashMap<usize, Id>>>,
+    /// The item scrolled to by `scroll_to_index`, until its position is known from its layout.
+    scroll_target: Option<(usize, ScrollAlignment)>,
+    scroll_duration: Option<Duration>,
+    animation: Option<ScrollAnimation>,
     child: VirtualStack<Item, (usize, T)>,
 }
 
@@ -34,6 +66,102 @@ impl<T> VirtualList<T> {
         self.selection
     }
 
+    /// Scrolls to the item at the index returned by `target`, placing it in the viewport
+    /// according to the alignment.
+    ///
+    /// The item doesn't need to be shown. Its position is first estimated from the item sizes,
+    /// then corrected once the item view is laid out.
+    pub fn scroll_to_index(
+        self,
+        target: impl Fn() -> Option<(usize, ScrollAlignment)> + 'static,
+    ) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            if let Some((index, alignment)) = target() {
+                id.update_state_deferred(ListUpdate::ScrollToIndex(index, alignment));
+            }
+        });
+        self
+    }
+
+    /// Animates the scrolling of [`scroll_to_index`](Self::scroll_to_index) over `duration`.
+    pub fn animate_scroll(mut self, duration: Duration) -> Self {
+        self.scroll_duration = Some(duration);
+        self
+    }
+
+    /// The position of an item along the main axis, from its layout if it's shown or from
+    /// the item sizes otherwise.
+    fn item_span(&self, app_state: &AppState, index: usize) -> Option<(f64, f64)> {
+        let item_id = self.item_ids.borrow().get(&index).copied();
+        if let Some(item_id) = item_id {
+            let mut shown = false;
+            self.child.for_each_child(&mut |item| {
+                shown = item.id() == item_id;
+                shown
+            });
+            if let Some(layout) = app_state.get_layout(item_id).filter(|_| shown) {
+                let (start, size) = match self.direction {
+                    VirtualDirection::Vertical => (layout.location.y, layout.size.height),
+                    VirtualDirection::Horizontal => (layout.location.x, layout.size.width),
+                };
+                return Some((start as f64, (start + size) as f64));
+            }
+        }
+        self.offsets.with_untracked(|offsets| {
+            offsets
+                .get(index..index + 2)
+                .map(|offsets| (offsets[0], offsets[1]))
+        })
+    }
+
+    /// The start of the viewport showing the item with the given alignment.
+    fn target_offset(
+        &self,
+        app_state: &AppState,
+        index: usize,
+        alignment: ScrollAlignment,
+    ) -> Option<f64> {
+        let (start, end) = self.item_span(app_state, index)?;
+        let (viewport_start, length, content) = match self.direction {
+            VirtualDirection::Vertical => (
+                self.viewport.y0,
+                self.viewport.height(),
+                self.child_size.height,
+            ),
+            VirtualDirection::Horizontal => (
+                self.viewport.x0,
+                self.viewport.width(),
+                self.child_size.width,
+            ),
+        };
+        let offset = match alignment {
+            ScrollAlignment::Start => start,
+            ScrollAlignment::Center => (start + end - length) / 2.0,
+            ScrollAlignment::End => end - length,
+            ScrollAlignment::Nearest if start < viewport_start => start,
+            ScrollAlignment::Nearest if end > viewport_start + length => end - length,
+            ScrollAlignment::Nearest => viewport_start,
+        };
+        Some(offset.min(content - length).max(0.0))
+    }
+
+    fn current_offset(&self) -> f64 {
+        match self.direction {
+            VirtualDirection::Vertical => self.viewport.y0,
+            VirtualDirection::Horizontal => self.viewport.x0,
+        }
+    }
+
+    /// Scrolls the viewport to start at `offset` along the main axis.
+    fn scroll_to_offset(&self, offset: f64) {
+        let rect = match self.direction {
+            VirtualDirection::Vertical => self.viewport.with_origin((self.viewport.x0, offset)),
+            VirtualDirection::Horizontal => self.viewport.with_origin((offset, self.viewport.y0)),
+        };
+        self.child.id().scroll_to(Some(rect));
+    }
+
     pub fn on_select(self, on_select: impl Fn(Option<usize>) + 'static) -> Self {
         create_effect(move |_| {
             let selection = self.selection.get();
@@ -63,6 +191,8 @@ where
     let selection = create_rw_signal(None);
     let length = create_rw_signal(0);
     let offsets = create_rw_signal(Vec::new());
+    let item_ids = Rc::new(RefCell::new(HashMap::new()));
+    let item_ids_ = item_ids.clone();
     create_effect(move |_| {
         selection.track();
         id.update_state(ListUpdate::SelectionChanged);
@@ -85,7 +215,8 @@ where
                     current += item_size;
                 }
             }
-            VirtualItemSize::Fn(size_fn) => {
+            // Measured items are placed at their estimated sizes when scrolling to them
+            VirtualItemSize::Fn(size_fn) | VirtualItemSize::Measured(size_fn) => {
                 for item in items.slice(0..(items.total_len())) {
                     new_offsets.push(current);
                     current += size_fn(&item);
@@ -102,12 +233,18 @@ where
     let item_size = match shared.1 {
         VirtualItemSize::Fixed(..) => VirtualItemSize::Fixed(Box::new(move || match shared_.1 {
             VirtualItemSize::Fixed(ref f) => f(),
-            VirtualItemSize::Fn(..) => panic!(),
+            _ => panic!(),
         })),
         VirtualItemSize::Fn(..) => VirtualItemSize::Fn(Box::new(move |(_, e)| match shared_.1 {
-            VirtualItemSize::Fixed(..) => panic!(),
             VirtualItemSize::Fn(ref f) => f(e),
+            _ => panic!(),
         })),
+        VirtualItemSize::Measured(..) => {
+            VirtualItemSize::Measured(Box::new(move |(_, e)| match shared_.1 {
+                VirtualItemSize::Measured(ref f) => f(e),
+                _ => panic!(),
+            }))
+        }
     };
     let stack = virtual_stack(
         direction,
@@ -119,10 +256,11 @@ where
         },
         move |(_, e)| key_fn(e),
         move |(index, e)| {
+            let item_id = Id::next();
+            item_ids_.borrow_mut().insert(index, item_id);
             Item {
-                data: ViewData::new(Id::next()),
-                selection,
-                index,
+                data: ViewData::new(item_id),
+                is_selected: Box::new(move || selection.get_untracked() == Some(index)),
                 child: Box::new(view_fn(e)),
             }
             .on_click_stop(move |_| {
@@ -143,6 +281,11 @@ where
         direction,
         offsets,
         child_size: Size::ZERO,
+        viewport: Rect::ZERO,
+        item_ids,
+        scroll_target: None,
+        scroll_duration: None,
+        animation: None,
         child: stack,
     }
     .keyboard_navigatable()
@@ -260,6 +403,50 @@ impl<T> View for VirtualList<T> {
                         });
                     }
                 }
+                ListUpdate::ScrollToIndex(index, alignment) => {
+                    self.scroll_target = Some((index, alignment));
+                    let Some(offset) = self.target_offset(cx.app_state, index, alignment) else {
+                        return;
+                    };
+                    match self.scroll_duration {
+                        Some(duration) => {
+                            self.animation = Some(ScrollAnimation {
+                                from: self.current_offset(),
+                                to: offset,
+                                start: Instant::now(),
+                                duration,
+                            });
+                            self.id().update_state(ListUpdate::ScrollFrame);
+                        }
+                        None => self.scroll_to_offset(offset),
+                    }
+                }
+                ListUpdate::ScrollFrame => {
+                    // Follow the corrected position of the target as its items are shown
+                    let to = self.scroll_target.and_then(|(index, alignment)| {
+                        self.target_offset(cx.app_state, index, alignment)
+                    });
+                    let Some(animation) = self.animation.as_mut() else {
+                        return;
+                    };
+                    if let Some(to) = to {
+                        animation.to = to;
+                    }
+                    let t = (animation.start.elapsed().as_secs_f64()
+                        / animation.duration.as_secs_f64().max(f64::EPSILON))
+                    .min(1.0);
+                    let eased = 1.0 - (1.0 - t).powi(3);
+                    let offset = animation.from + (animation.to - animation.from) * eased;
+                    if t < 1.0 {
+                        let id = self.id();
+                        exec_after(Duration::from_millis(16), move |_| {
+                            id.update_state(ListUpdate::ScrollFrame);
+                        });
+                    } else {
+                        self.animation = None;
+                    }
+                    self.scroll_to_offset(offset);
+                }
             }
         }
     }
@@ -270,7 +457,30 @@ impl<T> View for VirtualList<T> {
             .get_layout(self.child.id())
             .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
             .unwrap();
+        self.viewport = cx.current_viewport();
+
+        let rect = cx.compute_view_layout(&mut self.child);
+
+        // Correct an estimated scroll position once the target item is laid out
+        if let Some((index, alignment)) = self.scroll_target.filter(|_| self.animation.is_none()) {
+            let item_id = self.item_ids.borrow().get(&index).copied();
+            let mut shown = false;
+            if let Some(item_id) = item_id {
+                self.child.for_each_child(&mut |item| {
+                    shown = item.id() == item_id;
+                    shown
+                });
+            }
+            if shown {
+                self.scroll_target = None;
+                if let Some(offset) = self.target_offset(cx.app_state, index, alignment) {
+                    if (offset - self.current_offset()).abs() > 0.5 {
+                        self.scroll_to_offset(offset);
+                    }
+                }
+            }
+        }
 
-        cx.compute_view_layout(&mut self.child)
+        rect
     }
 }
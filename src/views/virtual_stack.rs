@@ -1,11 +1,21 @@
-use std::{hash::Hash, marker::PhantomData, ops::Range};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Range,
+    rc::Rc,
+};
 
-use floem_reactive::{as_child_of_current_scope, create_effect, create_signal, Scope, WriteSignal};
-use kurbo::{Rect, Size};
+use floem_reactive::{
+    as_child_of_current_scope, create_effect, create_rw_signal, create_signal, RwSignal, Scope,
+    WriteSignal,
+};
+use kurbo::{Rect, Size, Vec2};
+use rustc_hash::FxHasher;
 use smallvec::SmallVec;
 use taffy::{
     prelude::Node,
-    style::{Dimension, FlexDirection, LengthPercentage},
+    style::{Dimension, FlexDirection, LengthPercentage, LengthPercentageAuto},
 };
 
 use crate::{
@@ -15,7 +25,7 @@ use crate::{
     view::{self, View, ViewData},
 };
 
-use super::{apply_diff, diff, Diff, DiffOpAdd, FxIndexSet, HashRun};
+use super::{apply_diff, diff, scroll::scroll_ancestor_by, Diff, DiffOpAdd, FxIndexSet, HashRun};
 
 #[derive(Clone, Copy)]
 pub enum VirtualDirection {
@@ -23,9 +33,27 @@ pub enum VirtualDirection {
     Horizontal,
 }
 
+/// The edge of the viewport a pinned item of a [`VirtualStack`] is shown at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualPin {
+    /// The top of a vertical stack or the left of a horizontal stack.
+    Start,
+    /// The bottom of a vertical stack or the right of a horizontal stack.
+    End,
+}
+
+type PinFn<T> = Rc<dyn Fn(&T) -> Option<VirtualPin>>;
+
 pub enum VirtualItemSize<T> {
     Fn(Box<dyn Fn(&T) -> f64>),
     Fixed(Box<dyn Fn() -> f64>),
+    /// Items are measured when they're first laid out, with their sizes cached by key. Until
+    /// then an item takes the size estimated by the function.
+    ///
+    /// When an item before the visible ones is measured with a different size than its
+    /// estimate, the enclosing scroll view is scrolled by the difference so the visible items
+    /// don't move.
+    Measured(Box<dyn Fn(&T) -> f64>),
 }
 
 pub trait VirtualVector<T> {
@@ -63,12 +91,58 @@ where
     content_size: f64,
     offset_node: Option<Node>,
     content_node: Option<Node>,
+    pinned: RwSignal<Option<PinFn<T>>>,
+    /// The pinned items, with the ones pinned to the start first.
+    pinned_children: Vec<Option<(V, Scope)>>,
+    pinned_start_len: usize,
+    pinned_nodes: Option<(Node, Node)>,
+    /// The measured sizes of the items of a [`VirtualItemSize::Measured`] stack, by key hash.
+    measured: Option<RwSignal<HashMap<u64, f64>>>,
+    /// The key hashes of the children and the sizes they were given, to measure them against.
+    measured_children: Vec<(u64, f64)>,
 }
 
 struct VirtualStackState<T> {
     diff: Diff<T>,
+    pinned_diff: Diff<T>,
+    pinned_start_len: usize,
     before_size: f64,
     content_size: f64,
+    measured_children: Vec<(u64, f64)>,
+}
+
+fn key_hash<K: Hash>(key: &K) -> u64 {
+    let mut hasher = FxHasher::default();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The diff from the previous keys to `keys`, adding the views of `items` in the same order.
+fn items_diff<K: Eq + Hash, T>(
+    prev: Option<&FxIndexSet<K>>,
+    keys: &FxIndexSet<K>,
+    items: Vec<T>,
+) -> Diff<T> {
+    if let Some(prev) = prev {
+        let mut diff = diff(prev, keys);
+        let mut items = items
+            .into_iter()
+            .map(|i| Some(i))
+            .collect::<SmallVec<[Option<_>; 128]>>();
+        for added in &mut diff.added {
+            added.view = Some(items[added.at].take().unwrap());
+        }
+        diff
+    } else {
+        let mut diff = Diff::default();
+        for (i, item) in items.into_iter().enumerate() {
+            diff.added.push(DiffOpAdd {
+                at: i,
+                view: Some(item),
+            });
+        }
+        diff
+    }
 }
 
 pub fn virtual_stack<T, IF, I, KF, K, VF, V>(
@@ -90,9 +164,18 @@ where
     let id = Id::next();
 
     let (viewport, set_viewport) = create_signal(Rect::ZERO);
+    let pinned: RwSignal<Option<PinFn<T>>> = create_rw_signal(None);
+    let measured = match &item_size {
+        VirtualItemSize::Measured(_) => Some(create_rw_signal(HashMap::new())),
+        _ => None,
+    };
 
     create_effect(move |prev| {
         let mut items_vector = each_fn();
+        let pin_fn = pinned.get();
+        if let Some(measured) = measured {
+            measured.track();
+        }
         let viewport = viewport.get();
         let min = match direction {
             VirtualDirection::Vertical => viewport.y0,
@@ -103,10 +186,66 @@ where
             VirtualDirection::Horizontal => viewport.width() + viewport.x0,
         };
         let mut items = Vec::new();
+        let mut pinned_start = Vec::new();
+        let mut pinned_end = Vec::new();
 
         let mut before_size = 0.0;
         let mut content_size = 0.0;
+        let mut measured_children = Vec::new();
         match &item_size {
+            _ if pin_fn.is_some() => {
+                // Pinned items are taken out of the flow and shown over the edges of the
+                // viewport, which needs every item to find them.
+                let pin_fn = pin_fn.as_ref().unwrap();
+                let fixed_size = match &item_size {
+                    VirtualItemSize::Fixed(item_size) => Some(item_size()),
+                    VirtualItemSize::Fn(_) | VirtualItemSize::Measured(_) => None,
+                };
+                let size_of = |item: &T| match &item_size {
+                    VirtualItemSize::Fixed(_) => fixed_size.unwrap_or_default(),
+                    VirtualItemSize::Fn(size_fn) => size_fn(item),
+                    VirtualItemSize::Measured(estimate) => {
+                        measured_size(measured, key_hash(&key_fn(item)))
+                            .unwrap_or_else(|| estimate(item))
+                    }
+                };
+                let total_len = items_vector.total_len();
+                let mut flow = Vec::new();
+                let mut start_size = 0.0;
+                let mut end_size = 0.0;
+                for item in items_vector.slice(0..total_len) {
+                    let item_size = size_of(&item);
+                    match pin_fn(&item) {
+                        Some(VirtualPin::Start) => {
+                            start_size += item_size;
+                            pinned_start.push(item);
+                        }
+                        Some(VirtualPin::End) => {
+                            end_size += item_size;
+                            pinned_end.push(item);
+                        }
+                        None => flow.push((item, item_size)),
+                    }
+                }
+
+                // The flow starts below the items pinned to the start, so none are hidden
+                let mut main_axis = start_size;
+                before_size = start_size;
+                content_size = start_size + end_size;
+                for (item, item_size) in flow {
+                    content_size += item_size;
+                    if main_axis + item_size < min {
+                        main_axis += item_size;
+                        before_size += item_size;
+                        continue;
+                    }
+
+                    if main_axis <= max {
+                        main_axis += item_size;
+                        items.push(item);
+                    }
+                }
+            }
             VirtualItemSize::Fixed(item_size) => {
                 let item_size = item_size();
                 let total_len = items_vector.total_len();
@@ -146,40 +285,86 @@ where
                     }
                 }
             }
+            VirtualItemSize::Measured(estimate) => {
+                let mut main_axis = 0.0;
+                let total_len = items_vector.total_len();
+                for item in items_vector.slice(0..total_len) {
+                    let hash = key_hash(&key_fn(&item));
+                    let item_size =
+                        measured_size(measured, hash).unwrap_or_else(|| estimate(&item));
+                    content_size += item_size;
+                    if main_axis + item_size < min {
+                        main_axis += item_size;
+                        before_size += item_size;
+                        continue;
+                    }
+
+                    if main_axis <= max {
+                        main_axis += item_size;
+                        items.push(item);
+                        measured_children.push((hash, item_size));
+                    }
+                }
+            }
         };
 
         let hashed_items = items.iter().map(&key_fn).collect::<FxIndexSet<_>>();
-        let (prev_before_size, prev_content_size, diff) =
-            if let Some((prev_before_size, prev_content_size, HashRun(prev_hash_run))) = prev {
-                let mut diff = diff(&prev_hash_run, &hashed_items);
-                let mut items = items
-                    .into_iter()
-                    .map(|i| Some(i))
-                    .collect::<SmallVec<[Option<_>; 128]>>();
-                for added in &mut diff.added {
-                    added.view = Some(items[added.at].take().unwrap());
-                }
-                (prev_before_size, prev_content_size, diff)
+        let pinned_start_len = pinned_start.len();
+        pinned_start.append(&mut pinned_end);
+        let hashed_pinned = pinned_start.iter().map(&key_fn).collect::<FxIndexSet<_>>();
+        let (prev_before_size, prev_content_size, prev_start_len, prev_sizes, diff, pinned_diff) =
+            if let Some((
+                prev_before_size,
+                prev_content_size,
+                prev_start_len,
+                prev_sizes,
+                HashRun(prev_hash_run),
+                HashRun(prev_pinned),
+            )) = prev
+            {
+                (
+                    prev_before_size,
+                    prev_content_size,
+                    prev_start_len,
+                    prev_sizes,
+                    items_diff(Some(&prev_hash_run), &hashed_items, items),
+                    items_diff(Some(&prev_pinned), &hashed_pinned, pinned_start),
+                )
             } else {
-                let mut diff = Diff::default();
-                for (i, item) in items.into_iter().enumerate() {
-                    diff.added.push(DiffOpAdd {
-                        at: i,
-                        view: Some(item),
-                    });
-                }
-                (0.0, 0.0, diff)
+                (
+                    0.0,
+                    0.0,
+                    0,
+                    Vec::new(),
+                    items_diff(None, &hashed_items, items),
+                    items_diff(None, &hashed_pinned, pinned_start),
+                )
             };
 
-        if !diff.is_empty() || prev_before_size != before_size || prev_content_size != content_size
+        if !diff.is_empty()
+            || !pinned_diff.is_empty()
+            || prev_before_size != before_size
+            || prev_content_size != content_size
+            || prev_start_len != pinned_start_len
+            || prev_sizes != measured_children
         {
             id.update_state(VirtualStackState {
                 diff,
+                pinned_diff,
+                pinned_start_len,
                 before_size,
                 content_size,
+                measured_children: measured_children.clone(),
             });
         }
-        (before_size, content_size, HashRun(hashed_items))
+        (
+            before_size,
+            content_size,
+            pinned_start_len,
+            measured_children,
+            HashRun(hashed_items),
+            HashRun(hashed_pinned),
+        )
     });
 
     let view_fn = Box::new(as_child_of_current_scope(view_fn));
@@ -196,6 +381,74 @@ where
         content_size: 0.0,
         offset_node: None,
         content_node: None,
+        pinned,
+        pinned_children: Vec::new(),
+        pinned_start_len: 0,
+        pinned_nodes: None,
+        measured,
+        measured_children: Vec::new(),
+    }
+}
+
+fn measured_size(measured: Option<RwSignal<HashMap<u64, f64>>>, hash: u64) -> Option<f64> {
+    measured?.with_untracked(|measured| measured.get(&hash).copied())
+}
+
+impl<V: View, T: 'static> VirtualStack<V, T> {
+    /// Pins the items for which `pin` returns an edge, showing them at that edge of the
+    /// viewport wherever the stack is scrolled.
+    ///
+    /// Pinned items are taken out of the scrolling items and are created and removed with the
+    /// data like the other items, so rows such as the active download or the current track
+    /// stay visible. `pin` is called for every item when the items or the viewport change.
+    pub fn pinned(self, pin: impl Fn(&T) -> Option<VirtualPin> + 'static) -> Self {
+        self.pinned.set(Some(Rc::new(pin)));
+        self
+    }
+
+    /// Caches the laid out sizes of the children of a [`VirtualItemSize::Measured`] stack,
+    /// scrolling to make up for the ones which changed size before the viewport.
+    fn measure_children(&mut self, cx: &ComputeLayoutCx<'_>) {
+        let Some(measured) = self.measured else {
+            return;
+        };
+        let viewport_start = match self.direction {
+            VirtualDirection::Vertical => self.viewport.y0,
+            VirtualDirection::Horizontal => self.viewport.x0,
+        };
+        let mut changed = Vec::new();
+        let mut correction = 0.0;
+        let children = self.children.iter().filter_map(|child| child.as_ref());
+        for ((child, _), (hash, size)) in children.zip(self.measured_children.iter_mut()) {
+            let Some(layout) = cx.app_state().get_layout(child.id()) else {
+                continue;
+            };
+            let (measured_size, position) = match self.direction {
+                VirtualDirection::Vertical => (layout.size.height, layout.location.y),
+                VirtualDirection::Horizontal => (layout.size.width, layout.location.x),
+            };
+            let measured_size = measured_size as f64;
+            if (measured_size - *size).abs() < 0.5 {
+                continue;
+            }
+            if (position as f64) < viewport_start {
+                correction += measured_size - *size;
+            }
+            // Not measured again until the new size reaches the stack
+            *size = measured_size;
+            changed.push((*hash, measured_size));
+        }
+        if changed.is_empty() {
+            return;
+        }
+        measured.update(|measured| measured.extend(changed));
+        if correction != 0.0 {
+            let delta = match self.direction {
+                VirtualDirection::Vertical => Vec2::new(0.0, correction),
+                VirtualDirection::Horizontal => Vec2::new(correction, 0.0),
+            };
+            scroll_ancestor_by(self.id(), delta);
+        }
     }
 }
 
@@ -209,7 +462,13 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
     }
 
     fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
-        for child in self.children.iter().filter_map(|child| child.as_ref()) {
+        // Pinned items come last so they're painted over the scrolling items
+        for child in self
+            .children
+            .iter()
+            .chain(self.pinned_children.iter())
+            .filter_map(|child| child.as_ref())
+        {
             if for_each(&child.0) {
                 break;
             }
@@ -217,7 +476,12 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
     }
 
     fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
-        for child in self.children.iter_mut().filter_map(|child| child.as_mut()) {
+        for child in self
+            .children
+            .iter_mut()
+            .chain(self.pinned_children.iter_mut())
+            .filter_map(|child| child.as_mut())
+        {
             if for_each(&mut child.0) {
                 break;
             }
@@ -229,9 +493,10 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
         for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
     ) {
         for child in self
-            .children
+            .pinned_children
             .iter_mut()
             .rev()
+            .chain(self.children.iter_mut().rev())
             .filter_map(|child| child.as_mut())
         {
             if for_each(&mut child.0) {
@@ -246,14 +511,18 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
 
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
         if let Ok(state) = state.downcast::<VirtualStackState<T>>() {
+            self.measured_children = state.measured_children;
             if self.before_size == state.before_size
                 && self.content_size == state.content_size
+                && self.pinned_start_len == state.pinned_start_len
                 && state.diff.is_empty()
+                && state.pinned_diff.is_empty()
             {
                 return;
             }
             self.before_size = state.before_size;
             self.content_size = state.content_size;
+            self.pinned_start_len = state.pinned_start_len;
             apply_diff(
                 self.id(),
                 cx.app_state,
@@ -261,6 +530,13 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
                 &mut self.children,
                 &self.view_fn,
             );
+            apply_diff(
+                self.id(),
+                cx.app_state,
+                state.pinned_diff,
+                &mut self.pinned_children,
+                &self.view_fn,
+            );
             cx.request_all(self.id());
         }
     }
@@ -272,6 +548,17 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
                 .iter_mut()
                 .filter_map(|child| Some(cx.layout_view(&mut child.as_mut()?.0)))
                 .collect::<Vec<_>>();
+            let mut pinned_nodes = self
+                .pinned_children
+                .iter_mut()
+                .map(|child| child.as_mut().map(|child| cx.layout_view(&mut child.0)))
+                .collect::<Vec<_>>();
+            let pinned_end_nodes = pinned_nodes
+                .split_off(self.pinned_start_len.min(pinned_nodes.len()))
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let pinned_start_nodes = pinned_nodes.into_iter().flatten().collect::<Vec<_>>();
             let content_size = match self.direction {
                 VirtualDirection::Vertical => taffy::prelude::Size {
                     width: Dimension::Percent(1.0),
@@ -322,9 +609,21 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
                         VirtualDirection::Vertical => FlexDirection::Column,
                         VirtualDirection::Horizontal => FlexDirection::Row,
                     },
-                    size: taffy::prelude::Size {
-                        width: Dimension::Percent(1.0),
-                        height: Dimension::Percent(1.0),
+                    // Measured items take the size of their content instead of shrinking to
+                    // fit the estimated size
+                    size: match (self.direction, self.measured.is_some()) {
+                        (VirtualDirection::Vertical, true) => taffy::prelude::Size {
+                            width: Dimension::Percent(1.0),
+                            height: Dimension::Auto,
+                        },
+                        (VirtualDirection::Horizontal, true) => taffy::prelude::Size {
+                            width: Dimension::Auto,
+                            height: Dimension::Percent(1.0),
+                        },
+                        (_, false) => taffy::prelude::Size {
+                            width: Dimension::Percent(1.0),
+                            height: Dimension::Percent(1.0),
+                        },
                     },
                     ..Default::default()
                 },
@@ -338,10 +637,69 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
                 },
             );
             let _ = cx.app_state_mut().taffy.set_children(offset_node, &nodes);
-            let _ = cx
-                .app_state_mut()
-                .taffy
-                .set_children(content_node, &[offset_node]);
+
+            if pinned_start_nodes.is_empty() && pinned_end_nodes.is_empty() {
+                let _ = cx
+                    .app_state_mut()
+                    .taffy
+                    .set_children(content_node, &[offset_node]);
+                return vec![content_node];
+            }
+
+            if self.pinned_nodes.is_none() {
+                let taffy = &mut cx.app_state_mut().taffy;
+                self.pinned_nodes = Some((
+                    taffy.new_leaf(taffy::style::Style::DEFAULT).unwrap(),
+                    taffy.new_leaf(taffy::style::Style::DEFAULT).unwrap(),
+                ));
+            }
+            let (start_node, end_node) = self.pinned_nodes.unwrap();
+            // Keep the pinned items at the edges of the viewport
+            let (viewport_start, viewport_end) = match self.direction {
+                VirtualDirection::Vertical => (self.viewport.y0, self.viewport.y1),
+                VirtualDirection::Horizontal => (self.viewport.x0, self.viewport.x1),
+            };
+            let end_inset = (self.content_size - viewport_end).max(0.0);
+            for (node, inset, at_start) in [
+                (start_node, viewport_start, true),
+                (end_node, end_inset, false),
+            ] {
+                let inset = LengthPercentageAuto::Points(inset as f32);
+                let (start, end) = if at_start {
+                    (inset, LengthPercentageAuto::Auto)
+                } else {
+                    (LengthPercentageAuto::Auto, inset)
+                };
+                let _ = cx.app_state_mut().taffy.set_style(
+                    node,
+                    taffy::style::Style {
+                        position: taffy::style::Position::Absolute,
+                        inset: match self.direction {
+                            VirtualDirection::Vertical => taffy::prelude::Rect {
+                                left: LengthPercentageAuto::Points(0.0),
+                                top: start,
+                                right: LengthPercentageAuto::Points(0.0),
+                                bottom: end,
+                            },
+                            VirtualDirection::Horizontal => taffy::prelude::Rect {
+                                left: start,
+                                top: LengthPercentageAuto::Points(0.0),
+                                right: end,
+                                bottom: LengthPercentageAuto::Points(0.0),
+                            },
+                        },
+                        flex_direction: match self.direction {
+                            VirtualDirection::Vertical => FlexDirection::Column,
+                            VirtualDirection::Horizontal => FlexDirection::Row,
+                        },
+                        ..Default::default()
+                    },
+                );
+            }
+            let taffy = &mut cx.app_state_mut().taffy;
+            let _ = taffy.set_children(start_node, &pinned_start_nodes);
+            let _ = taffy.set_children(end_node, &pinned_end_nodes);
+            let _ = taffy.set_children(content_node, &[offset_node, start_node, end_node]);
             vec![content_node]
         })
     }
@@ -354,9 +712,15 @@ impl<V: View + 'static, T> View for VirtualStack<V, T> {
 
             self.viewport = viewport;
             self.set_viewport.set(viewport);
+            if self.pinned_children.iter().any(|child| child.is_some()) {
+                // Move the pinned items along with the viewport
+                cx.app_state_mut().request_layout(self.id());
+            }
         }
 
-        view::default_compute_layout(self, cx)
+        let rect = view::default_compute_layout(self, cx);
+        self.measure_children(cx);
+        rect
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
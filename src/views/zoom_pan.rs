@@ -0,0 +1,239 @@
+use std::ops::RangeInclusive;
+
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Rect, Size, Vec2};
+
+use crate::{
+    context::{ComputeLayoutCx, EventCx, PaintCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    style::Style,
+    view::{self, View, ViewData},
+};
+
+/// How much the zoom changes for each pixel of Ctrl+wheel scrolling.
+const ZOOM_PER_PIXEL: f64 = 0.002;
+
+enum ZoomPanUpdate {
+    FitToView,
+}
+
+/// A container which zooms and pans its child. See [`zoom_pan`]
+pub struct ZoomPan {
+    data: ViewData,
+    child: Box<dyn View>,
+    zoom: RwSignal<f64>,
+    pan: RwSignal<Vec2>,
+    zoom_range: RangeInclusive<f64>,
+    /// Where a pan by dragging started, and the pan then.
+    drag_start: Option<(Point, Vec2)>,
+    fit_requested: bool,
+    size: Size,
+}
+
+/// Shows `child` zoomed and panned, for node editors, maps, image viewers and the like.
+///
+/// The child is laid out at its own size, then scaled by [`ZoomPan::zoom`] and moved by
+/// [`ZoomPan::pan`], and clipped to the container. Ctrl+wheel and touchpad pinches zoom about
+/// the pointer, and the wheel alone pans. Dragging with the middle button, or with the primary
+/// button where the child doesn't handle the press, pans too. Events reach the child at
+/// positions in its own, unzoomed, coordinates.
+///
+/// ```ignore
+/// let view = zoom_pan(img(move || image.clone())).zoom_range(0.1..=8.0);
+/// let zoom = view.zoom();
+/// v_stack((
+///     label(move || format!("{:.0}%", zoom.get() * 100.0)),
+///     view.style(|s| s.size_full()),
+/// ))
+/// ```
+pub fn zoom_pan<V: View + 'static>(child: V) -> ZoomPan {
+    let id = Id::next();
+    let zoom = create_rw_signal(1.0);
+    let pan = create_rw_signal(Vec2::ZERO);
+    create_effect(move |_| {
+        zoom.track();
+        pan.track();
+        id.request_paint();
+    });
+    ZoomPan {
+        data: ViewData::new(id),
+        child: Box::new(child),
+        zoom,
+        pan,
+        zoom_range: 0.1..=10.0,
+        drag_start: None,
+        fit_requested: false,
+        size: Size::ZERO,
+    }
+}
+
+impl ZoomPan {
+    /// The zoom level, where 1.0 shows the child at its actual size.
+    pub fn zoom(&self) -> RwSignal<f64> {
+        self.zoom
+    }
+
+    /// Where the origin of the child is shown in the container.
+    pub fn pan(&self) -> RwSignal<Vec2> {
+        self.pan
+    }
+
+    /// Limits how far the pointer and touchpad zoom. Defaults to `0.1..=10.0`.
+    pub fn zoom_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.zoom_range = range;
+        self
+    }
+
+    /// Zooms and pans to show the whole child centered in the container, once it's laid out.
+    pub fn fit_to_view(&self) {
+        self.id().update_state(ZoomPanUpdate::FitToView);
+    }
+
+    /// The laid out rectangle of the child, in its parent's unzoomed coordinates.
+    fn child_rect(&self, cx: &ComputeLayoutCx) -> Option<Rect> {
+        let layout = cx.app_state().get_layout(self.child.id())?;
+        Some(Rect::from_origin_size(
+            (layout.location.x as f64, layout.location.y as f64),
+            (layout.size.width as f64, layout.size.height as f64),
+        ))
+    }
+
+    fn zoom_at(&self, pos: Point, factor: f64) {
+        let zoom = self.zoom.get_untracked();
+        let new_zoom = (zoom * factor).clamp(*self.zoom_range.start(), *self.zoom_range.end());
+        if new_zoom == zoom {
+            return;
+        }
+        // Keep the point under the pointer in place
+        let pan = self.pan.get_untracked();
+        let new_pan = pos.to_vec2() - (pos.to_vec2() - pan) * (new_zoom / zoom);
+        self.zoom.set(new_zoom);
+        self.pan.set(new_pan);
+    }
+}
+
+impl View for ZoomPan {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "ZoomPan".into()
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().items_start())
+    }
+
+    fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<ZoomPanUpdate>() {
+            match *state {
+                ZoomPanUpdate::FitToView => {
+                    self.fit_requested = true;
+                    cx.request_layout(self.id());
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        if let Some(layout) = cx.app_state().get_layout(self.id()) {
+            self.size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        }
+        if self.fit_requested {
+            if let Some(child) = self.child_rect(cx).filter(|rect| rect.area() > 0.0) {
+                self.fit_requested = false;
+                let zoom = (self.size.width / child.width())
+                    .min(self.size.height / child.height())
+                    .clamp(*self.zoom_range.start(), *self.zoom_range.end());
+                let shown = child.size() * zoom;
+                let pan = Vec2::new(
+                    (self.size.width - shown.width) / 2.0,
+                    (self.size.height - shown.height) / 2.0,
+                ) - child.origin().to_vec2() * zoom;
+                self.zoom.set(zoom);
+                self.pan.set(pan);
+            }
+        }
+        view::default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let zoom = self.zoom.get_untracked();
+        let pan = self.pan.get_untracked();
+        let child_event = event.clone().offset((pan.x, pan.y)).scale(zoom);
+        if self.drag_start.is_none()
+            && cx
+                .view_event(&mut self.child, id_path, child_event)
+                .is_processed()
+        {
+            return EventPropagation::Stop;
+        }
+
+        match &event {
+            Event::PointerWheel(wheel) => {
+                if wheel.modifiers.control_key() {
+                    self.zoom_at(wheel.pos, (-wheel.delta.y * ZOOM_PER_PIXEL).exp());
+                } else {
+                    self.pan.set(pan - wheel.delta);
+                }
+                EventPropagation::Stop
+            }
+            Event::PointerDown(pointer)
+                if pointer.button.is_primary() || pointer.button.is_auxiliary() =>
+            {
+                self.drag_start = Some((pointer.pos, pan));
+                cx.update_active(self.id());
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer) => match self.drag_start {
+                Some((start, start_pan)) => {
+                    self.pan.set(start_pan + (pointer.pos - start));
+                    EventPropagation::Stop
+                }
+                None => EventPropagation::Continue,
+            },
+            Event::PointerUp(_) if self.drag_start.is_some() => {
+                self.drag_start = None;
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let zoom = self.zoom.get_untracked();
+        let pan = self.pan.get_untracked();
+        cx.save();
+        cx.clip(&self.size.to_rect());
+        cx.offset((pan.x, pan.y));
+        cx.scale(zoom);
+        cx.paint_view(&mut self.child);
+        cx.restore();
+    }
+}
@@ -1,3 +1,5 @@
+use accesskit::Role;
+
 use crate::{
     style_class,
     view::View,
@@ -11,4 +13,5 @@ pub fn button<S: Display + 'static>(label: impl Fn() -> S + 'static) -> impl Vie
     container(views::label(label))
         .keyboard_navigatable()
         .class(ButtonClass)
+        .accessible(|cx| cx.set_role(Role::Button))
 }
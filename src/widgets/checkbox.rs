@@ -1,3 +1,5 @@
+use accesskit::Role;
+
 use crate::{
     style_class,
     view::View,
@@ -6,16 +8,17 @@ use crate::{
         ValueContainer,
     },
 };
-use floem_reactive::ReadSignal;
 use std::fmt::Display;
 
 style_class!(pub CheckboxClass);
 
 style_class!(pub LabeledCheckboxClass);
 
-fn checkbox_svg(checked: ReadSignal<bool>) -> impl View {
-    const CHECKBOX_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-2 -2 16 16"><polygon points="5.19,11.83 0.18,7.44 1.82,5.56 4.81,8.17 10,1.25 12,2.75" /></svg>"#;
-    let svg_str = move || if checked.get() { CHECKBOX_SVG } else { "" }.to_string();
+const CHECKBOX_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-2 -2 16 16"><polygon points="5.19,11.83 0.18,7.44 1.82,5.56 4.81,8.17 10,1.25 12,2.75" /></svg>"#;
+const MIXED_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-2 -2 16 16"><rect x="1" y="5" width="10" height="2" /></svg>"#;
+
+pub(crate) fn checkbox_svg(checked: impl Fn() -> bool + 'static) -> impl View {
+    let svg_str = move || if checked() { CHECKBOX_SVG } else { "" }.to_string();
     svg(svg_str).class(CheckboxClass)
 }
 
@@ -24,11 +27,15 @@ pub fn checkbox(checked: impl Fn() -> bool + 'static) -> ValueContainer<bool> {
     let (inbound_signal, outbound_signal) = create_value_container_signals(checked);
 
     value_container(
-        checkbox_svg(inbound_signal.read_only())
+        checkbox_svg(move || inbound_signal.get())
             .keyboard_navigatable()
             .on_click_stop(move |_| {
                 let checked = inbound_signal.get_untracked();
                 outbound_signal.set(!checked);
+            })
+            .accessible(move |cx| {
+                cx.set_role(Role::CheckBox);
+                cx.set_checked(Some(inbound_signal.get_untracked()));
             }),
         move || outbound_signal.get(),
     )
@@ -43,7 +50,7 @@ pub fn labeled_checkbox<S: Display + 'static>(
 
     value_container(
         h_stack((
-            checkbox_svg(inbound_signal.read_only()),
+            checkbox_svg(move || inbound_signal.get()),
             views::label(label),
         ))
         .class(LabeledCheckboxClass)
@@ -52,7 +59,44 @@ pub fn labeled_checkbox<S: Display + 'static>(
             let checked = inbound_signal.get_untracked();
             outbound_signal.set(!checked);
         })
+        .accessible(move |cx| {
+            cx.set_role(Role::CheckBox);
+            cx.set_checked(Some(inbound_signal.get_untracked()));
+        })
         .style(|s| s.items_center().justify_center()),
         move || outbound_signal.get(),
     )
 }
+
+/// Renders a checkbox which is checked for `Some(true)`, unchecked for `Some(false)` and shows
+/// a dash for `None`, the mixed state of a checkbox controlling several others.
+///
+/// Clicking an unchecked or mixed checkbox checks it.
+pub fn tri_state_checkbox(
+    state: impl Fn() -> Option<bool> + 'static,
+) -> ValueContainer<Option<bool>> {
+    let (inbound_signal, outbound_signal) = create_value_container_signals(state);
+    let svg_str = move || {
+        match inbound_signal.get() {
+            Some(true) => CHECKBOX_SVG,
+            Some(false) => "",
+            None => MIXED_SVG,
+        }
+        .to_string()
+    };
+
+    value_container(
+        svg(svg_str)
+            .class(CheckboxClass)
+            .keyboard_navigatable()
+            .on_click_stop(move |_| {
+                let checked = inbound_signal.get_untracked() == Some(true);
+                outbound_signal.set(Some(!checked));
+            })
+            .accessible(move |cx| {
+                cx.set_role(Role::CheckBox);
+                cx.set_checked(inbound_signal.get_untracked());
+            }),
+        move || outbound_signal.get(),
+    )
+}
@@ -0,0 +1,387 @@
+use std::{cmp::Reverse, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::Point;
+use taffy::style::AlignItems;
+
+use crate::{
+    action::{add_overlay_with_config, remove_overlay, OverlayConfig},
+    context::{AppState, UpdateCx},
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    shortcut::{active_shortcuts, register_shortcut, Shortcut, ShortcutHandle},
+    style_class,
+    view::{View, ViewData},
+    views::{dyn_stack, empty, h_stack, label, scroll, v_stack, Decorators},
+    widgets::text_input,
+};
+
+style_class!(pub CommandPaletteClass);
+style_class!(pub CommandPaletteItemClass);
+
+/// The number of recently run commands remembered.
+const RECENT_LIMIT: usize = 16;
+
+#[derive(Clone)]
+struct Command {
+    name: String,
+    shortcut: Option<Shortcut>,
+    action: Rc<dyn Fn()>,
+}
+
+enum PaletteUpdate {
+    Show(bool),
+}
+
+/// Scores how well `query` matches `text`, or returns `None` unless the characters of the
+/// query appear in `text` in order, ignoring case and spaces. Characters following each
+/// other or starting a word score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+    let mut score = 0;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        loop {
+            let (index, c) = text_chars.next()?;
+            let word_start = match prev {
+                None => true,
+                Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && c.is_uppercase()),
+            };
+            prev = Some(c);
+            if c.to_lowercase().eq(query_char.to_lowercase()) {
+                score += 1;
+                if word_start {
+                    score += 3;
+                }
+                if last_match.map_or(false, |last| text[last..index].chars().count() == 1) {
+                    score += 5;
+                }
+                last_match = Some(index);
+                break;
+            }
+        }
+    }
+    Some(score)
+}
+
+/// The commands matching `query`, best match first. Recently run commands come first among
+/// the commands matching as well, then the commands are sorted by name.
+fn rank(commands: &[Command], query: &str, recent: &[String]) -> Vec<Command> {
+    let mut matches: Vec<_> = commands
+        .iter()
+        .filter_map(|command| {
+            let score = fuzzy_score(query, &command.name)?;
+            let recent = recent.iter().position(|name| *name == command.name);
+            Some(((Reverse(score), recent.unwrap_or(usize::MAX)), command))
+        })
+        .collect();
+    matches.sort_by(|(a_key, a), (b_key, b)| a_key.cmp(b_key).then_with(|| a.name.cmp(&b.name)));
+    matches
+        .into_iter()
+        .map(|(_, command)| command.clone())
+        .collect()
+}
+
+/// Closes the palette and runs `command`, remembering it as the most recent command.
+fn run(command: &Command, opened: RwSignal<bool>, recent: RwSignal<Vec<String>>) {
+    recent.update(|recent| {
+        recent.retain(|name| *name != command.name);
+        recent.insert(0, command.name.clone());
+        recent.truncate(RECENT_LIMIT);
+    });
+    opened.set(false);
+    (command.action)();
+}
+
+/// A searchable list of commands shown above the window. See [`command_palette`]
+pub struct CommandPalette {
+    data: ViewData,
+    opened: RwSignal<bool>,
+    recent: RwSignal<Vec<String>>,
+    commands: Vec<Command>,
+    shortcut: Option<ShortcutHandle>,
+    overlay: Option<Id>,
+    /// The view that had focus when the palette opened, to return focus to when it closes.
+    prev_focus: Option<Id>,
+}
+
+/// A command palette like the one of VS Code, opened with `Cmd+Shift+P` (`Ctrl+Shift+P` outside
+/// of macOS).
+///
+/// The palette lists the [named](ShortcutHandle::name) shortcuts which apply to the focused
+/// view with their keys, and the commands added with [`CommandPalette::command`]. Typing
+/// filters them with a fuzzy search, the arrow keys move the selection, `Enter` runs the
+/// selected command and `Escape` closes the palette. The commands run recently are listed
+/// first.
+///
+/// The palette view itself takes no space, so it can be placed anywhere in the window. The
+/// list is shown in an overlay with the [`CommandPaletteClass`] class.
+///
+/// ## Example
+/// ```ignore
+/// register_shortcut("Cmd+S", save).unwrap().name("File: Save");
+/// v_stack((
+///     editor(),
+///     command_palette().command("View: Toggle Sidebar", toggle_sidebar),
+/// ))
+/// ```
+pub fn command_palette() -> CommandPalette {
+    let id = Id::next();
+    let opened = create_rw_signal(false);
+    create_effect(move |_| {
+        id.update_state(PaletteUpdate::Show(opened.get()));
+    });
+    CommandPalette {
+        data: ViewData::new(id),
+        opened,
+        recent: create_rw_signal(Vec::new()),
+        commands: Vec::new(),
+        shortcut: None,
+        overlay: None,
+        prev_focus: None,
+    }
+    .shortcut("Cmd+Shift+P")
+}
+
+impl CommandPalette {
+    /// Opens the palette with `shortcut` instead of `Cmd+Shift+P`. The shortcut isn't bound if
+    /// it conflicts with another shortcut of the window.
+    pub fn shortcut(mut self, shortcut: &str) -> Self {
+        if let Some(handle) = self.shortcut.take() {
+            handle.unregister();
+        }
+        let opened = self.opened;
+        self.shortcut = register_shortcut(shortcut, move || opened.set(true)).ok();
+        self
+    }
+
+    /// Lists a command without a shortcut in the palette.
+    pub fn command(mut self, name: impl Into<String>, action: impl Fn() + 'static) -> Self {
+        self.commands.push(Command {
+            name: name.into(),
+            shortcut: None,
+            action: Rc::new(action),
+        });
+        self
+    }
+
+    /// Whether the palette is shown. Setting it opens or closes the palette.
+    pub fn opened(&self) -> RwSignal<bool> {
+        self.opened
+    }
+
+    /// The names of the commands run from the palette, most recent first. It can be set to
+    /// restore the recent commands of a previous session.
+    pub fn recent(&self) -> RwSignal<Vec<String>> {
+        self.recent
+    }
+
+    fn open(&mut self, app_state: &AppState) {
+        if self.overlay.is_some() {
+            return;
+        }
+        self.prev_focus = app_state.focus;
+        let mut commands: Vec<Command> = active_shortcuts(self.prev_focus)
+            .into_iter()
+            .filter_map(|info| {
+                let handle = info.handle;
+                Some(Command {
+                    name: info.name?,
+                    shortcut: Some(info.shortcut),
+                    action: Rc::new(move || handle.run()),
+                })
+            })
+            .collect();
+        commands.extend(self.commands.iter().cloned());
+
+        let opened = self.opened;
+        let recent = self.recent;
+        let config = OverlayConfig::default()
+            .backdrop(Color::BLACK.with_alpha_factor(0.2))
+            .dismiss_on_outside_click(true)
+            .on_dismiss(move || opened.set(false));
+        self.overlay = Some(add_overlay_with_config(Point::ZERO, config, move |_| {
+            palette_view(commands, opened, recent)
+        }));
+    }
+
+    fn close(&mut self) {
+        let Some(overlay) = self.overlay.take() else {
+            return;
+        };
+        remove_overlay(overlay);
+        if let Some(prev_focus) = self.prev_focus.take() {
+            prev_focus.request_focus();
+        }
+    }
+}
+
+fn palette_view(
+    commands: Vec<Command>,
+    opened: RwSignal<bool>,
+    recent: RwSignal<Vec<String>>,
+) -> impl View {
+    let query = create_rw_signal(String::new());
+    let matches = create_rw_signal(Vec::new());
+    let selected = create_rw_signal(0);
+    create_effect(move |_| {
+        let list = query.with(|query| recent.with(|recent| rank(&commands, query, recent)));
+        selected.set(0);
+        matches.set(list);
+    });
+
+    let input = text_input(query)
+        .placeholder("Type a command")
+        .on_event(EventListener::KeyDown, move |event| {
+            let Event::KeyDown(key_event) = event else {
+                return EventPropagation::Continue;
+            };
+            let count = matches.with_untracked(|matches| matches.len());
+            match key_event.key.logical_key {
+                Key::Named(NamedKey::ArrowDown) if count > 0 => {
+                    selected.update(|selected| *selected = (*selected + 1) % count);
+                }
+                Key::Named(NamedKey::ArrowUp) if count > 0 => {
+                    selected.update(|selected| *selected = (*selected + count - 1) % count);
+                }
+                Key::Named(NamedKey::Enter) => {
+                    let index = selected.get_untracked();
+                    let command = matches.with_untracked(|matches| matches.get(index).cloned());
+                    if let Some(command) = command {
+                        run(&command, opened, recent);
+                    }
+                }
+                Key::Named(NamedKey::Escape) => opened.set(false),
+                _ => return EventPropagation::Continue,
+            }
+            EventPropagation::Stop
+        })
+        .style(|s| s.width_full());
+    input.id().request_focus();
+
+    let list = scroll(
+        dyn_stack(
+            move || matches.get().into_iter().enumerate(),
+            |(index, command)| (*index, command.name.clone()),
+            move |(index, command)| {
+                let name = command.name.clone();
+                let keys = command
+                    .shortcut
+                    .as_ref()
+                    .map(|shortcut| shortcut.to_string())
+                    .unwrap_or_default();
+                let item = h_stack((
+                    label(move || name.clone()),
+                    empty().style(|s| s.flex_grow(1.0)),
+                    label(move || keys.clone()).style(|s| s.color(Color::GRAY)),
+                ))
+                .class(CommandPaletteItemClass)
+                .on_click_stop(move |_| run(&command, opened, recent))
+                .style(move |s| {
+                    s.items_center()
+                        .gap(16.0, 0.0)
+                        .apply_if(selected.get() == index, |s| {
+                            s.background(Color::rgba8(0, 0, 0, 30))
+                        })
+                });
+                let item_id = item.id();
+                create_effect(move |_| {
+                    if selected.get() == index {
+                        item_id.scroll_to(None);
+                    }
+                });
+                item
+            },
+        )
+        .style(|s| s.flex_col().width_full()),
+    )
+    .style(|s| s.max_height(320.0).width_full());
+
+    let no_matches = label(|| "No matching commands").style(move |s| {
+        s.padding(8.0)
+            .color(Color::GRAY)
+            .apply_if(matches.with(|matches| !matches.is_empty()), |s| s.hide())
+    });
+
+    v_stack((input, list, no_matches))
+        .class(CommandPaletteClass)
+        .style(|s| {
+            s.width(500.0)
+                .max_width_pct(90.0)
+                .margin_top(60.0)
+                .align_self(AlignItems::Center)
+                .gap(0.0, 4.0)
+        })
+}
+
+impl View for CommandPalette {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "CommandPalette".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<PaletteUpdate>() {
+            match *state {
+                PaletteUpdate::Show(true) => self.open(cx.app_state),
+                PaletteUpdate::Show(false) => self.close(),
+            }
+        }
+    }
+}
+
+impl Drop for CommandPalette {
+    fn drop(&mut self) {
+        self.close();
+        if let Some(handle) = self.shortcut.take() {
+            handle.unregister();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{fuzzy_score, rank, Command};
+
+    #[test]
+    fn fuzzy_ranking() {
+        assert_eq!(fuzzy_score("", "File: Save"), Some(0));
+        assert_eq!(fuzzy_score("fsa", "File: Save"), Some(4 + 4 + 1 + 5));
+        assert_eq!(fuzzy_score("sf", "File: Save"), None);
+        assert!(fuzzy_score("save", "File: Save") > fuzzy_score("save", "Select All Voices"));
+
+        let commands: Vec<Command> = ["File: Save", "File: Save As", "View: Sort Lines"]
+            .into_iter()
+            .map(|name| Command {
+                name: name.to_string(),
+                shortcut: None,
+                action: Rc::new(|| {}),
+            })
+            .collect();
+        let names = |query: &str, recent: &[String]| {
+            let ranked = rank(&commands, query, recent);
+            ranked
+                .into_iter()
+                .map(|command| command.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names("", &["View: Sort Lines".to_string()]),
+            ["View: Sort Lines", "File: Save", "File: Save As"]
+        );
+        assert_eq!(names("save as", &[]), ["File: Save As"]);
+        assert_eq!(names("sl", &[]), ["View: Sort Lines"]);
+    }
+}
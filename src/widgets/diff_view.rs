@@ -0,0 +1,392 @@
+use std::{rc::Rc, time::Duration};
+
+use floem_peniko::Color;
+use floem_reactive::{create_memo, create_rw_signal, Memo, RwSignal};
+use kurbo::Point;
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+use crate::{
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    intern::intern,
+    keyboard::{Key, NamedKey},
+    style_class,
+    view::{View, ViewData},
+    views::{
+        h_stack, h_stack_from_iter, label, scroll, virtual_stack, Decorators, Scroll,
+        VirtualDirection, VirtualItemSize,
+    },
+};
+
+style_class!(pub DiffViewClass);
+style_class!(pub DiffLineClass);
+
+const ROW_HEIGHT: f64 = 18.0;
+const GUTTER_WIDTH: f64 = 44.0;
+const DELETE_COLOR: Color = Color::rgba8(255, 0, 0, 28);
+const DELETE_WORD_COLOR: Color = Color::rgba8(255, 0, 0, 80);
+const INSERT_COLOR: Color = Color::rgba8(0, 180, 0, 28);
+const INSERT_WORD_COLOR: Color = Color::rgba8(0, 180, 0, 80);
+const GUTTER_COLOR: Color = Color::rgb8(0x90, 0x90, 0x90);
+/// How long the diff may take before falling back to a coarser one, for very large texts.
+const DIFF_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How a [`diff_view`] lays out the two texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMode {
+    /// The removed and added lines interleaved in a single column.
+    Unified,
+    /// The old text on the left and the new text on the right, with matching lines aligned.
+    SideBySide,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DiffLine {
+    tag: ChangeTag,
+    /// The line number in the old text, from 1.
+    old_number: Option<usize>,
+    /// The line number in the new text, from 1.
+    new_number: Option<usize>,
+    /// The text of the line in parts, flagged with whether the part changed within the line.
+    parts: Vec<(bool, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DiffRow {
+    unified: bool,
+    left: Option<DiffLine>,
+    right: Option<DiffLine>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct DiffRows {
+    unified: im::Vector<DiffRow>,
+    /// The first row of each hunk in `unified`.
+    unified_hunks: Vec<usize>,
+    side_by_side: im::Vector<DiffRow>,
+    /// The first row of each hunk in `side_by_side`.
+    side_by_side_hunks: Vec<usize>,
+}
+
+fn compute_diff(old: &str, new: &str) -> DiffRows {
+    let diff = TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(old, new);
+    let mut rows = DiffRows::default();
+    let mut in_hunk = false;
+    for op in diff.ops() {
+        let changed = op.tag() != DiffTag::Equal;
+        // Adjacent changes form one hunk
+        if changed && !in_hunk {
+            rows.unified_hunks.push(rows.unified.len());
+            rows.side_by_side_hunks.push(rows.side_by_side.len());
+        }
+        in_hunk = changed;
+
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+        for change in diff.iter_inline_changes(op) {
+            let line = DiffLine {
+                tag: change.tag(),
+                old_number: change.old_index().map(|index| index + 1),
+                new_number: change.new_index().map(|index| index + 1),
+                parts: change
+                    .iter_strings_lossy()
+                    .map(|(emphasized, text)| {
+                        (emphasized, text.trim_end_matches(['\n', '\r']).to_string())
+                    })
+                    .collect(),
+            };
+            rows.unified.push_back(DiffRow {
+                unified: true,
+                left: Some(line.clone()),
+                right: None,
+            });
+            match line.tag {
+                ChangeTag::Equal => rows.side_by_side.push_back(DiffRow {
+                    unified: false,
+                    left: Some(line.clone()),
+                    right: Some(line),
+                }),
+                ChangeTag::Delete => deleted.push(line),
+                ChangeTag::Insert => inserted.push(line),
+            }
+        }
+
+        // The lines of a replacement are paired up, padding the shorter side
+        let len = deleted.len().max(inserted.len());
+        let mut deleted = deleted.into_iter();
+        let mut inserted = inserted.into_iter();
+        for _ in 0..len {
+            rows.side_by_side.push_back(DiffRow {
+                unified: false,
+                left: deleted.next(),
+                right: inserted.next(),
+            });
+        }
+    }
+    rows
+}
+
+fn number_view(number: Option<usize>) -> impl View {
+    label(move || number.map(|number| number.to_string()).unwrap_or_default()).style(|s| {
+        s.width(GUTTER_WIDTH)
+            .padding_right(6.0)
+            .justify_end()
+            .color(GUTTER_COLOR)
+    })
+}
+
+fn text_view(line: Option<DiffLine>) -> impl View {
+    let (sign, background, word_background) = match line.as_ref().map(|line| line.tag) {
+        Some(ChangeTag::Delete) => ("-", Some(DELETE_COLOR), DELETE_WORD_COLOR),
+        Some(ChangeTag::Insert) => ("+", Some(INSERT_COLOR), INSERT_WORD_COLOR),
+        _ => (" ", None, Color::TRANSPARENT),
+    };
+    let parts = line.map(|line| line.parts).unwrap_or_default();
+    h_stack((
+        label(move || sign).style(|s| s.width(14.0).color(GUTTER_COLOR)),
+        h_stack_from_iter(parts.into_iter().map(move |(emphasized, text)| {
+            label(move || text.clone())
+                .style(move |s| s.apply_if(emphasized, |s| s.background(word_background)))
+        })),
+    ))
+    .style(move |s| {
+        s.flex_grow(1.0)
+            .height_full()
+            .items_center()
+            .apply_opt(background, |s, background| s.background(background))
+    })
+}
+
+fn row_view(row: DiffRow) -> impl View {
+    let stack = if row.unified {
+        let line = row.left.unwrap();
+        h_stack((
+            number_view(line.old_number),
+            number_view(line.new_number),
+            text_view(Some(line)),
+        ))
+    } else {
+        let left_number = row.left.as_ref().and_then(|line| line.old_number);
+        let right_number = row.right.as_ref().and_then(|line| line.new_number);
+        h_stack((
+            h_stack((number_view(left_number), text_view(row.left)))
+                .style(|s| s.width_pct(50.0).min_width(0.0)),
+            h_stack((number_view(right_number), text_view(row.right)))
+                .style(|s| s.width_pct(50.0).min_width(0.0)),
+        ))
+    };
+    stack
+        .class(DiffLineClass)
+        .style(|s| s.height(ROW_HEIGHT).min_width_full().items_center())
+}
+
+/// A view of the differences between two texts. See [`diff_view`]
+pub struct DiffView {
+    data: ViewData,
+    child: Scroll,
+    mode: RwSignal<DiffMode>,
+    hunks: Memo<Vec<usize>>,
+    current_hunk: RwSignal<Option<usize>>,
+}
+
+/// Shows the differences between the `old` and `new` texts, either as a unified diff or side
+/// by side. Changed lines are highlighted, and within a changed line the words which changed
+/// are highlighted more strongly.
+///
+/// Only the visible lines are created, so large files are shown quickly. The diff is made of
+/// hunks, runs of changed lines, which can be moved between with [`DiffView::next_hunk`] and
+/// [`DiffView::previous_hunk`], or with `F7` and `Shift+F7` when the view is focused.
+///
+/// ```ignore
+/// let view = diff_view(move || saved.get(), move || edited.get());
+/// let mode = view.mode();
+/// v_stack((
+///     button(|| "Side by side").on_click_stop(move |_| mode.set(DiffMode::SideBySide)),
+///     view.style(|s| s.size_full()),
+/// ))
+/// ```
+pub fn diff_view(
+    old: impl Fn() -> String + 'static,
+    new: impl Fn() -> String + 'static,
+) -> DiffView {
+    let id = Id::next();
+    let monospace = intern("monospace");
+    let mode = create_rw_signal(DiffMode::Unified);
+    let current_hunk = create_rw_signal(None::<usize>);
+    let diff = create_memo(move |_| Rc::new(compute_diff(&old(), &new())));
+    let rows = create_memo(move |_| {
+        let diff = diff.get();
+        match mode.get() {
+            DiffMode::Unified => diff.unified.clone(),
+            DiffMode::SideBySide => diff.side_by_side.clone(),
+        }
+    });
+    let hunks = create_memo(move |_| {
+        let diff = diff.get();
+        match mode.get() {
+            DiffMode::Unified => diff.unified_hunks.clone(),
+            DiffMode::SideBySide => diff.side_by_side_hunks.clone(),
+        }
+    });
+
+    let lines = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || rows.get(),
+        |row: &DiffRow| row.clone(),
+        row_view,
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = scroll(lines).scroll_to(move || {
+        let row = current_hunk
+            .get()
+            .and_then(|hunk| hunks.with(|hunks| hunks.get(hunk).copied()))?;
+        Some(Point::new(0.0, row as f64 * ROW_HEIGHT))
+    });
+
+    DiffView {
+        data: ViewData::new(id),
+        child,
+        mode,
+        hunks,
+        current_hunk,
+    }
+    .class(DiffViewClass)
+    .keyboard_navigatable()
+    .on_event(EventListener::KeyDown, move |event| {
+        let Event::KeyDown(key_event) = event else {
+            return EventPropagation::Continue;
+        };
+        if key_event.key.logical_key != Key::Named(NamedKey::F7) {
+            return EventPropagation::Continue;
+        }
+        if key_event.modifiers.shift_key() {
+            move_hunk(hunks, current_hunk, false);
+        } else {
+            move_hunk(hunks, current_hunk, true);
+        }
+        EventPropagation::Stop
+    })
+    .style(move |s| s.font_family(monospace))
+}
+
+fn move_hunk(hunks: Memo<Vec<usize>>, current_hunk: RwSignal<Option<usize>>, forward: bool) {
+    let count = hunks.with_untracked(|hunks| hunks.len());
+    if count == 0 {
+        return;
+    }
+    let next = match (current_hunk.get_untracked(), forward) {
+        (Some(hunk), true) => (hunk + 1).min(count - 1),
+        (Some(hunk), false) => hunk.saturating_sub(1),
+        (None, true) => 0,
+        (None, false) => count - 1,
+    };
+    current_hunk.set(Some(next));
+}
+
+impl DiffView {
+    /// Whether the diff is shown unified or side by side. Starts as [`DiffMode::Unified`].
+    pub fn mode(&self) -> RwSignal<DiffMode> {
+        self.mode
+    }
+
+    /// Sets the starting [`DiffMode`].
+    pub fn diff_mode(self, mode: DiffMode) -> Self {
+        self.mode.set(mode);
+        self
+    }
+
+    /// The number of hunks, runs of changed lines, in the diff.
+    pub fn hunk_count(&self) -> usize {
+        self.hunks.with(|hunks| hunks.len())
+    }
+
+    /// The hunk last moved to, which is scrolled into view.
+    pub fn current_hunk(&self) -> RwSignal<Option<usize>> {
+        self.current_hunk
+    }
+
+    /// Scrolls to the next hunk, or the first one if none was moved to yet.
+    pub fn next_hunk(&self) {
+        move_hunk(self.hunks, self.current_hunk, true);
+    }
+
+    /// Scrolls to the previous hunk, or the last one if none was moved to yet.
+    pub fn previous_hunk(&self) {
+        move_hunk(self.hunks, self.current_hunk, false);
+    }
+}
+
+impl View for DiffView {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "DiffView".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(row: &Option<DiffLine>) -> String {
+        row.as_ref()
+            .map(|line| line.parts.iter().map(|(_, text)| text.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn rows_and_hunks() {
+        let rows = compute_diff("a\nb\nc\nd\n", "a\nB\nc\nd\ne\n");
+        assert_eq!(rows.unified.len(), 6);
+        assert_eq!(rows.unified_hunks, vec![1, 5]);
+        assert_eq!(rows.side_by_side.len(), 5);
+        assert_eq!(rows.side_by_side_hunks, vec![1, 4]);
+
+        let replaced = &rows.side_by_side[1];
+        assert_eq!(text(&replaced.left), "b");
+        assert_eq!(text(&replaced.right), "B");
+        let added = &rows.side_by_side[4];
+        assert_eq!(added.left, None);
+        assert_eq!(added.right.as_ref().unwrap().new_number, Some(5));
+    }
+
+    #[test]
+    fn changed_words() {
+        let rows = compute_diff("let x = 1;\n", "let y = 1;\n");
+        let changed: Vec<_> = rows.side_by_side[0]
+            .right
+            .as_ref()
+            .unwrap()
+            .parts
+            .iter()
+            .filter(|(emphasized, _)| *emphasized)
+            .map(|(_, text)| text.clone())
+            .collect();
+        assert_eq!(changed, vec!["y".to_string()]);
+    }
+}
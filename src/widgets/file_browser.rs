@@ -0,0 +1,598 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
+
+use floem_peniko::Color;
+use floem_reactive::{
+    create_effect, create_memo, create_rw_signal, create_trigger, RwSignal, Scope, Trigger,
+};
+
+use crate::{
+    event::{Event, EventListener, EventPropagation},
+    ext_event::create_ext_action,
+    id::Id,
+    keyboard::{Key, ModifiersState, NamedKey},
+    style_class,
+    view::{View, ViewData},
+    views::{dyn_container, dyn_stack, h_stack, label, scroll, text_input, Decorators},
+};
+
+style_class!(pub FileBrowserClass);
+style_class!(pub FileBrowserRowClass);
+
+/// How a [`file_browser`] shows its directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileBrowserMode {
+    /// The entries of the current directory, where opening a directory makes it the current
+    /// one.
+    List,
+    /// The entries of the root directory, where directories expand in place.
+    Tree,
+}
+
+/// The order of the entries of a [`file_browser`]. Directories always come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSort {
+    Name,
+    /// By extension, then name.
+    Kind,
+    Size,
+    Modified,
+}
+
+/// A file or directory shown by a [`file_browser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    /// The size in bytes, 0 for directories.
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileEntry {
+    fn extension(&self) -> String {
+        Path::new(&self.name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+}
+
+/// The icon shown before the name of `entry`, picked by its file type.
+pub fn file_icon(entry: &FileEntry) -> &'static str {
+    if entry.is_dir {
+        return "📁";
+    }
+    match entry.extension().as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" | "tiff" => "🖼",
+        "mp3" | "wav" | "flac" | "ogg" | "m4a" | "aac" => "🎵",
+        "mp4" | "mkv" | "mov" | "avi" | "webm" => "🎞",
+        "zip" | "tar" | "gz" | "xz" | "bz2" | "7z" | "rar" | "zst" => "📦",
+        "rs" | "c" | "h" | "cpp" | "hpp" | "py" | "js" | "ts" | "go" | "java" | "swift" | "kt"
+        | "sh" | "toml" | "json" | "yaml" | "yml" | "xml" | "html" | "css" => "📜",
+        "md" | "txt" | "pdf" | "doc" | "docx" | "odt" | "rtf" => "📝",
+        _ => "📄",
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct Row {
+    entry: FileEntry,
+    depth: usize,
+    expanded: bool,
+}
+
+/// A browser of the files below a directory. See [`file_browser`]
+pub struct FileBrowser {
+    data: ViewData,
+    child: Box<dyn View>,
+    current_dir: RwSignal<PathBuf>,
+    selection: RwSignal<Vec<PathBuf>>,
+    sort: RwSignal<FileSort>,
+    sort_descending: RwSignal<bool>,
+    show_hidden: RwSignal<bool>,
+    error: RwSignal<Option<String>>,
+    refresh: Trigger,
+    on_open: RwSignal<Option<Rc<dyn Fn(&Path)>>>,
+}
+
+/// Reads directories on a background thread into a cache shared by the rows.
+#[derive(Clone, Copy)]
+struct Loader {
+    scope: Scope,
+    cache: RwSignal<HashMap<PathBuf, Vec<FileEntry>>>,
+    error: RwSignal<Option<String>>,
+}
+
+impl Loader {
+    fn load(self, dir: PathBuf) {
+        let cache = self.cache;
+        let error = self.error;
+        let send = create_ext_action(self.scope, move |(dir, entries)| match entries {
+            Ok(entries) => cache.update(|cache| {
+                cache.insert(dir, entries);
+            }),
+            Err(e) => error.set(Some(format!("{}: {e}", dir.display()))),
+        });
+        std::thread::spawn(move || {
+            let entries = read_entries(&dir).map_err(|e| e.to_string());
+            send((dir, entries));
+        });
+    }
+
+    /// Loads `dir` unless it's already loaded.
+    fn ensure(self, dir: &Path) {
+        if !self.cache.with_untracked(|cache| cache.contains_key(dir)) {
+            self.load(dir.to_path_buf());
+        }
+    }
+
+    fn reload_all(self) {
+        let dirs: Vec<_> = self
+            .cache
+            .with_untracked(|cache| cache.keys().cloned().collect());
+        for dir in dirs {
+            self.load(dir);
+        }
+    }
+}
+
+fn read_entries(dir: &Path) -> std::io::Result<Vec<FileEntry>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Follows symlinks, so linked directories can be expanded
+        let Ok(metadata) = std::fs::metadata(entry.path()) else {
+            continue;
+        };
+        entries.push(FileEntry {
+            path: entry.path(),
+            name,
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        });
+    }
+    Ok(entries)
+}
+
+fn sort_entries(entries: &mut [FileEntry], sort: FileSort, descending: bool) {
+    entries.sort_by(|a, b| {
+        let name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        let order = match sort {
+            FileSort::Name => name(),
+            FileSort::Kind => a.extension().cmp(&b.extension()).then_with(name),
+            FileSort::Size => a.size.cmp(&b.size).then_with(name),
+            FileSort::Modified => a.modified.cmp(&b.modified).then_with(name),
+        };
+        let order = if descending { order.reverse() } else { order };
+        b.is_dir.cmp(&a.is_dir).then(order)
+    });
+}
+
+/// Adds the rows of the entries of `dir` to `rows`, followed by the rows of the expanded
+/// directories below it in tree mode.
+fn push_rows(
+    rows: &mut Vec<Row>,
+    cache: &HashMap<PathBuf, Vec<FileEntry>>,
+    expanded: &HashSet<PathBuf>,
+    mode: FileBrowserMode,
+    (sort, descending, show_hidden): (FileSort, bool, bool),
+    dir: &Path,
+    depth: usize,
+) {
+    let Some(entries) = cache.get(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries
+        .iter()
+        .filter(|entry| show_hidden || !entry.name.starts_with('.'))
+        .cloned()
+        .collect();
+    sort_entries(&mut entries, sort, descending);
+    for entry in entries {
+        let is_expanded =
+            mode == FileBrowserMode::Tree && entry.is_dir && expanded.contains(&entry.path);
+        let path = entry.path.clone();
+        rows.push(Row {
+            entry,
+            depth,
+            expanded: is_expanded,
+        });
+        if is_expanded {
+            let order = (sort, descending, show_hidden);
+            push_rows(rows, cache, expanded, mode, order, &path, depth + 1);
+        }
+    }
+}
+
+/// Whether the modifier to toggle an entry in the selection is held, `Cmd` on macOS and `Ctrl`
+/// elsewhere.
+fn toggle_modifier(modifiers: ModifiersState) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.super_key()
+    } else {
+        modifiers.control_key()
+    }
+}
+
+/// Browses the files and directories below `root`, as a [list](FileBrowserMode::List) of the
+/// current directory or an expandable [tree](FileBrowserMode::Tree).
+///
+/// Directories are read in the background when they're first shown. Clicking an entry selects
+/// it, with `Ctrl` (`Cmd` on macOS) toggling entries and `Shift` selecting a range, and the
+/// arrow keys move the selection. Double clicking or pressing `Enter` opens an entry:
+/// directories are expanded or entered, files are passed to [`FileBrowser::on_open`]. `F2`
+/// renames the selected entry in place, and `Backspace` goes to the parent directory in list
+/// mode.
+///
+/// ```ignore
+/// file_browser(dirs::home_dir().unwrap(), FileBrowserMode::Tree)
+///     .on_open(|path| open_document(path))
+///     .style(|s| s.size_full())
+/// ```
+pub fn file_browser(root: impl Into<PathBuf>, mode: FileBrowserMode) -> FileBrowser {
+    let id = Id::next();
+    let root: PathBuf = root.into();
+    let current_dir = create_rw_signal(root);
+    let selection = create_rw_signal(Vec::<PathBuf>::new());
+    let sort = create_rw_signal(FileSort::Name);
+    let sort_descending = create_rw_signal(false);
+    let show_hidden = create_rw_signal(false);
+    let error = create_rw_signal(None);
+    let expanded = create_rw_signal(HashSet::<PathBuf>::new());
+    let renaming = create_rw_signal(None::<PathBuf>);
+    // The entry clicked last, which shift-clicks extend from
+    let anchor = create_rw_signal(None::<PathBuf>);
+    let on_open = create_rw_signal(None::<Rc<dyn Fn(&Path)>>);
+    let loader = Loader {
+        scope: Scope::current(),
+        cache: create_rw_signal(HashMap::new()),
+        error,
+    };
+    // Also loads the directories set from outside through `FileBrowser::current_dir`
+    create_effect(move |_| current_dir.with(|dir| loader.ensure(dir)));
+    let refresh = create_trigger();
+    create_effect(move |first: Option<()>| {
+        refresh.track();
+        if first.is_some() {
+            loader.reload_all();
+        }
+    });
+
+    let rows = create_memo(move |_| {
+        let order = (sort.get(), sort_descending.get(), show_hidden.get());
+        let mut rows = Vec::new();
+        loader.cache.with(|cache| {
+            expanded.with(|expanded| {
+                let dir = current_dir.get();
+                push_rows(&mut rows, cache, expanded, mode, order, &dir, 0);
+            })
+        });
+        rows
+    });
+
+    let open = Rc::new(move |entry: &FileEntry| {
+        if entry.is_dir {
+            match mode {
+                FileBrowserMode::List => {
+                    selection.set(Vec::new());
+                    anchor.set(None);
+                    current_dir.set(entry.path.clone());
+                }
+                FileBrowserMode::Tree => {
+                    loader.ensure(&entry.path);
+                    expanded.update(|expanded| {
+                        if !expanded.remove(&entry.path) {
+                            expanded.insert(entry.path.clone());
+                        }
+                    });
+                }
+            }
+        } else if let Some(on_open) = on_open.get_untracked() {
+            on_open(&entry.path);
+        }
+    });
+
+    let select = move |path: &Path, modifiers: ModifiersState| {
+        let range = match anchor.get_untracked() {
+            Some(anchor) if modifiers.shift_key() => rows.with_untracked(|rows| {
+                let position = |path: &Path| rows.iter().position(|row| row.entry.path == path);
+                let (Some(from), Some(to)) = (position(&anchor), position(path)) else {
+                    return None;
+                };
+                Some(
+                    rows[from.min(to)..=from.max(to)]
+                        .iter()
+                        .map(|row| row.entry.path.clone())
+                        .collect::<Vec<_>>(),
+                )
+            }),
+            _ => None,
+        };
+        match range {
+            Some(range) => selection.set(range),
+            None if toggle_modifier(modifiers) => {
+                selection.update(|selection| {
+                    match selection.iter().position(|selected| selected == path) {
+                        Some(index) => {
+                            selection.remove(index);
+                        }
+                        None => selection.push(path.to_path_buf()),
+                    }
+                });
+                anchor.set(Some(path.to_path_buf()));
+            }
+            None => {
+                selection.set(vec![path.to_path_buf()]);
+                anchor.set(Some(path.to_path_buf()));
+            }
+        }
+    };
+
+    let rename = move |from: &Path, name: &str| {
+        renaming.set(None);
+        let name = name.trim();
+        if name.is_empty() || from.file_name().map_or(false, |old| old == name) {
+            return;
+        }
+        let Some(parent) = from.parent() else {
+            return;
+        };
+        let to = parent.join(name);
+        match std::fs::rename(from, &to) {
+            Ok(()) => {
+                selection.update(|selection| {
+                    for selected in selection.iter_mut().filter(|selected| *selected == from) {
+                        *selected = to.clone();
+                    }
+                });
+                anchor.set(Some(to));
+                loader.load(parent.to_path_buf());
+            }
+            Err(e) => error.set(Some(format!("{}: {e}", from.display()))),
+        }
+    };
+
+    let row_open = open.clone();
+    let list = dyn_stack(
+        move || rows.get(),
+        |row| (row.entry.path.clone(), row.expanded),
+        move |row| {
+            let path = row.entry.path.clone();
+            let name_path = path.clone();
+            let name = row.entry.name.clone();
+            let chevron = match (mode, row.entry.is_dir, row.expanded) {
+                (FileBrowserMode::Tree, true, true) => "▾",
+                (FileBrowserMode::Tree, true, false) => "▸",
+                _ => "",
+            };
+            let icon = file_icon(&row.entry);
+            let open = row_open.clone();
+            let entry = row.entry.clone();
+            let selected_path = path.clone();
+            let click_path = path.clone();
+            h_stack((
+                label(move || chevron).style(move |s| {
+                    s.width(12.0)
+                        .apply_if(mode == FileBrowserMode::List, |s| s.hide())
+                }),
+                label(move || icon),
+                dyn_container(
+                    move || renaming.get().as_deref() == Some(name_path.as_path()),
+                    move |is_renaming| {
+                        if !is_renaming {
+                            let name = name.clone();
+                            return Box::new(
+                                label(move || name.clone()).style(|s| s.text_ellipsis()),
+                            );
+                        }
+                        let buffer = create_rw_signal(name.clone());
+                        let from = path.clone();
+                        Box::new(
+                            text_input(buffer)
+                                .on_mount(|id| id.request_focus())
+                                .on_key_down(
+                                    Key::Named(NamedKey::Enter),
+                                    ModifiersState::empty(),
+                                    move |_| rename(&from, &buffer.get_untracked()),
+                                )
+                                .on_key_down(
+                                    Key::Named(NamedKey::Escape),
+                                    ModifiersState::empty(),
+                                    move |_| renaming.set(None),
+                                )
+                                .on_event_cont(EventListener::FocusLost, move |_| {
+                                    renaming.set(None)
+                                })
+                                .style(|s| s.flex_grow(1.0)),
+                        )
+                    },
+                )
+                .style(|s| s.flex_grow(1.0).min_width(0.0)),
+            ))
+            .class(FileBrowserRowClass)
+            .on_click_stop(move |event| {
+                if let Event::PointerUp(event) = event {
+                    select(&click_path, event.modifiers);
+                }
+            })
+            .on_double_click_stop(move |_| open(&entry))
+            .style(move |s| {
+                let selected = selection.with(|selection| selection.contains(&selected_path));
+                s.items_center()
+                    .gap(4.0, 0.0)
+                    .padding_vert(2.0)
+                    .padding_right(6.0)
+                    .padding_left(6.0 + row.depth as f64 * 16.0)
+                    .hover(|s| s.background(Color::rgba8(0, 0, 0, 10)))
+                    .apply_if(selected, |s| s.background(Color::rgba8(0, 0, 0, 30)))
+            })
+        },
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = scroll(list)
+        .keyboard_navigatable()
+        .on_event(EventListener::KeyDown, move |event| {
+            let Event::KeyDown(key_event) = event else {
+                return EventPropagation::Continue;
+            };
+            if renaming.get_untracked().is_some() {
+                return EventPropagation::Continue;
+            }
+            let last = anchor.get_untracked();
+            let handled = match key_event.key.logical_key {
+                Key::Named(key @ (NamedKey::ArrowDown | NamedKey::ArrowUp)) => {
+                    let next = rows.with_untracked(|rows| {
+                        let index = last
+                            .as_ref()
+                            .and_then(|last| rows.iter().position(|row| &row.entry.path == last));
+                        let index = match (index, key) {
+                            (None, _) => 0,
+                            (Some(index), NamedKey::ArrowDown) => {
+                                (index + 1).min(rows.len().saturating_sub(1))
+                            }
+                            (Some(index), _) => index.saturating_sub(1),
+                        };
+                        rows.get(index).map(|row| row.entry.path.clone())
+                    });
+                    if let Some(next) = next {
+                        select(&next, key_event.modifiers);
+                    }
+                    true
+                }
+                Key::Named(NamedKey::Enter) => {
+                    let entry = last.and_then(|last| {
+                        rows.with_untracked(|rows| {
+                            rows.iter()
+                                .find(|row| row.entry.path == last)
+                                .map(|row| row.entry.clone())
+                        })
+                    });
+                    if let Some(entry) = entry {
+                        open(&entry);
+                    }
+                    true
+                }
+                Key::Named(NamedKey::F2) => {
+                    let selected = selection.get_untracked();
+                    if let [path] = selected.as_slice() {
+                        renaming.set(Some(path.clone()));
+                    }
+                    true
+                }
+                Key::Named(NamedKey::Backspace) if mode == FileBrowserMode::List => {
+                    let parent =
+                        current_dir.with_untracked(|dir| dir.parent().map(Path::to_path_buf));
+                    if let Some(parent) = parent {
+                        selection.set(Vec::new());
+                        anchor.set(None);
+                        current_dir.set(parent);
+                    }
+                    true
+                }
+                _ => false,
+            };
+            if handled {
+                EventPropagation::Stop
+            } else {
+                EventPropagation::Continue
+            }
+        })
+        .style(|s| s.size_full());
+
+    FileBrowser {
+        data: ViewData::new(id),
+        child: Box::new(child),
+        current_dir,
+        selection,
+        sort,
+        sort_descending,
+        show_hidden,
+        error,
+        refresh,
+        on_open,
+    }
+    .class(FileBrowserClass)
+}
+
+impl FileBrowser {
+    /// The selected entries, in the order they were selected.
+    pub fn selection(&self) -> RwSignal<Vec<PathBuf>> {
+        self.selection
+    }
+
+    /// The directory whose entries are shown. It only changes in list mode, where it can also
+    /// be set to show another directory.
+    pub fn current_dir(&self) -> RwSignal<PathBuf> {
+        self.current_dir
+    }
+
+    pub fn sort(&self) -> RwSignal<FileSort> {
+        self.sort
+    }
+
+    pub fn sort_descending(&self) -> RwSignal<bool> {
+        self.sort_descending
+    }
+
+    /// The last error reading a directory or renaming an entry.
+    pub fn error(&self) -> RwSignal<Option<String>> {
+        self.error
+    }
+
+    /// Whether the entries whose names start with a dot are shown, which they aren't by
+    /// default.
+    pub fn show_hidden(&self) -> RwSignal<bool> {
+        self.show_hidden
+    }
+
+    /// Called with the path of a file when it's double clicked, or when pressing `Enter` with
+    /// it selected.
+    pub fn on_open(self, on_open: impl Fn(&Path) + 'static) -> Self {
+        self.on_open.set(Some(Rc::new(on_open)));
+        self
+    }
+
+    /// Notifying the trigger reads the shown directories again, such as after they were
+    /// changed by something else.
+    pub fn refresh(&self) -> Trigger {
+        self.refresh
+    }
+}
+
+impl View for FileBrowser {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "FileBrowser".into()
+    }
+}
@@ -0,0 +1,736 @@
+use std::{collections::HashSet, fmt, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_memo, create_rw_signal, Memo, RwSignal};
+
+use crate::{
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    keyboard::Key,
+    menu::{Menu, MenuItem},
+    style_class,
+    view::{View, ViewData},
+    views::{
+        h_stack, label, scroll, v_stack, virtual_stack, Decorators, Stack, VirtualDirection,
+        VirtualItemSize,
+    },
+    widgets::text_input,
+    Clipboard,
+};
+
+style_class!(pub JsonViewClass);
+style_class!(pub JsonRowClass);
+
+const ROW_HEIGHT: f64 = 20.0;
+const KEY_COLOR: Color = Color::rgb8(0x88, 0x1c, 0x8c);
+const STRING_COLOR: Color = Color::rgb8(0x1a, 0x7f, 0x37);
+const NUMBER_COLOR: Color = Color::rgb8(0x0b, 0x53, 0xc9);
+const LITERAL_COLOR: Color = Color::rgb8(0xb3, 0x5c, 0x00);
+const SUMMARY_COLOR: Color = Color::rgb8(0x80, 0x80, 0x80);
+const MATCH_COLOR: Color = Color::rgba8(255, 200, 0, 90);
+const SELECTED_COLOR: Color = Color::rgba8(0, 0, 0, 30);
+
+/// A structured value shown by a [`json_view`], such as a parsed JSON document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataValue {
+    Null,
+    Bool(bool),
+    /// A number, kept as written so large integers and exact decimals aren't rounded.
+    Number(String),
+    String(String),
+    Array(Vec<DataValue>),
+    /// The members of an object, in the order they were written.
+    Object(Vec<(String, DataValue)>),
+}
+
+/// An error parsing JSON with [`DataValue::parse_json`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// The byte offset in the text where the error was found.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn error<T>(&self, message: &'static str) -> Result<T, JsonError> {
+        Err(JsonError {
+            offset: self.pos,
+            message,
+        })
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return self.error(message);
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str, value: DataValue) -> Result<DataValue, JsonError> {
+        if !self.text[self.pos..].starts_with(word) {
+            return self.error("unexpected character");
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<DataValue, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(DataValue::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return self.error("expected a key");
+                    }
+                    let key = self.string()?;
+                    self.expect(b':', "expected ':'")?;
+                    members.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(DataValue::Object(members));
+                        }
+                        _ => return self.error("expected ',' or '}'"),
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(DataValue::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(DataValue::Array(items));
+                        }
+                        _ => return self.error("expected ',' or ']'"),
+                    }
+                }
+            }
+            Some(b'"') => Ok(DataValue::String(self.string()?)),
+            Some(b't') => self.literal("true", DataValue::Bool(true)),
+            Some(b'f') => self.literal("false", DataValue::Bool(false)),
+            Some(b'n') => self.literal("null", DataValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => self.error("unexpected character"),
+            None => self.error("unexpected end"),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<DataValue, JsonError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        if self.peek() == Some(b'0') {
+            // Leading zeros aren't allowed
+            self.pos += 1;
+        } else if self.digits() == 0 {
+            return self.error("expected a digit");
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if self.digits() == 0 {
+                return self.error("expected a digit");
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                return self.error("expected a digit");
+            }
+        }
+        Ok(DataValue::Number(self.text[start..self.pos].to_string()))
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let hex = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(hex) => {
+                self.pos += 4;
+                Ok(hex)
+            }
+            None => self.error("invalid unicode escape"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        // Skip the opening quote
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let Some(ch) = self.text[self.pos..].chars().next() else {
+                return self.error("unterminated string");
+            };
+            self.pos += ch.len_utf8();
+            match ch {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escape = self.peek();
+                    self.pos += 1;
+                    match escape {
+                        Some(b'"') => string.push('"'),
+                        Some(b'\\') => string.push('\\'),
+                        Some(b'/') => string.push('/'),
+                        Some(b'b') => string.push('\u{8}'),
+                        Some(b'f') => string.push('\u{c}'),
+                        Some(b'n') => string.push('\n'),
+                        Some(b'r') => string.push('\r'),
+                        Some(b't') => string.push('\t'),
+                        Some(b'u') => {
+                            let mut code = self.hex4()?;
+                            // A surrogate pair encodes a character outside the basic plane
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.text[self.pos..].starts_with("\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                code = if (0xdc00..0xe000).contains(&low) {
+                                    0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00)
+                                } else {
+                                    0xfffd
+                                };
+                            }
+                            string.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return self.error("invalid escape"),
+                    }
+                }
+                ch if (ch as u32) < 0x20 => return self.error("control character in string"),
+                ch => string.push(ch),
+            }
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, string: &str) {
+    out.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+impl DataValue {
+    /// Parses a JSON document.
+    pub fn parse_json(text: &str) -> Result<DataValue, JsonError> {
+        let mut parser = JsonParser { text, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return parser.error("trailing characters");
+        }
+        Ok(value)
+    }
+
+    /// Writes the value as compact JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match self {
+            DataValue::Null => out.push_str("null"),
+            DataValue::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            DataValue::Number(number) => out.push_str(number),
+            DataValue::String(string) => write_json_string(out, string),
+            DataValue::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    item.write_json(out);
+                }
+                out.push(']');
+            }
+            DataValue::Object(members) => {
+                out.push('{');
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(out, key);
+                    out.push(':');
+                    value.write_json(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<(Segment, &DataValue)> {
+        match self {
+            DataValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (Segment::Index(index), item))
+                .collect(),
+            DataValue::Object(members) => members
+                .iter()
+                .map(|(key, value)| (Segment::Key(key.clone()), value))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn get(&self, path: &[Segment]) -> Option<&DataValue> {
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match (self, first) {
+            (DataValue::Array(items), Segment::Index(index)) => items.get(*index)?,
+            (DataValue::Object(members), Segment::Key(key)) => {
+                &members.iter().find(|(name, _)| name == key)?.1
+            }
+            _ => return None,
+        };
+        child.get(rest)
+    }
+
+    /// The text shown for the value: scalars as JSON, containers as a summary of their size.
+    fn summary(&self) -> String {
+        match self {
+            DataValue::Array(items) => format!("[{}]", items.len()),
+            DataValue::Object(members) => format!("{{{}}}", members.len()),
+            value => value.to_json(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            DataValue::String(_) => STRING_COLOR,
+            DataValue::Number(_) => NUMBER_COLOR,
+            DataValue::Bool(_) | DataValue::Null => LITERAL_COLOR,
+            DataValue::Array(_) | DataValue::Object(_) => SUMMARY_COLOR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Formats a path like `$.users[0].name`, with keys which aren't identifiers quoted.
+fn path_string(path: &[Segment]) -> String {
+    let mut out = String::from("$");
+    for segment in path {
+        match segment {
+            Segment::Index(index) => out.push_str(&format!("[{index}]")),
+            Segment::Key(key)
+                if !key.is_empty()
+                    && !key.starts_with(|ch: char| ch.is_ascii_digit())
+                    && key.chars().all(|ch| ch.is_alphanumeric() || ch == '_') =>
+            {
+                out.push('.');
+                out.push_str(key);
+            }
+            Segment::Key(key) => {
+                out.push('[');
+                write_json_string(&mut out, key);
+                out.push(']');
+            }
+        }
+    }
+    out
+}
+
+#[derive(Clone, PartialEq)]
+struct Row {
+    path: Rc<Vec<Segment>>,
+    depth: usize,
+    expandable: bool,
+    expanded: bool,
+    matched: bool,
+    summary: String,
+    color: Color,
+}
+
+/// Adds the paths of the containers holding a value or key matching `query` to `revealed`,
+/// returning whether `value` or its children match.
+fn find_matches(
+    value: &DataValue,
+    key: Option<&Segment>,
+    path: &mut Vec<Segment>,
+    query: &str,
+    revealed: &mut HashSet<Vec<Segment>>,
+) -> bool {
+    let mut matched = row_matches(value, key, query);
+    for (segment, child) in value.children() {
+        path.push(segment.clone());
+        let child_matched = find_matches(child, Some(&segment), path, query, revealed);
+        path.pop();
+        if child_matched {
+            revealed.insert(path.clone());
+            matched = true;
+        }
+    }
+    matched
+}
+
+fn row_matches(value: &DataValue, key: Option<&Segment>, query: &str) -> bool {
+    let key_matches = matches!(key, Some(Segment::Key(key)) if key.to_lowercase().contains(query));
+    let value_matches = match value {
+        DataValue::Array(_) | DataValue::Object(_) => false,
+        DataValue::String(string) => string.to_lowercase().contains(query),
+        value => value.to_json().contains(query),
+    };
+    key_matches || value_matches
+}
+
+struct RowsState<'a> {
+    expanded: &'a HashSet<Vec<Segment>>,
+    revealed: &'a HashSet<Vec<Segment>>,
+    query: &'a str,
+}
+
+fn push_rows(
+    rows: &mut im::Vector<Row>,
+    value: &DataValue,
+    path: &mut Vec<Segment>,
+    depth: usize,
+    state: &RowsState,
+) {
+    let expandable = matches!(value, DataValue::Array(items) if !items.is_empty())
+        || matches!(value, DataValue::Object(members) if !members.is_empty());
+    let expanded = expandable && (state.expanded.contains(path) || state.revealed.contains(path));
+    rows.push_back(Row {
+        path: Rc::new(path.clone()),
+        depth,
+        expandable,
+        expanded,
+        matched: !state.query.is_empty() && row_matches(value, path.last(), state.query),
+        summary: value.summary(),
+        color: value.color(),
+    });
+    if expanded {
+        for (segment, child) in value.children() {
+            path.push(segment);
+            push_rows(rows, child, path, depth + 1, state);
+            path.pop();
+        }
+    }
+}
+
+/// A viewer of structured data. See [`json_view`]
+pub struct JsonView {
+    data: ViewData,
+    child: Stack,
+    search: RwSignal<String>,
+    selected: RwSignal<Option<Rc<Vec<Segment>>>>,
+    selected_path: Memo<Option<String>>,
+}
+
+/// Shows `value` as an expandable tree, such as a JSON document parsed with
+/// [`DataValue::parse_json`]. Keys and the kinds of values are colored.
+///
+/// Only the visible rows are created, so large documents are shown quickly. The search field
+/// above the tree highlights the matching keys and values, expanding the containers holding
+/// them. Clicking a row selects it, and `Ctrl+C` (`Cmd+C` on macOS) copies the path of the
+/// selected row, like `$.users[0].name`. The context menu of a row copies its path or value.
+///
+/// ```ignore
+/// let response = create_rw_signal(DataValue::Null);
+/// json_view(move || response.get()).style(|s| s.size_full())
+/// ```
+pub fn json_view(value: impl Fn() -> DataValue + 'static) -> JsonView {
+    let id = Id::next();
+    let root = create_rw_signal(Rc::new(DataValue::Null));
+    create_effect(move |_| root.set(Rc::new(value())));
+    let search = create_rw_signal(String::new());
+    // The root is expanded to start with
+    let expanded = create_rw_signal(HashSet::from([Vec::<Segment>::new()]));
+    let selected = create_rw_signal(None::<Rc<Vec<Segment>>>);
+
+    let revealed = create_memo(move |_| {
+        let query = search.with(|search| search.trim().to_lowercase());
+        let mut revealed = HashSet::new();
+        if !query.is_empty() {
+            root.with(|root| find_matches(root, None, &mut Vec::new(), &query, &mut revealed));
+        }
+        revealed
+    });
+    let rows = create_memo(move |_| {
+        let query = search.with(|search| search.trim().to_lowercase());
+        let mut rows = im::Vector::new();
+        expanded.with(|expanded| {
+            revealed.with(|revealed| {
+                let state = RowsState {
+                    expanded,
+                    revealed,
+                    query: &query,
+                };
+                root.with(|root| push_rows(&mut rows, root, &mut Vec::new(), 0, &state));
+            })
+        });
+        rows
+    });
+
+    let copy_path = move |path: &[Segment]| {
+        let _ = Clipboard::set_contents(path_string(path));
+    };
+    let copy_value = move |path: &[Segment]| {
+        if let Some(value) = root.with_untracked(|root| root.get(path).map(DataValue::to_json)) {
+            let _ = Clipboard::set_contents(value);
+        }
+    };
+
+    let tree = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || rows.get(),
+        |row: &Row| (row.path.clone(), row.expanded, row.matched),
+        move |row| {
+            let path = row.path.clone();
+            let key = match path.last() {
+                Some(Segment::Key(key)) => format!("{key}:"),
+                Some(Segment::Index(index)) => format!("{index}:"),
+                None => String::new(),
+            };
+            let toggle_path = path.clone();
+            let arrow = match (row.expandable, row.expanded) {
+                (true, true) => "▾",
+                (true, false) => "▸",
+                (false, _) => "",
+            };
+            let summary = row.summary.clone();
+            let color = row.color;
+            let matched = row.matched;
+            let selected_path = path.clone();
+            let menu_path = path.clone();
+            h_stack((
+                label(move || arrow)
+                    .on_click_stop(move |_| {
+                        let path = toggle_path.as_ref().clone();
+                        expanded.update(|expanded| {
+                            if !expanded.remove(&path) {
+                                expanded.insert(path);
+                            }
+                        });
+                    })
+                    .style(|s| s.width(12.0)),
+                label(move || key.clone()).style(|s| s.color(KEY_COLOR)),
+                label(move || summary.clone()).style(move |s| s.color(color).text_ellipsis()),
+            ))
+            .class(JsonRowClass)
+            .on_click_stop(move |_| selected.set(Some(path.clone())))
+            .context_menu(move || {
+                let path = menu_path.clone();
+                let value_path = menu_path.clone();
+                Menu::new("")
+                    .entry(MenuItem::new("Copy Path").action(move || copy_path(&path)))
+                    .entry(MenuItem::new("Copy Value").action(move || copy_value(&value_path)))
+            })
+            .style(move |s| {
+                let is_selected =
+                    selected.with(|selected| selected.as_ref() == Some(&selected_path));
+                s.items_center()
+                    .gap(4.0, 0.0)
+                    .height(ROW_HEIGHT)
+                    .padding_left(4.0 + row.depth as f64 * 14.0)
+                    .apply_if(matched, |s| s.background(MATCH_COLOR))
+                    .apply_if(is_selected, |s| s.background(SELECTED_COLOR))
+            })
+        },
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = v_stack((
+        text_input(search)
+            .placeholder("Search")
+            .style(|s| s.width_full()),
+        scroll(tree)
+            .keyboard_navigatable()
+            .on_event(EventListener::KeyDown, move |event| {
+                let Event::KeyDown(key_event) = event else {
+                    return EventPropagation::Continue;
+                };
+                let modifier = if cfg!(target_os = "macos") {
+                    key_event.modifiers.super_key()
+                } else {
+                    key_event.modifiers.control_key()
+                };
+                match &key_event.key.logical_key {
+                    Key::Character(ch) if modifier && ch.eq_ignore_ascii_case("c") => {
+                        if let Some(path) = selected.get_untracked() {
+                            copy_path(&path);
+                        }
+                        EventPropagation::Stop
+                    }
+                    _ => EventPropagation::Continue,
+                }
+            })
+            .style(|s| s.flex_grow(1.0).width_full()),
+    ))
+    .style(|s| s.gap(0.0, 8.0).size_full());
+
+    let selected_path =
+        create_memo(move |_| selected.with(|path| path.as_deref().map(|path| path_string(path))));
+
+    JsonView {
+        data: ViewData::new(id),
+        child,
+        search,
+        selected,
+        selected_path,
+    }
+    .class(JsonViewClass)
+}
+
+impl JsonView {
+    /// The text of the search field.
+    pub fn search(&self) -> RwSignal<String> {
+        self.search
+    }
+
+    /// The path of the selected row, like `$.users[0].name`.
+    pub fn selected_path(&self) -> Memo<Option<String>> {
+        self.selected_path
+    }
+
+    /// Clears the selection.
+    pub fn clear_selection(&self) {
+        self.selected.set(None);
+    }
+}
+
+impl View for JsonView {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "JsonView".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_json() {
+        let value =
+            DataValue::parse_json(r#" {"a": [1, -2.5e3, true, null], "b c": "x\"é😀"} "#).unwrap();
+        assert_eq!(
+            value,
+            DataValue::Object(vec![
+                (
+                    "a".to_string(),
+                    DataValue::Array(vec![
+                        DataValue::Number("1".to_string()),
+                        DataValue::Number("-2.5e3".to_string()),
+                        DataValue::Bool(true),
+                        DataValue::Null,
+                    ])
+                ),
+                ("b c".to_string(), DataValue::String("x\"é😀".to_string())),
+            ])
+        );
+        assert_eq!(
+            value.to_json(),
+            r#"{"a":[1,-2.5e3,true,null],"b c":"x\"é😀"}"#
+        );
+        assert!(DataValue::parse_json("[1,]").is_err());
+        assert!(DataValue::parse_json("01").is_err());
+        assert_eq!(DataValue::parse_json("[1] x").unwrap_err().offset, 4);
+    }
+
+    #[test]
+    fn paths() {
+        let path = [
+            Segment::Key("users".to_string()),
+            Segment::Index(0),
+            Segment::Key("first name".to_string()),
+        ];
+        assert_eq!(path_string(&path), r#"$.users[0]["first name"]"#);
+    }
+}
@@ -1,11 +1,18 @@
+use std::{collections::HashSet, hash::Hash, rc::Rc};
+
+use floem_reactive::{create_rw_signal, RwSignal};
+
 use crate::{
+    event::Event,
     style_class,
     view::View,
-    views::{self, container, Decorators, List},
+    views::{self, container, h_stack, v_stack, Decorators, List, Stack},
+    widgets::{checkbox_svg, tri_state_checkbox},
 };
 
 style_class!(pub ListClass);
 style_class!(pub ListItemClass);
+style_class!(pub CheckboxListHeaderClass);
 
 pub fn list<V>(iterator: impl IntoIterator<Item = V>) -> List
 where
@@ -18,3 +25,84 @@ where
     )
     .class(ListClass)
 }
+
+/// A [`list`] with a checkbox on each row and a "select all" checkbox above the rows.
+///
+/// `selected_keys` holds the keys of the checked rows. The "select all" checkbox shows a mixed
+/// state when only some rows are checked. Shift-clicking a checkbox gives the rows between it
+/// and the previously clicked checkbox the same state.
+pub fn checkbox_list<T, K, V>(
+    items: impl IntoIterator<Item = T>,
+    key_fn: impl Fn(&T) -> K,
+    view_fn: impl Fn(T) -> V,
+    selected_keys: RwSignal<HashSet<K>>,
+) -> Stack
+where
+    K: Eq + Hash + Clone + 'static,
+    V: View + 'static,
+{
+    let items: Vec<T> = items.into_iter().collect();
+    let keys: Rc<Vec<K>> = Rc::new(items.iter().map(key_fn).collect());
+    // The row clicked last, which shift-clicks extend from
+    let anchor = create_rw_signal(None::<usize>);
+
+    let all_keys = keys.clone();
+    let all_state = move || {
+        selected_keys.with(|selected| {
+            let checked = all_keys.iter().filter(|key| selected.contains(key)).count();
+            if checked == 0 {
+                Some(false)
+            } else if checked == all_keys.len() {
+                Some(true)
+            } else {
+                None
+            }
+        })
+    };
+    let all_keys = keys.clone();
+    let header = container(tri_state_checkbox(all_state).on_update(move |checked| {
+        selected_keys.update(|selected| {
+            for key in all_keys.iter() {
+                if checked == Some(true) {
+                    selected.insert(key.clone());
+                } else {
+                    selected.remove(key);
+                }
+            }
+        });
+    }))
+    .class(CheckboxListHeaderClass);
+
+    let rows = items.into_iter().enumerate().map(|(index, item)| {
+        let keys = keys.clone();
+        let key = keys[index].clone();
+        let checkbox = checkbox_svg(move || selected_keys.with(|selected| selected.contains(&key)))
+            .keyboard_navigatable()
+            .on_click_stop(move |e| {
+                let shift = match e {
+                    Event::PointerUp(e) => e.modifiers.shift_key(),
+                    Event::KeyDown(e) => e.modifiers.shift_key(),
+                    _ => false,
+                };
+                let checked =
+                    !selected_keys.with_untracked(|selected| selected.contains(&keys[index]));
+                let range = match anchor.get_untracked() {
+                    Some(anchor) if shift => anchor.min(index)..=anchor.max(index),
+                    _ => index..=index,
+                };
+                selected_keys.update(|selected| {
+                    for key in &keys[range] {
+                        if checked {
+                            selected.insert(key.clone());
+                        } else {
+                            selected.remove(key);
+                        }
+                    }
+                });
+                anchor.set(Some(index));
+            });
+        h_stack((checkbox, view_fn(item))).style(|s| s.items_center())
+    });
+
+    v_stack((header, list(rows)))
+}
@@ -6,7 +6,10 @@
 use crate::{
     style::{Background, CursorStyle, Foreground, Style, Transition},
     unit::{PxPct, UnitExt},
-    views::scroll,
+    views::{
+        scroll, ChipClass, ChipSuggestionsClass, ChipsInputClass, ContextMenuClass,
+        ContextMenuItemClass, ContextMenuSeparatorClass, ContextMenuShortcutClass, TextAreaClass,
+    },
     widgets::{self, slider::SliderClass},
 };
 use floem_peniko::Color;
@@ -39,6 +42,30 @@ pub use radio_button::*;
 mod text_input;
 pub use text_input::*;
 
+mod wizard;
+pub use wizard::*;
+
+mod file_browser;
+pub use file_browser::*;
+
+mod property_grid;
+pub use property_grid::*;
+
+mod json_view;
+pub use json_view::*;
+
+mod diff_view;
+pub use diff_view::*;
+
+mod node_graph;
+pub use node_graph::*;
+
+mod tree;
+pub use tree::*;
+
+mod command_palette;
+pub use command_palette::*;
+
 pub(crate) struct Theme {
     pub(crate) background: Color,
     pub(crate) style: Rc<Style>,
@@ -63,6 +90,8 @@ pub(crate) fn default_theme() -> Theme {
     let light_hover_bg_color = Color::rgb8(250, 252, 248);
     let light_focus_hover_bg_color = Color::rgb8(250, 249, 251);
 
+    let invalid_color = Color::rgb8(196, 43, 28);
+
     let focus_applied_style = Style::new().border_color(Color::rgb8(114, 74, 140));
 
     let focus_visible_applied_style = Style::new().outline(3.0);
@@ -218,7 +247,8 @@ pub(crate) fn default_theme() -> Theme {
         .disabled(|s| {
             s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
                 .color(Color::GRAY)
-        });
+        })
+        .invalid(|s| s.border_color(invalid_color));
 
     let item_focused_style = Style::new().selected(|s| {
         s.background(selected_bg_color)
@@ -242,6 +272,24 @@ pub(crate) fn default_theme() -> Theme {
         .class(RadioButtonClass, |_| radio_button_style)
         .class(RadioButtonDotClass, |_| radio_button_dot_style)
         .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
+        .class(ChipsInputClass, |_| input_style.clone().padding(4.0))
+        .class(ChipClass, |s| {
+            s.padding_horiz(8.0)
+                .padding_vert(2.0)
+                .border_radius(10.0)
+                .background(Color::rgb8(228, 228, 228))
+        })
+        .class(ChipSuggestionsClass, |s| {
+            s.border(0.5)
+                .border_color(Color::rgb8(140, 140, 140))
+                .border_radius(border_radius)
+                .margin_top(2.0)
+                .background(Color::WHITE)
+                .box_shadow_blur(2.0)
+                .box_shadow_v_offset(2.0)
+                .box_shadow_color(Color::BLACK.with_alpha_factor(0.2))
+        })
+        .class(TextAreaClass, |_| input_style.clone().padding(8.0))
         .class(TextInputClass, |_| input_style)
         .class(ButtonClass, |_| button_style)
         .class(scroll::Handle, |s| {
@@ -269,10 +317,60 @@ pub(crate) fn default_theme() -> Theme {
                 .set(slider::EdgeAlign, true)
                 .set(slider::HandleRadius, PxPct::Pct(100.))
         })
+        .class(TextInputErrorClass, |s| {
+            s.color(invalid_color)
+                .font_size(FONT_SIZE * 0.9)
+                .margin_top(3.0)
+        })
         .class(PlaceholderTextClass, |s| {
             s.color(Color::rgba8(158, 158, 158, 30))
                 .font_size(FONT_SIZE)
         })
+        .class(ContextMenuClass, |s| {
+            s.min_width(200.0)
+                .padding(4.0)
+                .border(0.5)
+                .border_color(Color::rgb8(140, 140, 140))
+                .border_radius(border_radius)
+                .background(Color::WHITE)
+                .cursor(CursorStyle::Default)
+                .box_shadow_blur(4.0)
+                .box_shadow_v_offset(2.0)
+                .box_shadow_color(Color::BLACK.with_alpha_factor(0.2))
+        })
+        .class(ContextMenuItemClass, |s| {
+            s.padding_horiz(12.0)
+                .padding_vert(4.0)
+                .items_center()
+                .border_radius(border_radius)
+                .selected(|s| s.background(selected_bg_color))
+                .disabled(|s| s.color(Color::GRAY))
+        })
+        .class(ContextMenuShortcutClass, |s| {
+            s.margin_left(20.0).color(Color::rgb8(128, 128, 128))
+        })
+        .class(ContextMenuSeparatorClass, |s| {
+            s.height(1.0)
+                .margin_vert(4.0)
+                .background(Color::rgb8(220, 220, 220))
+        })
+        .class(CommandPaletteClass, |s| {
+            s.padding(6.0)
+                .border(0.5)
+                .border_color(Color::rgb8(140, 140, 140))
+                .border_radius(border_radius)
+                .background(Color::WHITE)
+                .box_shadow_blur(8.0)
+                .box_shadow_v_offset(4.0)
+                .box_shadow_color(Color::BLACK.with_alpha_factor(0.25))
+        })
+        .class(CommandPaletteItemClass, |s| {
+            s.padding_horiz(8.0)
+                .padding_vert(4.0)
+                .border_radius(border_radius)
+                .cursor(CursorStyle::Pointer)
+                .hover(|s| s.background(Color::rgba8(0, 0, 0, 15)))
+        })
         .class(TooltipClass, |s| {
             s.border(0.5)
                 .border_color(Color::rgb8(140, 140, 140))
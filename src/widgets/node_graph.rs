@@ -0,0 +1,986 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    ops::RangeInclusive,
+    rc::Rc,
+};
+
+use floem_peniko::Color;
+use floem_reactive::{
+    as_child_of_current_scope, create_effect, create_rw_signal, ReadSignal, RwSignal, Scope,
+};
+use floem_renderer::Renderer;
+use kurbo::{Circle, CubicBez, ParamCurveNearest, Point, Rect, Size, Vec2};
+use rustc_hash::FxHasher;
+
+use crate::{
+    context::{AppState, ComputeLayoutCx, EventCx, PaintCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style_class,
+    view::{self, view_children_set_parent_id, View, ViewData},
+    views::{h_stack, label, v_stack, v_stack_from_iter, Decorators},
+};
+
+style_class!(pub NodeGraphClass);
+style_class!(pub GraphNodeClass);
+
+/// How much the zoom changes for each pixel of Ctrl+wheel scrolling.
+const ZOOM_PER_PIXEL: f64 = 0.002;
+const PORT_RADIUS: f64 = 5.0;
+/// How far from a port or an edge the pointer may be to grab it, in logical pixels.
+const HIT_DISTANCE: f64 = 6.0;
+const EDGE_WIDTH: f64 = 2.0;
+const NODE_BORDER_COLOR: Color = Color::rgb8(0xb0, 0xb0, 0xb0);
+const SELECTED_COLOR: Color = Color::rgb8(0x3b, 0x82, 0xf6);
+const SELECTION_FILL: Color = Color::rgba8(0x3b, 0x82, 0xf6, 40);
+const PENDING_EDGE_COLOR: Color = Color::rgb8(0x90, 0x90, 0x90);
+const PORT_COLORS: [Color; 6] = [
+    Color::rgb8(0xe0, 0x6c, 0x75),
+    Color::rgb8(0x98, 0xc3, 0x79),
+    Color::rgb8(0xe5, 0xc0, 0x7b),
+    Color::rgb8(0x61, 0xaf, 0xef),
+    Color::rgb8(0xc6, 0x78, 0xdd),
+    Color::rgb8(0x56, 0xb6, 0xc2),
+];
+
+/// Identifies a node of a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(u64);
+
+/// An input or output of a node. Edges only connect ports of the same type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub ty: String,
+}
+
+impl Port {
+    pub fn new(name: impl Into<String>, ty: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ty: ty.into(),
+        }
+    }
+}
+
+/// A port of a node, by its index in the inputs or the outputs of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortRef {
+    pub node: NodeId,
+    pub index: usize,
+}
+
+impl PortRef {
+    pub fn new(node: NodeId, index: usize) -> Self {
+        Self { node, index }
+    }
+}
+
+/// A connection from an output port to an input port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: PortRef,
+    pub to: PortRef,
+}
+
+/// Why [`Graph::connect`] refused an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectError {
+    /// The output or the input doesn't exist.
+    NoSuchPort,
+    /// The output and the input are on the same node.
+    SameNode,
+    /// The output and the input have different types.
+    TypeMismatch,
+}
+
+impl std::fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConnectError::NoSuchPort => "no such port",
+            ConnectError::SameNode => "the ports are on the same node",
+            ConnectError::TypeMismatch => "the ports have different types",
+        })
+    }
+}
+
+impl std::error::Error for ConnectError {}
+
+#[derive(Clone)]
+pub struct GraphNode {
+    pub id: NodeId,
+    /// Where the top left corner of the node is on the canvas.
+    pub position: RwSignal<Point>,
+    pub inputs: Vec<Port>,
+    pub outputs: Vec<Port>,
+}
+
+/// The reactive model of a [`node_graph`]: nodes with typed ports, and the edges between them.
+///
+/// An input takes a single edge, connecting another output to it replaces its edge. An output
+/// can feed any number of inputs.
+#[derive(Clone, Copy)]
+pub struct Graph {
+    nodes: RwSignal<Vec<GraphNode>>,
+    edges: RwSignal<Vec<Edge>>,
+    next_id: RwSignal<u64>,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self {
+            nodes: create_rw_signal(Vec::new()),
+            edges: create_rw_signal(Vec::new()),
+            next_id: create_rw_signal(0),
+        }
+    }
+
+    pub fn nodes(&self) -> ReadSignal<Vec<GraphNode>> {
+        self.nodes.read_only()
+    }
+
+    pub fn edges(&self) -> ReadSignal<Vec<Edge>> {
+        self.edges.read_only()
+    }
+
+    /// The node with this id, without tracking the nodes.
+    pub fn node(&self, id: NodeId) -> Option<GraphNode> {
+        self.nodes
+            .with_untracked(|nodes| nodes.iter().find(|node| node.id == id).cloned())
+    }
+
+    pub fn add_node(&self, position: Point, inputs: Vec<Port>, outputs: Vec<Port>) -> NodeId {
+        let id = NodeId(self.next_id.get_untracked());
+        self.next_id.set(id.0 + 1);
+        let node = GraphNode {
+            id,
+            position: create_rw_signal(position),
+            inputs,
+            outputs,
+        };
+        self.nodes.update(|nodes| nodes.push(node));
+        id
+    }
+
+    /// Removes the node and its edges.
+    pub fn remove_node(&self, id: NodeId) {
+        let has_edges = self.edges.with_untracked(|edges| {
+            edges
+                .iter()
+                .any(|edge| edge.from.node == id || edge.to.node == id)
+        });
+        if has_edges {
+            self.edges
+                .update(|edges| edges.retain(|edge| edge.from.node != id && edge.to.node != id));
+        }
+        self.nodes
+            .update(|nodes| nodes.retain(|node| node.id != id));
+    }
+
+    /// Connects the output `from` to the input `to`, replacing the edge of the input.
+    pub fn connect(&self, from: PortRef, to: PortRef) -> Result<(), ConnectError> {
+        let output = self.port(from, true).ok_or(ConnectError::NoSuchPort)?;
+        let input = self.port(to, false).ok_or(ConnectError::NoSuchPort)?;
+        if from.node == to.node {
+            return Err(ConnectError::SameNode);
+        }
+        if output.ty != input.ty {
+            return Err(ConnectError::TypeMismatch);
+        }
+        let edge = Edge { from, to };
+        if self.edges.with_untracked(|edges| edges.contains(&edge)) {
+            return Ok(());
+        }
+        self.edges.update(|edges| {
+            edges.retain(|edge| edge.to != to);
+            edges.push(edge);
+        });
+        Ok(())
+    }
+
+    pub fn disconnect(&self, edge: Edge) {
+        if self.edges.with_untracked(|edges| edges.contains(&edge)) {
+            self.edges.update(|edges| edges.retain(|e| *e != edge));
+        }
+    }
+
+    /// The edge connected to this input.
+    pub fn input_edge(&self, to: PortRef) -> Option<Edge> {
+        self.edges
+            .with_untracked(|edges| edges.iter().find(|edge| edge.to == to).copied())
+    }
+
+    fn port(&self, port: PortRef, output: bool) -> Option<Port> {
+        self.nodes.with_untracked(|nodes| {
+            let node = nodes.iter().find(|node| node.id == port.node)?;
+            let ports = if output { &node.outputs } else { &node.inputs };
+            ports.get(port.index).cloned()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Side {
+    Input,
+    Output,
+}
+
+enum NodeGraphUpdate {
+    Nodes(Vec<GraphNode>),
+}
+
+/// What the pointer is doing since it was pressed.
+enum Gesture {
+    Pan {
+        start: Point,
+        start_pan: Vec2,
+    },
+    /// Moving the selected nodes, from the canvas point where the drag started and the
+    /// positions of the nodes then.
+    MoveNodes {
+        start: Point,
+        positions: Vec<(RwSignal<Point>, Point)>,
+    },
+    /// Dragging a new edge from a port to the canvas point `to`.
+    Connect {
+        from: (Side, PortRef),
+        to: Point,
+    },
+    /// Selecting the nodes in a rectangle, adding to the nodes `initial` selected before.
+    Select {
+        start: Point,
+        end: Point,
+        initial: HashSet<NodeId>,
+    },
+}
+
+/// A node graph editor. See [`node_graph`]
+pub struct NodeGraph {
+    data: ViewData,
+    graph: Graph,
+    nodes: Vec<(NodeId, Box<dyn View>, Scope)>,
+    view_fn: Box<dyn Fn(GraphNode) -> (Box<dyn View>, Scope)>,
+    /// The label views of the ports, which the port circles are painted next to.
+    ports: Rc<RefCell<HashMap<(Side, PortRef), Id>>>,
+    selection: RwSignal<HashSet<NodeId>>,
+    selected_edge: RwSignal<Option<Edge>>,
+    zoom: RwSignal<f64>,
+    pan: RwSignal<Vec2>,
+    zoom_range: RangeInclusive<f64>,
+    grid: f64,
+    gesture: Option<Gesture>,
+    size: Size,
+}
+
+/// A node graph editor for [`Graph`], for shader editors, pipeline tools and the like.
+///
+/// Each node shows the view `view_fn` makes for it, above its input ports on the left and its
+/// output ports on the right, at its position on a canvas which zooms and pans like a
+/// [`zoom_pan`](crate::views::zoom_pan). Dragging a node moves the selected nodes, snapped to
+/// a grid, dragging from a port connects it to a port of the same type, and dragging from an
+/// input picks its edge up. Dragging on the background selects the nodes in a rectangle,
+/// Shift adds to the selection, and Delete removes the selected nodes or edge.
+///
+/// ```ignore
+/// let graph = Graph::new();
+/// let color = graph.add_node(Point::new(20.0, 20.0), vec![], vec![Port::new("rgb", "vec3")]);
+/// let output = graph.add_node(Point::new(240.0, 40.0), vec![Port::new("rgb", "vec3")], vec![]);
+/// graph.connect(PortRef::new(color, 0), PortRef::new(output, 0)).unwrap();
+/// node_graph(graph, |node| label(move || format!("Node {:?}", node.id)))
+///     .style(|s| s.size_full())
+/// ```
+pub fn node_graph<V: View + 'static>(
+    graph: Graph,
+    view_fn: impl Fn(&GraphNode) -> V + 'static,
+) -> NodeGraph {
+    let id = Id::next();
+    let selection = create_rw_signal(HashSet::new());
+    let selected_edge = create_rw_signal(None);
+    let zoom = create_rw_signal(1.0);
+    let pan = create_rw_signal(Vec2::ZERO);
+    let ports = Rc::new(RefCell::new(HashMap::new()));
+
+    create_effect(move |_| {
+        let nodes = graph.nodes.get();
+        id.update_state(NodeGraphUpdate::Nodes(nodes));
+    });
+    create_effect(move |_| {
+        graph.edges.track();
+        selected_edge.track();
+        zoom.track();
+        pan.track();
+        id.request_paint();
+    });
+
+    let view_fn = {
+        let ports = ports.clone();
+        as_child_of_current_scope(move |node: GraphNode| -> Box<dyn View> {
+            let content = view_fn(&node);
+            Box::new(node_frame(&node, content, &ports, selection))
+        })
+    };
+    NodeGraph {
+        data: ViewData::new(id),
+        graph,
+        nodes: Vec::new(),
+        view_fn: Box::new(view_fn),
+        ports,
+        selection,
+        selected_edge,
+        zoom,
+        pan,
+        zoom_range: 0.1..=4.0,
+        grid: 10.0,
+        gesture: None,
+        size: Size::ZERO,
+    }
+    .class(NodeGraphClass)
+}
+
+fn node_frame(
+    node: &GraphNode,
+    content: impl View + 'static,
+    ports: &Rc<RefCell<HashMap<(Side, PortRef), Id>>>,
+    selection: RwSignal<HashSet<NodeId>>,
+) -> impl View {
+    let id = node.id;
+    let port_label = |side: Side, index: usize, port: &Port| {
+        let name = port.name.clone();
+        let view = label(move || name.clone()).style(move |s| match side {
+            Side::Input => s.padding_left(PORT_RADIUS * 2.0),
+            Side::Output => s.padding_right(PORT_RADIUS * 2.0),
+        });
+        ports
+            .borrow_mut()
+            .insert((side, PortRef::new(id, index)), view.id());
+        view
+    };
+    let inputs = v_stack_from_iter(
+        node.inputs
+            .iter()
+            .enumerate()
+            .map(|(index, port)| port_label(Side::Input, index, port)),
+    );
+    let outputs = v_stack_from_iter(
+        node.outputs
+            .iter()
+            .enumerate()
+            .map(|(index, port)| port_label(Side::Output, index, port)),
+    )
+    .style(|s| s.items_end());
+
+    let position = node.position;
+    v_stack((
+        content,
+        h_stack((inputs, outputs)).style(|s| s.justify_between().gap(12.0, 0.0)),
+    ))
+    .class(GraphNodeClass)
+    .style(move |s| {
+        let position = position.get();
+        let selected = selection.with(|selection| selection.contains(&id));
+        s.absolute()
+            .inset_left(position.x)
+            .inset_top(position.y)
+            .min_width(120.0)
+            .padding_vert(6.0)
+            .gap(0.0, 6.0)
+            .border(1.0)
+            .border_radius(6.0)
+            .border_color(NODE_BORDER_COLOR)
+            .background(Color::WHITE)
+            .apply_if(selected, |s| s.border_color(SELECTED_COLOR))
+    })
+}
+
+fn port_color(ty: &str) -> Color {
+    let mut hasher = FxHasher::default();
+    ty.hash(&mut hasher);
+    PORT_COLORS[(hasher.finish() % PORT_COLORS.len() as u64) as usize]
+}
+
+/// Rounds `point` to the grid, unless `grid` is 0.0.
+fn snap(point: Point, grid: f64) -> Point {
+    if grid <= 0.0 {
+        return point;
+    }
+    Point::new(
+        (point.x / grid).round() * grid,
+        (point.y / grid).round() * grid,
+    )
+}
+
+/// The curve of an edge, leaving the output to the right and entering the input from the left.
+fn edge_curve(from: Point, to: Point) -> CubicBez {
+    let reach = ((to.x - from.x).abs() / 2.0).max(40.0);
+    CubicBez::new(
+        from,
+        from + Vec2::new(reach, 0.0),
+        to - Vec2::new(reach, 0.0),
+        to,
+    )
+}
+
+impl NodeGraph {
+    pub fn graph(&self) -> Graph {
+        self.graph
+    }
+
+    /// The selected nodes.
+    pub fn selection(&self) -> RwSignal<HashSet<NodeId>> {
+        self.selection
+    }
+
+    pub fn selected_edge(&self) -> RwSignal<Option<Edge>> {
+        self.selected_edge
+    }
+
+    /// The zoom level, where 1.0 shows the nodes at their actual size.
+    pub fn zoom(&self) -> RwSignal<f64> {
+        self.zoom
+    }
+
+    /// Where the origin of the canvas is shown in the view.
+    pub fn pan(&self) -> RwSignal<Vec2> {
+        self.pan
+    }
+
+    /// Limits how far the pointer and touchpad zoom. Defaults to `0.1..=4.0`.
+    pub fn zoom_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.zoom_range = range;
+        self
+    }
+
+    /// Snaps the positions of the dragged nodes to a grid of this size, 0.0 turns the snapping
+    /// off. Defaults to 10.0.
+    pub fn snap_to_grid(mut self, grid: f64) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Removes the selected nodes and the selected edge from the graph.
+    pub fn delete_selection(&self) {
+        delete_selection(self.graph, self.selection, self.selected_edge);
+    }
+
+    fn canvas_point(&self, pos: Point) -> Point {
+        let zoom = self.zoom.get_untracked();
+        let pan = self.pan.get_untracked();
+        ((pos.to_vec2() - pan) / zoom).to_point()
+    }
+
+    /// The laid out rectangle of a view in the canvas, in canvas coordinates.
+    fn canvas_rect(&self, app_state: &mut AppState, id: Id) -> Rect {
+        let origin = app_state.get_layout_rect(self.id()).origin();
+        app_state.get_layout_rect(id) - origin.to_vec2()
+    }
+
+    fn node_rect(&self, app_state: &mut AppState, node: NodeId) -> Option<Rect> {
+        let (_, view, _) = self.nodes.iter().find(|(id, _, _)| *id == node)?;
+        Some(self.canvas_rect(app_state, view.id()))
+    }
+
+    fn port_point(&self, app_state: &mut AppState, side: Side, port: PortRef) -> Option<Point> {
+        let label = *self.ports.borrow().get(&(side, port))?;
+        let node = self.node_rect(app_state, port.node)?;
+        let label = self.canvas_rect(app_state, label);
+        let x = match side {
+            Side::Input => node.x0,
+            Side::Output => node.x1,
+        };
+        Some(Point::new(x, label.center().y))
+    }
+
+    fn port_at(&self, app_state: &mut AppState, point: Point) -> Option<(Side, PortRef)> {
+        let distance = HIT_DISTANCE / self.zoom.get_untracked();
+        let ports: Vec<(Side, PortRef)> = self.ports.borrow().keys().copied().collect();
+        ports.into_iter().find(|(side, port)| {
+            self.port_point(app_state, *side, *port)
+                .is_some_and(|p| p.distance(point) <= distance)
+        })
+    }
+
+    /// The topmost node at this point.
+    fn node_at(&self, app_state: &mut AppState, point: Point) -> Option<NodeId> {
+        self.nodes.iter().rev().find_map(|(id, view, _)| {
+            self.canvas_rect(app_state, view.id())
+                .contains(point)
+                .then_some(*id)
+        })
+    }
+
+    fn edge_points(&self, app_state: &mut AppState, edge: Edge) -> Option<(Point, Point)> {
+        let from = self.port_point(app_state, Side::Output, edge.from)?;
+        let to = self.port_point(app_state, Side::Input, edge.to)?;
+        Some((from, to))
+    }
+
+    fn edge_at(&self, app_state: &mut AppState, point: Point) -> Option<Edge> {
+        let distance = HIT_DISTANCE / self.zoom.get_untracked();
+        let edges = self.graph.edges.get_untracked();
+        edges.into_iter().find(|edge| {
+            self.edge_points(app_state, *edge)
+                .is_some_and(|(from, to)| {
+                    edge_curve(from, to).nearest(point, 0.1).distance_sq <= distance * distance
+                })
+        })
+    }
+
+    fn zoom_at(&self, pos: Point, factor: f64) {
+        let zoom = self.zoom.get_untracked();
+        let new_zoom = (zoom * factor).clamp(*self.zoom_range.start(), *self.zoom_range.end());
+        if new_zoom == zoom {
+            return;
+        }
+        // Keep the point under the pointer in place
+        let pan = self.pan.get_untracked();
+        let new_pan = pos.to_vec2() - (pos.to_vec2() - pan) * (new_zoom / zoom);
+        self.zoom.set(new_zoom);
+        self.pan.set(new_pan);
+    }
+
+    fn pointer_down(&mut self, app_state: &mut AppState, point: Point, shift: bool) {
+        if let Some(node) = self.node_at(app_state, point) {
+            self.selected_edge.set(None);
+            let selected = self.selection.with_untracked(|s| s.contains(&node));
+            if shift {
+                self.selection.update(|selection| {
+                    if !selection.remove(&node) {
+                        selection.insert(node);
+                    }
+                });
+            } else if !selected {
+                self.selection.set(HashSet::from([node]));
+            }
+            let selection = self.selection.get_untracked();
+            let positions = self.graph.nodes.with_untracked(|nodes| {
+                nodes
+                    .iter()
+                    .filter(|node| selection.contains(&node.id))
+                    .map(|node| (node.position, node.position.get_untracked()))
+                    .collect()
+            });
+            self.gesture = Some(Gesture::MoveNodes {
+                start: point,
+                positions,
+            });
+        } else if let Some(edge) = self.edge_at(app_state, point) {
+            self.selection.set(HashSet::new());
+            self.selected_edge.set(Some(edge));
+        } else {
+            let initial = if shift {
+                self.selection.get_untracked()
+            } else {
+                HashSet::new()
+            };
+            self.selection.set(initial.clone());
+            self.selected_edge.set(None);
+            self.gesture = Some(Gesture::Select {
+                start: point,
+                end: point,
+                initial,
+            });
+        }
+    }
+
+    fn start_connect(&mut self, port: (Side, PortRef), point: Point) {
+        let from = match port {
+            // Dragging from a connected input picks its edge up
+            (Side::Input, input) => match self.graph.input_edge(input) {
+                Some(edge) => {
+                    self.graph.disconnect(edge);
+                    (Side::Output, edge.from)
+                }
+                None => port,
+            },
+            (Side::Output, _) => port,
+        };
+        self.gesture = Some(Gesture::Connect { from, to: point });
+    }
+
+    fn finish_connect(&self, app_state: &mut AppState, from: (Side, PortRef), point: Point) {
+        let Some(to) = self.port_at(app_state, point) else {
+            return;
+        };
+        let edge = match (from, to) {
+            ((Side::Output, output), (Side::Input, input))
+            | ((Side::Input, input), (Side::Output, output)) => (output, input),
+            _ => return,
+        };
+        // Ports of different types simply don't connect
+        let _ = self.graph.connect(edge.0, edge.1);
+    }
+
+    fn pointer_move(&mut self, app_state: &mut AppState, pos: Point, point: Point) -> bool {
+        let Some(gesture) = self.gesture.as_mut() else {
+            return false;
+        };
+        match gesture {
+            Gesture::Pan { start, start_pan } => {
+                self.pan.set(*start_pan + (pos - *start));
+            }
+            Gesture::MoveNodes { start, positions } => {
+                let delta = point - *start;
+                let grid = self.grid;
+                for (position, start) in positions.iter() {
+                    let moved = snap(*start + delta, grid);
+                    if position.get_untracked() != moved {
+                        position.set(moved);
+                    }
+                }
+            }
+            Gesture::Connect { to, .. } => {
+                *to = point;
+                self.data.id().request_paint();
+            }
+            Gesture::Select {
+                start,
+                end,
+                initial,
+            } => {
+                *end = point;
+                let rect = Rect::from_points(*start, *end);
+                let mut selection = initial.clone();
+                for (id, view, _) in &self.nodes {
+                    let origin = app_state.get_layout_rect(self.data.id()).origin();
+                    let node = app_state.get_layout_rect(view.id()) - origin.to_vec2();
+                    if node.intersect(rect).area() > 0.0 {
+                        selection.insert(*id);
+                    }
+                }
+                if self.selection.with_untracked(|s| *s != selection) {
+                    self.selection.set(selection);
+                }
+                self.data.id().request_paint();
+            }
+        }
+        true
+    }
+}
+
+fn delete_selection(
+    graph: Graph,
+    selection: RwSignal<HashSet<NodeId>>,
+    selected_edge: RwSignal<Option<Edge>>,
+) {
+    if let Some(edge) = selected_edge.get_untracked() {
+        graph.disconnect(edge);
+        selected_edge.set(None);
+    }
+    let nodes = selection.get_untracked();
+    if !nodes.is_empty() {
+        for node in nodes {
+            graph.remove_node(node);
+        }
+        selection.set(HashSet::new());
+    }
+}
+
+impl View for NodeGraph {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for (_, child, _) in &self.nodes {
+            if for_each(child.as_ref()) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for (_, child, _) in &mut self.nodes {
+            if for_each(child.as_mut()) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for (_, child, _) in self.nodes.iter_mut().rev() {
+            if for_each(child.as_mut()) {
+                break;
+            }
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "NodeGraph".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<NodeGraphUpdate>() {
+            match *state {
+                NodeGraphUpdate::Nodes(nodes) => {
+                    let mut old: HashMap<NodeId, (Box<dyn View>, Scope)> = self
+                        .nodes
+                        .drain(..)
+                        .map(|(id, view, scope)| (id, (view, scope)))
+                        .collect();
+                    for node in nodes {
+                        let id = node.id;
+                        let (view, scope) = match old.remove(&id) {
+                            Some(child) => child,
+                            None => {
+                                let (view, scope) = (self.view_fn)(node);
+                                view.id().set_parent(self.id());
+                                view_children_set_parent_id(view.as_ref());
+                                (view, scope)
+                            }
+                        };
+                        self.nodes.push((id, view, scope));
+                    }
+                    for (id, (mut view, scope)) in old {
+                        self.ports
+                            .borrow_mut()
+                            .retain(|(_, port), _| port.node != id);
+                        cx.app_state.remove_view(view.as_mut());
+                        scope.dispose();
+                    }
+                    let alive: HashSet<NodeId> = self.nodes.iter().map(|(id, _, _)| *id).collect();
+                    if self
+                        .selection
+                        .with_untracked(|selection| !selection.is_subset(&alive))
+                    {
+                        self.selection
+                            .update(|selection| selection.retain(|id| alive.contains(id)));
+                    }
+                    cx.request_all(self.id());
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        if let Some(layout) = cx.app_state().get_layout(self.id()) {
+            self.size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        }
+        view::default_compute_layout(self, cx)
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let zoom = self.zoom.get_untracked();
+        let pan = self.pan.get_untracked();
+
+        if let Event::PointerDown(pointer) = &event {
+            if pointer.button.is_primary() && self.gesture.is_none() {
+                let point = self.canvas_point(pointer.pos);
+                if let Some(port) = self.port_at(cx.app_state, point) {
+                    cx.update_focus(self.id(), false);
+                    cx.update_active(self.id());
+                    self.start_connect(port, point);
+                    return EventPropagation::Stop;
+                }
+            }
+        }
+
+        if self.gesture.is_none() {
+            let child_event = event.clone().offset((pan.x, pan.y)).scale(zoom);
+            for (_, child, _) in self.nodes.iter_mut().rev() {
+                if cx
+                    .view_event(child.as_mut(), id_path, child_event.clone())
+                    .is_processed()
+                {
+                    return EventPropagation::Stop;
+                }
+            }
+        }
+
+        match &event {
+            Event::PointerWheel(wheel) => {
+                if wheel.modifiers.control_key() {
+                    self.zoom_at(wheel.pos, (-wheel.delta.y * ZOOM_PER_PIXEL).exp());
+                } else {
+                    self.pan.set(pan - wheel.delta);
+                }
+                EventPropagation::Stop
+            }
+            Event::PointerDown(pointer) if pointer.button.is_primary() => {
+                cx.update_focus(self.id(), false);
+                cx.update_active(self.id());
+                let point = self.canvas_point(pointer.pos);
+                self.pointer_down(cx.app_state, point, pointer.modifiers.shift_key());
+                EventPropagation::Stop
+            }
+            Event::PointerDown(pointer) if pointer.button.is_auxiliary() => {
+                cx.update_active(self.id());
+                self.gesture = Some(Gesture::Pan {
+                    start: pointer.pos,
+                    start_pan: pan,
+                });
+                EventPropagation::Stop
+            }
+            Event::PointerMove(pointer) => {
+                let point = self.canvas_point(pointer.pos);
+                if self.pointer_move(cx.app_state, pointer.pos, point) {
+                    EventPropagation::Stop
+                } else {
+                    EventPropagation::Continue
+                }
+            }
+            Event::PointerUp(pointer) => match self.gesture.take() {
+                Some(gesture) => {
+                    if let Gesture::Connect { from, .. } = gesture {
+                        let point = self.canvas_point(pointer.pos);
+                        self.finish_connect(cx.app_state, from, point);
+                    }
+                    self.id().request_paint();
+                    EventPropagation::Stop
+                }
+                None => EventPropagation::Continue,
+            },
+            Event::KeyDown(key_event)
+                if matches!(
+                    key_event.key.logical_key,
+                    Key::Named(NamedKey::Delete) | Key::Named(NamedKey::Backspace)
+                ) =>
+            {
+                delete_selection(self.graph, self.selection, self.selected_edge);
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let zoom = self.zoom.get_untracked();
+        let pan = self.pan.get_untracked();
+        cx.save();
+        cx.clip(&self.size.to_rect());
+        cx.offset((pan.x, pan.y));
+        cx.scale(zoom);
+
+        let selected_edge = self.selected_edge.get_untracked();
+        for edge in self.graph.edges.get_untracked() {
+            let Some((from, to)) = self.edge_points(cx.app_state, edge) else {
+                continue;
+            };
+            let color = if selected_edge == Some(edge) {
+                SELECTED_COLOR
+            } else {
+                self.graph
+                    .port(edge.from, true)
+                    .map_or(PENDING_EDGE_COLOR, |port| port_color(&port.ty))
+            };
+            cx.stroke(&edge_curve(from, to), color, EDGE_WIDTH);
+        }
+        if let Some(Gesture::Connect { from, to }) = &self.gesture {
+            if let Some(start) = self.port_point(cx.app_state, from.0, from.1) {
+                let (output, input) = match from.0 {
+                    Side::Output => (start, *to),
+                    Side::Input => (*to, start),
+                };
+                cx.stroke(&edge_curve(output, input), PENDING_EDGE_COLOR, EDGE_WIDTH);
+            }
+        }
+
+        for (_, child, _) in &mut self.nodes {
+            cx.paint_view(child.as_mut());
+        }
+
+        let ports: Vec<(Side, PortRef)> = self.ports.borrow().keys().copied().collect();
+        for (side, port) in ports {
+            let Some(point) = self.port_point(cx.app_state, side, port) else {
+                continue;
+            };
+            let Some(port) = self.graph.port(port, side == Side::Output) else {
+                continue;
+            };
+            let circle = Circle::new(point, PORT_RADIUS);
+            cx.fill(&circle, port_color(&port.ty), 0.0);
+            cx.stroke(&circle, Color::WHITE, 1.5);
+        }
+
+        if let Some(Gesture::Select { start, end, .. }) = &self.gesture {
+            let rect = Rect::from_points(*start, *end);
+            cx.fill(&rect, SELECTION_FILL, 0.0);
+            cx.stroke(&rect, SELECTED_COLOR, 1.0 / zoom);
+        }
+        cx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Point;
+
+    use super::{ConnectError, Edge, Graph, Port, PortRef};
+
+    #[test]
+    fn connect() {
+        let graph = Graph::new();
+        let a = graph.add_node(
+            Point::ZERO,
+            vec![],
+            vec![Port::new("out", "float"), Port::new("rgb", "vec3")],
+        );
+        let b = graph.add_node(Point::ZERO, vec![Port::new("in", "float")], vec![]);
+        let c = graph.add_node(
+            Point::ZERO,
+            vec![Port::new("in", "float")],
+            vec![Port::new("out", "float")],
+        );
+
+        assert_eq!(
+            graph.connect(PortRef::new(a, 1), PortRef::new(b, 0)),
+            Err(ConnectError::TypeMismatch)
+        );
+        assert_eq!(
+            graph.connect(PortRef::new(c, 0), PortRef::new(c, 0)),
+            Err(ConnectError::SameNode)
+        );
+        assert_eq!(
+            graph.connect(PortRef::new(a, 2), PortRef::new(b, 0)),
+            Err(ConnectError::NoSuchPort)
+        );
+
+        graph
+            .connect(PortRef::new(a, 0), PortRef::new(b, 0))
+            .unwrap();
+        graph
+            .connect(PortRef::new(a, 0), PortRef::new(c, 0))
+            .unwrap();
+        // An input takes a single edge
+        graph
+            .connect(PortRef::new(c, 0), PortRef::new(b, 0))
+            .unwrap();
+        assert_eq!(
+            graph.edges().get_untracked(),
+            vec![
+                Edge {
+                    from: PortRef::new(a, 0),
+                    to: PortRef::new(c, 0)
+                },
+                Edge {
+                    from: PortRef::new(c, 0),
+                    to: PortRef::new(b, 0)
+                },
+            ]
+        );
+
+        graph.remove_node(c);
+        assert!(graph.edges().get_untracked().is_empty());
+        assert_eq!(graph.nodes().get_untracked().len(), 2);
+    }
+}
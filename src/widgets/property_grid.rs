@@ -0,0 +1,467 @@
+use std::{collections::HashSet, rc::Rc};
+
+use floem_peniko::Color;
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+
+use crate::{
+    event::EventListener,
+    id::Id,
+    keyboard::{Key, ModifiersState, NamedKey},
+    style_class,
+    unit::UnitExt,
+    view::{View, ViewData},
+    views::{
+        container_box, empty, h_stack, label, popover, scroll, v_stack, v_stack_from_iter,
+        Decorators, Stack,
+    },
+    widgets::{checkbox, text_input, tooltip},
+};
+
+style_class!(pub PropertyGridClass);
+style_class!(pub PropertyGroupHeaderClass);
+style_class!(pub PropertyRowClass);
+style_class!(pub PropertyNameClass);
+
+enum Editor {
+    Text(RwSignal<String>),
+    Number {
+        value: RwSignal<f64>,
+        min: f64,
+        max: f64,
+        step: f64,
+    },
+    Bool(RwSignal<bool>),
+    Choice {
+        value: RwSignal<usize>,
+        options: Rc<Vec<String>>,
+    },
+    Color(RwSignal<Color>),
+}
+
+/// A row of a [`property_grid`], editing one value.
+pub struct Property {
+    name: String,
+    group: Option<String>,
+    description: Option<String>,
+    editor: Editor,
+}
+
+impl Property {
+    fn new(name: impl Into<String>, editor: Editor) -> Self {
+        Property {
+            name: name.into(),
+            group: None,
+            description: None,
+            editor,
+        }
+    }
+
+    /// Edits `value` in a text field.
+    pub fn text(name: impl Into<String>, value: RwSignal<String>) -> Self {
+        Self::new(name, Editor::Text(value))
+    }
+
+    /// Edits `value` in a text field taking numbers, which the arrow keys step up and down.
+    /// See [`Property::range`] and [`Property::step`]
+    pub fn number(name: impl Into<String>, value: RwSignal<f64>) -> Self {
+        Self::new(
+            name,
+            Editor::Number {
+                value,
+                min: f64::NEG_INFINITY,
+                max: f64::INFINITY,
+                step: 1.0,
+            },
+        )
+    }
+
+    /// Edits `value` with a checkbox.
+    pub fn bool(name: impl Into<String>, value: RwSignal<bool>) -> Self {
+        Self::new(name, Editor::Bool(value))
+    }
+
+    /// Edits `value`, the index of one of `options`, with a dropdown listing the options.
+    pub fn choice<S: Into<String>>(
+        name: impl Into<String>,
+        value: RwSignal<usize>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let options = Rc::new(options.into_iter().map(Into::into).collect());
+        Self::new(name, Editor::Choice { value, options })
+    }
+
+    /// Edits `value` as a `#rrggbb` or `#rrggbbaa` hex code, next to a swatch of the color.
+    pub fn color(name: impl Into<String>, value: RwSignal<Color>) -> Self {
+        Self::new(name, Editor::Color(value))
+    }
+
+    /// Places the property under a collapsible header with the other properties of `group`.
+    /// Groups are shown in the order of their first property, after the ungrouped properties.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Shown in a tooltip over the name, and matched by the search.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Clamps the value of a [number](Property::number) property.
+    pub fn range(mut self, range: std::ops::RangeInclusive<f64>) -> Self {
+        if let Editor::Number { min, max, .. } = &mut self.editor {
+            (*min, *max) = range.into_inner();
+        }
+        self
+    }
+
+    /// Sets how much the arrow keys change a [number](Property::number) property, 1 by default.
+    pub fn step(mut self, step: f64) -> Self {
+        if let Editor::Number { step: s, .. } = &mut self.editor {
+            *s = step;
+        }
+        self
+    }
+
+    /// The lowercased text the search matches against.
+    fn search_text(&self) -> String {
+        [
+            Some(&self.name),
+            self.group.as_ref(),
+            self.description.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|text| text.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
+
+/// Formats a color as `#rrggbb`, or `#rrggbbaa` if it's translucent.
+fn color_to_hex(color: Color) -> String {
+    let hex = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+    if color.a == 255 {
+        hex
+    } else {
+        format!("{hex}{:02x}", color.a)
+    }
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa`, where the `#` is optional.
+fn color_from_hex(text: &str) -> Option<Color> {
+    let hex = text.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::rgba8(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// A text field editing `value` through `format` and `parse`.
+///
+/// The text isn't replaced while it already parses to the value, so typing `1.` into a
+/// number field isn't reformatted to `1`. It's formatted again when the field loses focus.
+fn parsed_input<T: Clone + PartialEq + 'static>(
+    value: RwSignal<T>,
+    format: fn(T) -> String,
+    parse: impl Fn(&str) -> Option<T> + 'static,
+) -> impl View {
+    let buffer = create_rw_signal(format(value.get_untracked()));
+    let parse = Rc::new(parse);
+    {
+        let parse = parse.clone();
+        create_effect(move |_| {
+            let value = value.get();
+            if buffer.with_untracked(|text| parse(text)).as_ref() != Some(&value) {
+                buffer.set(format(value));
+            }
+        });
+    }
+    create_effect(move |_| {
+        if let Some(parsed) = buffer.with(|text| parse(text)) {
+            if value.with_untracked(|value| *value != parsed) {
+                value.set(parsed);
+            }
+        }
+    });
+    text_input(buffer).on_event_cont(EventListener::FocusLost, move |_| {
+        buffer.set(format(value.get_untracked()));
+    })
+}
+
+fn editor_view(editor: Editor) -> Box<dyn View> {
+    match editor {
+        Editor::Text(value) => Box::new(text_input(value).style(|s| s.width_full())),
+        Editor::Number {
+            value,
+            min,
+            max,
+            step,
+        } => {
+            let nudge = move |by: f64| value.update(|value| *value = (*value + by).clamp(min, max));
+            Box::new(
+                parsed_input(
+                    value,
+                    |value| value.to_string(),
+                    move |text| {
+                        let number = text.trim().parse::<f64>().ok()?;
+                        Some(number.clamp(min, max))
+                    },
+                )
+                .on_key_down(
+                    Key::Named(NamedKey::ArrowUp),
+                    ModifiersState::empty(),
+                    move |_| nudge(step),
+                )
+                .on_key_down(
+                    Key::Named(NamedKey::ArrowDown),
+                    ModifiersState::empty(),
+                    move |_| nudge(-step),
+                )
+                .style(|s| s.width_full()),
+            )
+        }
+        Editor::Bool(value) => {
+            Box::new(checkbox(move || value.get()).on_update(move |checked| value.set(checked)))
+        }
+        Editor::Choice { value, options } => {
+            let open = create_rw_signal(false);
+            let current = options.clone();
+            let anchor = label(move || {
+                let name = current.get(value.get()).cloned().unwrap_or_default();
+                format!("{name} ▾")
+            })
+            .style(|s| s.width_full());
+            Box::new(
+                popover(anchor, move || {
+                    v_stack_from_iter(options.iter().cloned().enumerate().map(|(index, option)| {
+                        label(move || option.clone())
+                            .on_click_stop(move |_| {
+                                value.set(index);
+                                open.set(false);
+                            })
+                            .style(move |s| {
+                                s.padding_horiz(8.0)
+                                    .padding_vert(4.0)
+                                    .hover(|s| s.background(Color::rgba8(0, 0, 0, 15)))
+                                    .apply_if(value.get() == index, |s| s.font_bold())
+                            })
+                    }))
+                })
+                .show(move || open.get())
+                .on_toggle(move |is_open| open.set(is_open))
+                .style(|s| s.width_full()),
+            )
+        }
+        Editor::Color(value) => Box::new(
+            h_stack((
+                empty().style(move |s| {
+                    s.size(16.0, 16.0)
+                        .background(value.get())
+                        .border(1.0)
+                        .border_color(Color::GRAY)
+                }),
+                parsed_input(value, color_to_hex, color_from_hex).style(|s| s.flex_grow(1.0)),
+            ))
+            .style(|s| s.items_center().gap(6.0, 0.0).width_full()),
+        ),
+    }
+}
+
+/// An editable grid of properties. See [`property_grid`]
+pub struct PropertyGrid {
+    data: ViewData,
+    child: Stack,
+    search: RwSignal<String>,
+}
+
+/// An editable grid with a row for each of `properties`, showing its name next to an editor
+/// for its value, such as the inspector of an editor or a settings panel.
+///
+/// A search field above the rows filters them by their name, group and description.
+/// Grouped properties are shown under headers which collapse the group when clicked.
+///
+/// ```ignore
+/// let name = create_rw_signal("Button".to_string());
+/// let width = create_rw_signal(120.0);
+/// let visible = create_rw_signal(true);
+/// let align = create_rw_signal(0);
+/// let color = create_rw_signal(Color::BLACK);
+/// property_grid([
+///     Property::text("Name", name),
+///     Property::number("Width", width).range(0.0..=1000.0).group("Layout"),
+///     Property::choice("Align", align, ["Start", "Center", "End"]).group("Layout"),
+///     Property::bool("Visible", visible).group("Appearance"),
+///     Property::color("Color", color).group("Appearance"),
+/// ])
+/// ```
+pub fn property_grid(properties: impl IntoIterator<Item = Property>) -> PropertyGrid {
+    let search = create_rw_signal(String::new());
+    let collapsed = create_rw_signal(HashSet::<String>::new());
+    let query = move || search.with(|search| search.trim().to_lowercase());
+
+    // The ungrouped properties come first, then the groups in order of appearance
+    let mut groups: Vec<(Option<String>, Vec<Property>)> = vec![(None, Vec::new())];
+    for property in properties {
+        match groups
+            .iter_mut()
+            .find(|(group, _)| *group == property.group)
+        {
+            Some((_, properties)) => properties.push(property),
+            None => groups.push((property.group.clone(), vec![property])),
+        }
+    }
+
+    let sections = groups.into_iter().map(|(group, properties)| {
+        let texts: Rc<Vec<String>> =
+            Rc::new(properties.iter().map(Property::search_text).collect());
+        let any_match = {
+            let texts = texts.clone();
+            move || {
+                let query = query();
+                texts.iter().any(|text| text.contains(&query))
+            }
+        };
+        let is_collapsed = {
+            let group = group.clone();
+            move || {
+                // Searching shows the matches in collapsed groups too
+                query().is_empty()
+                    && group
+                        .as_ref()
+                        .map_or(false, |group| collapsed.with(|c| c.contains(group)))
+            }
+        };
+
+        let header: Box<dyn View> = match group.clone() {
+            Some(group) => {
+                let title = group.clone();
+                let is_collapsed = is_collapsed.clone();
+                let any_match = any_match.clone();
+                Box::new(
+                    label(move || {
+                        let arrow = if is_collapsed() { "▸" } else { "▾" };
+                        format!("{arrow} {title}")
+                    })
+                    .class(PropertyGroupHeaderClass)
+                    .on_click_stop(move |_| {
+                        collapsed.update(|collapsed| {
+                            if !collapsed.remove(&group) {
+                                collapsed.insert(group.clone());
+                            }
+                        });
+                    })
+                    .style(move |s| {
+                        s.font_bold()
+                            .padding_vert(4.0)
+                            .apply_if(!any_match(), |s| s.hide())
+                    }),
+                )
+            }
+            None => Box::new(empty()),
+        };
+
+        let rows =
+            v_stack_from_iter(properties.into_iter().enumerate().map(|(index, property)| {
+                let texts = texts.clone();
+                let name = property.name.clone();
+                let name_label = label(move || name.clone()).class(PropertyNameClass);
+                let name_view: Box<dyn View> = match property.description.clone() {
+                    Some(description) => Box::new(tooltip(name_label, move || {
+                        let description = description.clone();
+                        label(move || description.clone())
+                    })),
+                    None => Box::new(name_label),
+                };
+                h_stack((
+                    container_box(name_view).style(|s| s.width(40.pct()).text_ellipsis()),
+                    container_box(editor_view(property.editor)).style(|s| s.flex_grow(1.0)),
+                ))
+                .class(PropertyRowClass)
+                .style(move |s| {
+                    let visible = texts[index].contains(&query());
+                    s.items_center()
+                        .gap(8.0, 0.0)
+                        .padding_vert(2.0)
+                        .apply_if(!visible, |s| s.hide())
+                })
+            }))
+            .style(move |s| s.flex_col().apply_if(is_collapsed(), |s| s.hide()));
+
+        v_stack((container_box(header), rows))
+    });
+
+    let child = v_stack((
+        text_input(search)
+            .placeholder("Search")
+            .style(|s| s.width_full()),
+        scroll(v_stack_from_iter(sections).style(|s| s.flex_col().width_full()))
+            .style(|s| s.flex_grow(1.0).width_full()),
+    ))
+    .style(|s| s.gap(0.0, 8.0).size_full());
+
+    PropertyGrid {
+        data: ViewData::new(Id::next()),
+        child,
+        search,
+    }
+    .class(PropertyGridClass)
+}
+
+impl PropertyGrid {
+    /// The text of the search field.
+    pub fn search(&self) -> RwSignal<String> {
+        self.search
+    }
+}
+
+impl View for PropertyGrid {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "PropertyGrid".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        let color = Color::rgba8(0x12, 0xab, 0xff, 0x80);
+        assert_eq!(color_to_hex(color), "#12abff80");
+        assert_eq!(color_from_hex("#12abff80"), Some(color));
+        assert_eq!(
+            color_from_hex("12ABFF"),
+            Some(Color::rgb8(0x12, 0xab, 0xff))
+        );
+        assert_eq!(color_to_hex(Color::rgb8(0x12, 0xab, 0xff)), "#12abff");
+        assert_eq!(color_from_hex("#12abf"), None);
+        assert_eq!(color_from_hex("#12abfg"), None);
+    }
+}
@@ -1,5 +1,6 @@
 //! A toggle button widget. An example can be found in widget-gallery/button in the floem examples.
 
+use accesskit::{Action, ActionData, Role};
 use floem_peniko::Color;
 use floem_reactive::create_effect;
 use floem_renderer::Renderer;
@@ -7,6 +8,7 @@ use floem_winit::keyboard::{Key, NamedKey};
 use kurbo::{Circle, Point, RoundedRect};
 
 use crate::{
+    accessibility::AccessCx,
     prop, prop_extracter,
     style::{Background, BorderRadius, Foreground, Height},
     style_class,
@@ -194,6 +196,18 @@ impl View for Slider {
                     self.held = false;
                     false
                 }
+                crate::event::Event::AccessibilityAction { action, data } => {
+                    cx.app_state_mut().request_layout(self.id());
+                    match (action, data) {
+                        (Action::Increment, _) => self.percent += 10.,
+                        (Action::Decrement, _) => self.percent -= 10.,
+                        (Action::SetValue, Some(ActionData::NumericValue(value))) => {
+                            self.percent = value as f32
+                        }
+                        _ => {}
+                    }
+                    true
+                }
                 crate::event::Event::KeyDown(event) => {
                     if event.key.logical_key == Key::Named(NamedKey::ArrowLeft) {
                         cx.app_state_mut().request_layout(self.id());
@@ -224,6 +238,16 @@ impl View for Slider {
         EventPropagation::Continue
     }
 
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::Slider);
+        cx.set_numeric_value(self.percent as f64, 0.0..=100.0, 10.0);
+        if !self.disable_events {
+            cx.add_action(Action::Increment);
+            cx.add_action(Action::Decrement);
+            cx.add_action(Action::SetValue);
+        }
+    }
+
     fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
         let style = cx.style();
         let mut paint = false;
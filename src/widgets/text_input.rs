@@ -6,6 +6,7 @@ use floem_reactive::RwSignal;
 
 style_class!(pub TextInputClass);
 style_class!(pub PlaceholderTextClass);
+style_class!(pub TextInputErrorClass);
 
 pub fn text_input(buffer: RwSignal<String>) -> TextInput {
     views::text_input(buffer).class(TextInputClass)
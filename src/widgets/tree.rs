@@ -0,0 +1,663 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    rc::Rc,
+    time::Duration,
+};
+
+use floem_peniko::Color;
+use floem_reactive::{
+    create_effect, create_memo, create_rw_signal, create_trigger, RwSignal, Trigger,
+};
+use kurbo::Rect;
+
+use crate::{
+    action::exec_after,
+    event::{Event, EventListener, EventPropagation},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style_class,
+    view::{View, ViewData},
+    views::{
+        container, empty, h_stack, h_stack_from_iter, label, scroll, virtual_stack, Decorators,
+        Scroll, VirtualDirection, VirtualItemSize,
+    },
+};
+
+style_class!(pub TreeClass);
+style_class!(pub TreeRowClass);
+
+const ROW_HEIGHT: f64 = 22.0;
+const INDENT: f64 = 16.0;
+const GUIDE_COLOR: Color = Color::rgba8(0, 0, 0, 40);
+const SELECTED_COLOR: Color = Color::rgba8(0, 0, 0, 30);
+const DROP_COLOR: Color = Color::rgb8(0x3b, 0x82, 0xf6);
+const DROP_INTO_COLOR: Color = Color::rgba8(0x3b, 0x82, 0xf6, 50);
+/// How long a node has to be dragged over a collapsed node to expand it.
+const EXPAND_DELAY: Duration = Duration::from_millis(600);
+
+#[derive(Clone, PartialEq)]
+struct TreeRow<T> {
+    node: T,
+    depth: usize,
+    expandable: bool,
+    expanded: bool,
+}
+
+/// Loads the children of the nodes of a [`tree`] the first time they're needed.
+struct Loader<'a, T> {
+    children_fn: &'a dyn Fn(&T) -> Vec<T>,
+    is_leaf: Option<&'a dyn Fn(&T) -> bool>,
+    cache: &'a mut HashMap<T, Rc<Vec<T>>>,
+}
+
+impl<T: Clone + Eq + Hash> Loader<'_, T> {
+    /// Whether the node may have children, without loading them unless it's a leaf.
+    fn expandable(&self, node: &T) -> bool {
+        if self.is_leaf.is_some_and(|is_leaf| is_leaf(node)) {
+            return false;
+        }
+        // Until they're loaded, the children are assumed to exist
+        self.cache
+            .get(node)
+            .map_or(true, |children| !children.is_empty())
+    }
+
+    fn children(&mut self, node: &T) -> Rc<Vec<T>> {
+        if let Some(children) = self.cache.get(node) {
+            return children.clone();
+        }
+        let children = Rc::new((self.children_fn)(node));
+        self.cache.insert(node.clone(), children.clone());
+        children
+    }
+}
+
+fn push_rows<T: Clone + Eq + Hash>(
+    rows: &mut im::Vector<TreeRow<T>>,
+    node: &T,
+    depth: usize,
+    expanded: &HashSet<T>,
+    loader: &mut Loader<T>,
+) {
+    let expandable = loader.expandable(node);
+    let is_expanded = expandable && expanded.contains(node);
+    let children = is_expanded.then(|| loader.children(node));
+    rows.push_back(TreeRow {
+        node: node.clone(),
+        depth,
+        // Expanding a node may show it has no children after all
+        expandable: expandable && children.as_ref().map_or(true, |c| !c.is_empty()),
+        expanded: is_expanded,
+    });
+    for child in children.iter().flat_map(|children| children.iter()) {
+        push_rows(rows, child, depth + 1, expanded, loader);
+    }
+}
+
+/// What a key pressed in a [`tree`] does.
+enum TreeAction<T> {
+    /// Selects a node, if there's one in that direction.
+    Select(Option<T>),
+    Toggle(T),
+}
+
+/// The row of the parent of the row at `index`.
+fn parent_row<T>(rows: &im::Vector<TreeRow<T>>, index: usize) -> Option<usize> {
+    let depth = rows.get(index)?.depth;
+    (0..index).rev().find(|&row| rows[row].depth < depth)
+}
+
+/// The row after the descendants of the row at `index`.
+fn subtree_end<T>(rows: &im::Vector<TreeRow<T>>, index: usize) -> usize {
+    let depth = rows[index].depth;
+    (index + 1..rows.len())
+        .find(|&row| rows[row].depth <= depth)
+        .unwrap_or(rows.len())
+}
+
+/// The node after the row at `index` with the same parent, if there's one.
+fn next_sibling<T: Clone>(rows: &im::Vector<TreeRow<T>>, index: usize) -> Option<T> {
+    let next = rows.get(subtree_end(rows, index))?;
+    (next.depth == rows[index].depth).then(|| next.node.clone())
+}
+
+/// Where a node dragged in a [`tree`] was dropped. See [`Tree::on_drop`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeDrop<T> {
+    /// The dragged node.
+    pub node: T,
+    /// The node it's moved into.
+    pub parent: T,
+    /// The child of `parent` it's placed before, or `None` to place it after the last child.
+    pub before: Option<T>,
+}
+
+/// The part of a row a node is dragged over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropZone {
+    Before,
+    Into,
+    After,
+}
+
+impl DropZone {
+    /// The zone at `y` in a row.
+    fn at(y: f64) -> DropZone {
+        if y < ROW_HEIGHT / 4.0 {
+            DropZone::Before
+        } else if y > ROW_HEIGHT * 3.0 / 4.0 {
+            DropZone::After
+        } else {
+            DropZone::Into
+        }
+    }
+}
+
+/// Where `node` goes when it's dropped on the `zone` of the row at `index`, unless it would go
+/// into itself or stay where it is.
+fn drop_at<T: Clone + PartialEq>(
+    rows: &im::Vector<TreeRow<T>>,
+    node: &T,
+    index: usize,
+    zone: DropZone,
+) -> Option<TreeDrop<T>> {
+    let from = rows.iter().position(|row| row.node == *node)?;
+    if (from..subtree_end(rows, from)).contains(&index) {
+        return None;
+    }
+    let row = rows.get(index)?;
+    let parent_of = |index| parent_row(rows, index).map(|parent| rows[parent].node.clone());
+    let (parent, before) = match zone {
+        DropZone::Into => (row.node.clone(), None),
+        DropZone::Before => (parent_of(index)?, Some(row.node.clone())),
+        // Dropping after an expanded node makes the node its first child
+        DropZone::After if row.expanded && index + 1 < subtree_end(rows, index) => {
+            (row.node.clone(), Some(rows[index + 1].node.clone()))
+        }
+        DropZone::After => (parent_of(index)?, next_sibling(rows, index)),
+    };
+    let unmoved = parent_of(from).as_ref() == Some(&parent) && before == next_sibling(rows, from);
+    (!unmoved).then(|| TreeDrop {
+        node: node.clone(),
+        parent,
+        before,
+    })
+}
+
+/// A hierarchical view of nodes. See [`tree`]
+pub struct Tree<T: 'static> {
+    data: ViewData,
+    child: Scroll,
+    expanded: RwSignal<HashSet<T>>,
+    selected: RwSignal<Option<T>>,
+    is_leaf: RwSignal<Option<Rc<dyn Fn(&T) -> bool>>>,
+    on_drop: RwSignal<Option<Rc<dyn Fn(TreeDrop<T>)>>>,
+    can_drop: RwSignal<Option<Rc<dyn Fn(&TreeDrop<T>) -> bool>>>,
+    cache: Rc<RefCell<HashMap<T, Rc<Vec<T>>>>>,
+    reload: Trigger,
+}
+
+/// Shows `root` and its descendants as an expandable tree, with `view_fn` making the view of
+/// each node, indented under its parent with guide lines.
+///
+/// `children_fn` is only called for a node the first time it's expanded, so a file explorer
+/// only reads the directories it shows, and only the visible rows are created. Until a node is
+/// expanded it's shown as expandable, unless [`Tree::is_leaf`] says otherwise.
+///
+/// Clicking a row selects it, and clicking its arrow or double clicking it expands or collapses
+/// it. The Up and Down keys move the selection, Right expands the selected node or moves to its
+/// first child, and Left collapses it or moves to its parent.
+///
+/// With [`Tree::on_drop`], the nodes can be dragged onto other nodes or between them to move
+/// them.
+///
+/// ```ignore
+/// tree(
+///     PathBuf::from("."),
+///     |dir| std::fs::read_dir(dir).into_iter().flatten().flatten().map(|entry| entry.path()),
+///     |path| {
+///         let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+///         label(move || name.clone())
+///     },
+/// )
+/// .is_leaf(|path| !path.is_dir())
+/// .style(|s| s.size_full())
+/// ```
+pub fn tree<T, I, V>(
+    root: T,
+    children_fn: impl Fn(&T) -> I + 'static,
+    view_fn: impl Fn(&T) -> V + 'static,
+) -> Tree<T>
+where
+    T: Clone + Eq + Hash + 'static,
+    I: IntoIterator<Item = T>,
+    V: View + 'static,
+{
+    let id = Id::next();
+    // The root is expanded to start with
+    let expanded = create_rw_signal(HashSet::from([root.clone()]));
+    let selected = create_rw_signal(None::<T>);
+    let is_leaf = create_rw_signal(None::<Rc<dyn Fn(&T) -> bool>>);
+    let on_drop = create_rw_signal(None::<Rc<dyn Fn(TreeDrop<T>)>>);
+    let can_drop = create_rw_signal(None::<Rc<dyn Fn(&TreeDrop<T>) -> bool>>);
+    let dragging = create_rw_signal(None::<T>);
+    let drop_target = create_rw_signal(None::<(T, DropZone)>);
+    let cache = Rc::new(RefCell::new(HashMap::new()));
+    let reload = create_trigger();
+    let children_fn = move |node: &T| children_fn(node).into_iter().collect::<Vec<_>>();
+
+    let rows = {
+        let cache = cache.clone();
+        create_memo(move |_| {
+            reload.track();
+            let is_leaf = is_leaf.get();
+            let mut cache = cache.borrow_mut();
+            let mut loader = Loader {
+                children_fn: &children_fn,
+                is_leaf: is_leaf.as_deref(),
+                cache: &mut *cache,
+            };
+            let mut rows = im::Vector::new();
+            expanded.with(|expanded| push_rows(&mut rows, &root, 0, expanded, &mut loader));
+            rows
+        })
+    };
+
+    let toggle = move |node: &T| {
+        expanded.update(|expanded| {
+            if !expanded.remove(node) {
+                expanded.insert(node.clone());
+            }
+        });
+    };
+
+    // Where the dragged node would go if it was dropped on the `zone` of `node`
+    let drop_on = move |node: &T, zone: DropZone| {
+        let dragged = dragging.get_untracked()?;
+        let drop = rows.with_untracked(|rows| {
+            let index = rows.iter().position(|row| row.node == *node)?;
+            drop_at(rows, &dragged, index, zone)
+        })?;
+        let allowed = can_drop
+            .with_untracked(|can_drop| can_drop.as_ref().map_or(true, |can_drop| can_drop(&drop)));
+        allowed.then_some(drop)
+    };
+
+    let rows_view = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || rows.get(),
+        |row: &TreeRow<T>| (row.node.clone(), row.expanded, row.expandable),
+        move |row| {
+            let guides = h_stack_from_iter((0..row.depth).map(|_| {
+                container(empty().style(|s| s.width(1.0).height_full().background(GUIDE_COLOR)))
+                    .style(|s| s.width(INDENT).height_full().justify_center())
+            }));
+            let arrow = match (row.expandable, row.expanded) {
+                (true, true) => "▾",
+                (true, false) => "▸",
+                (false, _) => "",
+            };
+            let toggle_node = row.node.clone();
+            let open_node = row.node.clone();
+            let select_node = row.node.clone();
+            let drag_node = row.node.clone();
+            let selected_node = row.node.clone();
+            let over_node = row.node.clone();
+            let drop_node = row.node.clone();
+            let target_node = row.node.clone();
+            let expandable = row.expandable;
+            let collapsed = row.expandable && !row.expanded;
+            let row_view = h_stack((
+                guides,
+                label(move || arrow)
+                    .on_click_stop(move |_| toggle(&toggle_node))
+                    .style(|s| s.width(INDENT).justify_center()),
+                view_fn(&row.node),
+            ))
+            .class(TreeRowClass)
+            .on_click_stop(move |_| selected.set(Some(select_node.clone())))
+            .on_double_click_stop(move |_| {
+                if expandable {
+                    toggle(&open_node);
+                }
+            })
+            .on_event_cont(EventListener::DragStart, move |_| {
+                dragging.set(Some(drag_node.clone()));
+            })
+            .on_event_cont(EventListener::DragOver, move |event| {
+                let Some(pos) = event.point() else {
+                    return;
+                };
+                let zone = DropZone::at(pos.y);
+                let target = drop_on(&over_node, zone).map(|_| (over_node.clone(), zone));
+                if drop_target.with_untracked(|current| *current == target) {
+                    return;
+                }
+                let expands = collapsed && zone == DropZone::Into && target.is_some();
+                drop_target.set(target);
+                if expands {
+                    let node = over_node.clone();
+                    exec_after(EXPAND_DELAY, move |_| {
+                        let still_over = drop_target.with_untracked(|target| {
+                            target.as_ref() == Some(&(node.clone(), zone))
+                        });
+                        if still_over {
+                            expanded.update(|expanded| {
+                                expanded.insert(node);
+                            });
+                        }
+                    });
+                }
+            })
+            .on_event(EventListener::Drop, move |_| {
+                let zone = drop_target.with_untracked(|target| {
+                    target
+                        .as_ref()
+                        .filter(|(node, _)| *node == drop_node)
+                        .map(|(_, zone)| *zone)
+                });
+                let Some(drop) = zone.and_then(|zone| drop_on(&drop_node, zone)) else {
+                    return EventPropagation::Continue;
+                };
+                drop_target.set(None);
+                if let Some(on_drop) = on_drop.get_untracked() {
+                    on_drop(drop);
+                }
+                // Processing the drop keeps the dragged row from sliding back
+                EventPropagation::Stop
+            })
+            .on_event_cont(EventListener::DragEnd, move |_| {
+                dragging.set(None);
+                drop_target.set(None);
+            })
+            .style(move |s| {
+                let is_selected =
+                    selected.with(|selected| selected.as_ref() == Some(&selected_node));
+                let zone = drop_target.with(|target| {
+                    target
+                        .as_ref()
+                        .filter(|(node, _)| *node == target_node)
+                        .map(|(_, zone)| *zone)
+                });
+                s.items_center()
+                    .height(ROW_HEIGHT)
+                    .padding_left(4.0)
+                    .apply_if(is_selected, |s| s.background(SELECTED_COLOR))
+                    .apply_if(zone == Some(DropZone::Into), |s| {
+                        s.background(DROP_INTO_COLOR)
+                    })
+                    .apply_if(zone == Some(DropZone::Before), |s| {
+                        s.border_top(2.0).border_color(DROP_COLOR)
+                    })
+                    .apply_if(zone == Some(DropZone::After), |s| {
+                        s.border_bottom(2.0).border_color(DROP_COLOR)
+                    })
+            });
+            let row_id = row_view.id();
+            create_effect(move |_| {
+                if on_drop.with(Option::is_some) {
+                    row_id.draggable();
+                }
+            });
+            row_view
+        },
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = scroll(rows_view)
+        .ensure_visible(move || {
+            let index = selected.with(|selected| {
+                let selected = selected.as_ref()?;
+                rows.with_untracked(|rows| rows.iter().position(|row| row.node == *selected))
+            });
+            index.map_or(Rect::ZERO, |index| {
+                Rect::new(0.0, 0.0, 1.0, ROW_HEIGHT).with_origin((0.0, index as f64 * ROW_HEIGHT))
+            })
+        })
+        .keyboard_navigatable()
+        .on_event(EventListener::KeyDown, move |event| {
+            let Event::KeyDown(key_event) = event else {
+                return EventPropagation::Continue;
+            };
+            let Key::Named(key) = &key_event.key.logical_key else {
+                return EventPropagation::Continue;
+            };
+            // The rows are read before expanding or collapsing a node, which changes them
+            let action = rows.with_untracked(|rows| {
+                let current = selected.with_untracked(|selected| {
+                    let selected = selected.as_ref()?;
+                    rows.iter().position(|row| row.node == *selected)
+                });
+                let select = |index: Option<usize>| {
+                    Some(TreeAction::Select(
+                        index.map(|index| rows[index].node.clone()),
+                    ))
+                };
+                match (key, current) {
+                    (NamedKey::ArrowDown, Some(index)) => {
+                        select(Some((index + 1).min(rows.len() - 1)))
+                    }
+                    (NamedKey::ArrowUp, Some(index)) => select(Some(index.saturating_sub(1))),
+                    (NamedKey::ArrowDown | NamedKey::Home, None) | (NamedKey::Home, _) => {
+                        select((!rows.is_empty()).then_some(0))
+                    }
+                    (NamedKey::ArrowUp | NamedKey::End, None) | (NamedKey::End, _) => {
+                        select(rows.len().checked_sub(1))
+                    }
+                    (NamedKey::ArrowRight, Some(index)) => {
+                        let row = &rows[index];
+                        if row.expanded {
+                            select(Some(index + 1).filter(|&child| child < rows.len()))
+                        } else if row.expandable {
+                            Some(TreeAction::Toggle(row.node.clone()))
+                        } else {
+                            select(None)
+                        }
+                    }
+                    (NamedKey::ArrowLeft, Some(index)) => {
+                        let row = &rows[index];
+                        if row.expanded {
+                            Some(TreeAction::Toggle(row.node.clone()))
+                        } else {
+                            select(parent_row(rows, index))
+                        }
+                    }
+                    _ => None,
+                }
+            });
+            match action {
+                Some(TreeAction::Select(Some(node))) => selected.set(Some(node)),
+                Some(TreeAction::Select(None)) => {}
+                Some(TreeAction::Toggle(node)) => toggle(&node),
+                None => return EventPropagation::Continue,
+            }
+            EventPropagation::Stop
+        })
+        .style(|s| s.size_full());
+
+    Tree {
+        data: ViewData::new(id),
+        child,
+        expanded,
+        selected,
+        is_leaf,
+        on_drop,
+        can_drop,
+        cache,
+        reload,
+    }
+    .class(TreeClass)
+}
+
+impl<T: Clone + Eq + Hash + 'static> Tree<T> {
+    /// The expanded nodes.
+    pub fn expanded(&self) -> RwSignal<HashSet<T>> {
+        self.expanded
+    }
+
+    /// The selected node.
+    pub fn selected(&self) -> RwSignal<Option<T>> {
+        self.selected
+    }
+
+    /// Tells which nodes have no children, so they're shown without an arrow before they're
+    /// expanded, without calling `children_fn`.
+    pub fn is_leaf(self, is_leaf: impl Fn(&T) -> bool + 'static) -> Self {
+        self.is_leaf.set(Some(Rc::new(is_leaf)));
+        self
+    }
+
+    /// Lets the nodes be dragged onto a node to move them into it, or between nodes to move
+    /// them there, calling `on_drop` with where the node was dropped. The drop position is shown
+    /// with a line, collapsed nodes expand when a node is held over them and the tree scrolls
+    /// when it's dragged near its edges.
+    ///
+    /// The tree doesn't move the node itself. The app moves it in its data, then
+    /// [reloads](Tree::reload) the old and the new parent.
+    pub fn on_drop(self, on_drop: impl Fn(TreeDrop<T>) + 'static) -> Self {
+        self.on_drop.set(Some(Rc::new(on_drop)));
+        self
+    }
+
+    /// Rejects the drops `can_drop` returns `false` for, such as moving a file into a file.
+    /// A node can never be dropped into itself or its descendants.
+    pub fn can_drop(self, can_drop: impl Fn(&TreeDrop<T>) -> bool + 'static) -> Self {
+        self.can_drop.set(Some(Rc::new(can_drop)));
+        self
+    }
+
+    /// Forgets the children loaded for `node`, so they're loaded again from `children_fn`,
+    /// such as after the directory of a file explorer changed.
+    pub fn reload(&self, node: &T) {
+        if self.cache.borrow_mut().remove(node).is_some() {
+            self.reload.notify();
+        }
+    }
+}
+
+impl<T> View for Tree<T> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Tree".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+    };
+
+    use super::{drop_at, parent_row, push_rows, DropZone, Loader, TreeDrop};
+
+    #[test]
+    fn lazy_rows() {
+        let loaded = RefCell::new(Vec::new());
+        let children_fn = |node: &u32| {
+            loaded.borrow_mut().push(*node);
+            if *node < 100 {
+                vec![node * 10 + 1, node * 10 + 2]
+            } else {
+                vec![]
+            }
+        };
+        let is_leaf = |node: &u32| *node == 2;
+        let mut cache = HashMap::new();
+        let mut loader = Loader {
+            children_fn: &children_fn,
+            is_leaf: Some(&is_leaf),
+            cache: &mut cache,
+        };
+
+        let mut rows = im::Vector::new();
+        push_rows(&mut rows, &0, 0, &HashSet::from([0, 1]), &mut loader);
+        let shown: Vec<_> = rows
+            .iter()
+            .map(|row| (row.node, row.depth, row.expandable))
+            .collect();
+        assert_eq!(
+            shown,
+            vec![
+                (0, 0, true),
+                (1, 1, true),
+                (11, 2, true),
+                (12, 2, true),
+                (2, 1, false),
+            ]
+        );
+        // Only the expanded nodes were asked for their children
+        assert_eq!(*loaded.borrow(), vec![0, 1]);
+        assert_eq!(parent_row(&rows, 3), Some(1));
+        assert_eq!(parent_row(&rows, 4), Some(0));
+        assert_eq!(parent_row(&rows, 0), None);
+    }
+
+    #[test]
+    fn drop_positions() {
+        let children_fn = |node: &u32| {
+            if *node < 10 {
+                vec![node * 10 + 1, node * 10 + 2]
+            } else {
+                vec![]
+            }
+        };
+        let mut cache = HashMap::new();
+        let mut loader = Loader {
+            children_fn: &children_fn,
+            is_leaf: None,
+            cache: &mut cache,
+        };
+        // 0, 1, 11, 12, 2
+        let mut rows = im::Vector::new();
+        push_rows(&mut rows, &0, 0, &HashSet::from([0, 1]), &mut loader);
+        let drop = |parent, before| {
+            Some(TreeDrop {
+                node: 2,
+                parent,
+                before,
+            })
+        };
+
+        assert_eq!(drop_at(&rows, &2, 2, DropZone::Into), drop(11, None));
+        assert_eq!(drop_at(&rows, &2, 2, DropZone::Before), drop(1, Some(11)));
+        assert_eq!(drop_at(&rows, &2, 3, DropZone::After), drop(1, None));
+        // After an expanded node is before its first child
+        assert_eq!(drop_at(&rows, &2, 1, DropZone::After), drop(1, Some(11)));
+        assert_eq!(drop_at(&rows, &2, 1, DropZone::Before), drop(0, Some(1)));
+
+        // Not where the node already is, nor into itself and its descendants
+        assert_eq!(drop_at(&rows, &2, 0, DropZone::Into), None);
+        assert_eq!(drop_at(&rows, &11, 3, DropZone::Before), None);
+        assert_eq!(drop_at(&rows, &2, 4, DropZone::Before), None);
+        assert_eq!(drop_at(&rows, &1, 2, DropZone::Into), None);
+        assert_eq!(drop_at(&rows, &1, 1, DropZone::After), None);
+        // The root has no siblings
+        assert_eq!(drop_at(&rows, &2, 0, DropZone::Before), None);
+    }
+}
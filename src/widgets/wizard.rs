@@ -0,0 +1,197 @@
+use std::rc::Rc;
+
+use floem_peniko::Color;
+use floem_reactive::{create_rw_signal, RwSignal};
+
+use crate::{
+    context::UpdateCx,
+    id::Id,
+    style_class,
+    view::{View, ViewData},
+    views::{self, empty, h_stack, h_stack_from_iter, v_stack, Decorators, Stack},
+    widgets::button,
+};
+
+style_class!(pub WizardClass);
+style_class!(pub WizardHeaderClass);
+style_class!(pub WizardStepTitleClass);
+
+enum WizardUpdate {
+    Next,
+    Back,
+}
+
+/// A step of a [`wizard`]. See [`wizard_step`]
+pub struct WizardStep {
+    title: String,
+    view: Box<dyn View>,
+    valid: Rc<dyn Fn() -> bool>,
+}
+
+/// A step of a [`wizard`] showing `view`, with `title` in the progress header.
+pub fn wizard_step<V: View + 'static>(title: impl Into<String>, view: V) -> WizardStep {
+    WizardStep {
+        title: title.into(),
+        view: Box::new(view),
+        valid: Rc::new(|| true),
+    }
+}
+
+impl WizardStep {
+    /// Only allows going to the next step while `valid` returns true. It's reactive, so it
+    /// can check the values entered in the step.
+    pub fn valid(mut self, valid: impl Fn() -> bool + 'static) -> Self {
+        self.valid = Rc::new(valid);
+        self
+    }
+}
+
+/// A sequence of steps shown one at a time. See [`wizard`]
+pub struct Wizard {
+    data: ViewData,
+    child: Stack,
+    current: RwSignal<usize>,
+    gates: Vec<Rc<dyn Fn() -> bool>>,
+    on_finish: Option<Box<dyn Fn()>>,
+}
+
+/// Shows `steps` one at a time with a header showing the progress through them, and `Back`
+/// and `Next` buttons to move between them. The `Next` button is disabled while the current
+/// step isn't [valid](WizardStep::valid), and becomes `Finish` on the last step.
+///
+/// The steps are kept alive when hidden, so what was entered in them is kept when going back.
+/// The current step is held by [`Wizard::current`].
+///
+/// ```ignore
+/// let name = create_rw_signal(String::new());
+/// wizard([
+///     wizard_step("Welcome", label(|| "Let's set things up")),
+///     wizard_step("Name", text_input(name)).valid(move || !name.get().is_empty()),
+///     wizard_step("Done", label(|| "All set")),
+/// ])
+/// .on_finish(|| close_window(window_id))
+/// ```
+pub fn wizard(steps: impl IntoIterator<Item = WizardStep>) -> Wizard {
+    let id = Id::next();
+    let current = create_rw_signal(0);
+    let steps: Vec<_> = steps.into_iter().collect();
+    let count = steps.len();
+    let gates: Vec<_> = steps.iter().map(|step| step.valid.clone()).collect();
+
+    let header = h_stack_from_iter(steps.iter().enumerate().map(|(index, step)| {
+        let title = format!("{}. {}", index + 1, step.title);
+        views::label(move || title.clone())
+            .class(WizardStepTitleClass)
+            .style(move |s| {
+                let current = current.get();
+                s.apply_if(index == current, |s| s.font_bold())
+                    .apply_if(index > current, |s| s.color(Color::GRAY))
+            })
+    }))
+    .class(WizardHeaderClass)
+    .style(|s| s.gap(16.0, 0.0));
+
+    let pages = views::stack_from_iter(steps.into_iter().enumerate().map(|(index, step)| {
+        views::container_box(step.view).style(move |s| {
+            s.flex_grow(1.0)
+                .apply_if(index != current.get(), |s| s.hide())
+        })
+    }))
+    .style(|s| s.flex_col().flex_grow(1.0));
+
+    let current_gate = {
+        let gates = gates.clone();
+        move || gates.get(current.get()).map_or(false, |valid| valid())
+    };
+    let buttons = h_stack((
+        button(|| "Back")
+            .disabled(move || current.get() == 0)
+            .on_click_stop(move |_| id.update_state(WizardUpdate::Back)),
+        empty().style(|s| s.flex_grow(1.0)),
+        button(move || {
+            if current.get() + 1 >= count {
+                "Finish"
+            } else {
+                "Next"
+            }
+        })
+        .disabled(move || !current_gate())
+        .on_click_stop(move |_| id.update_state(WizardUpdate::Next)),
+    ));
+
+    Wizard {
+        data: ViewData::new(id),
+        child: v_stack((header, pages, buttons)).style(|s| s.gap(0.0, 12.0).size_full()),
+        current,
+        gates,
+        on_finish: None,
+    }
+    .class(WizardClass)
+}
+
+impl Wizard {
+    /// The index of the current step. Setting it moves to the step without checking whether
+    /// the steps before it are valid.
+    pub fn current(&self) -> RwSignal<usize> {
+        self.current
+    }
+
+    /// Called when pressing `Finish` on the last step, if it's valid.
+    pub fn on_finish(mut self, on_finish: impl Fn() + 'static) -> Self {
+        self.on_finish = Some(Box::new(on_finish));
+        self
+    }
+}
+
+impl View for Wizard {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Wizard".into()
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<WizardUpdate>() {
+            let current = self.current.get_untracked();
+            match *state {
+                WizardUpdate::Back => {
+                    if current > 0 {
+                        self.current.set(current - 1);
+                    }
+                }
+                WizardUpdate::Next => {
+                    // The gate is checked again as the button may be clicked before it's disabled
+                    if !self.gates.get(current).map_or(false, |valid| valid()) {
+                        return;
+                    }
+                    if current + 1 < self.gates.len() {
+                        self.current.set(current + 1);
+                    } else if let Some(on_finish) = &self.on_finish {
+                        on_finish();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -24,6 +24,44 @@ pub struct WindowConfig {
     pub(crate) resizable: Option<bool>,
     pub(crate) window_level: Option<WindowLevel>,
     pub(crate) apply_default_theme: Option<bool>,
+    pub(crate) theme: Option<Theme>,
+    pub(crate) macos_titlebar: Option<MacOsTitlebar>,
+    pub(crate) active: Option<bool>,
+}
+
+/// The appearance of the native titlebar on macOS. See [`WindowConfig::macos_titlebar`]
+#[derive(Default, Debug, Clone, Copy)]
+pub struct MacOsTitlebar {
+    pub(crate) transparent: bool,
+    pub(crate) title_hidden: bool,
+    pub(crate) buttons_hidden: bool,
+    pub(crate) traffic_lights_offset: Option<(f64, f64)>,
+}
+
+impl MacOsTitlebar {
+    /// Makes the titlebar transparent and extends the content of the window beneath it.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn title_hidden(mut self, title_hidden: bool) -> Self {
+        self.title_hidden = title_hidden;
+        self
+    }
+
+    /// Hides the close, minimize and zoom buttons.
+    pub fn buttons_hidden(mut self, buttons_hidden: bool) -> Self {
+        self.buttons_hidden = buttons_hidden;
+        self
+    }
+
+    /// Moves the close, minimize and zoom buttons to be inset by `x` and `y` from the top
+    /// left corner of the window.
+    pub fn traffic_lights_offset(mut self, x: f64, y: f64) -> Self {
+        self.traffic_lights_offset = Some((x, y));
+        self
+    }
 }
 
 impl WindowConfig {
@@ -77,6 +115,12 @@ impl WindowConfig {
         self
     }
 
+    /// Whether the window is focused when it's opened. Defaults to `true`.
+    pub fn active(mut self, active: bool) -> Self {
+        self.active = Some(active);
+        self
+    }
+
     /// If set to true, the stylesheet for Floem's default theme will be
     /// injected into your window. You may want to disable this when using a
     /// completely custom theme.
@@ -84,6 +128,30 @@ impl WindowConfig {
         self.apply_default_theme = Some(apply_default_theme);
         self
     }
+
+    /// Sets whether the native window decorations, such as the titlebar, use a dark or light
+    /// appearance instead of following the system. This is supported on Windows, macOS and
+    /// Wayland.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Sets the appearance of the titlebar on macOS. This is ignored on other platforms.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// WindowConfig::default().macos_titlebar(
+    ///     MacOsTitlebar::default()
+    ///         .transparent(true)
+    ///         .title_hidden(true)
+    ///         .traffic_lights_offset(12.0, 18.0),
+    /// )
+    /// ```
+    pub fn macos_titlebar(mut self, macos_titlebar: MacOsTitlebar) -> Self {
+        self.macos_titlebar = Some(macos_titlebar);
+        self
+    }
 }
 
 /// create a new window. You'll need to create Application first, otherwise it
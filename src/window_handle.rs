@@ -4,47 +4,65 @@ use std::{
     time::{Duration, Instant},
 };
 
+use accesskit::{Action, Role};
 use floem_reactive::{with_scope, RwSignal, Scope};
 use floem_renderer::Renderer;
 use floem_winit::{
     dpi::{LogicalPosition, LogicalSize},
     event::{ElementState, Ime, MouseButton, MouseScrollDelta},
     keyboard::{Key, ModifiersState, NamedKey},
-    window::{CursorIcon, WindowId},
+    window::{CursorGrabMode, CursorIcon, WindowId},
 };
 use image::DynamicImage;
 use indexmap::IndexMap;
 use kurbo::{Affine, Point, Rect, Size, Vec2};
 
-#[cfg(target_os = "linux")]
-use crate::unit::UnitExt;
-#[cfg(target_os = "linux")]
-use crate::views::{container_box, stack, Decorators};
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+use crate::views::open_context_menu;
 use crate::{
-    animate::{AnimPropKind, AnimUpdateMsg, AnimValue, AnimatedProp, SizeUnit},
+    accessibility::{self, AccessCx, AccessibilityEvent},
+    action::{remove_overlay, OverlayConfig},
+    animate::{AnimId, AnimPropKind, AnimUpdateMsg, AnimValue, AnimatedProp, SizeUnit},
+    app::{add_app_update_event, AppUpdateEvent},
+    clipboard::Clipboard,
     context::{
         AppState, ComputeLayoutCx, EventCx, FrameUpdate, LayoutCx, MoveListener, PaintCx,
         PaintState, ResizeListener, StyleCx, UpdateCx,
     },
-    event::{Event, EventListener},
+    event::{Event, EventListener, EventPropagation},
+    focus::{self, FocusScope},
+    gamepad::{self, GamepadButton},
     id::{Id, IdPath, ID_PATHS},
     inspector::{self, Capture, CaptureState, CapturedView},
-    keyboard::KeyEvent,
+    keyboard::{KeyEvent, KeyInput},
+    memory::{self, MemoryReport},
     menu::Menu,
-    nav::view_arrow_navigation,
-    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    nav::{view_arrow_navigation, view_grid_navigation},
+    pointer::{
+        ContextMenuEvent, PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent,
+    },
     profiler::Profile,
-    style::{CursorStyle, Style, StyleSelector},
+    shortcut,
+    soft_keyboard::{remove_window_soft_keyboard, window_soft_keyboard, SoftKeyboardSignals},
+    style::{CursorStyle, Style, StyleSelector, TextScale},
+    svg_export::SvgRenderer,
+    system_colors::update_system_colors,
+    tear_off::TearOffPreview,
+    text_scale::{refresh_system_text_scale, text_scale},
     update::{
-        UpdateMessage, ANIM_UPDATE_MESSAGES, CENTRAL_DEFERRED_UPDATE_MESSAGES,
+        SimulatedInput, UpdateMessage, ANIM_UPDATE_MESSAGES, CENTRAL_DEFERRED_UPDATE_MESSAGES,
         CENTRAL_UPDATE_MESSAGES, CURRENT_RUNNING_VIEW_HANDLE, DEFERRED_UPDATE_MESSAGES,
         UPDATE_MESSAGES,
     },
-    view::{view_children_set_parent_id, view_tab_navigation, View, ViewData},
+    view::{view_children_set_parent_id, View, ViewData},
     view_data::{update_data, ChangeFlags},
+    views::{dismiss_popovers_at, dismiss_top_popover},
     widgets::{default_theme, Theme},
 };
 
+/// The wheel delta, in pixels, a touchpad pinch magnifying by 1.0 is sent as.
+const PINCH_WHEEL_DELTA: f64 = 500.0;
+
 /// The top-level window handle that owns the winit Window.
 /// Meant only for use with the root view of the application.
 /// Owns the `AppState` and is responsible for
@@ -67,12 +85,31 @@ pub(crate) struct WindowHandle {
     is_maximized: bool,
     transparent: bool,
     pub(crate) scale: f64,
+    /// The zoom controlled by the keyboard shortcuts, set by `set_window_zoom`.
+    zoom: Option<RwSignal<f64>>,
+    soft_keyboard: SoftKeyboardSignals,
     pub(crate) modifiers: ModifiersState,
     pub(crate) cursor_position: Point,
     pub(crate) window_position: Point,
     pub(crate) last_pointer_down: Option<(u8, Point, Instant)>,
-    #[cfg(target_os = "linux")]
-    pub(crate) context_menu: RwSignal<Option<(Menu, Point)>>,
+    /// The cursor grab applied to the window, as `(locked, confined)`.
+    cursor_grab: (bool, bool),
+    /// Set when the platform can't lock the cursor in place, so it is confined to the window
+    /// and moved back to where it was locked instead.
+    recenter_locked_cursor: bool,
+    /// Views to export as SVG once the pending updates are processed.
+    svg_exports: Vec<(Id, Box<dyn FnOnce(String)>)>,
+    /// Views to report the memory of once the pending updates are processed.
+    memory_reports: Vec<(Id, Box<dyn FnOnce(MemoryReport)>)>,
+    /// Input to send to views once their layout is up to date.
+    simulated_input: Vec<(Id, SimulatedInput)>,
+    /// The text scale the views were last styled with.
+    text_scale: f64,
+    title: WindowTitle,
+    /// The window following the pointer while a view is torn off this window.
+    tear_off_preview: Option<TearOffPreview>,
+    /// The connection to the assistive technologies, which isn't made for headless handles.
+    accessibility: Option<accessibility::Adapter>,
 }
 
 impl WindowHandle {
@@ -82,15 +119,76 @@ impl WindowHandle {
         transparent: bool,
         apply_default_theme: bool,
     ) -> Self {
-        let scope = Scope::new();
         let window_id = window.id();
-        let id = Id::next();
         let scale = window.scale_factor();
         let size: LogicalSize<f64> = window.inner_size().to_logical(scale);
         let size = Size::new(size.width, size.height);
-        let size = scope.create_rw_signal(Size::new(size.width, size.height));
-        let theme = scope.create_rw_signal(window.theme());
+        let theme = window.theme();
         let is_maximized = window.is_maximized();
+        let paint_state = PaintState::new(&window, scale, size * scale);
+        let accessibility = accessibility::Adapter::new(&window);
+        let mut window_handle = Self::from_parts(
+            Some(window),
+            window_id,
+            paint_state,
+            scale,
+            size,
+            theme,
+            is_maximized,
+            view_fn,
+            transparent,
+            apply_default_theme,
+        );
+        window_handle.accessibility = accessibility;
+        window_handle
+    }
+
+    /// Creates a handle which isn't backed by a window and renders `size` logical pixels into
+    /// an offscreen image, returned by [`WindowHandle::paint`].
+    pub(crate) fn new_headless(
+        view_fn: impl FnOnce(floem_winit::window::WindowId) -> Box<dyn View> + 'static,
+        size: Size,
+        scale: f64,
+        apply_default_theme: bool,
+    ) -> Self {
+        // SAFETY: the id is never passed to winit
+        let window_id = unsafe { WindowId::dummy() };
+        let paint_state = PaintState::new_headless(scale, size * scale);
+        Self::from_parts(
+            None,
+            window_id,
+            paint_state,
+            scale,
+            size,
+            None,
+            false,
+            view_fn,
+            false,
+            apply_default_theme,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts(
+        window: Option<floem_winit::window::Window>,
+        window_id: WindowId,
+        paint_state: PaintState,
+        scale: f64,
+        size: Size,
+        theme: Option<floem_winit::window::Theme>,
+        is_maximized: bool,
+        view_fn: impl FnOnce(floem_winit::window::WindowId) -> Box<dyn View> + 'static,
+        transparent: bool,
+        apply_default_theme: bool,
+    ) -> Self {
+        let scope = Scope::new();
+        let id = Id::next();
+        let title = WindowTitle::new(window.as_ref().map(|window| window.title()));
+        let size = scope.create_rw_signal(size);
+        if let Some(theme) = theme {
+            update_system_colors(theme);
+        }
+        let theme = scope.create_rw_signal(theme);
 
         set_current_view(id);
 
@@ -98,23 +196,10 @@ impl WindowHandle {
             id_paths.borrow_mut().insert(id, IdPath(vec![id]));
         });
 
-        #[cfg(target_os = "linux")]
-        let context_menu = scope.create_rw_signal(None);
+        let soft_keyboard = with_scope(scope, || window_soft_keyboard(id));
 
-        #[cfg(not(target_os = "linux"))]
         let view = with_scope(scope, move || view_fn(window_id));
 
-        #[cfg(target_os = "linux")]
-        let view = with_scope(scope, move || {
-            Box::new(
-                stack((
-                    container_box(view_fn(window_id)).style(|s| s.size(100.pct(), 100.pct())),
-                    context_menu_view(scope, window_id, context_menu, size),
-                ))
-                .style(|s| s.size(100.pct(), 100.pct())),
-            )
-        });
-
         view.id().set_parent(id);
         view_children_set_parent_id(&*view);
 
@@ -124,9 +209,8 @@ impl WindowHandle {
             overlays: Default::default(),
         };
 
-        let paint_state = PaintState::new(&window, scale, size.get_untracked() * scale);
         let mut window_handle = Self {
-            window: Some(window),
+            window,
             window_id,
             id,
             scope,
@@ -140,18 +224,36 @@ impl WindowHandle {
             transparent,
             profile: None,
             scale,
+            zoom: None,
+            soft_keyboard,
             modifiers: ModifiersState::default(),
             cursor_position: Point::ZERO,
             window_position: Point::ZERO,
-            #[cfg(target_os = "linux")]
-            context_menu,
             last_pointer_down: None,
+            cursor_grab: (false, false),
+            recenter_locked_cursor: false,
+            svg_exports: Vec::new(),
+            memory_reports: Vec::new(),
+            simulated_input: Vec::new(),
+            text_scale: text_scale(),
+            title,
+            tear_off_preview: None,
+            accessibility: None,
         };
         window_handle.app_state.set_root_size(size.get_untracked());
         window_handle
     }
 
+    /// The size of a view after the last layout.
+    pub(crate) fn view_size(&self, id: Id) -> Option<Size> {
+        self.app_state
+            .get_layout(id)
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+    }
+
     pub fn event(&mut self, event: Event) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("dispatch_event", event = ?event).entered();
         set_current_view(self.id);
         let event = event.scale(self.app_state.scale);
 
@@ -170,6 +272,12 @@ impl WindowHandle {
             (None, None)
         };
 
+        if let Event::PointerDown(pointer_event) = &event {
+            dismiss_popovers_at(cx.app_state, pointer_event.pos);
+            self.view
+                .dismiss_overlays_at(cx.app_state, pointer_event.pos);
+        }
+
         let is_pointer_down = matches!(&event, Event::PointerDown(_));
         let was_focused = if is_pointer_down {
             cx.app_state.clicking.clear();
@@ -181,6 +289,10 @@ impl WindowHandle {
         if event.needs_focus() {
             let mut processed = false;
 
+            if let Event::KeyDown(key_event) = &event {
+                processed = shortcut::dispatch(self.id, cx.app_state.focus, key_event);
+            }
+
             if !processed {
                 if let Some(id) = cx.app_state.focus {
                     let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
@@ -213,19 +325,43 @@ impl WindowHandle {
                     }
                 }
 
+                if !processed {
+                    if let (Some(zoom), Event::KeyDown(key_event)) = (self.zoom, &event) {
+                        processed = zoom_shortcut(zoom, key_event);
+                    }
+                }
+
                 if !processed {
                     if let Event::KeyDown(KeyEvent { key, modifiers }) = &event {
-                        if key.logical_key == Key::Named(NamedKey::Tab)
+                        if key.logical_key == Key::Named(NamedKey::Escape) && modifiers.is_empty() {
+                            // close the innermost popover, if any
+                            dismiss_top_popover(cx.app_state);
+                        } else if key.logical_key == Key::Named(NamedKey::Tab)
                             && (modifiers.is_empty() || *modifiers == ModifiersState::SHIFT)
                         {
                             let backwards = modifiers.contains(ModifiersState::SHIFT);
-                            view_tab_navigation(&self.view, cx.app_state, backwards);
+                            let focus = cx.app_state.focus;
+                            focus::tab_navigation(&self.view, cx.app_state, focus, backwards);
                             // view_debug_tree(&self.view);
                         } else if let Key::Character(character) = &key.logical_key {
                             // 'I' displays some debug information
                             if character.eq_ignore_ascii_case("i") {
                                 // view_debug_tree(&self.view);
                             }
+                        } else if modifiers.is_empty()
+                            && matches!(
+                                key.logical_key,
+                                Key::Named(
+                                    NamedKey::ArrowUp
+                                        | NamedKey::ArrowDown
+                                        | NamedKey::ArrowLeft
+                                        | NamedKey::ArrowRight
+                                )
+                            )
+                        {
+                            if let Key::Named(name) = key.logical_key {
+                                view_grid_navigation(name, cx.app_state);
+                            }
                         } else if *modifiers == ModifiersState::ALT {
                             if let Key::Named(
                                 name @ (NamedKey::ArrowUp
@@ -239,6 +375,20 @@ impl WindowHandle {
                         }
                     }
 
+                    if let Some(button) = event_gamepad_button(&event) {
+                        let key = match button {
+                            GamepadButton::DPadUp => Some(NamedKey::ArrowUp),
+                            GamepadButton::DPadDown => Some(NamedKey::ArrowDown),
+                            GamepadButton::DPadLeft => Some(NamedKey::ArrowLeft),
+                            GamepadButton::DPadRight => Some(NamedKey::ArrowRight),
+                            _ => None,
+                        };
+                        if let Some(key) = key {
+                            cx.app_state.keyboard_navigation = true;
+                            view_arrow_navigation(key, cx.app_state, &self.view);
+                        }
+                    }
+
                     let keyboard_trigger_end = cx.app_state.keyboard_navigation
                         && event.is_keyboard_trigger()
                         && matches!(event, Event::KeyUp(_));
@@ -287,7 +437,7 @@ impl WindowHandle {
             let hovered = &cx.app_state.hovered.clone();
             for id in was_hovered.unwrap().symmetric_difference(hovered) {
                 let view_state = cx.app_state.view_state(*id);
-                if view_state.animation.is_some()
+                if !view_state.animations.is_empty()
                     || view_state.has_style_selectors.has(StyleSelector::Hover)
                     || view_state.has_style_selectors.has(StyleSelector::Active)
                 {
@@ -354,6 +504,9 @@ impl WindowHandle {
 
     pub(crate) fn os_theme_changed(&mut self, theme: floem_winit::window::Theme) {
         self.os_theme.set(Some(theme));
+        update_system_colors(theme);
+        // The text size is changed in the same settings as the theme
+        refresh_system_text_scale();
     }
 
     pub(crate) fn size(&mut self, size: Size) {
@@ -383,40 +536,159 @@ impl WindowHandle {
         self.event(Event::WindowMoved(point));
     }
 
-    pub(crate) fn key_event(&mut self, key_event: floem_winit::event::KeyEvent) {
+    pub(crate) fn key_event(&mut self, key: KeyInput) {
         let event = KeyEvent {
-            key: key_event,
+            key,
             modifiers: self.modifiers,
         };
         if event.key.state.is_pressed() {
-            self.event(Event::KeyDown(event));
+            event.learn_layout();
+            let is_menu_key = event.key.logical_key == Key::Named(NamedKey::ContextMenu)
+                || (event.key.logical_key == Key::Named(NamedKey::F10)
+                    && event.modifiers == ModifiersState::SHIFT);
+            if is_menu_key {
+                self.keyboard_context_menu(event.modifiers);
+            } else if !(is_paste_shortcut(&event) && self.paste()) {
+                // Views listening to pastes get the clipboard content instead of the key
+                self.event(Event::KeyDown(event));
+            }
         } else {
             self.event(Event::KeyUp(event));
         }
     }
 
     pub(crate) fn pointer_move(&mut self, pos: Point) {
+        if self.app_state.pointer_lock.is_some() {
+            // The cursor stays where it was locked, motion is sent as `Event::PointerDelta`
+            if self.recenter_locked_cursor && self.cursor_position != pos {
+                if let Some(window) = self.window.as_ref() {
+                    let _ = window.set_cursor_position(LogicalPosition::new(
+                        self.cursor_position.x,
+                        self.cursor_position.y,
+                    ));
+                }
+            }
+            return;
+        }
         if self.cursor_position != pos {
             self.cursor_position = pos;
+            if matches!(self.app_state.last_cursor, Some(CursorStyle::Custom(_))) {
+                self.app_state.request_paint = true;
+            }
             let event = PointerMoveEvent {
                 pos,
                 modifiers: self.modifiers,
             };
             self.event(Event::PointerMove(event));
+            self.update_tear_off();
         }
     }
 
+    /// The position on the screen of `pos` in the window.
+    fn screen_position(&self, pos: Point) -> Option<Point> {
+        let window = self.window.as_ref()?;
+        let origin: LogicalPosition<f64> = window.inner_position().ok()?.to_logical(self.scale);
+        Some(Point::new(origin.x + pos.x, origin.y + pos.y))
+    }
+
+    /// Opens, moves or closes the preview of a view torn off the window as it's dragged in and
+    /// out of the window.
+    fn update_tear_off(&mut self) {
+        let Some(id) = self
+            .app_state
+            .dragging
+            .as_ref()
+            .filter(|dragging| dragging.released_at.is_none())
+            .map(|dragging| dragging.id)
+        else {
+            return;
+        };
+        let Some(tear_off) = self.app_state.view_state(id).tear_off.clone() else {
+            return;
+        };
+        let pos = self.cursor_position;
+        let inside = Rect::from_origin_size(Point::ZERO, self.size.get_untracked()).contains(pos);
+        match self.tear_off_preview.as_ref() {
+            Some(preview) if inside => {
+                preview.close();
+                self.tear_off_preview = None;
+            }
+            Some(preview) => {
+                if let Some(pointer) = self.screen_position(pos) {
+                    preview.move_to(pointer);
+                }
+            }
+            None if !inside => {
+                let Some(pointer) = self.screen_position(pos) else {
+                    return;
+                };
+                let scale = self.app_state.scale;
+                let size = self.app_state.get_layout_rect(id).size() * scale;
+                let grab = match self.app_state.drag_start {
+                    Some((drag_id, start)) if drag_id == id => start.to_vec2() * scale,
+                    _ => Vec2::ZERO,
+                };
+                self.tear_off_preview =
+                    Some(TearOffPreview::open(id, &tear_off, size, grab, pointer));
+            }
+            None => {}
+        }
+    }
+
+    /// Drops the view torn off the window, if its preview is shown.
+    fn drop_tear_off(&mut self) {
+        let Some(preview) = self.tear_off_preview.take() else {
+            return;
+        };
+        preview.close();
+        // The view stays where it was instead of moving back
+        self.app_state.dragging = None;
+        self.app_state.request_paint(preview.id);
+        let Some(tear_off) = self.app_state.view_state(preview.id).tear_off.clone() else {
+            return;
+        };
+        if let Some(pointer) = self.screen_position(self.cursor_position) {
+            add_app_update_event(AppUpdateEvent::TearOffDrop {
+                pointer,
+                origin: preview.origin(pointer),
+                preview: preview.window_id(),
+                on_drop: tear_off.on_drop,
+            });
+        }
+    }
+
+    pub(crate) fn pointer_delta(&mut self, delta: Vec2) {
+        let Some(id) = self.app_state.pointer_lock else {
+            return;
+        };
+        set_current_view(self.id);
+        let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
+        if let Some(id_path) = id_path {
+            let mut cx = EventCx {
+                app_state: &mut self.app_state,
+            };
+            cx.unconditional_view_event(
+                &mut self.view,
+                Some(id_path.dispatch()),
+                Event::PointerDelta(delta),
+            );
+        }
+        self.process_update();
+    }
+
     pub(crate) fn pointer_leave(&mut self) {
         set_current_view(self.id);
         let mut cx = EventCx {
             app_state: &mut self.app_state,
         };
+        // Restore the system cursor once the pointer is outside of the window
+        cx.app_state.cursor = None;
         let was_hovered = std::mem::take(&mut cx.app_state.hovered);
         for id in was_hovered {
             let view_state = cx.app_state.view_state(id);
             if view_state.has_style_selectors.has(StyleSelector::Hover)
                 || view_state.has_style_selectors.has(StyleSelector::Active)
-                || view_state.animation.is_some()
+                || !view_state.animations.is_empty()
             {
                 cx.app_state.request_style_recursive(id);
             }
@@ -448,6 +720,17 @@ impl WindowHandle {
         self.event(Event::PointerWheel(event));
     }
 
+    /// Touchpad pinches are sent as wheel events with Ctrl held, like browsers do, so views
+    /// which zoom with Ctrl+wheel zoom with pinches too.
+    pub(crate) fn touchpad_magnify(&mut self, delta: f64) {
+        let event = PointerWheelEvent {
+            pos: self.cursor_position,
+            delta: Vec2::new(0.0, -delta * PINCH_WHEEL_DELTA),
+            modifiers: self.modifiers | ModifiersState::CONTROL,
+        };
+        self.event(Event::PointerWheel(event));
+    }
+
     pub(crate) fn mouse_input(&mut self, button: MouseButton, state: ElementState) {
         let button: PointerButton = button.into();
         let count = if state.is_pressed() && button.is_primary() {
@@ -482,28 +765,231 @@ impl WindowHandle {
                 self.event(Event::PointerDown(event));
             }
             ElementState::Released => {
+                if button.is_primary() {
+                    self.drop_tear_off();
+                }
                 self.event(Event::PointerUp(event));
             }
         }
+
+        // macOS opens context menus when the button is pressed, other platforms on release
+        let opens_menu = if cfg!(target_os = "macos") {
+            state.is_pressed()
+        } else {
+            !state.is_pressed()
+        };
+        if button.is_secondary() && opens_menu {
+            self.event(Event::ContextMenu(ContextMenuEvent {
+                pos: self.cursor_position,
+                window_pos: self.cursor_position,
+                keyboard: false,
+                modifiers: self.modifiers,
+            }));
+        }
+    }
+
+    /// Sends the clipboard content to the focused view if it listens to
+    /// [`EventListener::Paste`], returning whether it did.
+    fn paste(&mut self) -> bool {
+        let Some(focus) = self.app_state.focus else {
+            return false;
+        };
+        if !self
+            .app_state
+            .has_event_listener(focus, EventListener::Paste)
+        {
+            return false;
+        }
+        match Clipboard::get_content() {
+            Ok(content) => {
+                self.event(Event::Paste(content));
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Shows the title segment of the view containing the focus before the window title.
+    fn update_title(&mut self) {
+        let title = &mut self.title;
+        // Segments of removed views
+        title.segments.retain(|id, _| id.has_id_path());
+        if title.focus != self.app_state.focus {
+            title.focus = self.app_state.focus;
+            let owner = title
+                .focus
+                .and_then(|focus| focus.id_path())
+                .and_then(|path| {
+                    path.0
+                        .iter()
+                        .rev()
+                        .find(|id| title.segments.contains_key(*id))
+                        .copied()
+                });
+            // The segment is kept when the focus moves to a view without one, such as a toolbar
+            if owner.is_some() {
+                title.owner = owner;
+            }
+        }
+        if title
+            .owner
+            .map_or(false, |owner| !title.segments.contains_key(&owner))
+        {
+            title.owner = None;
+        }
+        // Before anything is focused the most recently added segment is shown
+        let segment = match title.owner {
+            Some(owner) => title.segments.get(&owner),
+            None => title.segments.last().map(|(_, segment)| segment),
+        };
+        let text = match segment {
+            Some(segment) if !segment.is_empty() && !title.base.is_empty() => {
+                format!("{segment} — {}", title.base)
+            }
+            Some(segment) if !segment.is_empty() => segment.clone(),
+            _ => title.base.clone(),
+        };
+        if text != title.shown {
+            if let Some(window) = self.window.as_ref() {
+                window.set_title(&text);
+            }
+            title.shown = text;
+        }
+    }
+
+    /// Sends simulated input to the view with `id` through its ancestors, like the input sent
+    /// to the focused view.
+    fn simulate_input(&mut self, id: Id, input: SimulatedInput) {
+        let events = match input {
+            SimulatedInput::Click => {
+                let pointer_event = PointerInputEvent {
+                    pos: self.app_state.get_layout_rect(id).center(),
+                    button: PointerButton::Primary,
+                    modifiers: ModifiersState::empty(),
+                    count: 1,
+                };
+                vec![
+                    Event::PointerDown(pointer_event.clone()),
+                    Event::PointerUp(pointer_event),
+                ]
+            }
+            SimulatedInput::Key { key, modifiers } => vec![
+                Event::KeyDown(KeyEvent {
+                    key: KeyInput::new(key.clone(), ElementState::Pressed),
+                    modifiers,
+                }),
+                Event::KeyUp(KeyEvent {
+                    key: KeyInput::new(key, ElementState::Released),
+                    modifiers,
+                }),
+            ],
+            SimulatedInput::Text(text) => vec![Event::ImeCommit(text)],
+        };
+        let Some(id_path) = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned()) else {
+            return;
+        };
+        set_current_view(self.id);
+        let mut cx = EventCx {
+            app_state: &mut self.app_state,
+        };
+        for event in events {
+            cx.unconditional_view_event(&mut self.view, Some(id_path.dispatch()), event);
+        }
+        // Clear the state of the simulated click, as done after a real `PointerUp`
+        for id in mem::take(&mut cx.app_state.clicking) {
+            if cx.app_state.has_style_for_sel(id, StyleSelector::Active) {
+                cx.app_state.request_style_recursive(id);
+            }
+        }
+        cx.app_state.active = None;
+        cx.app_state.drag_start = None;
+    }
+
+    pub(crate) fn accessibility_event(&mut self, event: AccessibilityEvent) {
+        let request = match event {
+            AccessibilityEvent::Activated => {
+                self.schedule_repaint();
+                return;
+            }
+            AccessibilityEvent::Action(request) => request,
+        };
+        let id = accessibility::view_id(request.target);
+        match request.action {
+            Action::Default => self.simulated_input.push((id, SimulatedInput::Click)),
+            Action::Focus => {
+                let old = self.app_state.focus;
+                if old != Some(id) {
+                    self.app_state.clear_focus();
+                    self.app_state.update_focus(id, true);
+                    self.app_state.focus_changed(old, Some(id));
+                }
+            }
+            Action::ScrollIntoView => id.scroll_to(None),
+            action => {
+                let Some(id_path) = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned()) else {
+                    return;
+                };
+                set_current_view(self.id);
+                let mut cx = EventCx {
+                    app_state: &mut self.app_state,
+                };
+                let event = Event::AccessibilityAction {
+                    action,
+                    data: request.data,
+                };
+                cx.unconditional_view_event(&mut self.view, Some(id_path.dispatch()), event);
+            }
+        }
+        self.process_update();
+    }
+
+    /// Sends a context menu request to the focused view, positioned at its bottom left corner.
+    fn keyboard_context_menu(&mut self, modifiers: ModifiersState) {
+        let pos = self
+            .app_state
+            .focus
+            .map(|id| {
+                let rect = self.app_state.get_layout_rect(id);
+                Point::new(rect.x0, rect.y1)
+            })
+            .unwrap_or_default();
+        // The position is scaled back down by `event`
+        let pos = Point::new(pos.x * self.app_state.scale, pos.y * self.app_state.scale);
+        self.event(Event::ContextMenu(ContextMenuEvent {
+            pos,
+            window_pos: pos,
+            keyboard: true,
+            modifiers,
+        }));
     }
 
     pub(crate) fn focused(&mut self, focused: bool) {
+        if let Some(accessibility) = self.accessibility.as_ref() {
+            accessibility.window_focused(focused);
+        }
         if focused {
             self.event(Event::WindowGotFocus);
         } else {
+            // Pointer locks don't outlive the focus of the window
+            self.app_state.pointer_lock = None;
             self.event(Event::WindowLostFocus);
         }
     }
 
     fn style(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("style_pass").entered();
         let mut cx = StyleCx::new(&mut self.app_state, self.view.id());
         if let Some(style) = self.theme.as_ref().map(|theme| theme.style.clone()) {
             cx.current = style;
         }
+        cx.current = Rc::new(cx.current.as_ref().clone().set(TextScale, self.text_scale));
         cx.style_view(&mut self.view);
     }
 
     fn layout(&mut self) -> Duration {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("layout_pass").entered();
         let mut cx = LayoutCx::new(&mut self.app_state);
 
         cx.app_state_mut().root = Some(cx.layout_view(&mut self.view));
@@ -518,6 +1004,8 @@ impl WindowHandle {
     }
 
     fn compute_layout(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("compute_layout_pass").entered();
         self.app_state.request_compute_layout = false;
         let viewport = (self.app_state.root_size / self.app_state.scale).to_rect();
         let mut cx = ComputeLayoutCx::new(&mut self.app_state, viewport);
@@ -536,6 +1024,9 @@ impl WindowHandle {
 
         self.process_update_no_paint();
         self.paint();
+        if let Some(accessibility) = self.accessibility.as_mut() {
+            accessibility.update(&self.view, &mut self.app_state);
+        }
 
         // Request a new frame if there's any scheduled updates.
         if !self.app_state.scheduled_updates.is_empty() {
@@ -544,6 +1035,8 @@ impl WindowHandle {
     }
 
     pub fn paint(&mut self) -> Option<DynamicImage> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("paint_pass").entered();
         let mut cx = PaintCx {
             app_state: &mut self.app_state,
             paint_state: &mut self.paint_state,
@@ -577,6 +1070,16 @@ impl WindowHandle {
             );
         }
         cx.paint_view(&mut self.view);
+        if let (Some(CursorStyle::Custom(cursor)), None) =
+            (cx.app_state.last_cursor, cx.app_state.pointer_lock)
+        {
+            let scale = cx.app_state.scale;
+            let pos = Point::new(
+                self.cursor_position.x / scale,
+                self.cursor_position.y / scale,
+            );
+            cursor.paint(&mut cx, pos);
+        }
         if let Some(window) = self.window.as_ref() {
             if cx.app_state.capture.is_none() {
                 window.pre_present_notify();
@@ -640,6 +1143,7 @@ impl WindowHandle {
             scale: self.scale * self.app_state.scale,
             root: Rc::new(root),
             state: self.app_state.capture.take().unwrap(),
+            memory: memory::report(&self.view, &self.app_state),
         };
         // Process any updates produced by capturing
         self.process_update();
@@ -647,6 +1151,59 @@ impl WindowHandle {
         capture
     }
 
+    /// The memory retained by the view `id` and its children.
+    fn memory_report(&self, id: Id) -> Option<MemoryReport> {
+        let report = memory::report(&self.view, &self.app_state);
+        let root = report.root.find(id)?.clone();
+        Some(MemoryReport { root, ..report })
+    }
+
+    /// Paints the view `id` with an [`SvgRenderer`], with the view at the origin of the document.
+    fn export_svg(&mut self, id: Id) -> Option<String> {
+        let (svg, size) = self.paint_svg(id)?;
+        Some(svg.document(size))
+    }
+
+    /// Paints the view `id` and its children with an [`SvgRenderer`], with the view at its
+    /// origin. Returns the renderer and the size of the view.
+    pub(crate) fn paint_svg(&mut self, id: Id) -> Option<(SvgRenderer, Size)> {
+        fn find_view<'a>(view: &'a mut dyn View, id_path: &[Id]) -> Option<&'a mut dyn View> {
+            let (&id, id_path) = id_path.split_first()?;
+            if id != view.id() {
+                return None;
+            }
+            match id_path.first() {
+                Some(&child) => find_view(view.child_mut(child)?, id_path),
+                None => Some(view),
+            }
+        }
+
+        let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned())?;
+        let view = find_view(&mut self.view, id_path.dispatch())?;
+        let layout = self.app_state.get_layout(id)?;
+        let mut paint_state = PaintState {
+            renderer: crate::renderer::Renderer::Svg(SvgRenderer::new()),
+            scale: 1.0,
+        };
+        let mut cx = PaintCx {
+            app_state: &mut self.app_state,
+            paint_state: &mut paint_state,
+            transform: Affine::translate((-layout.location.x as f64, -layout.location.y as f64)),
+            clip: None,
+            z_index: None,
+            saved_transforms: Vec::new(),
+            saved_clips: Vec::new(),
+            saved_z_indexes: Vec::new(),
+        };
+        cx.paint_view(view);
+
+        let crate::renderer::Renderer::Svg(svg) = paint_state.renderer else {
+            return None;
+        };
+        let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        Some((svg, size))
+    }
+
     pub(crate) fn process_update(&mut self) {
         if self.process_update_no_paint() {
             self.schedule_repaint();
@@ -656,6 +1213,11 @@ impl WindowHandle {
     /// Processes updates and runs style and layout if needed.
     /// Returns `true` if painting is required.
     pub(crate) fn process_update_no_paint(&mut self) -> bool {
+        let text_scale = text_scale();
+        if text_scale != self.text_scale {
+            self.text_scale = text_scale;
+            self.app_state.request_style_recursive(self.id);
+        }
         let mut paint = false;
         loop {
             self.process_update_messages();
@@ -664,6 +1226,9 @@ impl WindowHandle {
                 && !self.has_deferred_update_messages()
                 && !self.has_anim_update_messages()
                 && !self.app_state.request_compute_layout
+                && self.svg_exports.is_empty()
+                && self.memory_reports.is_empty()
+                && self.simulated_input.is_empty()
             {
                 break;
             }
@@ -684,9 +1249,27 @@ impl WindowHandle {
 
             self.process_deferred_update_messages();
             self.process_anim_update_messages();
+
+            for (id, on_export) in mem::take(&mut self.svg_exports) {
+                if let Some(svg) = self.export_svg(id) {
+                    on_export(svg);
+                }
+            }
+
+            for (id, on_report) in mem::take(&mut self.memory_reports) {
+                if let Some(report) = self.memory_report(id) {
+                    on_report(report);
+                }
+            }
+
+            for (id, input) in mem::take(&mut self.simulated_input) {
+                self.simulate_input(id, input);
+            }
         }
 
         self.set_cursor();
+        self.set_cursor_grab();
+        self.update_title();
 
         // TODO: This should only use `self.app_state.request_paint)`
         paint || mem::take(&mut self.app_state.request_paint)
@@ -725,6 +1308,8 @@ impl WindowHandle {
     }
 
     fn process_update_messages(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("update_pass").entered();
         loop {
             self.process_central_messages();
             let msgs =
@@ -759,6 +1344,34 @@ impl WindowHandle {
                         cx.app_state.clear_focus();
                         cx.app_state.focus_changed(Some(id), None);
                     }
+                    UpdateMessage::FocusNext { id, backwards } => {
+                        let old = cx.app_state.focus;
+                        focus::tab_navigation(&self.view, cx.app_state, Some(id), backwards);
+                        if cx.app_state.focus != old {
+                            cx.app_state.focus_changed(old, cx.app_state.focus);
+                        }
+                    }
+                    UpdateMessage::FocusSignal { id, signal } => {
+                        signal.try_set(cx.app_state.focus == Some(id));
+                        cx.app_state.view_state(id).focus_signals.push(signal);
+                    }
+                    UpdateMessage::TabIndex { id, index } => {
+                        cx.app_state.view_state(id).tab_index = Some(index);
+                    }
+                    UpdateMessage::FocusScope { id, scope } => {
+                        cx.app_state.view_state(id).focus_scope = Some(scope);
+                    }
+                    UpdateMessage::PointerLock(id) => {
+                        cx.app_state.pointer_lock = Some(id);
+                    }
+                    UpdateMessage::ReleasePointerLock(id) => {
+                        if cx.app_state.pointer_lock == Some(id) {
+                            cx.app_state.pointer_lock = None;
+                        }
+                    }
+                    UpdateMessage::ConfineCursor { confined } => {
+                        cx.app_state.cursor_confined = confined;
+                    }
                     UpdateMessage::Active(id) => {
                         let old = cx.app_state.active;
                         cx.app_state.active = Some(id);
@@ -789,6 +1402,14 @@ impl WindowHandle {
                         }
                         cx.app_state.request_style_recursive(id);
                     }
+                    UpdateMessage::Invalid { id, is_invalid } => {
+                        if is_invalid {
+                            cx.app_state.invalid.insert(id);
+                        } else {
+                            cx.app_state.invalid.remove(&id);
+                        }
+                        cx.app_state.request_style_recursive(id);
+                    }
                     UpdateMessage::State { id, state } => {
                         let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
                         if let Some(id_path) = id_path {
@@ -827,9 +1448,15 @@ impl WindowHandle {
                     UpdateMessage::KeyboardNavigable { id } => {
                         cx.app_state.keyboard_navigable.insert(id);
                     }
+                    UpdateMessage::KeyboardNavigableGrid { id } => {
+                        cx.app_state.keyboard_navigable_grids.insert(id);
+                    }
                     UpdateMessage::Draggable { id } => {
                         cx.app_state.draggable.insert(id);
                     }
+                    UpdateMessage::TearOff { id, tear_off } => {
+                        cx.app_state.view_state(id).tear_off = Some(tear_off);
+                    }
                     UpdateMessage::DragWindow => {
                         if let Some(window) = self.window.as_ref() {
                             let _ = window.drag_window();
@@ -894,11 +1521,25 @@ impl WindowHandle {
                         let state = cx.app_state.view_state(id);
                         state.cleanup_listener = Some(action);
                     }
+                    UpdateMessage::MountListener { id, action } => {
+                        action(id);
+                    }
                     UpdateMessage::Animation { id, animation } => {
                         let view_state = cx.app_state.view_state(id);
-                        view_state.animation = Some(animation);
+                        // An animation sent again replaces itself
+                        match view_state
+                            .animations
+                            .iter_mut()
+                            .find(|a| a.id() == animation.id())
+                        {
+                            Some(existing) => *existing = animation,
+                            None => view_state.animations.push(animation),
+                        }
                         cx.request_style(id);
                     }
+                    UpdateMessage::WindowZoom(zoom) => {
+                        self.zoom = Some(zoom);
+                    }
                     UpdateMessage::WindowScale(scale) => {
                         cx.app_state.scale = scale;
                         cx.request_layout(self.view.id());
@@ -913,17 +1554,41 @@ impl WindowHandle {
                         let state = cx.app_state.view_state(id);
                         state.popout_menu = Some(menu);
                     }
+                    UpdateMessage::PaintUnder { id, action } => {
+                        let state = cx.app_state.view_state(id);
+                        state.paint_under = Some(action.into());
+                        cx.app_state.request_paint(id);
+                    }
+                    UpdateMessage::PaintOver { id, action } => {
+                        let state = cx.app_state.view_state(id);
+                        state.paint_over = Some(action.into());
+                        cx.app_state.request_paint(id);
+                    }
+                    UpdateMessage::HitTest { id, action } => {
+                        let state = cx.app_state.view_state(id);
+                        state.hit_test = Some(action);
+                    }
+                    UpdateMessage::Accessibility { id, action } => {
+                        let state = cx.app_state.view_state(id);
+                        state.accessibility = Some(action);
+                    }
                     UpdateMessage::ShowContextMenu { menu, pos } => {
-                        let mut menu = menu.popup();
-                        let platform_menu = menu.platform_menu();
-                        cx.app_state.context_menu.clear();
-                        cx.app_state.update_context_menu(&mut menu);
-                        #[cfg(target_os = "macos")]
-                        self.show_context_menu(platform_menu, pos);
-                        #[cfg(target_os = "windows")]
-                        self.show_context_menu(platform_menu, pos);
-                        #[cfg(target_os = "linux")]
-                        self.show_context_menu(menu, platform_menu, pos);
+                        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+                        {
+                            // There's no native context menu, the menu is rendered by Floem
+                            let pos = pos.unwrap_or_else(|| {
+                                (self.cursor_position.to_vec2() / cx.app_state.scale).to_point()
+                            });
+                            open_context_menu(cx.app_state, menu, pos);
+                        }
+                        #[cfg(any(target_os = "macos", target_os = "windows"))]
+                        {
+                            let mut menu = menu.popup();
+                            let platform_menu = menu.platform_menu();
+                            cx.app_state.context_menu.clear();
+                            cx.app_state.update_context_menu(&mut menu);
+                            self.show_context_menu(platform_menu, pos);
+                        }
                     }
                     UpdateMessage::WindowMenu { menu } => {
                         // let platform_menu = menu.platform_menu();
@@ -931,14 +1596,24 @@ impl WindowHandle {
                         // self.handle.set_menu(platform_menu);
                     }
                     UpdateMessage::SetWindowTitle { title } => {
+                        self.title.base = title;
+                    }
+                    UpdateMessage::TitleSegment { id, segment } => {
+                        self.title.segments.insert(id, segment);
+                    }
+                    UpdateMessage::SetWindowTheme { theme } => {
                         if let Some(window) = self.window.as_ref() {
-                            window.set_title(&title);
+                            window.set_theme(theme);
                         }
                     }
                     UpdateMessage::SetImeAllowed { allowed } => {
                         if let Some(window) = self.window.as_ref() {
                             window.set_ime_allowed(allowed);
                         }
+                        self.soft_keyboard.set_ime_allowed(allowed);
+                    }
+                    UpdateMessage::SoftKeyboardInsets { insets } => {
+                        self.soft_keyboard.set_insets(insets);
                     }
                     UpdateMessage::SetImeCursorArea { position, size } => {
                         if let Some(window) = self.window.as_ref() {
@@ -961,15 +1636,34 @@ impl WindowHandle {
                     UpdateMessage::Inspect => {
                         inspector::capture(self.window_id);
                     }
-                    UpdateMessage::AddOverlay { id, position, view } => {
+                    UpdateMessage::ExportSvg { id, on_export } => {
+                        self.svg_exports.push((id, on_export));
+                    }
+                    UpdateMessage::MemoryReport { id, on_report } => {
+                        self.memory_reports.push((id, on_report));
+                    }
+                    UpdateMessage::SimulateInput { id, input } => {
+                        self.simulated_input.push((id, input));
+                    }
+                    UpdateMessage::AddOverlay {
+                        id,
+                        position,
+                        config,
+                        view,
+                    } => {
                         let scope = self.scope.create_child();
 
                         let view = with_scope(scope, view);
+                        if config.modal {
+                            cx.app_state.view_state(id).focus_scope = Some(FocusScope::Trap);
+                        }
 
                         let view = OverlayView {
                             data: ViewData::new(id),
                             position,
                             scope,
+                            config,
+                            dismissed: false,
                             child: view,
                         };
 
@@ -977,13 +1671,19 @@ impl WindowHandle {
                         view_children_set_parent_id(&view);
 
                         self.view.overlays.insert(id, view);
+                        // Keeps the overlays ordered by z-index, the sort being stable
+                        self.view
+                            .overlays
+                            .sort_by(|_, a, _, b| a.config.z_index.cmp(&b.config.z_index));
                         cx.app_state.request_all(self.id);
                     }
                     UpdateMessage::RemoveOverlay { id } => {
-                        let mut overlay = self.view.overlays.remove(&id).unwrap();
-                        cx.app_state.remove_view(&mut overlay);
-                        overlay.scope.dispose();
-                        cx.app_state.request_all(self.id);
+                        // The overlay may have been removed already when it was dismissed
+                        if let Some(mut overlay) = self.view.overlays.shift_remove(&id) {
+                            cx.app_state.remove_view(&mut overlay);
+                            overlay.scope.dispose();
+                            cx.app_state.request_all(self.id);
+                        }
                     }
                 }
             }
@@ -1020,16 +1720,26 @@ impl WindowHandle {
                     val,
                 } => {
                     let view_id = self.app_state.get_view_id_by_anim_id(anim_id);
-                    self.process_update_anim_prop(view_id, kind, val);
+                    self.process_update_anim_prop(view_id, anim_id, kind, val);
                 }
             }
         }
     }
 
-    fn process_update_anim_prop(&mut self, view_id: Id, kind: AnimPropKind, val: AnimValue) {
+    fn process_update_anim_prop(
+        &mut self,
+        view_id: Id,
+        anim_id: AnimId,
+        kind: AnimPropKind,
+        val: AnimValue,
+    ) {
         let layout = self.app_state.get_layout(view_id).unwrap();
         let view_state = self.app_state.view_state(view_id);
-        let anim = view_state.animation.as_mut().unwrap();
+        let anim = view_state
+            .animations
+            .iter_mut()
+            .find(|a| a.id() == anim_id)
+            .unwrap();
         let prop = match kind {
             AnimPropKind::Scale => todo!(),
             AnimPropKind::Width => {
@@ -1120,29 +1830,58 @@ impl WindowHandle {
     }
 
     fn set_cursor(&mut self) {
-        let cursor = match self.app_state.cursor {
-            Some(CursorStyle::Default) => CursorIcon::Default,
-            Some(CursorStyle::Pointer) => CursorIcon::Pointer,
-            Some(CursorStyle::Text) => CursorIcon::Text,
-            Some(CursorStyle::ColResize) => CursorIcon::ColResize,
-            Some(CursorStyle::RowResize) => CursorIcon::RowResize,
-            Some(CursorStyle::WResize) => CursorIcon::WResize,
-            Some(CursorStyle::EResize) => CursorIcon::EResize,
-            Some(CursorStyle::NwResize) => CursorIcon::NwResize,
-            Some(CursorStyle::NeResize) => CursorIcon::NeResize,
-            Some(CursorStyle::SwResize) => CursorIcon::SwResize,
-            Some(CursorStyle::SeResize) => CursorIcon::SeResize,
-            Some(CursorStyle::SResize) => CursorIcon::SResize,
-            Some(CursorStyle::NResize) => CursorIcon::NResize,
-            Some(CursorStyle::NeswResize) => CursorIcon::NeswResize,
-            Some(CursorStyle::NwseResize) => CursorIcon::NwseResize,
-            None => CursorIcon::Default,
+        if self.app_state.cursor == self.app_state.last_cursor {
+            return;
+        }
+        let cursor = self.app_state.cursor;
+        let was_custom = matches!(self.app_state.last_cursor, Some(CursorStyle::Custom(_)));
+        let is_custom = matches!(cursor, Some(CursorStyle::Custom(_)));
+        if let Some(window) = self.window.as_ref() {
+            if is_custom != was_custom && !self.cursor_grab.0 {
+                window.set_cursor_visible(!is_custom);
+            }
+            if !is_custom {
+                window.set_cursor_icon(cursor_icon(cursor.as_ref()));
+            }
+        }
+        if is_custom || was_custom {
+            // Custom cursors are painted with the window contents
+            self.app_state.request_paint = true;
+        }
+        self.app_state.last_cursor = cursor;
+    }
+
+    fn set_cursor_grab(&mut self) {
+        let grab = (
+            self.app_state.pointer_lock.is_some(),
+            self.app_state.cursor_confined,
+        );
+        if grab == self.cursor_grab {
+            return;
+        }
+        let (locked, confined) = grab;
+        let lock_changed = locked != self.cursor_grab.0;
+        self.cursor_grab = grab;
+        self.recenter_locked_cursor = false;
+        let Some(window) = self.window.as_ref() else {
+            return;
         };
-        if cursor != self.app_state.last_cursor {
-            if let Some(window) = self.window.as_ref() {
-                window.set_cursor_icon(cursor);
+        let _ = if locked {
+            window.set_cursor_grab(CursorGrabMode::Locked).or_else(|_| {
+                self.recenter_locked_cursor = true;
+                window.set_cursor_grab(CursorGrabMode::Confined)
+            })
+        } else if confined {
+            window.set_cursor_grab(CursorGrabMode::Confined)
+        } else {
+            window.set_cursor_grab(CursorGrabMode::None)
+        };
+        if lock_changed {
+            let is_custom = matches!(self.app_state.last_cursor, Some(CursorStyle::Custom(_)));
+            window.set_cursor_visible(!locked && !is_custom);
+            if is_custom {
+                self.app_state.request_paint = true;
             }
-            self.app_state.last_cursor = cursor;
         }
     }
 
@@ -1154,6 +1893,9 @@ impl WindowHandle {
 
     pub(crate) fn destroy(&mut self) {
         self.event(Event::WindowClosed);
+        remove_window_soft_keyboard(self.id);
+        shortcut::remove_window_shortcuts(self.id);
+        release_ids(&self.view);
         self.scope.dispose();
     }
 
@@ -1194,18 +1936,6 @@ impl WindowHandle {
         }
     }
 
-    #[cfg(target_os = "linux")]
-    fn show_context_menu(
-        &self,
-        menu: Menu,
-        _platform_menu: floem_winit::menu::Menu,
-        pos: Option<Point>,
-    ) {
-        let pos = pos.unwrap_or(self.cursor_position);
-        let pos = Point::new(pos.x / self.app_state.scale, pos.y / self.app_state.scale);
-        self.context_menu.set(Some((menu, pos)));
-    }
-
     pub(crate) fn menu_action(&mut self, id: usize) {
         set_current_view(self.id);
         if let Some(action) = self.app_state.window_menu.get(&id) {
@@ -1245,285 +1975,13 @@ pub(crate) fn set_current_view(id: Id) {
     });
 }
 
-#[cfg(target_os = "linux")]
-fn context_menu_view(
-    cx: Scope,
-    window_id: WindowId,
-    context_menu: RwSignal<Option<(Menu, Point)>>,
-    window_size: RwSignal<Size>,
-) -> impl View {
-    use floem_peniko::Color;
-    use floem_reactive::{create_effect, create_rw_signal};
-
-    use crate::{
-        app::{add_app_update_event, AppUpdateEvent},
-        views::{dyn_stack, empty, svg, text},
-    };
-
-    #[derive(Clone, PartialEq, Eq, Hash)]
-    struct MenuDisplay {
-        id: Option<u64>,
-        enabled: bool,
-        title: String,
-        children: Option<Vec<Option<MenuDisplay>>>,
-    }
-
-    fn format_menu(menu: &Menu) -> Vec<Option<MenuDisplay>> {
-        menu.children
-            .iter()
-            .map(|e| match e {
-                crate::menu::MenuEntry::Separator => None,
-                crate::menu::MenuEntry::Item(i) => Some(MenuDisplay {
-                    id: Some(i.id),
-                    enabled: i.enabled,
-                    title: i.title.clone(),
-                    children: None,
-                }),
-                crate::menu::MenuEntry::SubMenu(m) => Some(MenuDisplay {
-                    id: None,
-                    enabled: m.item.enabled,
-                    title: m.item.title.clone(),
-                    children: Some(format_menu(m)),
-                }),
-            })
-            .collect()
-    }
-
-    let context_menu_items = cx.create_memo(move |_| {
-        context_menu.with(|menu| {
-            menu.as_ref()
-                .map(|(menu, _): &(Menu, Point)| format_menu(menu))
-        })
-    });
-    let context_menu_size = cx.create_rw_signal(Size::ZERO);
-    let focus_count = cx.create_rw_signal(0);
-
-    fn view_fn(
-        window_id: WindowId,
-        menu: Option<MenuDisplay>,
-        context_menu: RwSignal<Option<(Menu, Point)>>,
-        focus_count: RwSignal<i32>,
-        on_child_submenu_for_parent: RwSignal<bool>,
-    ) -> impl View {
-        if let Some(menu) = menu {
-            let menu_width = create_rw_signal(0.0);
-            let show_submenu = create_rw_signal(false);
-            let on_submenu = create_rw_signal(false);
-            let on_child_submenu = create_rw_signal(false);
-            let has_submenu = menu.children.is_some();
-            let submenu_svg = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M10.072 8.024L5.715 3.667l.618-.62L11 7.716v.618L6.333 13l-.618-.619 4.357-4.357z"/></svg>"#;
-            container_box(
-                stack((
-                    stack((
-                        text(menu.title),
-                        svg(|| submenu_svg.to_string()).style(move |s| {
-                            s.size(20.0, 20.0)
-                                .color(Color::rgb8(201, 201, 201))
-                                .margin_right(10.0)
-                                .margin_left(20.0)
-                                .apply_if(!has_submenu, |s| s.hide())
-                        }),
-                    ))
-                    .on_event_stop(EventListener::PointerEnter, move |_| {
-                        if has_submenu {
-                            show_submenu.set(true);
-                        }
-                    })
-                    .on_event_stop(EventListener::PointerLeave, move |_| {
-                        if has_submenu {
-                            show_submenu.set(false);
-                        }
-                    })
-                    .on_resize(move |rect| {
-                        let width = rect.width();
-                        if menu_width.get_untracked() != width {
-                            menu_width.set(width);
-                        }
-                    })
-                    .on_click_stop(move |_| {
-                        context_menu.set(None);
-                        focus_count.set(0);
-                        if let Some(id) = menu.id {
-                            add_app_update_event(AppUpdateEvent::MenuAction {
-                                window_id,
-                                action_id: id as usize,
-                            });
-                        }
-                    })
-                    .on_secondary_click_stop(move |_| {
-                        context_menu.set(None);
-                        focus_count.set(0);
-                        if let Some(id) = menu.id {
-                            add_app_update_event(AppUpdateEvent::MenuAction {
-                                window_id,
-                                action_id: id as usize,
-                            });
-                        }
-                    })
-                    .disabled(move || !menu.enabled)
-                    .style(|s| {
-                        s.width(100.pct())
-                            .min_width(100.pct())
-                            .padding_horiz(20.0)
-                            .justify_between()
-                            .items_center()
-                            .hover(|s| s.border_radius(10.0).background(Color::rgb8(65, 65, 65)))
-                            .active(|s| s.border_radius(10.0).background(Color::rgb8(92, 92, 92)))
-                            .disabled(|s| s.color(Color::rgb8(92, 92, 92)))
-                    }),
-                    dyn_stack(
-                        move || menu.children.clone().unwrap_or_default(),
-                        move |s| s.clone(),
-                        move |menu| {
-                            view_fn(window_id, menu, context_menu, focus_count, on_child_submenu)
-                        },
-                    )
-                    .keyboard_navigatable()
-                    .on_event_stop(EventListener::FocusGained, move |_| {
-                        focus_count.update(|count| {
-                            *count += 1;
-                        });
-                    })
-                    .on_event_stop(EventListener::FocusLost, move |_| {
-                        let count = focus_count
-                            .try_update(|count| {
-                                *count -= 1;
-                                *count
-                            })
-                            .unwrap();
-                        if count < 1 {
-                            context_menu.set(None);
-                        }
-                    })
-                    .on_event_stop(EventListener::KeyDown, move |event| {
-                        if let Event::KeyDown(event) = event {
-                            if event.key.logical_key == Key::Named(NamedKey::Escape) {
-                                context_menu.set(None);
-                            }
-                        }
-                    })
-                    .on_event_stop(EventListener::PointerDown, move |_| {})
-                    .on_event_stop(EventListener::PointerEnter, move |_| {
-                        if has_submenu {
-                            on_submenu.set(true);
-                            on_child_submenu_for_parent.set(true);
-                        }
-                    })
-                    .on_event_stop(EventListener::PointerLeave, move |_| {
-                        if has_submenu {
-                            on_submenu.set(false);
-                            on_child_submenu_for_parent.set(false);
-                        }
-                    })
-                    .style(move |s| {
-                        s.absolute()
-                            .min_width(200.0)
-                            .margin_top(-5.0)
-                            .margin_left(menu_width.get() as f32)
-                            .flex_col()
-                            .border_radius(10.0)
-                            .background(Color::rgb8(44, 44, 44))
-                            .padding(5.0)
-                            .cursor(CursorStyle::Default)
-                            .box_shadow_blur(5.0)
-                            .box_shadow_color(Color::BLACK)
-                            .apply_if(
-                                !show_submenu.get() && !on_submenu.get() && !on_child_submenu.get(),
-                                |s| s.hide(),
-                            )
-                    }),
-                ))
-                .style(|s| s.min_width(100.pct())),
-            )
-            .style(|s| s.min_width(100.pct()))
-        } else {
-            container_box(empty().style(|s| {
-                s.width(100.pct())
-                    .height(1.0)
-                    .margin_vert(5.0)
-                    .background(Color::rgb8(92, 92, 92))
-            }))
-            .style(|s| s.min_width(100.pct()).padding_horiz(20.0))
-        }
-    }
-
-    let on_child_submenu = create_rw_signal(false);
-    let view = dyn_stack(
-        move || context_menu_items.get().unwrap_or_default(),
-        move |s| s.clone(),
-        move |menu| view_fn(window_id, menu, context_menu, focus_count, on_child_submenu),
-    )
-    .on_resize(move |rect| {
-        context_menu_size.set(rect.size());
-    })
-    .on_event_stop(EventListener::PointerDown, move |_| {})
-    .keyboard_navigatable()
-    .on_event_stop(EventListener::KeyDown, move |event| {
-        if let Event::KeyDown(event) = event {
-            if event.key.logical_key == Key::Named(NamedKey::Escape) {
-                context_menu.set(None);
-            }
-        }
-    })
-    .on_event_stop(EventListener::FocusGained, move |_| {
-        focus_count.update(|count| {
-            *count += 1;
-        });
-    })
-    .on_event_stop(EventListener::FocusLost, move |_| {
-        let count = focus_count
-            .try_update(|count| {
-                *count -= 1;
-                *count
-            })
-            .unwrap();
-        if count < 1 {
-            context_menu.set(None);
-        }
-    })
-    .style(move |s| {
-        let window_size = window_size.get();
-        let menu_size = context_menu_size.get();
-        let is_acitve = context_menu.with(|m| m.is_some());
-        let mut pos = context_menu.with(|m| m.as_ref().map(|(_, pos)| *pos).unwrap_or_default());
-        if pos.x + menu_size.width > window_size.width {
-            pos.x = window_size.width - menu_size.width;
-        }
-        if pos.y + menu_size.height > window_size.height {
-            pos.y = window_size.height - menu_size.height;
-        }
-        s.absolute()
-            .min_width(200.0)
-            .flex_col()
-            .border_radius(10.0)
-            .background(Color::rgb8(44, 44, 44))
-            .color(Color::rgb8(201, 201, 201))
-            .z_index(999)
-            .line_height(2.0)
-            .padding(5.0)
-            .margin_left(pos.x as f32)
-            .margin_top(pos.y as f32)
-            .cursor(CursorStyle::Default)
-            .apply_if(!is_acitve, |s| s.hide())
-            .box_shadow_blur(5.0)
-            .box_shadow_color(Color::BLACK)
-    });
-
-    let id = view.id();
-
-    create_effect(move |_| {
-        if context_menu.with(|m| m.is_some()) {
-            id.request_focus();
-        }
-    });
-
-    view
-}
-
 struct OverlayView {
     data: ViewData,
     scope: Scope,
     position: Point,
+    config: OverlayConfig,
+    /// Set once the overlay is dismissed, until it's removed.
+    dismissed: bool,
     child: Box<dyn View>,
 }
 
@@ -1537,6 +1995,19 @@ impl View for OverlayView {
     }
 
     fn view_style(&self) -> Option<crate::style::Style> {
+        if self.config.modal {
+            // Covers the window, with the view at the position
+            return Some(
+                Style::new()
+                    .absolute()
+                    .inset(0.0)
+                    .flex_col()
+                    .items_start()
+                    .padding_left(self.position.x)
+                    .padding_top(self.position.y)
+                    .apply_opt(self.config.backdrop, |s, color| s.background(color)),
+            );
+        }
         Some(
             Style::new()
                 .absolute()
@@ -1545,6 +2016,20 @@ impl View for OverlayView {
         )
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let propagation = cx.view_event(&mut self.child, id_path, event.clone());
+        if self.config.modal && event.point().is_some() {
+            // Pointer events don't go through the backdrop
+            return EventPropagation::Stop;
+        }
+        propagation
+    }
+
     fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
         for_each(&self.child);
     }
@@ -1612,4 +2097,167 @@ impl View for WindowView {
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
         "Window".into()
     }
+
+    fn accessibility(&self, cx: &mut AccessCx) {
+        cx.set_role(Role::Window);
+    }
+}
+
+impl WindowView {
+    /// Dismisses the overlays dismissed by clicking at `pos`, from the top one down to the
+    /// first one whose view contains `pos` or which is modal.
+    fn dismiss_overlays_at(&mut self, app_state: &mut AppState, pos: Point) {
+        for overlay in self.overlays.values_mut().rev() {
+            if overlay.dismissed {
+                continue;
+            }
+            if app_state.get_layout_rect(overlay.child.id()).contains(pos) {
+                break;
+            }
+            if overlay.config.dismiss_on_outside_click {
+                overlay.dismissed = true;
+                remove_overlay(overlay.id());
+                if let Some(on_dismiss) = &overlay.config.on_dismiss {
+                    on_dismiss();
+                }
+            }
+            if overlay.config.modal {
+                break;
+            }
+        }
+    }
+}
+
+fn cursor_icon(cursor: Option<&CursorStyle>) -> CursorIcon {
+    match cursor {
+        None | Some(CursorStyle::Default) | Some(CursorStyle::Custom(_)) => CursorIcon::Default,
+        Some(CursorStyle::Pointer) => CursorIcon::Pointer,
+        Some(CursorStyle::Text) => CursorIcon::Text,
+        Some(CursorStyle::VerticalText) => CursorIcon::VerticalText,
+        Some(CursorStyle::ColResize) => CursorIcon::ColResize,
+        Some(CursorStyle::RowResize) => CursorIcon::RowResize,
+        Some(CursorStyle::WResize) => CursorIcon::WResize,
+        Some(CursorStyle::EResize) => CursorIcon::EResize,
+        Some(CursorStyle::NwResize) => CursorIcon::NwResize,
+        Some(CursorStyle::NeResize) => CursorIcon::NeResize,
+        Some(CursorStyle::SwResize) => CursorIcon::SwResize,
+        Some(CursorStyle::SeResize) => CursorIcon::SeResize,
+        Some(CursorStyle::SResize) => CursorIcon::SResize,
+        Some(CursorStyle::NResize) => CursorIcon::NResize,
+        Some(CursorStyle::EwResize) => CursorIcon::EwResize,
+        Some(CursorStyle::NsResize) => CursorIcon::NsResize,
+        Some(CursorStyle::NeswResize) => CursorIcon::NeswResize,
+        Some(CursorStyle::NwseResize) => CursorIcon::NwseResize,
+        Some(CursorStyle::Move) => CursorIcon::Move,
+        Some(CursorStyle::AllScroll) => CursorIcon::AllScroll,
+        Some(CursorStyle::Grab) => CursorIcon::Grab,
+        Some(CursorStyle::Grabbing) => CursorIcon::Grabbing,
+        Some(CursorStyle::ZoomIn) => CursorIcon::ZoomIn,
+        Some(CursorStyle::ZoomOut) => CursorIcon::ZoomOut,
+        Some(CursorStyle::Crosshair) => CursorIcon::Crosshair,
+        Some(CursorStyle::Cell) => CursorIcon::Cell,
+        Some(CursorStyle::Help) => CursorIcon::Help,
+        Some(CursorStyle::ContextMenu) => CursorIcon::ContextMenu,
+        Some(CursorStyle::Wait) => CursorIcon::Wait,
+        Some(CursorStyle::Progress) => CursorIcon::Progress,
+        Some(CursorStyle::NotAllowed) => CursorIcon::NotAllowed,
+        Some(CursorStyle::NoDrop) => CursorIcon::NoDrop,
+        Some(CursorStyle::Copy) => CursorIcon::Copy,
+        Some(CursorStyle::Alias) => CursorIcon::Alias,
+    }
+}
+
+/// Whether `event` is the platform shortcut for pasting.
+fn is_paste_shortcut(event: &KeyEvent) -> bool {
+    let command = if cfg!(target_os = "macos") {
+        ModifiersState::SUPER
+    } else {
+        ModifiersState::CONTROL
+    };
+    match &event.key.logical_key {
+        Key::Named(NamedKey::Paste) => true,
+        Key::Named(NamedKey::Insert) => event.modifiers == ModifiersState::SHIFT,
+        Key::Character(c) => event.modifiers == command && c.eq_ignore_ascii_case("v"),
+        _ => false,
+    }
+}
+
+/// Zooms in for `Ctrl` `=` or `Ctrl` `+`, out for `Ctrl` `-` and resets the zoom for `Ctrl` `0`,
+/// with `Cmd` instead of `Ctrl` on macOS. Returns `false` for other keys.
+fn zoom_shortcut(zoom: RwSignal<f64>, event: &KeyEvent) -> bool {
+    const STEPS: [f64; 15] = [
+        0.25, 0.33, 0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 3.0, 4.0,
+    ];
+    let modifier = if cfg!(target_os = "macos") {
+        ModifiersState::SUPER
+    } else {
+        ModifiersState::CONTROL
+    };
+    // Shift is ignored for the keyboard layouts which need it to type `+`
+    if event.modifiers.difference(ModifiersState::SHIFT) != modifier {
+        return false;
+    }
+    let Key::Character(character) = &event.key.logical_key else {
+        return false;
+    };
+    let current = zoom.get_untracked();
+    let new = match character.as_str() {
+        "=" | "+" => STEPS.iter().copied().find(|step| *step > current + 1e-6),
+        "-" => STEPS
+            .iter()
+            .rev()
+            .copied()
+            .find(|step| *step < current - 1e-6),
+        "0" => Some(1.0),
+        _ => return false,
+    };
+    if let Some(new) = new {
+        zoom.set(new);
+    }
+    true
+}
+
+/// The button pressed by a gamepad event, if gamepad navigation is enabled.
+fn event_gamepad_button(event: &Event) -> Option<GamepadButton> {
+    match event {
+        Event::Gamepad(gamepad_event) if gamepad::navigation_enabled() => gamepad_event.pressed(),
+        _ => None,
+    }
+}
+
+/// The title of a window, made of the title set with
+/// [`window_title`](crate::views::Decorators::window_title) and the
+/// [title segment](crate::views::Decorators::title_segment) of the focused view.
+struct WindowTitle {
+    base: String,
+    segments: IndexMap<Id, String>,
+    /// The view whose segment is shown.
+    owner: Option<Id>,
+    /// The focused view when `owner` was last looked up.
+    focus: Option<Id>,
+    shown: String,
+}
+
+impl WindowTitle {
+    fn new(title: Option<String>) -> Self {
+        let title = title.unwrap_or_default();
+        Self {
+            base: title.clone(),
+            segments: IndexMap::new(),
+            owner: None,
+            focus: None,
+            shown: title,
+        }
+    }
+}
+
+/// Makes the ids of `view` and its children available to new ids, as the window is closed.
+fn release_ids(view: &dyn View) {
+    view.for_each_child(&mut |child| {
+        release_ids(child);
+        false
+    });
+    let id = view.id();
+    id.remove_id_path();
+    id.release();
 }
@@ -4,13 +4,14 @@ use floem_peniko::{
     kurbo::{Affine, Point, Rect, Shape},
     BrushRef, Color, GradientKind,
 };
-use floem_renderer::cosmic_text::{CacheKey, SubpixelBin, SwashCache, SwashContent, TextLayout};
+use floem_renderer::cosmic_text::{CacheKey, SubpixelBin, SwashContent, TextLayout};
 use floem_renderer::tiny_skia::{
     self, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, MaskType, Paint, Path,
-    PathBuilder, Pattern, Pixmap, RadialGradient, Shader, SpreadMode, Stroke, Transform,
+    PathBuilder, Pattern, Pixmap, PremultipliedColorU8, RadialGradient, Shader, SpreadMode, Stroke,
+    Transform,
 };
 use floem_renderer::Img;
-use floem_renderer::Renderer;
+use floem_renderer::{color, rasterize_glyph, Renderer, TextRendering};
 use image::DynamicImage;
 use softbuffer::{Context, Surface};
 use std::collections::HashMap;
@@ -28,9 +29,11 @@ macro_rules! try_ret {
 }
 
 struct Glyph {
+    /// The colored glyph, or the coverage of each channel for subpixel antialiased glyphs.
     pixmap: Pixmap,
     left: f32,
     top: f32,
+    subpixel: bool,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -38,8 +41,9 @@ struct CacheColor(bool);
 
 pub struct TinySkiaRenderer {
     #[allow(unused)]
-    context: Context,
-    surface: Surface,
+    context: Option<Context>,
+    /// The window surface, `None` when rendering offscreen.
+    surface: Option<Surface>,
     pixmap: Pixmap,
     mask: Mask,
     scale: f64,
@@ -51,7 +55,8 @@ pub struct TinySkiaRenderer {
 
     image_cache: HashMap<Vec<u8>, (CacheColor, Rc<Pixmap>)>,
     #[allow(clippy::type_complexity)]
-    glyph_cache: HashMap<(CacheKey, Color), (CacheColor, Option<Rc<Glyph>>)>,
+    glyph_cache: HashMap<(CacheKey, Color, TextRendering), (CacheColor, Option<Rc<Glyph>>)>,
+    text_rendering: TextRendering,
 }
 
 impl TinySkiaRenderer {
@@ -77,8 +82,8 @@ impl TinySkiaRenderer {
         let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
 
         Ok(Self {
-            context,
-            surface,
+            context: Some(context),
+            surface: Some(surface),
             pixmap,
             mask,
             scale,
@@ -87,17 +92,43 @@ impl TinySkiaRenderer {
             cache_color: CacheColor(false),
             image_cache: Default::default(),
             glyph_cache: Default::default(),
+            text_rendering: TextRendering::default(),
+        })
+    }
+
+    /// Creates a renderer which draws into an offscreen pixmap. [`Renderer::finish`] returns
+    /// the rendered image.
+    pub fn new_headless(width: u32, height: u32, scale: f64) -> Result<Self> {
+        let pixmap =
+            Pixmap::new(width, height).ok_or_else(|| anyhow!("unable to create pixmap"))?;
+
+        let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
+
+        Ok(Self {
+            context: None,
+            surface: None,
+            pixmap,
+            mask,
+            scale,
+            transform: Affine::IDENTITY,
+            clip: None,
+            cache_color: CacheColor(false),
+            image_cache: Default::default(),
+            glyph_cache: Default::default(),
+            text_rendering: TextRendering::default(),
         })
     }
 
     pub fn resize(&mut self, width: u32, height: u32, scale: f64) {
         if width != self.pixmap.width() || height != self.pixmap.width() {
-            self.surface
-                .resize(
-                    NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
-                    NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
-                )
-                .expect("failed to resize surface");
+            if let Some(surface) = self.surface.as_mut() {
+                surface
+                    .resize(
+                        NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
+                        NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
+                    )
+                    .expect("failed to resize surface");
+            }
             self.pixmap = Pixmap::new(width, height).expect("unable to create pixmap");
             self.mask = Mask::new(width, height).expect("unable to create mask");
         }
@@ -110,6 +141,7 @@ impl TinySkiaRenderer {
 }
 
 fn to_color(color: Color) -> tiny_skia::Color {
+    let color = color::to_output(color);
     tiny_skia::Color::from_rgba8(color.r, color.g, color.b, color.a)
 }
 
@@ -145,10 +177,10 @@ impl TinySkiaRenderer {
         let shader = match brush.into() {
             BrushRef::Solid(c) => Shader::SolidColor(to_color(c)),
             BrushRef::Gradient(g) => {
-                let stops = g
-                    .stops
-                    .iter()
-                    .map(|s| GradientStop::new(s.offset, to_color(s.color)))
+                let stops: Vec<_> = g.stops.iter().map(|s| (s.offset, s.color)).collect();
+                let stops = color::linear_gradient_stops(&stops)
+                    .into_iter()
+                    .map(|(offset, color)| GradientStop::new(offset, to_color(color)))
                     .collect();
                 match g.kind {
                     GradientKind::Linear { start, end } => LinearGradient::new(
@@ -231,6 +263,42 @@ impl TinySkiaRenderer {
         }
     }
 
+    /// Blends a subpixel glyph with the pixmap one channel at a time, using the channels of
+    /// `coverage` as the coverage of the glyph.
+    fn render_subpixel_direct(&mut self, coverage: &Pixmap, color: Color, x: f32, y: f32) {
+        let rect = try_ret!(tiny_skia::Rect::from_xywh(
+            x,
+            y,
+            coverage.width() as f32,
+            coverage.height() as f32,
+        ));
+        let rect = try_ret!(self.clip_rect(rect));
+        let (x, y) = (x as i32, y as i32);
+        let width = self.pixmap.width() as i32;
+        let height = self.pixmap.height() as i32;
+        let alpha = color.a as u32;
+        let blend = |dst: u8, src: u8, coverage: u8| -> u8 {
+            let coverage = coverage as u32 * alpha / 255;
+            ((dst as u32 * (255 - coverage) + src as u32 * coverage) / 255) as u8
+        };
+
+        let src = coverage.pixels();
+        let dst = self.pixmap.pixels_mut();
+        for py in (rect.top() as i32).max(0)..(rect.bottom().ceil() as i32).min(height) {
+            for px in (rect.left() as i32).max(0)..(rect.right().ceil() as i32).min(width) {
+                let c = src[((py - y) * coverage.width() as i32 + px - x) as usize];
+                let d = &mut dst[(py * width + px) as usize];
+                let r = blend(d.red(), color.r, c.red());
+                let g = blend(d.green(), color.g, c.green());
+                let b = blend(d.blue(), color.b, c.blue());
+                let a = blend(d.alpha(), 255, c.alpha()).max(r).max(g).max(b);
+                if let Some(pixel) = PremultipliedColorU8::from_rgba(r, g, b, a) {
+                    *d = pixel;
+                }
+            }
+        }
+    }
+
     fn render_pixmap_rect(&mut self, pixmap: &Pixmap, rect: tiny_skia::Rect) {
         let paint = Paint {
             shader: Pattern::new(
@@ -300,13 +368,13 @@ impl TinySkiaRenderer {
     }
 
     fn cache_glyph(&mut self, cache_key: CacheKey, color: Color) -> Option<Rc<Glyph>> {
-        if let Some((color, glyph)) = self.glyph_cache.get_mut(&(cache_key, color)) {
+        let key = (cache_key, color, self.text_rendering);
+        if let Some((color, glyph)) = self.glyph_cache.get_mut(&key) {
             *color = self.cache_color;
             return glyph.clone();
         }
 
-        let mut swash_cache = SwashCache::new();
-        let image = swash_cache.get_image_uncached(cache_key)?;
+        let image = rasterize_glyph(cache_key, self.text_rendering)?;
 
         let result = if image.placement.width == 0 || image.placement.height == 0 {
             // We can't create an empty `Pixmap`
@@ -314,25 +382,39 @@ impl TinySkiaRenderer {
         } else {
             let mut pixmap = Pixmap::new(image.placement.width, image.placement.height)?;
 
-            if image.content == SwashContent::Mask {
-                for (a, &alpha) in pixmap.pixels_mut().iter_mut().zip(image.data.iter()) {
-                    *a = tiny_skia::Color::from_rgba8(color.r, color.g, color.b, alpha)
-                        .premultiply()
-                        .to_color_u8();
+            match image.content {
+                SwashContent::Mask => {
+                    for (a, &alpha) in pixmap.pixels_mut().iter_mut().zip(image.data.iter()) {
+                        *a = tiny_skia::Color::from_rgba8(color.r, color.g, color.b, alpha)
+                            .premultiply()
+                            .to_color_u8();
+                    }
+                }
+                SwashContent::SubpixelMask => {
+                    for (a, c) in pixmap.pixels_mut().iter_mut().zip(image.data.chunks(4)) {
+                        *a = PremultipliedColorU8::from_rgba(c[0], c[1], c[2], c[3])
+                            .unwrap_or(PremultipliedColorU8::TRANSPARENT);
+                    }
+                }
+                SwashContent::Color => {
+                    for (a, c) in pixmap.pixels_mut().iter_mut().zip(image.data.chunks(4)) {
+                        *a = tiny_skia::Color::from_rgba8(c[0], c[1], c[2], c[3])
+                            .premultiply()
+                            .to_color_u8();
+                    }
                 }
-            } else {
-                panic!("unexpected image content: {:?}", image.content);
             }
 
             Some(Rc::new(Glyph {
                 pixmap,
                 left: image.placement.left as f32,
                 top: image.placement.top as f32,
+                subpixel: image.content == SwashContent::SubpixelMask,
             }))
         };
 
         self.glyph_cache
-            .insert((cache_key, color), (self.cache_color, result.clone()));
+            .insert(key, (self.cache_color, result.clone()));
 
         result
     }
@@ -341,7 +423,12 @@ impl TinySkiaRenderer {
 impl Renderer for TinySkiaRenderer {
     fn begin(&mut self, _capture: bool) {
         self.transform = Affine::IDENTITY;
-        self.pixmap.fill(tiny_skia::Color::WHITE);
+        // Offscreen images keep the transparency of what is drawn on them
+        if self.surface.is_some() {
+            self.pixmap.fill(tiny_skia::Color::WHITE);
+        } else {
+            self.pixmap.fill(tiny_skia::Color::TRANSPARENT);
+        }
         self.clip = None;
     }
 
@@ -425,11 +512,20 @@ impl Renderer for TinySkiaRenderer {
                 let pixmap = self.cache_glyph(cache_key, glyph_run.color);
 
                 if let Some(glyph) = pixmap {
-                    self.render_pixmap_direct(
-                        &glyph.pixmap,
-                        glyph_x + glyph.left,
-                        glyph_y - glyph.top,
-                    );
+                    if glyph.subpixel {
+                        self.render_subpixel_direct(
+                            &glyph.pixmap,
+                            glyph_run.color,
+                            glyph_x + glyph.left,
+                            glyph_y - glyph.top,
+                        );
+                    } else {
+                        self.render_pixmap_direct(
+                            &glyph.pixmap,
+                            glyph_x + glyph.left,
+                            glyph_y - glyph.top,
+                        );
+                    }
                 }
             }
         }
@@ -496,6 +592,10 @@ impl Renderer for TinySkiaRenderer {
         self.transform = transform;
     }
 
+    fn set_text_rendering(&mut self, config: TextRendering) {
+        self.text_rendering = config;
+    }
+
     fn set_z_index(&mut self, _z_index: i32) {
         // FIXME: Remove this method?
     }
@@ -530,8 +630,17 @@ impl Renderer for TinySkiaRenderer {
         // Swap the cache color.
         self.cache_color = CacheColor(!self.cache_color.0);
 
-        let mut buffer = self
-            .surface
+        let Some(surface) = self.surface.as_mut() else {
+            // Offscreen rendering returns the contents of the pixmap
+            let mut image = image::RgbaImage::new(self.pixmap.width(), self.pixmap.height());
+            for (out_pixel, pixel) in image.pixels_mut().zip(self.pixmap.pixels().iter()) {
+                let pixel = pixel.demultiply();
+                *out_pixel = image::Rgba([pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]);
+            }
+            return Some(DynamicImage::ImageRgba8(image));
+        };
+
+        let mut buffer = surface
             .buffer_mut()
             .expect("failed to get the surface buffer");
 
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::sync::mpsc::sync_channel;
+
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+use wgpu::util::DeviceExt;
+
+/// The declarations available to effect shaders. The shader source is appended to it and must
+/// define the fragment entry point `fs_main`.
+pub const EFFECT_PRELUDE: &str = r#"
+struct EffectUniforms {
+    // The size of the source texture in physical pixels
+    size: vec2<f32>,
+    // The values passed to the effect, in order
+    values: array<vec4<f32>, 4>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> effect: EffectUniforms;
+
+// Returns the effect value at `index`
+fn effect_value(index: u32) -> f32 {
+    return effect.values[index / 4u][index % 4u];
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+"#;
+
+/// The maximum number of values passed to an effect shader.
+pub const MAX_EFFECT_VALUES: usize = 16;
+
+/// Runs fragment shaders over images on the GPU.
+pub struct EffectRunner {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// The pipelines of the shaders compiled so far, or `None` if the shader is invalid.
+    pipelines: HashMap<String, Option<wgpu::RenderPipeline>>,
+}
+
+impl EffectRunner {
+    pub fn new() -> Result<Self> {
+        let instance = wgpu::Instance::default();
+        let adapter =
+            futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            }))
+            .ok_or_else(|| anyhow!("can't get adapter"))?;
+        let (device, queue) = futures::executor::block_on(
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None),
+        )?;
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("effect_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            layout,
+            sampler,
+            pipelines: HashMap::new(),
+        })
+    }
+
+    fn pipeline(&mut self, source: &str) -> Option<&wgpu::RenderPipeline> {
+        if !self.pipelines.contains_key(source) {
+            // Catch the errors of invalid shaders instead of panicking
+            self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let module = self
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("effect_shader"),
+                    source: wgpu::ShaderSource::Wgsl(format!("{EFFECT_PRELUDE}\n{source}").into()),
+                });
+            let layout = self
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("effect_pipeline_layout"),
+                    bind_group_layouts: &[&self.layout],
+                    push_constant_ranges: &[],
+                });
+            let pipeline = self
+                .device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("effect_pipeline"),
+                    layout: Some(&layout),
+                    vertex: wgpu::VertexState {
+                        module: &module,
+                        entry_point: "vs_main",
+                        buffers: &[],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &module,
+                        entry_point: "fs_main",
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: wgpu::TextureFormat::Rgba8Unorm,
+                            blend: None,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                });
+            let error = futures::executor::block_on(self.device.pop_error_scope());
+            self.pipelines
+                .insert(source.to_string(), error.is_none().then_some(pipeline));
+        }
+        self.pipelines.get(source)?.as_ref()
+    }
+
+    /// Runs the fragment shader `source` over `input`, returning the output image.
+    ///
+    /// `values` are available to the shader through `effect_value`, and only the first
+    /// [`MAX_EFFECT_VALUES`] are used.
+    pub fn apply(&mut self, source: &str, input: &RgbaImage, values: &[f32]) -> Result<RgbaImage> {
+        let (width, height) = input.dimensions();
+        if width == 0 || height == 0 {
+            return Err(anyhow!("empty effect input"));
+        }
+
+        let mut uniforms = [0.0f32; 4 + MAX_EFFECT_VALUES];
+        uniforms[0] = width as f32;
+        uniforms[1] = height as f32;
+        for (uniform, value) in uniforms[4..].iter_mut().zip(values) {
+            *uniform = *value;
+        }
+
+        if self.pipeline(source).is_none() {
+            return Err(anyhow!("invalid effect shader"));
+        }
+        let pipeline = self.pipelines[source].as_ref().unwrap();
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let source_texture = self.device.create_texture_with_data(
+            &self.queue,
+            &wgpu::TextureDescriptor {
+                label: Some("effect_source"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            input.as_raw(),
+        );
+        let target = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("effect_target"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let contents: Vec<u8> = uniforms.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let uniform_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("effect_uniforms"),
+                contents: &contents,
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let source_view = source_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("effect_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let bytes_per_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("effect_output"),
+            size: bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("effect_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &output,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = output.slice(..);
+        let (tx, rx) = sync_channel(1);
+        slice.map_async(wgpu::MapMode::Read, move |r| {
+            let _ = tx.send(r);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for row in data.chunks(bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..(width * 4) as usize]);
+        }
+        drop(data);
+        output.unmap();
+
+        RgbaImage::from_raw(width, height, pixels).ok_or_else(|| anyhow!("invalid effect output"))
+    }
+}
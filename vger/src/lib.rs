@@ -1,3 +1,5 @@
+pub mod effect;
+
 use std::mem;
 use std::sync::mpsc::sync_channel;
 use std::sync::Arc;
@@ -7,8 +9,8 @@ use floem_peniko::{
     kurbo::{Affine, Point, Rect, Shape, Vec2},
     BrushRef, Color, GradientKind,
 };
-use floem_renderer::cosmic_text::{SubpixelBin, SwashCache, TextLayout};
-use floem_renderer::{tiny_skia, Img, Renderer};
+use floem_renderer::cosmic_text::{SubpixelBin, TextLayout};
+use floem_renderer::{rasterize_glyph, tiny_skia, Img, Renderer, TextAntialiasing, TextRendering};
 use floem_vger_rs::{Image, PaintIndex, PixelFormat, Vger};
 use image::{DynamicImage, EncodableLayout, RgbaImage};
 use wgpu::{Device, DeviceType, Queue, StoreOp, Surface, SurfaceConfiguration, TextureFormat};
@@ -25,6 +27,7 @@ pub struct VgerRenderer {
     transform: Affine,
     clip: Option<Rect>,
     capture: bool,
+    text_rendering: TextRendering,
 }
 
 const CLEAR_COLOR: wgpu::Color = wgpu::Color {
@@ -114,6 +117,7 @@ impl VgerRenderer {
             transform: Affine::IDENTITY,
             clip: None,
             capture: false,
+            text_rendering: TextRendering::default(),
         })
     }
 
@@ -396,7 +400,14 @@ impl Renderer for VgerRenderer {
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
-        let mut swash_cache = SwashCache::new();
+        // The glyph atlas only stores coverage masks
+        let text_rendering = TextRendering {
+            antialiasing: match self.text_rendering.antialiasing {
+                TextAntialiasing::Subpixel => TextAntialiasing::Grayscale,
+                antialiasing => antialiasing,
+            },
+            ..self.text_rendering
+        };
         let transform = self.transform.as_coeffs();
         let offset = Vec2::new(transform[4], transform[5]);
         let pos: Point = pos.into();
@@ -449,7 +460,7 @@ impl Renderer for VgerRenderer {
                             cache_key.font_size = font_size;
                             cache_key.x_bin = subpx_x;
                             cache_key.y_bin = subpx_y;
-                            let image = swash_cache.get_image_uncached(cache_key);
+                            let image = rasterize_glyph(cache_key, text_rendering);
                             image.unwrap_or_default()
                         },
                         paint,
@@ -526,6 +537,10 @@ impl Renderer for VgerRenderer {
         self.vger.set_z_index(z_index);
     }
 
+    fn set_text_rendering(&mut self, config: TextRendering) {
+        self.text_rendering = config;
+    }
+
     fn clip(&mut self, shape: &impl Shape) {
         let (rect, radius) = if let Some(rect) = shape.as_rect() {
             (rect, 0.0)
@@ -580,6 +595,7 @@ impl Renderer for VgerRenderer {
 }
 
 fn vger_color(color: Color) -> floem_vger_rs::Color {
+    let color = color::to_output(color);
     floem_vger_rs::Color {
         r: color.r as f32 / 255.0,
         g: color.g as f32 / 255.0,